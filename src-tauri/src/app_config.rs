@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 use std::str::FromStr;
 
 use crate::services::skill::SkillStore;
@@ -242,6 +243,9 @@ pub struct InstalledCommand {
     /// 分类（YAML category 字段）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub category: Option<String>,
+    /// 全部分类（YAML categories 列表，旧的单个 category 也会并入）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
     /// 允许的工具列表（YAML allowedTools 字段）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub allowed_tools: Option<Vec<String>>,
@@ -251,7 +255,10 @@ pub struct InstalledCommand {
     /// 角色列表（YAML personas 字段）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub personas: Option<Vec<String>>,
-    /// 其他未知 YAML 字段（保留扩展性）
+    /// 运行所需的环境变量（YAML requires_env 字段）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires_env: Vec<String>,
+    /// 其他 YAML 字段（argument-hint、model 及未识别字段，键名保持原样）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extra_metadata: Option<serde_json::Value>,
     /// 仓库所有者（GitHub 用户/组织）
@@ -282,6 +289,12 @@ pub struct InstalledCommand {
     /// 项目路径（当 scope="project" 时有效）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub project_path: Option<String>,
+    /// 用户备注（说明安装原因等，不随更新覆盖）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_note: Option<String>,
+    /// 用户自定义显示名称（优先于上游 name 展示，不随更新覆盖）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name_override: Option<String>,
 }
 
 /// 可发现的 Command（来自 GitHub 仓库）
@@ -301,6 +314,9 @@ pub struct DiscoverableCommand {
     /// 分类
     #[serde(skip_serializing_if = "Option::is_none")]
     pub category: Option<String>,
+    /// 全部分类（旧缓存中不存在时为空）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
     /// README/文档 URL
     #[serde(skip_serializing_if = "Option::is_none")]
     pub readme_url: Option<String>,
@@ -313,6 +329,9 @@ pub struct DiscoverableCommand {
     /// 文件在仓库中的完整路径（如 plugins/bun/commands/agent.md）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_path: Option<String>,
+    /// 多语言描述（frontmatter 提供时才有）
+    #[serde(flatten)]
+    pub localized: LocalizedDescriptions,
 }
 
 /// 未管理的 Command（在应用目录中发现但未被 CC Switch 管理）
@@ -332,6 +351,9 @@ pub struct UnmanagedCommand {
     pub description: Option<String>,
     /// 在哪些应用目录中发现（如 ["claude", "codex"]）
     pub found_in: Vec<String>,
+    /// 既没有 frontmatter 也没有斜杠命令特征，可能是误放入的笔记（仍允许导入）
+    #[serde(default)]
+    pub likely_not_a_command: bool,
 }
 
 /// Command 命名空间
@@ -374,6 +396,122 @@ pub struct CommandRepo {
     /// 添加时间戳（内置仓库为 0）
     #[serde(default)]
     pub added_at: i64,
+    /// 从发现列表中排除的 key（按发现结果的 key 匹配，不影响已安装项）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_keys: Vec<String>,
+    /// 仓库是否提供 Commands（首次扫描后填充，None 表示尚未扫描）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_commands: Option<bool>,
+    /// 仓库是否提供 Agents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_agents: Option<bool>,
+    /// 仓库是否提供 Hooks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_hooks: Option<bool>,
+    /// 用户修改过描述（内置仓库同步时保留用户的描述）
+    #[serde(default)]
+    pub user_edited_description: bool,
+    /// 仅浏览：出现在发现列表中供阅读和预览，但不能从中安装新资源
+    #[serde(default)]
+    pub browse_only: bool,
+}
+
+/// 多语言描述
+///
+/// 字段名与 `CommandRepo` 一致；资源的 frontmatter（Hooks 为 JSON）中写
+/// `description_zh:` 等字段时，发现与安装都会读取
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalizedDescriptions {
+    /// 中文描述
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_zh: Option<String>,
+    /// 英文描述
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_en: Option<String>,
+    /// 日文描述
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_ja: Option<String>,
+}
+
+impl LocalizedDescriptions {
+    /// 从 JSON 对象（如 `extra_metadata`）中读取多语言描述
+    pub fn from_map(map: &serde_json::Map<String, serde_json::Value>) -> Self {
+        let get = |key: &str| map.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+        Self {
+            description_zh: get("description_zh"),
+            description_en: get("description_en"),
+            description_ja: get("description_ja"),
+        }
+    }
+
+    /// 按语言代码获取描述（只看主语言，如 `zh-CN` → `zh`），空白描述视为不存在
+    pub fn get(&self, locale: &str) -> Option<&str> {
+        let lang = locale
+            .split(['-', '_'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        let description = match lang.as_str() {
+            "zh" => &self.description_zh,
+            "en" => &self.description_en,
+            "ja" => &self.description_ja,
+            _ => return None,
+        };
+        description.as_deref().filter(|d| !d.trim().is_empty())
+    }
+
+    /// 按界面语言选择描述
+    ///
+    /// 顺序：`locale` 对应语言 → 默认语言（英文，与前端 i18n 的 fallbackLng 一致）→
+    /// 单一 `description` 字段
+    pub fn resolve<'a>(&'a self, locale: &str, description: Option<&'a str>) -> Option<&'a str> {
+        self.get(locale)
+            .or_else(|| self.get(DEFAULT_DESCRIPTION_LANGUAGE))
+            .or(description)
+    }
+}
+
+/// 描述缺少界面语言版本时优先使用的语言
+const DEFAULT_DESCRIPTION_LANGUAGE: &str = "en";
+
+/// 带有多语言描述的可发现资源
+pub trait Describable {
+    /// 将 `description` 替换为 `locale` 下最合适的描述
+    fn localize_description(&mut self, locale: &str);
+}
+
+impl Describable for DiscoverableCommand {
+    fn localize_description(&mut self, locale: &str) {
+        if let Some(resolved) = self.localized.resolve(locale, None) {
+            self.description = resolved.to_string();
+        }
+    }
+}
+
+impl Describable for DiscoverableAgent {
+    fn localize_description(&mut self, locale: &str) {
+        if let Some(resolved) = self.localized.resolve(locale, None) {
+            self.description = resolved.to_string();
+        }
+    }
+}
+
+impl Describable for DiscoverableHook {
+    fn localize_description(&mut self, locale: &str) {
+        if let Some(resolved) = self.localized.resolve(locale, None) {
+            self.description = Some(resolved.to_string());
+        }
+    }
+}
+
+/// 按当前界面语言改写发现结果的描述，供返回前端前调用
+///
+/// 只改写返回值，仓库扫描缓存中仍保存原始描述
+pub fn localize_descriptions<T: Describable>(items: &mut [T]) {
+    let locale = crate::settings::get_language();
+    for item in items {
+        item.localize_description(&locale);
+    }
 }
 
 fn default_branch() -> String {
@@ -384,6 +522,241 @@ fn default_enabled() -> bool {
     true
 }
 
+/// 分页后的发现结果（Commands/Agents/Hooks 共用）
+///
+/// 仓库仍完整扫描（走缓存），仅对返回给前端的列表分页
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedDiscovery<T> {
+    /// 当前页条目
+    pub items: Vec<T>,
+    /// 条目总数（用于显示“50 / 430”）
+    pub total: u32,
+    /// 页码（从 0 开始）
+    pub page: u32,
+    /// 每页条目数
+    pub page_size: u32,
+    /// 是否还有下一页
+    pub has_more: bool,
+}
+
+impl<T> PaginatedDiscovery<T> {
+    /// 从完整列表中截取指定页
+    ///
+    /// `page_size` 为空或为 0 时返回整个列表（单页）
+    pub fn from_items(items: Vec<T>, page: Option<u32>, page_size: Option<u32>) -> Self {
+        let total = items.len();
+        let page_size = match page_size {
+            Some(size) if size > 0 => size as usize,
+            _ => total.max(1),
+        };
+        let page = page.unwrap_or(0) as usize;
+        let start = page.saturating_mul(page_size).min(total);
+        let end = start.saturating_add(page_size).min(total);
+
+        let items: Vec<T> = items.into_iter().skip(start).take(end - start).collect();
+
+        Self {
+            items,
+            total: total as u32,
+            page: page as u32,
+            page_size: page_size as u32,
+            has_more: end < total,
+        }
+    }
+}
+
+/// 单个仓库在一次发现中的数据来源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoDiscoveryStatus {
+    pub owner: String,
+    pub name: String,
+    pub branch: String,
+    /// 是否来自缓存（false 表示本次从 GitHub 获取）
+    pub from_cache: bool,
+    /// 来源数据的扫描时间（Unix 秒），获取失败时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scanned_at: Option<i64>,
+    /// 该仓库返回的条目数（排除与去重前）
+    pub count: usize,
+    /// 获取失败时的错误信息
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 带仓库来源信息的发现结果，便于判断列表是否来自过期缓存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryResult<T> {
+    pub items: Vec<T>,
+    pub repos: Vec<RepoDiscoveryStatus>,
+}
+
+/// 同步预演中单个文件的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncActionKind {
+    /// 目标文件不存在，将新建
+    Create,
+    /// 目标文件存在且内容不同，将覆盖
+    Overwrite,
+    /// 不会写入（内容一致或无法同步）
+    Skip,
+}
+
+/// 同步预演结果（Commands/Agents/Hooks 共用，不触碰磁盘）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncAction {
+    /// 目标应用
+    pub app: String,
+    /// 资源 ID（Hooks 为整个 hooks 字段）
+    pub id: String,
+    pub action: SyncActionKind,
+    /// 说明（跳过原因等）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl SyncAction {
+    /// 跳过某个资源
+    pub fn skip(app: &AppType, id: &str, reason: impl Into<String>) -> Self {
+        Self {
+            app: app.as_str().to_string(),
+            id: id.to_string(),
+            action: SyncActionKind::Skip,
+            reason: Some(reason.into()),
+        }
+    }
+
+    /// 根据源文件与目标文件的现状推断复制操作
+    pub fn for_file_copy(app: &AppType, id: &str, source: &Path, dest: &Path) -> Self {
+        if !source.exists() {
+            return Self::skip(app, id, "SSOT 中不存在该文件");
+        }
+
+        let (action, reason) = if !dest.exists() {
+            (SyncActionKind::Create, None)
+        } else {
+            match (std::fs::read(source), std::fs::read(dest)) {
+                (Ok(a), Ok(b)) if a == b => {
+                    (SyncActionKind::Skip, Some("内容一致，无需更新".to_string()))
+                }
+                (Ok(_), Ok(_)) => (
+                    SyncActionKind::Overwrite,
+                    Some("目标文件内容不同".to_string()),
+                ),
+                (Err(e), _) => (
+                    SyncActionKind::Skip,
+                    Some(format!("读取 SSOT 文件失败: {e}")),
+                ),
+                (_, Err(e)) => (
+                    SyncActionKind::Overwrite,
+                    Some(format!("读取目标文件失败: {e}")),
+                ),
+            }
+        };
+
+        Self {
+            app: app.as_str().to_string(),
+            id: id.to_string(),
+            action,
+            reason,
+        }
+    }
+}
+
+/// 同步失败的类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncFailureKind {
+    /// 目标目录或文件无写权限（只读目录、企业策略锁定等）
+    PermissionDenied,
+    /// 其他错误
+    Other,
+}
+
+/// 单个文件同步失败的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncFailure {
+    pub app: String,
+    pub id: String,
+    pub kind: SyncFailureKind,
+    pub message: String,
+}
+
+/// 批量同步结果
+///
+/// 单个文件失败不会中断整批同步，失败项汇总在 `failures` 中
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    /// 成功同步的文件数
+    pub synced: usize,
+    pub failures: Vec<SyncFailure>,
+}
+
+impl SyncFailure {
+    /// 按错误类型归类同步失败
+    fn from_error(app: &AppType, id: &str, e: anyhow::Error) -> Self {
+        let kind = match e.downcast_ref::<std::io::Error>().map(|io| io.kind()) {
+            Some(std::io::ErrorKind::PermissionDenied)
+            | Some(std::io::ErrorKind::ReadOnlyFilesystem) => SyncFailureKind::PermissionDenied,
+            _ => SyncFailureKind::Other,
+        };
+        log::warn!("同步 {} 到 {} 失败: {}", id, app.as_str(), e);
+        Self {
+            app: app.as_str().to_string(),
+            id: id.to_string(),
+            kind,
+            message: e.to_string(),
+        }
+    }
+}
+
+impl SyncReport {
+    /// 记录一次同步的结果
+    pub fn record(&mut self, app: &AppType, id: &str, result: anyhow::Result<()>) {
+        match result {
+            Ok(()) => self.synced += 1,
+            Err(e) => self.failures.push(SyncFailure::from_error(app, id, e)),
+        }
+    }
+}
+
+/// 已用 SSOT 覆盖的应用目录文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncedFile {
+    pub app: String,
+    pub id: String,
+}
+
+/// 仅同步漂移文件的结果
+///
+/// 只包含内容与 SSOT 不一致并被覆盖的文件，一致的文件不会被改写
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriftSyncReport {
+    pub synced: Vec<SyncedFile>,
+    pub failures: Vec<SyncFailure>,
+}
+
+impl DriftSyncReport {
+    /// 记录一次覆盖的结果
+    pub fn record(&mut self, app: &AppType, id: &str, result: anyhow::Result<()>) {
+        match result {
+            Ok(()) => self.synced.push(SyncedFile {
+                app: app.as_str().to_string(),
+                id: id.to_string(),
+            }),
+            Err(e) => self.failures.push(SyncFailure::from_error(app, id, e)),
+        }
+    }
+}
+
 // ========== Agent 相关类型 (v3.12.0+) ==========
 
 /// Agent 应用启用状态
@@ -463,6 +836,9 @@ pub struct InstalledAgent {
     /// 工具列表（YAML tools 字段）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<String>>,
+    /// 运行所需的环境变量（YAML requires_env 字段）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires_env: Vec<String>,
     /// 其他未知 YAML 字段（保留扩展性）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extra_metadata: Option<serde_json::Value>,
@@ -494,6 +870,12 @@ pub struct InstalledAgent {
     /// 项目路径（当 scope="project" 时有效）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub project_path: Option<String>,
+    /// 用户备注（说明安装原因等，不随更新覆盖）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_note: Option<String>,
+    /// 用户自定义显示名称（优先于上游 name 展示，不随更新覆盖）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name_override: Option<String>,
 }
 
 /// 可发现的 Agent（来自 GitHub 仓库）
@@ -528,6 +910,9 @@ pub struct DiscoverableAgent {
     /// 文件在仓库中的完整路径
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_path: Option<String>,
+    /// 多语言描述（frontmatter 提供时才有）
+    #[serde(flatten)]
+    pub localized: LocalizedDescriptions,
 }
 
 /// Agent 命名空间
@@ -701,6 +1086,20 @@ pub enum HookEventType {
     SessionEnd,
 }
 
+impl HookEventType {
+    /// 该事件是否使用 matcher
+    ///
+    /// 工具类事件按工具名匹配；SessionEnd 等会话级事件在 Claude 的配置结构中没有 matcher 字段
+    pub fn uses_matcher(&self) -> bool {
+        match self {
+            HookEventType::PreToolUse
+            | HookEventType::PostToolUse
+            | HookEventType::PermissionRequest => true,
+            HookEventType::SessionEnd => false,
+        }
+    }
+}
+
 impl std::fmt::Display for HookEventType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -732,9 +1131,80 @@ pub enum HookType {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct HookRule {
     /// 匹配器（如 "Bash", "Edit|Write", "*", ""）
+    ///
+    /// 解析时也接受数组形式（`["Edit", "Write"]`），统一合并为 `|` 分隔的字符串
+    #[serde(deserialize_with = "deserialize_matcher")]
     pub matcher: String,
     /// Hook 执行列表
     pub hooks: Vec<HookType>,
+    /// 仅在指定操作系统上生效（省略时所有系统均生效）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<HookOs>,
+}
+
+impl HookRule {
+    /// 该规则是否在当前操作系统上生效
+    pub fn applies_to_current_os(&self) -> bool {
+        self.applies_to_os(HookOs::current())
+    }
+
+    fn applies_to_os(&self, current: Option<HookOs>) -> bool {
+        self.os.is_none() || self.os == current
+    }
+}
+
+/// Hook 规则的操作系统限定
+///
+/// 同一个 Hook 文件可为不同系统提供不同命令（如 `.sh` 与 `.ps1`）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HookOs {
+    #[serde(alias = "darwin", alias = "mac")]
+    Macos,
+    #[serde(alias = "win")]
+    Windows,
+    Linux,
+}
+
+impl HookOs {
+    /// 当前操作系统（其他系统返回 None，此时只有未限定系统的规则生效）
+    pub fn current() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            Some(Self::Macos)
+        } else if cfg!(target_os = "windows") {
+            Some(Self::Windows)
+        } else if cfg!(target_os = "linux") {
+            Some(Self::Linux)
+        } else {
+            None
+        }
+    }
+}
+
+/// 解析 matcher：接受字符串、字符串数组或 null
+///
+/// 数组按 Claude 使用的 `|` 分隔形式合并（忽略空项），null 视为空字符串
+pub(crate) fn deserialize_matcher<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum MatcherInput {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match Option::<MatcherInput>::deserialize(deserializer)? {
+        None => String::new(),
+        Some(MatcherInput::One(matcher)) => matcher,
+        Some(MatcherInput::Many(items)) => items
+            .iter()
+            .map(|item| item.trim())
+            .filter(|item| !item.is_empty())
+            .collect::<Vec<_>>()
+            .join("|"),
+    })
 }
 
 /// 已安装的 Hook
@@ -762,6 +1232,9 @@ pub struct InstalledHook {
     pub enabled: bool,
     /// 执行优先级（数字越小越先执行）
     pub priority: i32,
+    /// 运行所需的环境变量（JSON requiresEnv 字段）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires_env: Vec<String>,
 
     /// 仓库所有者
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -793,6 +1266,12 @@ pub struct InstalledHook {
     /// 项目路径（当 scope="project" 时有效）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub project_path: Option<String>,
+    /// 用户备注（说明安装原因等，不随更新覆盖）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_note: Option<String>,
+    /// 用户自定义显示名称（优先于上游 name 展示，不随更新覆盖）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name_override: Option<String>,
 }
 
 /// 可发现的 Hook（来自仓库扫描）
@@ -830,6 +1309,9 @@ pub struct DiscoverableHook {
     /// 在仓库中的源路径（用于下载）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_path: Option<String>,
+    /// 多语言描述（frontmatter 提供时才有）
+    #[serde(flatten)]
+    pub localized: LocalizedDescriptions,
 }
 
 /// Hook 命名空间
@@ -1857,4 +2339,122 @@ mod tests {
                 .enabled
         );
     }
+
+    #[test]
+    fn paginated_discovery_slices_pages() {
+        let items: Vec<u32> = (0..7).collect();
+
+        let first = PaginatedDiscovery::from_items(items.clone(), Some(0), Some(3));
+        assert_eq!(first.items, vec![0, 1, 2]);
+        assert_eq!(first.total, 7);
+        assert!(first.has_more);
+
+        let last = PaginatedDiscovery::from_items(items.clone(), Some(2), Some(3));
+        assert_eq!(last.items, vec![6]);
+        assert!(!last.has_more);
+
+        let beyond = PaginatedDiscovery::from_items(items.clone(), Some(5), Some(3));
+        assert!(beyond.items.is_empty());
+        assert_eq!(beyond.total, 7);
+        assert!(!beyond.has_more);
+
+        let all = PaginatedDiscovery::from_items(items, None, None);
+        assert_eq!(all.items.len(), 7);
+        assert_eq!(all.page_size, 7);
+        assert!(!all.has_more);
+    }
+
+    #[test]
+    fn sync_action_for_file_copy_detects_create_overwrite_and_skip() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source.md");
+        let dest = temp.path().join("dest.md");
+        let app = AppType::Claude;
+
+        let missing = SyncAction::for_file_copy(&app, "a", &temp.path().join("nope.md"), &dest);
+        assert_eq!(missing.action, SyncActionKind::Skip);
+
+        fs::write(&source, "# v1").unwrap();
+        let create = SyncAction::for_file_copy(&app, "a", &source, &dest);
+        assert_eq!(create.action, SyncActionKind::Create);
+
+        fs::write(&dest, "# v1").unwrap();
+        let same = SyncAction::for_file_copy(&app, "a", &source, &dest);
+        assert_eq!(same.action, SyncActionKind::Skip);
+
+        fs::write(&dest, "# old").unwrap();
+        let overwrite = SyncAction::for_file_copy(&app, "a", &source, &dest);
+        assert_eq!(overwrite.action, SyncActionKind::Overwrite);
+        assert_eq!(overwrite.app, "claude");
+    }
+
+    #[test]
+    fn sync_report_classifies_permission_denied() {
+        let mut report = SyncReport::default();
+        let app = AppType::Claude;
+
+        report.record(&app, "ok", Ok(()));
+        report.record(
+            &app,
+            "locked",
+            Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied).into()),
+        );
+        report.record(&app, "broken", Err(anyhow::anyhow!("boom")));
+
+        assert_eq!(report.synced, 1);
+        assert_eq!(report.failures.len(), 2);
+        assert_eq!(report.failures[0].kind, SyncFailureKind::PermissionDenied);
+        assert_eq!(report.failures[1].kind, SyncFailureKind::Other);
+    }
+
+    fn sample_localized() -> LocalizedDescriptions {
+        LocalizedDescriptions {
+            description_zh: Some("代码审查".to_string()),
+            description_en: Some("Code review".to_string()),
+            description_ja: Some("  ".to_string()),
+        }
+    }
+
+    #[test]
+    fn localized_description_matches_exact_locale() {
+        let localized = sample_localized();
+        assert_eq!(localized.get("zh"), Some("代码审查"));
+        assert_eq!(
+            localized.resolve("en", Some("Reviews code")),
+            Some("Code review")
+        );
+    }
+
+    #[test]
+    fn localized_description_falls_back_to_language_only() {
+        let localized = sample_localized();
+        assert_eq!(localized.resolve("zh-CN", None), Some("代码审查"));
+        assert_eq!(localized.resolve("en_US", None), Some("Code review"));
+    }
+
+    #[test]
+    fn localized_description_falls_back_for_missing_locale() {
+        // 缺少的语言与空白描述都先回退到英文
+        let localized = sample_localized();
+        assert_eq!(localized.get("fr"), None);
+        assert_eq!(
+            localized.resolve("fr", Some("Reviews code")),
+            Some("Code review")
+        );
+        assert_eq!(
+            localized.resolve("ja", Some("Reviews code")),
+            Some("Code review")
+        );
+
+        // 没有英文时回退到单一描述字段
+        let without_en = LocalizedDescriptions {
+            description_en: None,
+            ..sample_localized()
+        };
+        assert_eq!(
+            without_en.resolve("ja", Some("Reviews code")),
+            Some("Reviews code")
+        );
+        assert_eq!(LocalizedDescriptions::default().resolve("zh", None), None);
+    }
 }
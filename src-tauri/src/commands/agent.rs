@@ -6,10 +6,15 @@
 //! - 支持命名空间组织
 
 use crate::app_config::{
-    AgentNamespace, AppType, CommandRepo, DiscoverableAgent, InstallScope, InstalledAgent,
-    UnmanagedAgent,
+    AgentNamespace, AppType, CommandRepo, DiscoverableAgent, DriftSyncReport, InstallScope,
+    InstalledAgent, PaginatedDiscovery, SyncAction, SyncReport, UnmanagedAgent,
 };
-use crate::services::agent::{AgentService, ChangeEvent, ConflictResolution, check_app_agents_support};
+use crate::services::agent::{
+    check_app_agents_support, AgentService, ChangeEvent, CompatWarning, ConflictResolution,
+};
+use crate::services::command::{CommandService, FileParseIssues, InstallOutcome, RepoValidation};
+use crate::services::config::MultiAppInstallResult;
+use crate::services::update::ResourceType;
 use crate::store::AppState;
 use std::sync::Arc;
 use tauri::State;
@@ -56,13 +61,16 @@ pub async fn install_agent_unified(
     project_path: Option<String>,
     service: State<'_, AgentServiceState>,
     app_state: State<'_, AppState>,
-) -> Result<InstalledAgent, String> {
+) -> Result<InstallOutcome<InstalledAgent>, String> {
     let app_type = parse_app_type(&current_app)?;
 
     // 先执行全局安装
-    let installed = service
+    let InstallOutcome {
+        installed,
+        warnings,
+    } = service
         .0
-        .install(&app_state.db, &agent, &app_type)
+        .install_with_warnings(&app_state.db, &agent, &app_type)
         .await
         .map_err(|e| e.to_string())?;
 
@@ -74,15 +82,56 @@ pub async fn install_agent_unified(
                 .map_err(|e| e.to_string())?;
 
             // 重新获取更新后的记录
-            return app_state
+            let installed = app_state
                 .db
                 .get_installed_agent(&installed.id)
                 .map_err(|e| e.to_string())?
-                .ok_or_else(|| "Agent not found after scope change".to_string());
+                .ok_or_else(|| "Agent not found after scope change".to_string())?;
+            return Ok(InstallOutcome {
+                installed,
+                warnings,
+            });
         }
     }
 
-    Ok(installed)
+    Ok(InstallOutcome {
+        installed,
+        warnings,
+    })
+}
+
+/// 一次安装 Agent 并启用到多个应用（全局范围），不支持 Agent 的应用被跳过并在结果中说明
+#[tauri::command]
+pub async fn install_agent_for_apps(
+    agent: DiscoverableAgent,
+    apps: Vec<String>,
+    service: State<'_, AgentServiceState>,
+    app_state: State<'_, AppState>,
+) -> Result<MultiAppInstallResult<InstalledAgent>, String> {
+    let apps = apps
+        .iter()
+        .map(|app| app.parse::<AppType>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    service
+        .0
+        .install_for_apps(&app_state.db, &agent, apps)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 从粘贴的文件内容创建 Agent（id 为空时从 name 推导）
+#[tauri::command]
+pub fn create_agent_from_content(
+    content: String,
+    current_app: String,
+    id: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<InstalledAgent, String> {
+    let app_type = parse_app_type(&current_app)?;
+    AgentService::create_from_content(&app_state.db, id.as_deref(), &content, &app_type)
+        .map_err(|e| e.to_string())
 }
 
 /// 卸载 Agent（统一卸载）
@@ -94,20 +143,15 @@ pub fn uninstall_agent_unified(id: String, app_state: State<'_, AppState>) -> Re
 
 /// 批量卸载 Agents
 ///
-/// 返回成功卸载的数量
+/// 返回成功卸载的数量；目标中有锁定的 Agent 且未传 force 时整批取消
 #[tauri::command]
 pub fn uninstall_agents_batch(
     ids: Vec<String>,
+    force: Option<bool>,
     app_state: State<'_, AppState>,
 ) -> Result<usize, String> {
-    let mut success_count = 0;
-    for id in &ids {
-        match AgentService::uninstall(&app_state.db, id) {
-            Ok(_) => success_count += 1,
-            Err(e) => log::warn!("卸载 Agent {} 失败: {}", id, e),
-        }
-    }
-    Ok(success_count)
+    AgentService::uninstall_batch(&app_state.db, &ids, force.unwrap_or(false))
+        .map_err(|e| e.to_string())
 }
 
 /// 切换 Agent 的应用启用状态
@@ -123,6 +167,42 @@ pub fn toggle_agent_app(
     Ok(true)
 }
 
+/// 为命名空间下的全部 Agents 切换应用启用状态，返回实际变更的数量
+#[tauri::command]
+pub fn toggle_agent_namespace_app(
+    namespace: String,
+    app: String,
+    enabled: bool,
+    app_state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let app_type = parse_app_type(&app)?;
+    AgentService::toggle_namespace_for_app(&app_state.db, &namespace, &app_type, enabled)
+        .map_err(|e| e.to_string())
+}
+
+/// 设置 Agent 的用户备注
+#[tauri::command]
+pub fn set_agent_user_note(
+    id: String,
+    note: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<bool, String> {
+    AgentService::set_user_note(&app_state.db, &id, note).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// 设置 Agent 的自定义显示名称（传空则恢复上游名称）
+#[tauri::command]
+pub fn set_agent_display_name(
+    id: String,
+    display_name: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<bool, String> {
+    AgentService::set_display_name_override(&app_state.db, &id, display_name)
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
 /// 修改 Agent 的安装范围
 ///
 /// 参数：
@@ -145,6 +225,23 @@ pub fn change_agent_scope(
     Ok(true)
 }
 
+/// 切换已安装 Agent 的来源分支
+///
+/// 会先校验文件在新分支上存在，然后重新下载并同步
+#[tauri::command]
+pub async fn switch_agent_branch(
+    service: State<'_, AgentServiceState>,
+    app_state: State<'_, AppState>,
+    id: String,
+    new_branch: String,
+) -> Result<InstalledAgent, String> {
+    service
+        .0
+        .switch_branch(&app_state.db, &id, &new_branch)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// 创建命名空间
 #[tauri::command]
 pub fn create_agent_namespace(namespace: String) -> Result<bool, String> {
@@ -199,6 +296,34 @@ pub async fn discover_available_agents(
         .map_err(|e| e.to_string())
 }
 
+/// 分页发现可安装的 Agents
+///
+/// # 参数
+/// - `force_refresh`: 是否强制刷新（跳过缓存，默认 false）
+/// - `page`: 页码（从 0 开始，默认 0）
+/// - `page_size`: 每页条目数（为空时返回全部）
+#[tauri::command]
+pub async fn discover_available_agents_paged(
+    service: State<'_, AgentServiceState>,
+    app_state: State<'_, AppState>,
+    force_refresh: Option<bool>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+) -> Result<PaginatedDiscovery<DiscoverableAgent>, String> {
+    let repos = AgentService::get_repos(&app_state.db).map_err(|e| e.to_string())?;
+    service
+        .0
+        .discover_available_paged(
+            &app_state.db,
+            repos,
+            force_refresh.unwrap_or(false),
+            page,
+            page_size,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // ========== 文件操作命令 ==========
 
 /// 获取 Agent 文件内容
@@ -207,6 +332,14 @@ pub fn get_agent_content(id: String) -> Result<String, String> {
     AgentService::get_agent_content(&id).map_err(|e| e.to_string())
 }
 
+/// 列出 frontmatter 存在解析问题的已安装 Agents
+#[tauri::command]
+pub fn list_agent_parse_issues(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<FileParseIssues>, String> {
+    AgentService::list_parse_issues(&app_state.db).map_err(|e| e.to_string())
+}
+
 /// 在外部编辑器中打开 Agent
 #[tauri::command]
 pub fn open_agent_in_editor(id: String) -> Result<bool, String> {
@@ -221,6 +354,16 @@ pub fn check_app_agents_support_cmd(app: String) -> Result<bool, String> {
     Ok(check_app_agents_support(&app_type))
 }
 
+/// 检查 Agent 的模型/工具在目标应用中的兼容性（安装前提示用）
+#[tauri::command]
+pub fn check_agent_compatibility(
+    agent: DiscoverableAgent,
+    app: String,
+) -> Result<Vec<CompatWarning>, String> {
+    let app_type = parse_app_type(&app)?;
+    Ok(AgentService::check_compatibility(&agent, &app_type))
+}
+
 // ========== 仓库管理命令 ==========
 
 /// 获取 Agent 仓库列表（共用 command_repos 表）
@@ -231,9 +374,16 @@ pub fn get_agent_repos(app_state: State<'_, AppState>) -> Result<Vec<CommandRepo
 
 /// 添加 Agent 仓库（共用 command_repos 表）
 #[tauri::command]
-pub fn add_agent_repo(repo: CommandRepo, app_state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn add_agent_repo(
+    repo: CommandRepo,
+    app_state: State<'_, AppState>,
+) -> Result<RepoValidation, String> {
+    // 先校验仓库，拼写错误或私有仓库缺少 Token 时立即报错；离线时只返回提示
+    let validation = CommandService::validate_repo(&app_state.db, &repo, ResourceType::Agent)
+        .await
+        .map_err(|e| e.to_string())?;
     AgentService::add_repo(&app_state.db, &repo).map_err(|e| e.to_string())?;
-    Ok(true)
+    Ok(validation)
 }
 
 /// 删除 Agent 仓库
@@ -244,8 +394,6 @@ pub fn remove_agent_repo(
     app_state: State<'_, AppState>,
 ) -> Result<bool, String> {
     AgentService::remove_repo(&app_state.db, &owner, &name).map_err(|e| e.to_string())?;
-    // 同时删除该仓库的缓存
-    let _ = app_state.db.delete_agent_repo_cache(&owner, &name);
     Ok(true)
 }
 
@@ -308,8 +456,22 @@ pub fn refresh_agents_from_ssot(app_state: State<'_, AppState>) -> Result<usize,
 
 /// 同步所有 Agents 到应用目录
 ///
-/// 确保所有已启用的应用目录与 SSOT 保持一致
+/// 确保所有已启用的应用目录与 SSOT 保持一致，单个文件失败不会中断整批同步
 #[tauri::command]
-pub fn sync_agents_to_apps(app_state: State<'_, AppState>) -> Result<usize, String> {
+pub fn sync_agents_to_apps(app_state: State<'_, AppState>) -> Result<SyncReport, String> {
     AgentService::sync_all_to_apps(&app_state.db).map_err(|e| e.to_string())
 }
+
+/// 只同步内容与 SSOT 不一致的 Agents（一致的文件保持不动）
+#[tauri::command]
+pub fn sync_drifted_agents_to_apps(
+    app_state: State<'_, AppState>,
+) -> Result<DriftSyncReport, String> {
+    AgentService::sync_drifted_only(&app_state.db).map_err(|e| e.to_string())
+}
+
+/// 预演同步 Agents 到应用目录（不写入磁盘）
+#[tauri::command]
+pub fn plan_sync_agents_to_apps(app_state: State<'_, AppState>) -> Result<Vec<SyncAction>, String> {
+    AgentService::plan_sync(&app_state.db).map_err(|e| e.to_string())
+}
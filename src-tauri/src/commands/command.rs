@@ -6,10 +6,20 @@
 //! - 支持命名空间组织
 
 use crate::app_config::{
-    AppType, CommandNamespace, CommandRepo, DiscoverableCommand, InstallScope, InstalledCommand,
-    UnmanagedCommand,
+    AppType, CommandNamespace, CommandRepo, DiscoverableCommand, DiscoveryResult, DriftSyncReport,
+    InstallScope, InstalledCommand, PaginatedDiscovery, SyncAction, SyncReport, UnmanagedCommand,
 };
-use crate::services::command::{ChangeEvent, CommandService, ConflictResolution};
+use crate::database::DiscoveryCachePruneResult;
+use crate::services::command::{
+    is_powerful_tool, matches_category, ChangeEvent, CommandService, ConflictResolution,
+    EffectiveAllowedTools, FileParseIssues, InstallOutcome, KeyInstallResult, ReconcileReport,
+    RepoImportResult, RepoOverlap, RepoValidation,
+};
+use crate::services::config::MultiAppInstallResult;
+use crate::services::recipe::{RecipeInstallResult, RecipeService};
+use crate::services::repo_installs::{InstalledByRepo, RepoInstallsService, RepoUninstallResult};
+use crate::services::repo_metadata::RepoMetadata;
+use crate::services::update::ResourceType;
 use crate::store::AppState;
 use std::sync::Arc;
 use tauri::State;
@@ -30,11 +40,20 @@ fn parse_app_type(app: &str) -> Result<AppType, String> {
 // ========== 统一管理命令 ==========
 
 /// 获取所有已安装的 Commands
+///
+/// # 参数
+/// - `category`: 仅返回属于该分类的 Commands（可选，大小写不敏感）
 #[tauri::command]
 pub fn get_installed_commands(
     app_state: State<'_, AppState>,
+    category: Option<String>,
 ) -> Result<Vec<InstalledCommand>, String> {
-    CommandService::get_all_installed(&app_state.db).map_err(|e| e.to_string())
+    let mut commands =
+        CommandService::get_all_installed(&app_state.db).map_err(|e| e.to_string())?;
+    if let Some(wanted) = category.as_deref() {
+        commands.retain(|c| matches_category(c.category.as_deref(), &c.categories, wanted));
+    }
+    Ok(commands)
 }
 
 /// 获取所有命名空间
@@ -45,6 +64,23 @@ pub fn get_command_namespaces(
     CommandService::get_namespaces(&app_state.db).map_err(|e| e.to_string())
 }
 
+/// 汇总指定应用上已启用 Commands 可调用的工具，并标出高权限工具
+#[tauri::command]
+pub fn get_effective_allowed_tools(
+    app: String,
+    app_state: State<'_, AppState>,
+) -> Result<EffectiveAllowedTools, String> {
+    let app_type = parse_app_type(&app)?;
+    let tools = CommandService::effective_allowed_tools(&app_state.db, &app_type)
+        .map_err(|e| e.to_string())?;
+    let powerful = tools
+        .iter()
+        .filter(|tool| is_powerful_tool(tool))
+        .cloned()
+        .collect();
+    Ok(EffectiveAllowedTools { tools, powerful })
+}
+
 /// 安装 Command（统一安装）
 ///
 /// 参数：
@@ -60,13 +96,16 @@ pub async fn install_command_unified(
     project_path: Option<String>,
     service: State<'_, CommandServiceState>,
     app_state: State<'_, AppState>,
-) -> Result<InstalledCommand, String> {
+) -> Result<InstallOutcome<InstalledCommand>, String> {
     let app_type = parse_app_type(&current_app)?;
 
     // 先执行全局安装
-    let installed = service
+    let InstallOutcome {
+        installed,
+        warnings,
+    } = service
         .0
-        .install(&app_state.db, &command, &app_type)
+        .install_with_warnings(&app_state.db, &command, &app_type)
         .await
         .map_err(|e| e.to_string())?;
 
@@ -78,15 +117,129 @@ pub async fn install_command_unified(
                 .map_err(|e| e.to_string())?;
 
             // 重新获取更新后的记录
-            return app_state
+            let installed = app_state
                 .db
                 .get_installed_command(&installed.id)
                 .map_err(|e| e.to_string())?
-                .ok_or_else(|| "Command not found after scope change".to_string());
+                .ok_or_else(|| "Command not found after scope change".to_string())?;
+            return Ok(InstallOutcome {
+                installed,
+                warnings,
+            });
         }
     }
 
-    Ok(installed)
+    Ok(InstallOutcome {
+        installed,
+        warnings,
+    })
+}
+
+/// 一次安装 Command 并启用到多个应用（全局范围），不支持 Command 的应用被跳过并在结果中说明
+#[tauri::command]
+pub async fn install_command_for_apps(
+    command: DiscoverableCommand,
+    apps: Vec<String>,
+    service: State<'_, CommandServiceState>,
+    app_state: State<'_, AppState>,
+) -> Result<MultiAppInstallResult<InstalledCommand>, String> {
+    let apps = apps
+        .iter()
+        .map(|app| app.parse::<AppType>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    service
+        .0
+        .install_for_apps(&app_state.db, &command, apps)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 从给定目录向上检测项目根目录（包含 `.git` 或 `.claude`），未找到时返回 None
+#[tauri::command]
+pub fn detect_current_project(cwd: String) -> Option<String> {
+    CommandService::detect_current_project(std::path::Path::new(&cwd))
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+/// 安装 Command 到从 `cwd` 检测到的项目（项目范围）
+#[tauri::command]
+pub async fn install_command_to_detected_project(
+    command: DiscoverableCommand,
+    current_app: String,
+    cwd: String,
+    service: State<'_, CommandServiceState>,
+    app_state: State<'_, AppState>,
+) -> Result<InstalledCommand, String> {
+    let app_type = parse_app_type(&current_app)?;
+    service
+        .0
+        .install_to_detected_project(
+            &app_state.db,
+            &command,
+            std::path::Path::new(&cwd),
+            &app_type,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 按 key 列表批量安装 Commands（如团队共享的列表），逐个返回结果
+///
+/// 同一 key 存在于多个仓库时使用 `preferred_repo`（`owner/name`）消歧
+#[tauri::command]
+pub async fn install_commands_by_keys(
+    keys: Vec<String>,
+    current_app: String,
+    preferred_repo: Option<String>,
+    service: State<'_, CommandServiceState>,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<KeyInstallResult>, String> {
+    let app_type = parse_app_type(&current_app)?;
+    let repos = CommandService::get_repos(&app_state.db).map_err(|e| e.to_string())?;
+    let results = service
+        .0
+        .install_keys(
+            &app_state.db,
+            repos,
+            &keys,
+            &app_type,
+            preferred_repo.as_deref(),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(results
+        .into_iter()
+        .map(|(key, result)| match result {
+            Ok(installed) => KeyInstallResult {
+                key,
+                success: true,
+                installed_id: Some(installed.id),
+                error: None,
+            },
+            Err(e) => KeyInstallResult {
+                key,
+                success: false,
+                installed_id: None,
+                error: Some(e.to_string()),
+            },
+        })
+        .collect())
+}
+
+/// 从粘贴的文件内容创建 Command（id 为空时从 name 推导）
+#[tauri::command]
+pub fn create_command_from_content(
+    content: String,
+    current_app: String,
+    id: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<InstalledCommand, String> {
+    let app_type = parse_app_type(&current_app)?;
+    CommandService::create_from_content(&app_state.db, id.as_deref(), &content, &app_type)
+        .map_err(|e| e.to_string())
 }
 
 /// 卸载 Command（统一卸载）
@@ -101,20 +254,15 @@ pub fn uninstall_command_unified(
 
 /// 批量卸载 Commands
 ///
-/// 返回成功卸载的数量
+/// 返回成功卸载的数量；目标中有锁定的 Command 且未传 force 时整批取消
 #[tauri::command]
 pub fn uninstall_commands_batch(
     ids: Vec<String>,
+    force: Option<bool>,
     app_state: State<'_, AppState>,
 ) -> Result<usize, String> {
-    let mut success_count = 0;
-    for id in &ids {
-        match CommandService::uninstall(&app_state.db, id) {
-            Ok(_) => success_count += 1,
-            Err(e) => log::warn!("卸载 Command {} 失败: {}", id, e),
-        }
-    }
-    Ok(success_count)
+    CommandService::uninstall_batch(&app_state.db, &ids, force.unwrap_or(false))
+        .map_err(|e| e.to_string())
 }
 
 /// 切换 Command 的应用启用状态
@@ -130,6 +278,42 @@ pub fn toggle_command_app(
     Ok(true)
 }
 
+/// 为命名空间下的全部 Commands 切换应用启用状态，返回实际变更的数量
+#[tauri::command]
+pub fn toggle_command_namespace_app(
+    namespace: String,
+    app: String,
+    enabled: bool,
+    app_state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let app_type = parse_app_type(&app)?;
+    CommandService::toggle_namespace_for_app(&app_state.db, &namespace, &app_type, enabled)
+        .map_err(|e| e.to_string())
+}
+
+/// 设置 Command 的用户备注
+#[tauri::command]
+pub fn set_command_user_note(
+    id: String,
+    note: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<bool, String> {
+    CommandService::set_user_note(&app_state.db, &id, note).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// 设置 Command 的自定义显示名称（传空则恢复上游名称）
+#[tauri::command]
+pub fn set_command_display_name(
+    id: String,
+    display_name: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<bool, String> {
+    CommandService::set_display_name_override(&app_state.db, &id, display_name)
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
 /// 修改 Command 的安装范围
 ///
 /// 参数：
@@ -152,6 +336,23 @@ pub fn change_command_scope(
     Ok(true)
 }
 
+/// 切换已安装 Command 的来源分支
+///
+/// 会先校验文件在新分支上存在，然后重新下载并同步
+#[tauri::command]
+pub async fn switch_command_branch(
+    service: State<'_, CommandServiceState>,
+    app_state: State<'_, AppState>,
+    id: String,
+    new_branch: String,
+) -> Result<InstalledCommand, String> {
+    service
+        .0
+        .switch_branch(&app_state.db, &id, &new_branch)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// 创建命名空间
 #[tauri::command]
 pub fn create_command_namespace(namespace: String) -> Result<bool, String> {
@@ -192,17 +393,90 @@ pub fn import_commands_from_apps(
 ///
 /// # 参数
 /// - `force_refresh`: 是否强制刷新（跳过缓存，默认 false）
+/// - `category`: 仅返回属于该分类的 Commands（可选，大小写不敏感）
 #[tauri::command]
 pub async fn discover_available_commands(
     service: State<'_, CommandServiceState>,
     app_state: State<'_, AppState>,
     force_refresh: Option<bool>,
+    category: Option<String>,
 ) -> Result<Vec<DiscoverableCommand>, String> {
     let repos = CommandService::get_repos(&app_state.db).map_err(|e| e.to_string())?;
-    service
+    let mut commands = service
         .0
         .discover_available(&app_state.db, repos, force_refresh.unwrap_or(false))
         .await
+        .map_err(|e| e.to_string())?;
+    if let Some(wanted) = category.as_deref() {
+        commands.retain(|c| matches_category(c.category.as_deref(), &c.categories, wanted));
+    }
+    Ok(commands)
+}
+
+/// 发现可安装的 Commands，并附带每个仓库的数据来源（缓存/新获取）与扫描时间
+///
+/// 用于排查“新命令为何没有出现”（缓存过期）以及在 UI 中展示数据新鲜度
+#[tauri::command]
+pub async fn discover_available_commands_detailed(
+    service: State<'_, CommandServiceState>,
+    app_state: State<'_, AppState>,
+    force_refresh: Option<bool>,
+    category: Option<String>,
+) -> Result<DiscoveryResult<DiscoverableCommand>, String> {
+    let repos = CommandService::get_repos(&app_state.db).map_err(|e| e.to_string())?;
+    let mut result = service
+        .0
+        .discover_available_detailed(&app_state.db, repos, force_refresh.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(wanted) = category.as_deref() {
+        result
+            .items
+            .retain(|c| matches_category(c.category.as_deref(), &c.categories, wanted));
+    }
+    Ok(result)
+}
+
+/// 分页发现可安装的 Commands
+///
+/// # 参数
+/// - `force_refresh`: 是否强制刷新（跳过缓存，默认 false）
+/// - `page`: 页码（从 0 开始，默认 0）
+/// - `page_size`: 每页条目数（为空时返回全部）
+#[tauri::command]
+pub async fn discover_available_commands_paged(
+    service: State<'_, CommandServiceState>,
+    app_state: State<'_, AppState>,
+    force_refresh: Option<bool>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+) -> Result<PaginatedDiscovery<DiscoverableCommand>, String> {
+    let repos = CommandService::get_repos(&app_state.db).map_err(|e| e.to_string())?;
+    service
+        .0
+        .discover_available_paged(
+            &app_state.db,
+            repos,
+            force_refresh.unwrap_or(false),
+            page,
+            page_size,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 比较两个仓库中重叠的 Commands（仅 A / 仅 B / 冲突 / 一致）
+#[tauri::command]
+pub async fn compare_command_repos(
+    service: State<'_, CommandServiceState>,
+    app_state: State<'_, AppState>,
+    repo_a: CommandRepo,
+    repo_b: CommandRepo,
+) -> Result<RepoOverlap, String> {
+    service
+        .0
+        .compare_repos(&app_state.db, repo_a, repo_b)
+        .await
         .map_err(|e| e.to_string())
 }
 
@@ -214,6 +488,14 @@ pub fn get_command_content(id: String) -> Result<String, String> {
     CommandService::get_command_content(&id).map_err(|e| e.to_string())
 }
 
+/// 列出 frontmatter 存在解析问题的已安装 Commands
+#[tauri::command]
+pub fn list_command_parse_issues(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<FileParseIssues>, String> {
+    CommandService::list_parse_issues(&app_state.db).map_err(|e| e.to_string())
+}
+
 /// 在外部编辑器中打开 Command
 #[tauri::command]
 pub fn open_command_in_editor(id: String) -> Result<bool, String> {
@@ -236,14 +518,48 @@ pub fn get_command_repos(app_state: State<'_, AppState>) -> Result<Vec<CommandRe
     CommandService::get_repos(&app_state.db).map_err(|e| e.to_string())
 }
 
+/// 获取仓库缓存的 ccswitch.json 元数据（展示名称、描述、图标）
+///
+/// 仓库未提供该文件或尚未被发现过时返回 None
+#[tauri::command]
+pub fn get_repo_metadata(
+    owner: String,
+    name: String,
+    app_state: State<'_, AppState>,
+) -> Result<Option<RepoMetadata>, String> {
+    app_state
+        .db
+        .get_repo_metadata(&owner, &name)
+        .map_err(|e| e.to_string())
+}
+
 /// 添加 Command 仓库
 #[tauri::command]
-pub fn add_command_repo(
+pub async fn add_command_repo(
     repo: CommandRepo,
     app_state: State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<RepoValidation, String> {
+    // 先校验仓库，拼写错误或私有仓库缺少 Token 时立即报错；离线时只返回提示
+    let validation = CommandService::validate_repo(&app_state.db, &repo, ResourceType::Command)
+        .await
+        .map_err(|e| e.to_string())?;
     CommandService::add_repo(&app_state.db, &repo).map_err(|e| e.to_string())?;
-    Ok(true)
+    Ok(validation)
+}
+
+/// 导出仓库列表（JSON，不含资源）
+#[tauri::command]
+pub fn export_command_repos(app_state: State<'_, AppState>) -> Result<String, String> {
+    CommandService::export_repos(&app_state.db).map_err(|e| e.to_string())
+}
+
+/// 导入仓库列表（跳过内置仓库和已存在的仓库）
+#[tauri::command]
+pub fn import_command_repos(
+    json: String,
+    app_state: State<'_, AppState>,
+) -> Result<RepoImportResult, String> {
+    CommandService::import_repos(&app_state.db, &json).map_err(|e| e.to_string())
 }
 
 /// 删除 Command 仓库（不允许删除内置仓库）
@@ -254,11 +570,86 @@ pub fn remove_command_repo(
     app_state: State<'_, AppState>,
 ) -> Result<bool, String> {
     CommandService::remove_repo(&app_state.db, &owner, &name).map_err(|e| e.to_string())?;
-    // 同时删除该仓库的缓存
-    let _ = app_state.db.delete_repo_cache(&owner, &name);
     Ok(true)
 }
 
+/// 列出从某个仓库安装的全部资源（Commands/Agents/Hooks/Skills）
+///
+/// 移除仓库前用于展示卸载计划
+#[tauri::command]
+pub fn list_installed_by_repo(
+    owner: String,
+    name: String,
+    app_state: State<'_, AppState>,
+) -> Result<InstalledByRepo, String> {
+    RepoInstallsService::list_installed_by_repo(&app_state.db, &owner, &name)
+        .map_err(|e| e.to_string())
+}
+
+/// 卸载从某个仓库安装的全部资源（调用前应先经用户确认 `list_installed_by_repo` 的结果）
+///
+/// 其中有锁定的资源且未传 force 时整批取消
+#[tauri::command]
+pub fn uninstall_all_from_repo(
+    owner: String,
+    name: String,
+    force: Option<bool>,
+    app_state: State<'_, AppState>,
+) -> Result<RepoUninstallResult, String> {
+    RepoInstallsService::uninstall_all_from_repo(
+        &app_state.db,
+        &owner,
+        &name,
+        force.unwrap_or(false),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 从仓库的发现结果中排除某个 Command（不影响已安装的）
+#[tauri::command]
+pub fn exclude_command_from_repo(
+    owner: String,
+    name: String,
+    key: String,
+    app_state: State<'_, AppState>,
+) -> Result<bool, String> {
+    CommandService::exclude_from_repo(&app_state.db, &owner, &name, &key).map_err(|e| e.to_string())
+}
+
+/// 取消排除仓库中的某个 Command
+#[tauri::command]
+pub fn unexclude_command_from_repo(
+    owner: String,
+    name: String,
+    key: String,
+    app_state: State<'_, AppState>,
+) -> Result<bool, String> {
+    CommandService::unexclude_from_repo(&app_state.db, &owner, &name, &key)
+        .map_err(|e| e.to_string())
+}
+
+/// 安装仓库中 recipe.json 列出的整套资源（Commands/Agents/Hooks/Skills），支持从中断处恢复
+#[tauri::command]
+pub async fn install_recipe(
+    repo: CommandRepo,
+    recipe_path: String,
+    current_app: String,
+    resume_token: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<RecipeInstallResult, String> {
+    let app_type = parse_app_type(&current_app)?;
+    RecipeService::new()
+        .install_recipe(
+            &app_state.db,
+            &repo,
+            &recipe_path,
+            &app_type,
+            resume_token.as_deref(),
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// 恢复内置 Command 仓库（添加缺失的内置仓库，不删除用户添加的）
 #[tauri::command]
 pub fn restore_builtin_command_repos(app_state: State<'_, AppState>) -> Result<usize, String> {
@@ -281,6 +672,56 @@ pub fn is_builtin_command_repo(
         .map_err(|e| e.to_string())
 }
 
+/// 修改仓库描述（标记为用户编辑，内置仓库同步时不再覆盖）
+#[tauri::command]
+pub fn update_command_repo_descriptions(
+    owner: String,
+    name: String,
+    description_zh: Option<String>,
+    description_en: Option<String>,
+    description_ja: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<bool, String> {
+    app_state
+        .db
+        .update_command_repo_descriptions(
+            &owner,
+            &name,
+            description_zh.as_deref(),
+            description_en.as_deref(),
+            description_ja.as_deref(),
+        )
+        .map_err(|e| e.to_string())
+}
+
+/// 设置或清除仓库的描述编辑标记（清除后下次同步恢复内置描述）
+#[tauri::command]
+pub fn set_command_repo_user_edited_description(
+    owner: String,
+    name: String,
+    edited: bool,
+    app_state: State<'_, AppState>,
+) -> Result<bool, String> {
+    app_state
+        .db
+        .set_command_repo_user_edited_description(&owner, &name, edited)
+        .map_err(|e| e.to_string())
+}
+
+/// 设置仓库的仅浏览标记（仅浏览的仓库不能安装新资源）
+#[tauri::command]
+pub fn set_command_repo_browse_only(
+    owner: String,
+    name: String,
+    browse_only: bool,
+    app_state: State<'_, AppState>,
+) -> Result<bool, String> {
+    app_state
+        .db
+        .set_command_repo_browse_only(&owner, &name, browse_only)
+        .map_err(|e| e.to_string())
+}
+
 /// 清除 Commands 发现缓存
 ///
 /// # 参数
@@ -304,6 +745,14 @@ pub fn clear_command_cache(
     }
 }
 
+/// 清理已删除仓库残留的 Commands/Agents/Hooks 发现缓存
+#[tauri::command]
+pub fn prune_orphaned_caches(
+    app_state: State<'_, AppState>,
+) -> Result<DiscoveryCachePruneResult, String> {
+    CommandService::prune_orphaned_caches(&app_state.db).map_err(|e| e.to_string())
+}
+
 // ========== 变更检测命令 ==========
 
 /// 检测 Commands 变更
@@ -338,10 +787,36 @@ pub fn refresh_commands_from_ssot(app_state: State<'_, AppState>) -> Result<usiz
     CommandService::refresh_from_ssot(&app_state.db).map_err(|e| e.to_string())
 }
 
+/// 以 SSOT 目录为准对账 Commands 数据库
+///
+/// 纳入未记录的 SSOT 文件、刷新已变化的记录、删除文件已丢失的记录
+#[tauri::command]
+pub fn reconcile_commands_from_ssot(
+    app_state: State<'_, AppState>,
+) -> Result<ReconcileReport, String> {
+    CommandService::reconcile_from_ssot(&app_state.db).map_err(|e| e.to_string())
+}
+
 /// 同步所有 Commands 到应用目录
 ///
-/// 确保所有已启用的应用目录与 SSOT 保持一致
+/// 确保所有已启用的应用目录与 SSOT 保持一致，单个文件失败不会中断整批同步
 #[tauri::command]
-pub fn sync_commands_to_apps(app_state: State<'_, AppState>) -> Result<usize, String> {
+pub fn sync_commands_to_apps(app_state: State<'_, AppState>) -> Result<SyncReport, String> {
     CommandService::sync_all_to_apps(&app_state.db).map_err(|e| e.to_string())
 }
+
+/// 只同步内容与 SSOT 不一致的 Commands（一致的文件保持不动）
+#[tauri::command]
+pub fn sync_drifted_commands_to_apps(
+    app_state: State<'_, AppState>,
+) -> Result<DriftSyncReport, String> {
+    CommandService::sync_drifted_only(&app_state.db).map_err(|e| e.to_string())
+}
+
+/// 预演同步 Commands 到应用目录（不写入磁盘）
+#[tauri::command]
+pub fn plan_sync_commands_to_apps(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<SyncAction>, String> {
+    CommandService::plan_sync(&app_state.db).map_err(|e| e.to_string())
+}
@@ -374,3 +374,102 @@ pub async fn extract_common_config_snippet(
     crate::services::provider::ProviderService::extract_common_config_snippet(&state, app)
         .map_err(|e| e.to_string())
 }
+
+/// 获取 Commands/Agents/Hooks/Skills 的同步状态汇总（仅计数，适合轮询）
+#[tauri::command]
+pub async fn get_sync_status(
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<crate::services::config::SyncStatus, String> {
+    crate::services::ConfigService::sync_status(&state.db).map_err(|e| e.to_string())
+}
+
+/// 按应用统计已启用的 Commands/Agents/Hooks/Skills/MCP 数量
+#[tauri::command]
+pub async fn get_resource_counts(
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<
+    std::collections::HashMap<crate::app_config::AppType, crate::services::config::ResourceCounts>,
+    String,
+> {
+    crate::services::ConfigService::resource_counts(&state.db).map_err(|e| e.to_string())
+}
+
+/// 校验 Commands/Agents/Hooks 的 SSOT 文件完整性（只读，不访问网络）
+#[tauri::command]
+pub async fn verify_integrity(
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<Vec<crate::services::config::IntegrityIssue>, String> {
+    crate::services::ConfigService::verify_integrity(&state.db).map_err(|e| e.to_string())
+}
+
+/// 获取启动时完整性校验发现的问题（同时通过 `integrity-issues` 事件推送）
+#[tauri::command]
+pub fn get_startup_integrity_issues() -> Vec<crate::services::config::IntegrityIssue> {
+    crate::services::ConfigService::startup_integrity_issues()
+}
+
+/// 将源应用已启用的资源（Commands/Agents/Hooks/Skills/MCP）复制到目标应用
+#[tauri::command]
+pub async fn copy_app_config(
+    state: tauri::State<'_, crate::store::AppState>,
+    from: String,
+    to: String,
+    resource_types: Vec<crate::services::config::ConfigTarget>,
+) -> Result<crate::services::config::CopyConfigSummary, String> {
+    let from = AppType::from_str(&from).map_err(|e| e.to_string())?;
+    let to = AppType::from_str(&to).map_err(|e| e.to_string())?;
+    crate::services::ConfigService::copy_app_config(&state, from, to, resource_types)
+        .map_err(|e| e.to_string())
+}
+
+/// 列出应用目录中没有 SSOT 对应文件的 Commands/Agents 文件
+#[tauri::command]
+pub async fn list_extra_app_files(
+    resource_type: crate::services::update::ResourceType,
+) -> Result<Vec<crate::services::config::ExtraFile>, String> {
+    crate::services::ConfigService::list_extra_app_files(resource_type).map_err(|e| e.to_string())
+}
+
+/// 列出各应用、各类资源实际生效的 SSOT 与应用侧路径，用于问题排查
+#[tauri::command]
+pub async fn get_path_report() -> Result<crate::services::config::PathReport, String> {
+    Ok(crate::services::ConfigService::path_report())
+}
+
+/// 列出配置目录被覆盖的应用、覆盖路径及其是否存在
+#[tauri::command]
+pub async fn get_override_report() -> Result<Vec<crate::services::config::OverrideInfo>, String> {
+    Ok(crate::services::ConfigService::override_report())
+}
+
+/// 为指定应用启用所有已安装的 Commands/Agents/Hooks/Skills，返回变更数量与失败项
+#[tauri::command]
+pub async fn enable_all_for_app(
+    state: tauri::State<'_, crate::store::AppState>,
+    app: String,
+    resource_types: Vec<crate::services::update::ResourceType>,
+) -> Result<crate::services::config::BulkToggleResult, String> {
+    let app = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    crate::services::ConfigService::enable_all_for_app(&state.db, &app, &resource_types)
+        .map_err(|e| e.to_string())
+}
+
+/// 为指定应用禁用所有已安装的 Commands/Agents/Hooks/Skills，返回变更数量与失败项
+///
+/// 锁定的资源会被跳过，除非传入 force
+#[tauri::command]
+pub async fn disable_all_for_app(
+    state: tauri::State<'_, crate::store::AppState>,
+    app: String,
+    resource_types: Vec<crate::services::update::ResourceType>,
+    force: Option<bool>,
+) -> Result<crate::services::config::BulkToggleResult, String> {
+    let app = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    crate::services::ConfigService::disable_all_for_app(
+        &state.db,
+        &app,
+        &resource_types,
+        force.unwrap_or(false),
+    )
+    .map_err(|e| e.to_string())
+}
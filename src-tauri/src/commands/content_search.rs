@@ -0,0 +1,17 @@
+//! 资源正文搜索命令层
+
+use crate::services::content_search::{ContentHit, ContentSearchService};
+use crate::services::update::ResourceType;
+use crate::store::AppState;
+use tauri::State;
+
+/// 在已安装资源的正文中搜索（不区分大小写，每个文件的匹配数有上限）
+#[tauri::command]
+pub fn search_content(
+    resource_type: ResourceType,
+    query: String,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<ContentHit>, String> {
+    ContentSearchService::search_content(&app_state.db, resource_type, &query)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,38 @@
+//! 重复安装命令层
+//!
+//! 检测从不同仓库安装的同内容 Commands/Agents/Skills，并合并为一份
+
+use crate::services::duplicate::{DuplicateGroup, DuplicateService, MergeResult};
+use crate::services::update::ResourceType;
+use crate::store::AppState;
+use tauri::State;
+
+/// 按内容哈希查找重复安装的资源
+#[tauri::command]
+pub fn find_duplicate_installs(
+    resource_type: ResourceType,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    DuplicateService::find_duplicates(&app_state.db, resource_type).map_err(|e| e.to_string())
+}
+
+/// 合并重复资源：应用启用状态并入 `keep_id`，其余副本被卸载
+///
+/// 副本中有锁定资源且未传 force 时整批取消
+#[tauri::command]
+pub fn merge_duplicates(
+    resource_type: ResourceType,
+    keep_id: String,
+    remove_ids: Vec<String>,
+    force: Option<bool>,
+    app_state: State<'_, AppState>,
+) -> Result<MergeResult, String> {
+    DuplicateService::merge_duplicates(
+        &app_state.db,
+        resource_type,
+        &keep_id,
+        &remove_ids,
+        force.unwrap_or(false),
+    )
+    .map_err(|e| e.to_string())
+}
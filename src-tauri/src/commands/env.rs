@@ -1,7 +1,13 @@
-use crate::services::env_checker::{check_env_conflicts as check_conflicts, EnvConflict};
+use crate::services::env_checker::{
+    check_env_conflicts as check_conflicts, check_env_requirements as check_requirements,
+    EnvConflict,
+};
 use crate::services::env_manager::{
     delete_env_vars as delete_vars, restore_from_backup, BackupInfo,
 };
+use crate::services::update::ResourceType;
+use crate::store::AppState;
+use tauri::State;
 
 /// Check environment variable conflicts for a specific app
 #[tauri::command]
@@ -9,6 +15,16 @@ pub fn check_env_conflicts(app: String) -> Result<Vec<EnvConflict>, String> {
     check_conflicts(&app)
 }
 
+/// Check which `requires_env` variables of an installed resource are unset
+#[tauri::command]
+pub fn check_env_requirements(
+    resource_type: ResourceType,
+    id: String,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    check_requirements(&app_state.db, resource_type, &id)
+}
+
 /// Delete environment variables with backup
 #[tauri::command]
 pub fn delete_env_vars(conflicts: Vec<EnvConflict>) -> Result<BackupInfo, String> {
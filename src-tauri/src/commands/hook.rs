@@ -8,12 +8,19 @@
 
 use crate::app_config::{
     AppType, CommandRepo, DiscoverableHook, HookNamespace, InstallScope, InstalledHook,
-    UnmanagedHook,
+    PaginatedDiscovery, SyncAction, UnmanagedHook,
 };
-use crate::services::hook::{check_app_hooks_support, HookService};
+use crate::services::command::{CommandService, RepoValidation};
+use crate::services::config::MultiAppInstallResult;
+use crate::services::hook::{
+    check_app_hooks_support, BulkMoveResult, HookCommandIssue, HookRebuildSummary, HookService,
+    HookTestRunResult, HookValidationReport, OfficialImportResult,
+};
+use crate::services::update::ResourceType;
 use crate::store::AppState;
+use std::path::Path;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{Emitter, State};
 
 /// HookService 状态包装
 pub struct HookServiceState(pub Arc<HookService>);
@@ -84,6 +91,52 @@ pub async fn install_hook_unified(
     Ok(installed)
 }
 
+/// 一次安装 Hook 并启用到多个应用（全局范围），不支持 Hook 的应用被跳过并在结果中说明
+#[tauri::command]
+pub async fn install_hook_for_apps(
+    hook: DiscoverableHook,
+    apps: Vec<String>,
+    service: State<'_, HookServiceState>,
+    app_state: State<'_, AppState>,
+) -> Result<MultiAppInstallResult<InstalledHook>, String> {
+    let apps = apps
+        .iter()
+        .map(|app| app.parse::<AppType>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    service
+        .0
+        .install_for_apps(&app_state.db, &hook, apps)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 从粘贴的Hook JSON创建 Hook（id 为空时从 name 推导）
+#[tauri::command]
+pub fn create_hook_from_content(
+    content: String,
+    current_app: String,
+    id: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<InstalledHook, String> {
+    let app_type = parse_app_type(&current_app)?;
+    HookService::create_from_content(&app_state.db, id.as_deref(), &content, &app_type)
+        .map_err(|e| e.to_string())
+}
+
+/// 从本地的 Claude 官方 hooks 格式文件导入（每个事件类型一个 Hook）
+#[tauri::command]
+pub fn import_official_hooks(
+    path: String,
+    current_app: String,
+    app_state: State<'_, AppState>,
+) -> Result<OfficialImportResult, String> {
+    let app_type = parse_app_type(&current_app)?;
+    HookService::import_official_format(&app_state.db, Path::new(&path), &app_type)
+        .map_err(|e| e.to_string())
+}
+
 /// 卸载 Hook（统一卸载）
 #[tauri::command]
 pub fn uninstall_hook_unified(id: String, app_state: State<'_, AppState>) -> Result<bool, String> {
@@ -115,6 +168,29 @@ pub fn toggle_hook_app(
     Ok(true)
 }
 
+/// 设置 Hook 的用户备注
+#[tauri::command]
+pub fn set_hook_user_note(
+    id: String,
+    note: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<bool, String> {
+    HookService::set_user_note(&app_state.db, &id, note).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// 设置 Hook 的自定义显示名称（传空则恢复上游名称）
+#[tauri::command]
+pub fn set_hook_display_name(
+    id: String,
+    display_name: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<bool, String> {
+    HookService::set_display_name_override(&app_state.db, &id, display_name)
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
 /// 修改 Hook 的安装范围
 ///
 /// 参数：
@@ -137,6 +213,23 @@ pub fn change_hook_scope(
     Ok(true)
 }
 
+/// 切换已安装 Hook 的来源分支
+///
+/// 会先校验文件在新分支上存在，然后重新下载并同步
+#[tauri::command]
+pub async fn switch_hook_branch(
+    service: State<'_, HookServiceState>,
+    app_state: State<'_, AppState>,
+    id: String,
+    new_branch: String,
+) -> Result<InstalledHook, String> {
+    service
+        .0
+        .switch_branch(&app_state.db, &id, &new_branch)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// 更新 Hook 优先级
 #[tauri::command]
 pub fn update_hook_priority(
@@ -172,12 +265,105 @@ pub fn delete_hook_namespace(
     Ok(true)
 }
 
+/// 将 Hook 移动到另一个命名空间（空字符串表示根命名空间）
+#[tauri::command]
+pub fn move_hook_to_namespace(
+    id: String,
+    namespace: String,
+    app_state: State<'_, AppState>,
+) -> Result<InstalledHook, String> {
+    HookService::move_to_namespace(&app_state.db, &id, &namespace).map_err(|e| e.to_string())
+}
+
+/// 将 ID 匹配通配模式（如 `legacy/**`）的 Hooks 批量移动到另一个命名空间
+#[tauri::command]
+pub fn bulk_move_hooks_namespace(
+    pattern: String,
+    namespace: String,
+    app_state: State<'_, AppState>,
+) -> Result<BulkMoveResult, String> {
+    HookService::bulk_move_namespace(&app_state.db, &pattern, &namespace).map_err(|e| e.to_string())
+}
+
+/// 重建指定应用 settings.json 的 hooks 字段（先备份，再按数据库重新生成）
+#[tauri::command]
+pub fn rebuild_app_hooks(
+    app: String,
+    app_state: State<'_, AppState>,
+) -> Result<HookRebuildSummary, String> {
+    let app_type = parse_app_type(&app)?;
+    HookService::rebuild_app_hooks(&app_state.db, &app_type).map_err(|e| e.to_string())
+}
+
+/// 检测指定应用配置中的 hooks 在上次同步后是否被外部修改
+#[tauri::command]
+pub fn check_hooks_external_modification(
+    app: String,
+    app_state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let app_type = parse_app_type(&app)?;
+    HookService::external_modification_check(&app_state.db, &app_type).map_err(|e| e.to_string())
+}
+
 /// 扫描未管理的 Hooks
 #[tauri::command]
 pub fn scan_unmanaged_hooks(app_state: State<'_, AppState>) -> Result<Vec<UnmanagedHook>, String> {
     HookService::scan_unmanaged(&app_state.db).map_err(|e| e.to_string())
 }
 
+/// 预览 matcher 匹配的工具名
+///
+/// `tools` 缺省时使用 Claude Code 内置工具列表
+#[tauri::command]
+pub fn preview_hook_matcher(matcher: String, tools: Option<Vec<String>>) -> Vec<String> {
+    HookService::preview_matcher(&matcher, &tools.unwrap_or_default())
+}
+
+/// 校验已安装 Hooks 引用的脚本/命令是否存在且可执行
+#[tauri::command]
+pub fn validate_hook_commands(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<HookCommandIssue>, String> {
+    HookService::validate_hook_commands(&app_state.db).map_err(|e| e.to_string())
+}
+
+/// 校验所有 Hook 的 SSOT JSON 文件
+///
+/// `quarantine_invalid` 为 true 时停用解析失败的 Hooks，并发射 `hooks-quarantined` 事件
+#[tauri::command]
+pub fn validate_hooks(
+    quarantine_invalid: Option<bool>,
+    app: tauri::AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<HookValidationReport, String> {
+    let mut report = HookValidationReport::default();
+    if quarantine_invalid.unwrap_or(false) {
+        report.quarantined =
+            HookService::quarantine_invalid(&app_state.db).map_err(|e| e.to_string())?;
+        if !report.quarantined.is_empty() {
+            let _ = app.emit("hooks-quarantined", &report.quarantined);
+        }
+    }
+    report.results = HookService::validate_all(&app_state.db).map_err(|e| e.to_string())?;
+    Ok(report)
+}
+
+/// 在临时工作目录中以子进程试运行 Hook 的命令（不做隔离），不修改应用配置
+///
+/// `simulated_event` 为空时按 Hook 的事件类型生成示例事件
+#[tauri::command]
+pub async fn test_run_hook(
+    id: String,
+    simulated_event: Option<serde_json::Value>,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<HookTestRunResult>, String> {
+    let db = Arc::clone(&app_state.db);
+    tokio::task::spawn_blocking(move || HookService::test_run(&db, &id, simulated_event))
+        .await
+        .map_err(|e| format!("试运行任务失败: {e}"))?
+        .map_err(|e| e.to_string())
+}
+
 // ========== 发现功能命令 ==========
 
 /// 发现可安装的 Hooks（从仓库获取，带缓存支持）
@@ -198,6 +384,34 @@ pub async fn discover_available_hooks(
         .map_err(|e| e.to_string())
 }
 
+/// 分页发现可安装的 Hooks
+///
+/// # 参数
+/// - `force_refresh`: 是否强制刷新（跳过缓存，默认 false）
+/// - `page`: 页码（从 0 开始，默认 0）
+/// - `page_size`: 每页条目数（为空时返回全部）
+#[tauri::command]
+pub async fn discover_available_hooks_paged(
+    service: State<'_, HookServiceState>,
+    app_state: State<'_, AppState>,
+    force_refresh: Option<bool>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+) -> Result<PaginatedDiscovery<DiscoverableHook>, String> {
+    let repos = HookService::get_repos(&app_state.db).map_err(|e| e.to_string())?;
+    service
+        .0
+        .discover_available_paged(
+            &app_state.db,
+            repos,
+            force_refresh.unwrap_or(false),
+            page,
+            page_size,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // ========== 文件操作命令 ==========
 
 /// 获取 Hook 文件内容
@@ -230,9 +444,16 @@ pub fn get_hook_repos(app_state: State<'_, AppState>) -> Result<Vec<CommandRepo>
 
 /// 添加 Hook 仓库（共用 command_repos 表）
 #[tauri::command]
-pub fn add_hook_repo(repo: CommandRepo, app_state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn add_hook_repo(
+    repo: CommandRepo,
+    app_state: State<'_, AppState>,
+) -> Result<RepoValidation, String> {
+    // 先校验仓库，拼写错误或私有仓库缺少 Token 时立即报错；离线时只返回提示
+    let validation = CommandService::validate_repo(&app_state.db, &repo, ResourceType::Hook)
+        .await
+        .map_err(|e| e.to_string())?;
     HookService::add_repo(&app_state.db, &repo).map_err(|e| e.to_string())?;
-    Ok(true)
+    Ok(validation)
 }
 
 /// 删除 Hook 仓库
@@ -243,8 +464,6 @@ pub fn remove_hook_repo(
     app_state: State<'_, AppState>,
 ) -> Result<bool, String> {
     HookService::remove_repo(&app_state.db, &owner, &name).map_err(|e| e.to_string())?;
-    // 同时删除该仓库的缓存
-    let _ = app_state.db.delete_hook_repo_cache(&owner, &name);
     Ok(true)
 }
 
@@ -288,3 +507,9 @@ pub fn refresh_hooks_from_ssot(app_state: State<'_, AppState>) -> Result<usize,
 pub fn sync_hooks_to_apps(app_state: State<'_, AppState>) -> Result<usize, String> {
     HookService::sync_all_to_apps(&app_state.db).map_err(|e| e.to_string())
 }
+
+/// 预演同步 Hooks 到应用目录（不写入磁盘）
+#[tauri::command]
+pub fn plan_sync_hooks_to_apps(app_state: State<'_, AppState>) -> Result<Vec<SyncAction>, String> {
+    HookService::plan_sync(&app_state.db).map_err(|e| e.to_string())
+}
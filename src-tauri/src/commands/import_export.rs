@@ -9,9 +9,10 @@ use crate::commands::sync_support::{
     post_sync_warning_from_result, run_post_import_sync, success_payload_with_warning,
 };
 use crate::database::backup::BackupEntry;
-use crate::database::Database;
+use crate::database::{Database, MissingColumn};
 use crate::error::AppError;
 use crate::services::provider::ProviderService;
+use crate::services::ConfigService;
 use crate::store::AppState;
 
 // ─── File import/export ──────────────────────────────────────
@@ -174,3 +175,30 @@ pub fn rename_db_backup(
 pub fn delete_db_backup(filename: String) -> Result<(), String> {
     Database::delete_backup(&filename).map_err(|e| e.to_string())
 }
+
+/// Snapshot the whole database to a file chosen by the user
+#[tauri::command]
+pub async fn snapshot_db(state: State<'_, AppState>, path: String) -> Result<i32, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        ConfigService::snapshot_db(&db, &PathBuf::from(path))
+    })
+    .await
+    .map_err(|e| format!("Snapshot failed: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// Validate a database snapshot and stage it to replace the database on next start
+#[tauri::command]
+pub async fn restore_db(path: String) -> Result<i32, String> {
+    tauri::async_runtime::spawn_blocking(move || ConfigService::restore_db(&PathBuf::from(path)))
+        .await
+        .map_err(|e| format!("Restore failed: {e}"))?
+        .map_err(|e: AppError| e.to_string())
+}
+
+/// Report columns missing from resource tables compared to the current schema
+#[tauri::command]
+pub fn verify_schema(state: State<'_, AppState>) -> Result<Vec<MissingColumn>, String> {
+    state.db.verify_schema().map_err(|e| e.to_string())
+}
@@ -205,3 +205,11 @@ pub async fn import_mcp_from_apps(state: State<'_, AppState>) -> Result<usize, S
     total += McpService::import_from_hermes(&state).unwrap_or(0);
     Ok(total)
 }
+
+/// 检查 MCP 配置中的常见问题（非致命警告）
+#[tauri::command]
+pub async fn lint_mcp_servers(
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::services::mcp::McpWarning>, String> {
+    McpService::lint(&state).map_err(|e| e.to_string())
+}
@@ -8,8 +8,10 @@ mod codex_oauth;
 mod coding_plan;
 pub mod command;
 mod config;
+mod content_search;
 mod copilot;
 mod deeplink;
+mod duplicate;
 mod env;
 mod failover;
 mod global_proxy;
@@ -20,19 +22,29 @@ mod lightweight;
 mod mcp;
 mod misc;
 mod model_fetch;
+mod namespace_check;
 mod omo;
 mod openclaw;
+mod orphaned_installs;
+mod pin_staleness;
 mod plugin;
 mod project;
 mod prompt;
+mod provenance;
 mod provider;
 mod proxy;
+mod repo_cache;
+mod resource_export;
+mod resource_lock;
+mod resource_preview;
+mod resource_stream;
 mod session_manager;
 mod settings;
 pub mod skill;
 mod stream_check;
 mod subscription;
 mod sync_support;
+mod trash;
 mod update;
 mod usage;
 mod webdav_sync;
@@ -46,8 +58,10 @@ pub use codex_oauth::*;
 pub use coding_plan::*;
 pub use command::*;
 pub use config::*;
+pub use content_search::*;
 pub use copilot::*;
 pub use deeplink::*;
+pub use duplicate::*;
 pub use env::*;
 pub use failover::*;
 pub use global_proxy::*;
@@ -58,18 +72,28 @@ pub use lightweight::*;
 pub use mcp::*;
 pub use misc::*;
 pub use model_fetch::*;
+pub use namespace_check::*;
 pub use omo::*;
 pub use openclaw::*;
+pub use orphaned_installs::*;
+pub use pin_staleness::*;
 pub use plugin::*;
 pub use project::*;
 pub use prompt::*;
+pub use provenance::*;
 pub use provider::*;
 pub use proxy::*;
+pub use repo_cache::*;
+pub use resource_export::*;
+pub use resource_lock::*;
+pub use resource_preview::*;
+pub use resource_stream::*;
 pub use session_manager::*;
 pub use settings::*;
 pub use skill::*;
 pub use stream_check::*;
 pub use subscription::*;
+pub use trash::*;
 pub use update::*;
 pub use usage::*;
 pub use webdav_sync::*;
@@ -0,0 +1,48 @@
+//! 命名空间一致性命令层
+
+use crate::services::namespace_check::{
+    NamespaceCheckService, NamespaceCleanupReport, NamespaceMismatch,
+};
+use crate::services::update::ResourceType;
+use crate::store::AppState;
+use tauri::State;
+
+/// 检查 Commands/Agents/Hooks 数据库中的命名空间与 SSOT 文件位置是否一致
+#[tauri::command]
+pub fn verify_namespace_consistency(
+    resource_type: ResourceType,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<NamespaceMismatch>, String> {
+    NamespaceCheckService::verify_namespace_consistency(&app_state.db, resource_type)
+        .map_err(|e| e.to_string())
+}
+
+/// 按 SSOT 文件的实际位置修复命名空间，返回已修复的项
+#[tauri::command]
+pub fn repair_namespaces(
+    resource_type: ResourceType,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<NamespaceMismatch>, String> {
+    NamespaceCheckService::repair_namespaces(&app_state.db, resource_type)
+        .map_err(|e| e.to_string())
+}
+
+/// 列出数据库中没有记录、但目录仍残留的空命名空间
+#[tauri::command]
+pub fn list_empty_namespaces(
+    resource_type: ResourceType,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    NamespaceCheckService::list_empty_namespaces(&app_state.db, resource_type)
+        .map_err(|e| e.to_string())
+}
+
+/// 删除空命名空间在 SSOT 和各应用目录中的残留目录
+#[tauri::command]
+pub fn cleanup_empty_namespaces(
+    resource_type: ResourceType,
+    app_state: State<'_, AppState>,
+) -> Result<NamespaceCleanupReport, String> {
+    NamespaceCheckService::cleanup_empty_namespaces(&app_state.db, resource_type)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,35 @@
+//! 未启用资源命令层
+
+use crate::services::config::ConfigTarget;
+use crate::services::orphaned_installs::{OrphanUninstallResult, OrphanedInstallsService};
+use crate::store::AppState;
+use tauri::State;
+
+/// 列出已安装但没有在任何应用中启用的资源 ID
+#[tauri::command]
+pub fn list_orphaned_installs(
+    resource_type: ConfigTarget,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    OrphanedInstallsService::list_orphaned_installs(&app_state, resource_type)
+        .map_err(|e| e.to_string())
+}
+
+/// 卸载用户确认过的未启用资源（调用前应先经用户确认 `list_orphaned_installs` 的结果）
+///
+/// 目标中有锁定资源且未传 force 时整批取消
+#[tauri::command]
+pub fn uninstall_orphaned_installs(
+    resource_type: ConfigTarget,
+    ids: Vec<String>,
+    force: Option<bool>,
+    app_state: State<'_, AppState>,
+) -> Result<OrphanUninstallResult, String> {
+    OrphanedInstallsService::uninstall_orphaned(
+        &app_state,
+        resource_type,
+        ids,
+        force.unwrap_or(false),
+    )
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,15 @@
+//! 固定提交落后检测命令层
+
+use crate::services::pin_staleness::{PinStalenessService, PinnedStatus};
+use crate::store::AppState;
+use tauri::State;
+
+/// 检查固定到提交 SHA 的资源在当前分支中是否已有变化
+#[tauri::command]
+pub async fn check_pinned_staleness(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<PinnedStatus>, String> {
+    PinStalenessService::check_pinned_staleness(&app_state.db)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,28 @@
+//! 资源来源命令层
+
+use crate::services::provenance::{InstallSource, Provenance, ProvenanceService};
+use crate::services::update::ResourceType;
+use crate::store::AppState;
+use tauri::State;
+
+/// 获取已安装资源的来源信息（仓库、分支、路径与安装时的哈希）
+#[tauri::command]
+pub fn get_provenance(
+    resource_type: ResourceType,
+    id: String,
+    app_state: State<'_, AppState>,
+) -> Result<Provenance, String> {
+    ProvenanceService::get_provenance(&app_state.db, resource_type, &id).map_err(|e| e.to_string())
+}
+
+/// 获取资源的安装来源（GitHub 地址），以及其他已配置仓库中同一 key 是否有不同版本
+#[tauri::command]
+pub async fn get_install_source(
+    resource_type: ResourceType,
+    id: String,
+    app_state: State<'_, AppState>,
+) -> Result<InstallSource, String> {
+    ProvenanceService::get_install_source(&app_state.db, resource_type, &id)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -5,8 +5,10 @@ use crate::app_config::AppType;
 use crate::commands::copilot::CopilotAuthState;
 use crate::error::AppError;
 use crate::provider::Provider;
+use crate::services::provider::{ProviderRotationPolicy, ProviderRotationState, ProviderTemplate};
 use crate::services::{
-    EndpointLatency, ProviderService, ProviderSortUpdate, SpeedtestService, SwitchResult,
+    EndpointLatency, ProviderService, ProviderSortUpdate, SpeedtestRecord, SpeedtestService,
+    SwitchResult,
 };
 use crate::store::AppState;
 use std::str::FromStr;
@@ -45,6 +47,23 @@ pub fn add_provider(
         .map_err(|e| e.to_string())
 }
 
+/// 获取内置供应商模板（预填 Base URL / 默认模型）
+#[tauri::command]
+pub fn get_provider_templates() -> Result<Vec<ProviderTemplate>, String> {
+    ProviderService::templates().map_err(|e| e.to_string())
+}
+
+/// 使用内置模板创建供应商，只需填写 API Key
+#[tauri::command]
+pub fn create_provider_from_template(
+    state: State<'_, AppState>,
+    template_id: String,
+    api_key: String,
+) -> Result<Provider, String> {
+    ProviderService::create_from_template(state.inner(), &template_id, &api_key)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn update_provider(
     state: State<'_, AppState>,
@@ -375,11 +394,45 @@ pub fn read_live_provider_settings(app: String) -> Result<serde_json::Value, Str
 
 #[tauri::command]
 pub async fn test_api_endpoints(
+    state: State<'_, AppState>,
     urls: Vec<String>,
     #[allow(non_snake_case)] timeoutSecs: Option<u64>,
 ) -> Result<Vec<EndpointLatency>, String> {
-    SpeedtestService::test_endpoints(urls, timeoutSecs)
+    let results = SpeedtestService::test_endpoints(urls, timeoutSecs)
         .await
+        .map_err(|e| e.to_string())?;
+
+    // 测速历史仅作记录，保存失败不影响本次结果
+    let tested_at = chrono::Utc::now().timestamp();
+    if let Err(e) = state.db.save_speedtest_results(&results, tested_at) {
+        log::warn!("保存测速历史失败: {e}");
+    }
+
+    Ok(results)
+}
+
+/// 查询端点测速历史（按时间倒序，默认最多 50 条）
+#[tauri::command]
+pub fn get_speedtest_history(
+    state: State<'_, AppState>,
+    url: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<SpeedtestRecord>, String> {
+    state
+        .db
+        .get_speedtest_history(url.as_deref(), limit.unwrap_or(50))
+        .map_err(|e| e.to_string())
+}
+
+/// 清空测速历史，未指定 url 时清空全部
+#[tauri::command]
+pub fn clear_speedtest_history(
+    state: State<'_, AppState>,
+    url: Option<String>,
+) -> Result<u64, String> {
+    state
+        .db
+        .clear_speedtest_history(url.as_deref())
         .map_err(|e| e.to_string())
 }
 
@@ -534,3 +587,68 @@ pub fn get_opencode_live_provider_ids() -> Result<Vec<String>, String> {
 // ============================================================================
 // OpenClaw 专属命令 → 已迁移至 commands/openclaw.rs
 // ============================================================================
+
+/// 开启/关闭供应商密钥加密（同时迁移已有密钥），返回被改写的供应商数量
+#[tauri::command]
+pub fn set_provider_key_encryption(
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<usize, String> {
+    ProviderService::set_key_encryption(state.inner(), enabled).map_err(|e| e.to_string())
+}
+
+/// 获取应用的供应商轮换策略
+#[tauri::command]
+pub fn get_provider_rotation_policy(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<ProviderRotationPolicy, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    state
+        .db
+        .get_provider_rotation_policy(app_type.as_str())
+        .map_err(|e| e.to_string())
+}
+
+/// 更新应用的供应商轮换策略
+#[tauri::command]
+pub fn set_provider_rotation_policy(
+    state: State<'_, AppState>,
+    app: String,
+    policy: ProviderRotationPolicy,
+) -> Result<(), String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    state
+        .db
+        .set_provider_rotation_policy(app_type.as_str(), &policy)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取应用最近一次供应商轮换的状态
+#[tauri::command]
+pub fn get_provider_rotation_state(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<ProviderRotationState, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    state
+        .db
+        .get_provider_rotation_state(app_type.as_str())
+        .map_err(|e| e.to_string())
+}
+
+/// 当前供应商接近限额时轮换到下一个供应商，返回切换到的供应商 ID
+#[tauri::command]
+pub fn rotate_provider_if_needed(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<Option<String>, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    let rotated =
+        ProviderService::rotate_if_needed(state.inner(), app_type).map_err(|e| e.to_string())?;
+    if let Some(rotation) = &rotated {
+        crate::notify_provider_rotated(&app_handle, &app, rotation);
+    }
+    Ok(rotated.map(|rotation| rotation.to))
+}
@@ -0,0 +1,48 @@
+//! 单仓库缓存刷新命令层
+
+use crate::commands::agent::AgentServiceState;
+use crate::commands::command::CommandServiceState;
+use crate::commands::hook::HookServiceState;
+use crate::services::command::CommandService;
+use crate::services::update::ResourceType;
+use crate::store::AppState;
+use tauri::State;
+
+/// 只重新下载并扫描一个仓库，更新其 Commands/Agents/Hooks 发现缓存
+///
+/// 返回该仓库中该类资源的新数量。`branch` 为空时使用仓库配置的分支，
+/// 与配置的分支不一致时报错；缓存保存失败时同样报错
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn refresh_repo_cache(
+    owner: String,
+    name: String,
+    branch: String,
+    resource_type: ResourceType,
+    app_state: State<'_, AppState>,
+    commands: State<'_, CommandServiceState>,
+    agents: State<'_, AgentServiceState>,
+    hooks: State<'_, HookServiceState>,
+) -> Result<usize, String> {
+    let db = &app_state.db;
+    let repo = CommandService::find_repo_for_refresh(db, &owner, &name, &branch)
+        .map_err(|e| e.to_string())?;
+
+    let count = match resource_type {
+        ResourceType::Command => commands.0.refresh_repo_cache(db, &repo).await,
+        ResourceType::Agent => agents.0.refresh_repo_cache(db, &repo).await,
+        ResourceType::Hook => hooks.0.refresh_repo_cache(db, &repo).await,
+        ResourceType::Skill => {
+            return Err(format!("{resource_type} 不支持按仓库刷新缓存"));
+        }
+    }
+    .map_err(|e| e.to_string())?;
+
+    log::info!(
+        "已刷新仓库缓存 {}/{}@{} ({resource_type}): {count} 个",
+        repo.owner,
+        repo.name,
+        repo.branch
+    );
+    Ok(count)
+}
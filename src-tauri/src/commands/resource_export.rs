@@ -0,0 +1,20 @@
+//! 单个资源导出命令层
+
+use crate::services::resource_export::{ExportedResource, ResourceExportService};
+use crate::services::update::ResourceType;
+use crate::store::AppState;
+use std::path::PathBuf;
+use tauri::State;
+
+/// 导出单个 Command/Agent/Hook（文件内容 + 清单），`target_path` 不为空时同时写入磁盘
+#[tauri::command]
+pub fn export_resource(
+    resource_type: ResourceType,
+    id: String,
+    target_path: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<ExportedResource, String> {
+    let target = target_path.map(PathBuf::from);
+    ResourceExportService::export_resource(&app_state.db, resource_type, &id, target.as_deref())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,32 @@
+//! 资源锁定命令层
+
+use crate::services::config::ConfigTarget;
+use crate::services::resource_lock::ResourceLockService;
+use crate::store::AppState;
+use tauri::State;
+
+/// 锁定或解锁资源（锁定的资源不参与批量更新、卸载与禁用）
+#[tauri::command]
+pub fn set_resource_locked(
+    resource_type: ConfigTarget,
+    id: String,
+    locked: bool,
+    app_state: State<'_, AppState>,
+) -> Result<(), String> {
+    ResourceLockService::set_locked(&app_state.db, resource_type, &id, locked)
+        .map_err(|e| e.to_string())
+}
+
+/// 列出该类型下已锁定资源的 ID（按 ID 排序）
+#[tauri::command]
+pub fn get_locked_resources(
+    resource_type: ConfigTarget,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let mut ids: Vec<String> = ResourceLockService::locked_ids(&app_state.db, resource_type)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+    ids.sort();
+    Ok(ids)
+}
@@ -0,0 +1,28 @@
+//! 资源预览命令层
+
+use crate::services::resource_preview::ResourcePreviewService;
+use crate::services::update::ResourceType;
+use crate::store::AppState;
+use tauri::State;
+
+/// 获取资源正文渲染后的 HTML（已清理脚本等不安全内容）
+#[tauri::command]
+pub fn get_resource_rendered(
+    resource_type: ResourceType,
+    id: String,
+    app_state: State<'_, AppState>,
+) -> Result<String, String> {
+    ResourcePreviewService::render(&app_state.db, resource_type, &id).map_err(|e| e.to_string())
+}
+
+/// 获取资源 SSOT 文件路径，供编辑使用
+#[tauri::command]
+pub fn get_resource_path(
+    resource_type: ResourceType,
+    id: String,
+    app_state: State<'_, AppState>,
+) -> Result<String, String> {
+    ResourcePreviewService::resource_path(&app_state.db, resource_type, &id)
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,123 @@
+//! 已安装资源分批推送命令层
+//!
+//! 资源数量很大时一次性返回会阻塞首屏渲染，这里按页查询数据库，
+//! 每页通过事件推送给前端，最后发送一个带总数的完成事件。
+
+use crate::error::AppError;
+use crate::services::update::ResourceType;
+use crate::store::AppState;
+use indexmap::IndexMap;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+/// 默认每批推送的资源数量
+const DEFAULT_CHUNK_SIZE: usize = 100;
+
+/// 单批资源事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceChunkEvent<T: Serialize + Clone> {
+    request_id: String,
+    resource_type: ResourceType,
+    /// 本批第一项在已推送列表中的位置
+    offset: usize,
+    items: Vec<T>,
+}
+
+/// 推送完成事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceStreamDoneEvent {
+    request_id: String,
+    resource_type: ResourceType,
+    total: usize,
+}
+
+/// 逐页查询并推送，返回推送的总数
+///
+/// 偏移按 DAO 扫描的原始行数推进，直到某页扫描不足 `chunk_size` 行为止；
+/// 解析失败被跳过的行不会让分页提前结束或重叠
+fn stream_pages<T: Serialize + Clone>(
+    app: &AppHandle,
+    request_id: &str,
+    resource_type: ResourceType,
+    chunk_size: usize,
+    mut fetch: impl FnMut(usize, usize) -> Result<(IndexMap<String, T>, usize), AppError>,
+) -> Result<usize, String> {
+    let mut offset = 0;
+    let mut total = 0;
+    loop {
+        let (page, scanned) = fetch(offset, chunk_size).map_err(|e| e.to_string())?;
+        let items: Vec<T> = page.into_values().collect();
+        let count = items.len();
+
+        if count > 0 {
+            let event = ResourceChunkEvent {
+                request_id: request_id.to_string(),
+                resource_type,
+                offset: total,
+                items,
+            };
+            app.emit("installed-resources-chunk", event)
+                .map_err(|e| e.to_string())?;
+        }
+
+        total += count;
+        offset += scanned;
+        if scanned < chunk_size {
+            break;
+        }
+    }
+    Ok(total)
+}
+
+/// 分批推送已安装资源列表
+///
+/// 每批通过 `installed-resources-chunk` 事件发送，全部发送后触发
+/// `installed-resources-done` 事件（携带总数）。`request_id` 由前端生成，用于区分并发请求。
+#[tauri::command]
+pub async fn stream_installed_resources(
+    resource_type: ResourceType,
+    request_id: String,
+    chunk_size: Option<usize>,
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let chunk_size = chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE).max(1);
+    let db = &app_state.db;
+
+    let total = match resource_type {
+        ResourceType::Command => {
+            stream_pages(&app, &request_id, resource_type, chunk_size, |o, l| {
+                db.get_installed_commands_page(o, Some(l))
+            })?
+        }
+        ResourceType::Agent => {
+            stream_pages(&app, &request_id, resource_type, chunk_size, |o, l| {
+                db.get_installed_agents_page(o, Some(l))
+            })?
+        }
+        ResourceType::Hook => {
+            stream_pages(&app, &request_id, resource_type, chunk_size, |o, l| {
+                db.get_installed_hooks_page(o, Some(l))
+            })?
+        }
+        ResourceType::Skill => {
+            stream_pages(&app, &request_id, resource_type, chunk_size, |o, l| {
+                db.get_installed_skills_page(o, Some(l))
+            })?
+        }
+    };
+
+    app.emit(
+        "installed-resources-done",
+        ResourceStreamDoneEvent {
+            request_id,
+            resource_type,
+            total,
+        },
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(total)
+}
@@ -21,6 +21,10 @@ fn merge_settings_for_save(
         }
         _ => {}
     }
+    // 加密开关需要同时迁移数据库，只能通过 set_provider_key_encryption 修改
+    incoming.encrypt_provider_keys = existing.encrypt_provider_keys;
+    // 超时需经过校验，只能通过 set_download_timeouts 修改
+    incoming.download_timeouts = existing.download_timeouts;
     incoming
 }
 
@@ -81,7 +85,7 @@ pub async fn set_auto_launch(enabled: bool) -> Result<bool, String> {
 #[cfg(test)]
 mod tests {
     use super::merge_settings_for_save;
-    use crate::settings::{AppSettings, WebDavSyncSettings};
+    use crate::settings::{AppSettings, TimeoutConfig, WebDavSyncSettings};
 
     #[test]
     fn save_settings_should_preserve_existing_webdav_when_payload_omits_it() {
@@ -103,6 +107,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn save_settings_should_not_reset_download_timeouts() {
+        let mut existing = AppSettings::default();
+        existing.download_timeouts.repo_download_secs = 300;
+
+        let incoming = AppSettings::default();
+        let merged = merge_settings_for_save(incoming, &existing);
+
+        assert_eq!(merged.download_timeouts.repo_download_secs, 300);
+    }
+
+    #[test]
+    fn download_timeouts_must_be_positive() {
+        let mut config = TimeoutConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.file_download_secs = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn save_settings_should_not_toggle_key_encryption() {
+        let mut existing = AppSettings::default();
+        existing.encrypt_provider_keys = true;
+
+        let incoming = AppSettings::default();
+        let merged = merge_settings_for_save(incoming, &existing);
+
+        assert!(merged.encrypt_provider_keys);
+    }
+
     #[test]
     fn save_settings_should_keep_incoming_webdav_when_present() {
         let mut existing = AppSettings::default();
@@ -283,8 +318,9 @@ pub async fn get_log_config(
 #[tauri::command]
 pub async fn set_log_config(
     state: tauri::State<'_, crate::AppState>,
-    config: crate::proxy::types::LogConfig,
+    mut config: crate::proxy::types::LogConfig,
 ) -> Result<bool, String> {
+    config.level = normalize_log_level(&config.level)?;
     state
         .db
         .set_log_config(&config)
@@ -297,3 +333,43 @@ pub async fn set_log_config(
     );
     Ok(true)
 }
+
+/// 运行时调整日志级别（同时开启日志），无需重启即可切换到 trace 复现问题
+#[tauri::command]
+pub async fn set_log_level(
+    state: tauri::State<'_, crate::AppState>,
+    level: String,
+) -> Result<crate::proxy::types::LogConfig, String> {
+    let mut config = state.db.get_log_config().map_err(|e| e.to_string())?;
+    config.enabled = true;
+    config.level = normalize_log_level(&level)?;
+    state
+        .db
+        .set_log_config(&config)
+        .map_err(|e| e.to_string())?;
+    log::set_max_level(config.to_level_filter());
+    log::info!("日志级别已切换为 {}", config.level);
+    Ok(config)
+}
+
+fn normalize_log_level(level: &str) -> Result<String, String> {
+    crate::proxy::types::LogConfig::normalize_level(level).ok_or_else(|| {
+        format!(
+            "无效的日志级别: {level}（可选: {}）",
+            crate::proxy::types::LogConfig::LEVELS.join(", ")
+        )
+    })
+}
+
+/// 获取资源下载超时配置
+#[tauri::command]
+pub async fn get_download_timeouts() -> Result<crate::settings::TimeoutConfig, String> {
+    Ok(crate::settings::get_timeout_config())
+}
+
+/// 设置资源下载超时配置（各项须为正数）
+#[tauri::command]
+pub async fn set_download_timeouts(config: crate::settings::TimeoutConfig) -> Result<bool, String> {
+    crate::settings::set_timeout_config(config).map_err(|e| e.to_string())?;
+    Ok(true)
+}
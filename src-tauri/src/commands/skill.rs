@@ -6,6 +6,7 @@
 
 use crate::app_config::{AppType, InstallScope, InstalledSkill, UnmanagedSkill};
 use crate::error::format_skill_error;
+use crate::services::config::MultiAppInstallResult;
 use crate::services::skill::{
     DiscoverableSkill, ImportSkillSelection, MigrationResult, Skill, SkillBackupEntry, SkillRepo,
     SkillService, SkillStorageLocation, SkillUninstallResult, SkillUpdateInfo,
@@ -80,6 +81,27 @@ pub async fn install_skill_unified(
         .map_err(|e| e.to_string())
 }
 
+/// 一次安装 Skill 并启用到多个应用（全局范围），不支持 Skill 的应用被跳过并在结果中说明
+#[tauri::command]
+pub async fn install_skill_for_apps(
+    skill: DiscoverableSkill,
+    apps: Vec<String>,
+    service: State<'_, SkillServiceState>,
+    app_state: State<'_, AppState>,
+) -> Result<MultiAppInstallResult<InstalledSkill>, String> {
+    let apps = apps
+        .iter()
+        .map(|app| app.parse::<AppType>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    service
+        .0
+        .install_for_apps(&app_state.db, &skill, apps)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// 卸载 Skill（新版统一卸载）
 #[tauri::command]
 pub fn uninstall_skill_unified(
@@ -102,20 +124,15 @@ pub fn restore_skill_backup(
 
 /// 批量卸载 Skills
 ///
-/// 返回成功卸载的数量
+/// 返回成功卸载的数量；目标中有锁定的 Skill 且未传 force 时整批取消
 #[tauri::command]
 pub fn uninstall_skills_batch(
     ids: Vec<String>,
+    force: Option<bool>,
     app_state: State<'_, AppState>,
 ) -> Result<usize, String> {
-    let mut success_count = 0;
-    for id in &ids {
-        match SkillService::uninstall(&app_state.db, id) {
-            Ok(_) => success_count += 1,
-            Err(e) => log::warn!("卸载 Skill {} 失败: {}", id, e),
-        }
-    }
-    Ok(success_count)
+    SkillService::uninstall_batch(&app_state.db, &ids, force.unwrap_or(false))
+        .map_err(|e| e.to_string())
 }
 
 /// 切换 Skill 的应用启用状态
@@ -399,6 +416,20 @@ pub fn is_builtin_skill_repo(
         .map_err(|e| e.to_string())
 }
 
+/// 设置仓库的仅浏览标记（仅浏览的仓库不能安装新 Skill）
+#[tauri::command]
+pub fn set_skill_repo_browse_only(
+    owner: String,
+    name: String,
+    browse_only: bool,
+    app_state: State<'_, AppState>,
+) -> Result<bool, String> {
+    app_state
+        .db
+        .set_skill_repo_browse_only(&owner, &name, browse_only)
+        .map_err(|e| e.to_string())
+}
+
 // ========== 命名空间管理命令 ==========
 
 /// 获取所有 Skill 命名空间
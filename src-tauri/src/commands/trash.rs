@@ -0,0 +1,37 @@
+//! 回收站命令层
+//!
+//! 卸载的 Commands/Agents/Hooks 会先进入回收站，可在保留期内恢复
+
+use crate::services::trash::{TrashEntry, TrashService, TrashedResource};
+use crate::store::AppState;
+use tauri::State;
+
+/// 获取回收站条目列表
+#[tauri::command]
+pub fn get_trash_entries() -> Result<Vec<TrashEntry>, String> {
+    TrashService::list().map_err(|e| e.to_string())
+}
+
+/// 从回收站恢复资源
+#[tauri::command]
+pub fn restore_from_trash(
+    record_id: String,
+    app_state: State<'_, AppState>,
+) -> Result<TrashedResource, String> {
+    TrashService::restore(&app_state.db, &record_id).map_err(|e| e.to_string())
+}
+
+/// 永久删除单个回收站条目
+#[tauri::command]
+pub fn delete_trash_entry(record_id: String) -> Result<bool, String> {
+    TrashService::delete(&record_id).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// 清空回收站
+///
+/// 返回删除的条目数
+#[tauri::command]
+pub fn empty_trash() -> Result<usize, String> {
+    TrashService::empty().map_err(|e| e.to_string())
+}
@@ -2,14 +2,17 @@
 //!
 //! 提供 Skills/Commands/Hooks/Agents 的更新检测和执行功能的 Tauri 命令。
 
-use crate::app_config::{AppType, DiscoverableCommand, DiscoverableAgent};
+use crate::app_config::{AppType, DiscoverableAgent, DiscoverableCommand};
 use crate::database::Database;
 use crate::error::AppError;
 use crate::services::agent::AgentService;
 use crate::services::command::CommandService;
+use crate::services::config::ConfigTarget;
+use crate::services::hook::HookService;
 use crate::services::github_api::{GitHubApiService, RateLimitInfo, UpdateCheckResult};
+use crate::services::resource_lock::ResourceLockService;
 use crate::services::skill::{DiscoverableSkill, SkillService};
-use crate::services::update::{BatchCheckResult, BatchUpdateResult, ResourceType, UpdateExecuteResult, UpdateService};
+use crate::services::update::{apps_after_update, BatchCheckResult, BatchUpdateResult, HashNormalizeResult, ResourceType, TagUpdateCheckResult, UpdateExecuteResult, UpdateService};
 use crate::store::AppState;
 use serde::{Deserialize, Serialize};
 use tauri::State;
@@ -99,6 +102,8 @@ pub async fn check_commands_updates(
         // 使用数据库中保存的 source_path
         let result = service
             .check_file_resource_update(
+                db,
+                ResourceType::Command,
                 &command.id,
                 command.repo_owner.as_deref(),
                 command.repo_name.as_deref(),
@@ -159,6 +164,8 @@ pub async fn check_commands_updates_by_ids(
     for command in commands_to_check {
         let result = service
             .check_file_resource_update(
+                db,
+                ResourceType::Command,
                 &command.id,
                 command.repo_owner.as_deref(),
                 command.repo_name.as_deref(),
@@ -199,6 +206,8 @@ pub async fn check_hooks_updates(
     for hook in hooks.values() {
         let result = service
             .check_file_resource_update(
+                db,
+                ResourceType::Hook,
                 &hook.id,
                 hook.repo_owner.as_deref(),
                 hook.repo_name.as_deref(),
@@ -239,6 +248,8 @@ pub async fn check_agents_updates(
     for agent in agents.values() {
         let result = service
             .check_file_resource_update(
+                db,
+                ResourceType::Agent,
                 &agent.id,
                 agent.repo_owner.as_deref(),
                 agent.repo_name.as_deref(),
@@ -299,6 +310,8 @@ pub async fn check_agents_updates_by_ids(
     for agent in agents_to_check {
         let result = service
             .check_file_resource_update(
+                db,
+                ResourceType::Agent,
                 &agent.id,
                 agent.repo_owner.as_deref(),
                 agent.repo_name.as_deref(),
@@ -388,6 +401,85 @@ pub async fn check_resource_updates(
     }
 }
 
+/// 与上游指定 tag 比对单个资源（而非分支 HEAD），结果附带比对所用的 tag
+#[tauri::command]
+pub async fn check_update_against_tag(
+    app_state: State<'_, AppState>,
+    resource_type: ResourceType,
+    id: String,
+    tag: String,
+) -> Result<TagUpdateCheckResult, AppError> {
+    let db = &app_state.db;
+    let github_token = db.get_setting("github_pat")?;
+    let service = UpdateService::new(github_token);
+    service
+        .check_update_against_tag(db, resource_type, &id, &tag)
+        .await
+}
+
+/// 列出仓库的 tag，按语义化版本从新到旧排序
+#[tauri::command]
+pub async fn list_repo_tags(
+    app_state: State<'_, AppState>,
+    owner: String,
+    repo: String,
+) -> Result<Vec<String>, AppError> {
+    let github_token = app_state.db.get_setting("github_pat")?;
+    let service = GitHubApiService::new(github_token);
+    service
+        .list_tags(&owner, &repo)
+        .await
+        .map_err(|e| AppError::Message(e.to_string()))
+}
+
+// ========== 上游差异对比 ==========
+
+/// 获取已安装资源与仓库当前版本的差异
+///
+/// 按 source_path 拉取远程内容，与本地 SSOT 文件生成 unified diff，内容一致时返回 None。
+/// 不依赖 file_hash，可排查哈希算法不一致导致的“已是最新”误判。
+#[tauri::command]
+pub async fn get_upstream_diff(
+    app_state: State<'_, AppState>,
+    resource_type: ResourceType,
+    id: String,
+) -> Result<Option<String>, AppError> {
+    UpdateService::upstream_diff(&app_state.db, resource_type, &id).await
+}
+
+/// 丢弃本地修改，将单个资源还原为安装时记录的上游版本，返回还原后的基线哈希
+#[tauri::command]
+pub async fn revert_to_upstream(
+    app_state: State<'_, AppState>,
+    resource_type: ResourceType,
+    id: String,
+) -> Result<Option<String>, AppError> {
+    UpdateService::revert_to_upstream(&app_state.db, resource_type, &id).await
+}
+
+/// 导出指定资源类型的发现缓存（JSON Lines）
+///
+/// 只读诊断用途：排查“仓库中的资源没有出现在发现列表”时，查看扫描器实际缓存的内容
+/// （含 repo/branch/source_path）。均为公开仓库元数据，不做脱敏。
+#[tauri::command]
+pub fn dump_discovery_cache(
+    app_state: State<'_, AppState>,
+    resource_type: ResourceType,
+) -> Result<String, AppError> {
+    let db = &app_state.db;
+    let dump = match resource_type {
+        ResourceType::Command => CommandService::dump_discovery_cache(db),
+        ResourceType::Agent => AgentService::dump_discovery_cache(db),
+        ResourceType::Hook => HookService::dump_discovery_cache(db),
+        ResourceType::Skill => {
+            return Err(AppError::Message(
+                "Skill 不使用发现缓存，暂不支持导出".to_string(),
+            ));
+        }
+    };
+    dump.map_err(|e| AppError::Message(e.to_string()))
+}
+
 // ========== 更新执行命令 ==========
 
 use std::sync::Arc;
@@ -465,10 +557,19 @@ async fn update_skill_internal(
         let _ = std::fs::remove_dir_all(&old_path);
     }
 
+    // 重新安装后写回的启用状态：默认恢复更新开始时的快照，
+    // 关闭 preserve_app_state_on_update 时以此刻数据库中的状态为准
+    let apps = apps_after_update(
+        installed.apps.clone(),
+        db.get_installed_skill(&skill_id)?
+            .map(|current| current.apps),
+        crate::settings::effective_preserve_app_state_on_update(),
+    );
+
     // 确定当前启用的应用（用于安装时的同步）
-    let current_app = if installed.apps.claude {
+    let current_app = if apps.claude {
         AppType::Claude
-    } else if installed.apps.codex {
+    } else if apps.codex {
         AppType::Codex
     } else {
         AppType::Gemini
@@ -479,17 +580,22 @@ async fn update_skill_internal(
 
     match skill_service.install(db, &discoverable, &current_app).await {
         Ok(updated_skill) => {
-            // 恢复原有的应用启用状态（install 只启用 current_app）
-            db.update_skill_apps(&skill_id, &installed.apps)?;
+            // 恢复应用启用状态（install 只启用 current_app）
+            db.update_skill_apps(&skill_id, &apps)?;
+
+            // 所有应用都已关闭时，撤回 install 写入 current_app 的文件
+            if !apps.claude && !apps.codex && !apps.gemini {
+                let _ = SkillService::remove_from_app(&installed.directory, &current_app);
+            }
 
             // 同步到其他启用的应用
-            if installed.apps.claude && current_app != AppType::Claude {
+            if apps.claude && current_app != AppType::Claude {
                 let _ = SkillService::copy_to_app(&installed.directory, &AppType::Claude);
             }
-            if installed.apps.codex && current_app != AppType::Codex {
+            if apps.codex && current_app != AppType::Codex {
                 let _ = SkillService::copy_to_app(&installed.directory, &AppType::Codex);
             }
-            if installed.apps.gemini && current_app != AppType::Gemini {
+            if apps.gemini && current_app != AppType::Gemini {
                 let _ = SkillService::copy_to_app(&installed.directory, &AppType::Gemini);
             }
 
@@ -523,12 +629,21 @@ pub async fn execute_skill_update(
 }
 
 /// 批量更新 Skills
+///
+/// 目标中有锁定的 Skill 且未传 force 时整批取消
 #[tauri::command]
 pub async fn update_skills_batch(
     app_state: State<'_, AppState>,
     skill_ids: Vec<String>,
+    force: Option<bool>,
 ) -> Result<BatchUpdateResult, AppError> {
     let db = &app_state.db;
+    ResourceLockService::ensure_unlocked(
+        db,
+        ConfigTarget::Skill,
+        skill_ids.iter().map(String::as_str),
+        force.unwrap_or(false),
+    )?;
     let mut results = Vec::new();
     let mut success_count = 0u32;
     let mut failed_count = 0u32;
@@ -696,6 +811,8 @@ async fn update_command_internal(
     // 检查更新并获取新的 hash
     let check_result = update_service
         .check_file_resource_update(
+            db,
+            ResourceType::Command,
             &command_id,
             Some(&repo_owner),
             Some(&repo_name),
@@ -724,11 +841,13 @@ async fn update_command_internal(
         namespace: installed.namespace.clone(),
         filename: installed.filename.clone(),
         category: installed.category.clone(),
+        categories: installed.categories.clone(),
         readme_url: installed.readme_url.clone(),
         repo_owner: repo_owner.clone(),
         repo_name: repo_name.clone(),
         repo_branch: repo_branch.clone(),
         source_path: Some(source_path.clone()),
+        localized: Default::default(),
     };
 
     // 删除 SSOT 中的旧文件，强制重新下载
@@ -740,10 +859,19 @@ async fn update_command_internal(
         let _ = std::fs::remove_file(&old_path);
     }
 
+    // 重新安装后写回的启用状态：默认恢复更新开始时的快照，
+    // 关闭 preserve_app_state_on_update 时以此刻数据库中的状态为准
+    let apps = apps_after_update(
+        installed.apps.clone(),
+        db.get_installed_command(&command_id)?
+            .map(|current| current.apps),
+        crate::settings::effective_preserve_app_state_on_update(),
+    );
+
     // 确定当前启用的应用（用于安装时的同步）
-    let current_app = if installed.apps.claude {
+    let current_app = if apps.claude {
         AppType::Claude
-    } else if installed.apps.codex {
+    } else if apps.codex {
         AppType::Codex
     } else {
         AppType::Gemini
@@ -754,17 +882,22 @@ async fn update_command_internal(
 
     match command_service.install(db, &discoverable, &current_app).await {
         Ok(updated_command) => {
-            // 恢复原有的应用启用状态（install 只启用 current_app）
-            db.update_command_apps(&command_id, &installed.apps)?;
+            // 恢复应用启用状态（install 只启用 current_app）
+            db.update_command_apps(&command_id, &apps)?;
+
+            // 所有应用都已关闭时，撤回 install 写入 current_app 的文件
+            if !apps.claude && !apps.codex && !apps.gemini {
+                let _ = CommandService::remove_from_app(&installed.id, &current_app);
+            }
 
             // 同步到其他启用的应用
-            if installed.apps.claude && current_app != AppType::Claude {
+            if apps.claude && current_app != AppType::Claude {
                 let _ = CommandService::copy_to_app(&installed.id, &AppType::Claude);
             }
-            if installed.apps.codex && current_app != AppType::Codex {
+            if apps.codex && current_app != AppType::Codex {
                 let _ = CommandService::copy_to_app(&installed.id, &AppType::Codex);
             }
-            if installed.apps.gemini && current_app != AppType::Gemini {
+            if apps.gemini && current_app != AppType::Gemini {
                 let _ = CommandService::copy_to_app(&installed.id, &AppType::Gemini);
             }
 
@@ -798,12 +931,21 @@ pub async fn update_command(
 }
 
 /// 批量更新 Commands
+///
+/// 目标中有锁定的 Command 且未传 force 时整批取消
 #[tauri::command]
 pub async fn update_commands_batch(
     app_state: State<'_, AppState>,
     command_ids: Vec<String>,
+    force: Option<bool>,
 ) -> Result<BatchUpdateResult, AppError> {
     let db = &app_state.db;
+    ResourceLockService::ensure_unlocked(
+        db,
+        ConfigTarget::Command,
+        command_ids.iter().map(String::as_str),
+        force.unwrap_or(false),
+    )?;
     let mut results = Vec::new();
     let mut success_count = 0u32;
     let mut failed_count = 0u32;
@@ -966,6 +1108,8 @@ async fn update_agent_internal(
     // 检查更新并获取新的 hash
     let check_result = update_service
         .check_file_resource_update(
+            db,
+            ResourceType::Agent,
             &agent_id,
             Some(&repo_owner),
             Some(&repo_name),
@@ -1000,6 +1144,7 @@ async fn update_agent_internal(
         repo_name: repo_name.clone(),
         repo_branch: repo_branch.clone(),
         source_path: installed.source_path.clone(),
+        localized: Default::default(),
     };
 
     // 删除 SSOT 中的旧文件，强制重新下载
@@ -1011,10 +1156,19 @@ async fn update_agent_internal(
         let _ = std::fs::remove_file(&old_path);
     }
 
+    // 重新安装后写回的启用状态：默认恢复更新开始时的快照，
+    // 关闭 preserve_app_state_on_update 时以此刻数据库中的状态为准
+    let apps = apps_after_update(
+        installed.apps.clone(),
+        db.get_installed_agent(&agent_id)?
+            .map(|current| current.apps),
+        crate::settings::effective_preserve_app_state_on_update(),
+    );
+
     // 确定当前启用的应用（用于安装时的同步）
-    let current_app = if installed.apps.claude {
+    let current_app = if apps.claude {
         AppType::Claude
-    } else if installed.apps.codex {
+    } else if apps.codex {
         AppType::Codex
     } else {
         AppType::Gemini
@@ -1025,17 +1179,22 @@ async fn update_agent_internal(
 
     match agent_service.install(db, &discoverable, &current_app).await {
         Ok(updated_agent) => {
-            // 恢复原有的应用启用状态（install 只启用 current_app）
-            db.update_agent_apps(&agent_id, &installed.apps)?;
+            // 恢复应用启用状态（install 只启用 current_app）
+            db.update_agent_apps(&agent_id, &apps)?;
+
+            // 所有应用都已关闭时，撤回 install 写入 current_app 的文件
+            if !apps.claude && !apps.codex && !apps.gemini {
+                let _ = AgentService::remove_from_app(&installed.id, &current_app);
+            }
 
             // 同步到其他启用的应用
-            if installed.apps.claude && current_app != AppType::Claude {
+            if apps.claude && current_app != AppType::Claude {
                 let _ = AgentService::copy_to_app(&installed.id, &AppType::Claude);
             }
-            if installed.apps.codex && current_app != AppType::Codex {
+            if apps.codex && current_app != AppType::Codex {
                 let _ = AgentService::copy_to_app(&installed.id, &AppType::Codex);
             }
-            if installed.apps.gemini && current_app != AppType::Gemini {
+            if apps.gemini && current_app != AppType::Gemini {
                 let _ = AgentService::copy_to_app(&installed.id, &AppType::Gemini);
             }
 
@@ -1069,12 +1228,21 @@ pub async fn update_agent(
 }
 
 /// 批量更新 Agents
+///
+/// 目标中有锁定的 Agent 且未传 force 时整批取消
 #[tauri::command]
 pub async fn update_agents_batch(
     app_state: State<'_, AppState>,
     agent_ids: Vec<String>,
+    force: Option<bool>,
 ) -> Result<BatchUpdateResult, AppError> {
     let db = &app_state.db;
+    ResourceLockService::ensure_unlocked(
+        db,
+        ConfigTarget::Agent,
+        agent_ids.iter().map(String::as_str),
+        force.unwrap_or(false),
+    )?;
     let mut results = Vec::new();
     let mut success_count = 0u32;
     let mut failed_count = 0u32;
@@ -1194,3 +1362,13 @@ pub async fn fix_agents_hash(
         results,
     })
 }
+
+/// 把安装时回退计算的本地内容哈希替换为 Git blob SHA
+///
+/// 消除 Commands/Agents/Hooks 因哈希格式不同而一直显示「有更新」的问题
+#[tauri::command]
+pub fn normalize_hashes_to_blob_sha(
+    app_state: State<'_, AppState>,
+) -> Result<HashNormalizeResult, AppError> {
+    UpdateService::normalize_hashes_to_blob_sha(&app_state.db)
+}
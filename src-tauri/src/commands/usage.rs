@@ -223,6 +223,12 @@ pub fn sync_session_usage(
     Ok(result)
 }
 
+/// 清除已有请求日志中的内容字段，只保留元数据
+#[tauri::command]
+pub fn redact_request_logs(state: State<'_, AppState>) -> Result<usize, AppError> {
+    state.db.redact_request_log_content()
+}
+
 /// 获取数据来源分布
 #[tauri::command]
 pub fn get_usage_data_sources(
@@ -126,6 +126,79 @@ pub fn get_app_config_path() -> PathBuf {
     get_app_config_dir().join("config.json")
 }
 
+/// 各应用的配置目录（已应用 settings 中的目录覆盖）
+pub fn get_app_config_dirs() -> Vec<(&'static str, PathBuf)> {
+    vec![
+        ("Claude", get_claude_config_dir()),
+        ("Codex", crate::codex_config::get_codex_config_dir()),
+        ("Gemini", crate::gemini_config::get_gemini_dir()),
+        ("OpenCode", crate::opencode_config::get_opencode_dir()),
+        ("OpenClaw", crate::openclaw_config::get_openclaw_dir()),
+        ("Hermes", crate::hermes_config::get_hermes_dir()),
+    ]
+}
+
+/// 规范化路径用于比较
+///
+/// 解析最近的已存在祖先目录（处理符号链接），其余部分按词法拼接并消除 `.`/`..`
+fn normalize_for_compare(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    let base = loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            break canonical;
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => break existing.to_path_buf(),
+        }
+    };
+
+    let mut result = base;
+    for name in rest.into_iter().rev() {
+        match name.to_str() {
+            Some(".") => {}
+            Some("..") => {
+                result.pop();
+            }
+            _ => result.push(name),
+        }
+    }
+    result
+}
+
+/// 校验 SSOT 目录不等于、也不位于任何应用配置目录内
+///
+/// SSOT 位于应用目录内时，同步到应用会把文件复制回 SSOT 自身，导致循环复制与数据损坏
+pub fn check_ssot_dir(ssot_dir: &Path, app_dirs: &[(&str, PathBuf)]) -> Result<(), AppError> {
+    let ssot = normalize_for_compare(ssot_dir);
+    for (app, dir) in app_dirs {
+        if ssot.starts_with(normalize_for_compare(dir)) {
+            return Err(AppError::InvalidInput(format!(
+                "SSOT 目录 {} 位于 {app} 配置目录 {} 内，请选择其他目录",
+                ssot_dir.display(),
+                dir.display()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// 按当前应用配置目录校验 SSOT 目录
+pub fn validate_ssot_dir(ssot_dir: &Path) -> Result<(), AppError> {
+    check_ssot_dir(ssot_dir, &get_app_config_dirs())
+}
+
+/// 启动时检查现有配置是否违反 SSOT 目录约束，仅记录警告
+pub fn warn_if_ssot_dir_invalid() {
+    if let Err(e) = validate_ssot_dir(&get_app_config_dir()) {
+        log::warn!("SSOT 目录配置有误，Commands/Agents/Hooks/Skills 操作将被拒绝: {e}");
+    }
+}
+
 /// 清理供应商名称，确保文件名安全
 #[allow(dead_code)]
 pub fn sanitize_provider_name(name: &str) -> String {
@@ -271,6 +344,22 @@ mod tests {
         let override_dir = PathBuf::from("/");
         assert!(derive_mcp_path_from_override(&override_dir).is_none());
     }
+
+    #[test]
+    fn check_ssot_dir_rejects_dirs_inside_app_dirs() {
+        let home = tempfile::tempdir().unwrap();
+        let claude = home.path().join(".claude");
+        fs::create_dir_all(&claude).unwrap();
+        let app_dirs = [("Claude", claude.clone())];
+
+        assert!(check_ssot_dir(&claude, &app_dirs).is_err());
+        assert!(check_ssot_dir(&claude.join("commands"), &app_dirs).is_err());
+        assert!(check_ssot_dir(&home.path().join("x/../.claude/agents"), &app_dirs).is_err());
+
+        assert!(check_ssot_dir(&home.path().join(".cc-switch"), &app_dirs).is_ok());
+        // 仅前缀相同的兄弟目录不算位于其中
+        assert!(check_ssot_dir(&home.path().join(".claude-ssot"), &app_dirs).is_ok());
+    }
 }
 
 /// 复制文件
@@ -15,6 +15,9 @@ use tempfile::NamedTempFile;
 
 const CC_SWITCH_SQL_EXPORT_HEADER: &str = "-- CC Switch SQLite 导出";
 
+/// 待恢复的数据库快照（位于配置目录，下次启动时替换主库）
+pub(crate) const PENDING_RESTORE_FILE: &str = "cc-switch.db.restore";
+
 /// Tables whose data rows are skipped when exporting for WebDAV sync.
 const SYNC_SKIP_TABLES: &[&str] = &[
     "proxy_request_logs",
@@ -163,6 +166,60 @@ impl Database {
         Ok(snapshot)
     }
 
+    /// 使用 SQLite backup API 将当前数据库写入 `path`，返回快照记录的 Schema 版本
+    ///
+    /// 目标文件已存在时会被覆盖
+    pub(crate) fn snapshot_to_file(&self, path: &Path) -> Result<i32, AppError> {
+        if path.exists() {
+            fs::remove_file(path).map_err(|e| AppError::io(path, e))?;
+        }
+
+        let conn = lock_conn!(self.conn);
+        let version = Self::get_user_version(&conn)?;
+        let mut dest_conn =
+            Connection::open(path).map_err(|e| AppError::Database(e.to_string()))?;
+        {
+            let backup = Backup::new(&conn, &mut dest_conn)
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            backup
+                .step(-1)
+                .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        // 显式写入 Schema 版本，恢复时据此校验
+        Self::set_user_version(&dest_conn, version)?;
+
+        Ok(version)
+    }
+
+    /// 启动时若存在待恢复的快照，先将当前主库复制到备份目录，再用快照替换主库
+    pub(crate) fn apply_pending_restore(db_path: &Path) -> Result<(), AppError> {
+        let Some(dir) = db_path.parent() else {
+            return Ok(());
+        };
+        let pending = dir.join(PENDING_RESTORE_FILE);
+        if !pending.exists() {
+            return Ok(());
+        }
+
+        if db_path.exists() {
+            let backup_dir = dir.join("backups");
+            fs::create_dir_all(&backup_dir).map_err(|e| AppError::io(&backup_dir, e))?;
+            let safety_path = backup_dir.join(format!(
+                "db_backup_before_restore_{}.db",
+                Local::now().format("%Y%m%d_%H%M%S")
+            ));
+            fs::copy(db_path, &safety_path).map_err(|e| AppError::io(db_path, e))?;
+            log::info!(
+                "Backed up current database to {} before restoring snapshot",
+                safety_path.display()
+            );
+        }
+
+        fs::rename(&pending, db_path).map_err(|e| AppError::io(&pending, e))?;
+        log::info!("Database restored from pending snapshot");
+        Ok(())
+    }
+
     fn validate_cc_switch_sql_export(sql: &str) -> Result<(), AppError> {
         let trimmed = sql.trim_start();
         if trimmed.starts_with(CC_SWITCH_SQL_EXPORT_HEADER) {
@@ -276,6 +333,16 @@ impl Database {
                 log::warn!("Periodic stream_check_logs cleanup failed: {e}");
             }
         }
+        match self.cleanup_old_speedtest_history(
+            crate::settings::effective_speedtest_history_retain_days(),
+        ) {
+            Ok(deleted) => {
+                reclaimed_rows += deleted;
+            }
+            Err(e) => {
+                log::warn!("Periodic speedtest_history cleanup failed: {e}");
+            }
+        }
         match self.rollup_and_prune(30) {
             Ok(deleted) => {
                 reclaimed_rows += deleted;
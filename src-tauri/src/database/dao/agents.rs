@@ -26,6 +26,18 @@ impl Database {
 
     /// 获取所有已安装的 Agents
     pub fn get_all_installed_agents(&self) -> Result<IndexMap<String, InstalledAgent>, AppError> {
+        self.get_installed_agents_page(0, None)
+            .map(|(agents, _)| agents)
+    }
+
+    /// 分页获取已安装的 Agents（排序与 `get_all_installed_agents` 一致），`limit` 为空时不限数量
+    ///
+    /// 同时返回本页扫描的原始行数（含解析时被跳过的行），调用方据此推进 `offset`
+    pub fn get_installed_agents_page(
+        &self,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> Result<(IndexMap<String, InstalledAgent>, usize), AppError> {
         let conn = lock_conn!(self.conn);
         let mut stmt = conn
             .prepare(
@@ -34,15 +46,17 @@ impl Database {
                        model, tools, extra_metadata,
                        repo_owner, repo_name, repo_branch, readme_url, source_path,
                        enabled_claude, enabled_codex, enabled_gemini,
-                       file_hash, installed_at, scope, project_path
+                       file_hash, installed_at, scope, project_path,
+                       user_note, display_name_override, requires_env
                 FROM agents
-                ORDER BY namespace, filename
+                ORDER BY namespace, filename, id
+                LIMIT ?1 OFFSET ?2
                 "#,
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         let rows = stmt
-            .query_map([], |row| {
+            .query_map(params![limit.map_or(-1, |l| l as i64), offset as i64], |row| {
                 Ok(InstalledAgent {
                     id: row.get(0)?,
                     name: row.get(1)?,
@@ -70,17 +84,25 @@ impl Database {
                     installed_at: row.get(17)?,
                     scope: row.get::<_, Option<String>>(18)?.unwrap_or_else(|| "global".to_string()),
                     project_path: row.get(19)?,
+                    user_note: row.get(20)?,
+                    display_name_override: row.get(21)?,
+                    requires_env: row
+                        .get::<_, Option<String>>(22)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
                 })
             })
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         let mut agents = IndexMap::new();
+        let mut scanned = 0;
         for row in rows {
             let agent = row.map_err(|e| AppError::Database(e.to_string()))?;
+            scanned += 1;
             agents.insert(agent.id.clone(), agent);
         }
 
-        Ok(agents)
+        Ok((agents, scanned))
     }
 
     /// 获取单个 Agent
@@ -93,7 +115,8 @@ impl Database {
                        model, tools, extra_metadata,
                        repo_owner, repo_name, repo_branch, readme_url, source_path,
                        enabled_claude, enabled_codex, enabled_gemini,
-                       file_hash, installed_at, scope, project_path
+                       file_hash, installed_at, scope, project_path,
+                       user_note, display_name_override, requires_env
                 FROM agents
                 WHERE id = ?1
                 "#,
@@ -129,6 +152,12 @@ impl Database {
                     installed_at: row.get(17)?,
                     scope: row.get::<_, Option<String>>(18)?.unwrap_or_else(|| "global".to_string()),
                     project_path: row.get(19)?,
+                    user_note: row.get(20)?,
+                    display_name_override: row.get(21)?,
+                    requires_env: row
+                        .get::<_, Option<String>>(22)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
                 })
             })
             .optional()
@@ -147,8 +176,10 @@ impl Database {
                 model, tools, extra_metadata,
                 repo_owner, repo_name, repo_branch, readme_url, source_path,
                 enabled_claude, enabled_codex, enabled_gemini,
-                file_hash, installed_at, scope, project_path
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)
+                file_hash, installed_at, scope, project_path,
+                user_note, display_name_override, requires_env, locked
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23,
+                COALESCE((SELECT locked FROM agents WHERE id = ?1), 0))
             "#,
             params![
                 agent.id,
@@ -171,6 +202,9 @@ impl Database {
                 agent.installed_at,
                 agent.scope,
                 agent.project_path,
+                agent.user_note,
+                agent.display_name_override,
+                to_json_string(&agent.requires_env)?,
             ],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -240,6 +274,36 @@ impl Database {
         Ok(affected > 0)
     }
 
+    /// 更新 Agent 的用户备注
+    pub fn update_agent_user_note(&self, id: &str, user_note: Option<&str>) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                "UPDATE agents SET user_note = ?1 WHERE id = ?2",
+                params![user_note, id],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(affected > 0)
+    }
+
+    /// 更新 Agent 的自定义显示名称
+    pub fn update_agent_display_name_override(
+        &self,
+        id: &str,
+        display_name: Option<&str>,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                "UPDATE agents SET display_name_override = ?1 WHERE id = ?2",
+                params![display_name, id],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(affected > 0)
+    }
+
     /// 按命名空间获取 Agents
     pub fn get_agents_by_namespace(
         &self,
@@ -253,7 +317,8 @@ impl Database {
                        model, tools, extra_metadata,
                        repo_owner, repo_name, repo_branch, readme_url, source_path,
                        enabled_claude, enabled_codex, enabled_gemini,
-                       file_hash, installed_at, scope, project_path
+                       file_hash, installed_at, scope, project_path,
+                       user_note, display_name_override, requires_env
                 FROM agents
                 WHERE namespace = ?1
                 ORDER BY filename
@@ -290,6 +355,12 @@ impl Database {
                     installed_at: row.get(17)?,
                     scope: row.get::<_, Option<String>>(18)?.unwrap_or_else(|| "global".to_string()),
                     project_path: row.get(19)?,
+                    user_note: row.get(20)?,
+                    display_name_override: row.get(21)?,
+                    requires_env: row
+                        .get::<_, Option<String>>(22)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
                 })
             })
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -502,31 +573,7 @@ impl Database {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    fn create_test_agent(id: &str, namespace: &str, filename: &str) -> InstalledAgent {
-        InstalledAgent {
-            id: id.to_string(),
-            name: format!("Test Agent {}", filename),
-            description: Some("A test agent".to_string()),
-            namespace: namespace.to_string(),
-            filename: filename.to_string(),
-            model: Some("sonnet".to_string()),
-            tools: Some(vec!["Read".to_string(), "Write".to_string()]),
-            extra_metadata: None,
-            repo_owner: Some("test-owner".to_string()),
-            repo_name: Some("test-repo".to_string()),
-            repo_branch: Some("main".to_string()),
-            readme_url: None,
-            source_path: Some(format!("agents/{}.md", filename)),
-            apps: AgentApps {
-                claude: true,
-                codex: false,
-                gemini: false,
-            },
-            file_hash: Some("abc123".to_string()),
-            installed_at: 1700000000,
-        }
-    }
+    use crate::test_support::create_test_agent;
 
     #[test]
     fn test_agent_crud() {
@@ -617,6 +664,7 @@ mod tests {
                 repo_name: "agents".to_string(),
                 repo_branch: "main".to_string(),
                 source_path: Some("agents/debugger.md".to_string()),
+                localized: Default::default(),
             },
         ];
 
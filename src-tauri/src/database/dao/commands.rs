@@ -7,12 +7,30 @@ use crate::app_config::{
 };
 use crate::database::{lock_conn, to_json_string, Database};
 use crate::error::AppError;
+use crate::services::builtin_repos::BuiltinRepoConfig;
+use crate::services::update::ResourceType;
 use indexmap::IndexMap;
 use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
 
 /// 缓存过期时间：24小时（秒）
 pub const CACHE_EXPIRY_SECONDS: i64 = 24 * 60 * 60;
 
+/// 清理发现缓存时各表删除的行数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryCachePruneResult {
+    pub commands: usize,
+    pub agents: usize,
+    pub hooks: usize,
+}
+
+impl DiscoveryCachePruneResult {
+    pub fn total(&self) -> usize {
+        self.commands + self.agents + self.hooks
+    }
+}
+
 /// Command 发现缓存条目
 #[derive(Debug, Clone)]
 pub struct CommandDiscoveryCache {
@@ -23,11 +41,45 @@ pub struct CommandDiscoveryCache {
     pub scanned_at: i64,
 }
 
+/// 读取 categories 列（JSON 数组）
+///
+/// 旧数据该列为空时，回退为仅包含单个 category 的列表
+fn read_categories(row: &rusqlite::Row<'_>) -> rusqlite::Result<Vec<String>> {
+    let categories = row
+        .get::<_, Option<String>>(24)?
+        .and_then(|s| serde_json::from_str::<Vec<String>>(&s).ok());
+    Ok(match categories {
+        Some(categories) if !categories.is_empty() => categories,
+        _ => row.get::<_, Option<String>>(5)?.into_iter().collect(),
+    })
+}
+
+/// 序列化仓库排除列表，空列表存为 NULL
+fn excluded_keys_to_json(keys: &[String]) -> Option<String> {
+    if keys.is_empty() {
+        None
+    } else {
+        serde_json::to_string(keys).ok()
+    }
+}
+
 impl Database {
     // ========== Commands CRUD ==========
 
     /// 获取所有已安装的 Commands
     pub fn get_all_installed_commands(&self) -> Result<IndexMap<String, InstalledCommand>, AppError> {
+        self.get_installed_commands_page(0, None)
+            .map(|(commands, _)| commands)
+    }
+
+    /// 分页获取已安装的 Commands（排序与 `get_all_installed_commands` 一致），`limit` 为空时不限数量
+    ///
+    /// 同时返回本页扫描的原始行数（含解析时被跳过的行），调用方据此推进 `offset`
+    pub fn get_installed_commands_page(
+        &self,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> Result<(IndexMap<String, InstalledCommand>, usize), AppError> {
         let conn = lock_conn!(self.conn);
         let mut stmt = conn
             .prepare(
@@ -36,15 +88,17 @@ impl Database {
                        allowed_tools, mcp_servers, personas, extra_metadata,
                        repo_owner, repo_name, repo_branch, readme_url, source_path,
                        enabled_claude, enabled_codex, enabled_gemini,
-                       file_hash, installed_at, scope, project_path
+                       file_hash, installed_at, scope, project_path,
+                       user_note, display_name_override, categories, requires_env
                 FROM commands
-                ORDER BY namespace, filename
+                ORDER BY namespace, filename, id
+                LIMIT ?1 OFFSET ?2
                 "#,
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         let rows = stmt
-            .query_map([], |row| {
+            .query_map(params![limit.map_or(-1, |l| l as i64), offset as i64], |row| {
                 Ok(InstalledCommand {
                     id: row.get(0)?,
                     name: row.get(1)?,
@@ -78,17 +132,26 @@ impl Database {
                     installed_at: row.get(19)?,
                     scope: row.get::<_, Option<String>>(20)?.unwrap_or_else(|| "global".to_string()),
                     project_path: row.get(21)?,
+                    user_note: row.get(22)?,
+                    display_name_override: row.get(23)?,
+                    categories: read_categories(row)?,
+                    requires_env: row
+                        .get::<_, Option<String>>(25)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
                 })
             })
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         let mut commands = IndexMap::new();
+        let mut scanned = 0;
         for row in rows {
             let command = row.map_err(|e| AppError::Database(e.to_string()))?;
+            scanned += 1;
             commands.insert(command.id.clone(), command);
         }
 
-        Ok(commands)
+        Ok((commands, scanned))
     }
 
     /// 获取单个 Command
@@ -101,7 +164,8 @@ impl Database {
                        allowed_tools, mcp_servers, personas, extra_metadata,
                        repo_owner, repo_name, repo_branch, readme_url, source_path,
                        enabled_claude, enabled_codex, enabled_gemini,
-                       file_hash, installed_at, scope, project_path
+                       file_hash, installed_at, scope, project_path,
+                       user_note, display_name_override, categories, requires_env
                 FROM commands
                 WHERE id = ?1
                 "#,
@@ -143,6 +207,13 @@ impl Database {
                     installed_at: row.get(19)?,
                     scope: row.get::<_, Option<String>>(20)?.unwrap_or_else(|| "global".to_string()),
                     project_path: row.get(21)?,
+                    user_note: row.get(22)?,
+                    display_name_override: row.get(23)?,
+                    categories: read_categories(row)?,
+                    requires_env: row
+                        .get::<_, Option<String>>(25)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
                 })
             })
             .optional()
@@ -161,8 +232,10 @@ impl Database {
                 allowed_tools, mcp_servers, personas, extra_metadata,
                 repo_owner, repo_name, repo_branch, readme_url, source_path,
                 enabled_claude, enabled_codex, enabled_gemini,
-                file_hash, installed_at, scope, project_path
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)
+                file_hash, installed_at, scope, project_path,
+                user_note, display_name_override, categories, requires_env, locked
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26,
+                COALESCE((SELECT locked FROM commands WHERE id = ?1), 0))
             "#,
             params![
                 command.id,
@@ -187,6 +260,10 @@ impl Database {
                 command.installed_at,
                 command.scope,
                 command.project_path,
+                command.user_note,
+                command.display_name_override,
+                to_json_string(&command.categories)?,
+                to_json_string(&command.requires_env)?,
             ],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -256,6 +333,36 @@ impl Database {
         Ok(affected > 0)
     }
 
+    /// 更新 Command 的用户备注
+    pub fn update_command_user_note(&self, id: &str, user_note: Option<&str>) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                "UPDATE commands SET user_note = ?1 WHERE id = ?2",
+                params![user_note, id],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(affected > 0)
+    }
+
+    /// 更新 Command 的自定义显示名称
+    pub fn update_command_display_name_override(
+        &self,
+        id: &str,
+        display_name: Option<&str>,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                "UPDATE commands SET display_name_override = ?1 WHERE id = ?2",
+                params![display_name, id],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(affected > 0)
+    }
+
     /// 按命名空间获取 Commands
     pub fn get_commands_by_namespace(
         &self,
@@ -269,7 +376,8 @@ impl Database {
                        allowed_tools, mcp_servers, personas, extra_metadata,
                        repo_owner, repo_name, repo_branch, readme_url, source_path,
                        enabled_claude, enabled_codex, enabled_gemini,
-                       file_hash, installed_at, scope, project_path
+                       file_hash, installed_at, scope, project_path,
+                       user_note, display_name_override, categories, requires_env
                 FROM commands
                 WHERE namespace = ?1
                 ORDER BY filename
@@ -312,6 +420,13 @@ impl Database {
                     installed_at: row.get(19)?,
                     scope: row.get::<_, Option<String>>(20)?.unwrap_or_else(|| "global".to_string()),
                     project_path: row.get(21)?,
+                    user_note: row.get(22)?,
+                    display_name_override: row.get(23)?,
+                    categories: read_categories(row)?,
+                    requires_env: row
+                        .get::<_, Option<String>>(25)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
                 })
             })
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -370,7 +485,8 @@ impl Database {
         let mut stmt = conn
             .prepare(
                 r#"
-                SELECT owner, name, branch, enabled, builtin, description_zh, description_en, description_ja, added_at
+                SELECT owner, name, branch, enabled, builtin, description_zh, description_en, description_ja, added_at, excluded_keys,
+                       has_commands, has_agents, has_hooks, user_edited_description, browse_only
                 FROM command_repos
                 ORDER BY added_at ASC, owner ASC, name ASC
                 "#,
@@ -389,6 +505,15 @@ impl Database {
                     description_en: row.get(6)?,
                     description_ja: row.get(7)?,
                     added_at: row.get(8)?,
+                    excluded_keys: row
+                        .get::<_, Option<String>>(9)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
+                    has_commands: row.get::<_, Option<i32>>(10)?.map(|v| v != 0),
+                    has_agents: row.get::<_, Option<i32>>(11)?.map(|v| v != 0),
+                    has_hooks: row.get::<_, Option<i32>>(12)?.map(|v| v != 0),
+                    user_edited_description: row.get::<_, i32>(13)? != 0,
+                    browse_only: row.get::<_, i32>(14)? != 0,
                 })
             })
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -402,23 +527,45 @@ impl Database {
     }
 
     /// 添加 Command 仓库
+    ///
+    /// GitHub 的 owner/name 不区分大小写：若已存在仅大小写不同的仓库，
+    /// 沿用已有记录的拼写并覆盖该记录，避免同一仓库被扫描两次
     pub fn add_command_repo(&self, repo: &CommandRepo) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
+        let (owner, name) = conn
+            .query_row(
+                "SELECT owner, name FROM command_repos
+                 WHERE owner = ?1 COLLATE NOCASE AND name = ?2 COLLATE NOCASE
+                 ORDER BY builtin DESC, added_at ASC LIMIT 1",
+                params![repo.owner, repo.name],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )
+            .optional()
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .unwrap_or_else(|| (repo.owner.clone(), repo.name.clone()));
+
         conn.execute(
             r#"
-            INSERT OR REPLACE INTO command_repos (owner, name, branch, enabled, builtin, description_zh, description_en, description_ja, added_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            INSERT OR REPLACE INTO command_repos (owner, name, branch, enabled, builtin, description_zh, description_en, description_ja, added_at, excluded_keys,
+                                                  has_commands, has_agents, has_hooks, user_edited_description, browse_only)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
             "#,
             params![
-                repo.owner,
-                repo.name,
+                owner,
+                name,
                 repo.branch,
                 repo.enabled as i32,
                 repo.builtin as i32,
                 repo.description_zh,
                 repo.description_en,
                 repo.description_ja,
-                repo.added_at
+                repo.added_at,
+                excluded_keys_to_json(&repo.excluded_keys),
+                repo.has_commands.map(|v| v as i32),
+                repo.has_agents.map(|v| v as i32),
+                repo.has_hooks.map(|v| v as i32),
+                repo.user_edited_description as i32,
+                repo.browse_only as i32
             ],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -426,6 +573,46 @@ impl Database {
         Ok(())
     }
 
+    /// 合并仅 owner/name 大小写不同的重复仓库记录
+    ///
+    /// 每组保留一条（内置仓库优先，其次最早添加的），其余删除。返回删除的记录数
+    pub fn dedupe_command_repos(&self) -> Result<usize, AppError> {
+        let repos = self.get_all_command_repos()?;
+
+        let mut groups: IndexMap<(String, String), Vec<CommandRepo>> = IndexMap::new();
+        for repo in repos {
+            groups
+                .entry((repo.owner.to_lowercase(), repo.name.to_lowercase()))
+                .or_default()
+                .push(repo);
+        }
+
+        let conn = lock_conn!(self.conn);
+        let mut removed = 0;
+        for (_, mut group) in groups {
+            if group.len() < 2 {
+                continue;
+            }
+            group.sort_by_key(|r| (!r.builtin, r.added_at));
+            for dup in &group[1..] {
+                removed += conn
+                    .execute(
+                        "DELETE FROM command_repos WHERE owner = ?1 AND name = ?2",
+                        params![dup.owner, dup.name],
+                    )
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+            }
+            log::info!(
+                "合并重复的 Command 仓库 {}/{}：删除 {} 条大小写变体",
+                group[0].owner,
+                group[0].name,
+                group.len() - 1
+            );
+        }
+
+        Ok(removed)
+    }
+
     /// 删除 Command 仓库（不允许删除内置仓库）
     pub fn remove_command_repo(&self, owner: &str, name: &str) -> Result<bool, AppError> {
         let conn = lock_conn!(self.conn);
@@ -471,21 +658,169 @@ impl Database {
         Ok(affected > 0)
     }
 
+    /// 将 key 加入或移出仓库的排除列表
+    ///
+    /// 返回排除列表是否发生变化；仓库不存在时返回错误
+    pub fn set_command_repo_key_excluded(
+        &self,
+        owner: &str,
+        name: &str,
+        key: &str,
+        excluded: bool,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+
+        let current: Option<String> = conn
+            .query_row(
+                "SELECT excluded_keys FROM command_repos WHERE owner = ?1 AND name = ?2",
+                params![owner, name],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .ok_or_else(|| AppError::InvalidInput(format!("仓库 {owner}/{name} 不存在")))?;
+
+        let mut keys: Vec<String> = current
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        let changed = if excluded {
+            if keys.iter().any(|k| k == key) {
+                false
+            } else {
+                keys.push(key.to_string());
+                true
+            }
+        } else {
+            let before = keys.len();
+            keys.retain(|k| k != key);
+            keys.len() != before
+        };
+
+        if changed {
+            conn.execute(
+                "UPDATE command_repos SET excluded_keys = ?1 WHERE owner = ?2 AND name = ?3",
+                params![excluded_keys_to_json(&keys), owner, name],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        Ok(changed)
+    }
+
+    /// 记录仓库是否提供某类资源（发现扫描后调用）
+    ///
+    /// 三类资源共用 `command_repos`，标记为 false 的仓库在非强制刷新时跳过该类扫描
+    pub fn set_command_repo_capability(
+        &self,
+        owner: &str,
+        name: &str,
+        resource_type: ResourceType,
+        available: bool,
+    ) -> Result<bool, AppError> {
+        let column = match resource_type {
+            ResourceType::Command => "has_commands",
+            ResourceType::Agent => "has_agents",
+            ResourceType::Hook => "has_hooks",
+            ResourceType::Skill => {
+                return Err(AppError::InvalidInput(format!(
+                    "{resource_type} 不使用 command_repos"
+                )))
+            }
+        };
+
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                &format!("UPDATE command_repos SET {column} = ?1 WHERE owner = ?2 AND name = ?3"),
+                params![available as i32, owner, name],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(affected > 0)
+    }
+
+    /// 修改仓库描述，并标记为用户编辑（内置仓库同步时不再覆盖）
+    pub fn update_command_repo_descriptions(
+        &self,
+        owner: &str,
+        name: &str,
+        description_zh: Option<&str>,
+        description_en: Option<&str>,
+        description_ja: Option<&str>,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                "UPDATE command_repos SET description_zh = ?1, description_en = ?2, description_ja = ?3,
+                        user_edited_description = 1
+                 WHERE owner = ?4 AND name = ?5",
+                params![description_zh, description_en, description_ja, owner, name],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(affected > 0)
+    }
+
+    /// 设置或清除仓库的描述编辑标记
+    ///
+    /// 清除后下次内置仓库同步会恢复内置描述
+    pub fn set_command_repo_user_edited_description(
+        &self,
+        owner: &str,
+        name: &str,
+        edited: bool,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                "UPDATE command_repos SET user_edited_description = ?1 WHERE owner = ?2 AND name = ?3",
+                params![edited as i32, owner, name],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(affected > 0)
+    }
+
+    /// 设置仓库的仅浏览标记
+    pub fn set_command_repo_browse_only(
+        &self,
+        owner: &str,
+        name: &str,
+        browse_only: bool,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                "UPDATE command_repos SET browse_only = ?1 WHERE owner = ?2 AND name = ?3",
+                params![browse_only as i32, owner, name],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(affected > 0)
+    }
+
     /// 同步内置 Command 仓库
     ///
     /// - 添加缺失的内置仓库
-    /// - 更新已存在内置仓库的描述（但保留用户的 enabled 和 branch 设置）
+    /// - 更新已存在内置仓库的描述（但保留用户的 enabled 和 branch 设置，
+    ///   以及标记为用户编辑过的描述）
     /// - 不删除用户自己添加的仓库
     pub fn sync_builtin_command_repos(&self) -> Result<(usize, usize), AppError> {
         use crate::services::builtin_repos::get_builtin_command_repos;
 
-        let builtin_repos = get_builtin_command_repos()?;
+        self.sync_builtin_command_repos_from(&get_builtin_command_repos()?)
+    }
+
+    fn sync_builtin_command_repos_from(
+        &self,
+        builtin_repos: &[BuiltinRepoConfig],
+    ) -> Result<(usize, usize), AppError> {
         let existing = self.get_all_command_repos()?;
 
-        // 构建现有仓库的 map
+        // 构建现有仓库的 map（owner/name 不区分大小写）
         let existing_map: std::collections::HashMap<(String, String), CommandRepo> = existing
             .into_iter()
-            .map(|r| ((r.owner.clone(), r.name.clone()), r))
+            .map(|r| ((r.owner.to_lowercase(), r.name.to_lowercase()), r))
             .collect();
 
         let mut added = 0;
@@ -494,9 +829,19 @@ impl Database {
         let conn = lock_conn!(self.conn);
 
         for builtin in builtin_repos {
-            let key = (builtin.owner.clone(), builtin.name.clone());
+            let key = (builtin.owner.to_lowercase(), builtin.name.to_lowercase());
 
             if let Some(existing_repo) = existing_map.get(&key) {
+                if existing_repo.user_edited_description {
+                    // 用户改过描述，只更新内置标记
+                    conn.execute(
+                        "UPDATE command_repos SET builtin = 1 WHERE owner = ?1 AND name = ?2",
+                        params![existing_repo.owner, existing_repo.name],
+                    )
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                    continue;
+                }
+
                 // 仓库已存在，更新描述但保留用户设置
                 conn.execute(
                     "UPDATE command_repos SET builtin = 1, description_zh = ?1, description_en = ?2, description_ja = ?3
@@ -505,8 +850,8 @@ impl Database {
                         builtin.description.zh,
                         builtin.description.en,
                         builtin.description.ja,
-                        builtin.owner,
-                        builtin.name,
+                        existing_repo.owner,
+                        existing_repo.name,
                     ],
                 )
                 .map_err(|e| AppError::Database(e.to_string()))?;
@@ -704,6 +1049,48 @@ impl Database {
         Ok(affected)
     }
 
+    /// 删除指定仓库在 Commands/Agents/Hooks 三张发现缓存表中的所有行
+    ///
+    /// 三类资源共用 `command_repos`，仓库删除后三张表的缓存都成为孤儿
+    pub fn delete_all_repo_caches(
+        &self,
+        owner: &str,
+        name: &str,
+    ) -> Result<DiscoveryCachePruneResult, AppError> {
+        Ok(DiscoveryCachePruneResult {
+            commands: self.delete_repo_cache(owner, name)?,
+            agents: self.delete_agent_repo_cache(owner, name)?,
+            hooks: self.delete_hook_repo_cache(owner, name)?,
+        })
+    }
+
+    /// 删除不在 `command_repos` 中的仓库的发现缓存
+    pub fn prune_orphaned_discovery_caches(&self) -> Result<DiscoveryCachePruneResult, AppError> {
+        let conn = lock_conn!(self.conn);
+        let prune = |table: &str| -> Result<usize, AppError> {
+            conn.execute(
+                &format!(
+                    "DELETE FROM {table} WHERE NOT EXISTS (
+                         SELECT 1 FROM command_repos r
+                         WHERE r.owner = {table}.repo_owner AND r.name = {table}.repo_name
+                     )"
+                ),
+                [],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))
+        };
+
+        let result = DiscoveryCachePruneResult {
+            commands: prune("command_discovery_cache")?,
+            agents: prune("agent_discovery_cache")?,
+            hooks: prune("hook_discovery_cache")?,
+        };
+        if result.total() > 0 {
+            log::info!("Pruned {} orphaned discovery cache rows", result.total());
+        }
+        Ok(result)
+    }
+
     /// 清理过期的缓存条目
     pub fn cleanup_expired_cache(&self) -> Result<usize, AppError> {
         let conn = lock_conn!(self.conn);
@@ -728,33 +1115,7 @@ impl Database {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    fn create_test_command(id: &str, namespace: &str, filename: &str) -> InstalledCommand {
-        InstalledCommand {
-            id: id.to_string(),
-            name: format!("Test Command {}", filename),
-            description: Some("A test command".to_string()),
-            namespace: namespace.to_string(),
-            filename: filename.to_string(),
-            category: Some("test".to_string()),
-            allowed_tools: Some(vec!["Bash".to_string(), "Read".to_string()]),
-            mcp_servers: None,
-            personas: None,
-            extra_metadata: None,
-            repo_owner: Some("test-owner".to_string()),
-            repo_name: Some("test-repo".to_string()),
-            repo_branch: Some("main".to_string()),
-            readme_url: None,
-            source_path: Some(format!("commands/{}/{}.md", namespace, filename)),
-            apps: CommandApps {
-                claude: true,
-                codex: false,
-                gemini: false,
-            },
-            file_hash: Some("abc123".to_string()),
-            installed_at: 1700000000,
-        }
-    }
+    use crate::test_support::create_test_command;
 
     #[test]
     fn test_command_crud() {
@@ -794,6 +1155,28 @@ mod tests {
         assert!(after_delete.is_none());
     }
 
+    #[test]
+    fn test_command_user_annotations() {
+        let db = Database::memory().unwrap();
+        db.save_command(&create_test_command("sc/agent", "sc", "agent"))
+            .unwrap();
+
+        db.update_command_user_note("sc/agent", Some("用于日常提交"))
+            .unwrap();
+        db.update_command_display_name_override("sc/agent", Some("My Agent"))
+            .unwrap();
+
+        let updated = db.get_installed_command("sc/agent").unwrap().unwrap();
+        assert_eq!(updated.user_note.as_deref(), Some("用于日常提交"));
+        assert_eq!(updated.display_name_override.as_deref(), Some("My Agent"));
+        assert_eq!(updated.name, "Test Command agent");
+
+        db.update_command_display_name_override("sc/agent", None)
+            .unwrap();
+        let cleared = db.get_installed_command("sc/agent").unwrap().unwrap();
+        assert!(cleared.display_name_override.is_none());
+    }
+
     #[test]
     fn test_commands_by_namespace() {
         let db = Database::memory().unwrap();
@@ -840,6 +1223,12 @@ mod tests {
             description_en: None,
             description_ja: None,
             added_at: 1234567890,
+            excluded_keys: Vec::new(),
+            has_commands: None,
+            has_agents: None,
+            has_hooks: None,
+            user_edited_description: false,
+            browse_only: false,
         };
 
         // Test add
@@ -865,6 +1254,137 @@ mod tests {
         assert!(repos.is_empty());
     }
 
+    fn sample_command_repo() -> CommandRepo {
+        CommandRepo {
+            owner: "anthropics".to_string(),
+            name: "claude-commands".to_string(),
+            branch: "main".to_string(),
+            enabled: true,
+            builtin: false,
+            description_zh: None,
+            description_en: None,
+            description_ja: None,
+            added_at: 1234567890,
+            excluded_keys: Vec::new(),
+            has_commands: None,
+            has_agents: None,
+            has_hooks: None,
+            user_edited_description: false,
+            browse_only: false,
+        }
+    }
+
+    #[test]
+    fn test_command_repo_excluded_keys() {
+        let db = Database::memory().unwrap();
+        db.add_command_repo(&sample_command_repo()).unwrap();
+
+        assert!(db
+            .set_command_repo_key_excluded("anthropics", "claude-commands", "sc/agent", true)
+            .unwrap());
+        assert!(!db
+            .set_command_repo_key_excluded("anthropics", "claude-commands", "sc/agent", true)
+            .unwrap());
+        let repos = db.get_all_command_repos().unwrap();
+        assert_eq!(repos[0].excluded_keys, vec!["sc/agent".to_string()]);
+        assert!(db
+            .set_command_repo_key_excluded("anthropics", "claude-commands", "sc/agent", false)
+            .unwrap());
+        let repos = db.get_all_command_repos().unwrap();
+        assert!(repos[0].excluded_keys.is_empty());
+        assert!(db
+            .set_command_repo_key_excluded("nobody", "missing", "x", true)
+            .is_err());
+    }
+
+    #[test]
+    fn test_command_repo_capability_flags() {
+        let db = Database::memory().unwrap();
+        db.add_command_repo(&sample_command_repo()).unwrap();
+
+        let (owner, name) = ("anthropics", "claude-commands");
+        let repos = db.get_all_command_repos().unwrap();
+        assert_eq!(repos[0].has_agents, None);
+        assert!(db
+            .set_command_repo_capability(owner, name, ResourceType::Agent, false)
+            .unwrap());
+        db.set_command_repo_capability(owner, name, ResourceType::Command, true)
+            .unwrap();
+        let repos = db.get_all_command_repos().unwrap();
+        assert_eq!(repos[0].has_commands, Some(true));
+        assert_eq!(repos[0].has_agents, Some(false));
+        assert_eq!(repos[0].has_hooks, None);
+        assert!(db
+            .set_command_repo_capability(owner, name, ResourceType::Skill, true)
+            .is_err());
+    }
+
+    #[test]
+    fn test_command_repo_browse_only_flag() {
+        let db = Database::memory().unwrap();
+        db.add_command_repo(&sample_command_repo()).unwrap();
+
+        assert!(db
+            .set_command_repo_browse_only("anthropics", "claude-commands", true)
+            .unwrap());
+        let repos = db.get_all_command_repos().unwrap();
+        assert!(repos[0].browse_only);
+        assert!(db
+            .set_command_repo_browse_only("anthropics", "claude-commands", false)
+            .unwrap());
+        let repos = db.get_all_command_repos().unwrap();
+        assert!(!repos[0].browse_only);
+    }
+
+    #[test]
+    fn test_command_repo_owner_name_case_insensitive() {
+        let db = Database::memory().unwrap();
+        let repo = |owner: &str, name: &str, added_at: i64| CommandRepo {
+            owner: owner.to_string(),
+            name: name.to_string(),
+            branch: "main".to_string(),
+            enabled: true,
+            builtin: false,
+            description_zh: None,
+            description_en: None,
+            description_ja: None,
+            added_at,
+            excluded_keys: Vec::new(),
+            has_commands: None,
+            has_agents: None,
+            has_hooks: None,
+            user_edited_description: false,
+            browse_only: false,
+        };
+
+        db.add_command_repo(&repo("Anthropics", "Claude-Commands", 1))
+            .unwrap();
+        db.add_command_repo(&repo("anthropics", "claude-commands", 2))
+            .unwrap();
+        let repos = db.get_all_command_repos().unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].owner, "Anthropics");
+        assert_eq!(repos[0].name, "Claude-Commands");
+        assert_eq!(repos[0].added_at, 2);
+
+        // 历史遗留的重复记录由 dedupe 合并，保留最早添加的一条
+        {
+            let conn = db.conn.lock().unwrap();
+            conn.execute(
+                "INSERT INTO command_repos (owner, name, branch, enabled, builtin, added_at)
+                 VALUES ('ANTHROPICS', 'claude-commands', 'main', 1, 0, 5)",
+                [],
+            )
+            .unwrap();
+        }
+        assert_eq!(db.get_all_command_repos().unwrap().len(), 2);
+        assert_eq!(db.dedupe_command_repos().unwrap(), 1);
+        let repos = db.get_all_command_repos().unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].owner, "Anthropics");
+        assert_eq!(db.dedupe_command_repos().unwrap(), 0);
+    }
+
     #[test]
     fn test_builtin_command_repo_cannot_be_deleted() {
         let db = Database::memory().unwrap();
@@ -879,6 +1399,12 @@ mod tests {
             description_en: Some("Official repo".to_string()),
             description_ja: Some("公式リポジトリ".to_string()),
             added_at: 0,
+            excluded_keys: Vec::new(),
+            has_commands: None,
+            has_agents: None,
+            has_hooks: None,
+            user_edited_description: false,
+            browse_only: false,
         };
 
         db.add_command_repo(&builtin_repo).unwrap();
@@ -892,4 +1418,94 @@ mod tests {
         assert_eq!(repos.len(), 1);
         assert!(repos[0].builtin);
     }
+
+    #[test]
+    fn test_builtin_sync_preserves_user_edited_description() {
+        use crate::services::builtin_repos::LocalizedDescription;
+
+        let db = Database::memory().unwrap();
+        let builtin = vec![BuiltinRepoConfig {
+            owner: "anthropic-ai".to_string(),
+            name: "claude-code".to_string(),
+            branch: "main".to_string(),
+            description: LocalizedDescription {
+                zh: "官方仓库".to_string(),
+                en: "Official repo".to_string(),
+                ja: "公式リポジトリ".to_string(),
+            },
+        }];
+        let sync = || db.sync_builtin_command_repos_from(&builtin).unwrap();
+        assert_eq!(sync(), (1, 0));
+
+        assert!(db
+            .update_command_repo_descriptions(
+                "anthropic-ai",
+                "claude-code",
+                Some("我的翻译"),
+                Some("Official repo"),
+                None,
+            )
+            .unwrap());
+
+        // 再次同步不覆盖用户的描述
+        assert_eq!(sync(), (0, 0));
+        let repos = db.get_all_command_repos().unwrap();
+        assert!(repos[0].user_edited_description);
+        assert_eq!(repos[0].description_zh.as_deref(), Some("我的翻译"));
+        assert_eq!(repos[0].description_ja, None);
+
+        // 清除标记后恢复内置描述
+        db.set_command_repo_user_edited_description("anthropic-ai", "claude-code", false)
+            .unwrap();
+        assert_eq!(sync(), (0, 1));
+        let repos = db.get_all_command_repos().unwrap();
+        assert!(!repos[0].user_edited_description);
+        assert_eq!(repos[0].description_zh.as_deref(), Some("官方仓库"));
+        assert_eq!(repos[0].description_ja.as_deref(), Some("公式リポジトリ"));
+    }
+    #[test]
+    fn test_prune_orphaned_discovery_caches() {
+        let db = Database::memory().unwrap();
+        for (owner, name) in [("kept", "repo"), ("gone", "repo")] {
+            db.add_command_repo(&CommandRepo {
+                owner: owner.to_string(),
+                name: name.to_string(),
+                branch: "main".to_string(),
+                enabled: true,
+                builtin: false,
+                description_zh: None,
+                description_en: None,
+                description_ja: None,
+                added_at: 1234567890,
+                excluded_keys: Vec::new(),
+                has_commands: None,
+                has_agents: None,
+                has_hooks: None,
+                user_edited_description: false,
+                browse_only: false,
+            })
+            .unwrap();
+            db.save_cached_commands(owner, name, "main", &[]).unwrap();
+            db.save_cached_agents(owner, name, "main", &[]).unwrap();
+            db.save_cached_hooks(owner, name, "main", &[]).unwrap();
+        }
+
+        // 仓库仍存在时不清理
+        assert_eq!(db.prune_orphaned_discovery_caches().unwrap().total(), 0);
+
+        db.remove_command_repo("gone", "repo").unwrap();
+        let pruned = db.prune_orphaned_discovery_caches().unwrap();
+        assert_eq!(
+            pruned,
+            DiscoveryCachePruneResult {
+                commands: 1,
+                agents: 1,
+                hooks: 1,
+            }
+        );
+        let kept = db.get_cached_commands("kept", "repo", "main").unwrap();
+        let gone = db.get_cached_commands("gone", "repo", "main").unwrap();
+        assert!(kept.is_some());
+        assert!(gone.is_none());
+    }
 }
@@ -23,11 +23,37 @@ pub struct HookDiscoveryCache {
 /// Hook 缓存过期时间（秒）- 与 Commands/Agents 共用同一常量
 pub use super::commands::CACHE_EXPIRY_SECONDS;
 
+/// 解析 event_type 列
+///
+/// 无法识别的事件类型记录警告并返回 None，不再回退为 PreToolUse，
+/// 避免该 Hook 被同步到错误的事件下
+fn parse_event_type(hook_id: &str, raw: &str) -> Option<HookEventType> {
+    match serde_json::from_value(serde_json::Value::String(raw.to_string())) {
+        Ok(event_type) => Some(event_type),
+        Err(_) => {
+            log::warn!("Hook {hook_id} 的事件类型 {raw} 无法识别，已跳过");
+            None
+        }
+    }
+}
+
 impl Database {
     // ========== Hooks CRUD ==========
 
     /// 获取所有已安装的 Hooks
     pub fn get_all_installed_hooks(&self) -> Result<IndexMap<String, InstalledHook>, AppError> {
+        self.get_installed_hooks_page(0, None)
+            .map(|(hooks, _)| hooks)
+    }
+
+    /// 分页获取已安装的 Hooks（排序与 `get_all_installed_hooks` 一致），`limit` 为空时不限数量
+    ///
+    /// 同时返回本页扫描的原始行数（含解析时被跳过的行），调用方据此推进 `offset`
+    pub fn get_installed_hooks_page(
+        &self,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> Result<(IndexMap<String, InstalledHook>, usize), AppError> {
         let conn = lock_conn!(self.conn);
         let mut stmt = conn
             .prepare(
@@ -37,26 +63,31 @@ impl Database {
                        enabled, priority,
                        repo_owner, repo_name, repo_branch, readme_url, source_path,
                        enabled_claude, enabled_codex, enabled_gemini,
-                       file_hash, installed_at, scope, project_path
+                       file_hash, installed_at, scope, project_path,
+                       user_note, display_name_override, requires_env
                 FROM hooks
-                ORDER BY priority, namespace, filename
+                ORDER BY priority, namespace, filename, id
+                LIMIT ?1 OFFSET ?2
                 "#,
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         let rows = stmt
-            .query_map([], |row| {
+            .query_map(params![limit.map_or(-1, |l| l as i64), offset as i64], |row| {
+                let id: String = row.get(0)?;
                 let event_type_str: String = row.get(5)?;
                 let rules_json: String = row.get(6)?;
+                let Some(event_type) = parse_event_type(&id, &event_type_str) else {
+                    return Ok(None);
+                };
 
-                Ok(InstalledHook {
-                    id: row.get(0)?,
+                Ok(Some(InstalledHook {
+                    id,
                     name: row.get(1)?,
                     description: row.get(2)?,
                     namespace: row.get(3)?,
                     filename: row.get(4)?,
-                    event_type: serde_json::from_str(&format!("\"{}\"", event_type_str))
-                        .unwrap_or(HookEventType::PreToolUse),
+                    event_type,
                     rules: serde_json::from_str(&rules_json).unwrap_or_default(),
                     enabled: row.get::<_, i32>(7)? != 0,
                     priority: row.get(8)?,
@@ -74,17 +105,26 @@ impl Database {
                     installed_at: row.get(18)?,
                     scope: row.get::<_, Option<String>>(19)?.unwrap_or_else(|| "global".to_string()),
                     project_path: row.get(20)?,
-                })
+                    user_note: row.get(21)?,
+                    display_name_override: row.get(22)?,
+                    requires_env: row
+                        .get::<_, Option<String>>(23)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
+                }))
             })
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         let mut hooks = IndexMap::new();
+        let mut scanned = 0;
         for row in rows {
-            let hook = row.map_err(|e| AppError::Database(e.to_string()))?;
-            hooks.insert(hook.id.clone(), hook);
+            scanned += 1;
+            if let Some(hook) = row.map_err(|e| AppError::Database(e.to_string()))? {
+                hooks.insert(hook.id.clone(), hook);
+            }
         }
 
-        Ok(hooks)
+        Ok((hooks, scanned))
     }
 
     /// 获取单个 Hook
@@ -98,7 +138,8 @@ impl Database {
                        enabled, priority,
                        repo_owner, repo_name, repo_branch, readme_url, source_path,
                        enabled_claude, enabled_codex, enabled_gemini,
-                       file_hash, installed_at, scope, project_path
+                       file_hash, installed_at, scope, project_path,
+                       user_note, display_name_override, requires_env
                 FROM hooks
                 WHERE id = ?1
                 "#,
@@ -107,17 +148,20 @@ impl Database {
 
         let result = stmt
             .query_row(params![id], |row| {
+                let id: String = row.get(0)?;
                 let event_type_str: String = row.get(5)?;
                 let rules_json: String = row.get(6)?;
+                let Some(event_type) = parse_event_type(&id, &event_type_str) else {
+                    return Ok(None);
+                };
 
-                Ok(InstalledHook {
-                    id: row.get(0)?,
+                Ok(Some(InstalledHook {
+                    id,
                     name: row.get(1)?,
                     description: row.get(2)?,
                     namespace: row.get(3)?,
                     filename: row.get(4)?,
-                    event_type: serde_json::from_str(&format!("\"{}\"", event_type_str))
-                        .unwrap_or(HookEventType::PreToolUse),
+                    event_type,
                     rules: serde_json::from_str(&rules_json).unwrap_or_default(),
                     enabled: row.get::<_, i32>(7)? != 0,
                     priority: row.get(8)?,
@@ -135,12 +179,46 @@ impl Database {
                     installed_at: row.get(18)?,
                     scope: row.get::<_, Option<String>>(19)?.unwrap_or_else(|| "global".to_string()),
                     project_path: row.get(20)?,
-                })
+                    user_note: row.get(21)?,
+                    display_name_override: row.get(22)?,
+                    requires_env: row
+                        .get::<_, Option<String>>(23)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
+                }))
             })
             .optional()
             .map_err(|e| AppError::Database(e.to_string()))?;
 
-        Ok(result)
+        Ok(result.flatten())
+    }
+
+    /// 列出事件类型无法识别的 Hook（id, 原始 event_type）
+    ///
+    /// 这些记录不会出现在 [`Self::get_all_installed_hooks`] 中，供健康检查提示用户
+    pub fn get_hooks_with_unknown_event_type(&self) -> Result<Vec<(String, String)>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare("SELECT id, event_type FROM hooks ORDER BY id")
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut unknown = Vec::new();
+        for row in rows {
+            let (id, raw) = row.map_err(|e| AppError::Database(e.to_string()))?;
+            if serde_json::from_value::<HookEventType>(serde_json::Value::String(raw.clone()))
+                .is_err()
+            {
+                unknown.push((id, raw));
+            }
+        }
+
+        Ok(unknown)
     }
 
     /// 保存 Hook（插入或更新）
@@ -156,8 +234,10 @@ impl Database {
                 enabled, priority,
                 repo_owner, repo_name, repo_branch, readme_url, source_path,
                 enabled_claude, enabled_codex, enabled_gemini,
-                file_hash, installed_at, scope, project_path
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)
+                file_hash, installed_at, scope, project_path,
+                user_note, display_name_override, requires_env, locked
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24,
+                COALESCE((SELECT locked FROM hooks WHERE id = ?1), 0))
             "#,
             params![
                 hook.id,
@@ -181,6 +261,9 @@ impl Database {
                 hook.installed_at,
                 hook.scope,
                 hook.project_path,
+                hook.user_note,
+                hook.display_name_override,
+                to_json_string(&hook.requires_env)?,
             ],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -295,6 +378,36 @@ impl Database {
         Ok(affected > 0)
     }
 
+    /// 更新 Hook 的用户备注
+    pub fn update_hook_user_note(&self, id: &str, user_note: Option<&str>) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                "UPDATE hooks SET user_note = ?1 WHERE id = ?2",
+                params![user_note, id],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(affected > 0)
+    }
+
+    /// 更新 Hook 的自定义显示名称
+    pub fn update_hook_display_name_override(
+        &self,
+        id: &str,
+        display_name: Option<&str>,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                "UPDATE hooks SET display_name_override = ?1 WHERE id = ?2",
+                params![display_name, id],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(affected > 0)
+    }
+
     /// 按命名空间获取 Hooks
     pub fn get_hooks_by_namespace(&self, namespace: &str) -> Result<Vec<InstalledHook>, AppError> {
         let conn = lock_conn!(self.conn);
@@ -306,7 +419,8 @@ impl Database {
                        enabled, priority,
                        repo_owner, repo_name, repo_branch, readme_url, source_path,
                        enabled_claude, enabled_codex, enabled_gemini,
-                       file_hash, installed_at, scope, project_path
+                       file_hash, installed_at, scope, project_path,
+                       user_note, display_name_override, requires_env
                 FROM hooks
                 WHERE namespace = ?1
                 ORDER BY priority, filename
@@ -316,17 +430,20 @@ impl Database {
 
         let rows = stmt
             .query_map(params![namespace], |row| {
+                let id: String = row.get(0)?;
                 let event_type_str: String = row.get(5)?;
                 let rules_json: String = row.get(6)?;
+                let Some(event_type) = parse_event_type(&id, &event_type_str) else {
+                    return Ok(None);
+                };
 
-                Ok(InstalledHook {
-                    id: row.get(0)?,
+                Ok(Some(InstalledHook {
+                    id,
                     name: row.get(1)?,
                     description: row.get(2)?,
                     namespace: row.get(3)?,
                     filename: row.get(4)?,
-                    event_type: serde_json::from_str(&format!("\"{}\"", event_type_str))
-                        .unwrap_or(HookEventType::PreToolUse),
+                    event_type,
                     rules: serde_json::from_str(&rules_json).unwrap_or_default(),
                     enabled: row.get::<_, i32>(7)? != 0,
                     priority: row.get(8)?,
@@ -344,13 +461,19 @@ impl Database {
                     installed_at: row.get(18)?,
                     scope: row.get::<_, Option<String>>(19)?.unwrap_or_else(|| "global".to_string()),
                     project_path: row.get(20)?,
-                })
+                    user_note: row.get(21)?,
+                    display_name_override: row.get(22)?,
+                    requires_env: row
+                        .get::<_, Option<String>>(23)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
+                }))
             })
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         let mut hooks = Vec::new();
         for row in rows {
-            hooks.push(row.map_err(|e| AppError::Database(e.to_string()))?);
+            hooks.extend(row.map_err(|e| AppError::Database(e.to_string()))?);
         }
 
         Ok(hooks)
@@ -379,7 +502,8 @@ impl Database {
                    enabled, priority,
                    repo_owner, repo_name, repo_branch, readme_url, source_path,
                    enabled_claude, enabled_codex, enabled_gemini,
-                   file_hash, installed_at, scope, project_path
+                   file_hash, installed_at, scope, project_path,
+                   user_note, display_name_override, requires_env
             FROM hooks
             WHERE enabled = 1 AND {} = 1 AND event_type = ?1
             ORDER BY priority
@@ -393,17 +517,20 @@ impl Database {
 
         let rows = stmt
             .query_map(params![event_type.to_string()], |row| {
+                let id: String = row.get(0)?;
                 let event_type_str: String = row.get(5)?;
                 let rules_json: String = row.get(6)?;
+                let Some(event_type) = parse_event_type(&id, &event_type_str) else {
+                    return Ok(None);
+                };
 
-                Ok(InstalledHook {
-                    id: row.get(0)?,
+                Ok(Some(InstalledHook {
+                    id,
                     name: row.get(1)?,
                     description: row.get(2)?,
                     namespace: row.get(3)?,
                     filename: row.get(4)?,
-                    event_type: serde_json::from_str(&format!("\"{}\"", event_type_str))
-                        .unwrap_or(HookEventType::PreToolUse),
+                    event_type,
                     rules: serde_json::from_str(&rules_json).unwrap_or_default(),
                     enabled: row.get::<_, i32>(7)? != 0,
                     priority: row.get(8)?,
@@ -421,13 +548,19 @@ impl Database {
                     installed_at: row.get(18)?,
                     scope: row.get::<_, Option<String>>(19)?.unwrap_or_else(|| "global".to_string()),
                     project_path: row.get(20)?,
-                })
+                    user_note: row.get(21)?,
+                    display_name_override: row.get(22)?,
+                    requires_env: row
+                        .get::<_, Option<String>>(23)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
+                }))
             })
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         let mut hooks = Vec::new();
         for row in rows {
-            hooks.push(row.map_err(|e| AppError::Database(e.to_string()))?);
+            hooks.extend(row.map_err(|e| AppError::Database(e.to_string()))?);
         }
 
         Ok(hooks)
@@ -633,38 +766,7 @@ impl Database {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::app_config::{HookRule, HookType};
-
-    fn create_test_hook(id: &str, namespace: &str, filename: &str) -> InstalledHook {
-        InstalledHook {
-            id: id.to_string(),
-            name: format!("Test Hook {}", filename),
-            description: Some("A test hook".to_string()),
-            namespace: namespace.to_string(),
-            filename: filename.to_string(),
-            event_type: HookEventType::PreToolUse,
-            rules: vec![HookRule {
-                matcher: "Bash".to_string(),
-                hooks: vec![HookType::Command {
-                    command: "/usr/bin/test-hook".to_string(),
-                }],
-            }],
-            enabled: true,
-            priority: 100,
-            repo_owner: Some("test-owner".to_string()),
-            repo_name: Some("test-repo".to_string()),
-            repo_branch: Some("main".to_string()),
-            readme_url: None,
-            source_path: Some(format!("hooks/{}.json", filename)),
-            apps: HookApps {
-                claude: true,
-                codex: false,
-                gemini: false,
-            },
-            file_hash: Some("abc123".to_string()),
-            installed_at: 1700000000,
-        }
-    }
+    use crate::test_support::create_test_hook;
 
     #[test]
     fn test_hook_crud() {
@@ -790,4 +892,70 @@ mod tests {
         assert_eq!(hook1_updated.priority, 20);
         assert_eq!(hook2_updated.priority, 30);
     }
+
+    #[test]
+    fn unknown_event_type_is_not_coerced_to_pre_tool_use() {
+        let db = Database::memory().unwrap();
+        db.save_hook(&create_test_hook("session/start", "session", "start"))
+            .unwrap();
+        db.save_hook(&create_test_hook("known", "", "known"))
+            .unwrap();
+        {
+            let conn = lock_conn!(db.conn);
+            conn.execute(
+                "UPDATE hooks SET event_type = 'SessionStart' WHERE id = 'session/start'",
+                [],
+            )
+            .unwrap();
+        }
+
+        assert!(db.get_installed_hook("session/start").unwrap().is_none());
+        let all = db.get_all_installed_hooks().unwrap();
+        assert_eq!(all.keys().collect::<Vec<_>>(), ["known"]);
+        assert!(db
+            .get_enabled_hooks_by_event(&HookEventType::PreToolUse, "claude")
+            .unwrap()
+            .iter()
+            .all(|h| h.id != "session/start"));
+
+        assert_eq!(
+            db.get_hooks_with_unknown_event_type().unwrap(),
+            vec![("session/start".to_string(), "SessionStart".to_string())]
+        );
+    }
+
+    #[test]
+    fn hooks_page_advances_by_scanned_rows_with_stable_order() {
+        let db = Database::memory().unwrap();
+        // 排序字段完全相同，只能靠 id 区分先后
+        for id in ["ns/e", "ns/b", "ns/d", "ns/a", "ns/c"] {
+            db.save_hook(&create_test_hook(id, "ns", "dup")).unwrap();
+        }
+        {
+            let conn = lock_conn!(db.conn);
+            conn.execute(
+                "UPDATE hooks SET event_type = 'Bogus' WHERE id = 'ns/b'",
+                [],
+            )
+            .unwrap();
+        }
+
+        let mut seen = Vec::new();
+        let mut scanned_per_page = Vec::new();
+        let mut offset = 0;
+        loop {
+            let (page, scanned) = db.get_installed_hooks_page(offset, Some(2)).unwrap();
+            seen.extend(page.into_keys());
+            scanned_per_page.push(scanned);
+            offset += scanned;
+            if scanned < 2 {
+                break;
+            }
+        }
+
+        assert_eq!(scanned_per_page, [2, 2, 1]);
+        assert_eq!(seen, ["ns/a", "ns/c", "ns/d", "ns/e"]);
+        let all: Vec<String> = db.get_all_installed_hooks().unwrap().into_keys().collect();
+        assert_eq!(all, seen);
+    }
 }
@@ -0,0 +1,64 @@
+//! 可恢复批量安装的进度 DAO
+
+use std::collections::HashSet;
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+
+impl Database {
+    /// 记录某个条目已完成（重复记录时忽略）
+    pub fn record_import_item(&self, token: &str, item_key: &str) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR IGNORE INTO import_progress (token, item_key, completed_at)
+             VALUES (?1, ?2, ?3)",
+            rusqlite::params![token, item_key, chrono::Utc::now().timestamp()],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 获取某个 resume token 下已完成的条目
+    pub fn get_imported_items(&self, token: &str) -> Result<HashSet<String>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare("SELECT item_key FROM import_progress WHERE token = ?1")
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map([token], |row| row.get::<_, String>(0))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.collect::<Result<HashSet<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 清除某个 resume token 的全部进度
+    pub fn clear_import_progress(&self, token: &str) -> Result<u64, AppError> {
+        let conn = lock_conn!(self.conn);
+        let deleted = conn
+            .execute("DELETE FROM import_progress WHERE token = ?1", [token])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(deleted as u64)
+    }
+
+    /// Delete import progress not touched for `max_age_days` days (abandoned resumes).
+    /// Returns the number of deleted rows.
+    pub fn cleanup_stale_import_progress(&self, max_age_days: i64) -> Result<u64, AppError> {
+        let cutoff = chrono::Utc::now().timestamp() - max_age_days * 86400;
+        let conn = lock_conn!(self.conn);
+        let deleted = conn
+            .execute(
+                "DELETE FROM import_progress WHERE token IN (
+                     SELECT token FROM import_progress
+                     GROUP BY token HAVING MAX(completed_at) < ?1
+                 )",
+                [cutoff],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        if deleted > 0 {
+            log::info!("Cleaned up {deleted} stale import_progress rows");
+        }
+        Ok(deleted as u64)
+    }
+}
@@ -72,8 +72,10 @@ impl Database {
         conn.execute(
             "INSERT OR REPLACE INTO mcp_servers (
                 id, name, server_config, description, homepage, docs, tags,
-                enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_hermes
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_hermes,
+                locked
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12,
+                COALESCE((SELECT locked FROM mcp_servers WHERE id = ?1), 0))",
             params![
                 server.id,
                 server.name,
@@ -6,18 +6,22 @@ pub mod agents;
 pub mod commands;
 pub mod failover;
 pub mod hooks;
+pub mod import_progress;
 pub mod mcp;
 pub mod prompts;
 pub mod providers;
 pub mod providers_seed;
 pub mod proxy;
+pub mod resource_counts;
+pub mod resource_locks;
 pub mod settings;
 pub mod skills;
+pub mod speedtest;
 pub mod stream_check;
 pub mod universal_providers;
 pub mod usage_rollup;
 
 // 所有 DAO 方法都通过 Database impl 提供，无需单独导出
 // 导出特定类型供外部使用
-pub use commands::CACHE_EXPIRY_SECONDS;
+pub use commands::{DiscoveryCachePruneResult, CACHE_EXPIRY_SECONDS};
 pub use failover::FailoverQueueItem;
@@ -43,8 +43,9 @@ impl Database {
                 let meta_str: String = row.get(10)?;
                 let in_failover_queue: bool = row.get(11)?;
 
-                let settings_config =
+                let mut settings_config =
                     serde_json::from_str(&settings_config_str).unwrap_or(serde_json::Value::Null);
+                crate::provider_secrets::decrypt_from_storage(&mut settings_config);
                 let meta: ProviderMeta = serde_json::from_str(&meta_str).unwrap_or_default();
 
                 Ok((
@@ -150,7 +151,8 @@ impl Database {
                 let meta_str: String = row.get(9)?;
                 let in_failover_queue: bool = row.get(10)?;
 
-                let settings_config = serde_json::from_str(&settings_config_str).unwrap_or(serde_json::Value::Null);
+                let mut settings_config = serde_json::from_str(&settings_config_str).unwrap_or(serde_json::Value::Null);
+                crate::provider_secrets::decrypt_from_storage(&mut settings_config);
                 let meta: ProviderMeta = serde_json::from_str(&meta_str).unwrap_or_default();
 
                 Ok(Provider {
@@ -186,17 +188,25 @@ impl Database {
         let mut meta_clone = provider.meta.clone().unwrap_or_default();
         let endpoints = std::mem::take(&mut meta_clone.custom_endpoints);
 
-        let existing: Option<(bool, bool)> = tx
+        let existing: Option<(bool, bool, String)> = tx
             .query_row(
-                "SELECT is_current, in_failover_queue FROM providers WHERE id = ?1 AND app_type = ?2",
+                "SELECT is_current, in_failover_queue, settings_config FROM providers WHERE id = ?1 AND app_type = ?2",
                 params![provider.id, app_type],
-                |row| Ok((row.get(0)?, row.get(1)?)),
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
             )
             .ok();
 
         let is_update = existing.is_some();
-        let (is_current, in_failover_queue) =
-            existing.unwrap_or((false, provider.in_failover_queue));
+        let (is_current, in_failover_queue, stored_settings_config) = match existing {
+            Some((is_current, in_failover_queue, stored)) => {
+                (is_current, in_failover_queue, Some(stored))
+            }
+            None => (false, provider.in_failover_queue, None),
+        };
+        let settings_config = crate::provider_secrets::serialize_for_storage(
+            &provider.settings_config,
+            stored_settings_config.as_deref(),
+        )?;
 
         if is_update {
             tx.execute(
@@ -216,9 +226,7 @@ impl Database {
                 WHERE id = ?13 AND app_type = ?14",
                 params![
                     provider.name,
-                    serde_json::to_string(&provider.settings_config).map_err(|e| {
-                        AppError::Database(format!("Failed to serialize settings_config: {e}"))
-                    })?,
+                    settings_config,
                     provider.website_url,
                     provider.category,
                     provider.created_at,
@@ -246,8 +254,7 @@ impl Database {
                     provider.id,
                     app_type,
                     provider.name,
-                    serde_json::to_string(&provider.settings_config)
-                        .map_err(|e| AppError::Database(format!("Failed to serialize settings_config: {e}")))?,
+                    settings_config,
                     provider.website_url,
                     provider.category,
                     provider.created_at,
@@ -316,12 +323,17 @@ impl Database {
         settings_config: &serde_json::Value,
     ) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
+        let stored: Option<String> = conn
+            .query_row(
+                "SELECT settings_config FROM providers WHERE id = ?1 AND app_type = ?2",
+                params![provider_id, app_type],
+                |row| row.get(0),
+            )
+            .ok();
         conn.execute(
             "UPDATE providers SET settings_config = ?1 WHERE id = ?2 AND app_type = ?3",
             params![
-                serde_json::to_string(settings_config).map_err(|e| AppError::Database(format!(
-                    "Failed to serialize settings_config: {e}"
-                )))?,
+                crate::provider_secrets::serialize_for_storage(settings_config, stored.as_deref())?,
                 provider_id,
                 app_type
             ],
@@ -330,6 +342,63 @@ impl Database {
         Ok(())
     }
 
+    /// 按加密设置重写所有供应商的 `settings_config`
+    ///
+    /// `encrypt = true` 时加密残留的明文密钥，`false` 时解密全部密文，返回被改写的行数。
+    /// 在单个事务中完成，任一行失败则整体回滚。
+    pub fn reencrypt_provider_secrets(&self, encrypt: bool) -> Result<usize, AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows: Vec<(String, String, String)> = {
+            let mut stmt = tx
+                .prepare("SELECT id, app_type, settings_config FROM providers")
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            let iter = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            iter.collect::<Result<_, _>>()
+                .map_err(|e| AppError::Database(e.to_string()))?
+        };
+
+        let mut updated = 0;
+        for (id, app_type, settings_config_str) in rows {
+            let Ok(mut settings_config) =
+                serde_json::from_str::<serde_json::Value>(&settings_config_str)
+            else {
+                log::warn!("跳过无法解析的供应商配置: {app_type}/{id}");
+                continue;
+            };
+
+            let changed = if encrypt {
+                crate::provider_secrets::encrypt_settings_config(&mut settings_config)?
+            } else {
+                crate::provider_secrets::decrypt_settings_config(&mut settings_config)?
+            };
+            if changed == 0 {
+                continue;
+            }
+
+            tx.execute(
+                "UPDATE providers SET settings_config = ?1 WHERE id = ?2 AND app_type = ?3",
+                params![
+                    serde_json::to_string(&settings_config).map_err(|e| AppError::Database(
+                        format!("Failed to serialize settings_config: {e}")
+                    ))?,
+                    id,
+                    app_type
+                ],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            updated += 1;
+        }
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(updated)
+    }
+
     pub fn add_custom_endpoint(
         &self,
         app_type: &str,
@@ -471,11 +540,12 @@ impl Database {
                 Err(e) => return Err(AppError::Database(e.to_string())),
             };
 
-        let settings_config = serde_json::from_str(&settings_config_str).map_err(|e| {
+        let mut settings_config = serde_json::from_str(&settings_config_str).map_err(|e| {
             AppError::Database(format!(
                 "Failed to parse {category} provider settings_config (provider_id={id}): {e}"
             ))
         })?;
+        crate::provider_secrets::decrypt_from_storage(&mut settings_config);
         let meta: crate::provider::ProviderMeta = if meta_str.trim().is_empty() {
             crate::provider::ProviderMeta::default()
         } else {
@@ -0,0 +1,43 @@
+//! 资源计数 DAO
+//!
+//! 按应用聚合统计已启用的资源数量，供仪表盘显示
+
+use crate::app_config::AppType;
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+
+impl Database {
+    /// 统计 `table` 中各应用 `enabled_<app>` 列为真的行数
+    ///
+    /// 单次聚合查询，不加载整行；`only_globally_enabled` 为 true 时
+    /// 只统计 `enabled = 1` 的行（hooks 的全局开关）
+    pub fn count_enabled_per_app(
+        &self,
+        table: &str,
+        apps: &[AppType],
+        only_globally_enabled: bool,
+    ) -> Result<Vec<(AppType, u32)>, AppError> {
+        let columns = apps
+            .iter()
+            .map(|app| format!("COALESCE(SUM(enabled_{} != 0), 0)", app.as_str()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let filter = if only_globally_enabled {
+            " WHERE enabled != 0"
+        } else {
+            ""
+        };
+        let sql = format!("SELECT {columns} FROM {table}{filter}");
+
+        let conn = lock_conn!(self.conn);
+        let counts = conn
+            .query_row(&sql, [], |row| {
+                (0..apps.len())
+                    .map(|i| row.get::<_, u32>(i))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(apps.iter().cloned().zip(counts).collect())
+    }
+}
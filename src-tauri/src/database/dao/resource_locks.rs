@@ -0,0 +1,42 @@
+//! 资源锁定 DAO
+//!
+//! 锁定的资源不参与批量更新、批量卸载与批量禁用。锁定状态只存在于 `locked` 列，
+//! 各资源的 save 方法在覆盖写入时会保留该列
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use rusqlite::params;
+use std::collections::HashSet;
+
+impl Database {
+    /// 设置 `table` 中资源的锁定状态，资源不存在时返回 false
+    pub fn set_resource_locked(
+        &self,
+        table: &str,
+        id: &str,
+        locked: bool,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                &format!("UPDATE {table} SET locked = ?1 WHERE id = ?2"),
+                params![locked, id],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
+
+    /// 获取 `table` 中所有已锁定资源的 ID
+    pub fn get_locked_resource_ids(&self, table: &str) -> Result<HashSet<String>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(&format!("SELECT id FROM {table} WHERE locked != 0"))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let ids = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<HashSet<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(ids)
+    }
+}
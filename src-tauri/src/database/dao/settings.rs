@@ -333,4 +333,86 @@ impl Database {
             .map_err(|e| AppError::Database(format!("序列化日志配置失败: {e}")))?;
         self.set_setting("log_config", &json)
     }
+
+    // --- 供应商轮换 ---
+
+    /// 获取应用的供应商轮换策略，未设置时返回默认值（默认关闭）
+    pub fn get_provider_rotation_policy(
+        &self,
+        app_type: &str,
+    ) -> Result<crate::services::provider::ProviderRotationPolicy, AppError> {
+        match self.get_setting(&format!("provider_rotation_policy_{app_type}"))? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析轮换策略失败: {e}"))),
+            None => Ok(Default::default()),
+        }
+    }
+
+    /// 更新应用的供应商轮换策略
+    pub fn set_provider_rotation_policy(
+        &self,
+        app_type: &str,
+        policy: &crate::services::provider::ProviderRotationPolicy,
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(policy)
+            .map_err(|e| AppError::Database(format!("序列化轮换策略失败: {e}")))?;
+        self.set_setting(&format!("provider_rotation_policy_{app_type}"), &json)
+    }
+
+    /// 获取应用的供应商轮换状态
+    pub fn get_provider_rotation_state(
+        &self,
+        app_type: &str,
+    ) -> Result<crate::services::provider::ProviderRotationState, AppError> {
+        match self.get_setting(&format!("provider_rotation_state_{app_type}"))? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析轮换状态失败: {e}"))),
+            None => Ok(Default::default()),
+        }
+    }
+
+    /// 保存应用的供应商轮换状态
+    pub fn set_provider_rotation_state(
+        &self,
+        app_type: &str,
+        rotation_state: &crate::services::provider::ProviderRotationState,
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(rotation_state)
+            .map_err(|e| AppError::Database(format!("序列化轮换状态失败: {e}")))?;
+        self.set_setting(&format!("provider_rotation_state_{app_type}"), &json)
+    }
+
+    // --- 仓库元数据（ccswitch.json）---
+
+    /// 缓存仓库级元数据；仓库不再提供 ccswitch.json 时清除旧缓存
+    pub fn save_repo_metadata(
+        &self,
+        owner: &str,
+        name: &str,
+        metadata: Option<&crate::services::repo_metadata::RepoMetadata>,
+    ) -> Result<(), AppError> {
+        let key = format!("repo_metadata:{owner}/{name}");
+        match metadata {
+            Some(metadata) => {
+                let json = serde_json::to_string(metadata)
+                    .map_err(|e| AppError::Database(format!("序列化仓库元数据失败: {e}")))?;
+                self.set_setting(&key, &json)
+            }
+            None => self.delete_setting(&key).map(|_| ()),
+        }
+    }
+
+    /// 获取缓存的仓库级元数据
+    pub fn get_repo_metadata(
+        &self,
+        owner: &str,
+        name: &str,
+    ) -> Result<Option<crate::services::repo_metadata::RepoMetadata>, AppError> {
+        match self.get_setting(&format!("repo_metadata:{owner}/{name}"))? {
+            Some(json) => serde_json::from_str(&json)
+                .map(Some)
+                .map_err(|e| AppError::Database(format!("解析仓库元数据失败: {e}"))),
+            None => Ok(None),
+        }
+    }
 }
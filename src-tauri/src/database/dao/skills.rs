@@ -18,6 +18,18 @@ impl Database {
 
     /// 获取所有已安装的 Skills
     pub fn get_all_installed_skills(&self) -> Result<IndexMap<String, InstalledSkill>, AppError> {
+        self.get_installed_skills_page(0, None)
+            .map(|(skills, _)| skills)
+    }
+
+    /// 分页获取已安装的 Skills（排序与 `get_all_installed_skills` 一致），`limit` 为空时不限数量
+    ///
+    /// 同时返回本页扫描的原始行数（含解析时被跳过的行），调用方据此推进 `offset`
+    pub fn get_installed_skills_page(
+        &self,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> Result<(IndexMap<String, InstalledSkill>, usize), AppError> {
         let conn = lock_conn!(self.conn);
         let mut stmt = conn
             .prepare(
@@ -25,12 +37,13 @@ impl Database {
                         readme_url, enabled_claude, enabled_codex, enabled_gemini, enabled_opencode,
                         enabled_hermes, file_hash, content_hash, installed_at, updated_at,
                         scope, project_path
-                 FROM skills ORDER BY namespace ASC, name ASC",
+                 FROM skills ORDER BY namespace ASC, name ASC, id ASC
+                 LIMIT ?1 OFFSET ?2",
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         let skill_iter = stmt
-            .query_map([], |row| {
+            .query_map(params![limit.map_or(-1, |l| l as i64), offset as i64], |row| {
                 Ok(InstalledSkill {
                     id: row.get(0)?,
                     name: row.get(1)?,
@@ -59,11 +72,13 @@ impl Database {
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         let mut skills = IndexMap::new();
+        let mut scanned = 0;
         for skill_res in skill_iter {
             let skill = skill_res.map_err(|e| AppError::Database(e.to_string()))?;
+            scanned += 1;
             skills.insert(skill.id.clone(), skill);
         }
-        Ok(skills)
+        Ok((skills, scanned))
     }
 
     /// 获取单个已安装的 Skill
@@ -120,8 +135,9 @@ impl Database {
             "INSERT OR REPLACE INTO skills
              (id, name, description, directory, namespace, repo_owner, repo_name, repo_branch,
               readme_url, enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_hermes,
-              file_hash, content_hash, installed_at, updated_at, scope, project_path)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
+              file_hash, content_hash, installed_at, updated_at, scope, project_path, locked)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20,
+                     COALESCE((SELECT locked FROM skills WHERE id = ?1), 0))",
             params![
                 skill.id,
                 skill.name,
@@ -318,7 +334,7 @@ impl Database {
         let conn = lock_conn!(self.conn);
         let mut stmt = conn
             .prepare(
-                "SELECT owner, name, branch, enabled, builtin, description_zh, description_en, description_ja, added_at
+                "SELECT owner, name, branch, enabled, builtin, description_zh, description_en, description_ja, added_at, browse_only
                  FROM skill_repos ORDER BY added_at ASC, owner ASC, name ASC",
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -335,6 +351,7 @@ impl Database {
                     description_en: row.get(6)?,
                     description_ja: row.get(7)?,
                     added_at: row.get(8)?,
+                    browse_only: row.get::<_, i32>(9)? != 0,
                 })
             })
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -350,8 +367,8 @@ impl Database {
     pub fn save_skill_repo(&self, repo: &SkillRepo) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
         conn.execute(
-            "INSERT OR REPLACE INTO skill_repos (owner, name, branch, enabled, builtin, description_zh, description_en, description_ja, added_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            "INSERT OR REPLACE INTO skill_repos (owner, name, branch, enabled, builtin, description_zh, description_en, description_ja, added_at, browse_only)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 repo.owner,
                 repo.name,
@@ -362,12 +379,31 @@ impl Database {
                 repo.description_en,
                 repo.description_ja,
                 repo.added_at,
+                repo.browse_only as i32,
             ],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
     }
 
+    /// 设置 Skill 仓库的仅浏览标记
+    pub fn set_skill_repo_browse_only(
+        &self,
+        owner: &str,
+        name: &str,
+        browse_only: bool,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                "UPDATE skill_repos SET browse_only = ?1 WHERE owner = ?2 AND name = ?3",
+                params![browse_only as i32, owner, name],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(affected > 0)
+    }
+
     /// 删除 Skill 仓库（不允许删除内置仓库）
     pub fn delete_skill_repo(&self, owner: &str, name: &str) -> Result<bool, AppError> {
         let conn = lock_conn!(self.conn);
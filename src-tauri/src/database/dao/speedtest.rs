@@ -0,0 +1,142 @@
+//! 端点测速历史 DAO
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::speedtest::{EndpointLatency, SpeedtestRecord};
+
+impl Database {
+    /// 保存一次测速的全部结果（同一批次共用 `tested_at`）
+    pub fn save_speedtest_results(
+        &self,
+        results: &[EndpointLatency],
+        tested_at: i64,
+    ) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        for result in results {
+            tx.execute(
+                "INSERT INTO speedtest_history (url, latency_ms, status, error, tested_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![
+                    result.url,
+                    result.latency.map(|ms| ms.min(i64::MAX as u128) as i64),
+                    result.status.map(|s| s as i64),
+                    result.error,
+                    tested_at,
+                ],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 查询测速历史（按时间倒序）
+    ///
+    /// `url` 为空时返回所有端点的记录
+    pub fn get_speedtest_history(
+        &self,
+        url: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<SpeedtestRecord>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT url, latency_ms, status, error, tested_at
+                 FROM speedtest_history
+                 WHERE ?1 IS NULL OR url = ?1
+                 ORDER BY tested_at DESC, id DESC
+                 LIMIT ?2",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(rusqlite::params![url, limit], |row| {
+                Ok(SpeedtestRecord {
+                    url: row.get(0)?,
+                    latency_ms: row.get(1)?,
+                    status: row.get::<_, Option<i64>>(2)?.map(|s| s as u16),
+                    error: row.get(3)?,
+                    tested_at: row.get(4)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 清空测速历史，`url` 为空时清空全部
+    pub fn clear_speedtest_history(&self, url: Option<&str>) -> Result<u64, AppError> {
+        let conn = lock_conn!(self.conn);
+        let deleted = conn
+            .execute(
+                "DELETE FROM speedtest_history WHERE ?1 IS NULL OR url = ?1",
+                [url],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(deleted as u64)
+    }
+
+    /// Delete speedtest history older than `retain_days` days.
+    /// Returns the number of deleted rows.
+    pub fn cleanup_old_speedtest_history(&self, retain_days: i64) -> Result<u64, AppError> {
+        let cutoff = chrono::Utc::now().timestamp() - retain_days * 86400;
+        let conn = lock_conn!(self.conn);
+        let deleted = conn
+            .execute(
+                "DELETE FROM speedtest_history WHERE tested_at < ?1",
+                [cutoff],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        if deleted > 0 {
+            log::info!("Cleaned up {deleted} speedtest_history rows older than {retain_days} days");
+        }
+        Ok(deleted as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn latency(url: &str, ms: Option<u128>) -> EndpointLatency {
+        EndpointLatency {
+            url: url.to_string(),
+            latency: ms,
+            status: ms.map(|_| 200),
+            error: ms.is_none().then(|| "请求超时".to_string()),
+        }
+    }
+
+    #[test]
+    fn speedtest_history_roundtrip_and_cleanup() {
+        let db = Database::memory().unwrap();
+        let now = chrono::Utc::now().timestamp();
+
+        db.save_speedtest_results(&[latency("https://a", Some(120))], now - 40 * 86400)
+            .unwrap();
+        db.save_speedtest_results(
+            &[latency("https://a", Some(80)), latency("https://b", None)],
+            now,
+        )
+        .unwrap();
+
+        let history = db.get_speedtest_history(Some("https://a"), 10).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].latency_ms, Some(80));
+        assert_eq!(history[1].latency_ms, Some(120));
+
+        let all = db.get_speedtest_history(None, 10).unwrap();
+        assert_eq!(all.len(), 3);
+        let b = all.iter().find(|r| r.url == "https://b").unwrap();
+        assert_eq!(b.error.as_deref(), Some("请求超时"));
+
+        assert_eq!(db.cleanup_old_speedtest_history(30).unwrap(), 1);
+        assert_eq!(db.clear_speedtest_history(Some("https://b")).unwrap(), 1);
+        assert_eq!(db.get_speedtest_history(None, 10).unwrap().len(), 1);
+    }
+}
@@ -36,7 +36,8 @@ mod schema;
 mod tests;
 
 // DAO 类型导出供外部使用
-pub use dao::{FailoverQueueItem, CACHE_EXPIRY_SECONDS};
+pub use dao::{DiscoveryCachePruneResult, FailoverQueueItem, CACHE_EXPIRY_SECONDS};
+pub use schema::MissingColumn;
 
 use crate::config::get_app_config_dir;
 use crate::error::AppError;
@@ -48,7 +49,7 @@ use std::sync::Mutex;
 
 /// 当前 Schema 版本号
 /// 每次修改表结构时递增，并在 schema.rs 中添加相应的迁移逻辑
-pub(crate) const SCHEMA_VERSION: i32 = 15;
+pub(crate) const SCHEMA_VERSION: i32 = 25;
 
 /// 安全地序列化 JSON，避免 unwrap panic
 pub(crate) fn to_json_string<T: Serialize + ?Sized>(value: &T) -> Result<String, AppError> {
@@ -93,6 +94,9 @@ impl Database {
     /// 数据库文件位于 `~/.cc-switch/cc-switch.db`
     pub fn init() -> Result<Self, AppError> {
         let db_path = get_app_config_dir().join("cc-switch.db");
+        if let Err(e) = Self::apply_pending_restore(&db_path) {
+            log::error!("Failed to restore pending database snapshot: {e}");
+        }
         let db_exists = db_path.exists();
 
         // 确保父目录存在
@@ -134,6 +138,7 @@ impl Database {
         }
 
         db.apply_schema_migrations()?;
+        db.ensure_resource_scope_columns()?;
         if let Err(e) = db.ensure_incremental_auto_vacuum() {
             log::warn!("Failed to ensure incremental auto-vacuum: {e}");
         }
@@ -143,6 +148,17 @@ impl Database {
         if let Err(e) = db.cleanup_old_stream_check_logs(7) {
             log::warn!("Startup stream_check_logs cleanup failed: {e}");
         }
+        if let Err(e) = db.cleanup_old_speedtest_history(
+            crate::settings::effective_speedtest_history_retain_days(),
+        ) {
+            log::warn!("Startup speedtest_history cleanup failed: {e}");
+        }
+        if let Err(e) = db.cleanup_stale_import_progress(7) {
+            log::warn!("Startup import_progress cleanup failed: {e}");
+        }
+        if let Err(e) = db.prune_orphaned_discovery_caches() {
+            log::warn!("Startup discovery cache prune failed: {e}");
+        }
         if let Err(e) = db.rollup_and_prune(30) {
             log::warn!("Startup rollup_and_prune failed: {e}");
         }
@@ -7,6 +7,24 @@ use crate::error::AppError;
 use rusqlite::{params, Connection};
 use serde::Serialize;
 
+/// Schema 自检覆盖的资源表
+const RESOURCE_TABLES: [&str; 6] = [
+    "skills",
+    "commands",
+    "agents",
+    "hooks",
+    "skill_repos",
+    "command_repos",
+];
+
+/// Schema 自检发现的缺失列
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingColumn {
+    pub table: String,
+    pub column: String,
+}
+
 #[derive(Serialize)]
 struct LegacySkillMigrationRow {
     directory: String,
@@ -66,7 +84,7 @@ impl Database {
             description TEXT, homepage TEXT, docs TEXT, tags TEXT NOT NULL DEFAULT '[]',
             enabled_claude BOOLEAN NOT NULL DEFAULT 0, enabled_codex BOOLEAN NOT NULL DEFAULT 0,
             enabled_gemini BOOLEAN NOT NULL DEFAULT 0, enabled_opencode BOOLEAN NOT NULL DEFAULT 0,
-            enabled_hermes BOOLEAN NOT NULL DEFAULT 0
+            enabled_hermes BOOLEAN NOT NULL DEFAULT 0, locked BOOLEAN NOT NULL DEFAULT 0
         )",
             [],
         )
@@ -100,7 +118,8 @@ impl Database {
             installed_at INTEGER NOT NULL DEFAULT 0,
             updated_at INTEGER NOT NULL DEFAULT 0,
             scope TEXT NOT NULL DEFAULT 'global',
-            project_path TEXT
+            project_path TEXT,
+            locked BOOLEAN NOT NULL DEFAULT 0
         )",
             [],
         )
@@ -114,6 +133,7 @@ impl Database {
             builtin BOOLEAN NOT NULL DEFAULT 0,
             description_zh TEXT, description_en TEXT, description_ja TEXT,
             added_at INTEGER NOT NULL DEFAULT 0,
+            browse_only BOOLEAN NOT NULL DEFAULT 0,
             PRIMARY KEY (owner, name)
         )",
             [],
@@ -144,7 +164,12 @@ impl Database {
             file_hash TEXT,
             installed_at INTEGER NOT NULL DEFAULT 0,
             scope TEXT NOT NULL DEFAULT 'global',
-            project_path TEXT
+            project_path TEXT,
+            user_note TEXT,
+            display_name_override TEXT,
+            categories TEXT,
+            requires_env TEXT,
+            locked BOOLEAN NOT NULL DEFAULT 0
         )",
             [],
         )
@@ -170,6 +195,10 @@ impl Database {
             builtin BOOLEAN NOT NULL DEFAULT 0,
             description_zh TEXT, description_en TEXT, description_ja TEXT,
             added_at INTEGER NOT NULL DEFAULT 0,
+            excluded_keys TEXT,
+            has_commands INTEGER, has_agents INTEGER, has_hooks INTEGER,
+            user_edited_description BOOLEAN NOT NULL DEFAULT 0,
+            browse_only BOOLEAN NOT NULL DEFAULT 0,
             PRIMARY KEY (owner, name)
         )",
             [],
@@ -198,7 +227,11 @@ impl Database {
             file_hash TEXT,
             installed_at INTEGER NOT NULL DEFAULT 0,
             scope TEXT NOT NULL DEFAULT 'global',
-            project_path TEXT
+            project_path TEXT,
+            user_note TEXT,
+            display_name_override TEXT,
+            requires_env TEXT,
+            locked BOOLEAN NOT NULL DEFAULT 0
         )",
             [],
         )
@@ -248,7 +281,11 @@ impl Database {
             file_hash TEXT,
             installed_at INTEGER NOT NULL DEFAULT 0,
             scope TEXT NOT NULL DEFAULT 'global',
-            project_path TEXT
+            project_path TEXT,
+            user_note TEXT,
+            display_name_override TEXT,
+            requires_env TEXT,
+            locked BOOLEAN NOT NULL DEFAULT 0
         )",
             [],
         )
@@ -467,6 +504,12 @@ impl Database {
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
 
+        // 19. Speedtest History 表 (端点测速历史)
+        Self::create_speedtest_history_table(conn)?;
+
+        // 20. Import Progress 表 (可恢复批量安装的进度)
+        Self::create_import_progress_table(conn)?;
+
         // 尝试添加 live_takeover_active 列到 proxy_config 表
         let _ = conn.execute(
             "ALTER TABLE proxy_config ADD COLUMN live_takeover_active INTEGER NOT NULL DEFAULT 0",
@@ -636,6 +679,56 @@ impl Database {
                         Self::migrate_v14_to_v15(conn)?;
                         Self::set_user_version(conn, 15)?;
                     }
+                    15 => {
+                        log::info!("迁移数据库从 v15 到 v16（资源备注与显示名称覆盖）");
+                        Self::migrate_v15_to_v16(conn)?;
+                        Self::set_user_version(conn, 16)?;
+                    }
+                    16 => {
+                        log::info!("迁移数据库从 v16 到 v17（Commands 多分类）");
+                        Self::migrate_v16_to_v17(conn)?;
+                        Self::set_user_version(conn, 17)?;
+                    }
+                    17 => {
+                        log::info!("迁移数据库从 v17 到 v18（端点测速历史）");
+                        Self::migrate_v17_to_v18(conn)?;
+                        Self::set_user_version(conn, 18)?;
+                    }
+                    18 => {
+                        log::info!("迁移数据库从 v18 到 v19（仓库排除列表）");
+                        Self::migrate_v18_to_v19(conn)?;
+                        Self::set_user_version(conn, 19)?;
+                    }
+                    19 => {
+                        log::info!("迁移数据库从 v19 到 v20（批量安装进度表）");
+                        Self::migrate_v19_to_v20(conn)?;
+                        Self::set_user_version(conn, 20)?;
+                    }
+                    20 => {
+                        log::info!("迁移数据库从 v20 到 v21（仓库资源类型标记）");
+                        Self::migrate_v20_to_v21(conn)?;
+                        Self::set_user_version(conn, 21)?;
+                    }
+                    21 => {
+                        log::info!("迁移数据库从 v21 到 v22（资源环境变量需求）");
+                        Self::migrate_v21_to_v22(conn)?;
+                        Self::set_user_version(conn, 22)?;
+                    }
+                    22 => {
+                        log::info!("迁移数据库从 v22 到 v23（仓库描述编辑标记）");
+                        Self::migrate_v22_to_v23(conn)?;
+                        Self::set_user_version(conn, 23)?;
+                    }
+                    23 => {
+                        log::info!("迁移数据库从 v23 到 v24（资源锁定标记）");
+                        Self::migrate_v23_to_v24(conn)?;
+                        Self::set_user_version(conn, 24)?;
+                    }
+                    24 => {
+                        log::info!("迁移数据库从 v24 到 v25（仓库仅浏览标记）");
+                        Self::migrate_v24_to_v25(conn)?;
+                        Self::set_user_version(conn, 25)?;
+                    }
                     _ => {
                         return Err(AppError::Database(format!(
                             "未知的数据库版本 {version}，无法迁移到 {SCHEMA_VERSION}"
@@ -1783,6 +1876,169 @@ impl Database {
         Ok(())
     }
 
+    /// v15 -> v16 迁移：为 commands/agents/hooks 添加用户备注和显示名称覆盖列
+    fn migrate_v15_to_v16(conn: &Connection) -> Result<(), AppError> {
+        for table in ["commands", "agents", "hooks"] {
+            if !Self::table_exists(conn, table)? {
+                continue;
+            }
+            Self::add_column_if_missing(conn, table, "user_note", "TEXT")?;
+            Self::add_column_if_missing(conn, table, "display_name_override", "TEXT")?;
+        }
+
+        log::info!("v15 -> v16 迁移完成：已添加 user_note / display_name_override 列");
+        Ok(())
+    }
+
+    /// v16 -> v17 迁移：为 commands 添加 categories 列（JSON 数组）
+    ///
+    /// 旧数据保持为空，读取时回退到单个 category 字段
+    fn migrate_v16_to_v17(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "commands")? {
+            Self::add_column_if_missing(conn, "commands", "categories", "TEXT")?;
+        }
+
+        log::info!("v16 -> v17 迁移完成：已添加 commands.categories 列");
+        Ok(())
+    }
+
+    /// v17 -> v18 迁移：创建 speedtest_history 表
+    fn migrate_v17_to_v18(conn: &Connection) -> Result<(), AppError> {
+        Self::create_speedtest_history_table(conn)?;
+        log::info!("v17 -> v18 迁移完成：已创建 speedtest_history 表");
+        Ok(())
+    }
+
+    /// v18 -> v19 迁移：为 command_repos 添加 excluded_keys 列（JSON 数组）
+    fn migrate_v18_to_v19(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "command_repos")? {
+            Self::add_column_if_missing(conn, "command_repos", "excluded_keys", "TEXT")?;
+        }
+
+        log::info!("v18 -> v19 迁移完成：已添加 command_repos.excluded_keys 列");
+        Ok(())
+    }
+
+    /// v19 -> v20 迁移：创建 import_progress 表
+    fn migrate_v19_to_v20(conn: &Connection) -> Result<(), AppError> {
+        Self::create_import_progress_table(conn)?;
+        log::info!("v19 -> v20 迁移完成：已创建 import_progress 表");
+        Ok(())
+    }
+
+    /// v20 -> v21 迁移：为 command_repos 添加 has_commands/has_agents/has_hooks 列
+    ///
+    /// NULL 表示尚未扫描，首次发现时填充
+    fn migrate_v20_to_v21(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "command_repos")? {
+            for column in ["has_commands", "has_agents", "has_hooks"] {
+                Self::add_column_if_missing(conn, "command_repos", column, "INTEGER")?;
+            }
+        }
+
+        log::info!("v20 -> v21 迁移完成：已添加 command_repos 资源类型标记列");
+        Ok(())
+    }
+
+    /// v21 -> v22 迁移：为 commands/agents/hooks 添加 requires_env 列（JSON 数组）
+    fn migrate_v21_to_v22(conn: &Connection) -> Result<(), AppError> {
+        for table in ["commands", "agents", "hooks"] {
+            if Self::table_exists(conn, table)? {
+                Self::add_column_if_missing(conn, table, "requires_env", "TEXT")?;
+            }
+        }
+
+        log::info!("v21 -> v22 迁移完成：已添加 requires_env 列");
+        Ok(())
+    }
+
+    /// v22 -> v23 迁移：command_repos 添加 user_edited_description 列
+    ///
+    /// 标记后内置仓库同步不再覆盖用户修改过的描述
+    fn migrate_v22_to_v23(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "command_repos")? {
+            Self::add_column_if_missing(
+                conn,
+                "command_repos",
+                "user_edited_description",
+                "BOOLEAN NOT NULL DEFAULT 0",
+            )?;
+        }
+
+        log::info!("v22 -> v23 迁移完成：已添加 user_edited_description 列");
+        Ok(())
+    }
+
+    /// v23 -> v24 迁移：为资源表添加 locked 列（锁定的资源不参与批量操作）
+    fn migrate_v23_to_v24(conn: &Connection) -> Result<(), AppError> {
+        for table in ["mcp_servers", "skills", "commands", "agents", "hooks"] {
+            if !Self::table_exists(conn, table)? {
+                continue;
+            }
+            Self::add_column_if_missing(conn, table, "locked", "BOOLEAN NOT NULL DEFAULT 0")?;
+        }
+
+        log::info!("v23 -> v24 迁移完成：已添加 locked 列");
+        Ok(())
+    }
+
+    /// v24 -> v25 迁移：command_repos 与 skill_repos 添加 browse_only 列
+    ///
+    /// 仅浏览的仓库照常出现在发现列表中，但不能从中安装新资源
+    fn migrate_v24_to_v25(conn: &Connection) -> Result<(), AppError> {
+        for table in ["command_repos", "skill_repos"] {
+            if Self::table_exists(conn, table)? {
+                Self::add_column_if_missing(
+                    conn,
+                    table,
+                    "browse_only",
+                    "BOOLEAN NOT NULL DEFAULT 0",
+                )?;
+            }
+        }
+
+        log::info!("v24 -> v25 迁移完成：已添加 browse_only 列");
+        Ok(())
+    }
+
+    /// 创建批量安装进度表（每个 resume token 下每个已完成条目一行，全部完成后清除）
+    fn create_import_progress_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS import_progress (
+                token TEXT NOT NULL,
+                item_key TEXT NOT NULL,
+                completed_at INTEGER NOT NULL,
+                PRIMARY KEY (token, item_key)
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 import_progress 表失败: {e}")))?;
+        Ok(())
+    }
+
+    /// 创建端点测速历史表（每次测速每个端点一行）
+    fn create_speedtest_history_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS speedtest_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                url TEXT NOT NULL,
+                latency_ms INTEGER,
+                status INTEGER,
+                error TEXT,
+                tested_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 speedtest_history 表失败: {e}")))?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_speedtest_history_url
+             ON speedtest_history(url, tested_at DESC)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
     /// 插入默认模型定价数据
     /// 格式: (model_id, display_name, input, output, cache_read, cache_creation)
     /// 注意: model_id 使用短横线格式（如 claude-haiku-4-5），与 API 返回的模型名称标准化后一致
@@ -2459,6 +2715,61 @@ impl Database {
         Self::seed_model_pricing(conn)
     }
 
+    /// 修复资源表的 scope/project_path 列（每次启动执行，幂等）
+    ///
+    /// 从旧版本升级的数据库可能缺少这两列，或 scope 为 NULL，读取时行映射会失败
+    pub fn ensure_resource_scope_columns(&self) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        Self::ensure_resource_scope_columns_on_conn(&conn)
+    }
+
+    pub(crate) fn ensure_resource_scope_columns_on_conn(conn: &Connection) -> Result<(), AppError> {
+        for table in ["skills", "commands", "agents", "hooks"] {
+            if !Self::table_exists(conn, table)? {
+                continue;
+            }
+            Self::add_column_if_missing(conn, table, "scope", "TEXT NOT NULL DEFAULT 'global'")?;
+            Self::add_column_if_missing(conn, table, "project_path", "TEXT")?;
+
+            let sql = format!(
+                "UPDATE \"{table}\" SET scope = 'global' WHERE scope IS NULL OR scope = ''"
+            );
+            let fixed = conn
+                .execute(&sql, [])
+                .map_err(|e| AppError::Database(format!("修复 {table} 表的 scope 失败: {e}")))?;
+            if fixed > 0 {
+                log::info!("已将 {table} 表中 {fixed} 条记录的空 scope 修复为 global");
+            }
+        }
+        Ok(())
+    }
+
+    /// Schema 自检：对照当前版本的建表语句，列出资源表缺失的列（只读）
+    pub fn verify_schema(&self) -> Result<Vec<MissingColumn>, AppError> {
+        let conn = lock_conn!(self.conn);
+        Self::verify_schema_on_conn(&conn)
+    }
+
+    pub(crate) fn verify_schema_on_conn(conn: &Connection) -> Result<Vec<MissingColumn>, AppError> {
+        let expected =
+            Connection::open_in_memory().map_err(|e| AppError::Database(e.to_string()))?;
+        Self::create_tables_on_conn(&expected)?;
+
+        let mut missing = Vec::new();
+        for table in RESOURCE_TABLES {
+            let actual = Self::column_names(conn, table)?;
+            for column in Self::column_names(&expected, table)? {
+                if !actual.iter().any(|name| name.eq_ignore_ascii_case(&column)) {
+                    missing.push(MissingColumn {
+                        table: table.to_string(),
+                        column,
+                    });
+                }
+            }
+        }
+        Ok(missing)
+    }
+
     // --- 辅助方法 ---
 
     pub(crate) fn get_user_version(conn: &Connection) -> Result<i32, AppError> {
@@ -2513,9 +2824,17 @@ impl Database {
         table: &str,
         column: &str,
     ) -> Result<bool, AppError> {
-        Self::validate_identifier(table, "表名")?;
         Self::validate_identifier(column, "列名")?;
 
+        Ok(Self::column_names(conn, table)?
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(column)))
+    }
+
+    /// 读取表的全部列名（表不存在时为空）
+    fn column_names(conn: &Connection, table: &str) -> Result<Vec<String>, AppError> {
+        Self::validate_identifier(table, "表名")?;
+
         let sql = format!("PRAGMA table_info(\"{table}\");");
         let mut stmt = conn
             .prepare(&sql)
@@ -2523,15 +2842,14 @@ impl Database {
         let mut rows = stmt
             .query([])
             .map_err(|e| AppError::Database(format!("查询表结构失败: {e}")))?;
+        let mut names = Vec::new();
         while let Some(row) = rows.next().map_err(|e| AppError::Database(e.to_string()))? {
-            let name: String = row
-                .get(1)
-                .map_err(|e| AppError::Database(format!("读取列名失败: {e}")))?;
-            if name.eq_ignore_ascii_case(column) {
-                return Ok(true);
-            }
+            names.push(
+                row.get(1)
+                    .map_err(|e| AppError::Database(format!("读取列名失败: {e}")))?,
+            );
         }
-        Ok(false)
+        Ok(names)
     }
 
     fn add_column_if_missing(
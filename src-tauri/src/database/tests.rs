@@ -603,6 +603,55 @@ fn dry_run_validates_schema_compatibility() {
     );
 }
 
+#[test]
+fn ensure_resource_scope_columns_repairs_legacy_commands_table() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    Database::create_tables_on_conn(&conn).expect("create tables");
+
+    // 模拟旧版本：commands 表缺少 scope/project_path，hooks 表的 scope 允许 NULL
+    conn.execute_batch(
+        r#"
+        DROP TABLE commands;
+        CREATE TABLE commands (id TEXT PRIMARY KEY, name TEXT NOT NULL);
+        INSERT INTO commands (id, name) VALUES ('git/commit', 'commit');
+        DROP TABLE hooks;
+        CREATE TABLE hooks (id TEXT PRIMARY KEY, scope TEXT, project_path TEXT);
+        INSERT INTO hooks (id, scope) VALUES ('lint', NULL);
+        "#,
+    )
+    .expect("seed legacy tables");
+
+    let missing = Database::verify_schema_on_conn(&conn).expect("verify schema");
+    for column in ["scope", "project_path"] {
+        assert!(missing
+            .iter()
+            .any(|m| m.table == "commands" && m.column == column));
+    }
+
+    Database::ensure_resource_scope_columns_on_conn(&conn).expect("repair");
+    // 幂等：再次执行不报错
+    Database::ensure_resource_scope_columns_on_conn(&conn).expect("repair again");
+
+    let missing = Database::verify_schema_on_conn(&conn).expect("verify schema");
+    assert!(!missing
+        .iter()
+        .any(|m| m.column == "scope" || m.column == "project_path"));
+
+    for table in ["commands", "hooks"] {
+        let scope: String = conn
+            .query_row(&format!("SELECT scope FROM {table}"), [], |row| row.get(0))
+            .expect("read scope");
+        assert_eq!(scope, "global", "{table}.scope should be backfilled");
+    }
+
+    // 完整的新库没有缺失列
+    let fresh = Connection::open_in_memory().expect("open memory db");
+    Database::create_tables_on_conn(&fresh).expect("create tables");
+    assert!(Database::verify_schema_on_conn(&fresh)
+        .expect("verify schema")
+        .is_empty());
+}
+
 #[test]
 fn schema_model_pricing_is_seeded_on_init() {
     let db = Database::memory().expect("create memory db");
@@ -50,6 +50,7 @@ pub fn import_skill_from_deeplink(
         description_en: None,
         description_ja: None,
         added_at: now,
+        browse_only: false,
     };
 
     // Save using Database
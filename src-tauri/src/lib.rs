@@ -24,11 +24,14 @@ mod prompt;
 mod prompt_files;
 mod provider;
 mod provider_defaults;
+mod provider_secrets;
 mod proxy;
 mod services;
 mod session_manager;
 mod settings;
 mod store;
+#[cfg(test)]
+mod test_support;
 
 mod tray;
 mod usage_script;
@@ -184,6 +187,33 @@ async fn update_tray_menu(
     }
 }
 
+/// 供应商轮换后刷新托盘菜单，并发射 provider-switched 事件（携带切换警告）通知前端
+pub(crate) fn notify_provider_rotated(
+    app: &tauri::AppHandle,
+    app_type: &str,
+    rotation: &crate::services::provider::ProviderRotation,
+) {
+    if let Some(state) = app.try_state::<AppState>() {
+        if let Ok(new_menu) = tray::create_tray_menu(app, state.inner()) {
+            if let Some(tray) = app.tray_by_id(tray::TRAY_ID) {
+                if let Err(e) = tray.set_menu(Some(new_menu)) {
+                    log::error!("[Rotation] 更新托盘菜单失败: {e}");
+                }
+            }
+        }
+    }
+
+    let event_data = serde_json::json!({
+        "appType": app_type,
+        "providerId": rotation.to,
+        "source": "rotation",
+        "warnings": rotation.warnings
+    });
+    if let Err(e) = app.emit("provider-switched", event_data) {
+        log::error!("[Rotation] 发射事件失败: {e}");
+    }
+}
+
 #[cfg(target_os = "macos")]
 fn macos_tray_icon() -> Option<Image<'static>> {
     const ICON_BYTES: &[u8] = include_bytes!("../icons/tray/macos/statusbar_template_3x.png");
@@ -272,6 +302,7 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .setup(|app| {
             let _ = rustls::crypto::ring::default_provider().install_default();
@@ -328,6 +359,9 @@ pub fn run() {
                 )?;
             }
 
+            // 检查现有配置：SSOT 目录不能位于应用配置目录内
+            crate::config::warn_if_ssot_dir_invalid();
+
             // 初始化数据库
             let app_config_dir = crate::config::get_app_config_dir();
             let db_path = app_config_dir.join("cc-switch.db");
@@ -431,6 +465,10 @@ pub fn run() {
             }
 
             // 1.0.1. 同步内置 Commands 仓库（每次启动都会检查并添加缺失的内置仓库）
+            // 先合并仅大小写不同的重复仓库记录，避免同一仓库被扫描两次
+            if let Err(e) = app_state.db.dedupe_command_repos() {
+                log::warn!("✗ Failed to dedupe command repos: {e}");
+            }
             match app_state.db.sync_builtin_command_repos() {
                 Ok((added, updated)) if added > 0 || updated > 0 => {
                     log::info!("✓ Synced builtin command repos: added {added}, updated {updated}");
@@ -524,6 +562,17 @@ pub fn run() {
                 Err(e) => log::warn!("✗ Failed to seed official providers: {e}"),
             }
 
+            // 开启密钥加密时，补加密经导入/同步写入的明文密钥
+            if crate::provider_secrets::is_encryption_enabled() {
+                match app_state.db.reencrypt_provider_secrets(true) {
+                    Ok(count) if count > 0 => {
+                        log::info!("✓ Encrypted plaintext keys of {count} provider(s)");
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::warn!("✗ Failed to encrypt provider keys: {e}"),
+                }
+            }
+
             // 老用户 / 已确认的路径由 `fresh_install_at_startup` 自行拦截，这里不做写入。
             // 字段只由前端在用户点击"我知道了"时 save_settings 回写，语义是"用户显式确认过"。
             if !first_run_already_confirmed && fresh_install_at_startup {
@@ -837,6 +886,13 @@ pub fn run() {
             let hook_service = HookService::new();
             app.manage(commands::hook::HookServiceState(Arc::new(hook_service)));
 
+            // 清理过期的回收站条目
+            if let Err(e) = crate::services::trash::TrashService::purge_expired(
+                crate::services::trash::TRASH_RETENTION_DAYS,
+            ) {
+                log::warn!("清理过期回收站条目失败: {e}");
+            }
+
             // 初始化 CopilotAuthManager
             {
                 use crate::proxy::providers::copilot_auth::CopilotAuthManager;
@@ -927,6 +983,43 @@ pub fn run() {
                     log::warn!("Periodic backup failed on startup: {e}");
                 }
 
+                // 应用目录覆盖提示：覆盖后文件不会写入默认位置，容易被遗忘
+                for info in crate::services::ConfigService::override_report() {
+                    if info.exists {
+                        log::warn!("{} 的配置目录已被覆盖为 {}", info.app, info.override_path);
+                    } else {
+                        log::warn!(
+                            "{} 的配置目录已被覆盖为 {}，但该目录不存在",
+                            info.app,
+                            info.override_path
+                        );
+                    }
+                }
+
+                // 资源完整性校验（可选，只读，仅本地哈希）
+                if crate::settings::get_settings().verify_integrity_on_startup {
+                    match crate::services::ConfigService::verify_integrity(&state.db) {
+                        Ok(issues) if issues.is_empty() => {
+                            log::info!("资源完整性校验通过");
+                        }
+                        Ok(issues) => {
+                            for issue in &issues {
+                                log::warn!(
+                                    "资源完整性问题: {} {} ({:?})",
+                                    issue.resource_type,
+                                    issue.id,
+                                    issue.kind
+                                );
+                            }
+                            if let Err(e) = app_handle.emit("integrity-issues", &issues) {
+                                log::warn!("发送完整性问题事件失败: {e}");
+                            }
+                            crate::services::ConfigService::set_startup_integrity_issues(issues);
+                        }
+                        Err(e) => log::warn!("资源完整性校验失败: {e}"),
+                    }
+                }
+
                 // Periodic maintenance timer: run once per day while the app is running
                 let db_for_timer = state.db.clone();
                 tauri::async_runtime::spawn(async move {
@@ -1001,6 +1094,37 @@ pub fn run() {
                         }
                     }
                 });
+
+                // Provider rotation: 每 60 秒检查当前供应商是否接近限额
+                let app_handle_for_rotation = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    const PROVIDER_ROTATION_INTERVAL_SECS: u64 = 60;
+                    let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+                        PROVIDER_ROTATION_INTERVAL_SECS,
+                    ));
+                    loop {
+                        interval.tick().await;
+                        let handle = app_handle_for_rotation.clone();
+                        // switch 内部使用阻塞等待，放到阻塞线程中执行
+                        let _ = tauri::async_runtime::spawn_blocking(move || {
+                            let state = handle.state::<AppState>();
+                            for app_type in crate::app_config::AppType::all() {
+                                let app = app_type.as_str().to_string();
+                                match crate::services::ProviderService::rotate_if_needed(
+                                    state.inner(),
+                                    app_type,
+                                ) {
+                                    Ok(Some(rotation)) => {
+                                        notify_provider_rotated(&handle, &app, &rotation)
+                                    }
+                                    Ok(None) => {}
+                                    Err(e) => log::warn!("Provider rotation for {app} failed: {e}"),
+                                }
+                            }
+                        })
+                        .await;
+                    }
+                });
             });
 
             // Linux: 禁用 WebKitGTK 硬件加速，防止 EGL 初始化失败导致白屏
@@ -1055,6 +1179,8 @@ pub fn run() {
             commands::get_providers,
             commands::get_current_provider,
             commands::add_provider,
+            commands::get_provider_templates,
+            commands::create_provider_from_template,
             commands::update_provider,
             commands::delete_provider,
             commands::remove_provider_from_live_config,
@@ -1062,6 +1188,16 @@ pub fn run() {
             commands::import_default_config,
             commands::get_claude_config_status,
             commands::get_config_status,
+            commands::get_sync_status,
+            commands::get_resource_counts,
+            commands::verify_integrity,
+            commands::get_startup_integrity_issues,
+            commands::copy_app_config,
+            commands::enable_all_for_app,
+            commands::disable_all_for_app,
+            commands::list_extra_app_files,
+            commands::get_path_report,
+            commands::get_override_report,
             commands::get_claude_code_config_path,
             commands::get_config_dir,
             commands::open_config_folder,
@@ -1088,6 +1224,9 @@ pub fn run() {
             commands::set_copilot_optimizer_config,
             commands::get_log_config,
             commands::set_log_config,
+            commands::set_log_level,
+            commands::get_download_timeouts,
+            commands::set_download_timeouts,
             commands::restart_app,
             commands::check_for_updates,
             commands::is_portable_mode,
@@ -1123,6 +1262,7 @@ pub fn run() {
             commands::delete_mcp_server,
             commands::toggle_mcp_app,
             commands::import_mcp_from_apps,
+            commands::lint_mcp_servers,
             // Prompt management
             commands::get_prompts,
             commands::upsert_prompt,
@@ -1134,10 +1274,18 @@ pub fn run() {
             commands::fetch_models_for_config,
             // ours: endpoint speed test + custom endpoint management
             commands::test_api_endpoints,
+            commands::get_speedtest_history,
+            commands::clear_speedtest_history,
             commands::get_custom_endpoints,
+            commands::set_provider_key_encryption,
             commands::add_custom_endpoint,
             commands::remove_custom_endpoint,
             commands::update_endpoint_last_used,
+            // Provider rotation tied to usage limits
+            commands::get_provider_rotation_policy,
+            commands::set_provider_rotation_policy,
+            commands::get_provider_rotation_state,
+            commands::rotate_provider_if_needed,
             // app_config_dir override via Store
             commands::get_app_config_dir_override,
             commands::set_app_config_dir_override,
@@ -1159,6 +1307,9 @@ pub fn run() {
             commands::restore_db_backup,
             commands::rename_db_backup,
             commands::delete_db_backup,
+            commands::snapshot_db,
+            commands::restore_db,
+            commands::verify_schema,
             commands::sync_current_providers_live,
             // Deep link import
             commands::parse_deeplink,
@@ -1168,6 +1319,7 @@ pub fn run() {
             update_tray_menu,
             // Environment variable management
             commands::check_env_conflicts,
+            commands::check_env_requirements,
             commands::delete_env_vars,
             commands::restore_env_backup,
             // Skill management (v3.10.0+ unified)
@@ -1175,6 +1327,7 @@ pub fn run() {
             commands::get_skill_backups,
             commands::delete_skill_backup,
             commands::install_skill_unified,
+            commands::install_skill_for_apps,
             commands::uninstall_skill_unified,
             commands::uninstall_skills_batch,
             commands::restore_skill_backup,
@@ -1199,6 +1352,7 @@ pub fn run() {
             commands::remove_skill_repo,
             commands::restore_builtin_skill_repos,
             commands::is_builtin_skill_repo,
+            commands::set_skill_repo_browse_only,
             commands::install_skills_from_zip,
             // Skill namespace management (v3.12.0+)
             commands::get_skill_namespaces,
@@ -1208,45 +1362,83 @@ pub fn run() {
             // Command management (v3.11.0+ unified)
             commands::get_installed_commands,
             commands::get_command_namespaces,
+            commands::get_effective_allowed_tools,
             commands::install_command_unified,
+            commands::install_command_for_apps,
+            commands::detect_current_project,
+            commands::install_command_to_detected_project,
+            commands::install_commands_by_keys,
+            commands::create_command_from_content,
             commands::uninstall_command_unified,
             commands::uninstall_commands_batch,
             commands::toggle_command_app,
+            commands::toggle_command_namespace_app,
+            commands::set_command_user_note,
+            commands::set_command_display_name,
             commands::change_command_scope,
+            commands::switch_command_branch,
             commands::create_command_namespace,
             commands::delete_command_namespace,
             commands::scan_unmanaged_commands,
             commands::import_commands_from_apps,
             commands::discover_available_commands,
+            commands::discover_available_commands_detailed,
+            commands::discover_available_commands_paged,
+            commands::compare_command_repos,
             commands::get_command_content,
+            commands::list_command_parse_issues,
             commands::open_command_in_editor,
             commands::check_app_commands_support,
             commands::get_command_repos,
+            commands::get_repo_metadata,
+            commands::prune_orphaned_caches,
             commands::add_command_repo,
+            commands::export_command_repos,
+            commands::import_command_repos,
             commands::remove_command_repo,
+            commands::list_installed_by_repo,
+            commands::uninstall_all_from_repo,
+            commands::exclude_command_from_repo,
+            commands::unexclude_command_from_repo,
+            commands::install_recipe,
             commands::restore_builtin_command_repos,
             commands::is_builtin_command_repo,
+            commands::update_command_repo_descriptions,
+            commands::set_command_repo_user_edited_description,
+            commands::set_command_repo_browse_only,
             commands::clear_command_cache,
             commands::detect_command_changes,
             commands::resolve_command_conflict,
             commands::refresh_commands_from_ssot,
+            commands::reconcile_commands_from_ssot,
             commands::sync_commands_to_apps,
+            commands::sync_drifted_commands_to_apps,
+            commands::plan_sync_commands_to_apps,
             // Agent management (v3.11.0+ unified)
             commands::get_installed_agents,
             commands::get_agent_namespaces,
             commands::install_agent_unified,
+            commands::install_agent_for_apps,
+            commands::create_agent_from_content,
             commands::uninstall_agent_unified,
             commands::uninstall_agents_batch,
             commands::toggle_agent_app,
+            commands::toggle_agent_namespace_app,
+            commands::set_agent_user_note,
+            commands::set_agent_display_name,
             commands::change_agent_scope,
+            commands::switch_agent_branch,
             commands::create_agent_namespace,
             commands::delete_agent_namespace,
             commands::scan_unmanaged_agents,
             commands::import_agents_from_apps,
             commands::discover_available_agents,
+            commands::discover_available_agents_paged,
             commands::get_agent_content,
+            commands::list_agent_parse_issues,
             commands::open_agent_in_editor,
             commands::check_app_agents_support_cmd,
+            commands::check_agent_compatibility,
             commands::get_agent_repos,
             commands::add_agent_repo,
             commands::remove_agent_repo,
@@ -1255,20 +1447,37 @@ pub fn run() {
             commands::resolve_agent_conflict,
             commands::refresh_agents_from_ssot,
             commands::sync_agents_to_apps,
+            commands::sync_drifted_agents_to_apps,
+            commands::plan_sync_agents_to_apps,
             // Hook management (统一管理)
             commands::get_installed_hooks,
             commands::get_hook_namespaces,
             commands::install_hook_unified,
+            commands::install_hook_for_apps,
+            commands::create_hook_from_content,
+            commands::import_official_hooks,
             commands::uninstall_hook_unified,
             commands::toggle_hook_enabled,
             commands::toggle_hook_app,
+            commands::set_hook_user_note,
+            commands::set_hook_display_name,
             commands::change_hook_scope,
+            commands::switch_hook_branch,
             commands::update_hook_priority,
             commands::reorder_hooks,
             commands::create_hook_namespace,
             commands::delete_hook_namespace,
+            commands::move_hook_to_namespace,
+            commands::bulk_move_hooks_namespace,
             commands::scan_unmanaged_hooks,
+            commands::validate_hook_commands,
+            commands::validate_hooks,
+            commands::test_run_hook,
+            commands::rebuild_app_hooks,
+            commands::check_hooks_external_modification,
+            commands::preview_hook_matcher,
             commands::discover_available_hooks,
+            commands::discover_available_hooks_paged,
             commands::get_hook_content,
             commands::open_hook_in_editor,
             commands::check_app_hooks_support_cmd,
@@ -1278,6 +1487,41 @@ pub fn run() {
             commands::clear_hook_cache,
             commands::refresh_hooks_from_ssot,
             commands::sync_hooks_to_apps,
+            commands::plan_sync_hooks_to_apps,
+            // Trash (uninstalled commands/agents/hooks)
+            commands::get_trash_entries,
+            commands::restore_from_trash,
+            commands::delete_trash_entry,
+            commands::empty_trash,
+            // Duplicate installs (same content from different repos)
+            commands::find_duplicate_installs,
+            commands::merge_duplicates,
+            commands::dump_discovery_cache,
+            // Resource provenance (where an installed item came from)
+            commands::get_provenance,
+            commands::get_install_source,
+            commands::check_pinned_staleness,
+            commands::export_resource,
+            // Per-resource lock against bulk operations
+            commands::set_resource_locked,
+            commands::get_locked_resources,
+            // Sanitized HTML previews of resource bodies
+            commands::get_resource_rendered,
+            commands::get_resource_path,
+            // Chunked streaming of large installed-resource listings
+            commands::stream_installed_resources,
+            // Full-text search inside resource bodies
+            commands::search_content,
+            // SSOT-vs-DB namespace consistency
+            commands::verify_namespace_consistency,
+            commands::repair_namespaces,
+            commands::list_empty_namespaces,
+            commands::cleanup_empty_namespaces,
+            // Installed resources not enabled for any app
+            commands::list_orphaned_installs,
+            commands::uninstall_orphaned_installs,
+            // Refresh one repo's discovery cache
+            commands::refresh_repo_cache,
             // Resource update detection (v3.12.0+)
             commands::check_skills_updates,
             commands::check_skills_updates_by_ids,
@@ -1288,6 +1532,10 @@ pub fn run() {
             commands::check_agents_updates,
             commands::check_agents_updates_by_ids,
             commands::check_resource_updates,
+            commands::check_update_against_tag,
+            commands::list_repo_tags,
+            commands::get_upstream_diff,
+            commands::revert_to_upstream,
             commands::validate_github_token,
             commands::save_github_token,
             commands::get_github_token_status,
@@ -1300,6 +1548,7 @@ pub fn run() {
             commands::update_agent,
             commands::update_agents_batch,
             commands::fix_agents_hash,
+            commands::normalize_hashes_to_blob_sha,
             // Auto launch
             commands::set_auto_launch,
             commands::get_auto_launch_status,
@@ -1350,6 +1599,7 @@ pub fn run() {
             // Session usage sync
             commands::sync_session_usage,
             commands::get_usage_data_sources,
+            commands::redact_request_logs,
             // Stream health check
             commands::stream_check_provider,
             commands::stream_check_all_providers,
@@ -296,6 +296,18 @@ pub struct ProviderMeta {
     /// 用于多账号支持，关联到特定的 GitHub 账号
     #[serde(rename = "githubAccountId", skip_serializing_if = "Option::is_none")]
     pub github_account_id: Option<String>,
+    /// 附加请求头（如 OpenRouter 的 `HTTP-Referer`、`X-Title`）
+    /// 写入 live 配置的请求头字段，并在测试连接时一并发送
+    #[serde(
+        rename = "extraHeaders",
+        default,
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub extra_headers: HashMap<String, String>,
+    /// 部署名（Azure OpenAI 等按部署划分端点的服务）
+    /// 写入 live 配置、代理转发和测试连接时与基础 URL 拼接，见 `compose_deployment_url`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deployment: Option<String>,
 }
 
 impl ProviderMeta {
@@ -0,0 +1,440 @@
+//! 供应商密钥的静态加密
+//!
+//! 启用 `encrypt_provider_keys` 设置后，写入数据库的 `settings_config` 中的密钥字段
+//! （如 `ANTHROPIC_AUTH_TOKEN`、`apiKey`）会以 AES-256-GCM 加密为
+//! `enc:v1:<base64(nonce || ciphertext)>`，读取时自动解密。
+//!
+//! 主密钥保存在系统钥匙串中；钥匙串不可用时回退到 `~/.cc-switch/master.key`
+//! （仅当前用户可读）。解密不依赖设置开关，关闭加密后已有密文仍可正常读取。
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use once_cell::sync::OnceCell;
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::get_app_config_dir;
+use crate::error::AppError;
+
+/// 密文前缀（含格式版本）
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+const KEYRING_SERVICE: &str = "cc-switch";
+const KEYRING_USER: &str = "provider-master-key";
+const FALLBACK_KEY_FILE: &str = "master.key";
+const NONCE_LEN: usize = 12;
+
+static MASTER_KEY: OnceCell<[u8; 32]> = OnceCell::new();
+
+/// 当前是否启用供应商密钥加密
+pub fn is_encryption_enabled() -> bool {
+    crate::settings::get_settings().encrypt_provider_keys
+}
+
+/// 判断 JSON 字段名是否为需要加密的密钥字段
+fn is_secret_key(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    ["api_key", "apikey", "auth_token", "access_token", "secret"]
+        .iter()
+        .any(|suffix| lower.ends_with(suffix))
+        || matches!(lower.as_str(), "token" | "password")
+}
+
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+fn fallback_key_path() -> PathBuf {
+    get_app_config_dir().join(FALLBACK_KEY_FILE)
+}
+
+fn decode_key(encoded: &str) -> Option<[u8; 32]> {
+    BASE64.decode(encoded.trim()).ok()?.try_into().ok()
+}
+
+fn read_fallback_key() -> Result<Option<[u8; 32]>, AppError> {
+    let path = fallback_key_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?;
+    decode_key(&content)
+        .map(Some)
+        .ok_or_else(|| AppError::Config(format!("主密钥文件格式无效: {}", path.display())))
+}
+
+fn write_fallback_key(key: &[u8; 32]) -> Result<(), AppError> {
+    let path = fallback_key_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    }
+    fs::write(&path, BASE64.encode(key)).map_err(|e| AppError::io(&path, e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| AppError::io(&path, e))?;
+    }
+    Ok(())
+}
+
+/// 加载主密钥：钥匙串 → 回退文件 → 新生成
+///
+/// 新密钥优先写入钥匙串，失败时写入回退文件。已存在回退文件时不再写钥匙串，
+/// 避免两处保存不同的密钥。
+fn load_or_create_master_key() -> Result<[u8; 32], AppError> {
+    let entry = match keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER) {
+        Ok(entry) => Some(entry),
+        Err(e) => {
+            log::warn!("系统钥匙串不可用，使用本地主密钥文件: {e}");
+            None
+        }
+    };
+
+    if let Some(entry) = &entry {
+        match entry.get_password() {
+            Ok(encoded) => {
+                if let Some(key) = decode_key(&encoded) {
+                    return Ok(key);
+                }
+                log::warn!("钥匙串中的主密钥格式无效，忽略");
+            }
+            Err(keyring::Error::NoEntry) => {}
+            Err(e) => log::warn!("读取系统钥匙串失败，使用本地主密钥文件: {e}"),
+        }
+    }
+
+    if let Some(key) = read_fallback_key()? {
+        return Ok(key);
+    }
+
+    let key: [u8; 32] = Aes256Gcm::generate_key(OsRng).into();
+    let stored_in_keyring = entry
+        .as_ref()
+        .map(|entry| entry.set_password(&BASE64.encode(key)))
+        .map(|result| {
+            if let Err(e) = &result {
+                log::warn!("写入系统钥匙串失败，改用本地主密钥文件: {e}");
+            }
+            result.is_ok()
+        })
+        .unwrap_or(false);
+
+    if !stored_in_keyring {
+        write_fallback_key(&key)?;
+    }
+    log::info!("已生成供应商密钥加密主密钥");
+    Ok(key)
+}
+
+fn cipher() -> Result<Aes256Gcm, AppError> {
+    let key = MASTER_KEY.get_or_try_init(load_or_create_master_key)?;
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)))
+}
+
+/// 加密单个字符串（已加密的值原样返回）
+pub fn encrypt_str(plaintext: &str) -> Result<String, AppError> {
+    if is_encrypted(plaintext) {
+        return Ok(plaintext.to_string());
+    }
+    let cipher = cipher()?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|e| AppError::Message(format!("加密供应商密钥失败: {e}")))?;
+
+    let mut payload = nonce.to_vec();
+    payload.extend_from_slice(&ciphertext);
+    Ok(format!("{ENCRYPTED_PREFIX}{}", BASE64.encode(payload)))
+}
+
+/// 解密单个字符串（非密文原样返回）
+pub fn decrypt_str(value: &str) -> Result<String, AppError> {
+    let Some(encoded) = value.strip_prefix(ENCRYPTED_PREFIX) else {
+        return Ok(value.to_string());
+    };
+    let payload = BASE64
+        .decode(encoded)
+        .map_err(|e| AppError::Message(format!("供应商密钥密文格式无效: {e}")))?;
+    if payload.len() <= NONCE_LEN {
+        return Err(AppError::Message("供应商密钥密文长度无效".to_string()));
+    }
+    let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+    let plaintext = cipher()?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| AppError::Message("解密供应商密钥失败（主密钥不匹配？）".to_string()))?;
+    String::from_utf8(plaintext)
+        .map_err(|e| AppError::Message(format!("供应商密钥解密结果不是有效 UTF-8: {e}")))
+}
+
+/// 递归处理 JSON 中的密钥字段，返回被修改的字段数
+fn transform_secrets(
+    value: &mut Value,
+    transform: &dyn Fn(&str) -> Result<Option<String>, AppError>,
+) -> Result<usize, AppError> {
+    let mut changed = 0;
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if let Value::String(s) = child {
+                    if is_secret_key(key) && !s.is_empty() {
+                        if let Some(next) = transform(s)? {
+                            *s = next;
+                            changed += 1;
+                        }
+                    }
+                } else {
+                    changed += transform_secrets(child, transform)?;
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                changed += transform_secrets(item, transform)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(changed)
+}
+
+/// 加密 `settings_config` 中的所有明文密钥字段
+pub fn encrypt_settings_config(value: &mut Value) -> Result<usize, AppError> {
+    transform_secrets(value, &|s| {
+        if is_encrypted(s) {
+            Ok(None)
+        } else {
+            encrypt_str(s).map(Some)
+        }
+    })
+}
+
+/// 解密 `settings_config` 中的所有密文字段
+pub fn decrypt_settings_config(value: &mut Value) -> Result<usize, AppError> {
+    transform_secrets(value, &|s| {
+        if is_encrypted(s) {
+            decrypt_str(s).map(Some)
+        } else {
+            Ok(None)
+        }
+    })
+}
+
+/// 序列化 `settings_config` 用于写入数据库（按设置决定是否加密）
+///
+/// `stored` 为数据库中现有的 `settings_config`。读取时因无法解密而被清空的密钥字段
+/// 保留原密文，不会被空值覆盖；用户重新填写后才会替换
+pub fn serialize_for_storage(
+    settings_config: &Value,
+    stored: Option<&str>,
+) -> Result<String, AppError> {
+    let mut value = settings_config.clone();
+    if let Some(stored) = stored.and_then(|s| serde_json::from_str::<Value>(s).ok()) {
+        let kept = keep_undecryptable(&mut value, &stored);
+        if kept > 0 {
+            log::warn!("{kept} 个供应商密钥无法解密，保留原密文，请重新填写");
+        }
+    }
+    if is_encryption_enabled() {
+        encrypt_settings_config(&mut value)?;
+    }
+    serde_json::to_string(&value)
+        .map_err(|e| AppError::Database(format!("Failed to serialize settings_config: {e}")))
+}
+
+/// 将 `stored` 中无法解密、且在 `value` 中为空的密钥字段恢复为原密文，返回恢复的字段数
+fn keep_undecryptable(value: &mut Value, stored: &Value) -> usize {
+    let mut kept = 0;
+    match (value, stored) {
+        (Value::Object(map), Value::Object(stored_map)) => {
+            for (key, child) in map.iter_mut() {
+                let Some(stored_child) = stored_map.get(key) else {
+                    continue;
+                };
+                match (child, stored_child) {
+                    (Value::String(s), Value::String(previous)) => {
+                        if is_secret_key(key)
+                            && s.is_empty()
+                            && is_encrypted(previous)
+                            && decrypt_str(previous).is_err()
+                        {
+                            *s = previous.clone();
+                            kept += 1;
+                        }
+                    }
+                    (child, stored_child) => kept += keep_undecryptable(child, stored_child),
+                }
+            }
+        }
+        (Value::Array(items), Value::Array(stored_items)) => {
+            for (item, stored_item) in items.iter_mut().zip(stored_items) {
+                kept += keep_undecryptable(item, stored_item);
+            }
+        }
+        _ => {}
+    }
+    kept
+}
+
+/// 从数据库读取后解密 `settings_config`
+///
+/// 逐个字段解密；某个字段解密失败（例如数据库来自另一台设备）时清空该字段并记录警告，
+/// 不阻断读取，也避免把密文当作密钥写入应用配置。数据库中的密文由
+/// [`serialize_for_storage`] 保留，不会因保存被清空的值而丢失
+pub fn decrypt_from_storage(value: &mut Value) {
+    let result = transform_secrets(value, &|s| {
+        if !is_encrypted(s) {
+            return Ok(None);
+        }
+        Ok(Some(decrypt_str(s).unwrap_or_else(|e| {
+            log::warn!("解密供应商密钥失败，已清空该字段，请重新填写: {e}");
+            String::new()
+        })))
+    });
+    if let Err(e) = result {
+        log::warn!("解密供应商密钥失败: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn secret_key_detection_skips_token_limits() {
+        assert!(is_secret_key("ANTHROPIC_AUTH_TOKEN"));
+        assert!(is_secret_key("OPENAI_API_KEY"));
+        assert!(is_secret_key("apiKey"));
+        assert!(!is_secret_key("CLAUDE_CODE_MAX_OUTPUT_TOKENS"));
+        assert!(!is_secret_key("ANTHROPIC_BASE_URL"));
+    }
+
+    #[test]
+    fn transform_only_touches_secret_fields() {
+        let mut value = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-test",
+                "ANTHROPIC_BASE_URL": "https://example.com"
+            },
+            "options": [{ "apiKey": "k" }, { "apiKey": "" }]
+        });
+        let changed =
+            transform_secrets(&mut value, &|s| Ok(Some(format!("x-{s}")))).expect("transform");
+
+        assert_eq!(changed, 2);
+        assert_eq!(value["env"]["ANTHROPIC_AUTH_TOKEN"], "x-sk-test");
+        assert_eq!(value["env"]["ANTHROPIC_BASE_URL"], "https://example.com");
+        assert_eq!(value["options"][0]["apiKey"], "x-k");
+        assert_eq!(value["options"][1]["apiKey"], "");
+    }
+
+    /// 使用固定主密钥，避免测试读写系统钥匙串
+    fn use_test_master_key() {
+        let _ = MASTER_KEY.set([7u8; 32]);
+    }
+
+    #[test]
+    fn settings_config_round_trips_through_encryption() {
+        use_test_master_key();
+        let original = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-test",
+                "ANTHROPIC_BASE_URL": "https://example.com"
+            }
+        });
+
+        let mut value = original.clone();
+        assert_eq!(encrypt_settings_config(&mut value).unwrap(), 1);
+        assert!(is_encrypted(
+            value["env"]["ANTHROPIC_AUTH_TOKEN"].as_str().unwrap()
+        ));
+
+        decrypt_from_storage(&mut value);
+        assert_eq!(value, original);
+    }
+
+    /// 用另一把密钥加密，模拟数据库来自另一台设备
+    fn foreign_ciphertext(plaintext: &str) -> String {
+        let other = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&[9u8; 32]));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&other.encrypt(&nonce, plaintext.as_bytes()).unwrap());
+        format!("{ENCRYPTED_PREFIX}{}", BASE64.encode(payload))
+    }
+
+    #[test]
+    fn undecryptable_field_is_cleared_instead_of_kept() {
+        use_test_master_key();
+        let foreign = foreign_ciphertext("sk-foreign");
+
+        let mut value = json!({
+            "env": { "ANTHROPIC_AUTH_TOKEN": foreign, "OPENAI_API_KEY": "sk-plain" }
+        });
+        decrypt_from_storage(&mut value);
+
+        assert_eq!(value["env"]["ANTHROPIC_AUTH_TOKEN"], "");
+        assert_eq!(value["env"]["OPENAI_API_KEY"], "sk-plain");
+    }
+
+    #[test]
+    fn saving_a_provider_read_with_wrong_key_keeps_ciphertext() {
+        use crate::database::Database;
+        use crate::provider::Provider;
+
+        use_test_master_key();
+        let foreign = foreign_ciphertext("sk-foreign");
+        let db = Database::memory().unwrap();
+        let provider = Provider::with_id(
+            "p1".to_string(),
+            "Foreign".to_string(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": foreign.clone() } }),
+            None,
+        );
+        db.save_provider("claude", &provider).unwrap();
+        let stored_token = |db: &Database| -> String {
+            let conn = db.conn.lock().unwrap();
+            let raw: String = conn
+                .query_row(
+                    "SELECT settings_config FROM providers WHERE id = 'p1'",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            let value: Value = serde_json::from_str(&raw).unwrap();
+            value["env"]["ANTHROPIC_AUTH_TOKEN"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+
+        // 读取时清空，保存回去后数据库中仍是原密文
+        let read = db.get_provider_by_id("p1", "claude").unwrap().unwrap();
+        assert_eq!(read.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"], "");
+        db.save_provider("claude", &read).unwrap();
+        assert_eq!(stored_token(&db), foreign);
+        db.update_provider_settings_config("claude", "p1", &read.settings_config)
+            .unwrap();
+        assert_eq!(stored_token(&db), foreign);
+
+        // 重新填写后才替换
+        let mut reentered = read.clone();
+        reentered.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"] = json!("sk-new");
+        db.save_provider("claude", &reentered).unwrap();
+        assert_ne!(stored_token(&db), foreign);
+        let read = db.get_provider_by_id("p1", "claude").unwrap().unwrap();
+        assert_eq!(
+            read.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+            "sk-new"
+        );
+    }
+
+    #[test]
+    fn plaintext_passes_through_decrypt() {
+        assert_eq!(decrypt_str("sk-plain").unwrap(), "sk-plain");
+        assert!(!is_encrypted("sk-plain"));
+    }
+}
@@ -23,6 +23,7 @@ use super::{
 use crate::commands::{CodexOAuthState, CopilotAuthState};
 use crate::proxy::providers::codex_oauth_auth::CodexOAuthManager;
 use crate::proxy::providers::copilot_auth::CopilotAuthManager;
+use crate::services::provider::compose_deployment_url;
 use crate::{app_config::AppType, provider::Provider};
 use http::Extensions;
 use serde_json::Value;
@@ -765,8 +766,11 @@ impl RequestForwarder {
         extensions: &Extensions,
         adapter: &dyn ProviderAdapter,
     ) -> Result<(ProxyResponse, Option<String>), ProxyError> {
-        // 使用适配器提取 base_url
+        // 使用适配器提取 base_url，再拼接部署名（数据库中保存的是拆分的基础 URL + 部署名）
         let mut base_url = adapter.extract_base_url(provider)?;
+        if let Some(deployment) = provider.meta.as_ref().and_then(|m| m.deployment.as_deref()) {
+            base_url = compose_deployment_url(&base_url, deployment);
+        }
 
         let is_full_url = provider
             .meta
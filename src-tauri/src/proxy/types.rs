@@ -353,6 +353,15 @@ impl Default for LogConfig {
 }
 
 impl LogConfig {
+    /// 支持的日志级别
+    pub const LEVELS: [&'static str; 5] = ["error", "warn", "info", "debug", "trace"];
+
+    /// 校验日志级别（不区分大小写），返回规范化的小写级别
+    pub fn normalize_level(level: &str) -> Option<String> {
+        let level = level.trim().to_lowercase();
+        Self::LEVELS.contains(&level.as_str()).then_some(level)
+    }
+
     /// 将配置转换为 log::LevelFilter
     pub fn to_level_filter(&self) -> log::LevelFilter {
         if !self.enabled {
@@ -481,6 +490,14 @@ mod tests {
         assert_eq!(config.to_level_filter(), log::LevelFilter::Off);
     }
 
+    #[test]
+    fn test_log_config_normalize_level() {
+        assert_eq!(LogConfig::normalize_level(" TRACE "), Some("trace".into()));
+        assert_eq!(LogConfig::normalize_level("warn"), Some("warn".into()));
+        assert_eq!(LogConfig::normalize_level("verbose"), None);
+        assert_eq!(LogConfig::normalize_level(""), None);
+    }
+
     #[test]
     fn test_log_config_serde_roundtrip() {
         let config = LogConfig {
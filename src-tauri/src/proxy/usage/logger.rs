@@ -6,8 +6,22 @@ use crate::database::Database;
 use crate::error::AppError;
 use crate::services::usage_stats::find_model_pricing_row;
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use std::{str::FromStr, time::SystemTime};
 
+/// 请求日志记录级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestLogLevel {
+    /// 不记录请求日志
+    Off,
+    /// 只记录元数据（tokens/模型/状态码/耗时），不保存错误正文等内容
+    Metadata,
+    /// 完整记录（默认）
+    #[default]
+    Full,
+}
+
 /// 请求日志
 #[derive(Debug, Clone)]
 pub struct RequestLog {
@@ -42,7 +56,24 @@ impl<'a> UsageLogger<'a> {
     }
 
     /// 记录成功的请求
+    ///
+    /// 按设置中的 `request_log_level` 决定是否记录以及是否保留内容字段
     pub fn log_request(&self, log: &RequestLog) -> Result<(), AppError> {
+        self.log_request_with_level(log, crate::settings::get_request_log_level())
+    }
+
+    fn log_request_with_level(
+        &self,
+        log: &RequestLog,
+        level: RequestLogLevel,
+    ) -> Result<(), AppError> {
+        // 上游错误正文可能回显 prompt 内容，仅完整模式下保存
+        let error_message = match level {
+            RequestLogLevel::Off => return Ok(()),
+            RequestLogLevel::Metadata => None,
+            RequestLogLevel::Full => log.error_message.as_deref(),
+        };
+
         let conn = crate::database::lock_conn!(self.db.conn);
 
         let (input_cost, output_cost, cache_read_cost, cache_creation_cost, total_cost) =
@@ -98,7 +129,7 @@ impl<'a> UsageLogger<'a> {
                 log.latency_ms as i64,
                 log.first_token_ms.map(|v| v as i64),
                 log.status_code as i64,
-                log.error_message,
+                error_message,
                 log.session_id,
                 log.provider_type,
                 log.is_streaming as i64,
@@ -420,4 +451,54 @@ mod tests {
         assert_eq!(error, Some("Internal Server Error".to_string()));
         Ok(())
     }
+    #[test]
+    fn log_level_controls_stored_content() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let logger = UsageLogger::new(&db);
+        let log = |request_id: &str| RequestLog {
+            request_id: request_id.to_string(),
+            provider_id: "provider-1".to_string(),
+            app_type: "claude".to_string(),
+            model: "test-model".to_string(),
+            request_model: "test-model".to_string(),
+            usage: TokenUsage::default(),
+            cost: None,
+            latency_ms: 10,
+            first_token_ms: None,
+            status_code: 400,
+            error_message: Some("prompt echoed by upstream".to_string()),
+            session_id: None,
+            provider_type: None,
+            is_streaming: false,
+            cost_multiplier: "1.0".to_string(),
+        };
+
+        logger.log_request_with_level(&log("req-off"), RequestLogLevel::Off)?;
+        logger.log_request_with_level(&log("req-meta"), RequestLogLevel::Metadata)?;
+        logger.log_request_with_level(&log("req-full"), RequestLogLevel::Full)?;
+
+        let conn = crate::database::lock_conn!(db.conn);
+        let rows: Vec<(String, u16, Option<String>)> = conn
+            .prepare(
+                "SELECT request_id, status_code, error_message FROM proxy_request_logs
+                 ORDER BY request_id",
+            )
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    "req-full".to_string(),
+                    400,
+                    Some("prompt echoed by upstream".to_string())
+                ),
+                ("req-meta".to_string(), 400, None),
+            ]
+        );
+        Ok(())
+    }
 }
@@ -30,12 +30,24 @@
 //! ```
 
 use crate::app_config::{
-    AgentApps, AppType, CommandRepo, DiscoverableAgent, InstallScope, InstalledAgent,
-    UnmanagedAgent,
+    localize_descriptions, AgentApps, AppType, CommandRepo, DiscoverableAgent, DriftSyncReport,
+    InstallScope, InstalledAgent, LocalizedDescriptions, PaginatedDiscovery, SyncAction,
+    SyncReport, UnmanagedAgent,
 };
 use crate::config::get_app_config_dir;
 use crate::database::Database;
+use crate::services::command::{
+    copy_file_if_changed, deserialize_string_or_list, BranchSwitchTarget, CommandService,
+    FileParseIssues, InstallOutcome, ParseWarning, ParseWarningKind,
+};
+use crate::services::config::{ConfigService, ConfigTarget, MultiAppInstallResult};
+use crate::services::env_checker;
 use crate::services::github_api::GitHubApiService;
+use crate::services::repo_metadata::RepoMetadata;
+use crate::services::resource_lock::ResourceLockService;
+use crate::services::resumable_download;
+use crate::services::trash::{TrashService, TrashedResource};
+use crate::services::update::ResourceType;
 use anyhow::{anyhow, Result};
 use regex::Regex;
 use reqwest::Client;
@@ -61,6 +73,18 @@ pub struct AgentMetadata {
     /// 工具列表（支持数组或逗号分隔字符串）
     #[serde(default, deserialize_with = "deserialize_tools_flexible")]
     pub tools: Option<Vec<String>>,
+    /// 运行所需的环境变量（`requires_env: [GITHUB_TOKEN]`，也可写成单个字符串）
+    #[serde(
+        default,
+        rename = "requires_env",
+        alias = "requires-env",
+        alias = "requiresEnv",
+        deserialize_with = "deserialize_string_or_list"
+    )]
+    pub requires_env: Option<Vec<String>>,
+    /// 多语言描述（`description_zh` 等）
+    #[serde(flatten)]
+    pub localized: LocalizedDescriptions,
 }
 
 /// 灵活反序列化 tools 字段
@@ -120,7 +144,9 @@ impl AgentService {
         Self {
             http_client: Client::builder()
                 .user_agent("CC-Switch/3.9")
-                .timeout(std::time::Duration::from_secs(30))
+                .gzip(true)
+                .brotli(true)
+                .deflate(true)
                 .build()
                 .expect("Failed to create HTTP client"),
         }
@@ -133,6 +159,7 @@ impl AgentService {
     /// 返回 `~/.cc-switch/agents/`
     pub fn get_ssot_dir() -> Result<PathBuf> {
         let dir = get_app_config_dir().join("agents");
+        crate::config::validate_ssot_dir(&dir)?;
         if !dir.exists() {
             fs::create_dir_all(&dir)?;
         }
@@ -167,6 +194,20 @@ impl AgentService {
         Ok(agents_dir)
     }
 
+    /// 按安装范围解析 Agent 实际生效的文件
+    ///
+    /// 项目级安装优先使用项目目录中的副本，副本缺失时回退到 SSOT
+    pub fn resolve_installed_path(id: &str, scope: &InstallScope) -> Result<PathBuf> {
+        let relative_path = Self::id_to_relative_path(id);
+        if let InstallScope::Project(project_path) = scope {
+            let project_copy = Self::get_project_agents_dir(project_path)?.join(&relative_path);
+            if project_copy.exists() {
+                return Ok(project_copy);
+            }
+        }
+        Ok(Self::get_ssot_dir()?.join(relative_path))
+    }
+
     /// 检查范围冲突
     pub fn check_scope_conflict(
         db: &Arc<Database>,
@@ -301,9 +342,16 @@ impl AgentService {
     /// 如果 YAML 解析失败（例如 description 中包含未转义的冒号），
     /// 会尝试使用正则表达式进行容错解析。
     pub fn parse_agent_metadata(content: &str) -> Result<AgentMetadata> {
+        Self::parse_agent_metadata_with_warnings(content).map(|(metadata, _)| metadata)
+    }
+
+    /// 解析 Agent 元数据，同时返回容错解析过程中发现的问题
+    pub fn parse_agent_metadata_with_warnings(
+        content: &str,
+    ) -> Result<(AgentMetadata, Vec<ParseWarning>)> {
         // 检查是否以 YAML frontmatter 开始
         if !content.starts_with("---") {
-            return Ok(AgentMetadata::default());
+            return Ok((AgentMetadata::default(), Vec::new()));
         }
 
         // 查找结束标记
@@ -313,16 +361,48 @@ impl AgentService {
 
             // 首先尝试标准 YAML 解析
             match serde_yaml::from_str::<AgentMetadata>(yaml_content) {
-                Ok(metadata) => Ok(metadata),
-                Err(_e) => {
+                Ok(metadata) => Ok((metadata, Vec::new())),
+                Err(e) => {
                     // YAML 解析失败，尝试容错解析
                     // 这通常发生在 description 字段包含未转义的冒号时
-                    Ok(Self::parse_yaml_fallback(yaml_content))
+                    let metadata = Self::parse_yaml_fallback(yaml_content);
+                    let warnings =
+                        CommandService::fallback_warnings(&e, yaml_content, |key| match key {
+                            "name" => metadata.name.is_some(),
+                            "description" => metadata.description.is_some(),
+                            "model" => metadata.model.is_some(),
+                            "tools" => metadata.tools.is_some(),
+                            _ => false,
+                        });
+                    Ok((metadata, warnings))
                 }
             }
         } else {
-            Ok(AgentMetadata::default())
+            Ok((AgentMetadata::default(), Vec::new()))
+        }
+    }
+
+    /// 列出 frontmatter 存在解析问题的已安装 Agents
+    pub fn list_parse_issues(db: &Arc<Database>) -> Result<Vec<FileParseIssues>> {
+        let mut issues = Vec::new();
+
+        for agent in db.get_all_installed_agents()?.into_values() {
+            let scope = InstallScope::from_db(&agent.scope, agent.project_path.as_deref());
+            let path = Self::resolve_installed_path(&agent.id, &scope)?;
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let (_, warnings) = Self::parse_agent_metadata_with_warnings(&content)?;
+            if !warnings.is_empty() {
+                issues.push(FileParseIssues {
+                    id: agent.id,
+                    name: agent.name,
+                    warnings,
+                });
+            }
         }
+
+        Ok(issues)
     }
 
     /// 容错解析 YAML frontmatter
@@ -459,6 +539,28 @@ impl AgentService {
         agent: &DiscoverableAgent,
         current_app: &AppType,
     ) -> Result<InstalledAgent> {
+        self.install_with_warnings(db, agent, current_app)
+            .await
+            .map(|outcome| outcome.installed)
+    }
+
+    /// 安装 Agent，同时返回不阻止安装的问题（frontmatter 解析问题、模型/工具与目标应用不兼容）
+    pub async fn install_with_warnings(
+        &self,
+        db: &Arc<Database>,
+        agent: &DiscoverableAgent,
+        current_app: &AppType,
+    ) -> Result<InstallOutcome<InstalledAgent>> {
+        let installed = db.get_installed_agent(&agent.key)?;
+        CommandService::ensure_repo_installable(
+            db,
+            &agent.repo_owner,
+            &agent.repo_name,
+            installed
+                .as_ref()
+                .and_then(|a| a.repo_owner.as_deref().zip(a.repo_name.as_deref())),
+        )?;
+
         // 下载 Agent 内容
         let content = self.download_agent_content(agent).await?;
 
@@ -474,8 +576,26 @@ impl AgentService {
 
         fs::write(&dest_path, &content)?;
 
-        // 解析元数据
-        let metadata = Self::parse_agent_metadata(&content)?;
+        // 解析元数据（frontmatter 问题不阻止安装，随安装结果返回）
+        let (metadata, parse_warnings) = Self::parse_agent_metadata_with_warnings(&content)?;
+        let mut warnings: Vec<String> = parse_warnings
+            .into_iter()
+            .map(|warning| warning.message)
+            .collect();
+
+        // 兼容性问题同样不阻止安装
+        warnings.extend(
+            Self::check_metadata_compatibility(
+                metadata.model.as_deref().or(agent.model.as_deref()),
+                metadata.tools.as_deref().or(agent.tools.as_deref()),
+                current_app,
+            )
+            .into_iter()
+            .map(|warning| warning.message),
+        );
+        for warning in &warnings {
+            log::warn!("Agent {} 安装警告: {}", agent.key, warning);
+        }
 
         // 从 GitHub 获取 blob SHA（与更新检测使用相同的 hash 算法）
         let file_hash = if let Some(ref source_path) = agent.source_path {
@@ -509,6 +629,9 @@ impl AgentService {
 
         let (namespace, filename) = Self::parse_id(&agent.key);
 
+        // 更新时保留用户备注和自定义显示名称，name 仍以上游元数据为准
+        let existing = db.get_installed_agent(&agent.key)?;
+
         // 创建 InstalledAgent 记录
         let installed_agent = InstalledAgent {
             id: agent.key.clone(),
@@ -524,6 +647,7 @@ impl AgentService {
             filename,
             model: metadata.model.or(agent.model.clone()),
             tools: metadata.tools.or(agent.tools.clone()),
+            requires_env: metadata.requires_env.unwrap_or_default(),
             extra_metadata: None,
             repo_owner: Some(agent.repo_owner.clone()),
             repo_name: Some(agent.repo_name.clone()),
@@ -535,6 +659,8 @@ impl AgentService {
             installed_at: chrono::Utc::now().timestamp(),
             scope: "global".to_string(),
             project_path: None,
+            user_note: existing.as_ref().and_then(|e| e.user_note.clone()),
+            display_name_override: existing.and_then(|e| e.display_name_override),
         };
 
         // 保存到数据库
@@ -548,8 +674,132 @@ impl AgentService {
             installed_agent.name,
             current_app
         );
+        env_checker::warn_missing_env(
+            ResourceType::Agent,
+            &installed_agent.id,
+            &installed_agent.requires_env,
+        );
 
-        Ok(installed_agent)
+        Ok(InstallOutcome {
+            installed: installed_agent,
+            warnings,
+        })
+    }
+
+    /// 一次安装并启用到多个应用
+    ///
+    /// SSOT 只写入一次，之后逐个启用其余应用；不支持 Agent 的应用被跳过，启用失败的应用记录在结果中
+    pub async fn install_for_apps(
+        &self,
+        db: &Arc<Database>,
+        agent: &DiscoverableAgent,
+        apps: Vec<AppType>,
+    ) -> Result<MultiAppInstallResult<InstalledAgent>> {
+        ConfigService::install_for_apps(
+            ResourceType::Agent,
+            apps,
+            |app| async move { self.install(db, agent, &app).await },
+            |installed: &InstalledAgent, app: &AppType| {
+                Self::toggle_app(db, &installed.id, app, true)
+            },
+            |installed: &InstalledAgent| db.get_installed_agent(&installed.id).ok().flatten(),
+        )
+        .await
+    }
+
+    /// 切换已安装 Agent 的来源分支
+    ///
+    /// 先确认文件在新分支上存在，再重新下载到 SSOT、更新 blob SHA 基线，
+    /// 最后同步到所有已启用的应用。用户备注、应用开关等本地状态保持不变
+    pub async fn switch_branch(
+        &self,
+        db: &Arc<Database>,
+        id: &str,
+        new_branch: &str,
+    ) -> Result<InstalledAgent> {
+        let mut installed = db
+            .get_installed_agent(id)?
+            .ok_or_else(|| anyhow!("Agent not found: {}", id))?;
+        let target = BranchSwitchTarget::new(
+            "Agent",
+            id,
+            installed.repo_owner.as_deref(),
+            installed.repo_name.as_deref(),
+            installed.source_path.as_deref(),
+            new_branch,
+        )?;
+        let file_hash = target.fetch_blob_sha(db).await?;
+
+        let discoverable = DiscoverableAgent {
+            key: installed.id.clone(),
+            name: installed.name.clone(),
+            description: installed.description.clone().unwrap_or_default(),
+            namespace: installed.namespace.clone(),
+            filename: installed.filename.clone(),
+            model: installed.model.clone(),
+            tools: installed.tools.clone(),
+            readme_url: installed.readme_url.clone(),
+            repo_owner: target.repo_owner,
+            repo_name: target.repo_name,
+            repo_branch: target.branch.clone(),
+            source_path: Some(target.source_path),
+            localized: Default::default(),
+        };
+        let content = self.download_agent_content(&discoverable).await?;
+        let metadata = Self::parse_agent_metadata(&content)?;
+
+        let dest = Self::get_ssot_dir()?.join(Self::id_to_relative_path(id));
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&dest, &content)?;
+
+        if let Some(name) = metadata.name {
+            installed.name = name;
+        }
+        if metadata.description.is_some() {
+            installed.description = metadata.description;
+        }
+        installed.model = metadata.model.or(installed.model);
+        installed.tools = metadata.tools.or(installed.tools);
+        installed.repo_branch = Some(target.branch.clone());
+        installed.file_hash = Some(file_hash);
+        db.save_agent(&installed)?;
+
+        for (app, enabled) in [
+            (AppType::Claude, installed.apps.claude),
+            (AppType::Codex, installed.apps.codex),
+            (AppType::Gemini, installed.apps.gemini),
+        ] {
+            if enabled {
+                Self::copy_to_app(id, &app)?;
+            }
+        }
+
+        log::info!("Agent {} 已切换到分支 {}", id, target.branch);
+
+        Ok(installed)
+    }
+
+    /// 批量卸载 Agents，返回成功卸载的数量
+    ///
+    /// 目标中有锁定的 Agent 且未传 force 时整批取消；单个卸载失败只记录日志，不中断整批
+    pub fn uninstall_batch(db: &Arc<Database>, ids: &[String], force: bool) -> Result<usize> {
+        ResourceLockService::ensure_unlocked(
+            db,
+            ConfigTarget::Agent,
+            ids.iter().map(String::as_str),
+            force,
+        )?;
+
+        let mut success_count = 0;
+        for id in ids {
+            match Self::uninstall(db, id) {
+                Ok(_) => success_count += 1,
+                Err(e) => log::warn!("卸载 Agent {} 失败: {}", id, e),
+            }
+        }
+        Ok(success_count)
     }
 
     /// 卸载 Agent
@@ -569,12 +819,9 @@ impl AgentService {
             let _ = Self::remove_from_app(id, &app);
         }
 
-        // 从 SSOT 删除
+        // SSOT 文件移入回收站（连同数据库记录归档），支持误删后恢复
         let ssot_dir = Self::get_ssot_dir()?;
-        let agent_path = ssot_dir.join(Self::id_to_relative_path(id));
-        if agent_path.exists() {
-            fs::remove_file(&agent_path)?;
-        }
+        TrashService::move_to_trash(TrashedResource::Agent(agent.clone()))?;
 
         // 清理空的命名空间目录
         if !agent.namespace.is_empty() {
@@ -596,6 +843,30 @@ impl AgentService {
         Ok(())
     }
 
+    /// 设置用户备注（空字符串视为清除）
+    pub fn set_user_note(db: &Arc<Database>, id: &str, note: Option<String>) -> Result<()> {
+        let note = note.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        if !db.update_agent_user_note(id, note.as_deref())? {
+            return Err(anyhow!("Agent not found: {}", id));
+        }
+        Ok(())
+    }
+
+    /// 设置自定义显示名称（空字符串视为恢复上游名称）
+    pub fn set_display_name_override(
+        db: &Arc<Database>,
+        id: &str,
+        display_name: Option<String>,
+    ) -> Result<()> {
+        let display_name = display_name
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        if !db.update_agent_display_name_override(id, display_name.as_deref())? {
+            return Err(anyhow!("Agent not found: {}", id));
+        }
+        Ok(())
+    }
+
     /// 切换应用启用状态
     ///
     /// 启用：复制到应用目录
@@ -629,6 +900,52 @@ impl AgentService {
         Ok(())
     }
 
+    /// 为某个命名空间下的全部 Agents 切换应用启用状态
+    ///
+    /// 只处理该命名空间本身（根命名空间传空字符串），已处于目标状态的 Agent 跳过；
+    /// 单个 Agent 失败时记录日志并继续，返回实际变更的数量
+    pub fn toggle_namespace_for_app(
+        db: &Arc<Database>,
+        namespace: &str,
+        app: &AppType,
+        enabled: bool,
+    ) -> Result<usize> {
+        let ids = Self::namespace_toggle_targets(db, namespace, app, enabled)?;
+
+        let mut changed = 0;
+        for id in &ids {
+            match Self::toggle_app(db, id, app, enabled) {
+                Ok(()) => changed += 1,
+                Err(e) => log::warn!("切换 Agent {} 的 {:?} 状态失败: {}", id, app, e),
+            }
+        }
+
+        log::info!(
+            "命名空间 {} 的 {} 个 Agents 的 {:?} 状态已更新为 {}",
+            namespace,
+            changed,
+            app,
+            enabled
+        );
+
+        Ok(changed)
+    }
+
+    /// 命名空间中需要切换状态的 Agent ID
+    fn namespace_toggle_targets(
+        db: &Arc<Database>,
+        namespace: &str,
+        app: &AppType,
+        enabled: bool,
+    ) -> Result<Vec<String>> {
+        Ok(db
+            .get_agents_by_namespace(namespace)?
+            .into_iter()
+            .filter(|a| a.apps.is_enabled_for(app.as_str()) != enabled)
+            .map(|a| a.id)
+            .collect())
+    }
+
     /// 修改安装范围
     ///
     /// 将资源从一个范围迁移到另一个范围
@@ -968,6 +1285,7 @@ impl AgentService {
                 filename,
                 model: metadata.model,
                 tools: metadata.tools,
+                requires_env: metadata.requires_env.unwrap_or_default(),
                 extra_metadata: None,
                 repo_owner: None,
                 repo_name: None,
@@ -979,6 +1297,8 @@ impl AgentService {
                 installed_at: chrono::Utc::now().timestamp(),
                 scope: "global".to_string(),
                 project_path: None,
+                user_note: None,
+                display_name_override: None,
             };
 
             // 保存到数据库
@@ -991,10 +1311,78 @@ impl AgentService {
         Ok(imported)
     }
 
+    /// 从粘贴的文件内容创建 Agent（不来自仓库）
+    ///
+    /// frontmatter 必须是合法 YAML；ID 未提供时从 frontmatter 的 name 推导。
+    /// ID 已被占用时拒绝，创建后启用到当前应用
+    pub fn create_from_content(
+        db: &Arc<Database>,
+        proposed_id: Option<&str>,
+        content: &str,
+        app: &AppType,
+    ) -> Result<InstalledAgent> {
+        let (metadata, warnings) = Self::parse_agent_metadata_with_warnings(content)?;
+        if let Some(warning) = warnings
+            .iter()
+            .find(|w| w.kind == ParseWarningKind::YamlFallback)
+        {
+            return Err(anyhow!("frontmatter 无效: {}", warning.message));
+        }
+
+        let id = CommandService::resolve_pasted_id(proposed_id, metadata.name.as_deref())?;
+        let dest = Self::get_ssot_dir()?.join(Self::id_to_relative_path(&id));
+        if db.get_installed_agent(&id)?.is_some() || dest.exists() {
+            return Err(anyhow!("Agent {} 已存在", id));
+        }
+
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&dest, content)?;
+
+        let (namespace, filename) = Self::parse_id(&id);
+        let agent = InstalledAgent {
+            id: id.clone(),
+            name: metadata.name.unwrap_or_else(|| filename.clone()),
+            description: metadata.description,
+            namespace,
+            filename,
+            model: metadata.model,
+            tools: metadata.tools,
+            requires_env: metadata.requires_env.unwrap_or_default(),
+            extra_metadata: None,
+            repo_owner: None,
+            repo_name: None,
+            repo_branch: None,
+            readme_url: None,
+            source_path: None,
+            apps: AgentApps::only(app),
+            file_hash: Some(Self::compute_hash(content)),
+            installed_at: chrono::Utc::now().timestamp(),
+            scope: "global".to_string(),
+            project_path: None,
+            user_note: None,
+            display_name_override: None,
+        };
+
+        let created = db
+            .save_agent(&agent)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| Self::copy_to_app(&id, app));
+        if let Err(e) = created {
+            CommandService::discard_created("Agent", &id, &dest, || db.delete_agent(&id));
+            return Err(e);
+        }
+
+        log::info!("已从粘贴内容创建 Agent {}，已启用 {:?}", id, app);
+
+        Ok(agent)
+    }
+
     // ========== 文件同步方法 ==========
 
-    /// 复制 Agent 到应用目录
-    pub fn copy_to_app(id: &str, app: &AppType) -> Result<()> {
+    /// 复制 Agent 到应用目录（内容一致时不写入，返回是否实际写入）
+    pub fn copy_to_app(id: &str, app: &AppType) -> Result<bool> {
         let ssot_dir = Self::get_ssot_dir()?;
         let relative_path = Self::id_to_relative_path(id);
         let source = ssot_dir.join(&relative_path);
@@ -1011,11 +1399,12 @@ impl AgentService {
             fs::create_dir_all(parent)?;
         }
 
-        fs::copy(&source, &dest)?;
-
-        log::debug!("Agent {} 已复制到 {:?}", id, app);
+        let written = copy_file_if_changed(&source, &dest)?;
+        if written {
+            log::debug!("Agent {} 已复制到 {:?}", id, app);
+        }
 
-        Ok(())
+        Ok(written)
     }
 
     /// 从应用目录删除 Agent
@@ -1098,6 +1487,13 @@ impl AgentService {
                 continue;
             }
 
+            // 已知不提供 Agents 的仓库直接跳过（强制刷新时重新扫描以更新标记）
+            if repo.has_agents == Some(false) {
+                log::debug!("跳过不含 Agents 的仓库: {}/{}", repo.owner, repo.name);
+                statuses.push(status(repo, true, None, 0, None));
+                continue;
+            }
+
             // 尝试从缓存获取
             match db.get_cached_agents(&repo.owner, &repo.name, &repo.branch) {
                 Ok(Some(cache)) => {
@@ -1134,8 +1530,11 @@ impl AgentService {
                 .iter()
                 .map(|repo| self.fetch_repo_agents_with_cache(repo, &db_clone));
 
-            let results: Vec<Result<Vec<DiscoverableAgent>>> =
-                futures::future::join_all(fetch_tasks).await;
+            let results: Vec<Result<Vec<DiscoverableAgent>>> = CommandService::run_bounded(
+                fetch_tasks,
+                crate::settings::effective_discovery_concurrency(),
+            )
+            .await;
 
             for (repo, result) in repos_to_fetch.into_iter().zip(results.into_iter()) {
                 match result {
@@ -1156,42 +1555,129 @@ impl AgentService {
         // 去重并排序
         Self::deduplicate_agents(&mut agents);
         agents.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        localize_descriptions(&mut agents);
 
         Ok(agents)
     }
 
+    /// 分页列出可发现的 Agents
+    ///
+    /// 仓库仍完整扫描（复用 `discover_available` 的缓存），仅对结果分页，
+    /// 便于前端增量渲染大型仓库
+    pub async fn discover_available_paged(
+        &self,
+        db: &Arc<Database>,
+        repos: Vec<CommandRepo>,
+        force_refresh: bool,
+        page: Option<u32>,
+        page_size: Option<u32>,
+    ) -> Result<PaginatedDiscovery<DiscoverableAgent>> {
+        let items = self.discover_available(db, repos, force_refresh).await?;
+        Ok(PaginatedDiscovery::from_items(items, page, page_size))
+    }
+
+    /// 导出发现缓存（JSON Lines，每行一个可发现的 Agent）
+    ///
+    /// 只读诊断用途：直接读取缓存表，不触发网络请求。未缓存或已过期的仓库不输出
+    pub fn dump_discovery_cache(db: &Arc<Database>) -> Result<String> {
+        let mut lines = Vec::new();
+
+        for repo in Self::get_repos(db)? {
+            match db.get_cached_agents(&repo.owner, &repo.name, &repo.branch)? {
+                Some(cache) => {
+                    for item in &cache.agents {
+                        lines.push(serde_json::to_string(item)?);
+                    }
+                }
+                None => log::debug!("无有效缓存: {}/{}", repo.owner, repo.name),
+            }
+        }
+
+        Ok(lines.join("\n"))
+    }
+
+    /// 只重新下载并扫描一个仓库，更新其缓存，返回新的 Agents 数量
+    ///
+    /// 与发现流程不同，缓存保存失败时返回错误：用户显式刷新，需要知道缓存是否真的更新了
+    pub async fn refresh_repo_cache(
+        &self,
+        db: &Arc<Database>,
+        repo: &CommandRepo,
+    ) -> Result<usize> {
+        let (agents, metadata) = self.fetch_repo_agents(repo).await?;
+        Self::save_repo_cache(db, repo, &agents, metadata.as_ref())
+            .map_err(|e| anyhow!("保存 Agent 缓存失败: {}/{}: {}", repo.owner, repo.name, e))?;
+        Ok(agents.len())
+    }
+
     /// 从仓库获取 Agents 列表并更新缓存
     async fn fetch_repo_agents_with_cache(
         &self,
         repo: &CommandRepo,
         db: &Arc<Database>,
     ) -> Result<Vec<DiscoverableAgent>> {
-        let agents = self.fetch_repo_agents(repo).await?;
+        let (agents, metadata) = self.fetch_repo_agents(repo).await?;
 
-        // 保存到缓存
-        if let Err(e) = db.save_cached_agents(&repo.owner, &repo.name, &repo.branch, &agents) {
-            log::warn!(
+        match Self::save_repo_cache(db, repo, &agents, metadata.as_ref()) {
+            Ok(()) => log::debug!(
+                "已缓存 Agents: {}/{} ({} 个)",
+                repo.owner,
+                repo.name,
+                agents.len()
+            ),
+            Err(e) => log::warn!(
                 "保存 Agent 缓存失败: {}/{}: {}",
                 repo.owner,
                 repo.name,
                 e
-            );
-        } else {
-            log::debug!(
-                "已缓存 Agents: {}/{} ({} 个)",
+            ),
+        }
+
+        Ok(agents)
+    }
+
+    /// 保存一个仓库的发现结果（按仓库配置的分支）
+    ///
+    /// 资源类型标记与仓库元数据保存失败只记录日志，发现缓存本身的保存错误返回给调用方
+    fn save_repo_cache(
+        db: &Arc<Database>,
+        repo: &CommandRepo,
+        agents: &[DiscoverableAgent],
+        metadata: Option<&RepoMetadata>,
+    ) -> Result<()> {
+        // 记录仓库是否提供 Agents，之后的非强制发现据此跳过
+        if let Err(e) = db.set_command_repo_capability(
+            &repo.owner,
+            &repo.name,
+            ResourceType::Agent,
+            !agents.is_empty(),
+        ) {
+            log::warn!(
+                "保存仓库资源类型标记失败: {}/{}: {}",
                 repo.owner,
                 repo.name,
-                agents.len()
+                e
             );
         }
 
-        Ok(agents)
+        // 仓库级元数据（ccswitch.json）与发现结果一起缓存
+        if let Err(e) = db.save_repo_metadata(&repo.owner, &repo.name, metadata) {
+            log::warn!("保存仓库元数据失败: {}/{}: {}", repo.owner, repo.name, e);
+        }
+
+        db.save_cached_agents(&repo.owner, &repo.name, &repo.branch, agents)?;
+        Ok(())
     }
 
     /// 从仓库获取 Agents 列表（不带缓存）
-    async fn fetch_repo_agents(&self, repo: &CommandRepo) -> Result<Vec<DiscoverableAgent>> {
+    ///
+    /// 同时返回仓库根目录的 `ccswitch.json`（已合并到条目中）
+    async fn fetch_repo_agents(
+        &self,
+        repo: &CommandRepo,
+    ) -> Result<(Vec<DiscoverableAgent>, Option<RepoMetadata>)> {
         let temp_dir = timeout(
-            std::time::Duration::from_secs(60),
+            crate::settings::get_timeout_config().repo_download(),
             self.download_repo(repo),
         )
         .await
@@ -1202,9 +1688,14 @@ impl AgentService {
         // 扫描根目录和子目录
         Self::scan_repo_for_agents(&temp_dir, &temp_dir, repo, &mut agents)?;
 
+        let metadata = RepoMetadata::load(&temp_dir);
+        if let Some(metadata) = &metadata {
+            metadata.apply_to_agents(&mut agents);
+        }
+
         let _ = fs::remove_dir_all(&temp_dir);
 
-        Ok(agents)
+        Ok((agents, metadata))
     }
 
     /// 扫描仓库查找所有 agents 目录中的 agent 文件
@@ -1413,6 +1904,7 @@ impl AgentService {
                     repo_name: repo.name.clone(),
                     repo_branch: repo.branch.clone(),
                     source_path: Some(source_path),
+                    localized: metadata.localized,
                 });
             }
         }
@@ -1421,7 +1913,7 @@ impl AgentService {
     }
 
     /// 下载单个 Agent 内容
-    async fn download_agent_content(&self, agent: &DiscoverableAgent) -> Result<String> {
+    pub(crate) async fn download_agent_content(&self, agent: &DiscoverableAgent) -> Result<String> {
         // 优先使用 source_path（完整仓库路径），否则回退到旧逻辑
         let file_path = agent
             .source_path
@@ -1433,7 +1925,12 @@ impl AgentService {
             agent.repo_owner, agent.repo_name, agent.repo_branch, file_path
         );
 
-        let response = self.http_client.get(&url).send().await?;
+        let response = self
+            .http_client
+            .get(&url)
+            .timeout(crate::settings::get_timeout_config().file_download())
+            .send()
+            .await?;
 
         if !response.status().is_success() {
             return Err(anyhow!(
@@ -1466,18 +1963,14 @@ impl AgentService {
             repo.owner, repo.name, repo.branch
         );
 
-        let response = self.http_client.get(&zip_url).send().await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow!(
-                "下载仓库失败: {}/{} ({})",
-                repo.owner,
-                repo.name,
-                response.status()
-            ));
-        }
-
-        let bytes = response.bytes().await?;
+        let bytes = resumable_download::download_repo_zip(
+            &self.http_client,
+            &zip_url,
+            crate::settings::get_timeout_config().repo_download(),
+            crate::settings::effective_max_download_bytes(),
+        )
+        .await
+        .map_err(|e| anyhow!("下载仓库失败: {}/{} ({})", repo.owner, repo.name, e))?;
 
         // 保存到临时文件
         let zip_path = temp_dir.with_extension("zip");
@@ -1498,7 +1991,13 @@ impl AgentService {
                     let components: Vec<_> = path.components().collect();
                     if components.len() > 1 {
                         let rest: PathBuf = components[1..].iter().collect();
-                        temp_dir.join(rest)
+                        match CommandService::safe_join(&temp_dir, &rest) {
+                            Some(outpath) => outpath,
+                            None => {
+                                log::warn!("跳过越界的压缩包条目: {}", file.name());
+                                continue;
+                            }
+                        }
                     } else {
                         continue; // 跳过根目录
                     }
@@ -1555,13 +2054,14 @@ impl AgentService {
     /// 删除仓库
     pub fn remove_repo(db: &Arc<Database>, owner: &str, name: &str) -> Result<()> {
         db.remove_command_repo(owner, name)?;
+        CommandService::purge_repo_caches(db, owner, name)?;
         Ok(())
     }
 
     // ========== 变更检测与冲突解决 ==========
 
     /// 扫描 SSOT 目录中的所有 .md 文件
-    fn scan_ssot_files(ssot_dir: &Path) -> Result<HashMap<String, PathBuf>> {
+    pub(crate) fn scan_ssot_files(ssot_dir: &Path) -> Result<HashMap<String, PathBuf>> {
         let mut files = HashMap::new();
         Self::scan_dir_recursive(ssot_dir, ssot_dir, &mut files)?;
         Ok(files)
@@ -1714,17 +2214,22 @@ impl AgentService {
                             filename: filename.clone(),
                             model: metadata.model,
                             tools: metadata.tools,
+                            requires_env: metadata.requires_env.unwrap_or_default(),
                             extra_metadata: None,
                             repo_owner: existing.as_ref().and_then(|e| e.repo_owner.clone()),
                             repo_name: existing.as_ref().and_then(|e| e.repo_name.clone()),
                             repo_branch: existing.as_ref().and_then(|e| e.repo_branch.clone()),
                             readme_url: existing.as_ref().and_then(|e| e.readme_url.clone()),
                             source_path: Some(relative_path.to_string_lossy().to_string()),
-                            apps: existing.map(|e| e.apps).unwrap_or_default(),
+                            apps: existing.as_ref().map(|e| e.apps.clone()).unwrap_or_default(),
                             file_hash: Some(file_hash),
                             installed_at: chrono::Utc::now().timestamp(),
                             scope: "global".to_string(),
                             project_path: None,
+                            user_note: existing.as_ref().and_then(|e| e.user_note.clone()),
+                            display_name_override: existing
+                                .as_ref()
+                                .and_then(|e| e.display_name_override.clone()),
                         };
 
                         db.save_agent(&agent)
@@ -1772,17 +2277,22 @@ impl AgentService {
                     filename: filename.clone(),
                     model: metadata.model,
                     tools: metadata.tools,
+                    requires_env: metadata.requires_env.unwrap_or_default(),
                     extra_metadata: None,
                     repo_owner: existing.as_ref().and_then(|e| e.repo_owner.clone()),
                     repo_name: existing.as_ref().and_then(|e| e.repo_name.clone()),
                     repo_branch: existing.as_ref().and_then(|e| e.repo_branch.clone()),
                     readme_url: existing.as_ref().and_then(|e| e.readme_url.clone()),
                     source_path: Some(relative.to_string_lossy().to_string()),
-                    apps: existing.map(|e| e.apps).unwrap_or_default(),
+                    apps: existing.as_ref().map(|e| e.apps.clone()).unwrap_or_default(),
                     file_hash: Some(file_hash),
                     installed_at: chrono::Utc::now().timestamp(),
                     scope: "global".to_string(),
                     project_path: None,
+                    user_note: existing.as_ref().and_then(|e| e.user_note.clone()),
+                    display_name_override: existing
+                        .as_ref()
+                        .and_then(|e| e.display_name_override.clone()),
                 };
 
                 // save_agent 会自动处理插入或更新
@@ -1797,12 +2307,12 @@ impl AgentService {
 
     /// 同步所有已启用的 Agents 到应用目录
     ///
-    /// 确保所有已启用的应用目录与 SSOT 保持一致
-    /// 返回同步的文件数量
-    pub fn sync_all_to_apps(db: &Arc<Database>) -> Result<usize> {
+    /// 确保所有已启用的应用目录与 SSOT 保持一致。单个文件失败（如目录只读）
+    /// 不会中断整批同步，失败项汇总在返回的 `SyncReport` 中
+    pub fn sync_all_to_apps(db: &Arc<Database>) -> Result<SyncReport> {
         let agents = Self::get_all_installed(db)?;
         let ssot_dir = Self::get_ssot_dir()?;
-        let mut synced = 0;
+        let mut report = SyncReport::default();
 
         for agent in agents {
             let relative_path = Self::id_to_relative_path(&agent.id);
@@ -1825,19 +2335,89 @@ impl AgentService {
                 if let Ok(app_dir) = Self::get_app_agents_dir(&app_type) {
                     let app_path = app_dir.join(&relative_path);
 
-                    // 确保父目录存在
-                    if let Some(parent) = app_path.parent() {
-                        fs::create_dir_all(parent)?;
-                    }
+                    // 确保父目录存在后复制文件（内容一致时跳过）
+                    let result = app_path
+                        .parent()
+                        .map_or(Ok(()), fs::create_dir_all)
+                        .map_err(anyhow::Error::from)
+                        .and_then(|_| copy_file_if_changed(&ssot_path, &app_path).map(|_| ()));
+                    report.record(&app_type, &agent.id, result);
+                }
+            }
+        }
 
-                    // 复制文件
-                    fs::copy(&ssot_path, &app_path)?;
-                    synced += 1;
+        Ok(report)
+    }
+
+    /// 只同步内容与 SSOT 不一致的文件（KeepSsot 方向）
+    ///
+    /// `detect_changes` 会扫描整个应用目录，这里只处理已安装且对该应用启用的 Agent，
+    /// 未托管或未启用的文件保持不动
+    pub fn sync_drifted_only(db: &Arc<Database>) -> Result<DriftSyncReport> {
+        let installed: HashMap<String, InstalledAgent> = Self::get_all_installed(db)?
+            .into_iter()
+            .map(|agent| (agent.id.clone(), agent))
+            .collect();
+        let mut report = DriftSyncReport::default();
+
+        for event in Self::detect_changes(db)? {
+            if !matches!(event.event_type, ChangeEventType::AppConflict) {
+                continue;
+            }
+            let Some(app) = event.app.as_deref().and_then(|a| a.parse::<AppType>().ok()) else {
+                continue;
+            };
+            let enabled = installed
+                .get(&event.id)
+                .is_some_and(|agent| agent.apps.is_enabled_for(app.as_str()));
+            if !enabled {
+                continue;
+            }
+
+            let result = Self::resolve_conflict(db, &event.id, &app, ConflictResolution::KeepSsot);
+            report.record(&app, &event.id, result);
+        }
+
+        log::info!(
+            "已同步 {} 个与 SSOT 不一致的 Agent 文件，{} 个失败",
+            report.synced.len(),
+            report.failures.len()
+        );
+        Ok(report)
+    }
+
+    /// 预演 `sync_all_to_apps`：列出每个文件将执行的操作，不写入磁盘
+    pub fn plan_sync(db: &Arc<Database>) -> Result<Vec<SyncAction>> {
+        let agents = Self::get_all_installed(db)?;
+        let ssot_dir = Self::get_ssot_dir()?;
+        let mut actions = Vec::new();
+
+        for agent in agents {
+            let relative_path = Self::id_to_relative_path(&agent.id);
+            let ssot_path = ssot_dir.join(&relative_path);
+
+            for (app_type, enabled) in [
+                (AppType::Claude, agent.apps.claude),
+                (AppType::Codex, agent.apps.codex),
+                (AppType::Gemini, agent.apps.gemini),
+            ] {
+                if !enabled {
+                    continue;
                 }
+                let action = match Self::get_app_agents_dir(&app_type) {
+                    Ok(app_dir) => SyncAction::for_file_copy(
+                        &app_type,
+                        &agent.id,
+                        &ssot_path,
+                        &app_dir.join(&relative_path),
+                    ),
+                    Err(e) => SyncAction::skip(&app_type, &agent.id, e.to_string()),
+                };
+                actions.push(action);
             }
         }
 
-        Ok(synced)
+        Ok(actions)
     }
 }
 
@@ -1877,6 +2457,109 @@ pub enum ConflictResolution {
     KeepApp,
 }
 
+// ========== 兼容性检查 ==========
+
+/// Claude Code 支持的模型别名
+const CLAUDE_AGENT_MODELS: &[&str] = &["sonnet", "opus", "haiku", "inherit"];
+
+/// Claude Code 内置工具（Hook matcher 预览也使用此列表）
+pub(crate) const CLAUDE_AGENT_TOOLS: &[&str] = &[
+    "Bash",
+    "Edit",
+    "Glob",
+    "Grep",
+    "LS",
+    "MultiEdit",
+    "NotebookEdit",
+    "NotebookRead",
+    "Read",
+    "Task",
+    "TodoWrite",
+    "WebFetch",
+    "WebSearch",
+    "Write",
+];
+
+/// 兼容性警告类型
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CompatWarningKind {
+    /// 目标应用不支持 Agents
+    AppUnsupported,
+    /// 目标应用不支持指定的模型
+    UnsupportedModel,
+    /// 目标应用不支持指定的工具
+    UnsupportedTool,
+}
+
+/// 兼容性警告（非致命，仅提示）
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatWarning {
+    pub kind: CompatWarningKind,
+    pub app: String,
+    /// 触发警告的模型或工具名
+    pub value: Option<String>,
+    pub message: String,
+}
+
+impl AgentService {
+    /// 检查 Agent 声明的模型和工具在目标应用中是否可用
+    pub fn check_compatibility(agent: &DiscoverableAgent, app: &AppType) -> Vec<CompatWarning> {
+        Self::check_metadata_compatibility(agent.model.as_deref(), agent.tools.as_deref(), app)
+    }
+
+    fn check_metadata_compatibility(
+        model: Option<&str>,
+        tools: Option<&[String]>,
+        app: &AppType,
+    ) -> Vec<CompatWarning> {
+        let mut warnings = Vec::new();
+
+        let (models, builtin_tools) = match app {
+            AppType::Claude => (CLAUDE_AGENT_MODELS, CLAUDE_AGENT_TOOLS),
+            _ => {
+                warnings.push(CompatWarning {
+                    kind: CompatWarningKind::AppUnsupported,
+                    app: app.as_str().to_string(),
+                    value: None,
+                    message: format!("{} 暂不支持 Agents，安装后不会生效", app.as_str()),
+                });
+                return warnings;
+            }
+        };
+
+        if let Some(model) = model.map(str::trim).filter(|m| !m.is_empty()) {
+            let lower = model.to_lowercase();
+            // 除别名外也接受完整模型 ID（如 claude-sonnet-4-5）
+            if !models.contains(&lower.as_str()) && !lower.starts_with("claude-") {
+                warnings.push(CompatWarning {
+                    kind: CompatWarningKind::UnsupportedModel,
+                    app: app.as_str().to_string(),
+                    value: Some(model.to_string()),
+                    message: format!("{} 不支持模型 {}，将回退到默认模型", app.as_str(), model),
+                });
+            }
+        }
+
+        for tool in tools.unwrap_or_default() {
+            // 形如 Bash(git:*) 的权限写法只校验工具名；MCP 工具由用户配置决定
+            let name = tool.split('(').next().unwrap_or(tool).trim();
+            if name.is_empty() || name.starts_with("mcp__") || builtin_tools.contains(&name) {
+                continue;
+            }
+            warnings.push(CompatWarning {
+                kind: CompatWarningKind::UnsupportedTool,
+                app: app.as_str().to_string(),
+                value: Some(tool.clone()),
+                message: format!("{} 不支持工具 {}，Agent 将无法调用该工具", app.as_str(), tool),
+            });
+        }
+
+        warnings
+    }
+}
+
 /// 检查应用是否支持 Agents 功能
 pub fn check_app_agents_support(app: &AppType) -> bool {
     // 目前只有 Claude Code 确定支持 Agents
@@ -1888,3 +2571,101 @@ pub fn check_app_agents_support(app: &AppType) -> bool {
         AppType::OpenCode | AppType::OpenClaw | AppType::Hermes => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{create_test_agent, TempHome};
+    use serial_test::serial;
+
+    fn tools(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    #[serial]
+    fn batch_uninstall_refuses_locked_agents_unless_forced() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        for id in ["keep", "drop"] {
+            db.save_agent(&create_test_agent(id, "", id)).unwrap();
+        }
+        ResourceLockService::set_locked(&db, ConfigTarget::Agent, "keep", true).unwrap();
+
+        let ids = vec!["drop".to_string(), "keep".to_string()];
+        let err = AgentService::uninstall_batch(&db, &ids, false).unwrap_err();
+        assert!(err.to_string().contains("keep"));
+        assert!(db.get_installed_agent("drop").unwrap().is_some());
+
+        assert!(AgentService::uninstall_batch(&db, &ids, true).is_ok());
+    }
+
+    #[test]
+    fn parse_metadata_reads_localized_descriptions() {
+        let content = "---\nname: reviewer\ndescription: Reviews code\ndescription_zh: 代码审查\ndescription_ja: コードレビュー\n---\n# Reviewer\n";
+        let metadata = AgentService::parse_agent_metadata(content).unwrap();
+        assert_eq!(metadata.description.as_deref(), Some("Reviews code"));
+        assert_eq!(
+            metadata.localized.description_zh.as_deref(),
+            Some("代码审查")
+        );
+        assert_eq!(
+            metadata.localized.description_ja.as_deref(),
+            Some("コードレビュー")
+        );
+        assert!(metadata.localized.description_en.is_none());
+    }
+
+    #[test]
+    fn compatibility_accepts_known_model_and_tools() {
+        let tools = tools(&["Read", "Bash(git:*)", "mcp__github__search"]);
+        let warnings =
+            AgentService::check_metadata_compatibility(Some("opus"), Some(&tools), &AppType::Claude);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn compatibility_warns_unknown_model_and_tool() {
+        let tools = tools(&["Read", "Teleport"]);
+        let warnings =
+            AgentService::check_metadata_compatibility(Some("gpt-4o"), Some(&tools), &AppType::Claude);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].kind, CompatWarningKind::UnsupportedModel);
+        assert_eq!(warnings[1].kind, CompatWarningKind::UnsupportedTool);
+        assert_eq!(warnings[1].value.as_deref(), Some("Teleport"));
+    }
+
+    #[test]
+    fn compatibility_warns_unsupported_app() {
+        let warnings = AgentService::check_metadata_compatibility(None, None, &AppType::Codex);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, CompatWarningKind::AppUnsupported);
+    }
+
+    #[test]
+    #[serial]
+    fn create_from_content_cleans_up_when_enabling_fails() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let content = "---\nname: reviewer\ndescription: Reviews code\n---\n\nBody\n";
+        let dest = AgentService::get_ssot_dir()
+            .unwrap()
+            .join(AgentService::id_to_relative_path("reviewer"));
+
+        // 应用目录被同名文件占用，启用到应用时失败
+        let app_dir = AgentService::get_app_agents_dir(&AppType::Claude).unwrap();
+        fs::create_dir_all(app_dir.parent().unwrap()).unwrap();
+        fs::write(&app_dir, "not a directory").unwrap();
+
+        assert!(AgentService::create_from_content(&db, None, content, &AppType::Claude).is_err());
+        assert!(db.get_installed_agent("reviewer").unwrap().is_none());
+        assert!(!dest.exists());
+
+        fs::remove_file(&app_dir).unwrap();
+        let agent =
+            AgentService::create_from_content(&db, None, content, &AppType::Claude).unwrap();
+        assert_eq!(agent.id, "reviewer");
+        assert!(dest.exists());
+        assert!(app_dir.join("reviewer.md").exists());
+    }
+}
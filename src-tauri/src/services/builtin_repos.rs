@@ -70,10 +70,20 @@ pub struct RestoreResult {
 
 /// 获取内置仓库配置文件的路径
 fn get_builtin_repos_path() -> Result<PathBuf, AppError> {
+    Ok(get_resource_path("builtin-repos.json"))
+}
+
+/// 获取 `resources/` 下内置资源文件的路径
+///
+/// 开发环境优先读取 src-tauri/resources，生产环境读取应用资源目录，
+/// 均不存在时回退到开发路径（由调用方处理文件缺失）
+pub(crate) fn get_resource_path(file_name: &str) -> PathBuf {
     // 开发环境：直接从 src-tauri/resources 目录读取
-    let dev_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("resources/builtin-repos.json");
+    let dev_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("resources")
+        .join(file_name);
     if dev_path.exists() {
-        return Ok(dev_path);
+        return dev_path;
     }
 
     // 生产环境：从应用资源目录读取
@@ -85,10 +95,11 @@ fn get_builtin_repos_path() -> Result<PathBuf, AppError> {
                 .and_then(|p| p.parent()) // Contents
                 .and_then(|p| p.parent()) // .app
             {
-                let resource_path =
-                    bundle_path.join("Contents/Resources/resources/builtin-repos.json");
+                let resource_path = bundle_path
+                    .join("Contents/Resources/resources")
+                    .join(file_name);
                 if resource_path.exists() {
-                    return Ok(resource_path);
+                    return resource_path;
                 }
             }
         }
@@ -98,9 +109,9 @@ fn get_builtin_repos_path() -> Result<PathBuf, AppError> {
     {
         if let Ok(exe_path) = std::env::current_exe() {
             if let Some(exe_dir) = exe_path.parent() {
-                let resource_path = exe_dir.join("resources/builtin-repos.json");
+                let resource_path = exe_dir.join("resources").join(file_name);
                 if resource_path.exists() {
-                    return Ok(resource_path);
+                    return resource_path;
                 }
             }
         }
@@ -110,16 +121,16 @@ fn get_builtin_repos_path() -> Result<PathBuf, AppError> {
     {
         if let Ok(exe_path) = std::env::current_exe() {
             if let Some(exe_dir) = exe_path.parent() {
-                let resource_path = exe_dir.join("resources/builtin-repos.json");
+                let resource_path = exe_dir.join("resources").join(file_name);
                 if resource_path.exists() {
-                    return Ok(resource_path);
+                    return resource_path;
                 }
             }
         }
     }
 
     // 最后回退到开发路径
-    Ok(dev_path)
+    dev_path
 }
 
 /// 加载内置仓库配置
@@ -11,19 +11,29 @@ use regex::Regex;
 use reqwest::Client;
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 use tokio::time::timeout;
 
 use crate::app_config::{
-    AppType, CommandApps, CommandNamespace, CommandRepo, DiscoverableCommand, InstallScope,
-    InstalledCommand, UnmanagedCommand,
+    localize_descriptions, AppType, CommandApps, CommandNamespace, CommandRepo,
+    DiscoverableCommand, DiscoveryResult, DriftSyncReport, InstallScope, InstalledCommand,
+    LocalizedDescriptions, PaginatedDiscovery, RepoDiscoveryStatus, SyncAction, SyncReport,
+    UnmanagedCommand,
 };
 use crate::config::get_app_config_dir;
-use crate::database::Database;
-use crate::services::github_api::GitHubApiService;
+use crate::database::{Database, DiscoveryCachePruneResult};
+use crate::error::AppError;
+use crate::services::config::{ConfigService, ConfigTarget, MultiAppInstallResult};
+use crate::services::env_checker;
+use crate::services::github_api::{GitHubApiError, GitHubApiService};
+use crate::services::repo_metadata::RepoMetadata;
+use crate::services::resource_lock::ResourceLockService;
+use crate::services::resumable_download;
+use crate::services::trash::{TrashService, TrashedResource};
+use crate::services::update::ResourceType;
 
 // ========== 数据结构 ==========
 
@@ -34,12 +44,415 @@ pub struct CommandMetadata {
     pub name: Option<String>,
     pub description: Option<String>,
     pub category: Option<String>,
+    /// 多个分类，可写成 `categories: [git, testing]` 或单个字符串
+    #[serde(default, deserialize_with = "deserialize_string_or_list")]
+    pub categories: Option<Vec<String>>,
     #[serde(default)]
     pub allowed_tools: Option<Vec<String>>,
     #[serde(default)]
     pub mcp_servers: Option<Vec<String>>,
     #[serde(default)]
     pub personas: Option<Vec<String>>,
+    /// 参数提示（Claude `argument-hint`，如 `[pr-number] [priority]`）
+    #[serde(
+        default,
+        rename = "argument-hint",
+        alias = "argumentHint",
+        deserialize_with = "deserialize_argument_hint"
+    )]
+    pub argument_hint: Option<String>,
+    /// 执行该命令使用的模型（Claude `model`）
+    #[serde(default)]
+    pub model: Option<String>,
+    /// 运行所需的环境变量（`requires_env: [GITHUB_TOKEN]`，也可写成单个字符串）
+    #[serde(
+        default,
+        rename = "requires_env",
+        alias = "requires-env",
+        alias = "requiresEnv",
+        deserialize_with = "deserialize_string_or_list"
+    )]
+    pub requires_env: Option<Vec<String>>,
+    /// 其余未识别的字段（如 `allowed-tools`、`disable-model-invocation`），原样保留
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl CommandMetadata {
+    /// 组装存入 `extra_metadata` 的 JSON（键名保持 frontmatter 原样），没有内容时为 None
+    pub fn extra_metadata(&self) -> Option<serde_json::Value> {
+        let mut map = self.extra.clone();
+        if let Some(hint) = &self.argument_hint {
+            map.insert("argument-hint".to_string(), hint.clone().into());
+        }
+        if let Some(model) = &self.model {
+            map.insert("model".to_string(), model.clone().into());
+        }
+        (!map.is_empty()).then_some(serde_json::Value::Object(map))
+    }
+
+    /// 合并旧的单个 `category` 与 `categories` 列表（去空白、去重、保持顺序）
+    pub fn all_categories(&self) -> Vec<String> {
+        let mut result: Vec<String> = Vec::new();
+        for category in self.category.iter().chain(self.categories.iter().flatten()) {
+            let category = category.trim();
+            if !category.is_empty() && !result.iter().any(|c| c == category) {
+                result.push(category.to_string());
+            }
+        }
+        result
+    }
+}
+
+/// 反序列化 `argument-hint`
+///
+/// 未加引号的 `argument-hint: [message]` 会被 YAML 解析为列表，这里还原为 `[message]` 形式
+fn deserialize_argument_hint<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(match value {
+        Some(serde_json::Value::String(hint)) => Some(hint),
+        Some(serde_json::Value::Array(items)) => Some(
+            items
+                .iter()
+                .map(|item| match item {
+                    serde_json::Value::String(s) => format!("[{s}]"),
+                    other => format!("[{other}]"),
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        Some(serde_json::Value::Null) | None => None,
+        Some(other) => Some(other.to_string()),
+    })
+}
+
+/// 反序列化既可以是单个字符串也可以是字符串列表的 YAML 字段
+pub(crate) fn deserialize_string_or_list<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrList {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(
+        Option::<StringOrList>::deserialize(deserializer)?.map(|value| match value {
+            StringOrList::One(item) => vec![item],
+            StringOrList::Many(items) => items,
+        }),
+    )
+}
+
+/// 仅在目标文件与源文件内容不同时复制，返回是否实际写入
+///
+/// 大小不同直接复制；大小相同时再比较内容。内容一致时不写入，
+/// 避免无意义的磁盘写入和 mtime 变化触发编辑器重新加载
+pub(crate) fn copy_file_if_changed(source: &Path, dest: &Path) -> Result<bool> {
+    if let (Ok(src_meta), Ok(dest_meta)) = (fs::metadata(source), fs::metadata(dest)) {
+        if dest_meta.is_file()
+            && src_meta.len() == dest_meta.len()
+            && fs::read(source)? == fs::read(dest)?
+        {
+            return Ok(false);
+        }
+    }
+
+    fs::copy(source, dest)?;
+    Ok(true)
+}
+
+/// 判断 Command 是否属于指定分类（大小写不敏感）
+///
+/// 旧数据/旧缓存可能只有单个 `category`，此时回退比较该字段
+pub fn matches_category(category: Option<&str>, categories: &[String], wanted: &str) -> bool {
+    let wanted = wanted.trim();
+    if categories.is_empty() {
+        return category.is_some_and(|c| c.trim().eq_ignore_ascii_case(wanted));
+    }
+    categories.iter().any(|c| c.eq_ignore_ascii_case(wanted))
+}
+
+/// SSOT 与数据库对账结果（各类变更的数量）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileReport {
+    /// 新纳入管理的 SSOT 文件（数据库中原本没有记录）
+    pub adopted: usize,
+    /// 内容变化后已刷新元数据的记录
+    pub updated: usize,
+    /// 文件已不存在而被删除的记录
+    pub removed: usize,
+}
+
+/// 两个仓库的 Commands 重叠情况（按 key 比较）
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoOverlap {
+    /// 仅存在于仓库 A
+    pub only_a: Vec<String>,
+    /// 仅存在于仓库 B
+    pub only_b: Vec<String>,
+    /// 两边都有但内容不同
+    pub conflicting: Vec<String>,
+    /// 两边都有且内容一致
+    pub identical: Vec<String>,
+    /// 两边都有但内容获取失败、无法比较（格式 "key: 错误"）
+    pub failed: Vec<String>,
+}
+
+/// 按 key 批量安装时单个 key 的结果
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyInstallResult {
+    pub key: String,
+    pub success: bool,
+    /// 安装后的 Command ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 仓库列表导出格式（仅 command_repos，不含资源）
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ReposExport {
+    pub version: u32,
+    pub repos: Vec<CommandRepo>,
+}
+
+/// 当前仓库列表导出格式版本
+const REPOS_EXPORT_VERSION: u32 = 1;
+
+/// 导入仓库列表的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoImportResult {
+    /// 新添加的仓库（owner/name）
+    pub added: Vec<String>,
+    /// 跳过的仓库（内置、已存在或文件内重复）
+    pub skipped: Vec<String>,
+}
+
+/// 应用上已启用 Commands 可调用的工具汇总
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveAllowedTools {
+    /// 所有已启用 Commands 的 allowed_tools 并集
+    pub tools: BTreeSet<String>,
+    /// 其中的高权限工具（执行命令、修改文件或访问网络）
+    pub powerful: BTreeSet<String>,
+}
+
+/// 高权限工具名（`Bash(git add:*)` 这类带参数的写法按括号前的名称匹配）
+const POWERFUL_TOOLS: &[&str] = &[
+    "Bash",
+    "Write",
+    "Edit",
+    "MultiEdit",
+    "NotebookEdit",
+    "WebFetch",
+];
+
+/// 判断工具是否属于高权限工具
+pub fn is_powerful_tool(tool: &str) -> bool {
+    let base = tool.split('(').next().unwrap_or(tool).trim();
+    POWERFUL_TOOLS.iter().any(|t| t.eq_ignore_ascii_case(base))
+}
+
+/// 仓库校验结果（添加仓库前检查仓库、分支与资源目录）
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoValidation {
+    /// 实际校验的分支（未指定时为仓库默认分支）
+    pub branch: String,
+    /// `commands/` 目录下的 .md 文件数
+    pub commands: usize,
+    /// `agents/` 目录下的 .md 文件数
+    pub agents: usize,
+    /// `hooks/` 目录下的 .json 文件数
+    pub hooks: usize,
+    /// 网络不可用、限流等原因未能完成校验时的提示（仓库仍会添加）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+impl RepoValidation {
+    /// 统计仓库文件列表中各类资源的数量
+    ///
+    /// 与发现逻辑一致：资源目录最多位于第 3 层，跳过隐藏目录
+    fn count_resources<'a>(branch: &str, paths: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut result = Self {
+            branch: branch.to_string(),
+            ..Default::default()
+        };
+
+        for path in paths {
+            let segments: Vec<&str> = path.split('/').collect();
+            let Some((file_name, dirs)) = segments.split_last() else {
+                continue;
+            };
+            let Some(pos) = dirs
+                .iter()
+                .take(4)
+                .take_while(|d| !d.starts_with('.'))
+                .position(|d| matches!(*d, "commands" | "agents" | "hooks"))
+            else {
+                continue;
+            };
+
+            match (dirs[pos], file_name.rsplit_once('.').map(|(_, ext)| ext)) {
+                ("commands", Some("md")) => result.commands += 1,
+                ("agents", Some("md")) => result.agents += 1,
+                ("hooks", Some("json")) => result.hooks += 1,
+                _ => {}
+            }
+        }
+
+        result
+    }
+
+    /// 是否找到任何可发现的资源
+    pub fn is_empty(&self) -> bool {
+        self.commands == 0 && self.agents == 0 && self.hooks == 0
+    }
+
+    /// 指定资源类型找到的数量（Skills 不使用这三类目录，恒为 0）
+    pub fn count_of(&self, resource_type: ResourceType) -> usize {
+        match resource_type {
+            ResourceType::Command => self.commands,
+            ResourceType::Agent => self.agents,
+            ResourceType::Hook => self.hooks,
+            ResourceType::Skill => 0,
+        }
+    }
+
+    /// 校验请求失败时的处理
+    ///
+    /// 404 说明仓库或分支确实不存在，直接报错；网络、限流等暂时性问题
+    /// 不阻止添加，只在结果中附带提示
+    fn from_request_error(
+        repo: &CommandRepo,
+        branch: &str,
+        error: GitHubApiError,
+        not_found: impl FnOnce() -> anyhow::Error,
+    ) -> Result<Self> {
+        if matches!(error, GitHubApiError::NotFound) {
+            return Err(not_found());
+        }
+        log::warn!("暂时无法校验仓库 {}/{}: {}", repo.owner, repo.name, error);
+        Ok(Self {
+            branch: branch.to_string(),
+            warning: Some(format!(
+                "暂时无法校验仓库 {}/{}（{}），已直接添加",
+                repo.owner, repo.name, error
+            )),
+            ..Default::default()
+        })
+    }
+}
+
+/// frontmatter 解析问题的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseWarningKind {
+    /// 标准 YAML 解析失败，改用容错解析
+    YamlFallback,
+    /// 容错解析也无法读取的字段
+    UnparsedField,
+}
+
+/// frontmatter 解析问题（解析仍保持宽松，只是把问题暴露出来）
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseWarning {
+    pub kind: ParseWarningKind,
+    /// 相关字段，YAML 整体解析失败时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// 存在解析问题的已安装文件
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileParseIssues {
+    pub id: String,
+    pub name: String,
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// 安装结果及安装过程中发现的非致命问题
+///
+/// 序列化时展开已安装记录的字段，前端可直接当作已安装记录使用
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct InstallOutcome<T> {
+    #[serde(flatten)]
+    pub installed: T,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// 切换分支的目标来源（Commands/Agents/Hooks 共用）
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BranchSwitchTarget {
+    pub branch: String,
+    pub repo_owner: String,
+    pub repo_name: String,
+    pub source_path: String,
+}
+
+impl BranchSwitchTarget {
+    /// 校验新分支名非空，且记录来自仓库（本地导入的资源没有可切换的来源）
+    pub(crate) fn new(
+        kind: &str,
+        id: &str,
+        repo_owner: Option<&str>,
+        repo_name: Option<&str>,
+        source_path: Option<&str>,
+        new_branch: &str,
+    ) -> Result<Self> {
+        let branch = new_branch.trim();
+        if branch.is_empty() {
+            return Err(anyhow!("分支名不能为空"));
+        }
+        let (Some(repo_owner), Some(repo_name), Some(source_path)) =
+            (repo_owner, repo_name, source_path)
+        else {
+            return Err(anyhow!("本地导入的 {} 不支持切换分支: {}", kind, id));
+        };
+
+        Ok(Self {
+            branch: branch.to_string(),
+            repo_owner: repo_owner.to_string(),
+            repo_name: repo_name.to_string(),
+            source_path: source_path.to_string(),
+        })
+    }
+
+    /// 确认文件在新分支上存在，返回新的 blob SHA 作为更新检测基线
+    pub(crate) async fn fetch_blob_sha(&self, db: &Arc<Database>) -> Result<String> {
+        let github_token = db.get_setting("github_pat").ok().flatten();
+        let (file_hash, _size) = GitHubApiService::new(github_token)
+            .get_file_blob_sha(
+                &self.repo_owner,
+                &self.repo_name,
+                &self.branch,
+                &self.source_path,
+            )
+            .await
+            .map_err(|e| anyhow!("分支 {} 上找不到 {}: {}", self.branch, self.source_path, e))?;
+        Ok(file_hash)
+    }
 }
 
 /// 默认仓库配置
@@ -58,8 +471,18 @@ pub fn default_command_repos() -> Vec<CommandRepo> {
 
 // ========== CommandService ==========
 
+/// 仓库 ZIP 下载的默认大小上限（50 MB）
+pub const DEFAULT_MAX_DOWNLOAD_BYTES: u64 = 50 * 1024 * 1024;
+
+/// 两个仓库（owner, name）是否相同（忽略大小写）
+pub(crate) fn is_same_repo(a: (&str, &str), b: (&str, &str)) -> bool {
+    a.0.eq_ignore_ascii_case(b.0) && a.1.eq_ignore_ascii_case(b.1)
+}
+
 pub struct CommandService {
     http_client: Client,
+    /// 单次仓库下载允许的最大字节数；为 None 时使用设置中的上限
+    max_download_bytes: Option<u64>,
 }
 
 impl Default for CommandService {
@@ -71,19 +494,36 @@ impl Default for CommandService {
 impl CommandService {
     pub fn new() -> Self {
         Self {
+            // 请求压缩传输（gzip/deflate/br），响应由 reqwest 透明解压
             http_client: Client::builder()
                 .user_agent("cc-switch")
-                .timeout(std::time::Duration::from_secs(10))
+                .gzip(true)
+                .brotli(true)
+                .deflate(true)
                 .build()
                 .expect("Failed to create HTTP client"),
+            max_download_bytes: None,
         }
     }
 
+    /// 覆盖设置中的仓库下载大小上限
+    pub fn with_max_download_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_download_bytes = Some(max_bytes);
+        self
+    }
+
+    /// 当前生效的仓库下载大小上限
+    fn max_download_bytes(&self) -> u64 {
+        self.max_download_bytes
+            .unwrap_or_else(crate::settings::effective_max_download_bytes)
+    }
+
     // ========== 路径管理 ==========
 
     /// 获取 SSOT 目录（~/.cc-switch/commands/）
     pub fn get_ssot_dir() -> Result<PathBuf> {
         let dir = get_app_config_dir().join("commands");
+        crate::config::validate_ssot_dir(&dir)?;
         fs::create_dir_all(&dir)?;
         Ok(dir)
     }
@@ -131,6 +571,20 @@ impl CommandService {
         Ok(commands_dir)
     }
 
+    /// 按安装范围解析 Command 实际生效的文件
+    ///
+    /// 项目级安装优先使用项目目录中的副本，副本缺失时回退到 SSOT
+    pub fn resolve_installed_path(id: &str, scope: &InstallScope) -> Result<PathBuf> {
+        let relative_path = Self::id_to_relative_path(id);
+        if let InstallScope::Project(project_path) = scope {
+            let project_copy = Self::get_project_commands_dir(project_path)?.join(&relative_path);
+            if project_copy.exists() {
+                return Ok(project_copy);
+            }
+        }
+        Ok(Self::get_ssot_dir()?.join(relative_path))
+    }
+
     /// 根据安装范围获取目标 Commands 目录
     ///
     /// - Global: 使用应用目录（~/.claude/commands/）
@@ -264,6 +718,21 @@ impl CommandService {
         Ok(commands.into_values().collect())
     }
 
+    /// 汇总指定应用上已启用 Commands 声明的 allowed_tools
+    ///
+    /// 用于安全审查：列出这些 Commands 合计可调用的工具
+    pub fn effective_allowed_tools(db: &Arc<Database>, app: &AppType) -> Result<BTreeSet<String>> {
+        let commands = db.get_all_installed_commands()?;
+        Ok(commands
+            .values()
+            .filter(|c| c.apps.is_enabled_for(app))
+            .flat_map(|c| c.allowed_tools.iter().flatten())
+            .map(|tool| tool.trim())
+            .filter(|tool| !tool.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
     /// 获取所有命名空间
     pub fn get_namespaces(db: &Arc<Database>) -> Result<Vec<CommandNamespace>> {
         db.get_command_namespaces()
@@ -283,6 +752,40 @@ impl CommandService {
         command: &DiscoverableCommand,
         current_app: &AppType,
     ) -> Result<InstalledCommand> {
+        self.install_with_warnings(db, command, current_app)
+            .await
+            .map(|outcome| outcome.installed)
+    }
+
+    /// 安装 Command，同时返回不阻止安装的 frontmatter 解析问题
+    pub async fn install_with_warnings(
+        &self,
+        db: &Arc<Database>,
+        command: &DiscoverableCommand,
+        current_app: &AppType,
+    ) -> Result<InstallOutcome<InstalledCommand>> {
+        self.install_in_scope(db, command, current_app, &InstallScope::Global)
+            .await
+    }
+
+    /// 下载到 SSOT 并按安装范围复制：全局范围复制到当前应用目录，项目范围只复制到项目目录
+    async fn install_in_scope(
+        &self,
+        db: &Arc<Database>,
+        command: &DiscoverableCommand,
+        current_app: &AppType,
+        scope: &InstallScope,
+    ) -> Result<InstallOutcome<InstalledCommand>> {
+        let installed = db.get_installed_command(&command.key)?;
+        Self::ensure_repo_installable(
+            db,
+            &command.repo_owner,
+            &command.repo_name,
+            installed
+                .as_ref()
+                .and_then(|c| c.repo_owner.as_deref().zip(c.repo_name.as_deref())),
+        )?;
+
         let ssot_dir = Self::get_ssot_dir()?;
 
         // 计算目标路径
@@ -301,9 +804,16 @@ impl CommandService {
             fs::write(&dest, &content)?;
         }
 
-        // 读取并解析文件
+        // 读取并解析文件（frontmatter 问题不阻止安装，随安装结果返回）
         let content = fs::read_to_string(&dest)?;
-        let metadata = Self::parse_command_metadata(&content)?;
+        let (metadata, parse_warnings) = Self::parse_command_metadata_with_warnings(&content)?;
+        let warnings: Vec<String> = parse_warnings
+            .into_iter()
+            .map(|warning| warning.message)
+            .collect();
+        for warning in &warnings {
+            log::warn!("Command {} frontmatter 问题: {}", command.key, warning);
+        }
 
         // 从 GitHub 获取 blob SHA（与更新检测使用相同的 hash 算法）
         // 如果获取失败则回退到本地计算（但会导致更新检测不准确）
@@ -342,6 +852,18 @@ impl CommandService {
         };
 
         let (namespace, filename) = Self::parse_id(&command.key);
+        let (scope_str, project_path) = scope.to_db();
+
+        // 更新时保留用户备注和自定义显示名称，name 仍以上游元数据为准
+        let existing = db.get_installed_command(&command.key)?;
+
+        let extra_metadata = metadata.extra_metadata();
+
+        // 文件未声明分类时沿用发现阶段的分类
+        let categories = match metadata.all_categories() {
+            categories if categories.is_empty() => command.categories.clone(),
+            categories => categories,
+        };
 
         // 创建 InstalledCommand 记录
         let installed_command = InstalledCommand {
@@ -357,10 +879,12 @@ impl CommandService {
             namespace,
             filename,
             category: metadata.category.or(command.category.clone()),
+            categories,
             allowed_tools: metadata.allowed_tools,
             mcp_servers: metadata.mcp_servers,
             personas: metadata.personas,
-            extra_metadata: None,
+            requires_env: metadata.requires_env.unwrap_or_default(),
+            extra_metadata,
             repo_owner: Some(command.repo_owner.clone()),
             repo_name: Some(command.repo_name.clone()),
             repo_branch: Some(command.repo_branch.clone()),
@@ -369,23 +893,196 @@ impl CommandService {
             apps: CommandApps::only(current_app),
             file_hash: Some(file_hash),
             installed_at: chrono::Utc::now().timestamp(),
-            scope: "global".to_string(),
-            project_path: None,
+            scope: scope_str.to_string(),
+            project_path,
+            user_note: existing.as_ref().and_then(|e| e.user_note.clone()),
+            display_name_override: existing.and_then(|e| e.display_name_override),
         };
 
         // 保存到数据库
         db.save_command(&installed_command)?;
 
-        // 同步到当前应用目录
-        Self::copy_to_app(&command.key, current_app)?;
+        // 全局范围同步到当前应用目录，项目范围只复制到项目目录
+        match scope {
+            InstallScope::Global => {
+                Self::copy_to_app(&command.key, current_app)?;
+            }
+            InstallScope::Project(project_path) => {
+                Self::copy_to_project(&command.key, project_path)?;
+            }
+        }
 
         log::info!(
             "Command {} 安装成功，已启用 {:?}",
             installed_command.name,
             current_app
         );
+        env_checker::warn_missing_env(
+            ResourceType::Command,
+            &installed_command.id,
+            &installed_command.requires_env,
+        );
+
+        Ok(InstallOutcome {
+            installed: installed_command,
+            warnings,
+        })
+    }
+
+    /// 一次安装并启用到多个应用
+    ///
+    /// SSOT 只写入一次，之后逐个启用其余应用；不支持 Command 的应用被跳过，启用失败的应用记录在结果中
+    pub async fn install_for_apps(
+        &self,
+        db: &Arc<Database>,
+        command: &DiscoverableCommand,
+        apps: Vec<AppType>,
+    ) -> Result<MultiAppInstallResult<InstalledCommand>> {
+        ConfigService::install_for_apps(
+            ResourceType::Command,
+            apps,
+            |app| async move { self.install(db, command, &app).await },
+            |installed: &InstalledCommand, app: &AppType| {
+                Self::toggle_app(db, &installed.id, app, true)
+            },
+            |installed: &InstalledCommand| db.get_installed_command(&installed.id).ok().flatten(),
+        )
+        .await
+    }
+
+    /// 从 `cwd` 向上查找项目根目录（包含 `.git` 或 `.claude` 的最近一级目录）
+    ///
+    /// 用户主目录不视为项目根：其下的 `.claude` 是全局配置目录。
+    pub fn detect_current_project(cwd: &Path) -> Option<PathBuf> {
+        let home = crate::config::get_home_dir();
+        cwd.ancestors()
+            .filter(|dir| *dir != home)
+            .find(|dir| dir.join(".git").exists() || dir.join(".claude").is_dir())
+            .map(Path::to_path_buf)
+    }
+
+    /// 安装 Command 到从 `cwd` 检测到的项目（项目范围），省去手动选择项目路径
+    ///
+    /// 直接复制到项目目录，不经过全局安装；已以其他范围安装时拒绝，需先移除原有安装
+    pub async fn install_to_detected_project(
+        &self,
+        db: &Arc<Database>,
+        command: &DiscoverableCommand,
+        cwd: &Path,
+        current_app: &AppType,
+    ) -> Result<InstalledCommand> {
+        // 先检测项目，找不到时不产生任何安装
+        let project = Self::detect_current_project(cwd).ok_or_else(|| {
+            anyhow!(
+                "未能从 {} 向上找到项目根目录（需包含 .git 或 .claude）",
+                cwd.display()
+            )
+        })?;
+
+        let scope = InstallScope::Project(project);
+        Self::check_scope_conflict(db, &command.key, &scope)?;
+
+        self.install_in_scope(db, command, current_app, &scope)
+            .await
+            .map(|outcome| outcome.installed)
+    }
+
+    /// 切换已安装 Command 的来源分支
+    ///
+    /// 先确认文件在新分支上存在，再重新下载到 SSOT、更新 blob SHA 基线，
+    /// 最后同步到所有已启用的应用。用户备注、应用开关等本地状态保持不变
+    pub async fn switch_branch(
+        &self,
+        db: &Arc<Database>,
+        id: &str,
+        new_branch: &str,
+    ) -> Result<InstalledCommand> {
+        let mut installed = db
+            .get_installed_command(id)?
+            .ok_or_else(|| anyhow!("Command not found: {}", id))?;
+        let target = BranchSwitchTarget::new(
+            "Command",
+            id,
+            installed.repo_owner.as_deref(),
+            installed.repo_name.as_deref(),
+            installed.source_path.as_deref(),
+            new_branch,
+        )?;
+        let file_hash = target.fetch_blob_sha(db).await?;
+
+        let discoverable = DiscoverableCommand {
+            key: installed.id.clone(),
+            name: installed.name.clone(),
+            description: installed.description.clone().unwrap_or_default(),
+            namespace: installed.namespace.clone(),
+            filename: installed.filename.clone(),
+            category: installed.category.clone(),
+            categories: installed.categories.clone(),
+            readme_url: installed.readme_url.clone(),
+            repo_owner: target.repo_owner,
+            repo_name: target.repo_name,
+            repo_branch: target.branch.clone(),
+            source_path: Some(target.source_path),
+            localized: Default::default(),
+        };
+        let content = self.download_command_content(&discoverable).await?;
+        let metadata = Self::parse_command_metadata(&content)?;
+
+        let dest = Self::get_ssot_dir()?.join(Self::id_to_relative_path(id));
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&dest, &content)?;
+
+        installed.extra_metadata = metadata.extra_metadata();
+        if let Some(name) = metadata.name {
+            installed.name = name;
+        }
+        if metadata.description.is_some() {
+            installed.description = metadata.description;
+        }
+        let categories = metadata.all_categories();
+        if !categories.is_empty() {
+            installed.categories = categories;
+        }
+        installed.category = metadata.category.or(installed.category);
+        installed.allowed_tools = metadata.allowed_tools;
+        installed.mcp_servers = metadata.mcp_servers;
+        installed.personas = metadata.personas;
+        installed.repo_branch = Some(target.branch.clone());
+        installed.file_hash = Some(file_hash);
+        db.save_command(&installed)?;
+
+        for app in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+            if installed.apps.is_enabled_for(&app) {
+                Self::copy_to_app(id, &app)?;
+            }
+        }
+
+        log::info!("Command {} 已切换到分支 {}", id, target.branch);
 
-        Ok(installed_command)
+        Ok(installed)
+    }
+
+    /// 批量卸载 Commands，返回成功卸载的数量
+    ///
+    /// 目标中有锁定的 Command 且未传 force 时整批取消；单个卸载失败只记录日志，不中断整批
+    pub fn uninstall_batch(db: &Arc<Database>, ids: &[String], force: bool) -> Result<usize> {
+        ResourceLockService::ensure_unlocked(
+            db,
+            ConfigTarget::Command,
+            ids.iter().map(String::as_str),
+            force,
+        )?;
+
+        let mut success_count = 0;
+        for id in ids {
+            match Self::uninstall(db, id) {
+                Ok(_) => success_count += 1,
+                Err(e) => log::warn!("卸载 Command {} 失败: {}", id, e),
+            }
+        }
+        Ok(success_count)
     }
 
     /// 卸载 Command
@@ -405,12 +1102,9 @@ impl CommandService {
             let _ = Self::remove_from_app(id, &app);
         }
 
-        // 从 SSOT 删除
+        // SSOT 文件移入回收站（连同数据库记录归档），支持误删后恢复
         let ssot_dir = Self::get_ssot_dir()?;
-        let command_path = ssot_dir.join(Self::id_to_relative_path(id));
-        if command_path.exists() {
-            fs::remove_file(&command_path)?;
-        }
+        TrashService::move_to_trash(TrashedResource::Command(command.clone()))?;
 
         // 清理空的命名空间目录
         if !command.namespace.is_empty() {
@@ -432,6 +1126,30 @@ impl CommandService {
         Ok(())
     }
 
+    /// 设置用户备注（空字符串视为清除）
+    pub fn set_user_note(db: &Arc<Database>, id: &str, note: Option<String>) -> Result<()> {
+        let note = note.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        if !db.update_command_user_note(id, note.as_deref())? {
+            return Err(anyhow!("Command not found: {}", id));
+        }
+        Ok(())
+    }
+
+    /// 设置自定义显示名称（空字符串视为恢复上游名称）
+    pub fn set_display_name_override(
+        db: &Arc<Database>,
+        id: &str,
+        display_name: Option<String>,
+    ) -> Result<()> {
+        let display_name = display_name
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        if !db.update_command_display_name_override(id, display_name.as_deref())? {
+            return Err(anyhow!("Command not found: {}", id));
+        }
+        Ok(())
+    }
+
     /// 切换应用启用状态
     ///
     /// 启用：复制到应用目录
@@ -465,6 +1183,52 @@ impl CommandService {
         Ok(())
     }
 
+    /// 为某个命名空间下的全部 Commands 切换应用启用状态
+    ///
+    /// 只处理该命名空间本身（根命名空间传空字符串），已处于目标状态的 Command 跳过；
+    /// 单个 Command 失败时记录日志并继续，返回实际变更的数量
+    pub fn toggle_namespace_for_app(
+        db: &Arc<Database>,
+        namespace: &str,
+        app: &AppType,
+        enabled: bool,
+    ) -> Result<usize> {
+        let ids = Self::namespace_toggle_targets(db, namespace, app, enabled)?;
+
+        let mut changed = 0;
+        for id in &ids {
+            match Self::toggle_app(db, id, app, enabled) {
+                Ok(()) => changed += 1,
+                Err(e) => log::warn!("切换 Command {} 的 {:?} 状态失败: {}", id, app, e),
+            }
+        }
+
+        log::info!(
+            "命名空间 {} 的 {} 个 Commands 的 {:?} 状态已更新为 {}",
+            namespace,
+            changed,
+            app,
+            enabled
+        );
+
+        Ok(changed)
+    }
+
+    /// 命名空间中需要切换状态的 Command ID
+    fn namespace_toggle_targets(
+        db: &Arc<Database>,
+        namespace: &str,
+        app: &AppType,
+        enabled: bool,
+    ) -> Result<Vec<String>> {
+        Ok(db
+            .get_commands_by_namespace(namespace)?
+            .into_iter()
+            .filter(|c| c.apps.is_enabled_for(app) != enabled)
+            .map(|c| c.id)
+            .collect())
+    }
+
     /// 修改安装范围
     ///
     /// 将资源从一个范围迁移到另一个范围
@@ -664,6 +1428,7 @@ impl CommandService {
                         name: metadata.name.unwrap_or_else(|| id.clone()),
                         description: metadata.description,
                         found_in: vec![app_str.to_string()],
+                        likely_not_a_command: !Self::looks_like_command(&content),
                     });
             }
         }
@@ -739,17 +1504,20 @@ impl CommandService {
             }
 
             // 创建记录
+            let extra_metadata = metadata.extra_metadata();
             let command = InstalledCommand {
                 id: id.clone(),
                 name: metadata.name.unwrap_or_else(|| filename.clone()),
                 description: metadata.description,
                 namespace,
                 filename,
+                categories: metadata.all_categories(),
                 category: metadata.category,
                 allowed_tools: metadata.allowed_tools,
                 mcp_servers: metadata.mcp_servers,
                 personas: metadata.personas,
-                extra_metadata: None,
+                requires_env: metadata.requires_env.unwrap_or_default(),
+                extra_metadata,
                 repo_owner: None,
                 repo_name: None,
                 repo_branch: None,
@@ -760,6 +1528,8 @@ impl CommandService {
                 installed_at: chrono::Utc::now().timestamp(),
                 scope: "global".to_string(),
                 project_path: None,
+                user_note: None,
+                display_name_override: None,
             };
 
             // 保存到数据库
@@ -772,31 +1542,155 @@ impl CommandService {
         Ok(imported)
     }
 
-    // ========== 文件同步方法 ==========
-
-    /// 复制 Command 到应用目录
-    pub fn copy_to_app(id: &str, app: &AppType) -> Result<()> {
-        let ssot_dir = Self::get_ssot_dir()?;
-        let relative_path = Self::id_to_relative_path(id);
-        let source = ssot_dir.join(&relative_path);
+    /// 将用户输入或 frontmatter name 规范化为合法的资源 ID
+    ///
+    /// 按 `/` 拆分命名空间，每段转为小写、空白替换为 `-`，只保留字母数字、`-`、`_`；
+    /// 空段与 `.`/`..` 被丢弃，全部为空时返回 None（Agents/Hooks 复用）
+    pub(crate) fn sanitize_resource_id(raw: &str) -> Option<String> {
+        let segments: Vec<String> = raw
+            .split(['/', '\\'])
+            .map(|segment| {
+                let mut cleaned = String::new();
+                for c in segment.trim().to_lowercase().chars() {
+                    if c.is_alphanumeric() || c == '_' {
+                        cleaned.push(c);
+                    } else if (c == '-' || c.is_whitespace()) && !cleaned.ends_with('-') {
+                        cleaned.push('-');
+                    }
+                }
+                cleaned.trim_matches('-').to_string()
+            })
+            .filter(|segment| !segment.is_empty())
+            .collect();
 
-        if !source.exists() {
-            return Err(anyhow!("Command 不存在于 SSOT: {}", id));
-        }
+        (!segments.is_empty()).then(|| segments.join("/"))
+    }
 
-        let app_dir = Self::get_app_commands_dir(app)?;
-        let dest = app_dir.join(&relative_path);
+    /// 确定粘贴内容的资源 ID：优先使用传入的 ID，否则取 frontmatter 的 name
+    pub(crate) fn resolve_pasted_id(
+        proposed_id: Option<&str>,
+        name: Option<&str>,
+    ) -> Result<String> {
+        proposed_id
+            .filter(|id| !id.trim().is_empty())
+            .or(name)
+            .and_then(Self::sanitize_resource_id)
+            .ok_or_else(|| anyhow!("无法确定 ID：请填写 ID 或在 frontmatter 中提供 name"))
+    }
 
-        // 确保父目录存在
-        if let Some(parent) = dest.parent() {
-            fs::create_dir_all(parent)?;
+    /// 粘贴创建在写入 SSOT 之后失败时，清理已写入的文件和数据库记录（Agents/Hooks 复用）
+    pub(crate) fn discard_created(
+        kind: &str,
+        id: &str,
+        dest: &Path,
+        delete_row: impl FnOnce() -> std::result::Result<bool, AppError>,
+    ) {
+        if let Err(e) = fs::remove_file(dest) {
+            log::warn!("清理 {kind} {id} 的 SSOT 文件失败: {e}");
+        }
+        if let Err(e) = delete_row() {
+            log::warn!("清理 {kind} {id} 的数据库记录失败: {e}");
         }
-
-        fs::copy(&source, &dest)?;
-
-        log::debug!("Command {} 已复制到 {:?}", id, app);
-
-        Ok(())
+    }
+
+    /// 从粘贴的文件内容创建 Command（不来自仓库）
+    ///
+    /// frontmatter 必须是合法 YAML；ID 未提供时从 frontmatter 的 name 推导。
+    /// ID 已被占用时拒绝，创建后启用到当前应用
+    pub fn create_from_content(
+        db: &Arc<Database>,
+        proposed_id: Option<&str>,
+        content: &str,
+        app: &AppType,
+    ) -> Result<InstalledCommand> {
+        let (metadata, warnings) = Self::parse_command_metadata_with_warnings(content)?;
+        if let Some(warning) = warnings
+            .iter()
+            .find(|w| w.kind == ParseWarningKind::YamlFallback)
+        {
+            return Err(anyhow!("frontmatter 无效: {}", warning.message));
+        }
+
+        let id = Self::resolve_pasted_id(proposed_id, metadata.name.as_deref())?;
+        let dest = Self::get_ssot_dir()?.join(Self::id_to_relative_path(&id));
+        if db.get_installed_command(&id)?.is_some() || dest.exists() {
+            return Err(anyhow!("Command {} 已存在", id));
+        }
+
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&dest, content)?;
+
+        let (namespace, filename) = Self::parse_id(&id);
+        let extra_metadata = metadata.extra_metadata();
+        let command = InstalledCommand {
+            id: id.clone(),
+            name: metadata.name.unwrap_or_else(|| filename.clone()),
+            description: metadata.description,
+            namespace,
+            filename,
+            categories: metadata.all_categories(),
+            category: metadata.category,
+            allowed_tools: metadata.allowed_tools,
+            mcp_servers: metadata.mcp_servers,
+            personas: metadata.personas,
+            requires_env: metadata.requires_env.unwrap_or_default(),
+            extra_metadata,
+            repo_owner: None,
+            repo_name: None,
+            repo_branch: None,
+            readme_url: None,
+            source_path: None,
+            apps: CommandApps::only(app),
+            file_hash: Some(Self::compute_hash(content)),
+            installed_at: chrono::Utc::now().timestamp(),
+            scope: "global".to_string(),
+            project_path: None,
+            user_note: None,
+            display_name_override: None,
+        };
+
+        let created = db
+            .save_command(&command)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| Self::copy_to_app(&id, app));
+        if let Err(e) = created {
+            Self::discard_created("Command", &id, &dest, || db.delete_command(&id));
+            return Err(e);
+        }
+
+        log::info!("已从粘贴内容创建 Command {}，已启用 {:?}", id, app);
+
+        Ok(command)
+    }
+
+    // ========== 文件同步方法 ==========
+
+    /// 复制 Command 到应用目录（内容一致时不写入，返回是否实际写入）
+    pub fn copy_to_app(id: &str, app: &AppType) -> Result<bool> {
+        let ssot_dir = Self::get_ssot_dir()?;
+        let relative_path = Self::id_to_relative_path(id);
+        let source = ssot_dir.join(&relative_path);
+
+        if !source.exists() {
+            return Err(anyhow!("Command 不存在于 SSOT: {}", id));
+        }
+
+        let app_dir = Self::get_app_commands_dir(app)?;
+        let dest = app_dir.join(&relative_path);
+
+        // 确保父目录存在
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let written = copy_file_if_changed(&source, &dest)?;
+        if written {
+            log::debug!("Command {} 已复制到 {:?}", id, app);
+        }
+
+        Ok(written)
     }
 
     /// 从应用目录删除 Command
@@ -841,6 +1735,20 @@ impl CommandService {
 
     /// 列出所有可发现的 Commands（从仓库获取，带缓存支持）
     ///
+    /// 不关心数据来源时使用；需要区分缓存与新获取的结果请用 `discover_available_detailed`
+    pub async fn discover_available(
+        &self,
+        db: &Arc<Database>,
+        repos: Vec<CommandRepo>,
+        force_refresh: bool,
+    ) -> Result<Vec<DiscoverableCommand>> {
+        self.discover_available_detailed(db, repos, force_refresh)
+            .await
+            .map(|result| result.items)
+    }
+
+    /// 列出所有可发现的 Commands，并返回每个仓库的数据来源（缓存/新获取）与扫描时间
+    ///
     /// # 参数
     /// - `db`: 数据库连接，用于缓存查询和存储
     /// - `repos`: 仓库列表
@@ -850,15 +1758,26 @@ impl CommandService {
     /// - 缓存有效期：24小时
     /// - 强制刷新时跳过缓存直接从 GitHub 获取
     /// - 获取成功后更新缓存
-    pub async fn discover_available(
+    pub async fn discover_available_detailed(
         &self,
         db: &Arc<Database>,
         repos: Vec<CommandRepo>,
         force_refresh: bool,
-    ) -> Result<Vec<DiscoverableCommand>> {
+    ) -> Result<DiscoveryResult<DiscoverableCommand>> {
         use crate::database::CACHE_EXPIRY_SECONDS;
 
         let mut commands = Vec::new();
+        let mut statuses = Vec::new();
+        let status =
+            |repo: &CommandRepo, from_cache, scanned_at, count, error| RepoDiscoveryStatus {
+                owner: repo.owner.clone(),
+                name: repo.name.clone(),
+                branch: repo.branch.clone(),
+                from_cache,
+                scanned_at,
+                count,
+                error,
+            };
 
         // 仅使用启用的仓库
         let enabled_repos: Vec<CommandRepo> =
@@ -879,6 +1798,13 @@ impl CommandService {
                 continue;
             }
 
+            // 已知不提供 Commands 的仓库直接跳过（强制刷新时重新扫描以更新标记）
+            if repo.has_commands == Some(false) {
+                log::debug!("跳过不含 Commands 的仓库: {}/{}", repo.owner, repo.name);
+                statuses.push(status(repo, true, None, 0, None));
+                continue;
+            }
+
             // 尝试从缓存获取
             match db.get_cached_commands(&repo.owner, &repo.name, &repo.branch) {
                 Ok(Some(cache)) => {
@@ -891,6 +1817,13 @@ impl CommandService {
                             repo.name,
                             cache.commands.len()
                         );
+                        statuses.push(status(
+                            repo,
+                            true,
+                            Some(cache.scanned_at),
+                            cache.commands.len(),
+                            None,
+                        ));
                         cached_commands.extend(cache.commands);
                     } else {
                         log::debug!("缓存过期: {}/{}", repo.owner, repo.name);
@@ -915,18 +1848,34 @@ impl CommandService {
                 .iter()
                 .map(|repo| self.fetch_repo_commands_with_cache(repo, &db_clone));
 
-            let results: Vec<Result<Vec<DiscoverableCommand>>> =
-                futures::future::join_all(fetch_tasks).await;
+            let results: Vec<Result<Vec<DiscoverableCommand>>> = Self::run_bounded(
+                fetch_tasks,
+                crate::settings::effective_discovery_concurrency(),
+            )
+            .await;
+            let fetched_at = chrono::Utc::now().timestamp();
 
             for (repo, result) in repos_to_fetch.into_iter().zip(results.into_iter()) {
                 match result {
-                    Ok(repo_commands) => commands.extend(repo_commands),
-                    Err(e) => log::warn!(
-                        "获取仓库 {}/{} Commands 失败: {}",
-                        repo.owner,
-                        repo.name,
-                        e
-                    ),
+                    Ok(repo_commands) => {
+                        statuses.push(status(
+                            &repo,
+                            false,
+                            Some(fetched_at),
+                            repo_commands.len(),
+                            None,
+                        ));
+                        commands.extend(repo_commands);
+                    }
+                    Err(e) => {
+                        log::warn!(
+                            "获取仓库 {}/{} Commands 失败: {}",
+                            repo.owner,
+                            repo.name,
+                            e
+                        );
+                        statuses.push(status(&repo, false, None, 0, Some(e.to_string())));
+                    }
                 }
             }
         }
@@ -934,11 +1883,260 @@ impl CommandService {
         // 合并缓存的命令
         commands.extend(cached_commands);
 
+        // 过滤各仓库排除列表中的 key（已安装的不受影响）
+        Self::filter_excluded(&mut commands, &enabled_repos);
+
         // 去重并排序
         Self::deduplicate_commands(&mut commands);
         commands.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        localize_descriptions(&mut commands);
+        statuses.sort_by(|a, b| (&a.owner, &a.name).cmp(&(&b.owner, &b.name)));
 
-        Ok(commands)
+        Ok(DiscoveryResult {
+            items: commands,
+            repos: statuses,
+        })
+    }
+
+    /// 移除仓库排除列表中的 Commands
+    fn filter_excluded(commands: &mut Vec<DiscoverableCommand>, repos: &[CommandRepo]) {
+        let excluded: HashMap<(&str, &str), &[String]> = repos
+            .iter()
+            .filter(|repo| !repo.excluded_keys.is_empty())
+            .map(|repo| {
+                (
+                    (repo.owner.as_str(), repo.name.as_str()),
+                    repo.excluded_keys.as_slice(),
+                )
+            })
+            .collect();
+        if excluded.is_empty() {
+            return;
+        }
+
+        commands.retain(|cmd| {
+            !excluded
+                .get(&(cmd.repo_owner.as_str(), cmd.repo_name.as_str()))
+                .is_some_and(|keys| keys.contains(&cmd.key))
+        });
+    }
+
+    /// 分页列出可发现的 Commands
+    ///
+    /// 仓库仍完整扫描（复用 `discover_available` 的缓存），仅对结果分页，
+    /// 便于前端增量渲染大型仓库
+    pub async fn discover_available_paged(
+        &self,
+        db: &Arc<Database>,
+        repos: Vec<CommandRepo>,
+        force_refresh: bool,
+        page: Option<u32>,
+        page_size: Option<u32>,
+    ) -> Result<PaginatedDiscovery<DiscoverableCommand>> {
+        let items = self.discover_available(db, repos, force_refresh).await?;
+        Ok(PaginatedDiscovery::from_items(items, page, page_size))
+    }
+
+    /// 比较两个仓库中的 Commands，帮助用户在同时启用前了解重叠情况
+    ///
+    /// 复用发现缓存获取列表（不要求仓库已启用），仅对两边都有的 key 下载内容并比较哈希
+    pub async fn compare_repos(
+        &self,
+        db: &Arc<Database>,
+        mut repo_a: CommandRepo,
+        mut repo_b: CommandRepo,
+    ) -> Result<RepoOverlap> {
+        // discover_available 会过滤未启用的仓库，比较时两边都强制视为启用
+        repo_a.enabled = true;
+        repo_b.enabled = true;
+        let list_a = self.discover_available(db, vec![repo_a], false).await?;
+        let list_b = self.discover_available(db, vec![repo_b], false).await?;
+
+        let (mut overlap, shared) = Self::partition_overlap(&list_a, &list_b);
+
+        let hash_tasks = shared.iter().map(|(a, b)| async move {
+            let (content_a, content_b) = futures::future::join(
+                self.download_command_content(a),
+                self.download_command_content(b),
+            )
+            .await;
+            let same = Self::compute_hash(&content_a?) == Self::compute_hash(&content_b?);
+            Ok::<_, anyhow::Error>(same)
+        });
+        let results = futures::future::join_all(hash_tasks).await;
+
+        for ((command, _), same) in shared.into_iter().zip(results) {
+            Self::record_overlap(&mut overlap, &command.key, same);
+        }
+
+        Ok(overlap)
+    }
+
+    /// 按 key 划分两个列表：只在一边的直接写入结果，两边都有的返回待比较的配对
+    fn partition_overlap<'a>(
+        list_a: &'a [DiscoverableCommand],
+        list_b: &'a [DiscoverableCommand],
+    ) -> (
+        RepoOverlap,
+        Vec<(&'a DiscoverableCommand, &'a DiscoverableCommand)>,
+    ) {
+        let map_b: HashMap<&str, &DiscoverableCommand> =
+            list_b.iter().map(|c| (c.key.as_str(), c)).collect();
+        let keys_a: HashSet<&str> = list_a.iter().map(|c| c.key.as_str()).collect();
+
+        let mut overlap = RepoOverlap::default();
+        let mut shared = Vec::new();
+        for command in list_a {
+            match map_b.get(command.key.as_str()) {
+                Some(other) => shared.push((command, *other)),
+                None => overlap.only_a.push(command.key.clone()),
+            }
+        }
+        overlap.only_b = list_b
+            .iter()
+            .filter(|c| !keys_a.contains(c.key.as_str()))
+            .map(|c| c.key.clone())
+            .collect();
+
+        (overlap, shared)
+    }
+
+    /// 记录一个共有 key 的比较结果，内容获取失败时单独报告而不是当作冲突
+    fn record_overlap(overlap: &mut RepoOverlap, key: &str, same: Result<bool>) {
+        match same {
+            Ok(true) => overlap.identical.push(key.to_string()),
+            Ok(false) => overlap.conflicting.push(key.to_string()),
+            Err(e) => {
+                log::warn!("获取 Command {} 内容失败，无法比较: {}", key, e);
+                overlap.failed.push(format!("{key}: {e}"));
+            }
+        }
+    }
+
+    /// 按 key 列表批量安装 Commands（如团队共享的 `sc/agent` 列表）
+    ///
+    /// 每个 key 在已启用仓库的发现结果中查找；同一 key 出现在多个仓库时使用
+    /// `preferred_repo`（`owner/name`），未指定或不在候选中则该 key 报错。
+    /// 空行和 `#` 开头的行被忽略，重复的 key 只安装一次
+    pub async fn install_keys(
+        &self,
+        db: &Arc<Database>,
+        repos: Vec<CommandRepo>,
+        keys: &[String],
+        app: &AppType,
+        preferred_repo: Option<&str>,
+    ) -> Result<Vec<(String, Result<InstalledCommand>)>> {
+        let available = self.discover_available(db, repos, false).await?;
+
+        let mut results = Vec::new();
+        for key in Self::normalize_key_list(keys) {
+            let result = match Self::resolve_key(&available, &key, preferred_repo) {
+                Ok(command) => self.install(db, command, app).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = &result {
+                log::warn!("按 key 安装 Command {} 失败: {}", key, e);
+            }
+            results.push((key, result));
+        }
+
+        Ok(results)
+    }
+
+    /// 整理粘贴的 key 列表：去除空白、空行和 `#` 注释，按首次出现顺序去重
+    fn normalize_key_list(keys: &[String]) -> Vec<String> {
+        let mut seen = HashSet::new();
+        keys.iter()
+            .map(|key| key.trim())
+            .filter(|key| !key.is_empty() && !key.starts_with('#'))
+            .filter(|key| seen.insert(*key))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// 在发现结果中查找 key，多个仓库都有时按 `preferred_repo` 消歧
+    fn resolve_key<'a>(
+        available: &'a [DiscoverableCommand],
+        key: &str,
+        preferred_repo: Option<&str>,
+    ) -> Result<&'a DiscoverableCommand> {
+        let repo_of = |c: &DiscoverableCommand| format!("{}/{}", c.repo_owner, c.repo_name);
+        let candidates: Vec<&DiscoverableCommand> =
+            available.iter().filter(|c| c.key == key).collect();
+
+        match candidates.as_slice() {
+            [] => Err(anyhow!("已启用的仓库中找不到 {}", key)),
+            [only] => Ok(*only),
+            _ => {
+                let preferred = preferred_repo.map(str::trim).and_then(|wanted| {
+                    candidates
+                        .iter()
+                        .find(|c| repo_of(c).eq_ignore_ascii_case(wanted))
+                });
+                match preferred {
+                    Some(command) => Ok(*command),
+                    None => Err(anyhow!(
+                        "{} 存在于多个仓库（{}），请指定要使用的仓库",
+                        key,
+                        candidates
+                            .iter()
+                            .map(|c| repo_of(c))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )),
+                }
+            }
+        }
+    }
+
+    /// 导出发现缓存（JSON Lines，每行一个可发现的 Command）
+    ///
+    /// 只读诊断用途：直接读取缓存表，不触发网络请求。未缓存或已过期的仓库不输出
+    pub fn dump_discovery_cache(db: &Arc<Database>) -> Result<String> {
+        let mut lines = Vec::new();
+
+        for repo in Self::get_repos(db)? {
+            match db.get_cached_commands(&repo.owner, &repo.name, &repo.branch)? {
+                Some(cache) => {
+                    for item in &cache.commands {
+                        lines.push(serde_json::to_string(item)?);
+                    }
+                }
+                None => log::debug!("无有效缓存: {}/{}", repo.owner, repo.name),
+            }
+        }
+
+        Ok(lines.join("\n"))
+    }
+
+    /// 以有限并发执行一组任务，结果顺序与输入一致（Agents/Hooks/Skills 发现复用）
+    ///
+    /// 单个任务失败只体现在它自己的结果中，不会取消其他任务
+    pub(crate) async fn run_bounded<F>(
+        tasks: impl IntoIterator<Item = F>,
+        limit: usize,
+    ) -> Vec<F::Output>
+    where
+        F: std::future::Future,
+    {
+        futures::stream::iter(tasks)
+            .buffered(limit.max(1))
+            .collect()
+            .await
+    }
+
+    /// 只重新下载并扫描一个仓库，更新其缓存，返回新的 Commands 数量
+    ///
+    /// 与发现流程不同，缓存保存失败时返回错误：用户显式刷新，需要知道缓存是否真的更新了
+    pub async fn refresh_repo_cache(
+        &self,
+        db: &Arc<Database>,
+        repo: &CommandRepo,
+    ) -> Result<usize> {
+        let (commands, metadata) = self.fetch_repo_commands(repo).await?;
+        Self::save_repo_cache(db, repo, &commands, metadata.as_ref())
+            .map_err(|e| anyhow!("保存缓存失败: {}/{}: {}", repo.owner, repo.name, e))?;
+        Ok(commands.len())
     }
 
     /// 从仓库获取 Commands 列表并更新缓存
@@ -947,32 +2145,68 @@ impl CommandService {
         repo: &CommandRepo,
         db: &Arc<Database>,
     ) -> Result<Vec<DiscoverableCommand>> {
-        let commands = self.fetch_repo_commands(repo).await?;
+        let (commands, metadata) = self.fetch_repo_commands(repo).await?;
 
-        // 保存到缓存
-        if let Err(e) = db.save_cached_commands(&repo.owner, &repo.name, &repo.branch, &commands) {
-            log::warn!(
+        match Self::save_repo_cache(db, repo, &commands, metadata.as_ref()) {
+            Ok(()) => log::debug!(
+                "已缓存: {}/{} ({} 个命令)",
+                repo.owner,
+                repo.name,
+                commands.len()
+            ),
+            Err(e) => log::warn!(
                 "保存缓存失败: {}/{}: {}",
                 repo.owner,
                 repo.name,
                 e
-            );
-        } else {
-            log::debug!(
-                "已缓存: {}/{} ({} 个命令)",
+            ),
+        }
+
+        Ok(commands)
+    }
+
+    /// 保存一个仓库的发现结果（按仓库配置的分支）
+    ///
+    /// 资源类型标记与仓库元数据保存失败只记录日志，发现缓存本身的保存错误返回给调用方
+    fn save_repo_cache(
+        db: &Arc<Database>,
+        repo: &CommandRepo,
+        commands: &[DiscoverableCommand],
+        metadata: Option<&RepoMetadata>,
+    ) -> Result<()> {
+        // 记录仓库是否提供 Commands，之后的非强制发现据此跳过
+        if let Err(e) = db.set_command_repo_capability(
+            &repo.owner,
+            &repo.name,
+            ResourceType::Command,
+            !commands.is_empty(),
+        ) {
+            log::warn!(
+                "保存仓库资源类型标记失败: {}/{}: {}",
                 repo.owner,
                 repo.name,
-                commands.len()
+                e
             );
         }
 
-        Ok(commands)
+        // 仓库级元数据（ccswitch.json）与发现结果一起缓存
+        if let Err(e) = db.save_repo_metadata(&repo.owner, &repo.name, metadata) {
+            log::warn!("保存仓库元数据失败: {}/{}: {}", repo.owner, repo.name, e);
+        }
+
+        db.save_cached_commands(&repo.owner, &repo.name, &repo.branch, commands)?;
+        Ok(())
     }
 
     /// 从仓库获取 Commands 列表（不带缓存）
-    async fn fetch_repo_commands(&self, repo: &CommandRepo) -> Result<Vec<DiscoverableCommand>> {
+    ///
+    /// 同时返回仓库根目录的 `ccswitch.json`（已合并到条目中）
+    async fn fetch_repo_commands(
+        &self,
+        repo: &CommandRepo,
+    ) -> Result<(Vec<DiscoverableCommand>, Option<RepoMetadata>)> {
         let temp_dir = timeout(
-            std::time::Duration::from_secs(60),
+            crate::settings::get_timeout_config().repo_download(),
             self.download_repo(repo),
         )
         .await
@@ -983,9 +2217,14 @@ impl CommandService {
         // 扫描根目录和子目录
         Self::scan_repo_for_commands(&temp_dir, &temp_dir, repo, &mut commands)?;
 
+        let metadata = RepoMetadata::load(&temp_dir);
+        if let Some(metadata) = &metadata {
+            metadata.apply_to_commands(&mut commands);
+        }
+
         let _ = fs::remove_dir_all(&temp_dir);
 
-        Ok(commands)
+        Ok((commands, metadata))
     }
 
     /// 扫描仓库查找所有 commands 目录中的命令
@@ -1184,6 +2423,7 @@ impl CommandService {
                     description: metadata.description.unwrap_or_default(),
                     namespace: final_namespace,
                     filename: final_filename,
+                    categories: metadata.all_categories(),
                     category: metadata.category,
                     readme_url: Some(format!(
                         "https://github.com/{}/{}/blob/{}/{}",
@@ -1193,6 +2433,7 @@ impl CommandService {
                     repo_name: repo.name.clone(),
                     repo_branch: repo.branch.clone(),
                     source_path: Some(source_path),
+                    localized: LocalizedDescriptions::from_map(&metadata.extra),
                 });
             }
         }
@@ -1201,7 +2442,7 @@ impl CommandService {
     }
 
     /// 下载单个 Command 内容
-    async fn download_command_content(&self, command: &DiscoverableCommand) -> Result<String> {
+    pub(crate) async fn download_command_content(&self, command: &DiscoverableCommand) -> Result<String> {
         // 优先使用 source_path（完整仓库路径），否则回退到旧逻辑
         let file_path = command
             .source_path
@@ -1213,7 +2454,12 @@ impl CommandService {
             command.repo_owner, command.repo_name, command.repo_branch, file_path
         );
 
-        let response = self.http_client.get(&url).send().await?;
+        let response = self
+            .http_client
+            .get(&url)
+            .timeout(crate::settings::get_timeout_config().file_download())
+            .send()
+            .await?;
 
         if !response.status().is_success() {
             return Err(anyhow!(
@@ -1260,12 +2506,19 @@ impl CommandService {
 
     /// 下载并解压 ZIP
     async fn download_and_extract(&self, url: &str, dest: &Path) -> Result<()> {
-        let response = self.http_client.get(url).send().await?;
-        if !response.status().is_success() {
-            return Err(anyhow!("下载失败: HTTP {}", response.status().as_u16()));
-        }
+        let bytes = resumable_download::download_repo_zip(
+            &self.http_client,
+            url,
+            crate::settings::get_timeout_config().repo_download(),
+            self.max_download_bytes(),
+        )
+        .await?;
+
+        Self::extract_zip(&bytes, dest)
+    }
 
-        let bytes = response.bytes().await?;
+    /// 解压 ZIP 到目标目录（去掉压缩包内的根目录前缀）
+    fn extract_zip(bytes: &[u8], dest: &Path) -> Result<()> {
         let cursor = std::io::Cursor::new(bytes);
         let mut archive = zip::ZipArchive::new(cursor)?;
 
@@ -1277,9 +2530,11 @@ impl CommandService {
             return Err(anyhow!("空的 ZIP 文件"));
         };
 
+        fs::create_dir_all(dest)?;
+
         for i in 0..archive.len() {
             let mut file = archive.by_index(i)?;
-            let file_path = file.name();
+            let file_path = file.name().to_string();
 
             let relative_path =
                 if let Some(stripped) = file_path.strip_prefix(&format!("{root_name}/")) {
@@ -1292,7 +2547,10 @@ impl CommandService {
                 continue;
             }
 
-            let outpath = dest.join(relative_path);
+            let Some(outpath) = Self::safe_join(dest, relative_path) else {
+                log::warn!("跳过越界的压缩包条目: {}", file_path);
+                continue;
+            };
 
             if file.is_dir() {
                 fs::create_dir_all(&outpath)?;
@@ -1308,6 +2566,35 @@ impl CommandService {
         Ok(())
     }
 
+    /// 将压缩包条目路径拼接到解压根目录（防止 zip-slip）
+    ///
+    /// 拒绝绝对路径和 `..` 组件，并校验规范化后的路径仍位于根目录内，
+    /// 越界时返回 None。Agents/Hooks/Skills 的解压逻辑同样复用此检查。
+    pub(crate) fn safe_join(root: &Path, relative: impl AsRef<Path>) -> Option<PathBuf> {
+        let relative = relative.as_ref();
+        let is_plain = relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+        if !is_plain {
+            return None;
+        }
+
+        let outpath = root.join(relative);
+        let canonical_root = root.canonicalize().ok()?;
+
+        // 规范化最近的已存在祖先目录，避免通过符号链接逃逸
+        let mut existing = outpath.as_path();
+        while !existing.exists() {
+            existing = existing.parent()?;
+        }
+        let canonical = existing.canonicalize().ok()?;
+        if !canonical.starts_with(&canonical_root) {
+            return None;
+        }
+
+        Some(outpath)
+    }
+
     /// 去重 Commands 列表
     fn deduplicate_commands(commands: &mut Vec<DiscoverableCommand>) {
         let mut seen = HashMap::new();
@@ -1329,23 +2616,167 @@ impl CommandService {
     /// 如果 YAML 解析失败（例如 description 中包含未转义的冒号），
     /// 会尝试使用正则表达式进行容错解析。
     pub fn parse_command_metadata(content: &str) -> Result<CommandMetadata> {
+        Self::parse_command_metadata_with_warnings(content).map(|(metadata, _)| metadata)
+    }
+
+    /// 解析 Command 元数据，同时返回容错解析过程中发现的问题
+    pub fn parse_command_metadata_with_warnings(
+        content: &str,
+    ) -> Result<(CommandMetadata, Vec<ParseWarning>)> {
         let content = content.trim_start_matches('\u{feff}'); // Remove BOM
 
-        let parts: Vec<&str> = content.splitn(3, "---").collect();
-        if parts.len() < 3 {
-            return Ok(CommandMetadata::default());
+        if !Self::has_frontmatter(content) {
+            return Ok((Self::parse_markdown_fallback(content), Vec::new()));
         }
 
+        let parts: Vec<&str> = content.splitn(3, "---").collect();
         let front_matter = parts[1].trim();
 
-        // 首先尝试标准 YAML 解析
-        match serde_yaml::from_str::<CommandMetadata>(front_matter) {
-            Ok(meta) => Ok(meta),
-            Err(_) => {
-                // YAML 解析失败，尝试容错解析
-                Ok(Self::parse_yaml_fallback(front_matter))
+        // 首先尝试标准 YAML 解析，失败时使用容错解析
+        let (mut metadata, warnings) = match serde_yaml::from_str::<CommandMetadata>(front_matter) {
+            Ok(metadata) => (metadata, Vec::new()),
+            Err(e) => {
+                let metadata = Self::parse_yaml_fallback(front_matter);
+                let warnings = Self::fallback_warnings(&e, front_matter, |key| match key {
+                    "name" => metadata.name.is_some(),
+                    "description" => metadata.description.is_some(),
+                    "category" => metadata.category.is_some(),
+                    "categories" => metadata.categories.is_some(),
+                    "argument-hint" => metadata.argument_hint.is_some(),
+                    "model" => metadata.model.is_some(),
+                    _ => false,
+                });
+                (metadata, warnings)
+            }
+        };
+
+        // 只写了 categories 时，用第一个分类填充旧的单个 category 字段
+        if metadata.category.is_none() {
+            metadata.category = metadata.all_categories().into_iter().next();
+        }
+
+        Ok((metadata, warnings))
+    }
+
+    /// 内容是否以 YAML frontmatter 开头（正文中的 `---` 是分隔线，不算）
+    fn has_frontmatter(content: &str) -> bool {
+        let parts: Vec<&str> = content
+            .trim_start_matches('\u{feff}')
+            .splitn(3, "---")
+            .collect();
+        parts.len() == 3 && parts[0].trim().is_empty()
+    }
+
+    /// 文件内容是否像一个 Command
+    ///
+    /// 没有 frontmatter 且没有斜杠命令特征（`$ARGUMENTS`、`$1` 等参数占位符，
+    /// 或 `` !`cmd` `` 形式的命令执行）的 Markdown 多半是用户随手放入的笔记
+    pub(crate) fn looks_like_command(content: &str) -> bool {
+        if Self::has_frontmatter(content) {
+            return true;
+        }
+        let placeholder = Regex::new(r"\$(ARGUMENTS\b|[1-9]\b)").expect("valid regex");
+        placeholder.is_match(content) || content.contains("!`")
+    }
+
+    /// 没有 frontmatter 时，从正文推断元数据
+    ///
+    /// `name` 取第一个一级标题，`description` 取第一个段落（跳过标题与代码块）
+    fn parse_markdown_fallback(content: &str) -> CommandMetadata {
+        let mut name = None;
+        let mut paragraph: Vec<&str> = Vec::new();
+        let mut paragraph_done = false;
+        let mut in_code_block = false;
+
+        for line in content.lines() {
+            if name.is_some() && paragraph_done {
+                break;
+            }
+            let trimmed = line.trim();
+
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_code_block = !in_code_block;
+                paragraph_done |= !paragraph.is_empty();
+                continue;
+            }
+            if in_code_block {
+                continue;
+            }
+
+            if trimmed.starts_with('#') {
+                if let Some(heading) = trimmed.strip_prefix("# ") {
+                    let heading = heading.trim().trim_end_matches('#').trim_end();
+                    if name.is_none() && !heading.is_empty() {
+                        name = Some(heading.to_string());
+                    }
+                }
+                paragraph_done |= !paragraph.is_empty();
+            } else if trimmed.is_empty() {
+                paragraph_done |= !paragraph.is_empty();
+            } else if !paragraph_done {
+                paragraph.push(trimmed);
+            }
+        }
+
+        CommandMetadata {
+            name,
+            description: (!paragraph.is_empty()).then(|| paragraph.join(" ")),
+            ..Default::default()
+        }
+    }
+
+    /// 生成 YAML 解析失败后的问题列表
+    ///
+    /// 第一条说明已改用容错解析，之后每个顶层字段若 `is_parsed` 返回 false，
+    /// 则记录为无法读取的字段（供 Agents 复用）
+    pub(crate) fn fallback_warnings(
+        error: &serde_yaml::Error,
+        yaml_content: &str,
+        is_parsed: impl Fn(&str) -> bool,
+    ) -> Vec<ParseWarning> {
+        let mut warnings = vec![ParseWarning {
+            kind: ParseWarningKind::YamlFallback,
+            field: None,
+            message: format!("YAML 解析失败，已使用容错解析: {error}"),
+        }];
+
+        let mut seen = HashSet::new();
+        let key_re = Regex::new(r"(?m)^([A-Za-z_][\w-]*)\s*:").expect("valid regex");
+        for caps in key_re.captures_iter(yaml_content) {
+            let key = &caps[1];
+            if seen.insert(key.to_string()) && !is_parsed(key) {
+                warnings.push(ParseWarning {
+                    kind: ParseWarningKind::UnparsedField,
+                    field: Some(key.to_string()),
+                    message: format!("无法解析字段 {key}，已忽略"),
+                });
+            }
+        }
+
+        warnings
+    }
+
+    /// 列出 frontmatter 存在解析问题的已安装 Commands
+    pub fn list_parse_issues(db: &Arc<Database>) -> Result<Vec<FileParseIssues>> {
+        let mut issues = Vec::new();
+
+        for command in db.get_all_installed_commands()?.into_values() {
+            let scope = InstallScope::from_db(&command.scope, command.project_path.as_deref());
+            let path = Self::resolve_installed_path(&command.id, &scope)?;
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let (_, warnings) = Self::parse_command_metadata_with_warnings(&content)?;
+            if !warnings.is_empty() {
+                issues.push(FileParseIssues {
+                    id: command.id,
+                    name: command.name,
+                    warnings,
+                });
             }
         }
+
+        Ok(issues)
     }
 
     /// 容错解析 YAML frontmatter
@@ -1362,6 +2793,22 @@ impl CommandService {
             metadata.name = Some(caps[1].trim().to_string());
         }
 
+        // 提取 argument-hint 与 model 字段（单行，去掉引号）
+        for (key, target) in [
+            ("argument-hint", &mut metadata.argument_hint),
+            ("model", &mut metadata.model),
+        ] {
+            if let Some(caps) = Regex::new(&format!(r"(?m)^{}:\s*(.+?)$", regex::escape(key)))
+                .ok()
+                .and_then(|re| re.captures(yaml_content))
+            {
+                let value = caps[1].trim().trim_matches(['"', '\'']).to_string();
+                if !value.is_empty() {
+                    *target = Some(value);
+                }
+            }
+        }
+
         // 提取 category 字段
         if let Some(caps) = Regex::new(r"(?m)^category:\s*(.+?)$")
             .ok()
@@ -1370,10 +2817,50 @@ impl CommandService {
             metadata.category = Some(caps[1].trim().to_string());
         }
 
+        // 提取 categories 字段（行内 `[a, b]` / `a, b` 或块列表）
+        if let Some(start) = Regex::new(r"(?m)^categories:")
+            .ok()
+            .and_then(|re| re.find(yaml_content))
+        {
+            let after_key = &yaml_content[start.end()..];
+            let first_line = after_key.lines().next().unwrap_or("").trim();
+
+            let categories: Vec<String> = if first_line.is_empty() {
+                after_key
+                    .lines()
+                    .skip(1)
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map_while(|line| line.strip_prefix('-').map(|c| c.trim().to_string()))
+                    .filter(|c| !c.is_empty())
+                    .collect()
+            } else {
+                first_line
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .split(',')
+                    .map(|c| c.trim().trim_matches(['"', '\'']).to_string())
+                    .filter(|c| !c.is_empty())
+                    .collect()
+            };
+            if !categories.is_empty() {
+                metadata.categories = Some(categories);
+            }
+        }
+
         // 提取 description 字段（可能包含冒号）
         if let Some(desc_start) = yaml_content.find("description:") {
             let after_key = &yaml_content[desc_start + 12..];
-            let next_field_patterns = ["name:", "category:", "allowed_tools:", "mcp_servers:", "personas:"];
+            let next_field_patterns = [
+                "name:",
+                "category:",
+                "categories:",
+                "allowed_tools:",
+                "mcp_servers:",
+                "personas:",
+                "argument-hint:",
+                "model:",
+            ];
             let mut end_pos = after_key.len();
 
             for pattern in next_field_patterns {
@@ -1500,17 +2987,221 @@ impl CommandService {
             .map_err(|e| anyhow!("获取仓库失败: {}", e))
     }
 
-    /// 添加仓库
-    pub fn add_repo(db: &Arc<Database>, repo: &CommandRepo) -> Result<()> {
-        db.add_command_repo(repo)
+    /// 拒绝从仅浏览的仓库安装新资源
+    ///
+    /// Commands/Agents/Hooks 共用。`installed_repo` 为同 key 已安装资源的来源仓库：
+    /// 来自同一仓库时视为更新或重新安装，不受影响；来自其他仓库或本地导入时按新安装处理
+    pub(crate) fn ensure_repo_installable(
+        db: &Arc<Database>,
+        owner: &str,
+        name: &str,
+        installed_repo: Option<(&str, &str)>,
+    ) -> Result<()> {
+        if installed_repo.is_some_and(|repo| is_same_repo(repo, (owner, name))) {
+            return Ok(());
+        }
+        let browse_only = Self::get_repos(db)?
+            .into_iter()
+            .find(|r| r.browse_only && is_same_repo((&r.owner, &r.name), (owner, name)));
+        if let Some(repo) = browse_only {
+            return Err(anyhow!(
+                "仓库 {}/{} 仅供浏览，不能从中安装；如需安装请先取消仓库的仅浏览标记",
+                repo.owner,
+                repo.name
+            ));
+        }
+        Ok(())
+    }
+
+    /// 查找已添加的仓库（owner/name 忽略大小写）
+    ///
+    /// 供单仓库刷新缓存使用，Commands/Agents/Hooks 共用。
+    /// 指定的分支必须与仓库配置的分支一致，为空时使用配置的分支
+    pub fn find_repo_for_refresh(
+        db: &Arc<Database>,
+        owner: &str,
+        name: &str,
+        branch: &str,
+    ) -> Result<CommandRepo> {
+        let repo = Self::get_repos(db)?
+            .into_iter()
+            .find(|r| r.owner.eq_ignore_ascii_case(owner) && r.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow!("仓库未添加: {owner}/{name}"))?;
+        // 发现流程按仓库配置的分支读取缓存，刷新其他分支的结果不会被读到
+        let branch = branch.trim();
+        if !branch.is_empty() && branch != repo.branch {
+            return Err(anyhow!(
+                "仓库 {owner}/{name} 配置的分支为 {}，不能按分支 {branch} 刷新缓存，请先修改仓库分支",
+                repo.branch
+            ));
+        }
+        Ok(repo)
+    }
+
+    /// 校验仓库：仓库与分支存在，且包含要添加的资源类型
+    ///
+    /// 在添加仓库时调用，让拼写错误或缺少 Token 的私有仓库立即报错，
+    /// 而不是等到发现阶段才得到空列表。离线或限流时校验只作提示，不阻止添加
+    pub async fn validate_repo(
+        db: &Arc<Database>,
+        repo: &CommandRepo,
+        resource_type: ResourceType,
+    ) -> Result<RepoValidation> {
+        let github_token = db.get_setting("github_pat").ok().flatten();
+        let github_api = GitHubApiService::new(github_token);
+        let requested = repo.branch.trim();
+
+        let branch = match github_api.get_default_branch(&repo.owner, &repo.name).await {
+            Ok(_) if !requested.is_empty() => requested.to_string(),
+            Ok(default_branch) => default_branch,
+            Err(e) => {
+                return RepoValidation::from_request_error(repo, requested, e, || {
+                    anyhow!(
+                        "仓库 {}/{} 不存在，或为私有仓库且未配置 GitHub Token",
+                        repo.owner,
+                        repo.name
+                    )
+                })
+            }
+        };
+
+        let tree = match github_api
+            .get_tree(&repo.owner, &repo.name, &branch, "")
+            .await
+        {
+            Ok(tree) => tree,
+            Err(e) => {
+                return RepoValidation::from_request_error(repo, &branch, e, || {
+                    anyhow!(
+                        "分支 {} 在仓库 {}/{} 中不存在",
+                        branch,
+                        repo.owner,
+                        repo.name
+                    )
+                })
+            }
+        };
+
+        let validation = RepoValidation::count_resources(
+            &branch,
+            tree.tree
+                .iter()
+                .filter(|entry| entry.entry_type == "blob")
+                .map(|entry| entry.path.as_str()),
+        );
+
+        if validation.count_of(resource_type) == 0 {
+            return Err(anyhow!(
+                "仓库 {}/{}（分支 {}）中未找到任何 {}",
+                repo.owner,
+                repo.name,
+                branch,
+                resource_type
+            ));
+        }
+
+        Ok(validation)
+    }
+
+    /// 添加仓库
+    pub fn add_repo(db: &Arc<Database>, repo: &CommandRepo) -> Result<()> {
+        db.add_command_repo(repo)
             .map_err(|e| anyhow!("添加仓库失败: {}", e))
     }
 
+    /// 导出仓库列表（JSON），包含分支、启用状态和排除列表等用户设置
+    pub fn export_repos(db: &Arc<Database>) -> Result<String> {
+        let export = ReposExport {
+            version: REPOS_EXPORT_VERSION,
+            repos: Self::get_repos(db)?,
+        };
+        Ok(serde_json::to_string_pretty(&export)?)
+    }
+
+    /// 导入仓库列表
+    ///
+    /// 内置仓库由启动同步维护，导入时跳过；已存在的仓库（owner/name 忽略大小写）
+    /// 和文件内的重复项同样跳过，不覆盖本机设置
+    pub fn import_repos(db: &Arc<Database>, json: &str) -> Result<RepoImportResult> {
+        let export: ReposExport =
+            serde_json::from_str(json).map_err(|e| anyhow!("仓库列表格式无效: {}", e))?;
+        if export.version > REPOS_EXPORT_VERSION {
+            return Err(anyhow!(
+                "仓库列表版本 {} 高于当前支持的版本 {}",
+                export.version,
+                REPOS_EXPORT_VERSION
+            ));
+        }
+
+        let mut seen: HashSet<(String, String)> = Self::get_repos(db)?
+            .iter()
+            .map(|r| (r.owner.to_lowercase(), r.name.to_lowercase()))
+            .collect();
+        let now = chrono::Utc::now().timestamp();
+
+        let mut result = RepoImportResult::default();
+        for mut repo in export.repos {
+            let label = format!("{}/{}", repo.owner, repo.name);
+            if repo.builtin || !seen.insert((repo.owner.to_lowercase(), repo.name.to_lowercase())) {
+                result.skipped.push(label);
+                continue;
+            }
+            repo.added_at = now;
+            Self::add_repo(db, &repo)?;
+            result.added.push(label);
+        }
+
+        Ok(result)
+    }
+
+    /// 将发现结果中的某个 key 加入仓库排除列表，之后发现时不再出现
+    ///
+    /// 与按路径匹配的过滤不同，这里按发现结果的 key 排除；已安装的 Command 保持不变
+    pub fn exclude_from_repo(
+        db: &Arc<Database>,
+        owner: &str,
+        name: &str,
+        key: &str,
+    ) -> Result<bool> {
+        db.set_command_repo_key_excluded(owner, name, key, true)
+            .map_err(|e| anyhow!("排除 {} 失败: {}", key, e))
+    }
+
+    /// 将 key 移出仓库排除列表
+    pub fn unexclude_from_repo(
+        db: &Arc<Database>,
+        owner: &str,
+        name: &str,
+        key: &str,
+    ) -> Result<bool> {
+        db.set_command_repo_key_excluded(owner, name, key, false)
+            .map_err(|e| anyhow!("取消排除 {} 失败: {}", key, e))
+    }
+
     /// 删除仓库
     pub fn remove_repo(db: &Arc<Database>, owner: &str, name: &str) -> Result<()> {
         db.remove_command_repo(owner, name)?;
+        Self::purge_repo_caches(db, owner, name)?;
         Ok(())
     }
+
+    /// 删除仓库在三类资源中的发现缓存及缓存的 ccswitch.json 元数据
+    ///
+    /// Commands/Agents/Hooks 共用 `command_repos`，任一服务删除仓库时都需要调用
+    pub fn purge_repo_caches(
+        db: &Arc<Database>,
+        owner: &str,
+        name: &str,
+    ) -> Result<DiscoveryCachePruneResult> {
+        let result = db.delete_all_repo_caches(owner, name)?;
+        db.save_repo_metadata(owner, name, None)?;
+        Ok(result)
+    }
+
+    /// 清理已删除仓库残留的发现缓存，返回各表删除的行数
+    pub fn prune_orphaned_caches(db: &Arc<Database>) -> Result<DiscoveryCachePruneResult> {
+        Ok(db.prune_orphaned_discovery_caches()?)
+    }
 }
 
 // ========== 变更检测与冲突解决 ==========
@@ -1645,7 +3336,7 @@ impl CommandService {
     }
 
     /// 扫描 SSOT 目录中的所有 .md 文件
-    fn scan_ssot_files(ssot_dir: &Path) -> Result<HashMap<String, PathBuf>> {
+    pub(crate) fn scan_ssot_files(ssot_dir: &Path) -> Result<HashMap<String, PathBuf>> {
         let mut files = HashMap::new();
         Self::scan_dir_recursive(ssot_dir, ssot_dir, &mut files)?;
         Ok(files)
@@ -1720,8 +3411,14 @@ impl CommandService {
                     let file_hash = Self::compute_hash(&content);
 
                     if let Some(mut command) = db.get_installed_command(id)? {
+                        command.extra_metadata =
+                            metadata.extra_metadata().or(command.extra_metadata);
                         command.name = metadata.name.unwrap_or(command.name);
                         command.description = metadata.description.or(command.description);
+                        let categories = metadata.all_categories();
+                        if !categories.is_empty() {
+                            command.categories = categories;
+                        }
                         command.category = metadata.category.or(command.category);
                         command.allowed_tools = metadata.allowed_tools.or(command.allowed_tools);
                         command.mcp_servers = metadata.mcp_servers.or(command.mcp_servers);
@@ -1747,7 +3444,74 @@ impl CommandService {
     /// 重新解析所有已管理的 Command 文件，更新数据库中的元数据和哈希
     pub fn refresh_from_ssot(db: &Arc<Database>) -> Result<usize> {
         let ssot_dir = Self::get_ssot_dir()?;
-        let mut updated_count = 0;
+        let report = Self::refresh_installed(db, &ssot_dir)?;
+        Ok(report.updated)
+    }
+
+    /// 以 SSOT 目录为准对账整个数据库
+    ///
+    /// 在 `refresh_from_ssot` 的基础上，额外把数据库中没有记录的 SSOT 文件
+    /// 作为本地创建的 Command 纳入管理（无仓库来源，默认不启用任何应用），
+    /// 适用于在外部批量编辑 SSOT 目录之后
+    pub fn reconcile_from_ssot(db: &Arc<Database>) -> Result<ReconcileReport> {
+        let ssot_dir = Self::get_ssot_dir()?;
+        Self::reconcile_dir(db, &ssot_dir)
+    }
+
+    fn reconcile_dir(db: &Arc<Database>, ssot_dir: &Path) -> Result<ReconcileReport> {
+        let mut report = Self::refresh_installed(db, ssot_dir)?;
+
+        let installed = db.get_all_installed_commands()?;
+        let mut ssot_files: Vec<_> = Self::scan_ssot_files(ssot_dir)?
+            .into_iter()
+            .filter(|(id, _)| !installed.contains_key(id))
+            .collect();
+        ssot_files.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (id, file_path) in ssot_files {
+            let content = fs::read_to_string(&file_path)?;
+            let metadata = Self::parse_command_metadata(&content)?;
+            let (namespace, filename) = Self::parse_id(&id);
+
+            let extra_metadata = metadata.extra_metadata();
+            let command = InstalledCommand {
+                id: id.clone(),
+                name: metadata.name.unwrap_or_else(|| filename.clone()),
+                description: metadata.description,
+                namespace,
+                filename,
+                categories: metadata.all_categories(),
+                category: metadata.category,
+                allowed_tools: metadata.allowed_tools,
+                mcp_servers: metadata.mcp_servers,
+                personas: metadata.personas,
+                requires_env: metadata.requires_env.unwrap_or_default(),
+                extra_metadata,
+                repo_owner: None,
+                repo_name: None,
+                repo_branch: None,
+                readme_url: None,
+                source_path: None,
+                apps: CommandApps::default(),
+                file_hash: Some(Self::compute_hash(&content)),
+                installed_at: chrono::Utc::now().timestamp(),
+                scope: "global".to_string(),
+                project_path: None,
+                user_note: None,
+                display_name_override: None,
+            };
+
+            db.save_command(&command)?;
+            report.adopted += 1;
+            log::info!("Command {} 已从 SSOT 纳入管理", id);
+        }
+
+        Ok(report)
+    }
+
+    /// 按 SSOT 刷新已有记录：更新内容变化的记录，删除文件已不存在的记录
+    fn refresh_installed(db: &Arc<Database>, ssot_dir: &Path) -> Result<ReconcileReport> {
+        let mut report = ReconcileReport::default();
 
         let commands = db.get_all_installed_commands()?;
 
@@ -1757,6 +3521,7 @@ impl CommandService {
             if !file_path.exists() {
                 // 文件已删除，从数据库移除
                 db.delete_command(&command.id)?;
+                report.removed += 1;
                 log::info!("Command {} 已从数据库移除（文件不存在）", command.id);
                 continue;
             }
@@ -1770,8 +3535,10 @@ impl CommandService {
             if needs_update {
                 let metadata = Self::parse_command_metadata(&content)?;
 
+                command.extra_metadata = metadata.extra_metadata();
                 command.name = metadata.name.unwrap_or(command.filename.clone());
                 command.description = metadata.description;
+                command.categories = metadata.all_categories();
                 command.category = metadata.category;
                 command.allowed_tools = metadata.allowed_tools;
                 command.mcp_servers = metadata.mcp_servers;
@@ -1779,34 +3546,101 @@ impl CommandService {
                 command.file_hash = Some(current_hash);
 
                 db.save_command(&command)?;
-                updated_count += 1;
+                report.updated += 1;
 
                 log::info!("Command {} 已从 SSOT 刷新", command.id);
             }
         }
 
-        Ok(updated_count)
+        Ok(report)
     }
 
     /// 同步所有 Commands 到已启用的应用目录
     ///
-    /// 确保所有已启用的应用目录与 SSOT 保持一致
-    pub fn sync_all_to_apps(db: &Arc<Database>) -> Result<usize> {
+    /// 确保所有已启用的应用目录与 SSOT 保持一致。单个文件失败（如目录只读）
+    /// 不会中断整批同步，失败项汇总在返回的 `SyncReport` 中
+    pub fn sync_all_to_apps(db: &Arc<Database>) -> Result<SyncReport> {
         let commands = db.get_all_installed_commands()?;
-        let mut synced_count = 0;
+        let mut report = SyncReport::default();
 
         for command in commands.values() {
             for app in [AppType::Claude, AppType::Codex, AppType::Gemini] {
                 if command.apps.is_enabled_for(&app) {
-                    if Self::copy_to_app(&command.id, &app).is_ok() {
-                        synced_count += 1;
-                    }
+                    let result = Self::copy_to_app(&command.id, &app).map(|_| ());
+                    report.record(&app, &command.id, result);
                 }
             }
         }
 
-        log::info!("已同步 {} 个 Command 文件到应用目录", synced_count);
-        Ok(synced_count)
+        log::info!(
+            "已同步 {} 个 Command 文件到应用目录，{} 个失败",
+            report.synced,
+            report.failures.len()
+        );
+        Ok(report)
+    }
+
+    /// 只同步内容与 SSOT 不一致的文件（KeepSsot 方向）
+    ///
+    /// 先运行 `detect_changes`，仅覆盖报告为 `AppConflict` 的应用目录文件；
+    /// 与 SSOT 一致的文件保持不动，比 `sync_all_to_apps` 更轻量
+    pub fn sync_drifted_only(db: &Arc<Database>) -> Result<DriftSyncReport> {
+        let mut report = DriftSyncReport::default();
+
+        for (id, app) in Self::drifted_targets(Self::detect_changes(db)?) {
+            let result = Self::resolve_conflict(db, &id, &app, ConflictResolution::KeepSsot);
+            report.record(&app, &id, result);
+        }
+
+        log::info!(
+            "已同步 {} 个与 SSOT 不一致的 Command 文件，{} 个失败",
+            report.synced.len(),
+            report.failures.len()
+        );
+        Ok(report)
+    }
+
+    /// 从变更事件中取出应用目录与 SSOT 不一致的 (id, 应用)
+    fn drifted_targets(events: Vec<ChangeEvent>) -> Vec<(String, AppType)> {
+        events
+            .into_iter()
+            .filter(|e| matches!(e.event_type, ChangeEventType::AppConflict))
+            .filter_map(|e| {
+                let app = e.app.as_deref()?.parse().ok()?;
+                Some((e.id, app))
+            })
+            .collect()
+    }
+
+    /// 预演 `sync_all_to_apps`：列出每个文件将执行的操作，不写入磁盘
+    pub fn plan_sync(db: &Arc<Database>) -> Result<Vec<SyncAction>> {
+        let commands = db.get_all_installed_commands()?;
+        let ssot_dir = Self::get_ssot_dir()?;
+        let mut actions = Vec::new();
+
+        for command in commands.values() {
+            let relative_path = Self::id_to_relative_path(&command.id);
+            let source = ssot_dir.join(&relative_path);
+
+            for app in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+                if !command.apps.is_enabled_for(&app) {
+                    continue;
+                }
+                let action = match Self::get_app_commands_dir(&app) {
+                    Ok(app_dir) => SyncAction::for_file_copy(
+                        &app,
+                        &command.id,
+                        &source,
+                        &app_dir.join(&relative_path),
+                    ),
+                    Err(e) => SyncAction::skip(&app, &command.id, e.to_string()),
+                };
+                actions.push(action);
+            }
+        }
+
+        actions.sort_by(|a, b| a.id.cmp(&b.id).then_with(|| a.app.cmp(&b.app)));
+        Ok(actions)
     }
 }
 
@@ -1823,3 +3657,1133 @@ pub fn check_app_commands_support(app: &AppType) -> bool {
         AppType::OpenCode | AppType::OpenClaw | AppType::Hermes => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::SyncFailureKind;
+    use crate::test_support::{create_test_command, TempHome};
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn batch_uninstall_refuses_locked_commands_unless_forced() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        for id in ["keep", "drop"] {
+            db.save_command(&create_test_command(id, "", id)).unwrap();
+        }
+        ResourceLockService::set_locked(&db, ConfigTarget::Command, "keep", true).unwrap();
+
+        let ids = vec!["drop".to_string(), "keep".to_string()];
+        let err = CommandService::uninstall_batch(&db, &ids, false).unwrap_err();
+        assert!(err.to_string().contains("keep"));
+        // 整批取消，未锁定的也保留
+        assert!(db.get_installed_command("drop").unwrap().is_some());
+
+        assert!(CommandService::uninstall_batch(&db, &ids, true).is_ok());
+    }
+
+    #[test]
+    fn detect_current_project_walks_up_to_nearest_marker() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().join("repo");
+        let nested = root.join("src").join("deep");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+
+        let detected = CommandService::detect_current_project(&nested);
+        assert_eq!(detected.as_deref(), Some(root.as_path()));
+
+        let sub = root.join("packages").join("app");
+        fs::create_dir_all(sub.join(".claude")).unwrap();
+        let detected = CommandService::detect_current_project(&sub);
+        assert_eq!(detected.as_deref(), Some(sub.as_path()));
+    }
+
+    #[test]
+    fn safe_join_rejects_parent_and_absolute_paths() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+
+        assert!(CommandService::safe_join(root, "sc/agent.md").is_some());
+        assert!(CommandService::safe_join(root, "../evil.md").is_none());
+        assert!(CommandService::safe_join(root, "sc/../../evil.md").is_none());
+        assert!(CommandService::safe_join(root, "/etc/passwd").is_none());
+    }
+
+    #[test]
+    fn extract_zip_skips_entries_escaping_destination() {
+        use std::io::Write;
+
+        let mut buf = std::io::Cursor::new(Vec::new());
+        {
+            let mut writer = zip::ZipWriter::new(&mut buf);
+            let options = zip::write::SimpleFileOptions::default();
+            writer.start_file("repo-main/commands/ok.md", options).unwrap();
+            writer.write_all(b"# ok").unwrap();
+            writer
+                .start_file("repo-main/../../evil.md", options)
+                .unwrap();
+            writer.write_all(b"# evil").unwrap();
+            writer.finish().unwrap();
+        }
+
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("a").join("b");
+        CommandService::extract_zip(buf.get_ref(), &dest).unwrap();
+
+        assert!(dest.join("commands/ok.md").exists());
+        assert!(!temp.path().join("a").join("evil.md").exists());
+        assert!(!temp.path().join("evil.md").exists());
+    }
+
+    #[tokio::test]
+    async fn download_and_extract_decompresses_gzip_transparently() {
+        use std::io::Write;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let content = "---\ndescription: hello\n---\n# 你好\r\n".as_bytes();
+        let mut zip_bytes = std::io::Cursor::new(Vec::new());
+        {
+            let mut writer = zip::ZipWriter::new(&mut zip_bytes);
+            let options = zip::write::SimpleFileOptions::default();
+            writer
+                .start_file("repo-main/commands/hello.md", options)
+                .unwrap();
+            writer.write_all(content).unwrap();
+            writer.finish().unwrap();
+        }
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(zip_bytes.get_ref()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 4096];
+            let n = socket.read(&mut request).await.unwrap();
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/zip\r\n\
+                 Content-Encoding: gzip\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n",
+                gzipped.len()
+            );
+            socket.write_all(header.as_bytes()).await.unwrap();
+            socket.write_all(&gzipped).await.unwrap();
+            String::from_utf8_lossy(&request[..n]).to_ascii_lowercase()
+        });
+
+        let temp = tempfile::tempdir().unwrap();
+        CommandService::new()
+            .download_and_extract(&format!("http://{addr}/repo.zip"), temp.path())
+            .await
+            .unwrap();
+
+        let request = server.await.unwrap();
+        assert!(request.contains("accept-encoding:"));
+        assert!(request.contains("gzip"));
+        assert_eq!(
+            fs::read(temp.path().join("commands/hello.md")).unwrap(),
+            content
+        );
+    }
+
+    #[test]
+    fn drifted_targets_keeps_only_app_conflicts() {
+        let event = |id: &str, event_type, app: Option<&str>| ChangeEvent {
+            id: id.to_string(),
+            event_type,
+            app: app.map(|a| a.to_string()),
+            details: None,
+        };
+        let targets = CommandService::drifted_targets(vec![
+            event("a", ChangeEventType::AppConflict, Some("claude")),
+            event("b", ChangeEventType::SsotModified, None),
+            event("c", ChangeEventType::AppConflict, Some("unknown")),
+            event("d", ChangeEventType::AppConflict, Some("codex")),
+        ]);
+
+        assert_eq!(
+            targets,
+            vec![
+                ("a".to_string(), AppType::Claude),
+                ("d".to_string(), AppType::Codex)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn run_bounded_caps_in_flight_tasks_and_keeps_order() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let tasks = (0..20).map(|i| {
+            let (in_flight, peak) = (&in_flight, &peak);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                if i % 3 == 0 {
+                    Err(anyhow!("repo {i} failed"))
+                } else {
+                    Ok(i)
+                }
+            }
+        });
+
+        let results = CommandService::run_bounded(tasks, 4).await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 4);
+        assert_eq!(results.len(), 20);
+        // 失败的仓库不影响其他仓库，结果顺序与输入一致
+        assert!(results[0].is_err());
+        assert_eq!(results[1].as_ref().unwrap(), &1);
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 13);
+    }
+
+    #[test]
+    fn with_max_download_bytes_overrides_setting() {
+        let service = CommandService::new();
+        assert_eq!(service.max_download_bytes, None);
+        let service = service.with_max_download_bytes(1024);
+        assert_eq!(service.max_download_bytes(), 1024);
+    }
+
+    #[test]
+    fn parse_metadata_without_frontmatter_uses_heading_and_first_paragraph() {
+        let content = "# Review PR  #\n\nReview the current pull request\nand leave comments.\n\n\
+                       ## Steps\n\nSecond paragraph.";
+        let metadata = CommandService::parse_command_metadata(content).unwrap();
+
+        assert_eq!(metadata.name.as_deref(), Some("Review PR"));
+        assert_eq!(
+            metadata.description.as_deref(),
+            Some("Review the current pull request and leave comments.")
+        );
+    }
+
+    #[test]
+    fn parse_metadata_without_frontmatter_skips_code_blocks_and_rules() {
+        // 代码块中的 `# ...` 是 shell 注释；正文中的 `---` 是分隔线而非 frontmatter
+        let content = "```bash\n# not a title\n```\n\nRun the linter.\n\n---\n\n# Lint\n\n---\n";
+        let metadata = CommandService::parse_command_metadata(content).unwrap();
+
+        assert_eq!(metadata.name.as_deref(), Some("Lint"));
+        assert_eq!(metadata.description.as_deref(), Some("Run the linter."));
+
+        let empty = CommandService::parse_command_metadata("just text").unwrap();
+        assert_eq!(empty.name, None);
+        assert_eq!(empty.description.as_deref(), Some("just text"));
+    }
+
+    #[test]
+    fn looks_like_command_flags_plain_notes() {
+        assert!(CommandService::looks_like_command(
+            "---\ndescription: Commit changes\n---\nCommit the staged files."
+        ));
+        assert!(CommandService::looks_like_command(
+            "Fix issue #$ARGUMENTS following our coding standards."
+        ));
+        assert!(CommandService::looks_like_command(
+            "Review PR $1 with priority $2."
+        ));
+        assert!(CommandService::looks_like_command(
+            "## Context\n\n- Current status: !`git status`"
+        ));
+
+        assert!(!CommandService::looks_like_command(
+            "# Meeting notes\n\n- buy milk\n- costs $10\n\n---\n\nTODO"
+        ));
+        assert!(!CommandService::looks_like_command(""));
+    }
+
+    #[test]
+    fn parse_metadata_maps_single_category_into_list() {
+        let content = "---\nname: commit\ncategory: git\n---\n# body";
+        let metadata = CommandService::parse_command_metadata(content).unwrap();
+
+        assert_eq!(metadata.category.as_deref(), Some("git"));
+        assert_eq!(metadata.all_categories(), vec!["git".to_string()]);
+    }
+
+    #[test]
+    fn parse_metadata_accepts_categories_list() {
+        let content = "---\nname: test\ncategories:\n  - testing\n  - git\n---\n# body";
+        let metadata = CommandService::parse_command_metadata(content).unwrap();
+
+        assert_eq!(metadata.category.as_deref(), Some("testing"));
+        assert_eq!(
+            metadata.all_categories(),
+            vec!["testing".to_string(), "git".to_string()]
+        );
+
+        let inline = "---\ncategory: git\ncategories: [testing, git]\n---\n";
+        let metadata = CommandService::parse_command_metadata(inline).unwrap();
+        assert_eq!(
+            metadata.all_categories(),
+            vec!["git".to_string(), "testing".to_string()]
+        );
+    }
+
+    #[test]
+    fn parse_metadata_keeps_claude_frontmatter_fields() {
+        let content = "---\ndescription: Review a PR\nargument-hint: [pr-number]\nmodel: claude-sonnet-4-5\nallowed-tools: Bash(gh:*), Read\n---\n# body";
+        let metadata = CommandService::parse_command_metadata(content).unwrap();
+
+        assert_eq!(metadata.description.as_deref(), Some("Review a PR"));
+        assert_eq!(metadata.argument_hint.as_deref(), Some("[pr-number]"));
+        assert_eq!(metadata.model.as_deref(), Some("claude-sonnet-4-5"));
+        let extra = metadata.extra_metadata().unwrap();
+        assert_eq!(extra["model"], "claude-sonnet-4-5");
+        assert_eq!(extra["allowed-tools"], "Bash(gh:*), Read");
+
+        // 标准 YAML 失败时回退解析同样保留 argument-hint 与 model
+        let broken =
+            "---\ndescription: Usage: run it\nargument-hint: \"<file>\"\nmodel: haiku\n---\n";
+        let metadata = CommandService::parse_command_metadata(broken).unwrap();
+        assert_eq!(metadata.argument_hint.as_deref(), Some("<file>"));
+        assert_eq!(metadata.model.as_deref(), Some("haiku"));
+        assert_eq!(metadata.description.as_deref(), Some("Usage: run it"));
+    }
+
+    #[test]
+    fn parse_metadata_reads_requires_env_list_or_string() {
+        let content = "---\nrequires_env: [GITHUB_TOKEN, SLACK_WEBHOOK]\n---\n# body";
+        let metadata = CommandService::parse_command_metadata(content).unwrap();
+        assert_eq!(
+            metadata.requires_env,
+            Some(vec![
+                "GITHUB_TOKEN".to_string(),
+                "SLACK_WEBHOOK".to_string()
+            ])
+        );
+        assert!(metadata.extra_metadata().is_none());
+
+        let content = "---\nrequires-env: GITHUB_TOKEN\n---\n";
+        let metadata = CommandService::parse_command_metadata(content).unwrap();
+        assert_eq!(
+            metadata.requires_env,
+            Some(vec!["GITHUB_TOKEN".to_string()])
+        );
+    }
+
+    #[test]
+    fn parse_metadata_fallback_reads_categories() {
+        // description 中未转义的冒号会让标准 YAML 解析失败
+        let content = "---\ndescription: Usage: run it\ncategories: [git, review]\n---\n";
+        let metadata = CommandService::parse_command_metadata(content).unwrap();
+
+        assert_eq!(
+            metadata.all_categories(),
+            vec!["git".to_string(), "review".to_string()]
+        );
+        assert_eq!(metadata.description.as_deref(), Some("Usage: run it"));
+    }
+
+    #[test]
+    fn sanitize_resource_id_normalizes_segments() {
+        assert_eq!(
+            CommandService::sanitize_resource_id("My Review: PR!").as_deref(),
+            Some("my-review-pr")
+        );
+        assert_eq!(
+            CommandService::sanitize_resource_id(" SC / Build  Docs ").as_deref(),
+            Some("sc/build-docs")
+        );
+        assert_eq!(
+            CommandService::sanitize_resource_id("../../etc/passwd").as_deref(),
+            Some("etc/passwd")
+        );
+        assert!(CommandService::sanitize_resource_id("../ / ?").is_none());
+
+        assert_eq!(
+            CommandService::resolve_pasted_id(None, Some("Smart Commit")).unwrap(),
+            "smart-commit"
+        );
+        assert_eq!(
+            CommandService::resolve_pasted_id(Some("git/commit"), Some("Other")).unwrap(),
+            "git/commit"
+        );
+        assert!(CommandService::resolve_pasted_id(Some("  "), None).is_err());
+    }
+
+    #[test]
+    fn parse_metadata_reports_fallback_warnings() {
+        let content = "---\nname: Ok\n---\n# body";
+        let (_, warnings) = CommandService::parse_command_metadata_with_warnings(content).unwrap();
+        assert!(warnings.is_empty());
+
+        // description 中的冒号导致 YAML 失败，allowed_tools 在容错解析中无法读取
+        let broken = "---\nname: Deploy\ndescription: Usage: run it\nallowed_tools: [Bash]\n---\n";
+        let (metadata, warnings) =
+            CommandService::parse_command_metadata_with_warnings(broken).unwrap();
+        assert_eq!(metadata.name.as_deref(), Some("Deploy"));
+        assert_eq!(warnings[0].kind, ParseWarningKind::YamlFallback);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[1].kind, ParseWarningKind::UnparsedField);
+        assert_eq!(warnings[1].field.as_deref(), Some("allowed_tools"));
+    }
+
+    fn discoverable(key: &str, repo: &str) -> DiscoverableCommand {
+        let (namespace, filename) = CommandService::parse_id(key);
+        DiscoverableCommand {
+            key: key.to_string(),
+            name: filename.clone(),
+            description: String::new(),
+            namespace,
+            filename,
+            category: None,
+            categories: Vec::new(),
+            readme_url: None,
+            repo_owner: "owner".to_string(),
+            repo_name: repo.to_string(),
+            repo_branch: "main".to_string(),
+            source_path: None,
+            localized: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn install_for_apps_skips_apps_without_command_support() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let ssot = CommandService::get_ssot_dir()
+            .unwrap()
+            .join(CommandService::id_to_relative_path("git/commit"));
+        fs::create_dir_all(ssot.parent().unwrap()).unwrap();
+        fs::write(&ssot, "---\nname: commit\n---\nCommit changes\n").unwrap();
+
+        let result = CommandService::new()
+            .install_for_apps(
+                &db,
+                &discoverable("git/commit", "repo"),
+                vec![AppType::Claude, AppType::Codex, AppType::Gemini],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.enabled_apps, ["claude"]);
+        assert_eq!(result.skipped.len(), 2);
+        assert!(result.failed.is_empty());
+        assert!(result.installed.apps.is_enabled_for(&AppType::Claude));
+        assert!(!result.installed.apps.is_enabled_for(&AppType::Codex));
+        let relative = CommandService::id_to_relative_path("git/commit");
+        assert!(CommandService::get_app_commands_dir(&AppType::Claude)
+            .unwrap()
+            .join(&relative)
+            .exists());
+        assert!(!CommandService::get_app_commands_dir(&AppType::Codex)
+            .unwrap()
+            .join(&relative)
+            .exists());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn install_to_detected_project_requires_project_root() {
+        let home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let cwd = home.path().join("work").join("sub");
+        fs::create_dir_all(&cwd).unwrap();
+
+        let err = CommandService::new()
+            .install_to_detected_project(
+                &db,
+                &discoverable("git/commit", "repo"),
+                &cwd,
+                &AppType::Claude,
+            )
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("项目根目录"), "{err}");
+        assert!(db.get_installed_command("git/commit").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn install_to_detected_project_keeps_existing_global_install() {
+        let home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let project = home.path().join("project");
+        fs::create_dir_all(project.join(".git")).unwrap();
+
+        let relative = CommandService::id_to_relative_path("git/commit");
+        let ssot = CommandService::get_ssot_dir().unwrap().join(&relative);
+        fs::create_dir_all(ssot.parent().unwrap()).unwrap();
+        fs::write(&ssot, "---\nname: commit\n---\nCommit changes\n").unwrap();
+        let service = CommandService::new();
+
+        // 全新安装直接复制到项目目录，不写入全局应用目录
+        let installed = service
+            .install_to_detected_project(
+                &db,
+                &discoverable("git/commit", "repo"),
+                &project,
+                &AppType::Claude,
+            )
+            .await
+            .unwrap();
+        assert_eq!(installed.scope, "project");
+        assert!(CommandService::get_project_commands_dir(&project)
+            .unwrap()
+            .join(&relative)
+            .exists());
+        let claude_copy = CommandService::get_app_commands_dir(&AppType::Claude)
+            .unwrap()
+            .join(&relative);
+        assert!(!claude_copy.exists());
+
+        // 已全局安装的 Command 不会被移出全局目录
+        db.save_command(&create_test_command("git/commit", "git", "commit"))
+            .unwrap();
+        CommandService::copy_to_app("git/commit", &AppType::Claude).unwrap();
+        let err = service
+            .install_to_detected_project(
+                &db,
+                &discoverable("git/commit", "repo"),
+                &project,
+                &AppType::Claude,
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("全局"), "{err}");
+        assert!(claude_copy.exists());
+        let stored = db.get_installed_command("git/commit").unwrap().unwrap();
+        assert_eq!(stored.scope, "global");
+    }
+
+    #[test]
+    fn branch_switch_target_requires_branch_and_repo_source() {
+        let target = BranchSwitchTarget::new(
+            "Command",
+            "git/commit",
+            Some("owner"),
+            Some("repo"),
+            Some("commands/git/commit.md"),
+            "  dev ",
+        )
+        .unwrap();
+        assert_eq!(target.branch, "dev");
+        assert_eq!(target.source_path, "commands/git/commit.md");
+
+        let err = BranchSwitchTarget::new("Command", "x", Some("o"), Some("r"), Some("x.md"), " ")
+            .unwrap_err();
+        assert!(err.to_string().contains("分支名不能为空"));
+
+        let err = BranchSwitchTarget::new("Agent", "local", None, None, None, "dev").unwrap_err();
+        assert!(err.to_string().contains("本地导入的 Agent"));
+    }
+
+    #[test]
+    fn partition_overlap_splits_keys_by_repo() {
+        let list_a = vec![discoverable("git/commit", "a"), discoverable("review", "a")];
+        let list_b = vec![discoverable("review", "b"), discoverable("deploy", "b")];
+
+        let (overlap, shared) = CommandService::partition_overlap(&list_a, &list_b);
+        assert_eq!(overlap.only_a, ["git/commit"]);
+        assert_eq!(overlap.only_b, ["deploy"]);
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].0.repo_name, "a");
+        assert_eq!(shared[0].1.repo_name, "b");
+    }
+
+    #[test]
+    fn record_overlap_reports_fetch_failures_separately() {
+        let mut overlap = RepoOverlap::default();
+        CommandService::record_overlap(&mut overlap, "same", Ok(true));
+        CommandService::record_overlap(&mut overlap, "diff", Ok(false));
+        CommandService::record_overlap(&mut overlap, "gone", Err(anyhow!("HTTP 404")));
+
+        assert_eq!(overlap.identical, ["same"]);
+        assert_eq!(overlap.conflicting, ["diff"]);
+        assert_eq!(overlap.failed, ["gone: HTTP 404"]);
+    }
+
+    #[test]
+    fn dump_discovery_cache_emits_one_line_per_cached_item() {
+        let db = Arc::new(Database::memory().unwrap());
+        let repo = |name: &str| CommandRepo {
+            owner: "owner".to_string(),
+            name: name.to_string(),
+            branch: "main".to_string(),
+            enabled: true,
+            builtin: false,
+            description_zh: None,
+            description_en: None,
+            description_ja: None,
+            added_at: 0,
+            excluded_keys: Vec::new(),
+            has_commands: None,
+            has_agents: None,
+            has_hooks: None,
+            user_edited_description: false,
+            browse_only: false,
+        };
+        db.add_command_repo(&repo("cached")).unwrap();
+        db.add_command_repo(&repo("uncached")).unwrap();
+        let cached = [
+            discoverable("git/commit", "cached"),
+            discoverable("review", "cached"),
+        ];
+        db.save_cached_commands("owner", "cached", "main", &cached)
+            .unwrap();
+
+        let dump = CommandService::dump_discovery_cache(&db).unwrap();
+        let keys: Vec<String> = dump
+            .lines()
+            .map(|line| {
+                let item: DiscoverableCommand = serde_json::from_str(line).unwrap();
+                item.key
+            })
+            .collect();
+        assert_eq!(keys, ["git/commit", "review"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[serial]
+    fn sync_all_to_apps_continues_past_read_only_app_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let ssot_dir = CommandService::get_ssot_dir().unwrap();
+        for (id, namespace, filename) in [("locked/a", "locked", "a"), ("open", "", "open")] {
+            let path = ssot_dir.join(CommandService::id_to_relative_path(id));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, "# cmd").unwrap();
+            db.save_command(&create_test_command(id, namespace, filename))
+                .unwrap();
+        }
+
+        let app_dir = CommandService::get_app_commands_dir(&AppType::Claude).unwrap();
+        let locked = app_dir.join("locked");
+        fs::create_dir_all(&locked).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o555)).unwrap();
+        // 以 root 运行时权限位不生效，无法模拟只读目录
+        if fs::write(locked.join("probe"), "").is_ok() {
+            return;
+        }
+
+        let report = CommandService::sync_all_to_apps(&db).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(report.synced, 1);
+        assert!(app_dir.join("open.md").exists());
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].id, "locked/a");
+        assert_eq!(report.failures[0].kind, SyncFailureKind::PermissionDenied);
+    }
+
+    #[test]
+    #[serial]
+    fn list_parse_issues_reads_project_copy_for_project_scope() {
+        let home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let project = home.path().join("project");
+        let mut command = create_test_command("deploy", "", "deploy");
+        command.scope = "project".to_string();
+        command.project_path = Some(project.to_string_lossy().to_string());
+        db.save_command(&command).unwrap();
+
+        let project_copy = project.join(".claude/commands/deploy.md");
+        fs::create_dir_all(project_copy.parent().unwrap()).unwrap();
+        let broken = "---\nname: Deploy\ndescription: Usage: run it\n---\n";
+        fs::write(&project_copy, broken).unwrap();
+
+        let issues = CommandService::list_parse_issues(&db).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].id, "deploy");
+        assert_eq!(issues[0].warnings[0].kind, ParseWarningKind::YamlFallback);
+    }
+
+    #[test]
+    fn matches_category_falls_back_to_single_category() {
+        let categories = vec!["git".to_string(), "testing".to_string()];
+        assert!(matches_category(Some("git"), &categories, "Testing"));
+        assert!(!matches_category(Some("git"), &categories, "docs"));
+        assert!(matches_category(Some("git"), &[], "git"));
+        assert!(!matches_category(None, &[], "git"));
+    }
+
+    #[test]
+    fn copy_file_if_changed_skips_identical_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let source = temp.path().join("source.md");
+        let dest = temp.path().join("dest.md");
+        fs::write(&source, "# review\n").unwrap();
+
+        assert!(copy_file_if_changed(&source, &dest).unwrap());
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "# review\n");
+
+        // 内容一致时不改写，mtime 保持不变
+        let old = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        fs::File::options()
+            .write(true)
+            .open(&dest)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        assert!(!copy_file_if_changed(&source, &dest).unwrap());
+        assert_eq!(fs::metadata(&dest).unwrap().modified().unwrap(), old);
+
+        // 大小相同但内容不同时仍会覆盖
+        fs::write(&source, "# rebuke\n").unwrap();
+        assert!(copy_file_if_changed(&source, &dest).unwrap());
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "# rebuke\n");
+    }
+
+    #[test]
+    fn effective_allowed_tools_unions_enabled_commands() {
+        let db = Arc::new(Database::memory().unwrap());
+
+        for (id, claude, tools) in [
+            ("sc/commit", true, vec!["Bash(git add:*)", "Read"]),
+            ("sc/review", true, vec!["Read", " Grep "]),
+            ("sc/deploy", false, vec!["WebFetch"]),
+        ] {
+            let (namespace, filename) = CommandService::parse_id(id);
+            let mut command = create_test_command(id, &namespace, &filename);
+            command.allowed_tools = Some(tools.into_iter().map(str::to_string).collect());
+            command.apps.claude = claude;
+            db.save_command(&command).unwrap();
+        }
+
+        let tools = CommandService::effective_allowed_tools(&db, &AppType::Claude).unwrap();
+        assert_eq!(
+            tools.into_iter().collect::<Vec<_>>(),
+            ["Bash(git add:*)", "Grep", "Read"]
+        );
+        let codex = CommandService::effective_allowed_tools(&db, &AppType::Codex).unwrap();
+        assert!(codex.is_empty());
+
+        assert!(is_powerful_tool("Bash(git add:*)"));
+        assert!(is_powerful_tool("webfetch"));
+        assert!(!is_powerful_tool("Read"));
+    }
+
+    #[test]
+    fn namespace_toggle_targets_only_chosen_namespace() {
+        let db = Arc::new(Database::memory().unwrap());
+
+        for (id, claude) in [
+            ("sc/build", false),
+            ("sc/test", true),
+            ("other/run", false),
+            ("root", false),
+        ] {
+            let (namespace, filename) = CommandService::parse_id(id);
+            let mut command = create_test_command(id, &namespace, &filename);
+            command.apps.claude = claude;
+            db.save_command(&command).unwrap();
+        }
+
+        let enable =
+            CommandService::namespace_toggle_targets(&db, "sc", &AppType::Claude, true).unwrap();
+        assert_eq!(enable, ["sc/build"]);
+
+        let disable =
+            CommandService::namespace_toggle_targets(&db, "sc", &AppType::Claude, false).unwrap();
+        assert_eq!(disable, ["sc/test"]);
+
+        let root =
+            CommandService::namespace_toggle_targets(&db, "", &AppType::Claude, true).unwrap();
+        assert_eq!(root, ["root"]);
+    }
+
+    #[test]
+    fn reconcile_adopts_updates_and_removes() {
+        let temp = tempfile::tempdir().unwrap();
+        let ssot_dir = temp.path();
+        let db = Arc::new(Database::memory().unwrap());
+
+        fs::create_dir_all(ssot_dir.join("sc")).unwrap();
+        fs::write(ssot_dir.join("sc/new.md"), "---\nname: New\n---\n# new").unwrap();
+        fs::write(ssot_dir.join("edited.md"), "---\nname: Edited\n---\n# v2").unwrap();
+
+        for id in ["edited", "gone"] {
+            let (namespace, filename) = CommandService::parse_id(id);
+            let mut command = create_test_command(id, &namespace, &filename);
+            command.apps = CommandApps::default();
+            command.file_hash = Some("stale".to_string());
+            db.save_command(&command).unwrap();
+        }
+
+        let report = CommandService::reconcile_dir(&db, ssot_dir).unwrap();
+        assert_eq!(
+            report,
+            ReconcileReport {
+                adopted: 1,
+                updated: 1,
+                removed: 1,
+            }
+        );
+
+        let adopted = db.get_installed_command("sc/new").unwrap().unwrap();
+        assert_eq!(adopted.name, "New");
+        assert!(adopted.repo_owner.is_none());
+        assert_eq!(db.get_installed_command("edited").unwrap().unwrap().name, "Edited");
+        assert!(db.get_installed_command("gone").unwrap().is_none());
+
+        // 再次对账不应产生任何变更
+        let report = CommandService::reconcile_dir(&db, ssot_dir).unwrap();
+        assert_eq!(report, ReconcileReport::default());
+    }
+
+    #[test]
+    fn repo_validation_counts_resource_dirs() {
+        let paths = [
+            "README.md",
+            "commands/review.md",
+            "plugins/bun/commands/build.md",
+            "plugins/bun/commands/notes.txt",
+            "agents/planner.md",
+            "hooks/format.json",
+            "hooks/README.md",
+            ".github/commands/ci.md",
+            "a/b/c/d/commands/too-deep.md",
+        ];
+
+        let validation = RepoValidation::count_resources("main", paths);
+        assert_eq!(
+            validation,
+            RepoValidation {
+                branch: "main".to_string(),
+                commands: 2,
+                agents: 1,
+                hooks: 1,
+                warning: None,
+            }
+        );
+        assert!(!validation.is_empty());
+        assert_eq!(validation.count_of(ResourceType::Agent), 1);
+        assert_eq!(validation.count_of(ResourceType::Skill), 0);
+        assert!(RepoValidation::count_resources("main", ["docs/intro.md"]).is_empty());
+    }
+
+    #[test]
+    fn repo_validation_is_advisory_unless_not_found() {
+        let repo = CommandRepo {
+            owner: "owner".to_string(),
+            name: "repo".to_string(),
+            branch: "dev".to_string(),
+            enabled: true,
+            builtin: false,
+            description_zh: None,
+            description_en: None,
+            description_ja: None,
+            added_at: 0,
+            excluded_keys: Vec::new(),
+            has_commands: None,
+            has_agents: None,
+            has_hooks: None,
+            user_edited_description: false,
+            browse_only: false,
+        };
+        let not_found = || anyhow!("不存在");
+
+        let err =
+            RepoValidation::from_request_error(&repo, "dev", GitHubApiError::NotFound, not_found)
+                .unwrap_err();
+        assert_eq!(err.to_string(), "不存在");
+
+        let offline = RepoValidation::from_request_error(
+            &repo,
+            "dev",
+            GitHubApiError::NetworkError("offline".to_string()),
+            not_found,
+        )
+        .unwrap();
+        assert_eq!(offline.branch, "dev");
+        assert!(offline.warning.unwrap().contains("offline"));
+    }
+
+    #[test]
+    fn repos_export_round_trips_and_skips_builtins_and_duplicates() {
+        let source = Arc::new(Database::memory().unwrap());
+        let repo = |owner: &str, builtin: bool| CommandRepo {
+            owner: owner.to_string(),
+            name: "cmds".to_string(),
+            branch: "dev".to_string(),
+            enabled: false,
+            builtin,
+            description_zh: None,
+            description_en: None,
+            description_ja: None,
+            added_at: 1,
+            excluded_keys: vec!["noisy".to_string()],
+            has_commands: None,
+            has_agents: None,
+            has_hooks: None,
+            user_edited_description: false,
+            browse_only: false,
+        };
+        source.add_command_repo(&repo("alice", false)).unwrap();
+        source.add_command_repo(&repo("bob", false)).unwrap();
+        source.add_command_repo(&repo("official", true)).unwrap();
+        let json = CommandService::export_repos(&source).unwrap();
+
+        let target = Arc::new(Database::memory().unwrap());
+        target.add_command_repo(&repo("Bob", false)).unwrap();
+        let result = CommandService::import_repos(&target, &json).unwrap();
+        assert_eq!(result.added, ["alice/cmds"]);
+        assert_eq!(result.skipped, ["bob/cmds", "official/cmds"]);
+
+        let imported = target
+            .get_all_command_repos()
+            .unwrap()
+            .into_iter()
+            .find(|r| r.owner == "alice")
+            .unwrap();
+        assert_eq!(imported.branch, "dev");
+        assert!(!imported.enabled);
+        assert_eq!(imported.excluded_keys, ["noisy"]);
+
+        assert!(CommandService::import_repos(&target, "[]").is_err());
+    }
+
+    #[test]
+    fn refresh_rejects_branch_that_discovery_does_not_read() {
+        let db = Arc::new(Database::memory().unwrap());
+        db.add_command_repo(&CommandRepo {
+            owner: "awesome".to_string(),
+            name: "catalog".to_string(),
+            branch: "main".to_string(),
+            enabled: true,
+            builtin: false,
+            description_zh: None,
+            description_en: None,
+            description_ja: None,
+            added_at: 0,
+            excluded_keys: Vec::new(),
+            has_commands: None,
+            has_agents: None,
+            has_hooks: None,
+            user_edited_description: false,
+            browse_only: false,
+        })
+        .unwrap();
+
+        let repo = CommandService::find_repo_for_refresh(&db, "Awesome", "catalog", "").unwrap();
+        assert_eq!(repo.branch, "main");
+        assert!(CommandService::find_repo_for_refresh(&db, "awesome", "catalog", " main ").is_ok());
+        let err =
+            CommandService::find_repo_for_refresh(&db, "awesome", "catalog", "dev").unwrap_err();
+        assert!(err.to_string().contains("dev"));
+        assert!(CommandService::find_repo_for_refresh(&db, "other", "repo", "").is_err());
+    }
+
+    #[test]
+    fn save_repo_cache_is_read_back_and_reports_failures() {
+        let db = Arc::new(Database::memory().unwrap());
+        let repo = CommandRepo {
+            owner: "awesome".to_string(),
+            name: "catalog".to_string(),
+            branch: "main".to_string(),
+            enabled: true,
+            builtin: false,
+            description_zh: None,
+            description_en: None,
+            description_ja: None,
+            added_at: 0,
+            excluded_keys: Vec::new(),
+            has_commands: None,
+            has_agents: None,
+            has_hooks: None,
+            user_edited_description: false,
+            browse_only: false,
+        };
+
+        CommandService::save_repo_cache(&db, &repo, &[], None).unwrap();
+        assert!(db
+            .get_cached_commands("awesome", "catalog", "main")
+            .unwrap()
+            .is_some());
+
+        db.conn
+            .lock()
+            .unwrap()
+            .execute("DROP TABLE command_discovery_cache", [])
+            .unwrap();
+        assert!(CommandService::save_repo_cache(&db, &repo, &[], None).is_err());
+    }
+
+    #[test]
+    fn browse_only_repo_blocks_new_installs() {
+        let db = Arc::new(Database::memory().unwrap());
+        db.add_command_repo(&CommandRepo {
+            owner: "awesome".to_string(),
+            name: "catalog".to_string(),
+            branch: "main".to_string(),
+            enabled: true,
+            builtin: false,
+            description_zh: None,
+            description_en: None,
+            description_ja: None,
+            added_at: 0,
+            excluded_keys: Vec::new(),
+            has_commands: None,
+            has_agents: None,
+            has_hooks: None,
+            user_edited_description: false,
+            browse_only: true,
+        })
+        .unwrap();
+
+        let err =
+            CommandService::ensure_repo_installable(&db, "Awesome", "catalog", None).unwrap_err();
+        assert!(err.to_string().contains("awesome/catalog"));
+        // 从同一仓库安装的资源仍可更新
+        assert!(CommandService::ensure_repo_installable(
+            &db,
+            "awesome",
+            "catalog",
+            Some(("Awesome", "catalog"))
+        )
+        .is_ok());
+        // 同 key 资源来自其他仓库时仍按新安装处理
+        assert!(CommandService::ensure_repo_installable(
+            &db,
+            "awesome",
+            "catalog",
+            Some(("other", "repo"))
+        )
+        .is_err());
+        assert!(CommandService::ensure_repo_installable(&db, "other", "repo", None).is_ok());
+
+        db.set_command_repo_browse_only("awesome", "catalog", false)
+            .unwrap();
+        assert!(CommandService::ensure_repo_installable(&db, "awesome", "catalog", None).is_ok());
+    }
+
+    #[test]
+    fn filter_excluded_only_hides_listed_keys() {
+        let discovered = |owner: &str, key: &str| DiscoverableCommand {
+            key: key.to_string(),
+            name: key.to_string(),
+            description: String::new(),
+            namespace: String::new(),
+            filename: key.to_string(),
+            category: None,
+            categories: Vec::new(),
+            readme_url: None,
+            repo_owner: owner.to_string(),
+            repo_name: "cmds".to_string(),
+            repo_branch: "main".to_string(),
+            source_path: None,
+            localized: Default::default(),
+        };
+        let repo = CommandRepo {
+            owner: "alice".to_string(),
+            name: "cmds".to_string(),
+            branch: "main".to_string(),
+            enabled: true,
+            builtin: false,
+            description_zh: None,
+            description_en: None,
+            description_ja: None,
+            added_at: 0,
+            excluded_keys: vec!["noisy".to_string()],
+            has_commands: None,
+            has_agents: None,
+            has_hooks: None,
+            user_edited_description: false,
+            browse_only: false,
+        };
+
+        let mut commands = vec![
+            discovered("alice", "noisy"),
+            discovered("alice", "review"),
+            discovered("bob", "noisy"),
+        ];
+        CommandService::filter_excluded(&mut commands, &[repo]);
+
+        let remaining: Vec<(&str, &str)> = commands
+            .iter()
+            .map(|c| (c.repo_owner.as_str(), c.key.as_str()))
+            .collect();
+        assert_eq!(remaining, vec![("alice", "review"), ("bob", "noisy")]);
+    }
+
+    #[test]
+    fn install_keys_resolves_and_disambiguates_by_repo() {
+        let discovered = |owner: &str, key: &str| DiscoverableCommand {
+            key: key.to_string(),
+            name: key.to_string(),
+            description: String::new(),
+            namespace: String::new(),
+            filename: key.to_string(),
+            category: None,
+            categories: Vec::new(),
+            readme_url: None,
+            repo_owner: owner.to_string(),
+            repo_name: "cmds".to_string(),
+            repo_branch: "main".to_string(),
+            source_path: None,
+            localized: Default::default(),
+        };
+        let available = vec![
+            discovered("alice", "sc/agent"),
+            discovered("bob", "sc/agent"),
+            discovered("alice", "review"),
+        ];
+
+        let keys: Vec<String> = ["  review", "", "# team list", "sc/agent", "review"]
+            .iter()
+            .map(|k| k.to_string())
+            .collect();
+        assert_eq!(
+            CommandService::normalize_key_list(&keys),
+            vec!["review".to_string(), "sc/agent".to_string()]
+        );
+
+        let review = CommandService::resolve_key(&available, "review", None).unwrap();
+        assert_eq!(review.repo_owner, "alice");
+
+        let err = CommandService::resolve_key(&available, "sc/agent", None).unwrap_err();
+        assert!(err.to_string().contains("alice/cmds, bob/cmds"));
+        let agent = CommandService::resolve_key(&available, "sc/agent", Some("Bob/cmds")).unwrap();
+        assert_eq!(agent.repo_owner, "bob");
+        assert!(CommandService::resolve_key(&available, "sc/agent", Some("carol/cmds")).is_err());
+
+        assert!(CommandService::resolve_key(&available, "missing", None).is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn create_from_content_cleans_up_when_enabling_fails() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let content = "---\nname: Review\ndescription: Review code\n---\n\nBody\n";
+        let dest = CommandService::get_ssot_dir()
+            .unwrap()
+            .join(CommandService::id_to_relative_path("team/review"));
+
+        // 应用目录被同名文件占用，启用到应用时失败
+        let app_dir = CommandService::get_app_commands_dir(&AppType::Claude).unwrap();
+        fs::create_dir_all(app_dir.parent().unwrap()).unwrap();
+        fs::write(&app_dir, "not a directory").unwrap();
+
+        let create = || {
+            CommandService::create_from_content(&db, Some("team/review"), content, &AppType::Claude)
+        };
+        assert!(create().is_err());
+        assert!(db.get_installed_command("team/review").unwrap().is_none());
+        assert!(!dest.exists());
+
+        // 清理后可以用同一个 ID 重新创建
+        fs::remove_file(&app_dir).unwrap();
+        let command = create().unwrap();
+        assert_eq!(command.name, "Review");
+        assert!(command.apps.claude);
+        assert!(dest.exists());
+        assert!(app_dir.join("team").join("review.md").exists());
+        assert!(db.get_installed_command("team/review").unwrap().is_some());
+    }
+}
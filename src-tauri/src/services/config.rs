@@ -1,14 +1,325 @@
+use super::agent::{self, AgentService};
+use super::command::{self, CommandService};
+use super::env_checker;
+use super::github_api::GitHubApiService;
+use super::hook::{self, HookService};
+use super::mcp::McpService;
 use super::provider::{sanitize_claude_settings_for_live, ProviderService};
+use super::resource_lock::ResourceLockService;
+use super::skill::{SkillChangeKind, SkillService};
+use super::update::ResourceType;
 use crate::app_config::{AppType, MultiAppConfig};
+use crate::config::get_app_config_dir;
+use crate::database::backup::PENDING_RESTORE_FILE;
+use crate::database::{Database, SCHEMA_VERSION};
 use crate::error::AppError;
 use crate::provider::Provider;
+use crate::store::AppState;
 use chrono::Utc;
+use rusqlite::backup::Backup;
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 const MAX_BACKUPS: usize = 10;
 
+/// 配置操作的目标资源类型
+///
+/// 在来自仓库的 [`ResourceType`] 之外加上 MCP 服务器，用于锁定、跨应用复制、
+/// 路径报告等不涉及仓库更新的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigTarget {
+    Skill,
+    Command,
+    Hook,
+    Agent,
+    Mcp,
+}
+
+impl From<ResourceType> for ConfigTarget {
+    fn from(resource_type: ResourceType) -> Self {
+        match resource_type {
+            ResourceType::Skill => Self::Skill,
+            ResourceType::Command => Self::Command,
+            ResourceType::Hook => Self::Hook,
+            ResourceType::Agent => Self::Agent,
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Skill => write!(f, "Skill"),
+            Self::Command => write!(f, "Command"),
+            Self::Hook => write!(f, "Hook"),
+            Self::Agent => write!(f, "Agent"),
+            Self::Mcp => write!(f, "MCP"),
+        }
+    }
+}
+
+/// 启动时完整性校验发现的问题（前端监听器可能晚于校验就绪，需要时主动拉取）
+static STARTUP_INTEGRITY_ISSUES: Mutex<Vec<IntegrityIssue>> = Mutex::new(Vec::new());
+
+/// 单类资源的同步状态计数（按资源 ID 去重）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceSyncStatus {
+    /// 已安装总数
+    pub total: usize,
+    /// 无任何异常
+    pub in_sync: usize,
+    /// SSOT 文件在安装后被修改
+    pub drifted: usize,
+    /// 应用目录中的文件与 SSOT 不一致
+    pub conflicted: usize,
+    /// 数据库有记录但 SSOT 文件已丢失
+    pub orphaned: usize,
+}
+
+/// 各类资源的同步状态汇总，供界面轮询显示徽标
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    pub commands: ResourceSyncStatus,
+    pub agents: ResourceSyncStatus,
+    pub hooks: ResourceSyncStatus,
+    pub skills: ResourceSyncStatus,
+}
+
+/// 单个应用中已启用的各类资源数量
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceCounts {
+    pub commands: u32,
+    pub agents: u32,
+    /// 仅统计全局启用的 Hooks
+    pub hooks: u32,
+    pub skills: u32,
+    pub mcp_servers: u32,
+}
+
+/// 同步异常类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SyncIssue {
+    Drifted,
+    Conflicted,
+    Orphaned,
+}
+
+impl ResourceSyncStatus {
+    /// 由 (资源 ID, 异常类别) 列表汇总计数，同一资源的多条同类事件只计一次
+    fn tally(total: usize, issues: impl IntoIterator<Item = (String, SyncIssue)>) -> Self {
+        let mut drifted = HashSet::new();
+        let mut conflicted = HashSet::new();
+        let mut orphaned = HashSet::new();
+        let mut affected = HashSet::new();
+
+        for (id, issue) in issues {
+            match issue {
+                SyncIssue::Drifted => drifted.insert(id.clone()),
+                SyncIssue::Conflicted => conflicted.insert(id.clone()),
+                SyncIssue::Orphaned => orphaned.insert(id.clone()),
+            };
+            affected.insert(id);
+        }
+
+        Self {
+            total,
+            in_sync: total.saturating_sub(affected.len()),
+            drifted: drifted.len(),
+            conflicted: conflicted.len(),
+            orphaned: orphaned.len(),
+        }
+    }
+}
+
+/// 完整性问题类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityIssueKind {
+    /// SSOT 文件内容与数据库记录的哈希不一致
+    HashMismatch,
+    /// 数据库有记录但 SSOT 文件不存在
+    MissingFile,
+    /// SSOT 文件存在但无法读取
+    Unreadable,
+    /// 资源声明的环境变量（requires_env）未设置
+    MissingEnv,
+    /// Hook 的事件类型无法识别（该 Hook 不会被同步）
+    UnknownEventType,
+}
+
+/// 单条完整性问题
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityIssue {
+    pub resource_type: ResourceType,
+    pub id: String,
+    pub kind: IntegrityIssueKind,
+    /// 数据库中记录的哈希
+    pub expected_hash: Option<String>,
+    /// 按记录哈希的算法重新计算的结果（文件缺失或不可读时为空）
+    pub actual_hash: Option<String>,
+    pub message: Option<String>,
+}
+
+/// 跨应用复制成功的资源
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopiedResource {
+    pub resource_type: ConfigTarget,
+    pub id: String,
+}
+
+/// 跨应用复制时被跳过的资源
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedResource {
+    pub resource_type: ConfigTarget,
+    /// 为空表示整个资源类型被跳过
+    pub id: Option<String>,
+    pub reason: String,
+}
+
+/// 跨应用复制配置的结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyConfigSummary {
+    /// 新启用到目标应用的资源
+    pub copied: Vec<CopiedResource>,
+    /// 目标应用中早已启用、无需处理的资源数
+    pub already_enabled: usize,
+    pub skipped: Vec<SkippedResource>,
+}
+
+/// 按应用批量启用/禁用的结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkToggleResult {
+    /// 实际变更的资源数
+    pub changed: usize,
+    /// 切换或同步失败的资源（`id` 为空表示整类同步失败）
+    pub failed: Vec<SkippedResource>,
+}
+
+impl BulkToggleResult {
+    fn record(
+        &mut self,
+        resource_type: ResourceType,
+        id: Option<&str>,
+        result: anyhow::Result<()>,
+    ) {
+        match result {
+            Ok(()) => self.changed += 1,
+            Err(e) => {
+                log::warn!("切换 {resource_type} {} 失败: {e}", id.unwrap_or("*"));
+                self.failed.push(SkippedResource {
+                    resource_type: resource_type.into(),
+                    id: id.map(str::to_string),
+                    reason: e.to_string(),
+                });
+            }
+        }
+    }
+}
+
+impl CopyConfigSummary {
+    fn record(&mut self, resource_type: ConfigTarget, id: &str, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.copied.push(CopiedResource {
+                resource_type,
+                id: id.to_string(),
+            }),
+            Err(reason) => self.skipped.push(SkippedResource {
+                resource_type,
+                id: Some(id.to_string()),
+                reason,
+            }),
+        }
+    }
+}
+
+/// 应用目录中存在、但 SSOT 中没有对应文件的资源文件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraFile {
+    pub app: String,
+    /// 相对应用目录的 ID（如 "sc/agent"）
+    pub id: String,
+    /// 文件绝对路径
+    pub path: String,
+    pub size: u64,
+    /// 最后修改时间（Unix 秒）
+    pub modified_at: Option<i64>,
+}
+
+/// 单个路径的诊断信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathProbe {
+    pub path: String,
+    pub exists: bool,
+    /// 是否可写；路径不存在时检查最近的已存在上级目录
+    pub writable: bool,
+}
+
+/// 某个应用下某类资源的实际路径
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathReportEntry {
+    pub app: String,
+    pub resource_type: ConfigTarget,
+    /// SSOT 位置（MCP 存储在数据库中，为数据库文件）
+    pub ssot: Option<PathProbe>,
+    /// 应用侧目录或配置文件（已应用目录覆盖）
+    pub app_path: Option<PathProbe>,
+    /// 路径解析失败时的错误信息
+    pub error: Option<String>,
+}
+
+/// 一次安装到多个应用的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiAppInstallResult<T> {
+    pub installed: T,
+    /// 已启用的应用
+    pub enabled_apps: Vec<String>,
+    /// 不支持该资源类型而跳过的应用说明
+    pub skipped: Vec<String>,
+    /// 启用失败的应用说明（资源已安装，可稍后单独启用）
+    pub failed: Vec<String>,
+}
+
+/// 所有应用的路径诊断报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathReport {
+    pub home_dir: Option<String>,
+    /// CC Switch 配置目录（~/.cc-switch/ 或用户自定义位置）
+    pub config_dir: PathProbe,
+    pub entries: Vec<PathReportEntry>,
+    /// 当前生效的应用目录覆盖（非空时说明部分文件不会写入默认位置）
+    pub overrides: Vec<OverrideInfo>,
+}
+
+/// 设置中生效的应用配置目录覆盖
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideInfo {
+    pub app: String,
+    /// 覆盖后的目录（已解析 `~`）
+    pub override_path: String,
+    pub exists: bool,
+}
+
 /// 配置导入导出相关业务逻辑
 pub struct ConfigService;
 
@@ -83,6 +394,91 @@ impl ConfigService {
         Ok(())
     }
 
+    /// 使用 SQLite backup API 将整个数据库快照到 `path`，返回快照记录的 Schema 版本
+    pub fn snapshot_db(db: &Arc<Database>, path: &Path) -> Result<i32, AppError> {
+        let version = db.snapshot_to_file(path)?;
+        log::info!(
+            "Database snapshot written to {} (schema v{version})",
+            path.display()
+        );
+        Ok(version)
+    }
+
+    /// 校验快照的 Schema 版本后，安排在下次启动时用它替换当前数据库
+    ///
+    /// 运行中的连接不会被替换，需重启应用后生效；返回快照记录的 Schema 版本
+    pub fn restore_db(path: &Path) -> Result<i32, AppError> {
+        let pending = get_app_config_dir().join(PENDING_RESTORE_FILE);
+        let version = Self::stage_db_restore(path, &pending)?;
+        log::info!(
+            "Database snapshot {} (schema v{version}) staged, restart required",
+            path.display()
+        );
+        Ok(version)
+    }
+
+    fn stage_db_restore(snapshot: &Path, pending: &Path) -> Result<i32, AppError> {
+        if !snapshot.exists() {
+            return Err(AppError::InvalidInput(format!(
+                "快照文件不存在: {}",
+                snapshot.display()
+            )));
+        }
+
+        let source = Connection::open_with_flags(snapshot, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let version = Self::validate_db_snapshot(&source)?;
+
+        // 先写入临时文件再改名，避免启动时读到不完整的快照
+        let staging = pending.with_extension("restore.tmp");
+        if let Some(parent) = staging.parent() {
+            fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+        }
+        if staging.exists() {
+            fs::remove_file(&staging).map_err(|e| AppError::io(&staging, e))?;
+        }
+        {
+            let mut dest =
+                Connection::open(&staging).map_err(|e| AppError::Database(e.to_string()))?;
+            let backup =
+                Backup::new(&source, &mut dest).map_err(|e| AppError::Database(e.to_string()))?;
+            backup
+                .step(-1)
+                .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        fs::rename(&staging, pending).map_err(|e| AppError::io(&staging, e))?;
+
+        Ok(version)
+    }
+
+    /// 校验快照的 Schema 版本与完整性；比当前版本旧的快照会在启动时迁移
+    fn validate_db_snapshot(conn: &Connection) -> Result<i32, AppError> {
+        let version = Database::get_user_version(conn)?;
+        if version <= 0 {
+            return Err(AppError::localized(
+                "backup.snapshot.missing_version",
+                "快照缺少 Schema 版本，不是有效的 CC Switch 数据库快照。",
+                "The snapshot has no schema version and is not a CC Switch database snapshot.",
+            ));
+        }
+        if version > SCHEMA_VERSION {
+            return Err(AppError::localized(
+                "backup.snapshot.too_new",
+                format!("快照版本过新（{version}），当前应用仅支持 {SCHEMA_VERSION}，请升级应用后再恢复。"),
+                format!("The snapshot schema version ({version}) is newer than supported ({SCHEMA_VERSION}). Please upgrade the app before restoring."),
+            ));
+        }
+
+        let check: String = conn
+            .query_row("PRAGMA quick_check;", [], |row| row.get(0))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        if check != "ok" {
+            return Err(AppError::Database(format!("快照完整性校验失败: {check}")));
+        }
+
+        Ok(version)
+    }
+
     /// 同步当前供应商到对应的 live 配置。
     pub fn sync_current_providers_to_live(config: &mut MultiAppConfig) -> Result<(), AppError> {
         Self::sync_current_provider_for_app(config, &AppType::Claude)?;
@@ -231,4 +627,1240 @@ impl ConfigService {
 
         Ok(())
     }
+
+    /// 汇总 Commands/Agents/Hooks/Skills 的同步状态
+    ///
+    /// 复用各服务的 `detect_changes`，只返回计数，便于界面频繁轮询
+    pub fn sync_status(db: &Arc<Database>) -> Result<SyncStatus, AppError> {
+        let to_app_error = |e: anyhow::Error| AppError::Message(e.to_string());
+
+        let command_total = db.get_all_installed_commands()?.len();
+        let command_issues = CommandService::detect_changes(db)
+            .map_err(to_app_error)?
+            .into_iter()
+            .filter_map(|event| {
+                let issue = match event.event_type {
+                    command::ChangeEventType::SsotModified => SyncIssue::Drifted,
+                    command::ChangeEventType::AppConflict => SyncIssue::Conflicted,
+                    command::ChangeEventType::SsotDeleted => SyncIssue::Orphaned,
+                    command::ChangeEventType::SsotAdded => return None,
+                };
+                Some((event.id, issue))
+            });
+        let commands = ResourceSyncStatus::tally(command_total, command_issues);
+
+        let agent_total = db.get_all_installed_agents()?.len();
+        let agent_issues = AgentService::detect_changes(db)
+            .map_err(to_app_error)?
+            .into_iter()
+            .filter_map(|event| {
+                let issue = match event.event_type {
+                    agent::ChangeEventType::SsotModified => SyncIssue::Drifted,
+                    agent::ChangeEventType::AppConflict => SyncIssue::Conflicted,
+                    agent::ChangeEventType::SsotDeleted => SyncIssue::Orphaned,
+                    agent::ChangeEventType::SsotAdded => return None,
+                };
+                Some((event.id, issue))
+            });
+        let agents = ResourceSyncStatus::tally(agent_total, agent_issues);
+
+        Ok(SyncStatus {
+            commands,
+            agents,
+            hooks: Self::hook_sync_status(db)?,
+            skills: Self::skill_sync_status(db)?,
+        })
+    }
+
+    /// 按应用统计已启用的资源数量
+    ///
+    /// 在 SQL 中聚合，不加载整行，安装量大时也能快速返回；
+    /// 结果只包含至少支持一类资源的应用
+    pub fn resource_counts(
+        db: &Arc<Database>,
+    ) -> Result<HashMap<AppType, ResourceCounts>, AppError> {
+        let file_apps = [AppType::Claude, AppType::Codex, AppType::Gemini];
+        let skill_apps = [
+            AppType::Claude,
+            AppType::Codex,
+            AppType::Gemini,
+            AppType::OpenCode,
+            AppType::Hermes,
+        ];
+        let tables: [(&str, &[AppType], bool, fn(&mut ResourceCounts) -> &mut u32); 5] = [
+            ("commands", &file_apps, false, |c| &mut c.commands),
+            ("agents", &file_apps, false, |c| &mut c.agents),
+            ("hooks", &file_apps, true, |c| &mut c.hooks),
+            ("skills", &skill_apps, false, |c| &mut c.skills),
+            ("mcp_servers", &skill_apps, false, |c| &mut c.mcp_servers),
+        ];
+
+        let mut counts: HashMap<AppType, ResourceCounts> = HashMap::new();
+        for (table, apps, only_globally_enabled, field) in tables {
+            for (app, count) in db.count_enabled_per_app(table, apps, only_globally_enabled)? {
+                *field(counts.entry(app).or_default()) = count;
+            }
+        }
+
+        Ok(counts)
+    }
+
+    /// Hooks 没有独立的应用目录文件（合并写入 settings.json），
+    /// 因此只比较 SSOT 与安装时的哈希，`conflicted` 恒为 0
+    fn hook_sync_status(db: &Arc<Database>) -> Result<ResourceSyncStatus, AppError> {
+        let hooks = db.get_all_installed_hooks()?;
+        let ssot_dir = HookService::get_ssot_dir().map_err(|e| AppError::Message(e.to_string()))?;
+
+        let mut issues = Vec::new();
+        for hook in hooks.values() {
+            let path = ssot_dir.join(HookService::id_to_relative_path(&hook.id));
+            match fs::read(&path) {
+                Ok(content) => {
+                    if hook
+                        .file_hash
+                        .as_deref()
+                        .is_some_and(|stored| !Self::content_matches_hash(&content, stored))
+                    {
+                        issues.push((hook.id.clone(), SyncIssue::Drifted));
+                    }
+                }
+                Err(_) => issues.push((hook.id.clone(), SyncIssue::Orphaned)),
+            }
+        }
+
+        Ok(ResourceSyncStatus::tally(hooks.len(), issues))
+    }
+
+    /// Skills 以目录为单位，按目录哈希与安装时的 file_hash 比较，`conflicted` 恒为 0
+    fn skill_sync_status(db: &Arc<Database>) -> Result<ResourceSyncStatus, AppError> {
+        let total = db.get_all_installed_skills()?.len();
+        let issues = SkillService::detect_local_changes(db)
+            .map_err(|e| AppError::Message(e.to_string()))?
+            .into_iter()
+            .map(|change| {
+                let issue = match change.kind {
+                    SkillChangeKind::Modified => SyncIssue::Drifted,
+                    SkillChangeKind::Missing => SyncIssue::Orphaned,
+                };
+                (change.id, issue)
+            });
+
+        Ok(ResourceSyncStatus::tally(total, issues))
+    }
+
+    /// 将源应用已启用的资源复制到目标应用
+    ///
+    /// 对每个资源类型，把在 `from` 中启用、在 `to` 中未启用的资源启用到 `to` 并同步；
+    /// 任一应用不支持的资源类型整体跳过，单个资源失败不会中断整批复制
+    pub fn copy_app_config(
+        state: &AppState,
+        from: AppType,
+        to: AppType,
+        resource_types: Vec<ConfigTarget>,
+    ) -> Result<CopyConfigSummary, AppError> {
+        if from == to {
+            return Err(AppError::InvalidInput("源应用与目标应用相同".to_string()));
+        }
+
+        let db = &state.db;
+        let mut summary = CopyConfigSummary::default();
+        let mut seen = HashSet::new();
+
+        for resource_type in resource_types {
+            if !seen.insert(resource_type) {
+                continue;
+            }
+            if !Self::supports_app(resource_type, &from) || !Self::supports_app(resource_type, &to)
+            {
+                summary.skipped.push(SkippedResource {
+                    resource_type,
+                    id: None,
+                    reason: format!(
+                        "{} 或 {} 不支持 {resource_type}",
+                        from.as_str(),
+                        to.as_str()
+                    ),
+                });
+                continue;
+            }
+
+            match resource_type {
+                ConfigTarget::Command => {
+                    for command in db.get_all_installed_commands()?.values() {
+                        if !command.apps.is_enabled_for(&from) {
+                            continue;
+                        }
+                        if command.apps.is_enabled_for(&to) {
+                            summary.already_enabled += 1;
+                            continue;
+                        }
+                        let result = CommandService::toggle_app(db, &command.id, &to, true)
+                            .map_err(|e| e.to_string());
+                        summary.record(resource_type, &command.id, result);
+                    }
+                }
+                ConfigTarget::Agent => {
+                    for agent in db.get_all_installed_agents()?.values() {
+                        if !agent.apps.is_enabled_for(from.as_str()) {
+                            continue;
+                        }
+                        if agent.apps.is_enabled_for(to.as_str()) {
+                            summary.already_enabled += 1;
+                            continue;
+                        }
+                        let result = AgentService::toggle_app(db, &agent.id, &to, true)
+                            .map_err(|e| e.to_string());
+                        summary.record(resource_type, &agent.id, result);
+                    }
+                }
+                ConfigTarget::Hook => {
+                    // Hooks 合并写入同一个 settings.json，先批量更新启用状态，最后只同步一次
+                    let mut enabled_ids = Vec::new();
+                    for hook in db.get_all_installed_hooks()?.values() {
+                        if !hook.apps.is_enabled_for(from.as_str()) {
+                            continue;
+                        }
+                        if hook.apps.is_enabled_for(to.as_str()) {
+                            summary.already_enabled += 1;
+                            continue;
+                        }
+                        let mut apps = hook.apps.clone();
+                        apps.set_enabled_for(to.as_str(), true);
+                        match db.update_hook_apps(&hook.id, &apps) {
+                            Ok(_) => enabled_ids.push(hook.id.clone()),
+                            Err(e) => summary.record(resource_type, &hook.id, Err(e.to_string())),
+                        }
+                    }
+                    if !enabled_ids.is_empty() {
+                        let synced = HookService::sync_to_app(db, &to).map(|_| ());
+                        let result = synced.map_err(|e| format!("同步 Hooks 失败: {e}"));
+                        for id in &enabled_ids {
+                            summary.record(resource_type, id, result.clone());
+                        }
+                    }
+                }
+                ConfigTarget::Skill => {
+                    for skill in db.get_all_installed_skills()?.values() {
+                        if !skill.apps.is_enabled_for(&from) {
+                            continue;
+                        }
+                        if skill.apps.is_enabled_for(&to) {
+                            summary.already_enabled += 1;
+                            continue;
+                        }
+                        let result = SkillService::toggle_app(db, &skill.id, &to, true)
+                            .map_err(|e| e.to_string());
+                        summary.record(resource_type, &skill.id, result);
+                    }
+                }
+                ConfigTarget::Mcp => {
+                    for server in db.get_all_mcp_servers()?.values() {
+                        if !server.apps.is_enabled_for(&from) {
+                            continue;
+                        }
+                        if server.apps.is_enabled_for(&to) {
+                            summary.already_enabled += 1;
+                            continue;
+                        }
+                        let result = McpService::toggle_app(state, &server.id, to.clone(), true)
+                            .map_err(|e| e.to_string());
+                        summary.record(resource_type, &server.id, result);
+                    }
+                }
+            }
+        }
+
+        log::info!(
+            "已从 {} 复制 {} 项配置到 {}（跳过 {} 项）",
+            from.as_str(),
+            summary.copied.len(),
+            to.as_str(),
+            summary.skipped.len()
+        );
+        Ok(summary)
+    }
+
+    /// 为指定应用启用所有已安装的资源并同步，返回变更数量与失败项
+    ///
+    /// 应用不支持的资源类型会被跳过
+    pub fn enable_all_for_app(
+        db: &Arc<Database>,
+        app: &AppType,
+        resource_types: &[ResourceType],
+    ) -> Result<BulkToggleResult, AppError> {
+        Self::set_all_for_app(db, app, resource_types, true, false)
+    }
+
+    /// 为指定应用禁用所有已安装的资源并同步，返回变更数量与失败项
+    ///
+    /// 锁定的资源会被跳过，`force` 为 true 时一并禁用
+    pub fn disable_all_for_app(
+        db: &Arc<Database>,
+        app: &AppType,
+        resource_types: &[ResourceType],
+        force: bool,
+    ) -> Result<BulkToggleResult, AppError> {
+        Self::set_all_for_app(db, app, resource_types, false, force)
+    }
+
+    /// 批量设置资源在指定应用中的启用状态（单个资源失败记入结果，不中断整批）
+    ///
+    /// 禁用时跳过锁定的资源，除非 `force`
+    fn set_all_for_app(
+        db: &Arc<Database>,
+        app: &AppType,
+        resource_types: &[ResourceType],
+        enabled: bool,
+        force: bool,
+    ) -> Result<BulkToggleResult, AppError> {
+        let mut result = BulkToggleResult::default();
+        let mut seen = HashSet::new();
+
+        for &resource_type in resource_types {
+            if !seen.insert(resource_type) {
+                continue;
+            }
+            if !Self::supports_app(resource_type.into(), app) {
+                log::info!("批量切换跳过 {resource_type}：{} 不支持", app.as_str());
+                continue;
+            }
+
+            let locked = if enabled || force {
+                HashSet::new()
+            } else {
+                ResourceLockService::locked_ids(db, resource_type.into())?
+            };
+            if !locked.is_empty() {
+                log::info!("批量禁用跳过 {} 个已锁定的 {resource_type}", locked.len());
+            }
+
+            match resource_type {
+                ResourceType::Command => {
+                    for command in db.get_all_installed_commands()?.values() {
+                        if locked.contains(&command.id) {
+                            continue;
+                        }
+                        if command.apps.is_enabled_for(app) != enabled {
+                            let toggled = CommandService::toggle_app(db, &command.id, app, enabled);
+                            result.record(resource_type, Some(&command.id), toggled);
+                        }
+                    }
+                }
+                ResourceType::Agent => {
+                    for agent in db.get_all_installed_agents()?.values() {
+                        if locked.contains(&agent.id) {
+                            continue;
+                        }
+                        if agent.apps.is_enabled_for(app.as_str()) != enabled {
+                            let toggled = AgentService::toggle_app(db, &agent.id, app, enabled);
+                            result.record(resource_type, Some(&agent.id), toggled);
+                        }
+                    }
+                }
+                ResourceType::Hook => {
+                    // Hooks 合并写入同一个 settings.json，先批量更新启用状态，最后只同步一次
+                    let before = result.changed;
+                    for hook in db.get_all_installed_hooks()?.values() {
+                        if hook.apps.is_enabled_for(app.as_str()) == enabled
+                            || locked.contains(&hook.id)
+                        {
+                            continue;
+                        }
+                        let mut apps = hook.apps.clone();
+                        apps.set_enabled_for(app.as_str(), enabled);
+                        let updated = db
+                            .update_hook_apps(&hook.id, &apps)
+                            .map(|_| ())
+                            .map_err(anyhow::Error::from);
+                        result.record(resource_type, Some(&hook.id), updated);
+                    }
+                    if result.changed > before {
+                        if let Err(e) = HookService::sync_to_app(db, app) {
+                            result.failed.push(SkippedResource {
+                                resource_type: resource_type.into(),
+                                id: None,
+                                reason: format!("同步到 {} 失败: {e}", app.as_str()),
+                            });
+                        }
+                    }
+                }
+                ResourceType::Skill => {
+                    for skill in db.get_all_installed_skills()?.values() {
+                        if locked.contains(&skill.id) {
+                            continue;
+                        }
+                        if skill.apps.is_enabled_for(app) != enabled {
+                            let toggled = SkillService::toggle_app(db, &skill.id, app, enabled);
+                            result.record(resource_type, Some(&skill.id), toggled);
+                        }
+                    }
+                }
+            }
+        }
+
+        log::info!(
+            "已为 {} 批量{} {} 项资源",
+            app.as_str(),
+            if enabled { "启用" } else { "禁用" },
+            result.changed
+        );
+        Ok(result)
+    }
+
+    /// 资源类型是否支持指定应用
+    ///
+    /// Commands/Agents/Hooks 以各自的 `check_app_*_support` 为准，与前端的支持判断保持一致
+    pub(crate) fn supports_app(resource_type: ConfigTarget, app: &AppType) -> bool {
+        match resource_type {
+            ConfigTarget::Command => command::check_app_commands_support(app),
+            ConfigTarget::Agent => agent::check_app_agents_support(app),
+            ConfigTarget::Hook => hook::check_app_hooks_support(app),
+            ConfigTarget::Skill | ConfigTarget::Mcp => !matches!(app, AppType::OpenClaw),
+        }
+    }
+
+    /// 按资源类型拆分目标应用，返回去重后的受支持应用与被跳过应用的说明
+    pub(crate) fn split_supported_apps(
+        resource_type: ResourceType,
+        apps: Vec<AppType>,
+    ) -> (Vec<AppType>, Vec<String>) {
+        let mut supported: Vec<AppType> = Vec::new();
+        let mut skipped = Vec::new();
+        for app in apps {
+            if supported.contains(&app) {
+                continue;
+            }
+            if Self::supports_app(resource_type.into(), &app) {
+                supported.push(app);
+            } else {
+                let note = format!("{} 不支持 {resource_type}", app.as_str());
+                if !skipped.contains(&note) {
+                    skipped.push(note);
+                }
+            }
+        }
+        (supported, skipped)
+    }
+
+    /// 一次安装并启用到多个应用（Commands/Agents/Hooks/Skills 共用）
+    ///
+    /// 不支持该资源类型的应用被跳过；SSOT 只在第一个应用上安装一次，安装失败直接返回错误。
+    /// 之后逐个启用其余应用，单个应用启用失败不会中断，也不会回滚已完成的安装，
+    /// 而是记录到 `failed` 中，`enabled_apps` 只包含实际启用成功的应用
+    pub(crate) async fn install_for_apps<T, Fut>(
+        resource_type: ResourceType,
+        apps: Vec<AppType>,
+        install: impl FnOnce(AppType) -> Fut,
+        mut enable: impl FnMut(&T, &AppType) -> anyhow::Result<()>,
+        reload: impl FnOnce(&T) -> Option<T>,
+    ) -> anyhow::Result<MultiAppInstallResult<T>>
+    where
+        Fut: std::future::Future<Output = anyhow::Result<T>>,
+    {
+        let (apps, skipped) = Self::split_supported_apps(resource_type, apps);
+        let Some((first, rest)) = apps.split_first() else {
+            return Err(anyhow::anyhow!("没有支持 {resource_type} 的目标应用"));
+        };
+
+        let installed = install(first.clone()).await?;
+        let mut enabled_apps = vec![first.as_str().to_string()];
+        let mut failed = Vec::new();
+        for app in rest {
+            match enable(&installed, app) {
+                Ok(()) => enabled_apps.push(app.as_str().to_string()),
+                Err(e) => {
+                    log::warn!("为 {} 启用 {resource_type} 失败: {e}", app.as_str());
+                    failed.push(format!("{}: {e}", app.as_str()));
+                }
+            }
+        }
+        let installed = reload(&installed).unwrap_or(installed);
+
+        Ok(MultiAppInstallResult {
+            installed,
+            enabled_apps,
+            skipped,
+            failed,
+        })
+    }
+
+    /// 列出应用目录中没有 SSOT 对应文件的 Commands/Agents 文件
+    ///
+    /// 与 `scan_unmanaged`（按数据库记录判断）不同，这里直接比较应用目录与 SSOT 目录，
+    /// 便于用户决定导入或忽略。Hooks 合并写入 settings.json、Skills 以目录为单位，不在此列
+    pub fn list_extra_app_files(resource_type: ResourceType) -> Result<Vec<ExtraFile>, AppError> {
+        let to_app_error = |e: anyhow::Error| AppError::Message(e.to_string());
+        let apps = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+        let (ssot_dir, app_dirs) = match resource_type {
+            ResourceType::Command => (
+                CommandService::get_ssot_dir().map_err(to_app_error)?,
+                apps.into_iter()
+                    .filter_map(|app| {
+                        let dir = CommandService::get_app_commands_dir(&app).ok()?;
+                        Some((app, dir))
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            ResourceType::Agent => (
+                AgentService::get_ssot_dir().map_err(to_app_error)?,
+                apps.into_iter()
+                    .filter_map(|app| {
+                        let dir = AgentService::get_app_agents_dir(&app).ok()?;
+                        Some((app, dir))
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            other => {
+                return Err(AppError::InvalidInput(format!(
+                    "{other} 不以独立文件同步到应用目录，无法列出额外文件"
+                )));
+            }
+        };
+
+        let mut extra = Vec::new();
+        for (app, app_dir) in app_dirs {
+            Self::collect_extra_files(&app_dir, &app_dir, &ssot_dir, &app, &mut extra);
+        }
+        extra.sort_by(|a, b| a.app.cmp(&b.app).then_with(|| a.id.cmp(&b.id)));
+
+        Ok(extra)
+    }
+
+    /// 递归收集应用目录中 SSOT 不存在的 .md 文件（跳过隐藏项与不可读目录）
+    fn collect_extra_files(
+        current_dir: &Path,
+        app_dir: &Path,
+        ssot_dir: &Path,
+        app: &AppType,
+        extra: &mut Vec<ExtraFile>,
+    ) {
+        let Ok(entries) = fs::read_dir(current_dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            let path = entry.path();
+            if path.is_dir() {
+                Self::collect_extra_files(&path, app_dir, ssot_dir, app, extra);
+                continue;
+            }
+            if path.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+
+            let relative = path.strip_prefix(app_dir).unwrap_or(&path);
+            if ssot_dir.join(relative).exists() {
+                continue;
+            }
+
+            let metadata = entry.metadata().ok();
+            extra.push(ExtraFile {
+                app: app.as_str().to_string(),
+                id: relative
+                    .with_extension("")
+                    .to_string_lossy()
+                    .replace('\\', "/"),
+                path: path.to_string_lossy().to_string(),
+                size: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+                modified_at: metadata
+                    .and_then(|m| m.modified().ok())
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs() as i64),
+            });
+        }
+    }
+
+    /// 列出配置目录被覆盖的应用及覆盖路径
+    ///
+    /// 覆盖设置后容易被遗忘，导致文件写到了意料之外的位置
+    pub fn override_report() -> Vec<OverrideInfo> {
+        AppType::all()
+            .filter_map(|app| {
+                let dir = match app {
+                    AppType::Claude => crate::settings::get_claude_override_dir(),
+                    AppType::Codex => crate::settings::get_codex_override_dir(),
+                    AppType::Gemini => crate::settings::get_gemini_override_dir(),
+                    AppType::OpenCode => crate::settings::get_opencode_override_dir(),
+                    AppType::OpenClaw => crate::settings::get_openclaw_override_dir(),
+                    AppType::Hermes => crate::settings::get_hermes_override_dir(),
+                }?;
+                Some(OverrideInfo {
+                    app: app.as_str().to_string(),
+                    override_path: dir.to_string_lossy().to_string(),
+                    exists: dir.exists(),
+                })
+            })
+            .collect()
+    }
+
+    /// 列出每个应用、每类资源实际使用的 SSOT 与应用侧路径
+    ///
+    /// 用于排查"文件没有出现"一类问题：目录覆盖、主目录异常、权限不足等一目了然。
+    /// 应用不支持的资源类型不会出现在报告中
+    pub fn path_report() -> PathReport {
+        let config_dir = crate::config::get_app_config_dir();
+        let resource_types = [
+            ConfigTarget::Skill,
+            ConfigTarget::Command,
+            ConfigTarget::Agent,
+            ConfigTarget::Hook,
+            ConfigTarget::Mcp,
+        ];
+
+        let mut entries = Vec::new();
+        for app in AppType::all() {
+            for resource_type in resource_types {
+                if !Self::supports_app(resource_type, &app) {
+                    continue;
+                }
+
+                let (ssot, app_path, error) = match Self::resolve_paths(resource_type, &app) {
+                    Ok((ssot, app_path)) => (
+                        Some(Self::probe_path(&ssot)),
+                        app_path.as_deref().map(Self::probe_path),
+                        None,
+                    ),
+                    Err(e) => (None, None, Some(e.to_string())),
+                };
+
+                entries.push(PathReportEntry {
+                    app: app.as_str().to_string(),
+                    resource_type,
+                    ssot,
+                    app_path,
+                    error,
+                });
+            }
+        }
+
+        PathReport {
+            home_dir: dirs::home_dir().map(|p| p.to_string_lossy().to_string()),
+            config_dir: Self::probe_path(&config_dir),
+            entries,
+            overrides: Self::override_report(),
+        }
+    }
+
+    /// 解析资源的 SSOT 路径与应用侧路径
+    fn resolve_paths(
+        resource_type: ConfigTarget,
+        app: &AppType,
+    ) -> anyhow::Result<(PathBuf, Option<PathBuf>)> {
+        let paths = match resource_type {
+            ConfigTarget::Skill => (
+                SkillService::get_ssot_dir()?,
+                Some(SkillService::get_app_skills_dir(app)?),
+            ),
+            ConfigTarget::Command => (
+                CommandService::get_ssot_dir()?,
+                Some(CommandService::get_app_commands_dir(app)?),
+            ),
+            ConfigTarget::Agent => (
+                AgentService::get_ssot_dir()?,
+                Some(AgentService::get_app_agents_dir(app)?),
+            ),
+            ConfigTarget::Hook => (
+                HookService::get_ssot_dir()?,
+                Some(HookService::get_app_settings_path(app)?),
+            ),
+            ConfigTarget::Mcp => {
+                let app_path = match app {
+                    AppType::Claude => Some(crate::config::get_claude_mcp_path()),
+                    AppType::Codex => Some(crate::codex_config::get_codex_config_path()),
+                    AppType::Gemini => Some(crate::gemini_config::get_gemini_settings_path()),
+                    AppType::OpenCode => Some(crate::opencode_config::get_opencode_config_path()),
+                    AppType::Hermes => Some(crate::hermes_config::get_hermes_config_path()),
+                    AppType::OpenClaw => None,
+                };
+                (
+                    crate::config::get_app_config_dir().join("cc-switch.db"),
+                    app_path,
+                )
+            }
+        };
+        Ok(paths)
+    }
+
+    /// 检查路径是否存在、是否可写
+    ///
+    /// 只读取元数据中的只读标记，不在用户目录中创建或打开任何文件；
+    /// 路径不存在时检查最近的已存在上级目录，即"能否被创建"
+    fn probe_path(path: &Path) -> PathProbe {
+        let exists = path.exists();
+        let writable = path
+            .ancestors()
+            .find(|p| p.exists())
+            .and_then(|p| fs::metadata(p).ok())
+            .is_some_and(|meta| !meta.permissions().readonly());
+
+        PathProbe {
+            path: path.to_string_lossy().to_string(),
+            exists,
+            writable,
+        }
+    }
+
+    /// 记录启动时完整性校验的结果
+    pub fn set_startup_integrity_issues(issues: Vec<IntegrityIssue>) {
+        *STARTUP_INTEGRITY_ISSUES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = issues;
+    }
+
+    /// 获取启动时完整性校验发现的问题（未开启启动校验时为空）
+    pub fn startup_integrity_issues() -> Vec<IntegrityIssue> {
+        STARTUP_INTEGRITY_ISSUES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// 校验 Commands/Agents/Hooks 的 SSOT 文件完整性
+    ///
+    /// 只读、仅本地计算哈希，不访问网络；没有记录哈希的资源会被跳过。
+    /// 同时报告 requires_env 中声明但未设置的环境变量，以及事件类型无法识别的 Hook
+    pub fn verify_integrity(db: &Arc<Database>) -> Result<Vec<IntegrityIssue>, AppError> {
+        let to_app_error = |e: anyhow::Error| AppError::Message(e.to_string());
+        let mut issues = Vec::new();
+
+        let command_dir = CommandService::get_ssot_dir().map_err(to_app_error)?;
+        for command in db.get_all_installed_commands()?.values() {
+            let path = command_dir.join(CommandService::id_to_relative_path(&command.id));
+            issues.extend(Self::check_file_integrity(
+                ResourceType::Command,
+                &command.id,
+                &path,
+                command.file_hash.as_deref(),
+            ));
+            issues.extend(Self::check_required_env(
+                ResourceType::Command,
+                &command.id,
+                &command.requires_env,
+            ));
+        }
+
+        let agent_dir = AgentService::get_ssot_dir().map_err(to_app_error)?;
+        for agent in db.get_all_installed_agents()?.values() {
+            let path = agent_dir.join(AgentService::id_to_relative_path(&agent.id));
+            issues.extend(Self::check_file_integrity(
+                ResourceType::Agent,
+                &agent.id,
+                &path,
+                agent.file_hash.as_deref(),
+            ));
+            issues.extend(Self::check_required_env(
+                ResourceType::Agent,
+                &agent.id,
+                &agent.requires_env,
+            ));
+        }
+
+        let hook_dir = HookService::get_ssot_dir().map_err(to_app_error)?;
+        for hook in db.get_all_installed_hooks()?.values() {
+            let path = hook_dir.join(HookService::id_to_relative_path(&hook.id));
+            issues.extend(Self::check_file_integrity(
+                ResourceType::Hook,
+                &hook.id,
+                &path,
+                hook.file_hash.as_deref(),
+            ));
+            issues.extend(Self::check_required_env(
+                ResourceType::Hook,
+                &hook.id,
+                &hook.requires_env,
+            ));
+        }
+        for (id, raw) in db.get_hooks_with_unknown_event_type()? {
+            issues.push(IntegrityIssue {
+                resource_type: ResourceType::Hook,
+                id,
+                kind: IntegrityIssueKind::UnknownEventType,
+                expected_hash: None,
+                actual_hash: None,
+                message: Some(format!("无法识别的事件类型: {raw}")),
+            });
+        }
+
+        Ok(issues)
+    }
+
+    /// 检查资源声明的环境变量是否都已设置
+    fn check_required_env(
+        resource_type: ResourceType,
+        id: &str,
+        required: &[String],
+    ) -> Option<IntegrityIssue> {
+        let missing = env_checker::find_missing_env_vars(required);
+        (!missing.is_empty()).then(|| IntegrityIssue {
+            resource_type,
+            id: id.to_string(),
+            kind: IntegrityIssueKind::MissingEnv,
+            expected_hash: None,
+            actual_hash: None,
+            message: Some(format!("缺少环境变量: {}", missing.join(", "))),
+        })
+    }
+
+    /// 校验单个 SSOT 文件
+    ///
+    /// 安装时记录的可能是 GitHub blob SHA（40 位）或本地 SHA-256（64 位），
+    /// 按记录哈希的长度选择对应算法重新计算
+    fn check_file_integrity(
+        resource_type: ResourceType,
+        id: &str,
+        path: &Path,
+        stored_hash: Option<&str>,
+    ) -> Option<IntegrityIssue> {
+        let stored_hash = stored_hash?;
+        let issue = |kind, actual_hash, message| IntegrityIssue {
+            resource_type,
+            id: id.to_string(),
+            kind,
+            expected_hash: Some(stored_hash.to_string()),
+            actual_hash,
+            message,
+        };
+
+        let content = match fs::read(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Some(issue(IntegrityIssueKind::MissingFile, None, None));
+            }
+            Err(e) => {
+                return Some(issue(
+                    IntegrityIssueKind::Unreadable,
+                    None,
+                    Some(e.to_string()),
+                ));
+            }
+        };
+
+        let actual = Self::compute_hash_like(&content, stored_hash);
+
+        if actual.eq_ignore_ascii_case(stored_hash) {
+            None
+        } else {
+            Some(issue(IntegrityIssueKind::HashMismatch, Some(actual), None))
+        }
+    }
+
+    /// 按已存哈希的长度选择算法（40 位 blob SHA / 64 位 SHA-256）计算内容哈希
+    fn compute_hash_like(content: &[u8], stored_hash: &str) -> String {
+        if stored_hash.len() == 40 {
+            GitHubApiService::compute_blob_sha(content)
+        } else {
+            let mut hasher = Sha256::new();
+            hasher.update(content);
+            format!("{:x}", hasher.finalize())
+        }
+    }
+
+    fn content_matches_hash(content: &[u8], stored_hash: &str) -> bool {
+        Self::compute_hash_like(content, stored_hash).eq_ignore_ascii_case(stored_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{create_test_command, create_test_hook, TempHome};
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn hook_sync_status_accepts_blob_sha_baseline() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let content = "{\"PreToolUse\": []}\n";
+        let path = HookService::get_ssot_dir()
+            .unwrap()
+            .join(HookService::id_to_relative_path("lint"));
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+
+        let mut hook = create_test_hook("lint", "", "lint");
+        hook.file_hash = Some(GitHubApiService::compute_blob_sha(content.as_bytes()));
+        db.save_hook(&hook).unwrap();
+
+        let status = ConfigService::hook_sync_status(&db).unwrap();
+        assert_eq!((status.in_sync, status.drifted), (1, 0));
+
+        fs::write(&path, "{\"PreToolUse\": [1]}\n").unwrap();
+        let status = ConfigService::hook_sync_status(&db).unwrap();
+        assert_eq!((status.in_sync, status.drifted), (0, 1));
+    }
+
+    #[test]
+    fn tally_counts_each_resource_once() {
+        let issues = vec![
+            ("a".to_string(), SyncIssue::Drifted),
+            ("a".to_string(), SyncIssue::Conflicted),
+            ("b".to_string(), SyncIssue::Conflicted),
+            ("b".to_string(), SyncIssue::Conflicted),
+            ("c".to_string(), SyncIssue::Orphaned),
+        ];
+
+        let status = ResourceSyncStatus::tally(5, issues);
+
+        assert_eq!(status.total, 5);
+        assert_eq!(status.in_sync, 2);
+        assert_eq!(status.drifted, 1);
+        assert_eq!(status.conflicted, 2);
+        assert_eq!(status.orphaned, 1);
+    }
+
+    #[test]
+    fn resource_counts_tally_enabled_rows_per_app() {
+        let db = Arc::new(Database::memory().unwrap());
+        {
+            let conn = db.conn.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO commands (id, name, filename, enabled_claude, enabled_codex)
+                     VALUES ('a', 'a', 'a', 1, 0), ('b', 'b', 'b', 1, 1), ('c', 'c', 'c', 0, 0);
+                 INSERT INTO hooks (id, name, filename, event_type, rules_json, enabled, enabled_claude)
+                     VALUES ('h1', 'h1', 'h1', 'PreToolUse', '[]', 1, 1),
+                            ('h2', 'h2', 'h2', 'PreToolUse', '[]', 0, 1);",
+            )
+            .unwrap();
+        }
+
+        let counts = ConfigService::resource_counts(&db).unwrap();
+
+        let claude = &counts[&AppType::Claude];
+        assert_eq!(claude.commands, 2);
+        // 全局禁用的 Hook 不计入
+        assert_eq!(claude.hooks, 1);
+        assert_eq!(counts[&AppType::Codex].commands, 1);
+        assert_eq!(counts[&AppType::OpenCode], ResourceCounts::default());
+        assert!(!counts.contains_key(&AppType::OpenClaw));
+    }
+
+    #[test]
+    #[serial]
+    fn enable_all_for_app_reports_failed_items() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        for id in ["present", "missing"] {
+            let mut command = create_test_command(id, "", id);
+            command.apps.claude = false;
+            db.save_command(&command).unwrap();
+        }
+        let ssot_dir = CommandService::get_ssot_dir().unwrap();
+        fs::write(ssot_dir.join("present.md"), "# present\n").unwrap();
+
+        let types = [ResourceType::Command, ResourceType::Command];
+        let result = ConfigService::enable_all_for_app(&db, &AppType::Claude, &types).unwrap();
+
+        assert_eq!(result.changed, 1);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].id.as_deref(), Some("missing"));
+        let app_dir = CommandService::get_app_commands_dir(&AppType::Claude).unwrap();
+        assert!(app_dir.join("present.md").exists());
+        let present = db.get_installed_command("present").unwrap().unwrap();
+        assert!(present.apps.claude);
+
+        let result =
+            ConfigService::disable_all_for_app(&db, &AppType::Claude, &types, false).unwrap();
+        assert_eq!(result.changed, 1);
+        assert!(result.failed.is_empty());
+        assert!(!app_dir.join("present.md").exists());
+    }
+
+    #[test]
+    #[serial]
+    fn disable_all_for_app_skips_locked_items_unless_forced() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let ssot_dir = CommandService::get_ssot_dir().unwrap();
+        for id in ["locked", "free"] {
+            db.save_command(&create_test_command(id, "", id)).unwrap();
+            fs::write(ssot_dir.join(format!("{id}.md")), format!("# {id}\n")).unwrap();
+            CommandService::copy_to_app(id, &AppType::Claude).unwrap();
+        }
+        ResourceLockService::set_locked(&db, ConfigTarget::Command, "locked", true).unwrap();
+
+        let types = [ResourceType::Command];
+        let result =
+            ConfigService::disable_all_for_app(&db, &AppType::Claude, &types, false).unwrap();
+        assert_eq!(result.changed, 1);
+        let is_enabled = |id: &str| db.get_installed_command(id).unwrap().unwrap().apps.claude;
+        assert!(is_enabled("locked"));
+        assert!(!is_enabled("free"));
+
+        let result =
+            ConfigService::disable_all_for_app(&db, &AppType::Claude, &types, true).unwrap();
+        assert_eq!(result.changed, 1);
+        assert!(!is_enabled("locked"));
+    }
+
+    #[test]
+    fn check_file_integrity_accepts_blob_sha_and_sha256() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("demo.md");
+        fs::write(&path, "hello\n").expect("write file");
+
+        let blob_sha = "ce013625030ba8dba906f756967f9e9ca394464a";
+        let sha256 = CommandService::compute_hash("hello\n");
+        for stored in [blob_sha, sha256.as_str()] {
+            let issue = ConfigService::check_file_integrity(
+                ResourceType::Command,
+                "demo",
+                &path,
+                Some(stored),
+            );
+            assert!(issue.is_none(), "hash {stored} should match");
+        }
+
+        assert!(
+            ConfigService::check_file_integrity(ResourceType::Command, "demo", &path, None)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn check_file_integrity_reports_mismatch_and_missing_file() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("demo.md");
+        fs::write(&path, "edited\n").expect("write file");
+
+        let stored = CommandService::compute_hash("hello\n");
+        let issue =
+            ConfigService::check_file_integrity(ResourceType::Agent, "demo", &path, Some(&stored))
+                .expect("mismatch should be reported");
+        assert_eq!(issue.kind, IntegrityIssueKind::HashMismatch);
+        assert_eq!(
+            issue.actual_hash,
+            Some(CommandService::compute_hash("edited\n"))
+        );
+
+        let missing = dir.path().join("missing.md");
+        let issue = ConfigService::check_file_integrity(
+            ResourceType::Hook,
+            "missing",
+            &missing,
+            Some(&stored),
+        )
+        .expect("missing file should be reported");
+        assert_eq!(issue.kind, IntegrityIssueKind::MissingFile);
+        assert!(issue.actual_hash.is_none());
+    }
+
+    #[test]
+    fn supports_app_matches_resource_apps() {
+        let cases = [
+            (ConfigTarget::Command, AppType::Claude, true),
+            (ConfigTarget::Command, AppType::Codex, false),
+            (ConfigTarget::Agent, AppType::Gemini, false),
+            (ConfigTarget::Hook, AppType::Codex, false),
+            (ConfigTarget::Hook, AppType::OpenCode, false),
+            (ConfigTarget::Mcp, AppType::OpenCode, true),
+            (ConfigTarget::Skill, AppType::OpenClaw, false),
+        ];
+        for (resource_type, app, expected) in cases {
+            assert_eq!(
+                ConfigService::supports_app(resource_type, &app),
+                expected,
+                "{resource_type} / {}",
+                app.as_str()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn install_for_apps_collects_enable_failures() {
+        let mut attempted = Vec::new();
+        let result = ConfigService::install_for_apps(
+            ResourceType::Skill,
+            vec![
+                AppType::Claude,
+                AppType::Codex,
+                AppType::OpenClaw,
+                AppType::Gemini,
+            ],
+            |app| async move { Ok(format!("installed-for-{}", app.as_str())) },
+            |_: &String, app: &AppType| {
+                attempted.push(app.clone());
+                if *app == AppType::Codex {
+                    Err(anyhow::anyhow!("codex dir is read-only"))
+                } else {
+                    Ok(())
+                }
+            },
+            |_: &String| None,
+        )
+        .await
+        .expect("enable failures must not fail the install");
+
+        assert_eq!(result.installed, "installed-for-claude");
+        assert_eq!(attempted, [AppType::Codex, AppType::Gemini]);
+        assert_eq!(result.enabled_apps, ["claude", "gemini"]);
+        assert_eq!(result.skipped.len(), 1);
+        assert_eq!(result.failed, ["codex: codex dir is read-only"]);
+    }
+
+    #[tokio::test]
+    async fn install_for_apps_fails_without_supported_app_or_on_install_error() {
+        let no_app = ConfigService::install_for_apps(
+            ResourceType::Hook,
+            vec![AppType::Codex, AppType::Gemini],
+            |_| async { Ok(()) },
+            |_: &(), _: &AppType| Ok(()),
+            |_: &()| None,
+        )
+        .await;
+        assert!(no_app.is_err());
+
+        let mut enabled = false;
+        let failed = ConfigService::install_for_apps(
+            ResourceType::Skill,
+            vec![AppType::Claude, AppType::Codex],
+            |_| async { Err::<(), _>(anyhow::anyhow!("download failed")) },
+            |_: &(), _: &AppType| {
+                enabled = true;
+                Ok(())
+            },
+            |_: &()| None,
+        )
+        .await;
+        assert!(failed.is_err());
+        assert!(!enabled);
+    }
+
+    #[test]
+    fn split_supported_apps_dedupes_and_reports_unsupported() {
+        let (supported, skipped) = ConfigService::split_supported_apps(
+            ResourceType::Skill,
+            vec![
+                AppType::Claude,
+                AppType::OpenClaw,
+                AppType::Codex,
+                AppType::Claude,
+                AppType::OpenClaw,
+            ],
+        );
+        assert_eq!(supported, [AppType::Claude, AppType::Codex]);
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].starts_with("openclaw"));
+
+        // Agents 与 check_app_agents_support 一致：Codex/Gemini 被跳过
+        let (supported, skipped) = ConfigService::split_supported_apps(
+            ResourceType::Agent,
+            vec![AppType::Claude, AppType::Codex, AppType::Gemini],
+        );
+        assert_eq!(supported, [AppType::Claude]);
+        assert_eq!(skipped.len(), 2);
+    }
+
+    #[test]
+    fn collect_extra_files_skips_ssot_counterparts() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let app_dir = temp.path().join("app");
+        let ssot_dir = temp.path().join("ssot");
+        for (dir, rel) in [
+            (&app_dir, "managed.md"),
+            (&app_dir, "sc/mine.md"),
+            (&app_dir, "notes.txt"),
+            (&app_dir, ".hidden/secret.md"),
+            (&ssot_dir, "managed.md"),
+        ] {
+            let path = dir.join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, "# demo").unwrap();
+        }
+
+        let mut extra = Vec::new();
+        ConfigService::collect_extra_files(
+            &app_dir,
+            &app_dir,
+            &ssot_dir,
+            &AppType::Claude,
+            &mut extra,
+        );
+
+        assert_eq!(extra.len(), 1);
+        assert_eq!(extra[0].app, "claude");
+        assert_eq!(extra[0].id, "sc/mine");
+        assert_eq!(extra[0].size, 6);
+    }
+
+    #[test]
+    fn db_snapshot_restores_on_next_start_and_rejects_newer_schema() {
+        let temp = tempfile::tempdir().unwrap();
+        let db = Arc::new(Database::memory().unwrap());
+        db.apply_schema_migrations().unwrap();
+
+        let snapshot = temp.path().join("snapshot.db");
+        let version = ConfigService::snapshot_db(&db, &snapshot).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+
+        let pending = temp.path().join(PENDING_RESTORE_FILE);
+        assert_eq!(
+            ConfigService::stage_db_restore(&snapshot, &pending).unwrap(),
+            SCHEMA_VERSION
+        );
+
+        let db_path = temp.path().join("cc-switch.db");
+        fs::write(&db_path, b"current").unwrap();
+        Database::apply_pending_restore(&db_path).unwrap();
+        assert!(!pending.exists());
+        let restored = Connection::open(&db_path).unwrap();
+        assert_eq!(
+            Database::get_user_version(&restored).unwrap(),
+            SCHEMA_VERSION
+        );
+        // 替换前的主库应保留在备份目录
+        let backups = fs::read_dir(temp.path().join("backups")).unwrap().count();
+        assert_eq!(backups, 1);
+
+        let conn = Connection::open(&snapshot).unwrap();
+        Database::set_user_version(&conn, SCHEMA_VERSION + 1).unwrap();
+        drop(conn);
+        assert!(ConfigService::stage_db_restore(&snapshot, &pending).is_err());
+        assert!(!pending.exists());
+    }
+
+    #[test]
+    fn probe_path_reports_missing_paths_via_parent() {
+        let temp = tempfile::tempdir().unwrap();
+        let file = temp.path().join("settings.json");
+        fs::write(&file, "{}").unwrap();
+
+        let existing = ConfigService::probe_path(&file);
+        assert!(existing.exists);
+        assert!(existing.writable);
+
+        let missing = ConfigService::probe_path(&temp.path().join("a").join("b"));
+        assert!(!missing.exists);
+        assert!(missing.writable);
+
+        // 探测不应留下临时文件
+        let leftovers = fs::read_dir(temp.path()).unwrap().count();
+        assert_eq!(leftovers, 1);
+    }
+
+    #[test]
+    fn probe_path_reports_readonly_file_without_touching_it() {
+        let temp = tempfile::tempdir().unwrap();
+        let file = temp.path().join("settings.json");
+        fs::write(&file, "{}").unwrap();
+        let mut perms = fs::metadata(&file).unwrap().permissions();
+        perms.set_readonly(true);
+        fs::set_permissions(&file, perms).unwrap();
+        let modified = fs::metadata(&file).unwrap().modified().unwrap();
+
+        let probe = ConfigService::probe_path(&file);
+        assert!(probe.exists);
+        assert!(!probe.writable);
+        assert_eq!(fs::metadata(&file).unwrap().modified().unwrap(), modified);
+
+        let mut perms = fs::metadata(&file).unwrap().permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        perms.set_readonly(false);
+        fs::set_permissions(&file, perms).unwrap();
+    }
 }
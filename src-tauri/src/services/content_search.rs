@@ -0,0 +1,159 @@
+//! 资源正文搜索
+//!
+//! 用户常记得某个 Command 提示词里的片段，却记不住名称。这里在已安装资源的
+//! SSOT 文件正文（frontmatter 之后）中按不区分大小写的子串查找，返回匹配行及
+//! 上下文。每个文件的匹配数有上限，避免常见词返回过多结果。
+
+use crate::database::Database;
+use crate::services::resource_preview::{strip_frontmatter, ResourcePreviewService};
+use crate::services::update::ResourceType;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::Arc;
+
+/// 每个文件最多返回的匹配行数
+const MAX_MATCHES_PER_FILE: usize = 5;
+
+/// 返回的单行最大字符数
+const MAX_LINE_CHARS: usize = 200;
+
+/// 匹配的一行及其上下文
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineMatch {
+    /// 在文件中的行号（从 1 开始，包含 frontmatter）
+    pub line_number: usize,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+/// 单个资源的搜索结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentHit {
+    pub id: String,
+    pub matches: Vec<LineMatch>,
+    /// 匹配行超过上限，只返回了前几处
+    pub truncated: bool,
+}
+
+/// 截断过长的行（按字符计）
+fn clip(line: &str) -> String {
+    let line = line.trim_end();
+    match line.char_indices().nth(MAX_LINE_CHARS) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+/// 在正文中查找包含 `query`（已转小写）的行
+fn search_body(content: &str, query: &str) -> (Vec<LineMatch>, bool) {
+    let body = strip_frontmatter(content);
+    // 正文之前的行数，用于换算为文件行号
+    let offset = content[..content.len() - body.len()].lines().count();
+    let lines: Vec<&str> = body.lines().collect();
+
+    let mut matches = Vec::new();
+    let mut truncated = false;
+    for (i, line) in lines.iter().enumerate() {
+        if !line.to_lowercase().contains(query) {
+            continue;
+        }
+        if matches.len() == MAX_MATCHES_PER_FILE {
+            truncated = true;
+            break;
+        }
+        matches.push(LineMatch {
+            line_number: offset + i + 1,
+            text: clip(line),
+            before: i.checked_sub(1).map(|j| clip(lines[j])),
+            after: lines.get(i + 1).map(|l| clip(l)),
+        });
+    }
+    (matches, truncated)
+}
+
+pub struct ContentSearchService;
+
+impl ContentSearchService {
+    /// 在已安装资源的正文中搜索（不区分大小写）
+    ///
+    /// 读取失败或 SSOT 文件缺失的资源直接跳过
+    pub fn search_content(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+        query: &str,
+    ) -> Result<Vec<ContentHit>> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Err(anyhow!("搜索内容不能为空"));
+        }
+
+        let ids: Vec<String> = match resource_type {
+            ResourceType::Command => db.get_all_installed_commands()?.into_keys().collect(),
+            ResourceType::Agent => db.get_all_installed_agents()?.into_keys().collect(),
+            ResourceType::Hook => db.get_all_installed_hooks()?.into_keys().collect(),
+            ResourceType::Skill => db.get_all_installed_skills()?.into_keys().collect(),
+        };
+
+        let mut hits = Vec::new();
+        for id in ids {
+            let Ok(path) = ResourcePreviewService::resource_path(db, resource_type, &id) else {
+                continue;
+            };
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let (matches, truncated) = search_body(&content, &query);
+            if !matches.is_empty() {
+                hits.push(ContentHit {
+                    id,
+                    matches,
+                    truncated,
+                });
+            }
+        }
+
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn searches_body_case_insensitively_with_context() {
+        let content = "---\nname: review\ndescription: Review the diff\n---\n\
+                       # Review\n\
+                       Check the DIFF for bugs.\n\
+                       Then summarize.\n";
+
+        let (matches, truncated) = search_body(content, "diff");
+        assert!(!truncated);
+        // frontmatter 中的 description 不参与匹配
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            matches[0],
+            LineMatch {
+                line_number: 6,
+                text: "Check the DIFF for bugs.".to_string(),
+                before: Some("# Review".to_string()),
+                after: Some("Then summarize.".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn limits_matches_per_file() {
+        let content = "todo\n".repeat(MAX_MATCHES_PER_FILE + 2);
+        let (matches, truncated) = search_body(&content, "todo");
+        assert_eq!(matches.len(), MAX_MATCHES_PER_FILE);
+        assert!(truncated);
+        assert_eq!(matches[0].before, None);
+    }
+}
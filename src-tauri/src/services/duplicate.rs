@@ -0,0 +1,484 @@
+//! 重复安装检测与合并
+//!
+//! 同一个 Command/Agent/Skill 从不同仓库安装时会得到不同 ID、内容几乎相同的多份文件。
+//! 这里按规范化后的内容哈希分组，并支持把应用启用状态合并到保留项后卸载其余副本。
+//!
+//! - Commands/Agents：统一换行符、去掉行尾空白和首尾空行后计算 SHA256
+//! - Skills：使用 SSOT 目录的 Merkle 哈希（与 GitHub tree SHA 一致）
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::services::agent::AgentService;
+use crate::services::command::CommandService;
+use crate::services::resource_lock::ResourceLockService;
+use crate::services::skill::SkillService;
+use crate::services::update::ResourceType;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// 重复组中的单个已安装资源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateItem {
+    pub id: String,
+    pub name: String,
+    /// 来源仓库（owner/name），本地导入的资源为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    /// 已启用的应用
+    pub apps: Vec<AppType>,
+}
+
+/// 内容相同的一组已安装资源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub resource_type: ResourceType,
+    pub content_hash: String,
+    pub items: Vec<DuplicateItem>,
+}
+
+/// 合并结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeResult {
+    pub kept_id: String,
+    /// 已卸载的副本
+    pub removed: Vec<String>,
+    /// 从副本合并过来、保留项原本未启用的应用
+    pub newly_enabled: Vec<AppType>,
+    /// 未能卸载的副本及原因（格式 "id: 错误"）
+    pub failed: Vec<String>,
+}
+
+/// 应用启用标记（claude, codex, gemini）
+type AppFlags = [bool; 3];
+
+/// 校验通过后的合并计划
+struct MergePlan {
+    /// 合并后保留项的启用标记
+    flags: AppFlags,
+    /// 保留项原本未启用、合并后启用的应用
+    newly_enabled: Vec<AppType>,
+    /// 去重后待卸载的副本
+    remove: Vec<String>,
+}
+
+const APPS: [AppType; 3] = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+fn enabled_apps(flags: AppFlags) -> Vec<AppType> {
+    APPS.iter()
+        .zip(flags)
+        .filter(|(_, enabled)| *enabled)
+        .map(|(app, _)| app.clone())
+        .collect()
+}
+
+fn repo_label(owner: &Option<String>, name: &Option<String>) -> Option<String> {
+    match (owner, name) {
+        (Some(owner), Some(name)) => Some(format!("{owner}/{name}")),
+        _ => None,
+    }
+}
+
+pub struct DuplicateService;
+
+impl DuplicateService {
+    /// 规范化文本内容：统一换行符、去掉行尾空白和首尾空行
+    pub fn normalize_content(content: &str) -> String {
+        let lines: Vec<&str> = content
+            .lines()
+            .map(|line| line.trim_end())
+            .skip_while(|line| line.is_empty())
+            .collect();
+        let end = lines
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .map_or(0, |i| i + 1);
+        lines[..end].join("\n")
+    }
+
+    /// 按内容哈希分组，只保留包含多个条目的组
+    fn group_by_hash(
+        resource_type: ResourceType,
+        entries: Vec<(String, DuplicateItem)>,
+    ) -> Vec<DuplicateGroup> {
+        let mut by_hash: BTreeMap<String, Vec<DuplicateItem>> = BTreeMap::new();
+        for (hash, item) in entries {
+            by_hash.entry(hash).or_default().push(item);
+        }
+
+        by_hash
+            .into_iter()
+            .filter(|(_, items)| items.len() > 1)
+            .map(|(content_hash, mut items)| {
+                items.sort_by(|a, b| a.id.cmp(&b.id));
+                DuplicateGroup {
+                    resource_type,
+                    content_hash,
+                    items,
+                }
+            })
+            .collect()
+    }
+
+    /// 查找内容重复的已安装资源
+    ///
+    /// 仅支持 Commands/Agents/Skills；SSOT 文件缺失或无法读取的条目不参与分组
+    pub fn find_duplicates(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+    ) -> Result<Vec<DuplicateGroup>> {
+        let mut entries = Vec::new();
+
+        match resource_type {
+            ResourceType::Command => {
+                for command in db.get_all_installed_commands()?.into_values() {
+                    let Ok(content) = CommandService::get_command_content(&command.id) else {
+                        continue;
+                    };
+                    let hash = Self::text_hash(&content);
+                    entries.push((
+                        hash,
+                        DuplicateItem {
+                            repo: repo_label(&command.repo_owner, &command.repo_name),
+                            apps: enabled_apps([
+                                command.apps.claude,
+                                command.apps.codex,
+                                command.apps.gemini,
+                            ]),
+                            id: command.id,
+                            name: command.name,
+                        },
+                    ));
+                }
+            }
+            ResourceType::Agent => {
+                for agent in db.get_all_installed_agents()?.into_values() {
+                    let Ok(content) = AgentService::get_agent_content(&agent.id) else {
+                        continue;
+                    };
+                    let hash = Self::text_hash(&content);
+                    entries.push((
+                        hash,
+                        DuplicateItem {
+                            repo: repo_label(&agent.repo_owner, &agent.repo_name),
+                            apps: enabled_apps([
+                                agent.apps.claude,
+                                agent.apps.codex,
+                                agent.apps.gemini,
+                            ]),
+                            id: agent.id,
+                            name: agent.name,
+                        },
+                    ));
+                }
+            }
+            ResourceType::Skill => {
+                let ssot_dir = SkillService::get_ssot_dir()?;
+                for skill in db.get_all_installed_skills()?.into_values() {
+                    let dir = ssot_dir.join(&skill.directory);
+                    if !dir.is_dir() {
+                        continue;
+                    }
+                    let Ok(hash) = SkillService::compute_directory_hash(&dir) else {
+                        continue;
+                    };
+                    entries.push((
+                        hash,
+                        DuplicateItem {
+                            repo: repo_label(&skill.repo_owner, &skill.repo_name),
+                            apps: enabled_apps([
+                                skill.apps.claude,
+                                skill.apps.codex,
+                                skill.apps.gemini,
+                            ]),
+                            id: skill.id,
+                            name: skill.name,
+                        },
+                    ));
+                }
+            }
+            other => return Err(anyhow!("{other} 不支持重复检测")),
+        }
+
+        Ok(Self::group_by_hash(resource_type, entries))
+    }
+
+    /// 校验副本并汇总启用标记
+    ///
+    /// `lookup` 返回资源的启用标记与规范化内容哈希；保留项或任一副本不存在、
+    /// 副本内容与保留项不同时直接报错，此时尚未做任何修改
+    fn plan_merge(
+        keep_id: &str,
+        remove_ids: &[String],
+        lookup: impl Fn(&str) -> Result<Option<(AppFlags, String)>>,
+    ) -> Result<MergePlan> {
+        let (keep_flags, keep_hash) =
+            lookup(keep_id)?.ok_or_else(|| anyhow!("保留项不存在: {keep_id}"))?;
+        let mut plan = MergePlan {
+            flags: keep_flags,
+            newly_enabled: Vec::new(),
+            remove: Vec::new(),
+        };
+
+        for id in remove_ids {
+            if id == keep_id || plan.remove.contains(id) {
+                continue;
+            }
+            let (dup_flags, dup_hash) = lookup(id)?.ok_or_else(|| anyhow!("副本不存在: {id}"))?;
+            if dup_hash != keep_hash {
+                return Err(anyhow!("{id} 的内容与 {keep_id} 不同，不能合并"));
+            }
+            for (merged, dup) in plan.flags.iter_mut().zip(dup_flags) {
+                *merged |= dup;
+            }
+            plan.remove.push(id.clone());
+        }
+
+        plan.newly_enabled = APPS
+            .iter()
+            .zip(plan.flags.iter().zip(keep_flags))
+            .filter(|(_, (merged, before))| **merged && !*before)
+            .map(|(app, _)| app.clone())
+            .collect();
+
+        Ok(plan)
+    }
+
+    /// 保留项启用完成后逐个卸载副本，单个失败不影响其余副本
+    fn remove_each(
+        keep_id: &str,
+        plan: MergePlan,
+        uninstall: impl Fn(&str) -> Result<()>,
+    ) -> MergeResult {
+        let mut result = MergeResult {
+            kept_id: keep_id.to_string(),
+            newly_enabled: plan.newly_enabled,
+            ..Default::default()
+        };
+        for id in plan.remove {
+            match uninstall(&id) {
+                Ok(()) => result.removed.push(id),
+                Err(e) => result.failed.push(format!("{id}: {e}")),
+            }
+        }
+        result
+    }
+
+    /// 文本资源的规范化内容哈希
+    fn text_hash(content: &str) -> String {
+        CommandService::compute_hash(&Self::normalize_content(content))
+    }
+
+    /// 合并重复资源：把副本的应用启用状态合并到 `keep_id`，然后卸载副本
+    ///
+    /// 所有副本的内容必须与保留项相同；先为保留项启用并复制到新增的应用，
+    /// 成功后才卸载副本。副本中有锁定资源且未传 `force` 时整批取消。
+    /// Commands/Agents 卸载后进入回收站，可以恢复
+    pub fn merge_duplicates(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+        keep_id: &str,
+        remove_ids: &[String],
+        force: bool,
+    ) -> Result<MergeResult> {
+        ResourceLockService::ensure_unlocked(
+            db,
+            resource_type.into(),
+            remove_ids.iter().map(String::as_str),
+            force,
+        )?;
+
+        let result = match resource_type {
+            ResourceType::Command => {
+                let plan = Self::plan_merge(keep_id, remove_ids, |id| {
+                    let Some(command) = db.get_installed_command(id)? else {
+                        return Ok(None);
+                    };
+                    let hash = Self::text_hash(&CommandService::get_command_content(id)?);
+                    let apps = command.apps;
+                    Ok(Some(([apps.claude, apps.codex, apps.gemini], hash)))
+                })?;
+                let mut keep = db
+                    .get_installed_command(keep_id)?
+                    .ok_or_else(|| anyhow!("Command not found: {keep_id}"))?;
+                [keep.apps.claude, keep.apps.codex, keep.apps.gemini] = plan.flags;
+                db.update_command_apps(keep_id, &keep.apps)?;
+                for app in &plan.newly_enabled {
+                    CommandService::copy_to_app(keep_id, app)?;
+                }
+                Self::remove_each(keep_id, plan, |id| CommandService::uninstall(db, id))
+            }
+            ResourceType::Agent => {
+                let plan = Self::plan_merge(keep_id, remove_ids, |id| {
+                    let Some(agent) = db.get_installed_agent(id)? else {
+                        return Ok(None);
+                    };
+                    let hash = Self::text_hash(&AgentService::get_agent_content(id)?);
+                    let apps = agent.apps;
+                    Ok(Some(([apps.claude, apps.codex, apps.gemini], hash)))
+                })?;
+                let mut keep = db
+                    .get_installed_agent(keep_id)?
+                    .ok_or_else(|| anyhow!("Agent not found: {keep_id}"))?;
+                [keep.apps.claude, keep.apps.codex, keep.apps.gemini] = plan.flags;
+                db.update_agent_apps(keep_id, &keep.apps)?;
+                for app in &plan.newly_enabled {
+                    AgentService::copy_to_app(keep_id, app)?;
+                }
+                Self::remove_each(keep_id, plan, |id| AgentService::uninstall(db, id))
+            }
+            ResourceType::Skill => {
+                let ssot_dir = SkillService::get_ssot_dir()?;
+                let plan = Self::plan_merge(keep_id, remove_ids, |id| {
+                    let Some(skill) = db.get_installed_skill(id)? else {
+                        return Ok(None);
+                    };
+                    let hash =
+                        SkillService::compute_directory_hash(&ssot_dir.join(&skill.directory))?;
+                    let apps = skill.apps;
+                    Ok(Some(([apps.claude, apps.codex, apps.gemini], hash)))
+                })?;
+                let mut keep = db
+                    .get_installed_skill(keep_id)?
+                    .ok_or_else(|| anyhow!("Skill not found: {keep_id}"))?;
+                [keep.apps.claude, keep.apps.codex, keep.apps.gemini] = plan.flags;
+                db.update_skill_apps(keep_id, &keep.apps)?;
+                for app in &plan.newly_enabled {
+                    SkillService::copy_to_app(&keep.directory, app)?;
+                }
+                Self::remove_each(keep_id, plan, |id| {
+                    SkillService::uninstall(db, id).map(|_| ())
+                })
+            }
+            other => return Err(anyhow!("{other} 不支持合并重复项")),
+        };
+
+        log::info!(
+            "合并重复{}: 保留 {}，卸载 {} 个副本，{} 个失败",
+            resource_type,
+            result.kept_id,
+            result.removed.len(),
+            result.failed.len()
+        );
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::config::ConfigTarget;
+    use crate::test_support::create_test_command;
+
+    fn item(id: &str) -> DuplicateItem {
+        DuplicateItem {
+            id: id.to_string(),
+            name: id.to_string(),
+            repo: None,
+            apps: vec![],
+        }
+    }
+
+    #[test]
+    fn normalize_ignores_line_endings_and_trailing_whitespace() {
+        let a = "---\r\ndescription: Review  \r\n---\r\n\r\nBody\r\n\r\n";
+        let b = "\n---\ndescription: Review\n---\n\nBody";
+        assert_eq!(
+            DuplicateService::normalize_content(a),
+            DuplicateService::normalize_content(b)
+        );
+        assert_ne!(
+            DuplicateService::normalize_content("Body"),
+            DuplicateService::normalize_content("Body 2")
+        );
+    }
+
+    #[test]
+    fn group_by_hash_keeps_only_duplicates() {
+        let groups = DuplicateService::group_by_hash(
+            ResourceType::Command,
+            vec![
+                ("h1".to_string(), item("b/review")),
+                ("h2".to_string(), item("solo")),
+                ("h1".to_string(), item("a/review")),
+            ],
+        );
+
+        assert_eq!(groups.len(), 1);
+        let ids: Vec<_> = groups[0].items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["a/review", "b/review"]);
+    }
+
+    #[test]
+    fn plan_merge_merges_flags_and_rejects_different_content() {
+        let lookup = |id: &str| {
+            Ok(match id {
+                "keep" => Some(([true, false, false], "h1".to_string())),
+                "dup-a" => Some(([false, true, false], "h1".to_string())),
+                "dup-b" => Some(([false, false, true], "h1".to_string())),
+                "other" => Some(([false, false, true], "h2".to_string())),
+                _ => None,
+            })
+        };
+        let ids = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let plan = DuplicateService::plan_merge("keep", &ids(&["dup-a", "keep", "dup-a"]), lookup)
+            .unwrap();
+        assert_eq!(plan.flags, [true, true, false]);
+        assert_eq!(plan.newly_enabled, [AppType::Codex]);
+        assert_eq!(plan.remove, ["dup-a"]);
+
+        let err = DuplicateService::plan_merge("keep", &ids(&["dup-b", "other"]), lookup)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("other"));
+        assert!(DuplicateService::plan_merge("keep", &ids(&["missing"]), lookup).is_err());
+        assert!(DuplicateService::plan_merge("missing", &ids(&["dup-a"]), lookup).is_err());
+    }
+
+    #[test]
+    fn remove_each_keeps_going_after_failures() {
+        let plan = MergePlan {
+            flags: [true, true, true],
+            newly_enabled: vec![AppType::Codex, AppType::Gemini],
+            remove: vec!["dup-a".to_string(), "dup-b".to_string()],
+        };
+        let result = DuplicateService::remove_each("keep", plan, |id| match id {
+            "dup-a" => Err(anyhow!("busy")),
+            _ => Ok(()),
+        });
+
+        // 卸载失败不影响已合并到保留项的启用状态
+        assert_eq!(result.kept_id, "keep");
+        assert_eq!(result.removed, ["dup-b"]);
+        assert_eq!(result.failed, ["dup-a: busy"]);
+        assert_eq!(result.newly_enabled, [AppType::Codex, AppType::Gemini]);
+    }
+
+    #[test]
+    fn merge_duplicates_refuses_locked_copies() {
+        let db = Arc::new(Database::memory().unwrap());
+        db.save_command(&create_test_command("keep", "", "keep"))
+            .unwrap();
+        db.save_command(&create_test_command("dup", "", "dup"))
+            .unwrap();
+        ResourceLockService::set_locked(&db, ConfigTarget::Command, "dup", true).unwrap();
+
+        let remove_ids = vec!["dup".to_string()];
+        let err = DuplicateService::merge_duplicates(
+            &db,
+            ResourceType::Command,
+            "keep",
+            &remove_ids,
+            false,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("dup"));
+        assert!(db.get_installed_command("dup").unwrap().is_some());
+    }
+}
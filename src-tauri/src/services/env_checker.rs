@@ -1,3 +1,5 @@
+use crate::database::Database;
+use crate::services::update::ResourceType;
 use serde::{Deserialize, Serialize};
 #[cfg(not(target_os = "windows"))]
 use std::fs;
@@ -31,6 +33,84 @@ pub fn check_env_conflicts(app: &str) -> Result<Vec<EnvConflict>, String> {
     Ok(conflicts)
 }
 
+/// Find which of the given environment variables are not set
+///
+/// A variable counts as set when it is present in the system environment or,
+/// on Unix, assigned in a shell configuration file (apps launched from the GUI
+/// do not inherit the shell environment).
+pub fn find_missing_env_vars(names: &[String]) -> Vec<String> {
+    if names.is_empty() {
+        return Vec::new();
+    }
+
+    let keywords: Vec<String> = names.iter().map(|name| name.to_uppercase()).collect();
+    let keywords: Vec<&str> = keywords.iter().map(String::as_str).collect();
+    let mut defined = Vec::new();
+    defined.extend(check_system_env(&keywords).unwrap_or_default());
+    #[cfg(not(target_os = "windows"))]
+    defined.extend(check_shell_configs(&keywords).unwrap_or_default());
+
+    missing_from(names, &defined)
+}
+
+/// Names from `names` that none of the `defined` variables match
+fn missing_from(names: &[String], defined: &[EnvConflict]) -> Vec<String> {
+    names
+        .iter()
+        .filter(|name| {
+            !defined.iter().any(|var| {
+                if cfg!(target_os = "windows") {
+                    var.var_name.eq_ignore_ascii_case(name)
+                } else {
+                    var.var_name == **name
+                }
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+/// Check the `requires_env` declared by an installed command, agent or hook
+///
+/// Returns the variables that are not set (empty when all are present).
+pub fn check_env_requirements(
+    db: &Database,
+    resource_type: ResourceType,
+    id: &str,
+) -> Result<Vec<String>, String> {
+    let required = match resource_type {
+        ResourceType::Command => db
+            .get_installed_command(id)
+            .map_err(|e| e.to_string())?
+            .map(|c| c.requires_env),
+        ResourceType::Agent => db
+            .get_installed_agent(id)
+            .map_err(|e| e.to_string())?
+            .map(|a| a.requires_env),
+        ResourceType::Hook => db
+            .get_installed_hook(id)
+            .map_err(|e| e.to_string())?
+            .map(|h| h.requires_env),
+        ResourceType::Skill => {
+            return Err(format!("{resource_type} does not declare requires_env"));
+        }
+    }
+    .ok_or_else(|| format!("{resource_type} not installed: {id}"))?;
+
+    Ok(find_missing_env_vars(&required))
+}
+
+/// Log a warning when an installed resource is missing required variables
+pub fn warn_missing_env(resource_type: ResourceType, id: &str, required: &[String]) {
+    let missing = find_missing_env_vars(required);
+    if !missing.is_empty() {
+        log::warn!(
+            "{resource_type} {id} requires unset environment variables: {}",
+            missing.join(", ")
+        );
+    }
+}
+
 /// Get relevant keywords for each app
 fn get_keywords_for_app(app: &str) -> Vec<&str> {
     match app.to_lowercase().as_str() {
@@ -165,4 +245,18 @@ mod tests {
         );
         assert_eq!(get_keywords_for_app("unknown"), Vec::<&str>::new());
     }
+
+    #[test]
+    fn missing_from_reports_only_undefined_names() {
+        let defined = vec![EnvConflict {
+            var_name: "GITHUB_TOKEN".to_string(),
+            var_value: "x".to_string(),
+            source_type: "file".to_string(),
+            source_path: "/home/user/.zshrc:3".to_string(),
+        }];
+        let names = vec!["GITHUB_TOKEN".to_string(), "SLACK_WEBHOOK".to_string()];
+
+        assert_eq!(missing_from(&names, &defined), vec!["SLACK_WEBHOOK"]);
+        assert!(missing_from(&[], &defined).is_empty());
+    }
 }
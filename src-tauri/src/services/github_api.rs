@@ -8,7 +8,7 @@
 use crate::error::AppError;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use sha1::{Digest, Sha1};
 
 // ========== 数据结构 ==========
 
@@ -143,9 +143,12 @@ impl GitHubApiService {
     /// 创建新的 GitHubApiService 实例
     pub fn new(token: Option<String>) -> Self {
         Self {
+            // 单文件下载与 API 响应同样接受压缩传输
             http_client: Client::builder()
                 .user_agent("CC-Switch/3.9")
-                .timeout(Duration::from_secs(30))
+                .gzip(true)
+                .brotli(true)
+                .deflate(true)
                 .build()
                 .expect("Failed to create HTTP client"),
             token,
@@ -159,7 +162,10 @@ impl GitHubApiService {
 
     /// 构建带认证的请求
     fn build_request(&self, url: &str) -> reqwest::RequestBuilder {
-        let mut req = self.http_client.get(url);
+        let mut req = self
+            .http_client
+            .get(url)
+            .timeout(crate::settings::get_timeout_config().api_call());
         if let Some(ref token) = self.token {
             req = req.bearer_auth(token);
         }
@@ -366,6 +372,16 @@ impl GitHubApiService {
         Ok(tree_data)
     }
 
+    /// 本地计算内容的 Git blob SHA（与 GitHub API 返回的 `sha` 一致）
+    ///
+    /// 算法：`sha1("blob {len}\0" + content)`，无需网络请求
+    pub fn compute_blob_sha(content: &[u8]) -> String {
+        let mut hasher = Sha1::new();
+        hasher.update(format!("blob {}\0", content.len()).as_bytes());
+        hasher.update(content);
+        format!("{:x}", hasher.finalize())
+    }
+
     /// 获取单个文件的 blob SHA
     ///
     /// 返回文件的 SHA 和内容大小
@@ -423,6 +439,117 @@ impl GitHubApiService {
         Ok((data.sha, data.size))
     }
 
+    /// 拼接原始文件下载地址，`base` 为设置中的 `github_raw_base_url`
+    fn raw_file_url(base: &str, owner: &str, repo: &str, branch: &str, path: &str) -> String {
+        let base = base.trim_end_matches('/');
+        let path = path.trim_start_matches('/');
+        format!("{base}/{owner}/{repo}/{branch}/{path}")
+    }
+
+    /// 下载单个文件的原始内容
+    ///
+    /// 地址前缀取自设置 `github_raw_base_url`（默认 raw.githubusercontent.com），
+    /// 无法直连 GitHub 时可配置为镜像地址。不消耗 API 配额，用于 API 不可用时的内容比对
+    pub async fn download_raw_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        path: &str,
+    ) -> Result<Vec<u8>, GitHubApiError> {
+        let base = crate::settings::get_github_raw_base_url();
+        let url = Self::raw_file_url(&base, owner, repo, branch, path);
+
+        let response = self
+            .http_client
+            .get(&url)
+            .timeout(crate::settings::get_timeout_config().file_download())
+            .send()
+            .await
+            .map_err(|e| GitHubApiError::NetworkError(e.to_string()))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(GitHubApiError::NotFound);
+        }
+        if !status.is_success() {
+            return Err(GitHubApiError::Other(format!(
+                "下载文件失败: HTTP {}",
+                status
+            )));
+        }
+
+        response
+            .bytes()
+            .await
+            .map(|b| b.to_vec())
+            .map_err(|e| GitHubApiError::NetworkError(e.to_string()))
+    }
+
+    /// 按 blob SHA 获取文件内容
+    ///
+    /// 与分支无关：分支前进或固定的提交被改写后，仍能取回该 SHA 对应的版本
+    pub async fn get_blob(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+    ) -> Result<Vec<u8>, GitHubApiError> {
+        let url = format!("https://api.github.com/repos/{owner}/{repo}/git/blobs/{sha}");
+
+        let response = self
+            .build_request(&url)
+            .send()
+            .await
+            .map_err(|e| GitHubApiError::NetworkError(e.to_string()))?;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(GitHubApiError::NotFound);
+        }
+
+        if status == reqwest::StatusCode::FORBIDDEN {
+            if let Some(rate_limit) = self.parse_rate_limit(&headers) {
+                if rate_limit.remaining == 0 {
+                    return Err(GitHubApiError::RateLimited(rate_limit));
+                }
+            }
+            return Err(GitHubApiError::Unauthorized);
+        }
+
+        if !status.is_success() {
+            return Err(GitHubApiError::Other(format!(
+                "获取 blob 失败: HTTP {}",
+                status
+            )));
+        }
+
+        #[derive(Deserialize)]
+        struct BlobResponse {
+            content: String,
+            encoding: String,
+        }
+
+        let data: BlobResponse = response
+            .json()
+            .await
+            .map_err(|e| GitHubApiError::Other(format!("解析响应失败: {e}")))?;
+        if data.encoding != "base64" {
+            return Err(GitHubApiError::Other(format!(
+                "不支持的 blob 编码: {}",
+                data.encoding
+            )));
+        }
+
+        use base64::Engine as _;
+        let encoded: String = data.content.split_whitespace().collect();
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| GitHubApiError::Other(format!("解码 blob 失败: {e}")))
+    }
+
     /// 计算目录的组合 hash
     ///
     /// 将目录下所有文件的 blob SHA 组合后计算 hash，
@@ -435,30 +562,55 @@ impl GitHubApiService {
         path: &str,
     ) -> Result<String, GitHubApiError> {
         let tree = self.get_tree(owner, repo, branch, path).await?;
+        Self::hash_tree_entries(&tree.tree, path).ok_or(GitHubApiError::NotFound)
+    }
 
-        // 只取 blob 类型的条目，按路径排序
-        let mut blobs: Vec<&GitHubTreeEntry> = tree
-            .tree
+    /// 按 `path` 下的 blob 条目计算目录组合 hash，没有可计入的文件时返回 None
+    ///
+    /// 只取 blob 类型的条目，相对 `path` 含隐藏组件的条目按 `is_hashed_path` 跳过，再按路径排序
+    fn hash_tree_entries(entries: &[GitHubTreeEntry], path: &str) -> Option<String> {
+        let prefix = path.trim_end_matches('/');
+        let mut blobs: Vec<&GitHubTreeEntry> = entries
             .iter()
             .filter(|e| e.entry_type == "blob")
+            .filter(|e| {
+                let relative = e
+                    .path
+                    .strip_prefix(prefix)
+                    .map(|rest| rest.trim_start_matches('/'))
+                    .unwrap_or(&e.path);
+                Self::is_hashed_path(relative)
+            })
             .collect();
 
         blobs.sort_by(|a, b| a.path.cmp(&b.path));
 
         if blobs.is_empty() {
-            return Err(GitHubApiError::NotFound);
+            return None;
         }
 
-        // 组合所有 blob SHA
-        let combined: String = blobs.iter().map(|b| b.sha.as_str()).collect();
+        Some(Self::combine_blob_shas(
+            blobs.iter().map(|b| b.sha.as_str()),
+        ))
+    }
 
-        // 计算 SHA256 hash
-        use sha2::{Digest, Sha256};
-        let mut hasher = Sha256::new();
-        hasher.update(combined.as_bytes());
-        let result = hasher.finalize();
+    /// 目录 hash 是否计入该相对路径（`/` 分隔）
+    ///
+    /// 任一路径组件以 `.` 开头即跳过，避免 `.DS_Store`、`.git` 等文件造成误报；
+    /// 远端 `get_directory_hash` 与本地 `SkillService::compute_directory_hash` 共用
+    pub fn is_hashed_path(relative: &str) -> bool {
+        !relative.split('/').any(|part| part.starts_with('.'))
+    }
 
-        Ok(format!("{:x}", result))
+    /// 将按路径排序的 blob SHA 拼接后计算 SHA256
+    ///
+    /// `get_directory_hash` 与本地 `SkillService::compute_directory_hash` 共用，保证两侧结果一致
+    pub fn combine_blob_shas<'a>(shas: impl IntoIterator<Item = &'a str>) -> String {
+        use sha2::Digest as _;
+        let combined: String = shas.into_iter().collect();
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(combined.as_bytes());
+        format!("{:x}", hasher.finalize())
     }
 
     /// 获取最新 commit 信息
@@ -519,6 +671,54 @@ impl GitHubApiService {
         Ok((message, timestamp))
     }
 
+    /// 列出仓库的 tag（最多 100 个），按语义化版本从新到旧排序
+    pub async fn list_tags(&self, owner: &str, repo: &str) -> Result<Vec<String>, GitHubApiError> {
+        let url = format!("https://api.github.com/repos/{owner}/{repo}/tags?per_page=100");
+
+        let response = self
+            .build_request(&url)
+            .send()
+            .await
+            .map_err(|e| GitHubApiError::NetworkError(e.to_string()))?;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(GitHubApiError::NotFound);
+        }
+
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            if let Some(rate_limit) = self.parse_rate_limit(&headers) {
+                if rate_limit.remaining == 0 {
+                    return Err(GitHubApiError::RateLimited(rate_limit));
+                }
+            }
+            return Err(GitHubApiError::Unauthorized);
+        }
+
+        if !status.is_success() {
+            return Err(GitHubApiError::Other(format!(
+                "HTTP {}: 获取 tag 列表失败",
+                status
+            )));
+        }
+
+        #[derive(Deserialize)]
+        struct TagResponse {
+            name: String,
+        }
+
+        let tags: Vec<TagResponse> = response
+            .json()
+            .await
+            .map_err(|e| GitHubApiError::Other(format!("解析响应失败: {e}")))?;
+
+        let mut names: Vec<String> = tags.into_iter().map(|t| t.name).collect();
+        sort_tags_semver(&mut names);
+        Ok(names)
+    }
+
     /// 验证 Token 有效性
     pub async fn validate_token(&self) -> Result<RateLimitInfo, GitHubApiError> {
         let url = "https://api.github.com/rate_limit";
@@ -540,9 +740,152 @@ impl GitHubApiService {
     }
 }
 
+/// 预发布标识中以 `.` 分隔的一段
+///
+/// 按语义化版本规则比较：纯数字按数值比较，且低于任何含字母的标识（变体顺序即比较顺序）
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum PrereleaseIdent {
+    Numeric(u64),
+    Alphanumeric(String),
+}
+
+/// 解析 `v1.2.3` / `1.2` / `1.2.3-beta.1` 形式的版本 tag
+///
+/// 返回 (major, minor, patch, 是否正式版, 预发布标识)，正式版排在同版本预发布之前；
+/// 构建元数据（`+` 之后）不参与比较
+fn semver_key(tag: &str) -> Option<(u64, u64, u64, bool, Vec<PrereleaseIdent>)> {
+    let version = tag.strip_prefix(['v', 'V']).unwrap_or(tag);
+    let version = version.split('+').next().unwrap_or(version);
+    let (core, pre) = version.split_once('-').unwrap_or((version, ""));
+    let mut parts = core.split('.').map(|p| p.parse::<u64>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    let pre: Vec<PrereleaseIdent> = if pre.is_empty() {
+        Vec::new()
+    } else {
+        pre.split('.')
+            .map(|ident| match ident.parse::<u64>() {
+                Ok(n) if ident.bytes().all(|b| b.is_ascii_digit()) => PrereleaseIdent::Numeric(n),
+                _ => PrereleaseIdent::Alphanumeric(ident.to_string()),
+            })
+            .collect()
+    };
+    Some((major, minor, patch, pre.is_empty(), pre))
+}
+
+/// 按语义化版本从新到旧排序；无法解析为版本号的 tag 排在最后并保持原顺序
+pub fn sort_tags_semver(tags: &mut [String]) {
+    tags.sort_by(|a, b| match (semver_key(a), semver_key(b)) {
+        (Some(a), Some(b)) => b.cmp(&a),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::skill::SkillService;
+    use std::fs;
+
+    #[test]
+    fn sort_tags_semver_orders_releases_newest_first() {
+        let mut tags: Vec<String> = ["dev", "v1.2.0", "v1.10.0", "v1.10.0-rc.1", "2.0", "latest"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        sort_tags_semver(&mut tags);
+        let expected = ["2.0", "v1.10.0", "v1.10.0-rc.1", "v1.2.0", "dev", "latest"];
+        assert_eq!(tags, expected);
+    }
+
+    #[test]
+    fn sort_tags_semver_compares_prerelease_identifiers_numerically() {
+        let mut tags: Vec<String> = [
+            "v1.0.0-rc.2",
+            "v1.0.0-rc.10",
+            "v1.0.0-alpha",
+            "v1.0.0-alpha.1",
+            "v1.0.0-alpha.beta",
+            "v1.0.0-beta.11",
+            "v1.0.0-beta.2",
+            "v1.0.0+build.5",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        sort_tags_semver(&mut tags);
+        // 语义化版本规范中的示例顺序（从新到旧）
+        let expected = [
+            "v1.0.0+build.5",
+            "v1.0.0-rc.10",
+            "v1.0.0-rc.2",
+            "v1.0.0-beta.11",
+            "v1.0.0-beta.2",
+            "v1.0.0-alpha.beta",
+            "v1.0.0-alpha.1",
+            "v1.0.0-alpha",
+        ];
+        assert_eq!(tags, expected);
+    }
+
+    #[test]
+    fn raw_file_url_uses_configured_base() {
+        assert_eq!(
+            GitHubApiService::raw_file_url(
+                "https://raw.githubusercontent.com",
+                "owner",
+                "repo",
+                "main",
+                "commands/review.md"
+            ),
+            "https://raw.githubusercontent.com/owner/repo/main/commands/review.md"
+        );
+        assert_eq!(
+            GitHubApiService::raw_file_url(
+                "https://mirror.example.com/raw/",
+                "owner",
+                "repo",
+                "dev",
+                "/hooks/lint.json"
+            ),
+            "https://mirror.example.com/raw/owner/repo/dev/hooks/lint.json"
+        );
+    }
+
+    #[test]
+    fn directory_hash_skips_dotfiles_on_both_sides() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join(".github")).unwrap();
+        fs::write(dir.join("SKILL.md"), "# demo").unwrap();
+        fs::write(dir.join(".DS_Store"), "junk").unwrap();
+        fs::write(dir.join(".github").join("ci.yml"), "on: push").unwrap();
+
+        let entry = |path: &str, content: &str| GitHubTreeEntry {
+            path: format!("skills/demo/{path}"),
+            entry_type: "blob".to_string(),
+            sha: GitHubApiService::compute_blob_sha(content.as_bytes()),
+            size: None,
+        };
+        let entries = vec![
+            entry("SKILL.md", "# demo"),
+            entry(".DS_Store", "junk"),
+            entry(".github/ci.yml", "on: push"),
+        ];
+
+        let remote = GitHubApiService::hash_tree_entries(&entries, "skills/demo").unwrap();
+        let local = SkillService::compute_directory_hash(dir).unwrap();
+        assert_eq!(remote, local);
+
+        let only_hidden = &entries[1..];
+        assert!(GitHubApiService::hash_tree_entries(only_hidden, "skills/demo").is_none());
+    }
 
     #[test]
     fn test_rate_limit_display() {
@@ -562,4 +905,17 @@ mod tests {
         let app_error: AppError = error.into();
         assert!(matches!(app_error, AppError::Message(_)));
     }
+
+    #[test]
+    fn test_compute_blob_sha_matches_git() {
+        // 与 `git hash-object` 的结果一致
+        assert_eq!(
+            GitHubApiService::compute_blob_sha(b""),
+            "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"
+        );
+        assert_eq!(
+            GitHubApiService::compute_blob_sha(b"hello\n"),
+            "ce013625030ba8dba906f756967f9e9ca394464a"
+        );
+    }
 }
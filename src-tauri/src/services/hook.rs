@@ -11,7 +11,7 @@
 //!
 //! - SSOT: `~/.cc-switch/hooks/`
 //! - Claude: `~/.claude/settings.json` → hooks 字段
-//! - Codex: `~/.codex/settings.json` → hooks 字段
+//! - Codex: `~/.codex/config.toml` → `[hooks]` 表（TOML 格式）
 //! - Gemini: `~/.gemini/settings.json` → hooks 字段
 //!
 //! ## Hook 文件格式 (JSON)
@@ -35,20 +35,32 @@
 //! ```
 
 use crate::app_config::{
-    AppType, CommandRepo, DiscoverableHook, HookApps, HookEventType, HookNamespace, HookRule,
-    HookType, InstallScope, InstalledHook, UnmanagedHook,
+    deserialize_matcher, localize_descriptions, AppType, CommandRepo, DiscoverableHook, HookApps,
+    HookEventType, HookNamespace, HookOs, HookRule, HookType, InstallScope, InstalledHook,
+    LocalizedDescriptions, PaginatedDiscovery, SyncAction, SyncActionKind, UnmanagedHook,
 };
 use crate::config::get_app_config_dir;
 use crate::database::Database;
+use crate::services::command::{BranchSwitchTarget, CommandService};
+use crate::services::config::{ConfigService, ConfigTarget, MultiAppInstallResult};
+use crate::services::env_checker;
 use crate::services::github_api::GitHubApiService;
+use crate::services::repo_metadata::RepoMetadata;
+use crate::services::resource_lock::ResourceLockService;
+use crate::services::resumable_download;
+use crate::services::trash::{TrashService, TrashedResource};
+use crate::services::update::ResourceType;
 use anyhow::{anyhow, Result};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::time::timeout;
 
 /// Hook 文件元数据（从 JSON 解析）
@@ -70,6 +82,12 @@ pub struct HookFileMetadata {
     /// 是否启用
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+    /// 运行所需的环境变量
+    #[serde(default, alias = "requires_env", skip_serializing_if = "Vec::is_empty")]
+    pub requires_env: Vec<String>,
+    /// 多语言描述（`description_zh` 等）
+    #[serde(flatten)]
+    pub localized: LocalizedDescriptions,
 }
 
 fn default_priority() -> i32 {
@@ -91,10 +109,14 @@ pub struct OfficialHooksFormat {
 /// 官方格式中的单个规则
 #[derive(Debug, Clone, Deserialize)]
 pub struct OfficialHookRule {
-    /// 匹配器
-    pub matcher: Option<String>,
+    /// 匹配器（数组形式已合并为 `|` 分隔）
+    #[serde(default, deserialize_with = "deserialize_matcher")]
+    pub matcher: String,
     /// hooks 命令列表
     pub hooks: Vec<OfficialHookCommand>,
+    /// 仅在指定操作系统上生效
+    #[serde(default)]
+    pub os: Option<HookOs>,
 }
 
 /// 官方格式中的命令
@@ -111,6 +133,27 @@ pub struct OfficialHookCommand {
     pub timeout: Option<u64>,
 }
 
+/// 解析官方格式中的事件名，不支持的事件（如 SessionStart）返回 None
+fn official_event_type(event_name: &str) -> Option<HookEventType> {
+    match event_name {
+        "PreToolUse" => Some(HookEventType::PreToolUse),
+        "PostToolUse" => Some(HookEventType::PostToolUse),
+        "PermissionRequest" => Some(HookEventType::PermissionRequest),
+        "SessionEnd" => Some(HookEventType::SessionEnd),
+        _ => None,
+    }
+}
+
+/// 由官方格式拆分出的 Hook 使用的 ID 后缀
+fn event_id_suffix(event_type: &HookEventType) -> &'static str {
+    match event_type {
+        HookEventType::PreToolUse => "pre-tool-use",
+        HookEventType::PostToolUse => "post-tool-use",
+        HookEventType::PermissionRequest => "permission-request",
+        HookEventType::SessionEnd => "session-end",
+    }
+}
+
 impl OfficialHooksFormat {
     /// 将官方格式转换为 CC Switch 的 HookFileMetadata 列表
     pub fn to_hook_metadata_list(&self) -> Vec<(HookEventType, HookFileMetadata)> {
@@ -118,21 +161,16 @@ impl OfficialHooksFormat {
 
         if let Some(hooks) = &self.hooks {
             for (event_name, rules) in hooks {
-                // 解析事件类型
-                let event_type = match event_name.as_str() {
-                    "PreToolUse" => HookEventType::PreToolUse,
-                    "PostToolUse" => HookEventType::PostToolUse,
-                    "PermissionRequest" => HookEventType::PermissionRequest,
-                    "SessionEnd" => HookEventType::SessionEnd,
-                    // 跳过不支持的事件类型（如 SessionStart）
-                    _ => continue,
+                // 跳过不支持的事件类型（如 SessionStart）
+                let Some(event_type) = official_event_type(event_name) else {
+                    continue;
                 };
 
                 // 转换规则
                 let converted_rules: Vec<HookRule> = rules
                     .iter()
                     .map(|r| HookRule {
-                        matcher: r.matcher.clone().unwrap_or_default(),
+                        matcher: r.matcher.clone(),
                         hooks: r
                             .hooks
                             .iter()
@@ -151,6 +189,7 @@ impl OfficialHooksFormat {
                                 }
                             })
                             .collect(),
+                        os: r.os,
                     })
                     .collect();
 
@@ -164,6 +203,8 @@ impl OfficialHooksFormat {
                             rules: converted_rules,
                             priority: default_priority(),
                             enabled: default_enabled(),
+                            requires_env: Vec::new(),
+                            localized: LocalizedDescriptions::default(),
                         },
                     ));
                 }
@@ -172,8 +213,163 @@ impl OfficialHooksFormat {
 
         result
     }
+
+    /// 不支持的事件名（按名称排序）
+    pub fn unsupported_events(&self) -> Vec<String> {
+        let mut events: Vec<String> = self
+            .hooks
+            .iter()
+            .flat_map(|hooks| hooks.keys())
+            .filter(|name| official_event_type(name).is_none())
+            .cloned()
+            .collect();
+        events.sort();
+        events
+    }
+}
+
+/// 从官方格式文件导入 Hooks 的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfficialImportResult {
+    /// 新建的 Hooks（每个事件类型一个）
+    pub imported: Vec<InstalledHook>,
+    /// 被跳过的事件及原因
+    pub warnings: Vec<String>,
+}
+
+/// Hook 命令校验问题类型
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HookCommandIssueKind {
+    /// 引用的脚本路径不存在
+    NotFound,
+    /// 脚本存在但没有可执行权限（仅 Unix）
+    NotExecutable,
+    /// 命令名在 PATH 中找不到
+    NotInPath,
+}
+
+/// Hook 命令校验问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookCommandIssue {
+    pub hook_id: String,
+    pub hook_name: String,
+    /// 受影响的应用（该 Hook 为其启用）
+    pub app: String,
+    /// 原始命令
+    pub command: String,
+    /// 解析出的脚本路径或程序名
+    pub target: String,
+    pub kind: HookCommandIssueKind,
+    pub message: String,
+}
+
+/// Hook SSOT 文件的 JSON 校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookValidation {
+    pub hook_id: String,
+    pub hook_name: String,
+    pub enabled: bool,
+    /// 解析错误（文件缺失或 JSON 无效）；为空表示校验通过
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 全量校验 Hook 文件的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookValidationReport {
+    pub results: Vec<HookValidation>,
+    /// 因解析失败被停用（隔离）的 Hook ID
+    pub quarantined: Vec<String>,
+}
+
+/// 重建应用 hooks 字段的结果摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookRebuildSummary {
+    pub app: String,
+    /// 写入的 hook 规则条目数
+    pub hooks_written: usize,
+    /// 涉及的事件类型数
+    pub event_count: usize,
+    /// 重建前 settings.json 的备份路径（文件不存在时为空）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_path: Option<String>,
 }
 
+/// 批量移动中的一项（旧 ID → 新 ID）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookMove {
+    pub from: String,
+    pub to: String,
+}
+
+/// 批量移动命名空间的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkMoveResult {
+    pub moved: Vec<HookMove>,
+    /// 目标 ID 已被占用（或与本批其他 Hook 重名）而跳过的项
+    pub collisions: Vec<HookMove>,
+    /// 移动失败的项（格式 "id: 错误"）
+    pub failed: Vec<String>,
+}
+
+/// Hook 试运行结果（单条命令）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookTestRunResult {
+    /// 所属规则的 matcher
+    pub matcher: String,
+    pub command: String,
+    /// 退出码（超时被终止或被信号杀死时为空）
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    /// 按 Claude hook 协议，退出码 2 表示阻止本次操作
+    pub blocked: bool,
+}
+
+/// 试运行超时时间（秒）
+const HOOK_TEST_TIMEOUT_SECS: u64 = 30;
+
+/// 试运行时 stdout/stderr 各自保留的最大字节数
+const HOOK_TEST_OUTPUT_LIMIT: u64 = 64 * 1024;
+
+/// 试运行子进程可继承的环境变量，其余一律清除
+const HOOK_TEST_ENV_ALLOWLIST: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "LANG",
+    "TMPDIR",
+    "TEMP",
+    "TMP",
+    "SYSTEMROOT",
+    "USERPROFILE",
+];
+
+/// Shell 内建命令与关键字，由 shell 自身执行，不需要在 PATH 中存在
+const SHELL_BUILTINS: &[&str] = &[
+    ":", ".", "[", "[[", "alias", "break", "builtin", "case", "cd", "command", "continue",
+    "declare", "echo", "eval", "exec", "exit", "export", "false", "for", "function", "if", "local",
+    "printf", "pwd", "read", "return", "set", "shift", "source", "test", "time", "trap", "true",
+    "type", "ulimit", "umask", "unset", "until", "wait", "while",
+];
+
+/// 作为解释器调用时，脚本路径是其后的第一个非选项参数
+const SCRIPT_INTERPRETERS: &[&str] = &[
+    "sh", "bash", "zsh", "fish", "python", "python3", "node", "deno", "bun", "ruby", "perl",
+    "php",
+];
+
 /// Hook 服务
 pub struct HookService {
     http_client: Client,
@@ -191,7 +387,9 @@ impl HookService {
         Self {
             http_client: Client::builder()
                 .user_agent("CC-Switch/3.9")
-                .timeout(std::time::Duration::from_secs(30))
+                .gzip(true)
+                .brotli(true)
+                .deflate(true)
                 .build()
                 .expect("Failed to create HTTP client"),
         }
@@ -204,23 +402,24 @@ impl HookService {
     /// 返回 `~/.cc-switch/hooks/`
     pub fn get_ssot_dir() -> Result<PathBuf> {
         let dir = get_app_config_dir().join("hooks");
+        crate::config::validate_ssot_dir(&dir)?;
         if !dir.exists() {
             fs::create_dir_all(&dir)?;
         }
         Ok(dir)
     }
 
-    /// 获取指定应用的 settings.json 路径
+    /// 获取指定应用写入 hooks 的配置文件路径
     ///
     /// - Claude: `~/.claude/settings.json`
-    /// - Codex: `~/.codex/settings.json`
+    /// - Codex: `~/.codex/config.toml`（TOML 格式）
     /// - Gemini: `~/.gemini/settings.json`
     pub fn get_app_settings_path(app: &AppType) -> Result<PathBuf> {
         let home = dirs::home_dir().ok_or_else(|| anyhow!("无法获取用户主目录"))?;
 
         let path = match app {
             AppType::Claude => home.join(".claude").join("settings.json"),
-            AppType::Codex => home.join(".codex").join("settings.json"),
+            AppType::Codex => crate::codex_config::get_codex_config_path(),
             AppType::Gemini => home.join(".gemini").join("settings.json"),
             AppType::OpenCode => home.join(".opencode").join("settings.json"),
             AppType::OpenClaw => home.join(".openclaw").join("settings.json"),
@@ -238,6 +437,20 @@ impl HookService {
         Ok(hooks_dir)
     }
 
+    /// 按安装范围解析 Hook 实际生效的文件
+    ///
+    /// 项目级安装优先使用项目目录中的副本，副本缺失时回退到 SSOT
+    pub fn resolve_installed_path(id: &str, scope: &InstallScope) -> Result<PathBuf> {
+        let relative_path = Self::id_to_relative_path(id);
+        if let InstallScope::Project(project_path) = scope {
+            let project_copy = Self::get_project_hooks_dir(project_path)?.join(&relative_path);
+            if project_copy.exists() {
+                return Ok(project_copy);
+            }
+        }
+        Ok(Self::get_ssot_dir()?.join(relative_path))
+    }
+
     /// 检查范围冲突
     pub fn check_scope_conflict(
         db: &Arc<Database>,
@@ -381,6 +594,176 @@ impl HookService {
             .map_err(|e| anyhow!("获取 Hook 失败: {}", e))
     }
 
+    /// 从粘贴的 Hook JSON 创建 Hook（不来自仓库）
+    ///
+    /// 内容必须是合法的 Hook JSON，且包含事件类型和至少一条规则；
+    /// ID 未提供时从 name 推导，ID 已被占用时拒绝。创建后启用到当前应用
+    pub fn create_from_content(
+        db: &Arc<Database>,
+        proposed_id: Option<&str>,
+        content: &str,
+        app: &AppType,
+    ) -> Result<InstalledHook> {
+        let metadata = Self::parse_hook_metadata(content)?;
+        let event_type = metadata
+            .event_type
+            .ok_or_else(|| anyhow!("Hook 缺少 eventType"))?;
+        if metadata.rules.is_empty() {
+            return Err(anyhow!("Hook 至少需要一条规则"));
+        }
+
+        let id = CommandService::resolve_pasted_id(proposed_id, metadata.name.as_deref())?;
+        let dest = Self::get_ssot_dir()?.join(Self::id_to_relative_path(&id));
+        if db.get_installed_hook(&id)?.is_some() || dest.exists() {
+            return Err(anyhow!("Hook {} 已存在", id));
+        }
+
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&dest, content)?;
+
+        let (namespace, filename) = Self::parse_id(&id);
+        let hook = InstalledHook {
+            id: id.clone(),
+            name: metadata.name.unwrap_or_else(|| filename.clone()),
+            description: metadata.description,
+            namespace,
+            filename,
+            event_type,
+            rules: metadata.rules,
+            enabled: metadata.enabled,
+            priority: metadata.priority,
+            requires_env: metadata.requires_env,
+            repo_owner: None,
+            repo_name: None,
+            repo_branch: None,
+            readme_url: None,
+            source_path: None,
+            apps: HookApps::only(app),
+            file_hash: Some(Self::compute_hash(content)),
+            installed_at: chrono::Utc::now().timestamp(),
+            scope: "global".to_string(),
+            project_path: None,
+            user_note: None,
+            display_name_override: None,
+        };
+
+        let created = db
+            .save_hook(&hook)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| Self::sync_to_app(db, app));
+        if let Err(e) = created {
+            CommandService::discard_created("Hook", &id, &dest, || db.delete_hook(&id));
+            return Err(e);
+        }
+
+        log::info!("已从粘贴内容创建 Hook {}，已启用 {:?}", id, app);
+
+        Ok(hook)
+    }
+
+    /// 从本地的 Claude 官方 hooks 格式文件导入 Hooks
+    ///
+    /// 每个事件类型创建一个受管 Hook（ID 为 `文件名-事件`），以 CC Switch 格式写入 SSOT，
+    /// 启用到当前应用并同步。不支持的事件与 ID 已被占用的事件会被跳过并记录在 warnings 中
+    pub fn import_official_format(
+        db: &Arc<Database>,
+        path: &Path,
+        app: &AppType,
+    ) -> Result<OfficialImportResult> {
+        let content =
+            fs::read_to_string(path).map_err(|e| anyhow!("读取 {} 失败: {e}", path.display()))?;
+        let official: OfficialHooksFormat = serde_json::from_str(&content)
+            .map_err(|e| anyhow!("不是 Claude 官方 hooks 格式: {e}"))?;
+        if official.hooks.is_none() {
+            return Err(anyhow!("文件中没有 hooks 字段"));
+        }
+
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let base_id = CommandService::sanitize_resource_id(&stem)
+            .unwrap_or_else(|| "imported-hooks".to_string());
+
+        let mut result = OfficialImportResult {
+            warnings: official
+                .unsupported_events()
+                .into_iter()
+                .map(|event| format!("不支持的事件 {event}，已跳过"))
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut converted = official.to_hook_metadata_list();
+        converted.sort_by_key(|(event_type, _)| event_id_suffix(event_type));
+
+        let ssot_dir = Self::get_ssot_dir()?;
+        for (event_type, mut metadata) in converted {
+            let id = format!("{base_id}-{}", event_id_suffix(&event_type));
+            let dest = ssot_dir.join(Self::id_to_relative_path(&id));
+            if db.get_installed_hook(&id)?.is_some() || dest.exists() {
+                result
+                    .warnings
+                    .push(format!("Hook {id} 已存在，已跳过 {event_type} 事件"));
+                continue;
+            }
+
+            let name = format!("{stem} {event_type}");
+            metadata.name = Some(name.clone());
+            let mut value = serde_json::to_value(&metadata)?;
+            strip_json_nulls(&mut value);
+            let hook_content = serde_json::to_string_pretty(&value)?;
+
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&dest, &hook_content)?;
+
+            let (namespace, filename) = Self::parse_id(&id);
+            let hook = InstalledHook {
+                id,
+                name,
+                description: metadata.description,
+                namespace,
+                filename,
+                event_type,
+                rules: metadata.rules,
+                enabled: metadata.enabled,
+                priority: metadata.priority,
+                requires_env: metadata.requires_env,
+                repo_owner: None,
+                repo_name: None,
+                repo_branch: None,
+                readme_url: None,
+                source_path: None,
+                apps: HookApps::only(app),
+                file_hash: Some(Self::compute_hash(&hook_content)),
+                installed_at: chrono::Utc::now().timestamp(),
+                scope: "global".to_string(),
+                project_path: None,
+                user_note: None,
+                display_name_override: None,
+            };
+            db.save_hook(&hook)?;
+            result.imported.push(hook);
+        }
+
+        if !result.imported.is_empty() {
+            Self::sync_to_app(db, app)?;
+        }
+
+        log::info!(
+            "已从 {} 导入 {} 个 Hook，跳过 {} 项",
+            path.display(),
+            result.imported.len(),
+            result.warnings.len()
+        );
+
+        Ok(result)
+    }
+
     /// 安装 Hook
     ///
     /// 流程：
@@ -395,6 +778,16 @@ impl HookService {
         hook: &DiscoverableHook,
         current_app: &AppType,
     ) -> Result<InstalledHook> {
+        let installed = db.get_installed_hook(&hook.key)?;
+        CommandService::ensure_repo_installable(
+            db,
+            &hook.repo_owner,
+            &hook.repo_name,
+            installed
+                .as_ref()
+                .and_then(|h| h.repo_owner.as_deref().zip(h.repo_name.as_deref())),
+        )?;
+
         // 下载 Hook 内容
         let content = self.download_hook_content(hook).await?;
 
@@ -445,6 +838,9 @@ impl HookService {
 
         let (namespace, filename) = Self::parse_id(&hook.key);
 
+        // 更新时保留用户备注和自定义显示名称，name 仍以上游元数据为准
+        let existing = db.get_installed_hook(&hook.key)?;
+
         // 创建 InstalledHook 记录
         let installed_hook = InstalledHook {
             id: hook.key.clone(),
@@ -460,6 +856,7 @@ impl HookService {
             },
             enabled: metadata.enabled,
             priority: metadata.priority,
+            requires_env: metadata.requires_env,
             repo_owner: Some(hook.repo_owner.clone()),
             repo_name: Some(hook.repo_name.clone()),
             repo_branch: Some(hook.repo_branch.clone()),
@@ -470,6 +867,8 @@ impl HookService {
             installed_at: chrono::Utc::now().timestamp(),
             scope: "global".to_string(),
             project_path: None,
+            user_note: existing.as_ref().and_then(|e| e.user_note.clone()),
+            display_name_override: existing.and_then(|e| e.display_name_override),
         };
 
         // 保存到数据库
@@ -483,28 +882,124 @@ impl HookService {
             installed_hook.name,
             current_app
         );
+        env_checker::warn_missing_env(
+            ResourceType::Hook,
+            &installed_hook.id,
+            &installed_hook.requires_env,
+        );
 
         Ok(installed_hook)
     }
 
+    /// 一次安装并启用到多个应用
+    ///
+    /// SSOT 只写入一次，之后逐个启用其余应用；不支持 Hook 的应用被跳过，启用失败的应用记录在结果中
+    pub async fn install_for_apps(
+        &self,
+        db: &Arc<Database>,
+        hook: &DiscoverableHook,
+        apps: Vec<AppType>,
+    ) -> Result<MultiAppInstallResult<InstalledHook>> {
+        ConfigService::install_for_apps(
+            ResourceType::Hook,
+            apps,
+            |app| async move { self.install(db, hook, &app).await },
+            |installed: &InstalledHook, app: &AppType| {
+                Self::toggle_app(db, &installed.id, app, true)
+            },
+            |installed: &InstalledHook| db.get_installed_hook(&installed.id).ok().flatten(),
+        )
+        .await
+    }
+
+    /// 切换已安装 Hook 的来源分支
+    ///
+    /// 先确认文件在新分支上存在，再重新下载到 SSOT、更新 blob SHA 基线，
+    /// 最后重新生成各应用 settings.json 中的 hooks。启用状态与优先级保持不变
+    pub async fn switch_branch(
+        &self,
+        db: &Arc<Database>,
+        id: &str,
+        new_branch: &str,
+    ) -> Result<InstalledHook> {
+        let mut installed = db
+            .get_installed_hook(id)?
+            .ok_or_else(|| anyhow!("Hook not found: {}", id))?;
+        let target = BranchSwitchTarget::new(
+            "Hook",
+            id,
+            installed.repo_owner.as_deref(),
+            installed.repo_name.as_deref(),
+            installed.source_path.as_deref(),
+            new_branch,
+        )?;
+        let file_hash = target.fetch_blob_sha(db).await?;
+
+        let discoverable = DiscoverableHook {
+            key: installed.id.clone(),
+            name: installed.name.clone(),
+            description: installed.description.clone(),
+            namespace: installed.namespace.clone(),
+            filename: installed.filename.clone(),
+            event_type: installed.event_type.clone(),
+            rules: installed.rules.clone(),
+            priority: installed.priority,
+            readme_url: installed.readme_url.clone(),
+            repo_owner: target.repo_owner,
+            repo_name: target.repo_name,
+            repo_branch: target.branch.clone(),
+            source_path: Some(target.source_path),
+            localized: Default::default(),
+        };
+        let content = self.download_hook_content(&discoverable).await?;
+        let metadata = Self::parse_hook_metadata(&content)?;
+
+        let dest = Self::get_ssot_dir()?.join(Self::id_to_relative_path(id));
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&dest, &content)?;
+
+        if let Some(name) = metadata.name {
+            installed.name = name;
+        }
+        if metadata.description.is_some() {
+            installed.description = metadata.description;
+        }
+        if let Some(event_type) = metadata.event_type {
+            installed.event_type = event_type;
+        }
+        if !metadata.rules.is_empty() {
+            installed.rules = metadata.rules;
+        }
+        installed.repo_branch = Some(target.branch.clone());
+        installed.file_hash = Some(file_hash);
+        db.save_hook(&installed)?;
+
+        Self::sync_all_to_apps(db)?;
+
+        log::info!("Hook {} 已切换到分支 {}", id, target.branch);
+
+        Ok(installed)
+    }
+
     /// 卸载 Hook
     ///
     /// 流程：
     /// 1. 从所有应用 settings.json 移除
     /// 2. 从 SSOT 删除
     /// 3. 从数据库删除
+    ///
+    /// 事件类型无法识别的记录读不出完整的 Hook，走 `uninstall_unreadable` 直接删除
     pub fn uninstall(db: &Arc<Database>, id: &str) -> Result<()> {
         // 获取 hook 信息
-        let hook = db
-            .get_installed_hook(id)?
-            .ok_or_else(|| anyhow!("Hook not found: {}", id))?;
+        let Some(hook) = db.get_installed_hook(id)? else {
+            return Self::uninstall_unreadable(db, id);
+        };
 
-        // 从 SSOT 删除
+        // SSOT 文件移入回收站（连同数据库记录归档），支持误删后恢复
         let ssot_dir = Self::get_ssot_dir()?;
-        let hook_path = ssot_dir.join(Self::id_to_relative_path(id));
-        if hook_path.exists() {
-            fs::remove_file(&hook_path)?;
-        }
+        TrashService::move_to_trash(TrashedResource::Hook(hook.clone()))?;
 
         // 清理空的命名空间目录
         if !hook.namespace.is_empty() {
@@ -529,6 +1024,29 @@ impl HookService {
         Ok(())
     }
 
+    /// 卸载事件类型无法识别的 Hook：直接删除 SSOT 文件与数据库记录，不进入回收站
+    ///
+    /// 这类记录从未被同步到应用配置，因此无需重新同步
+    fn uninstall_unreadable(db: &Arc<Database>, id: &str) -> Result<()> {
+        if !db
+            .get_hooks_with_unknown_event_type()?
+            .iter()
+            .any(|(unknown_id, _)| unknown_id == id)
+        {
+            return Err(anyhow!("Hook not found: {}", id));
+        }
+
+        let path = Self::get_ssot_dir()?.join(Self::id_to_relative_path(id));
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        db.delete_hook(id)?;
+
+        log::info!("已删除事件类型无法识别的 Hook {}", id);
+
+        Ok(())
+    }
+
     /// 切换 Hook 启用状态
     pub fn toggle_enabled(db: &Arc<Database>, id: &str, enabled: bool) -> Result<()> {
         db.update_hook_enabled(id, enabled)?;
@@ -541,6 +1059,30 @@ impl HookService {
         Ok(())
     }
 
+    /// 设置用户备注（空字符串视为清除）
+    pub fn set_user_note(db: &Arc<Database>, id: &str, note: Option<String>) -> Result<()> {
+        let note = note.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        if !db.update_hook_user_note(id, note.as_deref())? {
+            return Err(anyhow!("Hook not found: {}", id));
+        }
+        Ok(())
+    }
+
+    /// 设置自定义显示名称（空字符串视为恢复上游名称）
+    pub fn set_display_name_override(
+        db: &Arc<Database>,
+        id: &str,
+        display_name: Option<String>,
+    ) -> Result<()> {
+        let display_name = display_name
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        if !db.update_hook_display_name_override(id, display_name.as_deref())? {
+            return Err(anyhow!("Hook not found: {}", id));
+        }
+        Ok(())
+    }
+
     /// 切换应用启用状态
     pub fn toggle_app(db: &Arc<Database>, id: &str, app: &AppType, enabled: bool) -> Result<()> {
         // 获取当前 hook
@@ -674,6 +1216,197 @@ impl HookService {
         Ok(())
     }
 
+    /// 将 Hook 移动到另一个命名空间
+    ///
+    /// 流程：
+    /// 1. 移动 SSOT 中的 JSON 文件并更新数据库 id/namespace
+    /// 2. 项目范围的 Hook 同步迁移项目目录中的副本
+    /// 3. 重新同步所有应用（hooks 按内容合并写入 settings.json）
+    ///
+    /// 目标位置已存在同名 Hook 时拒绝移动
+    pub fn move_to_namespace(
+        db: &Arc<Database>,
+        id: &str,
+        new_namespace: &str,
+    ) -> Result<InstalledHook> {
+        let ssot_dir = Self::get_ssot_dir()?;
+        let moved = Self::move_in_dir(db, &ssot_dir, id, new_namespace)?;
+        if moved.id == id {
+            return Ok(moved);
+        }
+
+        Self::relocate_project_copy(id, &moved)?;
+        Self::sync_all_to_apps(db)?;
+
+        log::info!("Hook {} 已移动到 {}", id, moved.id);
+
+        Ok(moved)
+    }
+
+    /// 按 ID 通配模式批量移动 Hook 到另一个命名空间
+    ///
+    /// `*` 匹配单个路径段内的任意字符，`**` 可跨越 `/`，`?` 匹配单个字符，
+    /// 如 `legacy/**` 匹配 legacy 下的全部 Hook。每项复用单个移动的实现
+    /// （文件与数据库记录一起移动，失败时还原）；目标 ID 已存在的项只报告不移动，
+    /// 全部移动完成后统一重新同步应用
+    pub fn bulk_move_namespace(
+        db: &Arc<Database>,
+        pattern: &str,
+        target_namespace: &str,
+    ) -> Result<BulkMoveResult> {
+        let ssot_dir = Self::get_ssot_dir()?;
+        let result = Self::bulk_move_in_dir(db, &ssot_dir, pattern, target_namespace)?;
+
+        for item in &result.moved {
+            if let Some(moved) = db.get_installed_hook(&item.to)? {
+                if let Err(e) = Self::relocate_project_copy(&item.from, &moved) {
+                    log::warn!("迁移 Hook {} 的项目副本失败: {}", item.to, e);
+                }
+            }
+        }
+        if !result.moved.is_empty() {
+            Self::sync_all_to_apps(db)?;
+        }
+
+        log::info!(
+            "批量移动 {} 到 {}: {} 个成功, {} 个冲突, {} 个失败",
+            pattern,
+            target_namespace,
+            result.moved.len(),
+            result.collisions.len(),
+            result.failed.len()
+        );
+
+        Ok(result)
+    }
+
+    /// 在指定 SSOT 目录中批量移动匹配 `pattern` 的 Hook
+    fn bulk_move_in_dir(
+        db: &Arc<Database>,
+        ssot_dir: &Path,
+        pattern: &str,
+        target_namespace: &str,
+    ) -> Result<BulkMoveResult> {
+        let target = Self::normalize_namespace(target_namespace)?;
+        let existing = db.get_all_installed_hooks()?;
+        let mut planned: HashSet<String> = HashSet::new();
+        let mut result = BulkMoveResult::default();
+
+        for hook in existing.values() {
+            if !glob_match(pattern.trim(), &hook.id) || hook.namespace == target {
+                continue;
+            }
+
+            let to = if target.is_empty() {
+                hook.filename.clone()
+            } else {
+                format!("{}/{}", target, hook.filename)
+            };
+            let item = HookMove {
+                from: hook.id.clone(),
+                to,
+            };
+            if existing.contains_key(&item.to) || !planned.insert(item.to.clone()) {
+                result.collisions.push(item);
+                continue;
+            }
+
+            match Self::move_in_dir(db, ssot_dir, &item.from, target) {
+                Ok(_) => result.moved.push(item),
+                Err(e) => result.failed.push(format!("{}: {}", item.from, e)),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// 项目范围的 Hook 移动后，同步迁移项目目录中的副本
+    fn relocate_project_copy(old_id: &str, moved: &InstalledHook) -> Result<()> {
+        if moved.scope == "project" {
+            if let Some(project_path) = moved.project_path.as_deref() {
+                let project_path = Path::new(project_path);
+                Self::remove_from_project(old_id, project_path)?;
+                Self::copy_to_project(&moved.id, project_path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 规范化目标命名空间（去掉首尾 `/`），拒绝 `.`、`..` 等路径穿越写法
+    fn normalize_namespace(namespace: &str) -> Result<&str> {
+        let namespace = namespace.trim().trim_matches('/');
+        if namespace
+            .split('/')
+            .any(|seg| seg == "." || seg == ".." || seg.contains('\\'))
+            || namespace.contains("//")
+        {
+            return Err(anyhow!("无效的命名空间: {}", namespace));
+        }
+        Ok(namespace)
+    }
+
+    /// 在指定 SSOT 目录中移动 Hook 文件并更新数据库记录
+    fn move_in_dir(
+        db: &Arc<Database>,
+        ssot_dir: &Path,
+        id: &str,
+        new_namespace: &str,
+    ) -> Result<InstalledHook> {
+        let new_namespace = Self::normalize_namespace(new_namespace)?;
+
+        let mut hook = db
+            .get_installed_hook(id)?
+            .ok_or_else(|| anyhow!("Hook not found: {}", id))?;
+        if hook.namespace == new_namespace {
+            return Ok(hook);
+        }
+
+        let new_id = if new_namespace.is_empty() {
+            hook.filename.clone()
+        } else {
+            format!("{}/{}", new_namespace, hook.filename)
+        };
+        if db.get_installed_hook(&new_id)?.is_some() {
+            return Err(anyhow!("目标位置已存在 Hook: {}", new_id));
+        }
+
+        let source = ssot_dir.join(Self::id_to_relative_path(id));
+        let dest = ssot_dir.join(Self::id_to_relative_path(&new_id));
+        if !source.exists() {
+            return Err(anyhow!("Hook 不存在于 SSOT: {}", id));
+        }
+        if dest.exists() {
+            return Err(anyhow!("目标文件已存在: {}", dest.display()));
+        }
+
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(&source, &dest)?;
+
+        let old_namespace = std::mem::replace(&mut hook.namespace, new_namespace.to_string());
+        hook.id = new_id;
+        if let Err(e) = db.save_hook(&hook) {
+            // 数据库写入失败时还原文件，保持 SSOT 与数据库一致
+            let _ = fs::rename(&dest, &source);
+            return Err(anyhow!("保存 Hook 失败: {}", e));
+        }
+        db.delete_hook(id)?;
+
+        // 清理移出后变空的命名空间目录（含上级目录）
+        if !old_namespace.is_empty() {
+            let mut dir = ssot_dir.join(&old_namespace);
+            while dir != ssot_dir && fs::remove_dir(&dir).is_ok() {
+                match dir.parent() {
+                    Some(parent) => dir = parent.to_path_buf(),
+                    None => break,
+                }
+            }
+        }
+
+        Ok(hook)
+    }
+
     /// 获取所有命名空间
     pub fn get_namespaces(db: &Arc<Database>) -> Result<Vec<HookNamespace>> {
         db.get_hook_namespaces()
@@ -804,23 +1537,12 @@ impl HookService {
                 HookEventType::SessionEnd => "SessionEnd",
             };
 
-            // 将每个规则转换为 hooks 配置项
-            for rule in &hook.rules {
-                let hooks_array: Vec<serde_json::Value> = rule
-                    .hooks
-                    .iter()
-                    .map(|h| serde_json::to_value(h).unwrap_or(serde_json::Value::Null))
-                    .collect();
-
-                let entry = serde_json::json!({
-                    "matcher": rule.matcher,
-                    "hooks": hooks_array
-                });
-
+            // 将每个规则转换为 hooks 配置项（跳过限定为其他操作系统的规则）
+            for rule in hook.rules.iter().filter(|r| r.applies_to_current_os()) {
                 config
                     .entry(event_key.to_string())
                     .or_default()
-                    .push(entry);
+                    .push(Self::rule_config_entry(&hook.event_type, rule));
             }
         }
 
@@ -831,65 +1553,836 @@ impl HookService {
         Ok(serde_json::to_value(config)?)
     }
 
-    /// 同步 hooks 到指定应用的 settings.json
+    /// 将单条规则转换为配置文件中的条目
+    ///
+    /// 不使用 matcher 的事件（如 SessionEnd）省略 `matcher` 字段，否则 Claude 会拒绝该配置
+    fn rule_config_entry(event_type: &HookEventType, rule: &HookRule) -> serde_json::Value {
+        let hooks_array: Vec<serde_json::Value> = rule
+            .hooks
+            .iter()
+            .map(|h| serde_json::to_value(h).unwrap_or(serde_json::Value::Null))
+            .collect();
+
+        if event_type.uses_matcher() {
+            serde_json::json!({
+                "matcher": rule.matcher,
+                "hooks": hooks_array
+            })
+        } else {
+            serde_json::json!({ "hooks": hooks_array })
+        }
+    }
+
+    /// 同步 hooks 到指定应用的配置文件（Codex 为 config.toml，其余为 settings.json）
     ///
     /// 采用合并模式：保留用户手动配置的 hooks，添加 CC Switch 管理的 hooks
     pub fn sync_to_app(db: &Arc<Database>, app: &AppType) -> Result<usize> {
+        if !check_app_hooks_support(app) {
+            log::debug!("{:?} 不支持 Hooks，跳过同步", app);
+            return Ok(0);
+        }
         let settings_path = Self::get_app_settings_path(app)?;
 
         // 生成 CC Switch 管理的 hooks 配置
         let managed_hooks = Self::generate_app_hooks_config(db, app)?;
 
-        // 读取现有配置
-        let mut settings: serde_json::Value = if settings_path.exists() {
-            let content = fs::read_to_string(&settings_path)?;
-            serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
+        // 更新 hooks 字段
+        // 简化处理：直接覆盖 hooks 字段
+        // TODO: 实现真正的合并模式，保留非 CC Switch 管理的 hooks
+        Self::write_settings_hooks(app, &settings_path, &managed_hooks)?;
+        Self::record_written_hash(db, app, &settings_path);
+
+        // 统计同步的 hooks 数量
+        let count = Self::count_hook_entries(&managed_hooks);
+
+        log::info!("已同步 {} 个 hooks 到 {:?}", count, app);
+
+        Ok(count)
+    }
+
+    /// 应用配置文件的格式
+    fn settings_format(app: &AppType) -> SettingsFormat {
+        match app {
+            AppType::Codex => SettingsFormat::Toml,
+            _ => SettingsFormat::Json,
+        }
+    }
+
+    /// 读取配置文件中的 hooks 字段（统一转换为 JSON 表示）
+    ///
+    /// 文件不存在或没有 hooks 字段时返回 `None`；文件无法解析时返回错误
+    fn read_settings_hooks(app: &AppType, path: &Path) -> Result<Option<serde_json::Value>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)?;
+
+        match Self::settings_format(app) {
+            SettingsFormat::Json => {
+                let settings: serde_json::Value = serde_json::from_str(&content)
+                    .map_err(|e| anyhow!("{} 不是有效的 JSON: {}", path.display(), e))?;
+                if !settings.is_object() {
+                    return Err(anyhow!("{} 顶层不是 JSON 对象", path.display()));
+                }
+                Ok(settings.get("hooks").cloned())
+            }
+            SettingsFormat::Toml => Self::hooks_from_toml(&content)
+                .map_err(|e| anyhow!("{} 不是有效的 TOML: {}", path.display(), e)),
+        }
+    }
+
+    /// 将 hooks 写入配置文件，保留其他字段
+    fn write_settings_hooks(app: &AppType, path: &Path, hooks: &serde_json::Value) -> Result<()> {
+        // 确保父目录存在
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        match Self::settings_format(app) {
+            SettingsFormat::Json => {
+                // 读取现有配置（无效时按空对象处理）
+                let mut settings: serde_json::Value = if path.exists() {
+                    let content = fs::read_to_string(path)?;
+                    serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
+                } else {
+                    serde_json::json!({})
+                };
+
+                // 确保 settings 是对象
+                if !settings.is_object() {
+                    settings = serde_json::json!({});
+                }
+                settings["hooks"] = hooks.clone();
+
+                // 写入配置（格式化输出）
+                fs::write(path, serde_json::to_string_pretty(&settings)?)?;
+            }
+            SettingsFormat::Toml => {
+                // config.toml 还包含供应商与 MCP 配置，无法解析时报错而不是覆盖
+                let base = if path.exists() {
+                    fs::read_to_string(path)?
+                } else {
+                    String::new()
+                };
+                let merged = Self::merge_hooks_into_toml(&base, hooks)?;
+                crate::config::write_text_file(path, &merged)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 从 TOML 文本中读取 hooks 表并转换为 JSON
+    fn hooks_from_toml(content: &str) -> Result<Option<serde_json::Value>> {
+        let table: toml::Table = content.parse()?;
+        table
+            .get("hooks")
+            .map(|hooks| serde_json::to_value(hooks).map_err(Into::into))
+            .transpose()
+    }
+
+    /// 将 hooks（JSON 表示）合并进 TOML 文本
+    ///
+    /// 使用 toml_edit 仅替换顶层 `hooks` 表，其余键、注释与顺序保持不变；
+    /// hooks 为空时移除该表。TOML 不支持 null，null 字段会被忽略
+    fn merge_hooks_into_toml(base: &str, hooks: &serde_json::Value) -> Result<String> {
+        let mut doc = if base.trim().is_empty() {
+            toml_edit::DocumentMut::default()
+        } else {
+            base.parse::<toml_edit::DocumentMut>()
+                .map_err(|e| anyhow!("解析 config.toml 失败: {e}"))?
+        };
+
+        let has_hooks = hooks.as_object().is_some_and(|obj| !obj.is_empty());
+        if !has_hooks {
+            doc.as_table_mut().remove("hooks");
+            return Ok(doc.to_string());
+        }
+
+        let mut hooks = hooks.clone();
+        strip_json_nulls(&mut hooks);
+        let mut wrapper = toml::Table::new();
+        wrapper.insert("hooks".to_string(), toml::Value::try_from(&hooks)?);
+        let hooks_doc = toml::to_string(&wrapper)?.parse::<toml_edit::DocumentMut>()?;
+        doc["hooks"] = hooks_doc["hooks"].clone();
+
+        Ok(doc.to_string())
+    }
+
+    /// 统计 hooks 配置中的规则条目数
+    fn count_hook_entries(hooks: &serde_json::Value) -> usize {
+        hooks
+            .as_object()
+            .map(|obj| obj.values().filter_map(|v| v.as_array()).map(|a| a.len()).sum())
+            .unwrap_or(0)
+    }
+
+    /// 记录上次写入后 hooks 字段哈希的设置键
+    fn written_hash_key(app: &AppType) -> String {
+        format!("hooks_written_hash_{}", app.as_str())
+    }
+
+    /// 计算配置文件中 hooks 字段的哈希（没有 hooks 字段时按 null 计算）
+    ///
+    /// 只比较 hooks 字段：同一文件中的供应商等配置也由 CC Switch 写入，
+    /// 而重新同步只会覆盖 hooks 字段
+    fn settings_hooks_hash(app: &AppType, path: &Path) -> Result<String> {
+        let hooks = Self::read_settings_hooks(app, path)?.unwrap_or(serde_json::Value::Null);
+        Ok(Self::compute_hash(&serde_json::to_string(&hooks)?))
+    }
+
+    /// 写入后记录 hooks 字段的哈希；失败只记录日志，不影响同步结果
+    fn record_written_hash(db: &Arc<Database>, app: &AppType, path: &Path) {
+        let result = Self::settings_hooks_hash(app, path).and_then(|hash| {
+            db.set_setting(&Self::written_hash_key(app), &hash)
+                .map_err(anyhow::Error::from)
+        });
+        if let Err(e) = result {
+            log::warn!("记录 {:?} hooks 写入哈希失败: {}", app, e);
+        }
+    }
+
+    /// 检测配置文件中的 hooks 在上次同步后是否被外部修改
+    ///
+    /// 比较上次写入后记录的哈希与当前文件内容，供 UI 在重新同步前提示
+    /// “配置已被外部修改，重新同步可能覆盖”。从未同步过时返回 false；
+    /// 文件无法解析也视为已修改
+    pub fn external_modification_check(db: &Arc<Database>, app: &AppType) -> Result<bool> {
+        let path = Self::get_app_settings_path(app)?;
+        Self::modified_since_written(db, app, &path)
+    }
+
+    fn modified_since_written(db: &Arc<Database>, app: &AppType, path: &Path) -> Result<bool> {
+        let Some(recorded) = db.get_setting(&Self::written_hash_key(app))? else {
+            return Ok(false);
+        };
+
+        match Self::settings_hooks_hash(app, path) {
+            Ok(current) => Ok(current != recorded),
+            Err(e) => {
+                log::debug!("读取 {} 失败，视为外部修改: {}", path.display(), e);
+                Ok(true)
+            }
+        }
+    }
+
+    /// 获取 settings.json 重建前的备份目录（~/.cc-switch/hook-backups/）
+    fn get_backup_dir() -> Result<PathBuf> {
+        let dir = get_app_config_dir().join("hook-backups");
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    /// 从数据库重建应用 settings.json 中的 hooks 字段
+    ///
+    /// 与 `sync_to_app` 不同，这里不信任现有文件中的任何 hooks 内容：先完整备份
+    /// settings.json，再丢弃 hooks 字段并按数据库中的受管状态重新生成。
+    /// 配置文件（Codex 为 config.toml）本身无法解析时直接报错（备份已保留），避免连带覆盖其他配置。
+    pub fn rebuild_app_hooks(db: &Arc<Database>, app: &AppType) -> Result<HookRebuildSummary> {
+        if !check_app_hooks_support(app) {
+            return Err(anyhow!("{} 不支持 Hooks", app.as_str()));
+        }
+
+        let settings_path = Self::get_app_settings_path(app)?;
+        let mut backup_path = None;
+        if settings_path.exists() {
+            let extension = settings_path
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("json");
+            let backup = Self::get_backup_dir()?.join(format!(
+                "{}_settings_{}.{}",
+                app.as_str(),
+                chrono::Utc::now().format("%Y%m%d_%H%M%S"),
+                extension
+            ));
+            fs::copy(&settings_path, &backup)?;
+            backup_path = Some(backup.to_string_lossy().to_string());
+
+            Self::read_settings_hooks(app, &settings_path)
+                .map_err(|e| anyhow!("无法重建 hooks（已备份到 {}）: {}", backup.display(), e))?;
+        }
+
+        let managed_hooks = Self::generate_app_hooks_config(db, app)?;
+        let hooks_written = Self::count_hook_entries(&managed_hooks);
+        let event_count = managed_hooks.as_object().map(|o| o.len()).unwrap_or(0);
+        Self::write_settings_hooks(app, &settings_path, &managed_hooks)?;
+        Self::record_written_hash(db, app, &settings_path);
+
+        log::info!(
+            "已重建 {:?} 的 hooks 字段：{} 个事件，{} 条规则",
+            app,
+            event_count,
+            hooks_written
+        );
+
+        Ok(HookRebuildSummary {
+            app: app.as_str().to_string(),
+            hooks_written,
+            event_count,
+            backup_path,
+        })
+    }
+
+    /// 同步 hooks 到所有应用
+    pub fn sync_all_to_apps(db: &Arc<Database>) -> Result<usize> {
+        let mut total = 0;
+
+        for app in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+            if !check_app_hooks_support(&app) {
+                continue;
+            }
+            match Self::sync_to_app(db, &app) {
+                Ok(count) => total += count,
+                Err(e) => log::warn!("同步 hooks 到 {:?} 失败: {}", app, e),
+            }
+        }
+
+        Ok(total)
+    }
+
+    /// 预演 `sync_all_to_apps`：逐个应用比较 settings.json 中的 hooks 字段，不写入磁盘
+    ///
+    /// Hooks 合并写入同一个 settings.json，因此每个应用只产生一条 id 为 `hooks` 的操作
+    pub fn plan_sync(db: &Arc<Database>) -> Result<Vec<SyncAction>> {
+        let mut actions = Vec::new();
+
+        for app in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+            let action = match Self::plan_app_sync(db, &app) {
+                Ok(action) => action,
+                Err(e) => SyncAction::skip(&app, "hooks", e.to_string()),
+            };
+            actions.push(action);
+        }
+
+        Ok(actions)
+    }
+
+    fn plan_app_sync(db: &Arc<Database>, app: &AppType) -> Result<SyncAction> {
+        if !check_app_hooks_support(app) {
+            return Ok(SyncAction::skip(app, "hooks", "该应用不支持 Hooks"));
+        }
+        let settings_path = Self::get_app_settings_path(app)?;
+        let managed_hooks = Self::generate_app_hooks_config(db, app)?;
+        let count = Self::count_hook_entries(&managed_hooks);
+
+        let (action, reason) = if !settings_path.exists() {
+            let file_name = settings_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            (
+                SyncActionKind::Create,
+                format!("新建 {file_name}，写入 {count} 条规则"),
+            )
+        } else {
+            let current = Self::read_settings_hooks(app, &settings_path)
+                .ok()
+                .flatten();
+            if current.as_ref() == Some(&managed_hooks) {
+                (
+                    SyncActionKind::Skip,
+                    "hooks 字段内容一致，无需更新".to_string(),
+                )
+            } else {
+                (
+                    SyncActionKind::Overwrite,
+                    format!("覆盖 hooks 字段，写入 {count} 条规则"),
+                )
+            }
+        };
+
+        Ok(SyncAction {
+            app: app.as_str().to_string(),
+            id: "hooks".to_string(),
+            action,
+            reason: Some(reason),
+        })
+    }
+
+    // ========== Matcher 预览 ==========
+
+    /// 判断 matcher 是否匹配指定工具名（与 Claude Code 的语义一致）
+    ///
+    /// 支持的语法：
+    /// - `""` 或 `"*"`：匹配所有工具
+    /// - 仅由字母、数字、`_` 和 `|` 组成：按 `|` 拆分后逐项精确匹配（区分大小写），
+    ///   如 `"Edit|Write"` 匹配 `Edit` 和 `Write`，但不匹配 `MultiEdit`
+    /// - 其他情况按正则表达式处理，且**不自动加锚点**：`"Notebook.*"` 匹配
+    ///   `NotebookEdit`，`"Edit.*"` 也会匹配 `MultiEdit`（子串命中即可）；
+    ///   需要整词匹配时请写 `"^Edit$"`。MCP 工具可用 `"mcp__memory__.*"` 匹配
+    /// - 无效的正则表达式不匹配任何工具
+    pub fn test_matcher(matcher: &str, tool_name: &str) -> bool {
+        let matcher = matcher.trim();
+        if matcher.is_empty() || matcher == "*" {
+            return true;
+        }
+
+        let is_simple = matcher
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '|');
+        if is_simple {
+            return matcher.split('|').any(|alt| alt == tool_name);
+        }
+
+        regex::Regex::new(matcher)
+            .map(|re| re.is_match(tool_name))
+            .unwrap_or(false)
+    }
+
+    /// 预览 matcher 匹配的工具
+    ///
+    /// `candidates` 为空时使用 Claude Code 内置工具列表
+    pub fn preview_matcher(matcher: &str, candidates: &[String]) -> Vec<String> {
+        if candidates.is_empty() {
+            crate::services::agent::CLAUDE_AGENT_TOOLS
+                .iter()
+                .filter(|tool| Self::test_matcher(matcher, tool))
+                .map(|tool| tool.to_string())
+                .collect()
+        } else {
+            candidates
+                .iter()
+                .filter(|tool| Self::test_matcher(matcher, tool))
+                .cloned()
+                .collect()
+        }
+    }
+
+    // ========== 文件校验 ==========
+
+    /// 解析所有已安装 Hook 的 SSOT JSON 文件，返回每个 Hook 的校验结果
+    pub fn validate_all(db: &Arc<Database>) -> Result<Vec<HookValidation>> {
+        let ssot_dir = Self::get_ssot_dir()?;
+        Self::validate_all_in_dir(db, &ssot_dir)
+    }
+
+    fn validate_all_in_dir(db: &Arc<Database>, ssot_dir: &Path) -> Result<Vec<HookValidation>> {
+        let results = Self::get_all_installed(db)?
+            .into_iter()
+            .map(|hook| {
+                let path = ssot_dir.join(Self::id_to_relative_path(&hook.id));
+                let error = match fs::read_to_string(&path) {
+                    Ok(content) => Self::parse_hook_metadata(&content)
+                        .err()
+                        .map(|e| e.to_string()),
+                    Err(e) => Some(format!("读取文件失败: {e}")),
+                };
+                HookValidation {
+                    hook_id: hook.id,
+                    hook_name: hook.name,
+                    enabled: hook.enabled,
+                    error,
+                }
+            })
+            .collect();
+        Ok(results)
+    }
+
+    /// 停用（不删除）SSOT 文件无法解析的 Hooks，使其在修复前不再参与同步
+    ///
+    /// 与「为应用禁用全部」一样跳过锁定的 Hooks。
+    /// 返回被隔离的 Hook ID；有 Hook 被隔离时重新同步到各应用。
+    pub fn quarantine_invalid(db: &Arc<Database>) -> Result<Vec<String>> {
+        let ssot_dir = Self::get_ssot_dir()?;
+        let quarantined = Self::quarantine_invalid_in_dir(db, &ssot_dir)?;
+        if !quarantined.is_empty() {
+            Self::sync_all_to_apps(db)?;
+        }
+        Ok(quarantined)
+    }
+
+    fn quarantine_invalid_in_dir(db: &Arc<Database>, ssot_dir: &Path) -> Result<Vec<String>> {
+        let locked = ResourceLockService::locked_ids(db, ConfigTarget::Hook)?;
+        let mut quarantined = Vec::new();
+        for result in Self::validate_all_in_dir(db, ssot_dir)? {
+            let Some(error) = result.error else {
+                continue;
+            };
+            if !result.enabled {
+                continue;
+            }
+            if locked.contains(&result.hook_id) {
+                log::warn!(
+                    "Hook {} 的 JSON 无效，但已锁定，未停用: {}",
+                    result.hook_id,
+                    error
+                );
+                continue;
+            }
+            db.update_hook_enabled(&result.hook_id, false)?;
+            log::warn!("Hook {} 的 JSON 无效，已停用: {}", result.hook_id, error);
+            quarantined.push(result.hook_id);
+        }
+        Ok(quarantined)
+    }
+
+    // ========== 命令校验 ==========
+
+    /// 校验已安装 Hooks 的命令是否可执行
+    ///
+    /// 对每个启用的 `HookType::Command` 解析出脚本路径（或程序名），检查：
+    /// - 路径形式：文件是否存在，Unix 下是否具有可执行位（经解释器调用的脚本只检查存在）
+    /// - 裸程序名：是否能在 PATH 中找到
+    ///
+    /// 含 `$CLAUDE_PROJECT_DIR` 等无法在此展开的变量的路径会被跳过。
+    /// 问题按 Hook 和其启用的应用逐条返回。
+    pub fn validate_hook_commands(db: &Arc<Database>) -> Result<Vec<HookCommandIssue>> {
+        let mut issues = Vec::new();
+
+        for hook in Self::get_all_installed(db)? {
+            if !hook.enabled {
+                continue;
+            }
+            let apps: Vec<&str> = ["claude", "codex", "gemini"]
+                .into_iter()
+                .filter(|app| hook.apps.is_enabled_for(app))
+                .collect();
+            if apps.is_empty() {
+                continue;
+            }
+
+            for rule in &hook.rules {
+                for hook_type in &rule.hooks {
+                    let HookType::Command { command } = hook_type else {
+                        continue;
+                    };
+                    let Some((target, kind)) = Self::check_command(command) else {
+                        continue;
+                    };
+                    let message = match kind {
+                        HookCommandIssueKind::NotFound => format!("脚本不存在: {target}"),
+                        HookCommandIssueKind::NotExecutable => {
+                            format!("脚本没有可执行权限: {target}")
+                        }
+                        HookCommandIssueKind::NotInPath => {
+                            format!("在 PATH 中找不到命令: {target}")
+                        }
+                    };
+                    for app in &apps {
+                        issues.push(HookCommandIssue {
+                            hook_id: hook.id.clone(),
+                            hook_name: hook.name.clone(),
+                            app: app.to_string(),
+                            command: command.clone(),
+                            target: target.clone(),
+                            kind: kind.clone(),
+                            message: message.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// 检查单条命令，返回 (目标, 问题类型)；无问题或无法判断时返回 None
+    fn check_command(command: &str) -> Option<(String, HookCommandIssueKind)> {
+        let tokens = Self::split_command(command);
+        let (program, rest) = tokens.split_first()?;
+
+        let program_name = Path::new(program)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if SCRIPT_INTERPRETERS.contains(&program_name.as_str()) {
+            if !program.contains('/') && !Self::find_in_path(program) {
+                return Some((program.clone(), HookCommandIssueKind::NotInPath));
+            }
+            // `bash -c "..."` 之类的内联脚本无法静态校验
+            if rest.iter().any(|arg| arg == "-c" || arg == "-e") {
+                return None;
+            }
+            let script = rest.iter().find(|arg| !arg.starts_with('-'))?;
+            let path = Self::expand_command_path(script)?;
+            return (!path.exists())
+                .then(|| (path.display().to_string(), HookCommandIssueKind::NotFound));
+        }
+
+        if program.contains('/') || program.contains('\\') {
+            let path = Self::expand_command_path(program)?;
+            if !path.exists() {
+                return Some((path.display().to_string(), HookCommandIssueKind::NotFound));
+            }
+            if !Self::is_executable(&path) {
+                return Some((
+                    path.display().to_string(),
+                    HookCommandIssueKind::NotExecutable,
+                ));
+            }
+            return None;
+        }
+
+        // 变量赋值前缀与 shell 内建命令不做检查
+        if program.contains('=')
+            || program.contains('$')
+            || SHELL_BUILTINS.contains(&program.as_str())
+        {
+            return None;
+        }
+        (!Self::find_in_path(program))
+            .then(|| (program.clone(), HookCommandIssueKind::NotInPath))
+    }
+
+    /// 按空白拆分命令，支持单/双引号
+    fn split_command(command: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        let mut current = String::new();
+        let mut quote: Option<char> = None;
+        let mut has_token = false;
+
+        for c in command.chars() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => current.push(c),
+                None if c == '"' || c == '\'' => {
+                    quote = Some(c);
+                    has_token = true;
+                }
+                None if c.is_whitespace() => {
+                    if has_token {
+                        tokens.push(std::mem::take(&mut current));
+                        has_token = false;
+                    }
+                }
+                // 遇到管道/连接符即停止，只校验第一段命令
+                None if matches!(c, '|' | ';' | '&') => break,
+                None => {
+                    current.push(c);
+                    has_token = true;
+                }
+            }
+        }
+        if has_token {
+            tokens.push(current);
+        }
+        tokens
+    }
+
+    /// 展开 `~` 与 `$HOME`；包含其他变量时返回 None
+    fn expand_command_path(raw: &str) -> Option<PathBuf> {
+        let home = crate::config::get_home_dir();
+        let home_str = home.to_string_lossy();
+        let expanded = if let Some(rest) = raw.strip_prefix("~/") {
+            home.join(rest).to_string_lossy().to_string()
+        } else {
+            raw.replace("${HOME}", &home_str).replace("$HOME", &home_str)
+        };
+        if expanded.contains('$') {
+            return None;
+        }
+        Some(PathBuf::from(expanded))
+    }
+
+    #[cfg(unix)]
+    fn is_executable(path: &Path) -> bool {
+        use std::os::unix::fs::PermissionsExt;
+        fs::metadata(path)
+            .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+    }
+
+    #[cfg(not(unix))]
+    fn is_executable(path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn find_in_path(program: &str) -> bool {
+        let Some(paths) = std::env::var_os("PATH") else {
+            return false;
+        };
+        std::env::split_paths(&paths).any(|dir| {
+            if cfg!(windows) {
+                ["", ".exe", ".cmd", ".bat"]
+                    .iter()
+                    .any(|ext| dir.join(format!("{program}{ext}")).is_file())
+            } else {
+                Self::is_executable(&dir.join(program))
+            }
+        })
+    }
+
+    // ========== 试运行 ==========
+
+    /// 试运行单个 Hook 的所有命令
+    ///
+    /// 每条 `HookType::Command` 在临时目录中以 `sh -c`（Windows 为 `cmd /C`）执行，
+    /// 事件 JSON 按 Claude hook 协议写入 stdin，只继承少量环境变量，
+    /// `CLAUDE_PROJECT_DIR` 指向临时目录，超时后连同其子进程一起强制终止。
+    /// `simulated_event` 为空时按 Hook 的事件类型生成示例事件。
+    /// 命令以当前用户权限运行，并非沙箱：可以读写当前用户能访问的任何文件、访问网络；
+    /// cc-switch 自身不读写任何应用配置。
+    /// Prompt 类型的 Hook 会被跳过
+    pub fn test_run(
+        db: &Arc<Database>,
+        id: &str,
+        simulated_event: Option<serde_json::Value>,
+    ) -> Result<Vec<HookTestRunResult>> {
+        let hook = Self::get_hook(db, id)?.ok_or_else(|| anyhow!("Hook not found: {id}"))?;
+        let work_dir = tempfile::tempdir()?;
+        let timeout = Duration::from_secs(HOOK_TEST_TIMEOUT_SECS);
+
+        let mut results = Vec::new();
+        for rule in &hook.rules {
+            let event = match &simulated_event {
+                Some(event) => event.clone(),
+                None => Self::synthetic_event(&hook.event_type, &rule.matcher, work_dir.path()),
+            };
+            let input = serde_json::to_vec(&event)?;
+
+            for hook_type in &rule.hooks {
+                let HookType::Command { command } = hook_type else {
+                    continue;
+                };
+                let mut result = Self::run_hook_command(command, &input, work_dir.path(), timeout)?;
+                result.matcher = rule.matcher.clone();
+                results.push(result);
+            }
+        }
+
+        log::info!("Hook {} 试运行完成，执行 {} 条命令", hook.id, results.len());
+        Ok(results)
+    }
+
+    /// 按事件类型生成示例事件（字段与 Claude Code 传给 hook 的 stdin JSON 一致）
+    fn synthetic_event(event_type: &HookEventType, matcher: &str, cwd: &Path) -> serde_json::Value {
+        let cwd_str = cwd.to_string_lossy().to_string();
+        let mut event = serde_json::json!({
+            "session_id": "cc-switch-hook-test",
+            "transcript_path": cwd.join("transcript.jsonl").to_string_lossy(),
+            "cwd": cwd_str,
+            "hook_event_name": event_type.to_string(),
+        });
+
+        // 优先用 Bash 作为示例工具，matcher 不匹配时取第一个匹配的内置工具
+        let tool_name = if Self::test_matcher(matcher, "Bash") {
+            "Bash".to_string()
+        } else {
+            Self::preview_matcher(matcher, &[])
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| matcher.to_string())
+        };
+        let tool_input = if tool_name == "Bash" {
+            serde_json::json!({ "command": "echo cc-switch hook test" })
         } else {
             serde_json::json!({})
         };
 
-        // 确保 settings 是对象
-        if !settings.is_object() {
-            settings = serde_json::json!({});
+        match event_type {
+            HookEventType::PreToolUse | HookEventType::PermissionRequest => {
+                event["tool_name"] = tool_name.into();
+                event["tool_input"] = tool_input;
+            }
+            HookEventType::PostToolUse => {
+                event["tool_name"] = tool_name.into();
+                event["tool_input"] = tool_input;
+                event["tool_response"] = serde_json::json!({
+                    "stdout": "cc-switch hook test",
+                    "stderr": "",
+                    "interrupted": false,
+                });
+            }
+            HookEventType::SessionEnd => {
+                event["reason"] = "other".into();
+            }
         }
+        event
+    }
 
-        // 更新 hooks 字段
-        // 简化处理：直接覆盖 hooks 字段
-        // TODO: 实现真正的合并模式，保留非 CC Switch 管理的 hooks
-        settings["hooks"] = managed_hooks;
-
-        // 确保父目录存在
-        if let Some(parent) = settings_path.parent() {
-            fs::create_dir_all(parent)?;
+    /// 在临时工作目录中执行一条 hook 命令
+    ///
+    /// 只限制工作目录、环境变量和运行时间，超时时连同命令启动的子进程一起终止；
+    /// 不做文件系统或网络隔离
+    fn run_hook_command(
+        command: &str,
+        input: &[u8],
+        cwd: &Path,
+        timeout: Duration,
+    ) -> Result<HookTestRunResult> {
+        let mut cmd = if cfg!(windows) {
+            let mut cmd = std::process::Command::new("cmd");
+            cmd.args(["/C", command]);
+            cmd
+        } else {
+            let mut cmd = std::process::Command::new("sh");
+            cmd.args(["-c", command]);
+            cmd
+        };
+        cmd.env_clear();
+        for key in HOOK_TEST_ENV_ALLOWLIST {
+            if let Some(value) = std::env::var_os(key) {
+                cmd.env(key, value);
+            }
         }
+        cmd.env("CLAUDE_PROJECT_DIR", cwd)
+            .current_dir(cwd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let started = Instant::now();
+        let mut tree = HookProcessTree::spawn(&mut cmd)
+            .map_err(|e| anyhow!("启动命令失败: {command}: {e}"))?;
+
+        // stdin 在独立线程写入，命令不读 stdin 时也不会阻塞
+        if let Some(mut stdin) = tree.child.stdin.take() {
+            let input = input.to_vec();
+            std::thread::spawn(move || {
+                let _ = stdin.write_all(&input);
+            });
+        }
+        let stdout = Self::spawn_output_reader(tree.child.stdout.take());
+        let stderr = Self::spawn_output_reader(tree.child.stderr.take());
 
-        // 写入配置（格式化输出）
-        let content = serde_json::to_string_pretty(&settings)?;
-        fs::write(&settings_path, content)?;
-
-        // 统计同步的 hooks 数量
-        let count = settings["hooks"]
-            .as_object()
-            .map(|obj| obj.values().filter_map(|v| v.as_array()).map(|a| a.len()).sum())
-            .unwrap_or(0);
-
-        log::info!("已同步 {} 个 hooks 到 {:?}", count, app);
-
-        Ok(count)
+        let mut timed_out = false;
+        let status = loop {
+            if let Some(status) = tree.child.try_wait()? {
+                break Some(status);
+            }
+            if started.elapsed() >= timeout {
+                tree.kill();
+                timed_out = true;
+                break None;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+        let duration_ms = started.elapsed().as_millis() as u64;
+
+        // 后台子进程可能仍持有管道，超时后只等待片刻
+        let grace = Duration::from_millis(if timed_out { 200 } else { 2000 });
+        let exit_code = status.and_then(|s| s.code());
+
+        Ok(HookTestRunResult {
+            matcher: String::new(),
+            command: command.to_string(),
+            exit_code,
+            stdout: stdout.recv_timeout(grace).unwrap_or_default(),
+            stderr: stderr.recv_timeout(grace).unwrap_or_default(),
+            timed_out,
+            duration_ms,
+            blocked: exit_code == Some(2),
+        })
     }
 
-    /// 同步 hooks 到所有应用
-    pub fn sync_all_to_apps(db: &Arc<Database>) -> Result<usize> {
-        let mut total = 0;
-
-        for app in [AppType::Claude, AppType::Codex, AppType::Gemini] {
-            match Self::sync_to_app(db, &app) {
-                Ok(count) => total += count,
-                Err(e) => log::warn!("同步 hooks 到 {:?} 失败: {}", app, e),
+    /// 在后台线程读取子进程输出，超过上限的部分丢弃
+    fn spawn_output_reader(
+        pipe: Option<impl Read + Send + 'static>,
+    ) -> std::sync::mpsc::Receiver<String> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe
+                    .by_ref()
+                    .take(HOOK_TEST_OUTPUT_LIMIT)
+                    .read_to_end(&mut buf);
+                let _ = std::io::copy(&mut pipe, &mut std::io::sink());
             }
-        }
-
-        Ok(total)
+            let _ = tx.send(String::from_utf8_lossy(&buf).into_owned());
+        });
+        rx
     }
 
     // ========== 扫描未管理 Hooks ==========
@@ -911,21 +2404,23 @@ impl HookService {
                 continue;
             }
 
-            let content = match fs::read_to_string(&settings_path) {
-                Ok(c) => c,
-                Err(_) => continue,
-            };
-
-            let settings: serde_json::Value = match serde_json::from_str(&content) {
-                Ok(v) => v,
-                Err(_) => continue,
+            let hooks_value = match Self::read_settings_hooks(&app, &settings_path) {
+                Ok(Some(v)) => v,
+                _ => continue,
             };
 
             // 解析 hooks 字段
-            if let Some(hooks_obj) = settings.get("hooks").and_then(|h| h.as_object()) {
+            if let Some(hooks_obj) = hooks_value.as_object() {
                 for (event_type_str, rules_array) in hooks_obj {
                     if let Some(rules) = rules_array.as_array() {
                         for rule in rules.iter() {
+                            // 数组形式的 matcher 合并为 `|` 分隔，与已管理的规则比较
+                            let matcher = rule
+                                .get("matcher")
+                                .cloned()
+                                .and_then(|m| deserialize_matcher(m).ok())
+                                .unwrap_or_default();
+
                             // 检查是否已被管理（简单检查，可能需要更复杂的逻辑）
                             if managed_hooks.values().any(|h| {
                                 let event_key = match h.event_type {
@@ -936,10 +2431,11 @@ impl HookService {
                                 };
                                 event_key == event_type_str
                                     && h.rules.iter().any(|r| {
-                                        rule.get("matcher")
-                                            .and_then(|m| m.as_str())
-                                            .map(|m| m == r.matcher)
-                                            .unwrap_or(false)
+                                        // 限定为其他操作系统的规则不会写入配置；
+                                        // SessionEnd 等事件写入时省略 matcher，不参与比较
+                                        r.applies_to_current_os()
+                                            && (!h.event_type.uses_matcher()
+                                                || matcher == r.matcher)
                                     })
                             }) {
                                 continue;
@@ -955,12 +2451,6 @@ impl HookService {
                             };
 
                             // 解析规则
-                            let matcher = rule
-                                .get("matcher")
-                                .and_then(|m| m.as_str())
-                                .unwrap_or("")
-                                .to_string();
-
                             let hooks_value = rule.get("hooks").cloned().unwrap_or(serde_json::json!([]));
                             let parsed_hooks: Vec<crate::app_config::HookType> =
                                 serde_json::from_value(hooks_value).unwrap_or_default();
@@ -1023,6 +2513,13 @@ impl HookService {
                 continue;
             }
 
+            // 已知不提供 Hooks 的仓库直接跳过（强制刷新时重新扫描以更新标记）
+            if repo.has_hooks == Some(false) {
+                log::debug!("跳过不含 Hooks 的仓库: {}/{}", repo.owner, repo.name);
+                statuses.push(status(repo, true, None, 0, None));
+                continue;
+            }
+
             // 尝试从缓存获取
             match db.get_cached_hooks(&repo.owner, &repo.name, &repo.branch) {
                 Ok(Some(cache)) => {
@@ -1059,8 +2556,11 @@ impl HookService {
                 .iter()
                 .map(|repo| self.fetch_repo_hooks_with_cache(repo, &db_clone));
 
-            let results: Vec<Result<Vec<DiscoverableHook>>> =
-                futures::future::join_all(fetch_tasks).await;
+            let results: Vec<Result<Vec<DiscoverableHook>>> = CommandService::run_bounded(
+                fetch_tasks,
+                crate::settings::effective_discovery_concurrency(),
+            )
+            .await;
 
             for (repo, result) in repos_to_fetch.into_iter().zip(results.into_iter()) {
                 match result {
@@ -1081,42 +2581,129 @@ impl HookService {
         // 去重并排序
         Self::deduplicate_hooks(&mut hooks);
         hooks.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        localize_descriptions(&mut hooks);
 
         Ok(hooks)
     }
 
+    /// 分页列出可发现的 Hooks
+    ///
+    /// 仓库仍完整扫描（复用 `discover_available` 的缓存），仅对结果分页，
+    /// 便于前端增量渲染大型仓库
+    pub async fn discover_available_paged(
+        &self,
+        db: &Arc<Database>,
+        repos: Vec<CommandRepo>,
+        force_refresh: bool,
+        page: Option<u32>,
+        page_size: Option<u32>,
+    ) -> Result<PaginatedDiscovery<DiscoverableHook>> {
+        let items = self.discover_available(db, repos, force_refresh).await?;
+        Ok(PaginatedDiscovery::from_items(items, page, page_size))
+    }
+
+    /// 导出发现缓存（JSON Lines，每行一个可发现的 Hook）
+    ///
+    /// 只读诊断用途：直接读取缓存表，不触发网络请求。未缓存或已过期的仓库不输出
+    pub fn dump_discovery_cache(db: &Arc<Database>) -> Result<String> {
+        let mut lines = Vec::new();
+
+        for repo in Self::get_repos(db)? {
+            match db.get_cached_hooks(&repo.owner, &repo.name, &repo.branch)? {
+                Some(cache) => {
+                    for item in &cache.hooks {
+                        lines.push(serde_json::to_string(item)?);
+                    }
+                }
+                None => log::debug!("无有效缓存: {}/{}", repo.owner, repo.name),
+            }
+        }
+
+        Ok(lines.join("\n"))
+    }
+
+    /// 只重新下载并扫描一个仓库，更新其缓存，返回新的 Hooks 数量
+    ///
+    /// 与发现流程不同，缓存保存失败时返回错误：用户显式刷新，需要知道缓存是否真的更新了
+    pub async fn refresh_repo_cache(
+        &self,
+        db: &Arc<Database>,
+        repo: &CommandRepo,
+    ) -> Result<usize> {
+        let (hooks, metadata) = self.fetch_repo_hooks(repo).await?;
+        Self::save_repo_cache(db, repo, &hooks, metadata.as_ref())
+            .map_err(|e| anyhow!("保存 Hook 缓存失败: {}/{}: {}", repo.owner, repo.name, e))?;
+        Ok(hooks.len())
+    }
+
     /// 从仓库获取 Hooks 列表并更新缓存
     async fn fetch_repo_hooks_with_cache(
         &self,
         repo: &CommandRepo,
         db: &Arc<Database>,
     ) -> Result<Vec<DiscoverableHook>> {
-        let hooks = self.fetch_repo_hooks(repo).await?;
+        let (hooks, metadata) = self.fetch_repo_hooks(repo).await?;
 
-        // 保存到缓存
-        if let Err(e) = db.save_cached_hooks(&repo.owner, &repo.name, &repo.branch, &hooks) {
-            log::warn!(
+        match Self::save_repo_cache(db, repo, &hooks, metadata.as_ref()) {
+            Ok(()) => log::debug!(
+                "已缓存 Hooks: {}/{} ({} 个)",
+                repo.owner,
+                repo.name,
+                hooks.len()
+            ),
+            Err(e) => log::warn!(
                 "保存 Hook 缓存失败: {}/{}: {}",
                 repo.owner,
                 repo.name,
                 e
-            );
-        } else {
-            log::debug!(
-                "已缓存 Hooks: {}/{} ({} 个)",
+            ),
+        }
+
+        Ok(hooks)
+    }
+
+    /// 保存一个仓库的发现结果（按仓库配置的分支）
+    ///
+    /// 资源类型标记与仓库元数据保存失败只记录日志，发现缓存本身的保存错误返回给调用方
+    fn save_repo_cache(
+        db: &Arc<Database>,
+        repo: &CommandRepo,
+        hooks: &[DiscoverableHook],
+        metadata: Option<&RepoMetadata>,
+    ) -> Result<()> {
+        // 记录仓库是否提供 Hooks，之后的非强制发现据此跳过
+        if let Err(e) = db.set_command_repo_capability(
+            &repo.owner,
+            &repo.name,
+            ResourceType::Hook,
+            !hooks.is_empty(),
+        ) {
+            log::warn!(
+                "保存仓库资源类型标记失败: {}/{}: {}",
                 repo.owner,
                 repo.name,
-                hooks.len()
+                e
             );
         }
 
-        Ok(hooks)
+        // 仓库级元数据（ccswitch.json）与发现结果一起缓存
+        if let Err(e) = db.save_repo_metadata(&repo.owner, &repo.name, metadata) {
+            log::warn!("保存仓库元数据失败: {}/{}: {}", repo.owner, repo.name, e);
+        }
+
+        db.save_cached_hooks(&repo.owner, &repo.name, &repo.branch, hooks)?;
+        Ok(())
     }
 
     /// 从仓库获取 Hooks 列表（不带缓存）
-    async fn fetch_repo_hooks(&self, repo: &CommandRepo) -> Result<Vec<DiscoverableHook>> {
+    ///
+    /// 同时返回仓库根目录的 `ccswitch.json`（已合并到条目中）
+    async fn fetch_repo_hooks(
+        &self,
+        repo: &CommandRepo,
+    ) -> Result<(Vec<DiscoverableHook>, Option<RepoMetadata>)> {
         let temp_dir = timeout(
-            std::time::Duration::from_secs(60),
+            crate::settings::get_timeout_config().repo_download(),
             self.download_repo(repo),
         )
         .await
@@ -1127,9 +2714,14 @@ impl HookService {
         // 扫描 hooks 目录
         Self::scan_repo_for_hooks(&temp_dir, &temp_dir, repo, &mut hooks)?;
 
+        let metadata = RepoMetadata::load(&temp_dir);
+        if let Some(metadata) = &metadata {
+            metadata.apply_to_hooks(&mut hooks);
+        }
+
         let _ = fs::remove_dir_all(&temp_dir);
 
-        Ok(hooks)
+        Ok((hooks, metadata))
     }
 
     /// 扫描仓库查找所有 hooks 目录中的 hook 文件
@@ -1321,6 +2913,7 @@ impl HookService {
                         repo_name: repo.name.clone(),
                         repo_branch: repo.branch.clone(),
                         source_path: Some(source_path),
+                        localized: metadata.localized,
                     });
                 } else {
                     // 尝试 Claude Code 官方格式（hooks 对象包含事件类型键）
@@ -1330,13 +2923,7 @@ impl HookService {
                         let converted = official.to_hook_metadata_list();
                         for (event_type, hook_meta) in converted {
                             // 为每个事件类型创建一个独立的 hook
-                            let event_suffix = match event_type {
-                                HookEventType::PreToolUse => "pre-tool-use",
-                                HookEventType::PostToolUse => "post-tool-use",
-                                HookEventType::PermissionRequest => "permission-request",
-                                HookEventType::SessionEnd => "session-end",
-                            };
-                            let id = format!("{}-{}", base_id, event_suffix);
+                            let id = format!("{}-{}", base_id, event_id_suffix(&event_type));
                             let (final_namespace, final_filename) = Self::parse_id(&id);
 
                             hooks.push(DiscoverableHook {
@@ -1358,6 +2945,7 @@ impl HookService {
                                 repo_name: repo.name.clone(),
                                 repo_branch: repo.branch.clone(),
                                 source_path: Some(source_path.clone()),
+                                localized: hook_meta.localized,
                             });
                         }
                     }
@@ -1369,7 +2957,7 @@ impl HookService {
     }
 
     /// 下载单个 Hook 内容
-    async fn download_hook_content(&self, hook: &DiscoverableHook) -> Result<String> {
+    pub(crate) async fn download_hook_content(&self, hook: &DiscoverableHook) -> Result<String> {
         let file_path = hook
             .source_path
             .clone()
@@ -1380,7 +2968,12 @@ impl HookService {
             hook.repo_owner, hook.repo_name, hook.repo_branch, file_path
         );
 
-        let response = self.http_client.get(&url).send().await?;
+        let response = self
+            .http_client
+            .get(&url)
+            .timeout(crate::settings::get_timeout_config().file_download())
+            .send()
+            .await?;
 
         if !response.status().is_success() {
             return Err(anyhow!(
@@ -1413,18 +3006,14 @@ impl HookService {
             repo.owner, repo.name, repo.branch
         );
 
-        let response = self.http_client.get(&zip_url).send().await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow!(
-                "下载仓库失败: {}/{} ({})",
-                repo.owner,
-                repo.name,
-                response.status()
-            ));
-        }
-
-        let bytes = response.bytes().await?;
+        let bytes = resumable_download::download_repo_zip(
+            &self.http_client,
+            &zip_url,
+            crate::settings::get_timeout_config().repo_download(),
+            crate::settings::effective_max_download_bytes(),
+        )
+        .await
+        .map_err(|e| anyhow!("下载仓库失败: {}/{} ({})", repo.owner, repo.name, e))?;
 
         // 保存到临时文件
         let zip_path = temp_dir.with_extension("zip");
@@ -1444,7 +3033,13 @@ impl HookService {
                     let components: Vec<_> = path.components().collect();
                     if components.len() > 1 {
                         let rest: PathBuf = components[1..].iter().collect();
-                        temp_dir.join(rest)
+                        match CommandService::safe_join(&temp_dir, &rest) {
+                            Some(outpath) => outpath,
+                            None => {
+                                log::warn!("跳过越界的压缩包条目: {}", file.name());
+                                continue;
+                            }
+                        }
                     } else {
                         continue;
                     }
@@ -1501,6 +3096,7 @@ impl HookService {
     /// 删除仓库
     pub fn remove_repo(db: &Arc<Database>, owner: &str, name: &str) -> Result<()> {
         db.remove_command_repo(owner, name)?;
+        CommandService::purge_repo_caches(db, owner, name)?;
         Ok(())
     }
 
@@ -1558,16 +3154,21 @@ impl HookService {
                     },
                     enabled: metadata.enabled,
                     priority: metadata.priority,
+                    requires_env: metadata.requires_env,
                     repo_owner: existing.as_ref().and_then(|e| e.repo_owner.clone()),
                     repo_name: existing.as_ref().and_then(|e| e.repo_name.clone()),
                     repo_branch: existing.as_ref().and_then(|e| e.repo_branch.clone()),
                     readme_url: existing.as_ref().and_then(|e| e.readme_url.clone()),
                     source_path: Some(relative.to_string_lossy().to_string()),
-                    apps: existing.map(|e| e.apps).unwrap_or_default(),
+                    apps: existing.as_ref().map(|e| e.apps.clone()).unwrap_or_default(),
                     file_hash: Some(file_hash),
                     installed_at: chrono::Utc::now().timestamp(),
                     scope: "global".to_string(),
                     project_path: None,
+                    user_note: existing.as_ref().and_then(|e| e.user_note.clone()),
+                    display_name_override: existing
+                        .as_ref()
+                        .and_then(|e| e.display_name_override.clone()),
                 };
 
                 db.save_hook(&hook)
@@ -1583,7 +3184,7 @@ impl HookService {
     }
 
     /// 扫描 SSOT 目录中的所有 .json 文件
-    fn scan_ssot_files(ssot_dir: &Path) -> Result<HashMap<String, PathBuf>> {
+    pub(crate) fn scan_ssot_files(ssot_dir: &Path) -> Result<HashMap<String, PathBuf>> {
         let mut files = HashMap::new();
         Self::scan_dir_recursive(ssot_dir, ssot_dir, &mut files)?;
         Ok(files)
@@ -1621,6 +3222,51 @@ impl HookService {
     }
 }
 
+/// 应用写入 hooks 的配置文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingsFormat {
+    /// settings.json（Claude/Gemini）
+    Json,
+    /// config.toml（Codex）
+    Toml,
+}
+
+/// ID 通配匹配：`*` 不跨越 `/`，`**` 可跨越 `/`，`?` 匹配单个非 `/` 字符
+fn glob_match(pattern: &str, id: &str) -> bool {
+    fn matches(p: &[char], s: &[char]) -> bool {
+        match p.split_first() {
+            None => s.is_empty(),
+            Some(('*', rest)) if rest.first() == Some(&'*') => {
+                let rest = &rest[1..];
+                // `**/` 也可以匹配零层目录
+                let without_slash = rest.strip_prefix(&['/']).unwrap_or(rest);
+                (0..=s.len()).any(|i| matches(rest, &s[i..])) || matches(without_slash, s)
+            }
+            Some(('*', rest)) => (0..=s.len())
+                .take_while(|&i| i == 0 || s[i - 1] != '/')
+                .any(|i| matches(rest, &s[i..])),
+            Some(('?', rest)) => s.first().is_some_and(|c| *c != '/') && matches(rest, &s[1..]),
+            Some((c, rest)) => s.first() == Some(c) && matches(rest, &s[1..]),
+        }
+    }
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let id: Vec<char> = id.chars().collect();
+    matches(&pattern, &id)
+}
+
+/// 递归移除 JSON 对象中的 null 字段（TOML 无 null 类型）
+fn strip_json_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(obj) => {
+            obj.retain(|_, v| !v.is_null());
+            obj.values_mut().for_each(strip_json_nulls);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_json_nulls),
+        _ => {}
+    }
+}
+
 /// 检查应用是否支持 Hooks 功能
 pub fn check_app_hooks_support(app: &AppType) -> bool {
     // Claude Code 支持 hooks
@@ -1632,3 +3278,651 @@ pub fn check_app_hooks_support(app: &AppType) -> bool {
         AppType::OpenCode | AppType::OpenClaw | AppType::Hermes => false,
     }
 }
+
+/// 试运行的 hook 命令及其启动的全部子进程
+///
+/// Unix 下命令在独立进程组中启动，超时时向整个进程组发送 SIGKILL；
+/// Windows 下把进程加入 Job 对象，超时时终止整个 Job。
+/// 只结束 `sh -c`/`cmd /C` 外壳时，hook 在后台启动的进程会继续运行并占用输出管道
+struct HookProcessTree {
+    child: std::process::Child,
+    #[cfg(windows)]
+    job: windows_sys::Win32::Foundation::HANDLE,
+}
+
+impl HookProcessTree {
+    fn spawn(cmd: &mut std::process::Command) -> std::io::Result<Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+        let child = cmd.spawn()?;
+        #[cfg(windows)]
+        let job = Self::assign_job(&child);
+
+        Ok(Self {
+            child,
+            #[cfg(windows)]
+            job,
+        })
+    }
+
+    /// 创建 Job 对象并加入子进程，失败时返回空句柄（超时只结束外壳进程）
+    #[cfg(windows)]
+    fn assign_job(child: &std::process::Child) -> windows_sys::Win32::Foundation::HANDLE {
+        use std::os::windows::io::AsRawHandle;
+        use windows_sys::Win32::System::JobObjects::{AssignProcessToJobObject, CreateJobObjectW};
+
+        // SAFETY: 参数均为空指针或有效句柄，进程句柄在 child 存活期间有效
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if !job.is_null() && AssignProcessToJobObject(job, child.as_raw_handle() as _) == 0 {
+                log::warn!("无法将 hook 进程加入 Job 对象，超时时只能结束外壳进程");
+            }
+            job
+        }
+    }
+
+    /// 终止整个进程树并回收外壳进程
+    fn kill(&mut self) {
+        #[cfg(unix)]
+        {
+            // SAFETY: 外壳进程尚未被回收，进程组 ID 等于其 pid（process_group(0)）
+            unsafe {
+                libc::killpg(self.child.id() as libc::pid_t, libc::SIGKILL);
+            }
+        }
+        #[cfg(windows)]
+        {
+            if !self.job.is_null() {
+                // SAFETY: job 为本结构持有的有效句柄
+                unsafe {
+                    windows_sys::Win32::System::JobObjects::TerminateJobObject(self.job, 1);
+                }
+            }
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(windows)]
+impl Drop for HookProcessTree {
+    fn drop(&mut self) {
+        if !self.job.is_null() {
+            // SAFETY: job 由 CreateJobObjectW 创建，只在这里关闭一次
+            unsafe {
+                windows_sys::Win32::Foundation::CloseHandle(self.job);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{create_test_hook, TempHome};
+    use serial_test::serial;
+
+    #[test]
+    fn test_matcher_wildcard_and_empty_match_everything() {
+        assert!(HookService::test_matcher("", "Bash"));
+        assert!(HookService::test_matcher("*", "mcp__github__create_issue"));
+    }
+
+    #[test]
+    fn test_matcher_alternation_is_exact() {
+        assert!(HookService::test_matcher("Edit|Write", "Edit"));
+        assert!(HookService::test_matcher("Edit|Write", "Write"));
+        assert!(!HookService::test_matcher("Edit|Write", "MultiEdit"));
+        assert!(!HookService::test_matcher("Bash", "bash"));
+    }
+
+    #[test]
+    fn test_matcher_regex_is_unanchored() {
+        assert!(HookService::test_matcher("Notebook.*", "NotebookEdit"));
+        assert!(HookService::test_matcher("Edit.*", "MultiEdit"));
+        assert!(!HookService::test_matcher("^Edit$", "MultiEdit"));
+        assert!(HookService::test_matcher("mcp__memory__.*", "mcp__memory__create"));
+        assert!(!HookService::test_matcher("(", "Bash"));
+    }
+
+    #[test]
+    fn preview_matcher_defaults_to_builtin_tools() {
+        let matched = HookService::preview_matcher("Edit|Write", &[]);
+        assert_eq!(matched, vec!["Edit", "Write"]);
+    }
+
+    #[test]
+    fn count_hook_entries_sums_rules_across_events() {
+        let hooks = serde_json::json!({
+            "PreToolUse": [{ "matcher": "Bash", "hooks": [] }, { "matcher": "Edit", "hooks": [] }],
+            "SessionEnd": [{ "matcher": "", "hooks": [] }]
+        });
+        assert_eq!(HookService::count_hook_entries(&hooks), 3);
+        assert_eq!(HookService::count_hook_entries(&serde_json::json!(null)), 0);
+    }
+
+    #[test]
+    fn session_end_entry_omits_matcher() {
+        let rule = HookRule {
+            matcher: String::new(),
+            hooks: vec![HookType::Command {
+                command: "~/.claude/hooks/cleanup.sh".to_string(),
+            }],
+            os: None,
+        };
+
+        let entry = HookService::rule_config_entry(&HookEventType::SessionEnd, &rule);
+        assert_eq!(
+            entry,
+            serde_json::json!({
+                "hooks": [{ "type": "command", "command": "~/.claude/hooks/cleanup.sh" }]
+            })
+        );
+
+        let entry = HookService::rule_config_entry(&HookEventType::PreToolUse, &rule);
+        assert_eq!(entry["matcher"], "");
+    }
+
+    #[test]
+    fn official_format_parses_matcherless_session_end() {
+        let official: OfficialHooksFormat = serde_json::from_value(serde_json::json!({
+            "hooks": {
+                "SessionEnd": [{
+                    "hooks": [{ "type": "command", "command": "echo bye" }]
+                }]
+            }
+        }))
+        .unwrap();
+
+        let list = official.to_hook_metadata_list();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].0, HookEventType::SessionEnd);
+        assert_eq!(list[0].1.rules[0].matcher, "");
+    }
+
+    #[test]
+    fn array_matcher_is_joined_into_pipe_form() {
+        let metadata = HookService::parse_hook_metadata(
+            r#"{
+                "eventType": "PreToolUse",
+                "rules": [
+                    { "matcher": ["Bash", " Edit ", ""], "hooks": [] },
+                    { "matcher": "Write", "hooks": [] }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(metadata.rules[0].matcher, "Bash|Edit");
+        assert_eq!(metadata.rules[1].matcher, "Write");
+
+        // 写入时统一为字符串形式
+        let written = serde_json::to_value(&metadata.rules[0]).unwrap();
+        assert_eq!(written["matcher"], "Bash|Edit");
+
+        let official: OfficialHooksFormat = serde_json::from_value(serde_json::json!({
+            "hooks": {
+                "PostToolUse": [{
+                    "matcher": ["Edit", "Write"],
+                    "hooks": [{ "type": "command", "command": "fmt" }]
+                }]
+            }
+        }))
+        .unwrap();
+        let list = official.to_hook_metadata_list();
+        assert_eq!(list[0].1.rules[0].matcher, "Edit|Write");
+    }
+
+    #[test]
+    fn split_command_handles_quotes_and_pipes() {
+        assert_eq!(
+            HookService::split_command(r#"bash "/tmp/my script.sh" --flag | tee log"#),
+            vec!["bash", "/tmp/my script.sh", "--flag"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn check_command_reports_missing_script() {
+        let (target, kind) =
+            HookService::check_command("sh /nonexistent/cc-switch/check.sh").unwrap();
+        assert_eq!(kind, HookCommandIssueKind::NotFound);
+        assert!(target.ends_with("check.sh"));
+
+        assert!(HookService::check_command("bash -c 'echo hi'").is_none());
+        assert!(HookService::check_command("$CLAUDE_PROJECT_DIR/hooks/a.sh").is_none());
+        assert_eq!(
+            HookService::check_command("definitely-not-a-real-binary-xyz")
+                .map(|(_, kind)| kind),
+            Some(HookCommandIssueKind::NotInPath)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn check_command_reports_missing_exec_bit() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("hook.sh");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let command = script.display().to_string();
+        assert_eq!(
+            HookService::check_command(&command).map(|(_, kind)| kind),
+            Some(HookCommandIssueKind::NotExecutable)
+        );
+
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(HookService::check_command(&command).is_none());
+    }
+
+    #[test]
+    fn check_command_skips_shell_builtins() {
+        for command in [
+            "echo done",
+            "cd /tmp && make",
+            "source ~/.hookrc",
+            "[ -f x ]",
+            "exit 0",
+        ] {
+            assert!(
+                HookService::check_command(command).is_none(),
+                "{command} 不应报告为 NotInPath"
+            );
+        }
+    }
+
+    #[test]
+    fn external_modification_is_detected_only_for_hooks_changes() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("settings.json");
+        let db = Arc::new(Database::memory().unwrap());
+        let app = AppType::Claude;
+        let modified = || HookService::modified_since_written(&db, &app, &path).unwrap();
+
+        // 从未同步过
+        fs::write(&path, r#"{"hooks": {}}"#).unwrap();
+        assert!(!modified());
+
+        let hooks = serde_json::json!({
+            "PreToolUse": [{"matcher": "Bash", "hooks": [{"type": "command", "command": "x"}]}]
+        });
+        HookService::write_settings_hooks(&app, &path, &hooks).unwrap();
+        HookService::record_written_hash(&db, &app, &path);
+        assert!(!modified());
+
+        // 其他字段（如供应商 env）变化不算外部修改 hooks
+        let mut settings: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        settings["env"] = serde_json::json!({"ANTHROPIC_BASE_URL": "https://example.com"});
+        fs::write(&path, settings.to_string()).unwrap();
+        assert!(!modified());
+
+        settings["hooks"]["PreToolUse"][0]["matcher"] = serde_json::json!("Edit");
+        fs::write(&path, settings.to_string()).unwrap();
+        assert!(modified());
+
+        fs::write(&path, "not json").unwrap();
+        assert!(modified());
+    }
+
+    #[test]
+    #[serial]
+    fn codex_sync_leaves_config_toml_untouched() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let mut hook = create_test_hook("fmt", "", "fmt");
+        hook.apps.codex = true;
+        db.save_hook(&hook).unwrap();
+
+        let config_path = HookService::get_app_settings_path(&AppType::Codex).unwrap();
+        fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+        let original = "model = \"o3\"\n";
+        fs::write(&config_path, original).unwrap();
+
+        assert_eq!(HookService::sync_to_app(&db, &AppType::Codex).unwrap(), 0);
+        HookService::sync_all_to_apps(&db).unwrap();
+
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), original);
+    }
+
+    #[test]
+    fn generated_config_only_includes_rules_for_current_os() {
+        let db = Arc::new(Database::memory().unwrap());
+        let rules: Vec<HookRule> = serde_json::from_value(serde_json::json!([
+            { "matcher": "Bash", "hooks": [{ "type": "command", "command": "all.sh" }] },
+            { "matcher": "Bash", "os": "macos", "hooks": [{ "type": "command", "command": "mac.sh" }] },
+            { "matcher": "Bash", "os": "linux", "hooks": [{ "type": "command", "command": "linux.sh" }] },
+            { "matcher": "Bash", "os": "windows", "hooks": [{ "type": "command", "command": "win.ps1" }] }
+        ]))
+        .unwrap();
+        let mut hook = create_test_hook("fmt", "", "fmt");
+        hook.rules = rules;
+        db.save_hook(&hook).unwrap();
+
+        let config = HookService::generate_app_hooks_config(&db, &AppType::Claude).unwrap();
+        let commands: Vec<&str> = config["PreToolUse"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["hooks"][0]["command"].as_str().unwrap())
+            .collect();
+
+        let mut expected = vec!["all.sh"];
+        if cfg!(target_os = "macos") {
+            expected.push("mac.sh");
+        } else if cfg!(target_os = "linux") {
+            expected.push("linux.sh");
+        } else if cfg!(target_os = "windows") {
+            expected.push("win.ps1");
+        }
+        assert_eq!(commands, expected);
+    }
+
+    #[test]
+    fn move_in_dir_relocates_file_and_guards_collisions() {
+        let temp = tempfile::tempdir().unwrap();
+        let ssot_dir = temp.path();
+        let db = Arc::new(Database::memory().unwrap());
+
+        let hook = |id: &str| {
+            let (namespace, filename) = HookService::parse_id(id);
+            let mut hook = create_test_hook(id, &namespace, &filename);
+            hook.apps = HookApps::default();
+            hook
+        };
+        for id in ["team/lint/check", "check"] {
+            db.save_hook(&hook(id)).unwrap();
+            let path = ssot_dir.join(HookService::id_to_relative_path(id));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, "{}").unwrap();
+        }
+
+        // 根命名空间已有同名 Hook，拒绝移动
+        let err = HookService::move_in_dir(&db, ssot_dir, "team/lint/check", "").unwrap_err();
+        assert!(err.to_string().contains("已存在"));
+        assert!(HookService::move_in_dir(&db, ssot_dir, "check", "../escape").is_err());
+
+        let moved = HookService::move_in_dir(&db, ssot_dir, "team/lint/check", "security").unwrap();
+        assert_eq!(moved.id, "security/check");
+        assert_eq!(moved.namespace, "security");
+        assert!(ssot_dir.join("security/check.json").exists());
+        assert!(db.get_installed_hook("team/lint/check").unwrap().is_none());
+        assert!(db.get_installed_hook("security/check").unwrap().is_some());
+        // 空的旧命名空间目录已逐级清理
+        assert!(!ssot_dir.join("team").exists());
+
+        // 批量移动：legacy/sub/check 与已有的 security/check 冲突，只报告不移动
+        for id in ["legacy/lint", "legacy/sub/check", "legacy/sub/format"] {
+            db.save_hook(&hook(id)).unwrap();
+            let path = ssot_dir.join(HookService::id_to_relative_path(id));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, "{}").unwrap();
+        }
+        let result =
+            HookService::bulk_move_in_dir(&db, ssot_dir, "legacy/**", "/security/").unwrap();
+        let mut moved: Vec<_> = result.moved.iter().map(|m| m.to.as_str()).collect();
+        moved.sort();
+        assert_eq!(moved, ["security/format", "security/lint"]);
+        assert_eq!(
+            result.collisions,
+            [HookMove {
+                from: "legacy/sub/check".to_string(),
+                to: "security/check".to_string(),
+            }]
+        );
+        assert!(result.failed.is_empty());
+        assert!(ssot_dir.join("legacy/sub/check.json").exists());
+        assert!(ssot_dir.join("security/lint.json").exists());
+    }
+
+    #[test]
+    fn quarantine_disables_hooks_with_invalid_json() {
+        let temp = tempfile::tempdir().unwrap();
+        let ssot_dir = temp.path();
+        let db = Arc::new(Database::memory().unwrap());
+
+        for (id, content) in [("good", "{}"), ("broken", "{ not json")] {
+            let (namespace, filename) = HookService::parse_id(id);
+            db.save_hook(&create_test_hook(id, &namespace, &filename))
+                .unwrap();
+            fs::write(ssot_dir.join(HookService::id_to_relative_path(id)), content).unwrap();
+        }
+
+        let results = HookService::validate_all_in_dir(&db, ssot_dir).unwrap();
+        let invalid: Vec<_> = results
+            .iter()
+            .filter(|r| r.error.is_some())
+            .map(|r| r.hook_id.as_str())
+            .collect();
+        assert_eq!(invalid, ["broken"]);
+
+        let quarantined = HookService::quarantine_invalid_in_dir(&db, ssot_dir).unwrap();
+        assert_eq!(quarantined, ["broken"]);
+        assert!(!db.get_installed_hook("broken").unwrap().unwrap().enabled);
+        assert!(db.get_installed_hook("good").unwrap().unwrap().enabled);
+
+        // 已停用的 Hook 不会被重复隔离
+        assert!(HookService::quarantine_invalid_in_dir(&db, ssot_dir)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn quarantine_skips_locked_hooks() {
+        let temp = tempfile::tempdir().unwrap();
+        let ssot_dir = temp.path();
+        let db = Arc::new(Database::memory().unwrap());
+        db.save_hook(&create_test_hook("broken", "", "broken"))
+            .unwrap();
+        fs::write(
+            ssot_dir.join(HookService::id_to_relative_path("broken")),
+            "{ not json",
+        )
+        .unwrap();
+        ResourceLockService::set_locked(&db, ConfigTarget::Hook, "broken", true).unwrap();
+
+        assert!(HookService::quarantine_invalid_in_dir(&db, ssot_dir)
+            .unwrap()
+            .is_empty());
+        assert!(db.get_installed_hook("broken").unwrap().unwrap().enabled);
+    }
+
+    #[test]
+    fn glob_match_handles_single_and_double_star() {
+        assert!(glob_match("legacy/*", "legacy/lint"));
+        assert!(!glob_match("legacy/*", "legacy/sub/lint"));
+        assert!(glob_match("legacy/**", "legacy/sub/lint"));
+        assert!(glob_match("**/lint", "lint"));
+        assert!(glob_match("**/lint", "a/b/lint"));
+        assert!(glob_match("pre-?", "pre-1"));
+        assert!(!glob_match("legacy/*", "legacyx/lint"));
+        assert!(glob_match("check", "check"));
+        assert!(!glob_match("check", "checks"));
+    }
+
+    #[test]
+    fn codex_toml_hooks_merge_round_trip_preserves_other_keys() {
+        let base = r#"# 用户注释
+model = "gpt-5"
+model_provider = "openai"
+
+[mcp_servers.fs]
+command = "npx"
+args = ["-y", "@modelcontextprotocol/server-filesystem"]
+
+[hooks]
+Stale = []
+"#;
+        let hooks = serde_json::json!({
+            "PreToolUse": [{
+                "matcher": "Bash",
+                "hooks": [{ "type": "command", "command": "echo pre", "timeout": null }]
+            }]
+        });
+
+        let merged = HookService::merge_hooks_into_toml(base, &hooks).unwrap();
+        assert!(merged.contains("# 用户注释"));
+
+        let parsed: toml::Table = merged.parse().unwrap();
+        assert_eq!(parsed["model"].as_str(), Some("gpt-5"));
+        assert_eq!(parsed["mcp_servers"]["fs"]["command"].as_str(), Some("npx"));
+
+        let expected = serde_json::json!({
+            "PreToolUse": [{
+                "matcher": "Bash",
+                "hooks": [{ "type": "command", "command": "echo pre" }]
+            }]
+        });
+        assert_eq!(
+            HookService::hooks_from_toml(&merged).unwrap(),
+            Some(expected.clone())
+        );
+
+        // 再次合并相同内容应保持不变
+        let again = HookService::merge_hooks_into_toml(&merged, &expected).unwrap();
+        assert_eq!(again, merged);
+
+        // 清空 hooks 时移除整个表，其余键保留
+        let cleared = HookService::merge_hooks_into_toml(&merged, &serde_json::json!({})).unwrap();
+        assert_eq!(HookService::hooks_from_toml(&cleared).unwrap(), None);
+        assert!(cleared.contains("[mcp_servers.fs]"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_hook_command_pipes_event_and_captures_result() {
+        let work_dir = tempfile::tempdir().unwrap();
+        let result = HookService::run_hook_command(
+            "cat; echo \"dir=$CLAUDE_PROJECT_DIR\" >&2; exit 2",
+            br#"{"hook_event_name":"PreToolUse"}"#,
+            work_dir.path(),
+            Duration::from_secs(10),
+        )
+        .unwrap();
+
+        assert_eq!(result.stdout, r#"{"hook_event_name":"PreToolUse"}"#);
+        assert_eq!(
+            result.stderr.trim(),
+            format!("dir={}", work_dir.path().display())
+        );
+        assert_eq!(result.exit_code, Some(2));
+        assert!(result.blocked);
+        assert!(!result.timed_out);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_hook_command_kills_on_timeout() {
+        let work_dir = tempfile::tempdir().unwrap();
+        let result = HookService::run_hook_command(
+            "exec sleep 5",
+            b"{}",
+            work_dir.path(),
+            Duration::from_millis(200),
+        )
+        .unwrap();
+
+        assert!(result.timed_out);
+        assert_eq!(result.exit_code, None);
+        assert!(result.duration_ms < 5000);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_hook_command_kills_background_children_on_timeout() {
+        let work_dir = tempfile::tempdir().unwrap();
+        let started = Instant::now();
+        let result = HookService::run_hook_command(
+            "sleep 5 & echo started; wait",
+            b"{}",
+            work_dir.path(),
+            Duration::from_millis(300),
+        )
+        .unwrap();
+
+        assert!(result.timed_out);
+        assert!(started.elapsed() < Duration::from_secs(3));
+        // 后台的 sleep 也被终止，输出管道关闭后才能读到完整输出
+        assert_eq!(result.stdout.trim(), "started");
+    }
+
+    #[test]
+    fn synthetic_event_follows_hook_protocol() {
+        let cwd = Path::new("/tmp/hook-test");
+        let pre = HookService::synthetic_event(&HookEventType::PreToolUse, "Edit|Write", cwd);
+        assert_eq!(pre["hook_event_name"], "PreToolUse");
+        assert_eq!(pre["tool_name"], "Edit");
+        assert!(pre.get("tool_response").is_none());
+
+        let post = HookService::synthetic_event(&HookEventType::PostToolUse, "", cwd);
+        assert_eq!(post["tool_name"], "Bash");
+        assert_eq!(post["tool_input"]["command"], "echo cc-switch hook test");
+        assert!(post["tool_response"].is_object());
+
+        let end = HookService::synthetic_event(&HookEventType::SessionEnd, "", cwd);
+        assert_eq!(end["reason"], "other");
+        assert!(end.get("tool_name").is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn create_from_content_cleans_up_when_sync_fails() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let content = r#"{
+            "name": "Format",
+            "eventType": "PostToolUse",
+            "rules": [{ "matcher": "Edit", "hooks": [{ "type": "command", "command": "fmt" }] }]
+        }"#;
+        let dest = HookService::get_ssot_dir()
+            .unwrap()
+            .join(HookService::id_to_relative_path("format"));
+
+        // settings.json 被同名目录占用，同步到应用时失败
+        let settings_path = HookService::get_app_settings_path(&AppType::Claude).unwrap();
+        fs::create_dir_all(&settings_path).unwrap();
+
+        assert!(HookService::create_from_content(&db, None, content, &AppType::Claude).is_err());
+        assert!(db.get_installed_hook("format").unwrap().is_none());
+        assert!(!dest.exists());
+
+        fs::remove_dir(&settings_path).unwrap();
+        let hook = HookService::create_from_content(&db, None, content, &AppType::Claude).unwrap();
+        assert_eq!(hook.id, "format");
+        assert!(dest.exists());
+        let settings = fs::read_to_string(&settings_path).unwrap();
+        assert!(settings.contains("fmt"));
+    }
+
+    #[test]
+    #[serial]
+    fn uninstall_removes_hook_with_unknown_event_type() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        db.save_hook(&create_test_hook("legacy/start", "legacy", "start"))
+            .unwrap();
+        db.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "UPDATE hooks SET event_type = 'Bogus' WHERE id = 'legacy/start'",
+                [],
+            )
+            .unwrap();
+        let path = HookService::get_ssot_dir()
+            .unwrap()
+            .join(HookService::id_to_relative_path("legacy/start"));
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "{}").unwrap();
+
+        HookService::uninstall(&db, "legacy/start").unwrap();
+
+        assert!(!path.exists());
+        assert!(db.get_hooks_with_unknown_event_type().unwrap().is_empty());
+        assert!(HookService::uninstall(&db, "legacy/start").is_err());
+    }
+}
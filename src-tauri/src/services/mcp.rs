@@ -1,11 +1,46 @@
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Value};
 use std::collections::HashMap;
+use std::sync::LazyLock;
 
 use crate::app_config::{AppType, McpServer};
 use crate::error::AppError;
 use crate::mcp;
 use crate::store::AppState;
 
+/// `${NAME}` 形式的环境变量引用；带 `:-默认值` 的引用不会被视为未定义
+static ENV_REF_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)(:-[^}]*)?\}").expect("valid env ref regex")
+});
+
+/// MCP 配置检查发现的问题类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum McpWarningKind {
+    /// stdio 服务器缺少 command，或命令不在 CC Switch 进程的 PATH 中
+    MissingCommand,
+    /// 引用了 CC Switch 进程中未定义的环境变量
+    UndefinedEnvVar,
+    /// env 中的变量相互（或自身）引用
+    CircularEnvVar,
+    /// 多个服务器使用相同名称
+    DuplicateName,
+    /// 未知的传输类型或缺少/无效的 url
+    InvalidTransport,
+}
+
+/// MCP 配置检查警告（非致命，仅供 UI 提示）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpWarning {
+    pub server_id: String,
+    pub server_name: String,
+    pub kind: McpWarningKind,
+    pub message: String,
+}
+
 /// MCP 相关业务逻辑（v3.7.0 统一结构）
 pub struct McpService;
 
@@ -428,4 +463,290 @@ impl McpService {
 
         Ok(new_count)
     }
+
+    /// 检查所有 MCP 服务器的常见配置问题，返回非致命警告
+    ///
+    /// 包括：命令缺失、引用未定义或循环引用的环境变量、重名、无效的传输类型。
+    ///
+    /// 命令与环境变量按 CC Switch 自身进程的 PATH 和环境检查。从桌面启动时它们可能与
+    /// 终端不同（不含 shell 配置文件中的设置），而 CLI 在终端中启动 MCP 服务器，
+    /// 因此这两类警告只说明 CC Switch 找不到，提示文字中会注明这一点。
+    pub fn lint(state: &AppState) -> Result<Vec<McpWarning>, AppError> {
+        let servers = state.db.get_all_mcp_servers()?;
+        Ok(lint_servers(
+            &servers,
+            |name| std::env::var_os(name).is_some(),
+            |cmd| crate::claude_mcp::validate_command_in_path(cmd).unwrap_or(false),
+        ))
+    }
+}
+
+fn lint_servers(
+    servers: &IndexMap<String, McpServer>,
+    env_defined: impl Fn(&str) -> bool,
+    command_exists: impl Fn(&str) -> bool,
+) -> Vec<McpWarning> {
+    let mut warnings = Vec::new();
+
+    for server in servers.values() {
+        let mut warn = |kind, message| {
+            warnings.push(McpWarning {
+                server_id: server.id.clone(),
+                server_name: server.name.clone(),
+                kind,
+                message,
+            })
+        };
+        let Some(spec) = server.server.as_object() else {
+            warn(
+                McpWarningKind::InvalidTransport,
+                "连接定义必须为 JSON 对象".to_string(),
+            );
+            continue;
+        };
+        lint_transport(spec, &command_exists, &mut warn);
+        lint_env_refs(spec, &env_defined, &mut warn);
+    }
+
+    // 名称不区分大小写比较，各应用的 live 配置中会出现难以区分的同名条目
+    let mut by_name: IndexMap<String, Vec<&McpServer>> = IndexMap::new();
+    for server in servers.values() {
+        by_name
+            .entry(server.name.trim().to_lowercase())
+            .or_default()
+            .push(server);
+    }
+    for group in by_name.values().filter(|group| group.len() > 1) {
+        for server in group {
+            let others: Vec<&str> = group
+                .iter()
+                .filter(|other| other.id != server.id)
+                .map(|other| other.id.as_str())
+                .collect();
+            warnings.push(McpWarning {
+                server_id: server.id.clone(),
+                server_name: server.name.clone(),
+                kind: McpWarningKind::DuplicateName,
+                message: format!("名称与 {} 重复", others.join(", ")),
+            });
+        }
+    }
+
+    warnings
+}
+
+fn str_field<'a>(spec: &'a Map<String, Value>, key: &str) -> &'a str {
+    spec.get(key).and_then(Value::as_str).unwrap_or("").trim()
+}
+
+fn lint_transport(
+    spec: &Map<String, Value>,
+    command_exists: &impl Fn(&str) -> bool,
+    warn: &mut impl FnMut(McpWarningKind, String),
+) {
+    // 省略 type 视为 stdio（与 validate_server_spec 一致）
+    match spec.get("type").and_then(Value::as_str).unwrap_or("stdio") {
+        "stdio" => {
+            let cmd = str_field(spec, "command");
+            if cmd.is_empty() {
+                warn(
+                    McpWarningKind::MissingCommand,
+                    "stdio 类型缺少 command 字段".to_string(),
+                );
+            } else if !cmd.contains("${") && !command_exists(cmd) {
+                warn(
+                    McpWarningKind::MissingCommand,
+                    format!(
+                        "在 CC Switch 的 PATH 中找不到命令 {cmd}（从桌面启动时 PATH 可能与终端不同，可改用绝对路径）"
+                    ),
+                );
+            }
+        }
+        transport @ ("http" | "sse") => {
+            let url = str_field(spec, "url");
+            if url.is_empty() {
+                warn(
+                    McpWarningKind::InvalidTransport,
+                    format!("{transport} 类型缺少 url 字段"),
+                );
+            } else if !url.contains("${")
+                && !url.starts_with("http://")
+                && !url.starts_with("https://")
+            {
+                warn(
+                    McpWarningKind::InvalidTransport,
+                    format!("{transport} 类型的 url 不是 http(s) 地址：{url}"),
+                );
+            }
+        }
+        other => warn(
+            McpWarningKind::InvalidTransport,
+            format!("未知的传输类型 {other}（应为 stdio、http 或 sse）"),
+        ),
+    }
+}
+
+/// 引用解析顺序：进程环境变量 → 同一服务器 env 中的其他条目
+///
+/// 只在 env 条目之间相互引用且无法从进程环境解析时才视为循环。
+fn lint_env_refs(
+    spec: &Map<String, Value>,
+    env_defined: &impl Fn(&str) -> bool,
+    warn: &mut impl FnMut(McpWarningKind, String),
+) {
+    let env = spec.get("env").and_then(Value::as_object);
+    let unresolved_refs = |text: &str| -> Vec<String> {
+        ENV_REF_RE
+            .captures_iter(text)
+            .filter(|caps| caps.get(2).is_none())
+            .map(|caps| caps[1].to_string())
+            .filter(|name| !env_defined(name))
+            .collect()
+    };
+
+    let mut texts: Vec<&str> = vec![str_field(spec, "command"), str_field(spec, "url")];
+    if let Some(args) = spec.get("args").and_then(Value::as_array) {
+        texts.extend(args.iter().filter_map(Value::as_str));
+    }
+    if let Some(headers) = spec.get("headers").and_then(Value::as_object) {
+        texts.extend(headers.values().filter_map(Value::as_str));
+    }
+
+    let mut undefined: IndexSet<String> = IndexSet::new();
+    let mut graph: IndexMap<&str, Vec<String>> = IndexMap::new();
+    for text in texts {
+        undefined.extend(
+            unresolved_refs(text)
+                .into_iter()
+                .filter(|name| env.is_none_or(|env| !env.contains_key(name))),
+        );
+    }
+    for (key, value) in env.into_iter().flatten() {
+        let refs = unresolved_refs(value.as_str().unwrap_or(""));
+        let (siblings, missing): (Vec<String>, Vec<String>) = refs
+            .into_iter()
+            .partition(|name| env.is_some_and(|env| env.contains_key(name)));
+        undefined.extend(missing);
+        graph.insert(key.as_str(), siblings);
+    }
+
+    for name in undefined {
+        warn(
+            McpWarningKind::UndefinedEnvVar,
+            format!(
+                "环境变量 {name} 在 CC Switch 进程中未定义（仅在 shell 配置中设置的变量无法检测到）"
+            ),
+        );
+    }
+    for key in graph.keys() {
+        if reaches(&graph, key, key, &mut Vec::new()) {
+            warn(
+                McpWarningKind::CircularEnvVar,
+                format!("环境变量 {key} 存在循环引用"),
+            );
+        }
+    }
+}
+
+/// 判断 env 引用图中能否从 `from` 走到 `target`
+fn reaches<'a>(
+    graph: &'a IndexMap<&str, Vec<String>>,
+    from: &str,
+    target: &str,
+    visited: &mut Vec<&'a str>,
+) -> bool {
+    let Some(next) = graph.get(from) else {
+        return false;
+    };
+    for name in next {
+        if name == target {
+            return true;
+        }
+        if !visited.contains(&name.as_str()) {
+            visited.push(name.as_str());
+            if reaches(graph, name, target, visited) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::McpApps;
+    use serde_json::json;
+
+    fn server(id: &str, name: &str, spec: Value) -> McpServer {
+        McpServer {
+            id: id.to_string(),
+            name: name.to_string(),
+            server: spec,
+            apps: McpApps::default(),
+            description: None,
+            homepage: None,
+            docs: None,
+            tags: Vec::new(),
+        }
+    }
+
+    fn kinds(servers: Vec<McpServer>) -> Vec<(String, McpWarningKind)> {
+        let servers: IndexMap<String, McpServer> =
+            servers.into_iter().map(|s| (s.id.clone(), s)).collect();
+        lint_servers(&servers, |name| name == "HOME", |cmd| cmd == "npx")
+            .into_iter()
+            .map(|w| (w.server_id, w.kind))
+            .collect()
+    }
+
+    #[test]
+    fn lint_flags_transport_and_command_problems() {
+        let found = kinds(vec![
+            server("ok", "ok", json!({"command": "npx", "args": ["-y", "pkg"]})),
+            server("no-cmd", "no-cmd", json!({"type": "stdio"})),
+            server("missing", "missing", json!({"command": "not-installed"})),
+            server(
+                "bad-url",
+                "bad-url",
+                json!({"type": "sse", "url": "ws://x"}),
+            ),
+            server("bad-type", "bad-type", json!({"type": "grpc"})),
+        ]);
+        assert_eq!(
+            found,
+            vec![
+                ("no-cmd".to_string(), McpWarningKind::MissingCommand),
+                ("missing".to_string(), McpWarningKind::MissingCommand),
+                ("bad-url".to_string(), McpWarningKind::InvalidTransport),
+                ("bad-type".to_string(), McpWarningKind::InvalidTransport),
+            ]
+        );
+    }
+
+    #[test]
+    fn lint_flags_env_refs_and_duplicate_names() {
+        let found = kinds(vec![
+            server(
+                "env",
+                "Env",
+                json!({
+                    "command": "npx",
+                    "args": ["${HOME}/x", "${TOKEN}", "${OPT:-default}"],
+                    "env": {"A": "${B}", "B": "${A}", "C": "${HOME}"}
+                }),
+            ),
+            server("dup", "env", json!({"command": "npx"})),
+        ]);
+        assert_eq!(
+            found,
+            vec![
+                ("env".to_string(), McpWarningKind::UndefinedEnvVar),
+                ("env".to_string(), McpWarningKind::CircularEnvVar),
+                ("env".to_string(), McpWarningKind::CircularEnvVar),
+                ("env".to_string(), McpWarningKind::DuplicateName),
+                ("dup".to_string(), McpWarningKind::DuplicateName),
+            ]
+        );
+    }
 }
@@ -5,17 +5,31 @@ pub mod builtin_repos;
 pub mod coding_plan;
 pub mod command;
 pub mod config;
+pub mod content_search;
+pub mod duplicate;
 pub mod env_checker;
 pub mod env_manager;
 pub mod github_api;
 pub mod hook;
 pub mod mcp;
 pub mod model_fetch;
+pub mod namespace_check;
+pub mod notification;
 pub mod omo;
+pub mod orphaned_installs;
+pub mod pin_staleness;
 pub mod project;
 pub mod prompt;
+pub mod provenance;
 pub mod provider;
 pub mod proxy;
+pub mod recipe;
+pub mod repo_installs;
+pub mod repo_metadata;
+pub mod resource_export;
+pub mod resource_lock;
+pub mod resource_preview;
+pub mod resumable_download;
 pub mod session_usage;
 pub mod session_usage_codex;
 pub mod session_usage_gemini;
@@ -23,6 +37,7 @@ pub mod skill;
 pub mod speedtest;
 pub mod stream_check;
 pub mod subscription;
+pub mod trash;
 pub mod update;
 pub mod usage_cache;
 pub mod usage_stats;
@@ -43,7 +58,7 @@ pub use provider::{ProviderService, ProviderSortUpdate, SwitchResult};
 pub use proxy::ProxyService;
 #[allow(unused_imports)]
 pub use skill::{DiscoverableSkill, Skill, SkillRepo, SkillService};
-pub use speedtest::{EndpointLatency, SpeedtestService};
+pub use speedtest::{EndpointLatency, SpeedtestRecord, SpeedtestService};
 pub use usage_cache::UsageCache;
 #[allow(unused_imports)]
 pub use usage_stats::{
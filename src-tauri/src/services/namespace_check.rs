@@ -0,0 +1,518 @@
+//! SSOT 与数据库的命名空间一致性检查
+//!
+//! Commands/Agents/Hooks 的 `namespace` 列应与 SSOT 文件所在目录一致。手动移动文件后
+//! 两者可能不一致（数据库记录 `sc/agent`，文件却在根目录），这里按 SSOT 文件路径
+//! 重新计算命名空间并与数据库比对，并可按文件的实际位置修复数据库记录。
+//!
+//! 另外检测数据库中已没有记录、但 SSOT 或应用目录中仍残留空目录的命名空间，
+//! 由用户显式触发清理。
+
+use crate::app_config::{AppType, InstallScope};
+use crate::database::Database;
+use crate::services::agent::AgentService;
+use crate::services::command::CommandService;
+use crate::services::hook::HookService;
+use crate::services::update::ResourceType;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// 命名空间不一致的记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceMismatch {
+    /// 数据库中的 ID
+    pub id: String,
+    /// 数据库中记录的命名空间
+    pub stored_namespace: String,
+    /// 按 SSOT 文件路径计算出的命名空间
+    pub actual_namespace: String,
+    /// 文件实际位置对应的 ID（与 `id` 不同表示文件已被移动）
+    pub actual_id: String,
+}
+
+/// 空命名空间清理结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceCleanupReport {
+    /// 已清理的命名空间
+    pub removed: Vec<String>,
+    /// 已删除的目录
+    pub removed_dirs: Vec<String>,
+    /// 删除失败的目录（格式 "路径: 错误"）
+    pub failed: Vec<String>,
+}
+
+/// 目录树中不含任何文件（只有空的子目录或完全为空）
+///
+/// 符号链接视为文件，不跟随
+fn is_empty_tree(dir: &Path) -> bool {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries.flatten().all(|entry| {
+                entry.file_type().is_ok_and(|t| t.is_dir()) && is_empty_tree(&entry.path())
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// 自下而上删除空目录，遇到文件时失败
+fn remove_empty_tree(dir: &Path) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            remove_empty_tree(&entry.path())?;
+        }
+    }
+    fs::remove_dir(dir)
+}
+
+/// 在各根目录中查找没有数据库记录且不含文件的命名空间目录
+///
+/// `used` 为数据库中的命名空间；`a/b` 这类嵌套命名空间会让顶层目录 `a` 视为在用
+fn find_empty_namespaces(
+    roots: &[PathBuf],
+    used: &HashSet<String>,
+) -> BTreeMap<String, Vec<PathBuf>> {
+    let in_use = |name: &str| {
+        used.iter()
+            .any(|ns| ns == name || ns.starts_with(&format!("{name}/")))
+    };
+
+    let mut found: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for root in roots {
+        let Ok(entries) = fs::read_dir(root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || in_use(&name) || !is_empty_tree(&entry.path()) {
+                continue;
+            }
+            found.entry(name).or_default().push(entry.path());
+        }
+    }
+    found
+}
+
+/// 项目级安装的项目路径，全局安装返回 None
+fn project_path_of(scope: &str, project_path: &Option<String>) -> Option<PathBuf> {
+    InstallScope::from_db(scope, project_path.as_deref())
+        .project_path()
+        .map(Path::to_path_buf)
+}
+
+/// 参与比对的记录字段
+struct NamespaceRecord {
+    id: String,
+    namespace: String,
+    filename: String,
+}
+
+pub struct NamespaceCheckService;
+
+impl NamespaceCheckService {
+    /// 检查数据库中的命名空间与 SSOT 文件位置是否一致
+    ///
+    /// 文件不在 ID 对应位置时，若 SSOT 中恰有一个未被记录的同名文件，视为被移动到该位置；
+    /// 找不到或有多个候选时跳过（文件缺失由其他检查处理）
+    pub fn verify_namespace_consistency(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+    ) -> Result<Vec<NamespaceMismatch>> {
+        let (records, files) = Self::load(db, resource_type)?;
+        Ok(Self::find_mismatches(&records, &files))
+    }
+
+    /// 按 SSOT 文件的实际位置修复数据库记录，返回已修复的项
+    ///
+    /// 文件被移动时记录改用新 ID，项目级安装的副本随之移动；
+    /// 应用目录中的全局副本在下次同步时更新
+    pub fn repair_namespaces(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+    ) -> Result<Vec<NamespaceMismatch>> {
+        let mut repaired = Vec::new();
+
+        for mismatch in Self::verify_namespace_consistency(db, resource_type)? {
+            match Self::repair_one(db, resource_type, &mismatch) {
+                Ok(()) => repaired.push(mismatch),
+                Err(e) => log::warn!("修复 {resource_type} {} 的命名空间失败: {e}", mismatch.id),
+            }
+        }
+
+        log::info!("已修复 {} 个 {resource_type} 的命名空间", repaired.len());
+        Ok(repaired)
+    }
+
+    /// 列出数据库中没有记录、但 SSOT 或应用目录中仍残留空目录的命名空间
+    pub fn list_empty_namespaces(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+    ) -> Result<Vec<String>> {
+        Ok(Self::empty_namespace_dirs(db, resource_type)?
+            .into_keys()
+            .collect())
+    }
+
+    /// 删除空命名空间在 SSOT 和各应用目录中的残留目录
+    ///
+    /// 只删除不含任何文件的目录；与 `delete_namespace` 不同，无需预先知道名称
+    pub fn cleanup_empty_namespaces(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+    ) -> Result<NamespaceCleanupReport> {
+        let mut report = NamespaceCleanupReport::default();
+
+        for (namespace, dirs) in Self::empty_namespace_dirs(db, resource_type)? {
+            let mut removed_any = false;
+            for dir in dirs {
+                match remove_empty_tree(&dir) {
+                    Ok(()) => {
+                        removed_any = true;
+                        report.removed_dirs.push(dir.display().to_string());
+                    }
+                    Err(e) => report.failed.push(format!("{}: {e}", dir.display())),
+                }
+            }
+            if removed_any {
+                report.removed.push(namespace);
+            }
+        }
+
+        log::info!(
+            "已清理 {} 个空的 {resource_type} 命名空间",
+            report.removed.len()
+        );
+        Ok(report)
+    }
+
+    /// 空命名空间 → 残留目录（SSOT 与各应用目录）
+    fn empty_namespace_dirs(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+    ) -> Result<BTreeMap<String, Vec<PathBuf>>> {
+        const APPS: [AppType; 3] = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+        let (used, roots): (HashSet<String>, Vec<PathBuf>) = match resource_type {
+            ResourceType::Command => (
+                db.get_all_installed_commands()?
+                    .into_values()
+                    .map(|c| c.namespace)
+                    .collect(),
+                std::iter::once(CommandService::get_ssot_dir())
+                    .chain(APPS.iter().map(CommandService::get_app_commands_dir))
+                    .flatten()
+                    .collect(),
+            ),
+            ResourceType::Agent => (
+                db.get_all_installed_agents()?
+                    .into_values()
+                    .map(|a| a.namespace)
+                    .collect(),
+                std::iter::once(AgentService::get_ssot_dir())
+                    .chain(APPS.iter().map(AgentService::get_app_agents_dir))
+                    .flatten()
+                    .collect(),
+            ),
+            // Hooks 同步到 settings.json，只有 SSOT 目录
+            ResourceType::Hook => (
+                db.get_all_installed_hooks()?
+                    .into_values()
+                    .map(|h| h.namespace)
+                    .collect(),
+                vec![HookService::get_ssot_dir()?],
+            ),
+            ResourceType::Skill => {
+                return Err(anyhow!("{resource_type} 没有命名空间"));
+            }
+        };
+
+        Ok(find_empty_namespaces(&roots, &used))
+    }
+
+    /// 读取数据库记录与 SSOT 文件列表（ID → 路径）
+    fn load(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+    ) -> Result<(Vec<NamespaceRecord>, HashMap<String, PathBuf>)> {
+        let loaded = match resource_type {
+            ResourceType::Command => (
+                db.get_all_installed_commands()?
+                    .into_values()
+                    .map(|c| NamespaceRecord {
+                        id: c.id,
+                        namespace: c.namespace,
+                        filename: c.filename,
+                    })
+                    .collect(),
+                CommandService::scan_ssot_files(&CommandService::get_ssot_dir()?)?,
+            ),
+            ResourceType::Agent => (
+                db.get_all_installed_agents()?
+                    .into_values()
+                    .map(|a| NamespaceRecord {
+                        id: a.id,
+                        namespace: a.namespace,
+                        filename: a.filename,
+                    })
+                    .collect(),
+                AgentService::scan_ssot_files(&AgentService::get_ssot_dir()?)?,
+            ),
+            ResourceType::Hook => (
+                db.get_all_installed_hooks()?
+                    .into_values()
+                    .map(|h| NamespaceRecord {
+                        id: h.id,
+                        namespace: h.namespace,
+                        filename: h.filename,
+                    })
+                    .collect(),
+                HookService::scan_ssot_files(&HookService::get_ssot_dir()?)?,
+            ),
+            ResourceType::Skill => {
+                return Err(anyhow!("{resource_type} 没有命名空间"));
+            }
+        };
+        Ok(loaded)
+    }
+
+    fn find_mismatches(
+        records: &[NamespaceRecord],
+        files: &HashMap<String, PathBuf>,
+    ) -> Vec<NamespaceMismatch> {
+        let filename_of = |id: &str| CommandService::parse_id(id).1;
+
+        // 没有对应记录的 SSOT 文件，以及文件不在 ID 对应位置的记录，均按文件名分组
+        let mut orphans: HashMap<String, Vec<&str>> = HashMap::new();
+        for id in files.keys() {
+            if !records.iter().any(|r| &r.id == id) {
+                orphans
+                    .entry(filename_of(id))
+                    .or_default()
+                    .push(id.as_str());
+            }
+        }
+        let mut missing: HashMap<&str, usize> = HashMap::new();
+        for record in records.iter().filter(|r| !files.contains_key(&r.id)) {
+            *missing.entry(record.filename.as_str()).or_default() += 1;
+        }
+
+        records
+            .iter()
+            .filter_map(|record| {
+                let actual_id = if files.contains_key(&record.id) {
+                    record.id.clone()
+                } else {
+                    // 只有一一对应时才能确定文件被移动到了哪里
+                    match orphans.get(&record.filename).map(Vec::as_slice) {
+                        Some([only]) if missing.get(record.filename.as_str()) == Some(&1) => {
+                            only.to_string()
+                        }
+                        _ => return None,
+                    }
+                };
+                let actual_namespace = CommandService::parse_id(&actual_id).0;
+
+                (actual_id != record.id || actual_namespace != record.namespace).then(|| {
+                    NamespaceMismatch {
+                        id: record.id.clone(),
+                        stored_namespace: record.namespace.clone(),
+                        actual_namespace,
+                        actual_id,
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn repair_one(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+        mismatch: &NamespaceMismatch,
+    ) -> Result<()> {
+        let not_found = || anyhow!("{resource_type} 未安装: {}", mismatch.id);
+        let moved = mismatch.actual_id != mismatch.id;
+
+        match resource_type {
+            ResourceType::Command => {
+                let mut command = db
+                    .get_installed_command(&mismatch.id)?
+                    .ok_or_else(not_found)?;
+                command.id = mismatch.actual_id.clone();
+                command.namespace = mismatch.actual_namespace.clone();
+                db.save_command(&command)?;
+                if moved {
+                    db.delete_command(&mismatch.id)?;
+                    let project = project_path_of(&command.scope, &command.project_path);
+                    if let Some(project_path) = project {
+                        CommandService::remove_from_project(&mismatch.id, &project_path)?;
+                        CommandService::copy_to_project(&command.id, &project_path)?;
+                    }
+                }
+            }
+            ResourceType::Agent => {
+                let mut agent = db
+                    .get_installed_agent(&mismatch.id)?
+                    .ok_or_else(not_found)?;
+                agent.id = mismatch.actual_id.clone();
+                agent.namespace = mismatch.actual_namespace.clone();
+                db.save_agent(&agent)?;
+                if moved {
+                    db.delete_agent(&mismatch.id)?;
+                    let project = project_path_of(&agent.scope, &agent.project_path);
+                    if let Some(project_path) = project {
+                        AgentService::remove_from_project(&mismatch.id, &project_path)?;
+                        AgentService::copy_to_project(&agent.id, &project_path)?;
+                    }
+                }
+            }
+            ResourceType::Hook => {
+                let mut hook = db.get_installed_hook(&mismatch.id)?.ok_or_else(not_found)?;
+                hook.id = mismatch.actual_id.clone();
+                hook.namespace = mismatch.actual_namespace.clone();
+                db.save_hook(&hook)?;
+                if moved {
+                    db.delete_hook(&mismatch.id)?;
+                    let project = project_path_of(&hook.scope, &hook.project_path);
+                    if let Some(project_path) = project {
+                        HookService::remove_from_project(&mismatch.id, &project_path)?;
+                        HookService::copy_to_project(&hook.id, &project_path)?;
+                    }
+                }
+            }
+            ResourceType::Skill => {
+                return Err(anyhow!("{resource_type} 没有命名空间"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{create_test_command, TempHome};
+    use serial_test::serial;
+
+    fn record(id: &str, namespace: &str) -> NamespaceRecord {
+        NamespaceRecord {
+            id: id.to_string(),
+            namespace: namespace.to_string(),
+            filename: CommandService::parse_id(id).1,
+        }
+    }
+
+    fn files(ids: &[&str]) -> HashMap<String, PathBuf> {
+        ids.iter()
+            .map(|id| (id.to_string(), PathBuf::from(format!("{id}.md"))))
+            .collect()
+    }
+
+    #[test]
+    fn detects_moved_file_and_drifted_column() {
+        let records = vec![
+            // 数据库记录在 sc 下，文件却被移到了根目录
+            record("sc/agent", "sc"),
+            // 文件位置正确，但 namespace 列与 ID 不一致
+            record("git/commit", ""),
+            record("review", ""),
+        ];
+        let files = files(&["agent", "git/commit", "review"]);
+
+        let mismatches = NamespaceCheckService::find_mismatches(&records, &files);
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(
+            mismatches[0],
+            NamespaceMismatch {
+                id: "sc/agent".to_string(),
+                stored_namespace: "sc".to_string(),
+                actual_namespace: String::new(),
+                actual_id: "agent".to_string(),
+            }
+        );
+        assert_eq!(mismatches[1].id, "git/commit");
+        assert_eq!(mismatches[1].actual_id, "git/commit");
+        assert_eq!(mismatches[1].actual_namespace, "git");
+    }
+
+    #[test]
+    fn finds_only_unused_namespaces_without_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let ssot = temp.path().join("ssot");
+        let app = temp.path().join("app");
+        for dir in [
+            "ssot/stale/nested",
+            "ssot/sc",
+            "ssot/git",
+            "app/stale",
+            "app/notes",
+        ] {
+            fs::create_dir_all(temp.path().join(dir)).unwrap();
+        }
+        fs::write(temp.path().join("ssot/sc/agent.md"), "x").unwrap();
+        // 含有未管理文件的目录不算空命名空间
+        fs::write(temp.path().join("app/notes/todo.md"), "x").unwrap();
+
+        // git 目录为空，但 git/commit 命名空间仍在使用
+        let used: HashSet<String> = ["sc", "git/commit"].map(String::from).into();
+        let found = find_empty_namespaces(&[ssot.clone(), app.clone()], &used);
+        assert_eq!(found.keys().collect::<Vec<_>>(), ["stale"]);
+        assert_eq!(found["stale"], [ssot.join("stale"), app.join("stale")]);
+
+        for dir in &found["stale"] {
+            remove_empty_tree(dir).unwrap();
+        }
+        assert!(!ssot.join("stale").exists());
+        assert!(ssot.join("sc/agent.md").exists());
+        assert!(remove_empty_tree(&app.join("notes")).is_err());
+    }
+
+    #[test]
+    fn ambiguous_or_missing_files_are_skipped() {
+        let records = vec![record("sc/agent", "sc"), record("gone", "")];
+        // 两个未记录的同名文件，无法确定 sc/agent 被移到了哪里
+        let files = files(&["a/agent", "b/agent"]);
+
+        assert!(NamespaceCheckService::find_mismatches(&records, &files).is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn repair_moves_project_copy_with_the_record() {
+        let home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let project = home.path().join("project");
+        let mut command = create_test_command("sc/agent", "sc", "agent");
+        command.scope = "project".to_string();
+        command.project_path = Some(project.to_string_lossy().to_string());
+        db.save_command(&command).unwrap();
+
+        // SSOT 文件被手动移到根目录，项目副本仍在旧位置
+        fs::write(
+            CommandService::get_ssot_dir().unwrap().join("agent.md"),
+            "x",
+        )
+        .unwrap();
+        let old_copy = project.join(".claude/commands/sc/agent.md");
+        fs::create_dir_all(old_copy.parent().unwrap()).unwrap();
+        fs::write(&old_copy, "x").unwrap();
+
+        let repaired =
+            NamespaceCheckService::repair_namespaces(&db, ResourceType::Command).unwrap();
+        assert_eq!(repaired.len(), 1);
+        assert!(db.get_installed_command("sc/agent").unwrap().is_none());
+        let moved = db.get_installed_command("agent").unwrap().unwrap();
+        assert_eq!(moved.namespace, "");
+        assert_eq!(moved.scope, "project");
+        assert!(!old_copy.exists());
+        assert!(project.join(".claude/commands/agent.md").exists());
+    }
+}
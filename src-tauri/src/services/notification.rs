@@ -0,0 +1,43 @@
+//! 系统通知服务
+//!
+//! 后台任务（如 WebDAV 自动同步）完成或失败时发送系统级通知：
+//! - 受 `background_notifications` 设置控制（默认关闭）
+//! - 主窗口处于焦点时不发送，前台操作已有应用内提示，避免重复通知
+//! - 点击通知由系统激活应用窗口
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// 后台任务通知
+pub struct BackgroundNotice<'a> {
+    pub title: &'a str,
+    pub body: &'a str,
+}
+
+/// 发送后台任务通知（按设置与窗口焦点过滤）
+pub fn notify_background(app: &AppHandle, notice: BackgroundNotice<'_>) {
+    if !crate::settings::get_settings().background_notifications {
+        return;
+    }
+
+    if is_main_window_focused(app) {
+        log::debug!("主窗口处于焦点，跳过系统通知: {}", notice.title);
+        return;
+    }
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(notice.title)
+        .body(notice.body)
+        .show()
+    {
+        log::warn!("发送系统通知失败: {e}");
+    }
+}
+
+fn is_main_window_focused(app: &AppHandle) -> bool {
+    app.get_webview_window("main")
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false)
+}
@@ -0,0 +1,180 @@
+//! 未启用的已安装资源
+//!
+//! 长期使用后会积累「已安装但没有在任何应用中启用」的资源。这里列出这些资源，
+//! 供用户清理或重新启用；批量卸载前需由用户确认列表，卸载时会再次校验，
+//! 期间被重新启用的资源会被跳过。
+
+use crate::services::agent::AgentService;
+use crate::services::command::CommandService;
+use crate::services::config::ConfigTarget;
+use crate::services::hook::HookService;
+use crate::services::mcp::McpService;
+use crate::services::repo_installs::uninstall_each;
+use crate::services::resource_lock::ResourceLockService;
+use crate::services::skill::SkillService;
+use crate::store::AppState;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// 批量卸载未启用资源的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanUninstallResult {
+    /// 已卸载的 ID
+    pub removed: Vec<String>,
+    /// 确认后已被重新启用或已不存在、因此未卸载的 ID
+    pub skipped: Vec<String>,
+    /// 未能卸载的资源及原因（格式 "类型 id: 错误"）
+    pub failed: Vec<String>,
+}
+
+/// 将用户确认的 ID 分为仍未启用（可卸载）与需跳过两组，保持确认时的顺序
+fn partition_confirmed(confirmed: Vec<String>, orphaned: &[String]) -> (Vec<String>, Vec<String>) {
+    let orphaned: HashSet<&str> = orphaned.iter().map(String::as_str).collect();
+    confirmed
+        .into_iter()
+        .partition(|id| orphaned.contains(id.as_str()))
+}
+
+pub struct OrphanedInstallsService;
+
+impl OrphanedInstallsService {
+    /// 列出没有在任何应用中启用的资源 ID（按 ID 排序）
+    ///
+    /// Hook 还需未全局启用才算未启用
+    pub fn list_orphaned_installs(
+        state: &AppState,
+        resource_type: ConfigTarget,
+    ) -> Result<Vec<String>> {
+        let db = &state.db;
+        let mut ids: Vec<String> = match resource_type {
+            ConfigTarget::Command => db
+                .get_all_installed_commands()?
+                .into_values()
+                .filter(|c| c.apps.is_empty())
+                .map(|c| c.id)
+                .collect(),
+            ConfigTarget::Agent => db
+                .get_all_installed_agents()?
+                .into_values()
+                .filter(|a| !a.apps.any_enabled())
+                .map(|a| a.id)
+                .collect(),
+            ConfigTarget::Hook => db
+                .get_all_installed_hooks()?
+                .into_values()
+                .filter(|h| !h.enabled && !h.apps.any_enabled())
+                .map(|h| h.id)
+                .collect(),
+            ConfigTarget::Skill => db
+                .get_all_installed_skills()?
+                .into_values()
+                .filter(|s| s.apps.is_empty())
+                .map(|s| s.id)
+                .collect(),
+            ConfigTarget::Mcp => db
+                .get_all_mcp_servers()?
+                .into_values()
+                .filter(|m| m.apps.is_empty())
+                .map(|m| m.id)
+                .collect(),
+        };
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// 卸载用户确认过的未启用资源
+    ///
+    /// 调用方应先用 [`Self::list_orphaned_installs`] 向用户展示卸载计划并确认；
+    /// 目标中有锁定资源且未传 force 时整批取消；单个资源卸载失败不会中断其余资源
+    pub fn uninstall_orphaned(
+        state: &AppState,
+        resource_type: ConfigTarget,
+        confirmed: Vec<String>,
+        force: bool,
+    ) -> Result<OrphanUninstallResult> {
+        let orphaned = Self::list_orphaned_installs(state, resource_type)?;
+        let (ids, skipped) = partition_confirmed(confirmed, &orphaned);
+        ResourceLockService::ensure_unlocked(
+            &state.db,
+            resource_type,
+            ids.iter().map(String::as_str),
+            force,
+        )?;
+        let mut result = OrphanUninstallResult {
+            skipped,
+            ..Default::default()
+        };
+
+        let db = &state.db;
+        let uninstall = |id: &str| -> Result<()> {
+            match resource_type {
+                ConfigTarget::Command => CommandService::uninstall(db, id),
+                ConfigTarget::Agent => AgentService::uninstall(db, id),
+                ConfigTarget::Hook => HookService::uninstall(db, id),
+                ConfigTarget::Skill => SkillService::uninstall(db, id).map(|_| ()),
+                ConfigTarget::Mcp => McpService::delete_server(state, id)
+                    .map(|_| ())
+                    .map_err(Into::into),
+            }
+        };
+        uninstall_each(
+            resource_type,
+            ids,
+            uninstall,
+            &mut result.removed,
+            &mut result.failed,
+        );
+
+        log::info!(
+            "已卸载未启用的 {resource_type}: {} 个, 跳过 {} 个, 失败 {} 个",
+            result.removed.len(),
+            result.skipped.len(),
+            result.failed.len()
+        );
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::HookApps;
+    use crate::database::Database;
+    use crate::test_support::create_test_hook;
+    use std::sync::Arc;
+
+    #[test]
+    fn partition_confirmed_skips_items_enabled_since_listing() {
+        let orphaned = vec!["a".to_string(), "c".to_string()];
+        let confirmed = vec!["c".to_string(), "b".to_string(), "a".to_string()];
+
+        let (ids, skipped) = partition_confirmed(confirmed, &orphaned);
+
+        assert_eq!(ids, ["c", "a"]);
+        assert_eq!(skipped, ["b"]);
+    }
+
+    #[test]
+    fn uninstall_orphaned_refuses_locked_hooks() {
+        let db = Arc::new(Database::memory().unwrap());
+        let mut hook = create_test_hook("idle", "", "idle");
+        hook.enabled = false;
+        hook.apps = HookApps::default();
+        db.save_hook(&hook).unwrap();
+        ResourceLockService::set_locked(&db, ConfigTarget::Hook, "idle", true).unwrap();
+
+        let state = AppState::new(db.clone());
+        let err = OrphanedInstallsService::uninstall_orphaned(
+            &state,
+            ConfigTarget::Hook,
+            vec!["idle".to_string()],
+            false,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("idle"));
+        assert!(db.get_installed_hook("idle").unwrap().is_some());
+    }
+}
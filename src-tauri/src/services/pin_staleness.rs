@@ -0,0 +1,254 @@
+//! 固定到提交的资源是否落后于分支
+//!
+//! 安装时分支填写为完整提交 SHA 的资源视为「已固定」。这里比较该提交中的文件
+//! blob 与仓库当前分支中的 blob，报告分支是否已在该文件上越过固定点，
+//! 供用户决定是否更新固定的提交。
+
+use crate::database::Database;
+use crate::services::command::CommandService;
+use crate::services::github_api::{GitHubApiError, GitHubApiService};
+use crate::services::update::{ResourceType, UpdateService};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// 文件在当前分支与固定提交之间的差异
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PinnedChange {
+    /// 与固定提交相同
+    Unchanged,
+    /// 当前分支中内容已变化
+    Modified,
+    /// 当前分支中文件已删除
+    Deleted,
+}
+
+/// 单个已固定资源的状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedStatus {
+    pub resource_type: ResourceType,
+    pub id: String,
+    pub repo_owner: String,
+    pub repo_name: String,
+    /// 固定的提交 SHA
+    pub pinned_commit: String,
+    /// 比较所用的分支（仓库配置的分支，未添加仓库时为默认分支）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// 分支中的文件已与固定提交不同
+    pub behind: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change: Option<PinnedChange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_blob: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_blob: Option<String>,
+    /// 固定提交到当前分支的 unified diff（仅内容变化时提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 已固定资源的仓库信息
+struct PinnedResource {
+    resource_type: ResourceType,
+    id: String,
+    owner: String,
+    name: String,
+    commit: String,
+    source_path: String,
+}
+
+/// 分支名是否为完整的提交 SHA
+fn is_commit_sha(branch: &str) -> bool {
+    branch.len() == 40 && branch.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub struct PinStalenessService;
+
+impl PinStalenessService {
+    /// 检查所有固定到提交的 Commands/Agents/Hooks 是否落后于当前分支
+    ///
+    /// 单个资源查询失败时记录在其 `error` 中，不影响其余资源
+    pub async fn check_pinned_staleness(db: &Arc<Database>) -> Result<Vec<PinnedStatus>> {
+        let pinned = Self::collect_pinned(db)?;
+        if pinned.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let github_api = GitHubApiService::new(db.get_setting("github_pat")?);
+        let repos = CommandService::get_repos(db)?;
+
+        let mut statuses = Vec::with_capacity(pinned.len());
+        for resource in pinned {
+            let configured = repos
+                .iter()
+                .find(|r| {
+                    r.owner.eq_ignore_ascii_case(&resource.owner)
+                        && r.name.eq_ignore_ascii_case(&resource.name)
+                })
+                .map(|r| r.branch.clone());
+            let branch = match configured {
+                Some(branch) => Ok(branch),
+                None => {
+                    github_api
+                        .get_default_branch(&resource.owner, &resource.name)
+                        .await
+                }
+            };
+
+            let mut status = PinnedStatus {
+                resource_type: resource.resource_type,
+                id: resource.id.clone(),
+                repo_owner: resource.owner.clone(),
+                repo_name: resource.name.clone(),
+                pinned_commit: resource.commit.clone(),
+                branch: None,
+                behind: false,
+                change: None,
+                pinned_blob: None,
+                current_blob: None,
+                diff: None,
+                error: None,
+            };
+            match branch {
+                Ok(branch) => {
+                    if let Err(e) =
+                        Self::compare(&github_api, &resource, &branch, &mut status).await
+                    {
+                        status.error = Some(e.to_string());
+                    }
+                    status.branch = Some(branch);
+                }
+                Err(e) => status.error = Some(e.to_string()),
+            }
+            statuses.push(status);
+        }
+
+        Ok(statuses)
+    }
+
+    /// 比较固定提交与分支中的文件 blob，内容变化时附带 diff
+    async fn compare(
+        github_api: &GitHubApiService,
+        resource: &PinnedResource,
+        branch: &str,
+        status: &mut PinnedStatus,
+    ) -> Result<(), GitHubApiError> {
+        let (owner, name, path) = (&resource.owner, &resource.name, &resource.source_path);
+        let (pinned_blob, _) = github_api
+            .get_file_blob_sha(owner, name, &resource.commit, path)
+            .await?;
+        status.pinned_blob = Some(pinned_blob.clone());
+
+        let current_blob = match github_api
+            .get_file_blob_sha(owner, name, branch, path)
+            .await
+        {
+            Ok((blob, _)) => blob,
+            Err(GitHubApiError::NotFound) => {
+                status.behind = true;
+                status.change = Some(PinnedChange::Deleted);
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+        status.current_blob = Some(current_blob.clone());
+
+        if current_blob == pinned_blob {
+            status.change = Some(PinnedChange::Unchanged);
+            return Ok(());
+        }
+        status.behind = true;
+        status.change = Some(PinnedChange::Modified);
+
+        let pinned = github_api
+            .download_raw_file(owner, name, &resource.commit, path)
+            .await?;
+        let current = github_api
+            .download_raw_file(owner, name, branch, path)
+            .await?;
+        status.diff = UpdateService::unified_diff(
+            &String::from_utf8_lossy(&pinned),
+            &String::from_utf8_lossy(&current),
+            &resource.id,
+        );
+        Ok(())
+    }
+
+    /// 收集分支为提交 SHA 且有仓库路径的已安装资源
+    fn collect_pinned(db: &Arc<Database>) -> Result<Vec<PinnedResource>> {
+        let mut pinned = Vec::new();
+        let mut push = |resource_type: ResourceType,
+                        id: &str,
+                        owner: &Option<String>,
+                        name: &Option<String>,
+                        branch: &Option<String>,
+                        source_path: &Option<String>| {
+            if let (Some(owner), Some(name), Some(commit), Some(source_path)) =
+                (owner, name, branch, source_path)
+            {
+                if is_commit_sha(commit) {
+                    pinned.push(PinnedResource {
+                        resource_type,
+                        id: id.to_string(),
+                        owner: owner.clone(),
+                        name: name.clone(),
+                        commit: commit.to_ascii_lowercase(),
+                        source_path: source_path.clone(),
+                    });
+                }
+            }
+        };
+
+        for c in db.get_all_installed_commands()?.values() {
+            push(
+                ResourceType::Command,
+                &c.id,
+                &c.repo_owner,
+                &c.repo_name,
+                &c.repo_branch,
+                &c.source_path,
+            );
+        }
+        for a in db.get_all_installed_agents()?.values() {
+            push(
+                ResourceType::Agent,
+                &a.id,
+                &a.repo_owner,
+                &a.repo_name,
+                &a.repo_branch,
+                &a.source_path,
+            );
+        }
+        for h in db.get_all_installed_hooks()?.values() {
+            push(
+                ResourceType::Hook,
+                &h.id,
+                &h.repo_owner,
+                &h.repo_name,
+                &h.repo_branch,
+                &h.source_path,
+            );
+        }
+
+        pinned.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(pinned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_full_commit_shas_count_as_pins() {
+        assert!(is_commit_sha("0123456789abcdef0123456789ABCDEF01234567"));
+        assert!(!is_commit_sha("main"));
+        assert!(!is_commit_sha("0123456"));
+        assert!(!is_commit_sha("release-2024-01-01-0123456789abcdefghijk"));
+    }
+}
@@ -0,0 +1,364 @@
+//! 已安装资源的来源信息
+//!
+//! 从数据库记录汇总资源来自哪个仓库、分支和路径，以及安装时记录的哈希，
+//! 方便用户在启用第三方资源前核查来源。只读，不访问网络。
+//!
+//! `get_install_source` 另外在其他已配置仓库的发现缓存中查找同一 key，
+//! 并从 GitHub 获取这些副本的 blob SHA，用于在仓库内容重叠时判断来源。
+
+use crate::database::Database;
+use crate::services::command::CommandService;
+use crate::services::github_api::GitHubApiService;
+use crate::services::update::ResourceType;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// 资源来源
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Provenance {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_branch: Option<String>,
+    /// 文件在仓库中的路径（Skills 为目录路径）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readme_url: Option<String>,
+    /// 获取文件时使用的 raw.githubusercontent.com 地址（Skills 指向 SKILL.md）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_url: Option<String>,
+    pub installed_at: i64,
+    /// 安装时记录的哈希（文件为 Git blob SHA，Skills 为目录 tree SHA）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_sha: Option<String>,
+    /// 本地导入或手动创建，没有仓库来源
+    pub local: bool,
+}
+
+impl Provenance {
+    fn new(
+        repo_owner: Option<String>,
+        repo_name: Option<String>,
+        repo_branch: Option<String>,
+        source_path: Option<String>,
+        readme_url: Option<String>,
+        installed_at: i64,
+        pinned_sha: Option<String>,
+    ) -> Self {
+        let raw_url = match (&repo_owner, &repo_name, &repo_branch, &source_path) {
+            (Some(owner), Some(name), Some(branch), Some(path)) => Some(format!(
+                "https://raw.githubusercontent.com/{owner}/{name}/{branch}/{}",
+                path.trim_start_matches('/')
+            )),
+            _ => None,
+        };
+
+        Self {
+            local: repo_owner.is_none() || repo_name.is_none(),
+            repo_owner,
+            repo_name,
+            repo_branch,
+            source_path,
+            readme_url,
+            raw_url,
+            installed_at,
+            pinned_sha,
+        }
+    }
+}
+
+/// GitHub 网页地址（文件为 blob 链接，目录为 tree 链接）
+fn github_url(owner: &str, name: &str, branch: &str, path: &str, is_dir: bool) -> String {
+    let kind = if is_dir { "tree" } else { "blob" };
+    format!(
+        "https://github.com/{owner}/{name}/{kind}/{branch}/{}",
+        path.trim_start_matches('/')
+    )
+}
+
+/// 其他已配置仓库中同一 key 的副本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlternativeSource {
+    pub repo_owner: String,
+    pub repo_name: String,
+    pub repo_branch: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_url: Option<String>,
+    /// 该副本当前的 Git blob SHA
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_sha: Option<String>,
+    /// 内容与已安装版本不同（可能是更新的版本）；无法比较时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub differs: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 资源的安装来源及其他仓库中的副本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallSource {
+    pub resource_type: ResourceType,
+    pub id: String,
+    #[serde(flatten)]
+    pub provenance: Provenance,
+    /// 安装时所用文件（Skills 为目录）的 GitHub 网页地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_url: Option<String>,
+    /// 其他已启用仓库中同一 key 的副本（仅 Commands/Agents/Hooks）
+    pub alternatives: Vec<AlternativeSource>,
+}
+
+pub struct ProvenanceService;
+
+impl ProvenanceService {
+    /// 获取已安装资源的来源信息
+    pub fn get_provenance(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+        id: &str,
+    ) -> Result<Provenance> {
+        let not_found = || anyhow!("{resource_type} 未安装: {id}");
+
+        let provenance = match resource_type {
+            ResourceType::Command => {
+                let c = db.get_installed_command(id)?.ok_or_else(not_found)?;
+                Provenance::new(
+                    c.repo_owner,
+                    c.repo_name,
+                    c.repo_branch,
+                    c.source_path,
+                    c.readme_url,
+                    c.installed_at,
+                    c.file_hash,
+                )
+            }
+            ResourceType::Agent => {
+                let a = db.get_installed_agent(id)?.ok_or_else(not_found)?;
+                Provenance::new(
+                    a.repo_owner,
+                    a.repo_name,
+                    a.repo_branch,
+                    a.source_path,
+                    a.readme_url,
+                    a.installed_at,
+                    a.file_hash,
+                )
+            }
+            ResourceType::Hook => {
+                let h = db.get_installed_hook(id)?.ok_or_else(not_found)?;
+                Provenance::new(
+                    h.repo_owner,
+                    h.repo_name,
+                    h.repo_branch,
+                    h.source_path,
+                    h.readme_url,
+                    h.installed_at,
+                    h.file_hash,
+                )
+            }
+            ResourceType::Skill => {
+                let skill = db.get_installed_skill(id)?.ok_or_else(not_found)?;
+                // 与更新检测一致：仓库路径取自 ID（owner/repo:path），否则为安装目录
+                let directory = skill
+                    .id
+                    .split(':')
+                    .nth(1)
+                    .map(|p| p.to_string())
+                    .unwrap_or_else(|| skill.directory.clone());
+                let mut provenance = Provenance::new(
+                    skill.repo_owner,
+                    skill.repo_name,
+                    skill.repo_branch,
+                    Some(format!("{directory}/SKILL.md")),
+                    skill.readme_url,
+                    skill.installed_at,
+                    skill.file_hash,
+                );
+                provenance.source_path = Some(directory);
+                provenance
+            }
+        };
+
+        Ok(provenance)
+    }
+
+    /// 获取资源的安装来源，并检查其他已配置仓库中是否有同一 key 的不同版本
+    ///
+    /// 候选副本只从未过期的发现缓存中查找，不会重新扫描仓库；
+    /// Skills 的 key 含仓库名，不存在跨仓库的同名副本
+    pub async fn get_install_source(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+        id: &str,
+    ) -> Result<InstallSource> {
+        let provenance = Self::get_provenance(db, resource_type, id)?;
+
+        let github_url = match (
+            &provenance.repo_owner,
+            &provenance.repo_name,
+            &provenance.repo_branch,
+            &provenance.source_path,
+        ) {
+            (Some(owner), Some(name), Some(branch), Some(path)) => Some(github_url(
+                owner,
+                name,
+                branch,
+                path,
+                resource_type == ResourceType::Skill,
+            )),
+            _ => None,
+        };
+
+        let candidates = Self::find_alternatives(db, resource_type, id, &provenance)?;
+        let alternatives = if candidates.is_empty() {
+            Vec::new()
+        } else {
+            let github_api = GitHubApiService::new(db.get_setting("github_pat")?);
+            let tasks = candidates.into_iter().map(|mut alt| {
+                let github_api = &github_api;
+                let installed_sha = provenance.pinned_sha.as_deref();
+                async move {
+                    let Some(path) = alt.source_path.clone() else {
+                        alt.error = Some("发现缓存中缺少源路径".to_string());
+                        return alt;
+                    };
+                    match github_api
+                        .get_file_blob_sha(&alt.repo_owner, &alt.repo_name, &alt.repo_branch, &path)
+                        .await
+                    {
+                        Ok((sha, _)) => {
+                            alt.differs = installed_sha.map(|installed| installed != sha);
+                            alt.blob_sha = Some(sha);
+                        }
+                        Err(e) => alt.error = Some(e.to_string()),
+                    }
+                    alt
+                }
+            });
+            futures::future::join_all(tasks).await
+        };
+
+        Ok(InstallSource {
+            resource_type,
+            id: id.to_string(),
+            provenance,
+            github_url,
+            alternatives,
+        })
+    }
+
+    /// 在其他已启用仓库的发现缓存中查找同一 key
+    fn find_alternatives(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+        id: &str,
+        provenance: &Provenance,
+    ) -> Result<Vec<AlternativeSource>> {
+        if !matches!(
+            resource_type,
+            ResourceType::Command | ResourceType::Agent | ResourceType::Hook
+        ) {
+            return Ok(Vec::new());
+        }
+
+        let same_repo = |owner: &str, name: &str| {
+            provenance
+                .repo_owner
+                .as_deref()
+                .is_some_and(|o| o.eq_ignore_ascii_case(owner))
+                && provenance
+                    .repo_name
+                    .as_deref()
+                    .is_some_and(|n| n.eq_ignore_ascii_case(name))
+        };
+
+        let mut alternatives = Vec::new();
+        for repo in CommandService::get_repos(db)? {
+            if !repo.enabled || same_repo(&repo.owner, &repo.name) {
+                continue;
+            }
+            let (owner, name, branch) = (&repo.owner, &repo.name, &repo.branch);
+            let source_path = match resource_type {
+                ResourceType::Command => db
+                    .get_cached_commands(owner, name, branch)?
+                    .and_then(|c| c.commands.into_iter().find(|c| c.key == id))
+                    .map(|c| c.source_path),
+                ResourceType::Agent => db
+                    .get_cached_agents(owner, name, branch)?
+                    .and_then(|c| c.agents.into_iter().find(|a| a.key == id))
+                    .map(|a| a.source_path),
+                ResourceType::Hook => db
+                    .get_cached_hooks(owner, name, branch)?
+                    .and_then(|c| c.hooks.into_iter().find(|h| h.key == id))
+                    .map(|h| h.source_path),
+                ResourceType::Skill => None,
+            };
+            let Some(source_path) = source_path else {
+                continue;
+            };
+
+            alternatives.push(AlternativeSource {
+                github_url: source_path
+                    .as_deref()
+                    .map(|path| github_url(owner, name, branch, path, false)),
+                repo_owner: repo.owner.clone(),
+                repo_name: repo.name.clone(),
+                repo_branch: repo.branch.clone(),
+                source_path,
+                blob_sha: None,
+                differs: None,
+                error: None,
+            });
+        }
+
+        Ok(alternatives)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provenance_builds_raw_url_only_for_repo_resources() {
+        let remote = Provenance::new(
+            Some("owner".to_string()),
+            Some("repo".to_string()),
+            Some("main".to_string()),
+            Some("plugins/bun/commands/agent.md".to_string()),
+            None,
+            1,
+            Some("abc123".to_string()),
+        );
+        assert!(!remote.local);
+        assert_eq!(
+            remote.raw_url.as_deref(),
+            Some("https://raw.githubusercontent.com/owner/repo/main/plugins/bun/commands/agent.md")
+        );
+
+        let local = Provenance::new(None, None, None, None, None, 1, None);
+        assert!(local.local);
+        assert!(local.raw_url.is_none());
+    }
+
+    #[test]
+    fn github_url_uses_tree_for_directories() {
+        assert_eq!(
+            github_url("owner", "repo", "main", "/commands/sc/agent.md", false),
+            "https://github.com/owner/repo/blob/main/commands/sc/agent.md"
+        );
+        assert_eq!(
+            github_url("owner", "repo", "dev", "skills/pdf", true),
+            "https://github.com/owner/repo/tree/dev/skills/pdf"
+        );
+    }
+}
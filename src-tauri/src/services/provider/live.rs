@@ -476,6 +476,415 @@ fn apply_common_config_to_settings(
     }
 }
 
+/// 把供应商附加请求头（`meta.extraHeaders`）写入配置的请求头字段
+///
+/// - Claude：`env.ANTHROPIC_CUSTOM_HEADERS`（每行一个 `Name: Value`）
+/// - Codex：当前 `model_provider` 对应 `[model_providers.<name>]` 的 `http_headers`
+/// - OpenCode：`options.headers`；OpenClaw：`headers`
+///
+/// Gemini / Hermes 的配置没有请求头字段，附加请求头仅在测试连接时使用。
+/// 同名请求头（不区分大小写）以附加请求头为准。
+fn apply_extra_headers_to_settings(
+    app_type: &AppType,
+    settings: &Value,
+    headers: &HashMap<String, String>,
+) -> Result<Value, AppError> {
+    if headers.is_empty() {
+        return Ok(settings.clone());
+    }
+
+    // 按名称排序，保证写出的配置稳定
+    let mut sorted: Vec<(&String, &String)> = headers.iter().collect();
+    sorted.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut result = settings.clone();
+    match app_type {
+        AppType::Claude => {
+            let existing = result
+                .pointer("/env/ANTHROPIC_CUSTOM_HEADERS")
+                .and_then(Value::as_str)
+                .unwrap_or("");
+            let mut lines: Vec<String> = existing
+                .lines()
+                .filter(|line| {
+                    let name = line.split(':').next().unwrap_or("").trim();
+                    !name.is_empty() && !headers.keys().any(|k| k.eq_ignore_ascii_case(name))
+                })
+                .map(str::to_string)
+                .collect();
+            lines.extend(sorted.iter().map(|(k, v)| format!("{k}: {v}")));
+
+            let env = result
+                .as_object_mut()
+                .map(|obj| obj.entry("env").or_insert_with(|| json!({})))
+                .and_then(Value::as_object_mut);
+            if let Some(env) = env {
+                env.insert(
+                    "ANTHROPIC_CUSTOM_HEADERS".to_string(),
+                    Value::String(lines.join("\n")),
+                );
+            }
+        }
+        AppType::Codex => {
+            let config_toml = settings.get("config").and_then(Value::as_str).unwrap_or("");
+            let mut doc = config_toml.parse::<DocumentMut>().map_err(|e| {
+                AppError::Message(format!(
+                    "Invalid Codex config.toml while applying extra headers: {e}"
+                ))
+            })?;
+            let Some(name) = doc
+                .get("model_provider")
+                .and_then(Item::as_str)
+                .map(str::to_string)
+            else {
+                log::debug!("Codex 配置未指定 model_provider，跳过附加请求头");
+                return Ok(result);
+            };
+            let Some(table) = doc
+                .get_mut("model_providers")
+                .and_then(|providers| providers.get_mut(&name))
+                .and_then(Item::as_table_like_mut)
+            else {
+                log::debug!("Codex 配置缺少 [model_providers.{name}]，跳过附加请求头");
+                return Ok(result);
+            };
+
+            let mut http_headers = toml_edit::InlineTable::new();
+            if let Some(existing) = table.get("http_headers").and_then(Item::as_table_like) {
+                for (k, v) in existing.iter() {
+                    if let Some(v) = v.as_str() {
+                        if !headers.keys().any(|h| h.eq_ignore_ascii_case(k)) {
+                            http_headers.insert(k, v.into());
+                        }
+                    }
+                }
+            }
+            for (k, v) in &sorted {
+                http_headers.insert(k.as_str(), v.as_str().into());
+            }
+            table.insert("http_headers", toml_edit::value(http_headers));
+
+            if let Some(obj) = result.as_object_mut() {
+                obj.insert("config".to_string(), Value::String(doc.to_string()));
+            }
+        }
+        AppType::OpenCode | AppType::OpenClaw => {
+            let target = if matches!(app_type, AppType::OpenCode) {
+                result
+                    .as_object_mut()
+                    .map(|obj| obj.entry("options").or_insert_with(|| json!({})))
+                    .and_then(Value::as_object_mut)
+            } else {
+                result.as_object_mut()
+            };
+            if let Some(target) = target {
+                let entry = target.entry("headers").or_insert_with(|| json!({}));
+                if let Some(map) = entry.as_object_mut() {
+                    map.retain(|k, _| !headers.keys().any(|h| h.eq_ignore_ascii_case(k)));
+                    for (k, v) in &sorted {
+                        map.insert(k.to_string(), Value::String(v.to_string()));
+                    }
+                }
+            }
+        }
+        AppType::Gemini | AppType::Hermes => {}
+    }
+
+    Ok(result)
+}
+
+/// 从 live 配置中移除供应商附加请求头，回填时使用
+///
+/// 附加请求头只保存在 `meta.extraHeaders` 中，若随回填写回 `settings_config`，
+/// 之后在元数据里删除的请求头仍会在下次切换时写入 live 配置。
+/// 同名请求头（不区分大小写）一并移除；移除后为空的请求头字段整体删除。
+pub(crate) fn strip_extra_headers_from_settings(
+    app_type: &AppType,
+    settings: Value,
+    headers: &HashMap<String, String>,
+) -> Value {
+    if headers.is_empty() {
+        return settings;
+    }
+    let is_extra = |name: &str| headers.keys().any(|k| k.eq_ignore_ascii_case(name.trim()));
+
+    let mut result = settings;
+    match app_type {
+        AppType::Claude => {
+            let Some(env) = result.get_mut("env").and_then(Value::as_object_mut) else {
+                return result;
+            };
+            let Some(existing) = env.get("ANTHROPIC_CUSTOM_HEADERS").and_then(Value::as_str) else {
+                return result;
+            };
+            let kept = existing
+                .lines()
+                .filter(|line| !is_extra(line.split(':').next().unwrap_or("")))
+                .collect::<Vec<_>>()
+                .join("\n");
+            if kept.is_empty() {
+                env.remove("ANTHROPIC_CUSTOM_HEADERS");
+            } else {
+                env.insert("ANTHROPIC_CUSTOM_HEADERS".to_string(), Value::String(kept));
+            }
+        }
+        AppType::Codex => {
+            let config_toml = result.get("config").and_then(Value::as_str).unwrap_or("");
+            let mut doc = match config_toml.parse::<DocumentMut>() {
+                Ok(doc) => doc,
+                Err(err) => {
+                    log::warn!("Failed to strip extra headers from Codex config.toml: {err}");
+                    return result;
+                }
+            };
+            let Some(name) = doc
+                .get("model_provider")
+                .and_then(Item::as_str)
+                .map(str::to_string)
+            else {
+                return result;
+            };
+            let Some(table) = doc
+                .get_mut("model_providers")
+                .and_then(|providers| providers.get_mut(&name))
+                .and_then(Item::as_table_like_mut)
+            else {
+                return result;
+            };
+            let Some(http_headers) = table
+                .get_mut("http_headers")
+                .and_then(|item| item.as_table_like_mut())
+            else {
+                return result;
+            };
+            let extra: Vec<String> = http_headers
+                .iter()
+                .map(|(k, _)| k.to_string())
+                .filter(|k| is_extra(k))
+                .collect();
+            for key in &extra {
+                http_headers.remove(key);
+            }
+            if http_headers.is_empty() {
+                table.remove("http_headers");
+            }
+
+            if let Some(obj) = result.as_object_mut() {
+                obj.insert("config".to_string(), Value::String(doc.to_string()));
+            }
+        }
+        AppType::OpenCode | AppType::OpenClaw => {
+            let parent = if matches!(app_type, AppType::OpenCode) {
+                result.get_mut("options").and_then(Value::as_object_mut)
+            } else {
+                result.as_object_mut()
+            };
+            if let Some(parent) = parent {
+                if let Some(map) = parent.get_mut("headers").and_then(Value::as_object_mut) {
+                    map.retain(|k, _| !is_extra(k));
+                    if map.is_empty() {
+                        parent.remove("headers");
+                    }
+                }
+            }
+        }
+        AppType::Gemini | AppType::Hermes => {}
+    }
+
+    result
+}
+
+/// 把部署名拼接到基础 URL 上（Azure OpenAI 等按部署划分端点的服务）
+///
+/// Azure 端点（`*.openai.azure.com` / `*.cognitiveservices.azure.com`）缺少
+/// `/openai/deployments` 时一并补上，其他网关直接追加 `/{deployment}`；
+/// 基础 URL 已以该部署结尾时保持不变，因此可重复调用
+pub(crate) fn compose_deployment_url(base_url: &str, deployment: &str) -> String {
+    let base = base_url.trim().trim_end_matches('/');
+    let deployment = deployment.trim().trim_matches('/');
+    if deployment.is_empty() || base.ends_with(&format!("/{deployment}")) {
+        return base.to_string();
+    }
+
+    if is_azure_url(base) && !base.contains("/openai/deployments") {
+        format!("{base}/openai/deployments/{deployment}")
+    } else {
+        format!("{base}/{deployment}")
+    }
+}
+
+/// 从拼接后的 URL 中去掉部署名，`compose_deployment_url` 的逆操作
+///
+/// Azure 端点同时去掉 `/openai/deployments`，再次拼接时会补回；
+/// URL 不以该部署结尾时原样返回
+pub(crate) fn strip_deployment_url(url: &str, deployment: &str) -> String {
+    let trimmed = url.trim().trim_end_matches('/');
+    let deployment = deployment.trim().trim_matches('/');
+    if deployment.is_empty() {
+        return url.to_string();
+    }
+    let Some(base) = trimmed.strip_suffix(&format!("/{deployment}")) else {
+        return url.to_string();
+    };
+    match base.strip_suffix("/openai/deployments") {
+        Some(host) if is_azure_url(host) => host.to_string(),
+        _ => base.to_string(),
+    }
+}
+
+/// 是否为 Azure OpenAI 端点（`*.openai.azure.com` / `*.cognitiveservices.azure.com`）
+fn is_azure_url(url: &str) -> bool {
+    let host = url
+        .split("://")
+        .nth(1)
+        .unwrap_or(url)
+        .split('/')
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    host.ends_with(".openai.azure.com") || host.ends_with(".cognitiveservices.azure.com")
+}
+
+/// 各应用配置中基础 URL 所在的 JSON 路径（Codex 存放在 TOML 中，单独处理）
+fn base_url_pointer(app_type: &AppType) -> Option<&'static str> {
+    match app_type {
+        AppType::Claude => Some("/env/ANTHROPIC_BASE_URL"),
+        AppType::Gemini => Some("/env/GOOGLE_GEMINI_BASE_URL"),
+        AppType::OpenCode => Some("/options/baseURL"),
+        AppType::OpenClaw => Some("/baseUrl"),
+        AppType::Hermes => Some("/base_url"),
+        AppType::Codex => None,
+    }
+}
+
+/// Codex config.toml 中基础 URL 所在的表：优先当前 `model_provider`，否则为顶层
+fn codex_base_url_table(doc: &mut DocumentMut) -> &mut dyn TableLike {
+    let provider = doc
+        .get("model_provider")
+        .and_then(Item::as_str)
+        .map(str::to_string);
+    let has_provider_url = provider.as_deref().is_some_and(|name| {
+        doc.get("model_providers")
+            .and_then(|providers| providers.get(name))
+            .and_then(|table| table.get("base_url"))
+            .is_some()
+    });
+
+    match provider {
+        Some(name) if has_provider_url => doc["model_providers"][&name]
+            .as_table_like_mut()
+            .expect("checked above"),
+        _ => doc.as_table_mut(),
+    }
+}
+
+/// 读取配置中的基础 URL
+pub(crate) fn settings_base_url(app_type: &AppType, settings: &Value) -> Option<String> {
+    let url = match base_url_pointer(app_type) {
+        Some(pointer) => settings.pointer(pointer)?.as_str()?.to_string(),
+        None => {
+            let config_toml = settings.get("config")?.as_str()?;
+            let mut doc = config_toml.parse::<DocumentMut>().ok()?;
+            codex_base_url_table(&mut doc)
+                .get("base_url")?
+                .as_str()?
+                .to_string()
+        }
+    };
+    (!url.trim().is_empty()).then_some(url)
+}
+
+/// 把供应商部署名（`meta.deployment`）拼接到配置中的基础 URL
+///
+/// 数据库中保存的仍是拆分的基础 URL + 部署名，只有写入 live 配置、代理转发和测试连接时才拼接
+pub(crate) fn apply_deployment_to_settings(
+    app_type: &AppType,
+    settings: &Value,
+    deployment: &str,
+) -> Result<Value, AppError> {
+    if deployment.trim().is_empty() {
+        return Ok(settings.clone());
+    }
+    map_settings_base_url(app_type, settings, |base| {
+        compose_deployment_url(base, deployment)
+    })
+}
+
+/// 从 live 配置的基础 URL 中去掉部署名，回填时使用
+///
+/// 否则拼接后的 URL 会写回 `settings_config`，修改部署名后新旧部署名叠加在一起
+pub(crate) fn strip_deployment_from_settings(
+    app_type: &AppType,
+    settings: Value,
+    deployment: &str,
+) -> Value {
+    match map_settings_base_url(app_type, &settings, |url| {
+        strip_deployment_url(url, deployment)
+    }) {
+        Ok(stripped) => stripped,
+        Err(err) => {
+            log::warn!(
+                "Failed to strip deployment from {} live config: {err}",
+                app_type.as_str()
+            );
+            settings
+        }
+    }
+}
+
+/// 改写配置中的基础 URL，未配置基础 URL 时原样返回
+fn map_settings_base_url(
+    app_type: &AppType,
+    settings: &Value,
+    map: impl Fn(&str) -> String,
+) -> Result<Value, AppError> {
+    let mut result = settings.clone();
+    match base_url_pointer(app_type) {
+        Some(pointer) => {
+            if let Some(url) = result.pointer_mut(pointer) {
+                if let Some(base) = url.as_str() {
+                    *url = Value::String(map(base));
+                }
+            }
+        }
+        None => {
+            let config_toml = settings.get("config").and_then(Value::as_str).unwrap_or("");
+            let mut doc = config_toml.parse::<DocumentMut>().map_err(|e| {
+                AppError::Message(format!(
+                    "Invalid Codex config.toml while applying deployment: {e}"
+                ))
+            })?;
+            let table = codex_base_url_table(&mut doc);
+            let Some(base) = table
+                .get("base_url")
+                .and_then(Item::as_str)
+                .map(str::to_string)
+            else {
+                return Ok(result);
+            };
+            table.insert("base_url", toml_edit::value(map(&base)));
+            if let Some(obj) = result.as_object_mut() {
+                obj.insert("config".to_string(), Value::String(doc.to_string()));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// 返回拼接了部署名的供应商副本（未设置部署名时原样返回）
+pub(crate) fn provider_with_deployment(
+    app_type: &AppType,
+    provider: &Provider,
+) -> Result<Provider, AppError> {
+    let mut provider = provider.clone();
+    if let Some(deployment) = provider.meta.as_ref().and_then(|m| m.deployment.clone()) {
+        provider.settings_config =
+            apply_deployment_to_settings(app_type, &provider.settings_config, &deployment)?;
+    }
+    Ok(provider)
+}
+
 pub(crate) fn build_effective_settings_with_common_config(
     db: &Database,
     app_type: &AppType,
@@ -511,6 +920,21 @@ pub(crate) fn write_live_with_common_config(
     effective_provider.settings_config =
         build_effective_settings_with_common_config(db, app_type, provider)?;
 
+    if let Some(meta) = provider.meta.as_ref() {
+        effective_provider.settings_config = apply_extra_headers_to_settings(
+            app_type,
+            &effective_provider.settings_config,
+            &meta.extra_headers,
+        )?;
+        if let Some(deployment) = meta.deployment.as_deref() {
+            effective_provider.settings_config = apply_deployment_to_settings(
+                app_type,
+                &effective_provider.settings_config,
+                deployment,
+            )?;
+        }
+    }
+
     write_live_snapshot(app_type, &effective_provider)
 }
 
@@ -1427,6 +1851,156 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn extra_headers_are_written_to_claude_env_and_codex_provider_table() {
+        let headers = HashMap::from([
+            ("X-Title".to_string(), "CC Switch".to_string()),
+            ("HTTP-Referer".to_string(), "https://cc.dev".to_string()),
+        ]);
+
+        let claude = json!({
+            "env": { "ANTHROPIC_CUSTOM_HEADERS": "x-title: Old\nX-Trace: abc" }
+        });
+        let applied = apply_extra_headers_to_settings(&AppType::Claude, &claude, &headers).unwrap();
+        assert_eq!(
+            applied["env"]["ANTHROPIC_CUSTOM_HEADERS"],
+            json!("X-Trace: abc\nHTTP-Referer: https://cc.dev\nX-Title: CC Switch")
+        );
+
+        let codex = json!({
+            "auth": {},
+            "config": "model_provider = \"openrouter\"\n\n[model_providers.openrouter]\nbase_url = \"https://openrouter.ai/api/v1\"\n"
+        });
+        let applied = apply_extra_headers_to_settings(&AppType::Codex, &codex, &headers).unwrap();
+        let doc = applied["config"]
+            .as_str()
+            .unwrap()
+            .parse::<DocumentMut>()
+            .unwrap();
+        let http_headers = &doc["model_providers"]["openrouter"]["http_headers"];
+        assert_eq!(http_headers["X-Title"].as_str(), Some("CC Switch"));
+        assert_eq!(
+            http_headers["HTTP-Referer"].as_str(),
+            Some("https://cc.dev")
+        );
+
+        let gemini = json!({ "env": {} });
+        let applied = apply_extra_headers_to_settings(&AppType::Gemini, &gemini, &headers).unwrap();
+        assert_eq!(applied, gemini);
+    }
+
+    #[test]
+    fn extra_headers_are_stripped_from_backfilled_settings() {
+        let headers = HashMap::from([("X-Title".to_string(), "CC Switch".to_string())]);
+
+        let claude = json!({
+            "env": { "ANTHROPIC_CUSTOM_HEADERS": "X-Trace: abc\nx-title: CC Switch" }
+        });
+        let stripped = strip_extra_headers_from_settings(&AppType::Claude, claude, &headers);
+        assert_eq!(
+            stripped["env"]["ANTHROPIC_CUSTOM_HEADERS"],
+            json!("X-Trace: abc")
+        );
+
+        let codex = json!({
+            "config": "model_provider = \"openrouter\"\n\n[model_providers.openrouter]\nbase_url = \"https://openrouter.ai/api/v1\"\n"
+        });
+        let applied = apply_extra_headers_to_settings(&AppType::Codex, &codex, &headers).unwrap();
+        let stripped = strip_extra_headers_from_settings(&AppType::Codex, applied, &headers);
+        let doc = stripped["config"]
+            .as_str()
+            .unwrap()
+            .parse::<DocumentMut>()
+            .unwrap();
+        assert!(doc["model_providers"]["openrouter"]
+            .get("http_headers")
+            .is_none());
+
+        let opencode = json!({ "options": { "headers": { "X-Title": "CC Switch" } } });
+        let stripped = strip_extra_headers_from_settings(&AppType::OpenCode, opencode, &headers);
+        assert_eq!(stripped, json!({ "options": {} }));
+    }
+
+    #[test]
+    fn compose_deployment_url_handles_azure_and_gateways() {
+        assert_eq!(
+            compose_deployment_url("https://res.openai.azure.com/", "gpt-4o"),
+            "https://res.openai.azure.com/openai/deployments/gpt-4o"
+        );
+        assert_eq!(
+            compose_deployment_url("https://res.openai.azure.com/openai/deployments", "gpt-4o"),
+            "https://res.openai.azure.com/openai/deployments/gpt-4o"
+        );
+        assert_eq!(
+            compose_deployment_url("https://gw.example.com/v1", "team-a"),
+            "https://gw.example.com/v1/team-a"
+        );
+        // 已拼接过的 URL 保持不变
+        assert_eq!(
+            compose_deployment_url("https://gw.example.com/v1/team-a", "team-a"),
+            "https://gw.example.com/v1/team-a"
+        );
+    }
+
+    #[test]
+    fn strip_deployment_url_reverses_compose() {
+        for (base, deployment) in [
+            ("https://res.openai.azure.com", "gpt-4o"),
+            ("https://gw.example.com/v1", "team-a"),
+        ] {
+            let composed = compose_deployment_url(base, deployment);
+            assert_eq!(strip_deployment_url(&composed, deployment), base);
+        }
+        // 用户自己写了 /openai/deployments 时，去掉后再拼接仍得到同一端点
+        let composed =
+            compose_deployment_url("https://res.openai.azure.com/openai/deployments", "gpt-4o");
+        let stripped = strip_deployment_url(&composed, "gpt-4o");
+        assert_eq!(compose_deployment_url(&stripped, "gpt-4o"), composed);
+        // 不以该部署结尾的 URL 保持不变
+        assert_eq!(
+            strip_deployment_url("https://gw.example.com/v1", "team-a"),
+            "https://gw.example.com/v1"
+        );
+    }
+
+    #[test]
+    fn deployment_is_stripped_from_backfilled_codex_config() {
+        let codex = json!({
+            "config": "model_provider = \"azure\"\n\n[model_providers.azure]\nbase_url = \"https://res.openai.azure.com\"\n"
+        });
+        let applied = apply_deployment_to_settings(&AppType::Codex, &codex, "gpt-4o").unwrap();
+        let stripped = strip_deployment_from_settings(&AppType::Codex, applied, "gpt-4o");
+        assert_eq!(
+            settings_base_url(&AppType::Codex, &stripped).as_deref(),
+            Some("https://res.openai.azure.com")
+        );
+    }
+
+    #[test]
+    fn deployment_is_applied_to_base_url_in_live_settings() {
+        let claude = json!({ "env": { "ANTHROPIC_BASE_URL": "https://gw.example.com" } });
+        let applied = apply_deployment_to_settings(&AppType::Claude, &claude, "prod").unwrap();
+        assert_eq!(
+            applied["env"]["ANTHROPIC_BASE_URL"],
+            json!("https://gw.example.com/prod")
+        );
+
+        let codex = json!({
+            "config": "model_provider = \"azure\"\n\n[model_providers.azure]\nbase_url = \"https://res.openai.azure.com\"\n"
+        });
+        let applied = apply_deployment_to_settings(&AppType::Codex, &codex, "gpt-4o").unwrap();
+        assert_eq!(
+            settings_base_url(&AppType::Codex, &applied).as_deref(),
+            Some("https://res.openai.azure.com/openai/deployments/gpt-4o")
+        );
+
+        // 没有基础 URL 时不做修改
+        let empty = json!({ "env": {} });
+        let applied = apply_deployment_to_settings(&AppType::Claude, &empty, "prod").unwrap();
+        assert_eq!(applied, empty);
+        assert_eq!(settings_base_url(&AppType::Claude, &empty), None);
+    }
+
     #[test]
     fn claude_common_config_apply_and_remove_roundtrip_for_non_overlapping_fields() {
         let settings = json!({
@@ -5,6 +5,8 @@
 mod endpoints;
 mod gemini_auth;
 mod live;
+mod rotation;
+mod templates;
 mod usage;
 
 use indexmap::IndexMap;
@@ -25,11 +27,16 @@ pub use live::{
     import_opencode_providers_from_live, read_live_settings, sync_current_to_live,
 };
 
+pub use rotation::{ProviderRotation, ProviderRotationPolicy, ProviderRotationState};
+pub use templates::{ProviderTemplate, TemplateField};
+
 // Internal re-exports (pub(crate))
 pub(crate) use live::sanitize_claude_settings_for_live;
 pub(crate) use live::{
-    build_effective_settings_with_common_config, normalize_provider_common_config_for_storage,
-    provider_exists_in_live_config, strip_common_config_from_live_settings,
+    build_effective_settings_with_common_config, compose_deployment_url,
+    normalize_provider_common_config_for_storage, provider_exists_in_live_config,
+    provider_with_deployment, settings_base_url, strip_common_config_from_live_settings,
+    strip_deployment_from_settings, strip_extra_headers_from_settings,
     sync_current_provider_for_app_to_live, write_live_with_common_config,
 };
 
@@ -1490,13 +1497,25 @@ impl ProviderService {
                     // Only backfill when switching to a different provider
                     if let Ok(live_config) = read_live_settings(app_type.clone()) {
                         if let Some(mut current_provider) = providers.get(&current_id).cloned() {
-                            current_provider.settings_config =
-                                strip_common_config_from_live_settings(
-                                    state.db.as_ref(),
+                            let mut settings = strip_common_config_from_live_settings(
+                                state.db.as_ref(),
+                                &app_type,
+                                &current_provider,
+                                live_config,
+                            );
+                            if let Some(meta) = current_provider.meta.as_ref() {
+                                settings = strip_extra_headers_from_settings(
                                     &app_type,
-                                    &current_provider,
-                                    live_config,
+                                    settings,
+                                    &meta.extra_headers,
                                 );
+                                if let Some(deployment) = meta.deployment.as_deref() {
+                                    settings = strip_deployment_from_settings(
+                                        &app_type, settings, deployment,
+                                    );
+                                }
+                            }
+                            current_provider.settings_config = settings;
                             if let Err(e) =
                                 state.db.save_provider(app_type.as_str(), &current_provider)
                             {
@@ -1938,6 +1957,27 @@ impl ProviderService {
         read_live_settings(app_type)
     }
 
+    /// Get builtin provider templates (loaded from resources/provider-templates.json)
+    pub fn templates() -> Result<Vec<ProviderTemplate>, AppError> {
+        templates::load_templates()
+    }
+
+    /// Create a provider from a builtin template, filling in base URL and default model
+    pub fn create_from_template(
+        state: &AppState,
+        template_id: &str,
+        api_key: &str,
+    ) -> Result<Provider, AppError> {
+        let template = Self::templates()?
+            .into_iter()
+            .find(|t| t.id == template_id)
+            .ok_or_else(|| AppError::InvalidInput(format!("未知的供应商模板: {template_id}")))?;
+
+        let (app_type, provider) = template.build_provider(api_key)?;
+        Self::add(state, app_type, provider.clone(), true)?;
+        Ok(provider)
+    }
+
     /// Get custom endpoints list (re-export)
     pub fn get_custom_endpoints(
         state: &AppState,
@@ -1977,6 +2017,42 @@ impl ProviderService {
         endpoints::update_endpoint_last_used(state, app_type, provider_id, url)
     }
 
+    /// 开启/关闭供应商密钥加密，并迁移数据库中已有的密钥
+    ///
+    /// 开启时先写设置再加密（迁移失败则回退设置）；关闭时先解密再写设置，
+    /// 保证任一时刻数据库内容都能被当前设置正确读取。返回被改写的供应商数量。
+    pub fn set_key_encryption(state: &AppState, enabled: bool) -> Result<usize, AppError> {
+        if enabled {
+            crate::settings::set_encrypt_provider_keys(true)?;
+            match state.db.reencrypt_provider_secrets(true) {
+                Ok(count) => {
+                    log::info!("已加密 {count} 个供应商的密钥");
+                    Ok(count)
+                }
+                Err(e) => {
+                    crate::settings::set_encrypt_provider_keys(false)?;
+                    Err(e)
+                }
+            }
+        } else {
+            let count = state.db.reencrypt_provider_secrets(false)?;
+            crate::settings::set_encrypt_provider_keys(false)?;
+            log::info!("已解密 {count} 个供应商的密钥");
+            Ok(count)
+        }
+    }
+
+    /// 当前供应商接近使用限额时按轮换策略切换到下一个供应商
+    ///
+    /// 返回本次轮换的结果（含切换警告）；未启用轮换、未达到阈值、处于冷却期或没有可用候选时返回 `None`。
+    /// 可由定时器或记录用量后调用。
+    pub fn rotate_if_needed(
+        state: &AppState,
+        app_type: AppType,
+    ) -> Result<Option<ProviderRotation>, AppError> {
+        rotation::rotate_if_needed(state, app_type)
+    }
+
     /// Update provider sort order
     pub fn update_sort_order(
         state: &AppState,
@@ -2131,6 +2207,70 @@ impl ProviderService {
             if let Some(usage_script) = &meta.usage_script {
                 validate_usage_script(usage_script)?;
             }
+            Self::validate_extra_headers(&meta.extra_headers)?;
+            if let Some(deployment) = meta.deployment.as_deref() {
+                Self::validate_deployment(app_type, provider, deployment)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 校验部署名：不能为空或包含 URL 分隔符，且供应商必须配置了基础 URL
+    fn validate_deployment(
+        app_type: &AppType,
+        provider: &Provider,
+        deployment: &str,
+    ) -> Result<(), AppError> {
+        let deployment = deployment.trim();
+        if deployment.is_empty()
+            || deployment
+                .chars()
+                .any(|c| c.is_whitespace() || matches!(c, '/' | '?' | '#'))
+        {
+            return Err(AppError::localized(
+                "provider.deployment.invalid",
+                format!("无效的部署名: {deployment}"),
+                format!("Invalid deployment name: {deployment}"),
+            ));
+        }
+        if settings_base_url(app_type, &provider.settings_config).is_none() {
+            return Err(AppError::localized(
+                "provider.deployment.missing_base_url",
+                "设置了部署名的供应商必须同时填写基础 URL",
+                "A provider with a deployment name must also set a base URL",
+            ));
+        }
+        Ok(())
+    }
+
+    /// 校验附加请求头：名称须为合法的 HTTP token，值不能含控制字符；
+    /// 由 HTTP 客户端管理的 Host / Content-Length 等头不允许覆盖
+    fn validate_extra_headers(headers: &HashMap<String, String>) -> Result<(), AppError> {
+        const RESERVED: [&str; 4] = ["host", "content-length", "transfer-encoding", "connection"];
+
+        for (name, value) in headers {
+            if reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_err() {
+                return Err(AppError::localized(
+                    "provider.extra_headers.invalid_name",
+                    format!("无效的请求头名称: {name}"),
+                    format!("Invalid header name: {name}"),
+                ));
+            }
+            if RESERVED.iter().any(|r| r.eq_ignore_ascii_case(name)) {
+                return Err(AppError::localized(
+                    "provider.extra_headers.reserved",
+                    format!("请求头 {name} 由客户端自动设置，不能自定义"),
+                    format!("Header {name} is managed by the HTTP client and cannot be set"),
+                ));
+            }
+            if reqwest::header::HeaderValue::from_str(value).is_err() {
+                return Err(AppError::localized(
+                    "provider.extra_headers.invalid_value",
+                    format!("请求头 {name} 的值包含非法字符"),
+                    format!("Value of header {name} contains invalid characters"),
+                ));
+            }
         }
 
         Ok(())
@@ -0,0 +1,387 @@
+//! Provider rotation
+//!
+//! 与严格的故障转移不同，轮换模式在当前供应商接近使用限额时主动切换到下一个供应商，
+//! 并直接写入应用配置。轮换策略与轮换状态按应用保存在 settings 表中。
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::services::usage_stats::ProviderLimitStatus;
+use crate::store::AppState;
+
+use super::ProviderService;
+
+/// 用户设置的轮换策略（按应用保存）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRotationPolicy {
+    /// 是否启用轮换
+    #[serde(default)]
+    pub enabled: bool,
+    /// 使用量达到限额的百分比时触发轮换（1-100）
+    #[serde(default = "default_threshold_percent")]
+    pub threshold_percent: u8,
+    /// 两次轮换之间的最短间隔（秒），避免来回切换
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+    /// 轮换顺序；为空时使用故障转移队列的顺序
+    #[serde(default)]
+    pub provider_ids: Vec<String>,
+}
+
+fn default_threshold_percent() -> u8 {
+    90
+}
+
+fn default_cooldown_secs() -> u64 {
+    300
+}
+
+impl Default for ProviderRotationPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_percent: default_threshold_percent(),
+            cooldown_secs: default_cooldown_secs(),
+            provider_ids: Vec::new(),
+        }
+    }
+}
+
+/// 持久化的轮换状态（按应用保存）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRotationState {
+    /// 最近一次轮换的时间（秒级时间戳）
+    pub last_rotated_at: Option<i64>,
+    /// 最近一次轮换前的供应商
+    pub last_from: Option<String>,
+    /// 最近一次轮换后的供应商
+    pub last_to: Option<String>,
+    /// 累计轮换次数
+    #[serde(default)]
+    pub rotation_count: u64,
+}
+
+/// 一次成功轮换的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderRotation {
+    /// 轮换前的供应商
+    pub from: String,
+    /// 轮换后的供应商
+    pub to: String,
+    /// 切换过程中产生的非致命警告（来自 `SwitchResult`）
+    pub warnings: Vec<String>,
+}
+
+/// 判断限额状态是否已达到阈值（日限额或月限额任一满足即可）
+///
+/// 未设置限额的供应商永远不会被视为接近限额。
+pub(crate) fn is_near_limit(status: &ProviderLimitStatus, threshold_percent: u8) -> bool {
+    let ratio = f64::from(threshold_percent.clamp(1, 100)) / 100.0;
+    let near = |usage: &str, limit: Option<&String>| {
+        let (Ok(usage), Some(Ok(limit))) = (usage.parse::<f64>(), limit.map(|l| l.parse::<f64>()))
+        else {
+            return false;
+        };
+        limit > 0.0 && usage >= limit * ratio
+    };
+
+    status.daily_exceeded
+        || status.monthly_exceeded
+        || near(&status.daily_usage, status.daily_limit.as_ref())
+        || near(&status.monthly_usage, status.monthly_limit.as_ref())
+}
+
+/// 从当前供应商之后开始按顺序寻找第一个可用的候选（round-robin）
+///
+/// 当前供应商不在候选列表中时从头开始；当前供应商本身不会被选中。
+pub(crate) fn pick_next<'a>(
+    current: &str,
+    candidates: &'a [String],
+    mut is_available: impl FnMut(&str) -> bool,
+) -> Option<&'a String> {
+    let start = candidates
+        .iter()
+        .position(|id| id == current)
+        .map(|i| i + 1)
+        .unwrap_or(0);
+
+    (0..candidates.len())
+        .map(|offset| &candidates[(start + offset) % candidates.len()])
+        .filter(|id| id.as_str() != current)
+        .find(|id| is_available(id))
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// 当前供应商接近限额时切换到下一个供应商，返回本次轮换的结果
+pub fn rotate_if_needed(
+    state: &AppState,
+    app_type: AppType,
+) -> Result<Option<ProviderRotation>, AppError> {
+    // 累加模式应用没有"当前供应商"，无需轮换
+    if app_type.is_additive_mode() {
+        return Ok(None);
+    }
+
+    let app = app_type.as_str().to_string();
+    let policy = state.db.get_provider_rotation_policy(&app)?;
+    if !policy.enabled {
+        return Ok(None);
+    }
+
+    let current = ProviderService::current(state, app_type.clone())?;
+    if current.is_empty() {
+        return Ok(None);
+    }
+
+    let current_status = state.db.check_provider_limits(&current, &app)?;
+    if !is_near_limit(&current_status, policy.threshold_percent) {
+        return Ok(None);
+    }
+
+    let mut rotation_state = state.db.get_provider_rotation_state(&app)?;
+    let now = now_secs();
+    if let Some(last) = rotation_state.last_rotated_at {
+        if now - last < policy.cooldown_secs as i64 {
+            log::debug!("[Rotation] {app} 仍在冷却期内，跳过轮换");
+            return Ok(None);
+        }
+    }
+
+    let providers = state.db.get_all_providers(&app)?;
+    let candidates: Vec<String> = if policy.provider_ids.is_empty() {
+        state
+            .db
+            .get_failover_queue(&app)?
+            .into_iter()
+            .map(|item| item.provider_id)
+            .collect()
+    } else {
+        policy.provider_ids.clone()
+    };
+
+    let next = pick_next(&current, &candidates, |id| {
+        providers.contains_key(id)
+            && state
+                .db
+                .check_provider_limits(id, &app)
+                .map(|status| !is_near_limit(&status, policy.threshold_percent))
+                .unwrap_or(false)
+    })
+    .cloned();
+
+    let Some(next) = next else {
+        log::warn!("[Rotation] {app} 当前供应商 {current} 接近限额，但没有可轮换的供应商");
+        return Ok(None);
+    };
+
+    let switch_result = ProviderService::switch(state, app_type, &next)?;
+    log::info!("[Rotation] {app}: {current} → {next}");
+    for warning in &switch_result.warnings {
+        log::warn!("[Rotation] {app} 切换到 {next} 时出现警告: {warning}");
+    }
+
+    rotation_state.last_rotated_at = Some(now);
+    rotation_state.last_from = Some(current.clone());
+    rotation_state.last_to = Some(next.clone());
+    rotation_state.rotation_count += 1;
+    state
+        .db
+        .set_provider_rotation_state(&app, &rotation_state)?;
+
+    Ok(Some(ProviderRotation {
+        from: current,
+        to: next,
+        warnings: switch_result.warnings,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::provider::{Provider, ProviderMeta};
+    use crate::test_support::TempHome;
+    use rusqlite::params;
+    use serde_json::json;
+    use serial_test::serial;
+    use std::sync::Arc;
+
+    fn status(daily: &str, daily_limit: Option<&str>) -> ProviderLimitStatus {
+        ProviderLimitStatus {
+            provider_id: "p".to_string(),
+            daily_usage: daily.to_string(),
+            daily_limit: daily_limit.map(str::to_string),
+            daily_exceeded: false,
+            monthly_usage: "0".to_string(),
+            monthly_limit: None,
+            monthly_exceeded: false,
+        }
+    }
+
+    #[test]
+    fn near_limit_respects_threshold() {
+        assert!(is_near_limit(&status("9.0", Some("10.00")), 90));
+        assert!(!is_near_limit(&status("8.9", Some("10.00")), 90));
+        assert!(!is_near_limit(&status("100.0", None), 90));
+    }
+
+    #[test]
+    fn pick_next_wraps_around_and_skips_unavailable() {
+        let ids: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let next = |current: &str, skip: &str| {
+            pick_next(current, &ids, |id| id != skip).map(String::as_str)
+        };
+        assert_eq!(next("b", ""), Some("c"));
+        assert_eq!(next("c", ""), Some("a"));
+        assert_eq!(next("a", "b"), Some("c"));
+        assert_eq!(next("x", ""), Some("a"));
+        assert_eq!(pick_next("a", &ids, |id| id == "a"), None);
+    }
+
+    /// 每个供应商日限额 10 美元
+    fn rotation_state(ids: &[&str]) -> AppState {
+        let db = Arc::new(Database::memory().unwrap());
+        for id in ids {
+            let mut provider = Provider::with_id(
+                id.to_string(),
+                format!("Provider {id}"),
+                json!({ "env": { "ANTHROPIC_BASE_URL": format!("https://{id}.example.com") } }),
+                None,
+            );
+            provider.meta = Some(ProviderMeta {
+                limit_daily_usd: Some("10".to_string()),
+                ..Default::default()
+            });
+            db.save_provider("claude", &provider).unwrap();
+        }
+        AppState::new(db)
+    }
+
+    fn record_usage(state: &AppState, provider_id: &str, cost: &str) {
+        let conn = state.db.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO proxy_request_logs (
+                request_id, provider_id, app_type, model,
+                input_tokens, output_tokens, total_cost_usd,
+                latency_ms, status_code, created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                format!("req-{provider_id}-{cost}"),
+                provider_id,
+                "claude",
+                "claude-3",
+                100,
+                50,
+                cost,
+                100,
+                200,
+                now_secs()
+            ],
+        )
+        .unwrap();
+    }
+
+    fn enable_rotation(state: &AppState, provider_ids: &[&str]) {
+        let policy = ProviderRotationPolicy {
+            enabled: true,
+            provider_ids: provider_ids.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+        state
+            .db
+            .set_provider_rotation_policy("claude", &policy)
+            .unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn rotate_skips_missing_and_exhausted_candidates_and_persists_state() {
+        let _home = TempHome::new();
+        let state = rotation_state(&["a", "b", "c"]);
+        ProviderService::switch(&state, AppType::Claude, "a").unwrap();
+
+        // 未启用轮换时即使接近限额也不切换
+        record_usage(&state, "a", "9.5");
+        assert_eq!(rotate_if_needed(&state, AppType::Claude).unwrap(), None);
+
+        // "missing" 不存在，b 同样接近限额，应跳到 c
+        enable_rotation(&state, &["a", "missing", "b", "c"]);
+        record_usage(&state, "b", "9.9");
+        let rotation = rotate_if_needed(&state, AppType::Claude).unwrap().unwrap();
+        assert_eq!(rotation.from, "a");
+        assert_eq!(rotation.to, "c");
+        assert_eq!(
+            ProviderService::current(&state, AppType::Claude).unwrap(),
+            "c"
+        );
+
+        let persisted = state.db.get_provider_rotation_state("claude").unwrap();
+        assert_eq!(persisted.last_from.as_deref(), Some("a"));
+        assert_eq!(persisted.last_to.as_deref(), Some("c"));
+        assert_eq!(persisted.rotation_count, 1);
+        assert!(persisted.last_rotated_at.is_some());
+    }
+
+    #[test]
+    #[serial]
+    fn rotate_respects_cooldown_and_reports_no_candidate() {
+        let _home = TempHome::new();
+        let state = rotation_state(&["a", "b"]);
+        ProviderService::switch(&state, AppType::Claude, "a").unwrap();
+        enable_rotation(&state, &["a", "b"]);
+
+        record_usage(&state, "a", "9.5");
+        let rotation = rotate_if_needed(&state, AppType::Claude).unwrap().unwrap();
+        assert_eq!(rotation.to, "b");
+
+        // b 也接近限额，但仍在冷却期内：不切换，也不改动持久化状态
+        record_usage(&state, "b", "9.5");
+        {
+            let conn = state.db.conn.lock().unwrap();
+            conn.execute("DELETE FROM proxy_request_logs WHERE provider_id = 'a'", [])
+                .unwrap();
+        }
+        assert_eq!(rotate_if_needed(&state, AppType::Claude).unwrap(), None);
+        let persisted = state.db.get_provider_rotation_state("claude").unwrap();
+        assert_eq!(persisted.rotation_count, 1);
+
+        // 冷却期结束后可以切回 a
+        let mut expired = persisted.clone();
+        expired.last_rotated_at = Some(now_secs() - default_cooldown_secs() as i64 - 1);
+        state
+            .db
+            .set_provider_rotation_state("claude", &expired)
+            .unwrap();
+        let rotation = rotate_if_needed(&state, AppType::Claude).unwrap().unwrap();
+        assert_eq!((rotation.from.as_str(), rotation.to.as_str()), ("b", "a"));
+        assert_eq!(
+            state
+                .db
+                .get_provider_rotation_state("claude")
+                .unwrap()
+                .rotation_count,
+            2
+        );
+
+        // a 再次超限且 b 也不可用时没有候选
+        record_usage(&state, "a", "9.8");
+        let mut expired = state.db.get_provider_rotation_state("claude").unwrap();
+        expired.last_rotated_at = Some(now_secs() - default_cooldown_secs() as i64 - 1);
+        state
+            .db
+            .set_provider_rotation_state("claude", &expired)
+            .unwrap();
+        assert_eq!(rotate_if_needed(&state, AppType::Claude).unwrap(), None);
+    }
+}
@@ -0,0 +1,169 @@
+//! Builtin provider templates
+//!
+//! Templates are loaded from `resources/provider-templates.json` so new
+//! providers can be added without touching code.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::builtin_repos::{get_resource_path, LocalizedDescription};
+
+/// 模板中需要用户填写的字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateField {
+    /// 字段标识（如 "apiKey"）
+    pub key: String,
+    /// 填写提示（多语言）
+    pub hint: LocalizedDescription,
+}
+
+/// 内置供应商模板
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderTemplate {
+    pub id: String,
+    pub name: String,
+    /// 适用的应用（目前仅支持 "claude"）
+    #[serde(default = "default_template_app")]
+    pub app: String,
+    pub base_url: String,
+    pub default_model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub website_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_color: Option<String>,
+    #[serde(default)]
+    pub required_fields: Vec<TemplateField>,
+}
+
+fn default_template_app() -> String {
+    "claude".to_string()
+}
+
+/// 模板配置文件结构
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderTemplatesConfig {
+    #[allow(dead_code)]
+    version: u32,
+    templates: Vec<ProviderTemplate>,
+}
+
+/// 加载内置供应商模板，配置文件缺失时返回空列表
+pub fn load_templates() -> Result<Vec<ProviderTemplate>, AppError> {
+    let path = get_resource_path("provider-templates.json");
+
+    if !path.exists() {
+        log::warn!("供应商模板配置文件不存在: {:?}，使用空列表", path);
+        return Ok(vec![]);
+    }
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::Config(format!("读取供应商模板配置失败: {e}")))?;
+
+    parse_templates(&content)
+}
+
+fn parse_templates(content: &str) -> Result<Vec<ProviderTemplate>, AppError> {
+    let config: ProviderTemplatesConfig = serde_json::from_str(content)
+        .map_err(|e| AppError::Config(format!("解析供应商模板配置失败: {e}")))?;
+    Ok(config.templates)
+}
+
+impl ProviderTemplate {
+    /// 用模板和 API Key 构建新的供应商
+    pub fn build_provider(&self, api_key: &str) -> Result<(AppType, Provider), AppError> {
+        let api_key = api_key.trim();
+        if api_key.is_empty() {
+            return Err(AppError::InvalidInput("API Key 不能为空".to_string()));
+        }
+
+        let app_type = self
+            .app
+            .parse::<AppType>()
+            .map_err(|e| AppError::InvalidInput(e.to_string()))?;
+        let settings_config = match app_type {
+            AppType::Claude => json!({
+                "env": {
+                    "ANTHROPIC_BASE_URL": self.base_url,
+                    "ANTHROPIC_AUTH_TOKEN": api_key,
+                    "ANTHROPIC_MODEL": self.default_model,
+                    "ANTHROPIC_DEFAULT_HAIKU_MODEL": self.default_model,
+                    "ANTHROPIC_DEFAULT_SONNET_MODEL": self.default_model,
+                    "ANTHROPIC_DEFAULT_OPUS_MODEL": self.default_model,
+                }
+            }),
+            other => {
+                return Err(AppError::InvalidInput(format!(
+                    "模板 {} 的应用类型 {} 暂不支持",
+                    self.id,
+                    other.as_str()
+                )));
+            }
+        };
+
+        let mut provider = Provider::with_id(
+            uuid::Uuid::new_v4().to_string(),
+            self.name.clone(),
+            settings_config,
+            self.website_url.clone(),
+        );
+        provider.category = self.category.clone();
+        provider.icon = self.icon.clone();
+        provider.icon_color = self.icon_color.clone();
+        provider.created_at = Some(chrono::Utc::now().timestamp_millis());
+
+        Ok((app_type, provider))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_templates_are_valid() {
+        let templates = load_templates().unwrap();
+        assert!(!templates.is_empty());
+
+        let mut ids = std::collections::HashSet::new();
+        for template in &templates {
+            assert!(
+                ids.insert(template.id.as_str()),
+                "重复的模板 ID: {}",
+                template.id
+            );
+            assert!(template.base_url.starts_with("https://"));
+            assert!(!template.default_model.is_empty());
+            assert!(template.build_provider("sk-test").is_ok());
+        }
+    }
+
+    #[test]
+    fn build_provider_fills_claude_env() {
+        let templates = parse_templates(
+            r#"{"version":1,"templates":[{"id":"demo","name":"Demo",
+                "baseUrl":"https://api.example.com","defaultModel":"demo-1"}]}"#,
+        )
+        .unwrap();
+
+        let (app_type, provider) = templates[0].build_provider(" sk-demo ").unwrap();
+        assert_eq!(app_type, AppType::Claude);
+        assert_eq!(provider.name, "Demo");
+        let env = &provider.settings_config["env"];
+        assert_eq!(env["ANTHROPIC_BASE_URL"], "https://api.example.com");
+        assert_eq!(env["ANTHROPIC_AUTH_TOKEN"], "sk-demo");
+        assert_eq!(env["ANTHROPIC_MODEL"], "demo-1");
+
+        assert!(templates[0].build_provider("  ").is_err());
+    }
+}
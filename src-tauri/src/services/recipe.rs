@@ -0,0 +1,647 @@
+//! Recipe 服务层
+//!
+//! Recipe 是仓库中的一个 `recipe.json`，列出一组配合使用的 Commands/Agents/Hooks/Skills，
+//! 用户可以一次性安装整个工作流。安装复用各资源服务的发现与安装逻辑。
+//!
+//! ## recipe.json 格式
+//!
+//! ```json
+//! {
+//!   "name": "Code Review",
+//!   "description": "PR 审查工作流",
+//!   "items": [
+//!     { "type": "agent", "key": "review/code-reviewer" },
+//!     { "type": "command", "key": "review/pr", "dependsOn": ["agent:review/code-reviewer"] }
+//!   ]
+//! }
+//! ```
+//!
+//! - `key`：Commands/Agents/Hooks 为仓库内的资源 key（namespace/filename），Skills 为目录路径
+//! - `dependsOn`：依赖的条目，写作 `key` 或 `type:key`；依赖安装失败时该条目会被跳过
+//! - 没有依赖关系时按 MCP → Skills → Agents → Hooks → Commands 的顺序安装
+//!
+//! ## 中断与恢复
+//!
+//! 每个条目安装成功后才写入 `import_progress` 表（单行插入，不会留下半条记录）。
+//! 未传入 resume token 时使用由仓库、recipe 路径和目标应用组成的默认 token，
+//! 因此即使上次运行在返回结果前崩溃，重新安装同一个 recipe 也会直接跳过已完成的条目；
+//! 全部完成后清除进度
+
+use crate::app_config::{AppType, CommandRepo};
+use crate::database::Database;
+use crate::services::agent::AgentService;
+use crate::services::command::CommandService;
+use crate::services::config::ConfigTarget;
+use crate::services::hook::HookService;
+use crate::services::skill::{SkillRepo, SkillService};
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// recipe.json 的内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recipe {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub items: Vec<RecipeItem>,
+}
+
+/// Recipe 中的单个资源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeItem {
+    #[serde(rename = "type")]
+    pub resource_type: ConfigTarget,
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+}
+
+impl RecipeItem {
+    /// 依赖引用是否指向本条目（`key` 或 `type:key`）
+    fn matches_ref(&self, reference: &str) -> bool {
+        reference == self.key
+            || reference == format!("{}:{}", type_name(self.resource_type), self.key)
+    }
+}
+
+/// 单个条目的安装结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecipeItemStatus {
+    Installed,
+    AlreadyInstalled,
+    /// 上次中断的运行中已完成，本次直接跳过
+    Resumed,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeItemResult {
+    pub resource_type: ConfigTarget,
+    pub key: String,
+    pub status: RecipeItemStatus,
+    pub error: Option<String>,
+}
+
+/// Recipe 安装结果，`items` 按实际安装顺序排列
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeInstallResult {
+    pub name: String,
+    pub items: Vec<RecipeItemResult>,
+    pub installed: usize,
+    pub failed: usize,
+    /// 有条目失败或被跳过时返回，传给下一次安装以从中断处继续
+    pub resume_token: Option<String>,
+}
+
+/// 资源类型在 recipe.json 中的写法（与 ConfigTarget 的序列化一致）
+fn type_name(resource_type: ConfigTarget) -> &'static str {
+    match resource_type {
+        ConfigTarget::Skill => "skill",
+        ConfigTarget::Command => "command",
+        ConfigTarget::Hook => "hook",
+        ConfigTarget::Agent => "agent",
+        ConfigTarget::Mcp => "mcp",
+    }
+}
+
+/// 无依赖关系时的默认安装顺序：被引用的资源优先
+fn type_rank(resource_type: ConfigTarget) -> u8 {
+    match resource_type {
+        ConfigTarget::Mcp => 0,
+        ConfigTarget::Skill => 1,
+        ConfigTarget::Agent => 2,
+        ConfigTarget::Hook => 3,
+        ConfigTarget::Command => 4,
+    }
+}
+
+/// 可恢复安装的进度，按 resume token 记录在 `import_progress` 表中
+struct ImportProgress<'a> {
+    db: &'a Arc<Database>,
+    token: String,
+    done: HashSet<String>,
+}
+
+impl<'a> ImportProgress<'a> {
+    /// 加载 token 下已有的进度（没有记录时即开始新的一轮）
+    fn load(db: &'a Arc<Database>, token: String) -> Result<Self> {
+        let done = db.get_imported_items(&token)?;
+        Ok(Self { db, token, done })
+    }
+
+    /// 未传入 resume token 时使用的默认 token，同一仓库、recipe 与应用总是相同
+    fn default_token(repo: &CommandRepo, recipe_path: &str, app: &AppType) -> String {
+        format!(
+            "{}/{}@{}:{}:{}",
+            repo.owner,
+            repo.name,
+            repo.branch,
+            recipe_path.trim_matches('/'),
+            app.as_str()
+        )
+    }
+
+    fn item_key(item: &RecipeItem) -> String {
+        format!("{}:{}", type_name(item.resource_type), item.key)
+    }
+
+    fn is_done(&self, item: &RecipeItem) -> bool {
+        self.done.contains(&Self::item_key(item))
+    }
+
+    /// 条目安装成功后调用；记录失败只影响恢复，不影响本次结果
+    fn mark_done(&mut self, item: &RecipeItem) {
+        let key = Self::item_key(item);
+        if let Err(e) = self.db.record_import_item(&self.token, &key) {
+            log::warn!("记录安装进度失败 {key}: {e}");
+        }
+        self.done.insert(key);
+    }
+
+    /// 结束本轮：全部完成时清除进度并返回 None，否则返回 resume token
+    fn finish(self, complete: bool) -> Option<String> {
+        if !complete {
+            return Some(self.token);
+        }
+        if let Err(e) = self.db.clear_import_progress(&self.token) {
+            log::warn!("清除安装进度失败: {e}");
+        }
+        None
+    }
+}
+
+pub struct RecipeService {
+    http_client: Client,
+}
+
+impl Default for RecipeService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecipeService {
+    pub fn new() -> Self {
+        Self {
+            http_client: Client::builder()
+                .user_agent("cc-switch")
+                .build()
+                .expect("Failed to create HTTP client"),
+        }
+    }
+
+    /// 解析 recipe.json
+    pub fn parse_recipe(content: &str) -> Result<Recipe> {
+        let recipe: Recipe =
+            serde_json::from_str(content).map_err(|e| anyhow!("recipe.json 格式错误: {e}"))?;
+        if recipe.items.is_empty() {
+            return Err(anyhow!("Recipe {} 没有列出任何资源", recipe.name));
+        }
+        Ok(recipe)
+    }
+
+    /// 计算安装顺序（返回条目下标）
+    ///
+    /// 拓扑排序：依赖先于被依赖者；同时可安装的条目按资源类型、再按原始顺序排列。
+    /// 重复条目、未知依赖与循环依赖均报错
+    pub fn install_order(recipe: &Recipe) -> Result<Vec<usize>> {
+        let items = &recipe.items;
+
+        let mut seen = HashSet::new();
+        for item in items {
+            if !seen.insert((item.resource_type, item.key.as_str())) {
+                return Err(anyhow!("Recipe 中存在重复条目: {}", item.key));
+            }
+        }
+
+        let mut deps: Vec<Vec<usize>> = Vec::with_capacity(items.len());
+        for item in items {
+            let mut item_deps = Vec::new();
+            for reference in &item.depends_on {
+                let matched: Vec<usize> = items
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, other)| other.matches_ref(reference))
+                    .map(|(i, _)| i)
+                    .collect();
+                if matched.is_empty() {
+                    return Err(anyhow!("{} 依赖的 {} 不在 Recipe 中", item.key, reference));
+                }
+                item_deps.extend(matched);
+            }
+            deps.push(item_deps);
+        }
+
+        let mut order = Vec::with_capacity(items.len());
+        let mut done = vec![false; items.len()];
+        while order.len() < items.len() {
+            let next = (0..items.len())
+                .filter(|&i| !done[i] && deps[i].iter().all(|&d| done[d]))
+                .min_by_key(|&i| (type_rank(items[i].resource_type), i))
+                .ok_or_else(|| anyhow!("Recipe 中存在循环依赖"))?;
+            done[next] = true;
+            order.push(next);
+        }
+
+        Ok(order)
+    }
+
+    /// 下载仓库中的 recipe.json
+    async fn fetch_recipe(&self, repo: &CommandRepo, recipe_path: &str) -> Result<Recipe> {
+        let path = recipe_path.trim_matches('/');
+        let url = format!(
+            "https://raw.githubusercontent.com/{}/{}/{}/{}",
+            repo.owner, repo.name, repo.branch, path
+        );
+
+        let response = self
+            .http_client
+            .get(&url)
+            .timeout(crate::settings::get_timeout_config().file_download())
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "下载 {}/{} 的 {} 失败: HTTP {}",
+                repo.owner,
+                repo.name,
+                path,
+                response.status().as_u16()
+            ));
+        }
+
+        Self::parse_recipe(&response.text().await?)
+    }
+
+    /// 安装仓库中 Recipe 列出的全部资源
+    ///
+    /// 按 `install_order` 顺序逐个安装，单个条目失败不会中断整批安装；
+    /// 依赖未成功安装的条目会被跳过。已安装的资源不会重复安装。
+    /// 传入上次返回的 `resume_token` 时跳过上次已完成的条目，未传入时使用默认 token
+    pub async fn install_recipe(
+        &self,
+        db: &Arc<Database>,
+        repo: &CommandRepo,
+        recipe_path: &str,
+        app: &AppType,
+        resume_token: Option<&str>,
+    ) -> Result<RecipeInstallResult> {
+        let recipe = self.fetch_recipe(repo, recipe_path).await?;
+        let order = Self::install_order(&recipe)?;
+        let token = resume_token
+            .map(str::to_string)
+            .unwrap_or_else(|| ImportProgress::default_token(repo, recipe_path, app));
+        let mut progress = ImportProgress::load(db, token)?;
+        let needs = |t: ConfigTarget| {
+            recipe
+                .items
+                .iter()
+                .any(|i| i.resource_type == t && !progress.is_done(i))
+        };
+
+        // 只发现 Recipe 用到的资源类型；发现失败时该类型的条目全部记为失败
+        let command_service = CommandService::new();
+        let agent_service = AgentService::new();
+        let hook_service = HookService::new();
+        let skill_service = SkillService::new();
+
+        let commands = if needs(ConfigTarget::Command) {
+            command_service
+                .discover_available(db, vec![repo.clone()], false)
+                .await
+                .map_err(|e| e.to_string())
+        } else {
+            Ok(Vec::new())
+        };
+        let agents = if needs(ConfigTarget::Agent) {
+            agent_service
+                .discover_available(db, vec![repo.clone()], false)
+                .await
+                .map_err(|e| e.to_string())
+        } else {
+            Ok(Vec::new())
+        };
+        let hooks = if needs(ConfigTarget::Hook) {
+            hook_service
+                .discover_available(db, vec![repo.clone()], false)
+                .await
+                .map_err(|e| e.to_string())
+        } else {
+            Ok(Vec::new())
+        };
+        let skills = if needs(ConfigTarget::Skill) {
+            let skill_repo = SkillRepo {
+                owner: repo.owner.clone(),
+                name: repo.name.clone(),
+                branch: repo.branch.clone(),
+                enabled: true,
+                builtin: false,
+                description_zh: None,
+                description_en: None,
+                description_ja: None,
+                added_at: 0,
+                browse_only: false,
+            };
+            skill_service
+                .discover_available(vec![skill_repo])
+                .await
+                .map_err(|e| e.to_string())
+        } else {
+            Ok(Vec::new())
+        };
+
+        let mut results: Vec<RecipeItemResult> = Vec::with_capacity(order.len());
+        let mut succeeded: HashSet<usize> = HashSet::new();
+        let mut complete = true;
+
+        for index in order {
+            let item = &recipe.items[index];
+
+            if progress.is_done(item) {
+                succeeded.insert(index);
+                results.push(RecipeItemResult {
+                    resource_type: item.resource_type,
+                    key: item.key.clone(),
+                    status: RecipeItemStatus::Resumed,
+                    error: None,
+                });
+                continue;
+            }
+
+            let failed_dep = item.depends_on.iter().find(|reference| {
+                recipe
+                    .items
+                    .iter()
+                    .enumerate()
+                    .any(|(i, other)| other.matches_ref(reference) && !succeeded.contains(&i))
+            });
+
+            if let Some(dep) = failed_dep {
+                complete = false;
+                results.push(RecipeItemResult {
+                    resource_type: item.resource_type,
+                    key: item.key.clone(),
+                    status: RecipeItemStatus::Skipped,
+                    error: Some(format!("依赖 {dep} 未安装成功")),
+                });
+                continue;
+            }
+
+            let outcome: Result<RecipeItemStatus, String> = match item.resource_type {
+                ConfigTarget::Command => match (&commands, db.get_installed_command(&item.key)) {
+                    (Err(e), _) => Err(e.clone()),
+                    (_, Err(e)) => Err(e.to_string()),
+                    (_, Ok(Some(_))) => Ok(RecipeItemStatus::AlreadyInstalled),
+                    (Ok(list), Ok(None)) => match list.iter().find(|c| c.key == item.key) {
+                        Some(command) => command_service
+                            .install(db, command, app)
+                            .await
+                            .map(|_| RecipeItemStatus::Installed)
+                            .map_err(|e| e.to_string()),
+                        None => Err(format!("仓库中未找到 Command {}", item.key)),
+                    },
+                },
+                ConfigTarget::Agent => match (&agents, db.get_installed_agent(&item.key)) {
+                    (Err(e), _) => Err(e.clone()),
+                    (_, Err(e)) => Err(e.to_string()),
+                    (_, Ok(Some(_))) => Ok(RecipeItemStatus::AlreadyInstalled),
+                    (Ok(list), Ok(None)) => match list.iter().find(|a| a.key == item.key) {
+                        Some(agent) => agent_service
+                            .install(db, agent, app)
+                            .await
+                            .map(|_| RecipeItemStatus::Installed)
+                            .map_err(|e| e.to_string()),
+                        None => Err(format!("仓库中未找到 Agent {}", item.key)),
+                    },
+                },
+                ConfigTarget::Hook => match (&hooks, db.get_installed_hook(&item.key)) {
+                    (Err(e), _) => Err(e.clone()),
+                    (_, Err(e)) => Err(e.to_string()),
+                    (_, Ok(Some(_))) => Ok(RecipeItemStatus::AlreadyInstalled),
+                    (Ok(list), Ok(None)) => match list.iter().find(|h| h.key == item.key) {
+                        Some(hook) => hook_service
+                            .install(db, hook, app)
+                            .await
+                            .map(|_| RecipeItemStatus::Installed)
+                            .map_err(|e| e.to_string()),
+                        None => Err(format!("仓库中未找到 Hook {}", item.key)),
+                    },
+                },
+                ConfigTarget::Skill => match &skills {
+                    Err(e) => Err(e.clone()),
+                    Ok(list) => {
+                        let directory = item.key.trim_matches('/');
+                        match list.iter().find(|s| s.directory == directory) {
+                            Some(skill) => match db.get_installed_skill(&skill.key) {
+                                Err(e) => Err(e.to_string()),
+                                Ok(Some(_)) => Ok(RecipeItemStatus::AlreadyInstalled),
+                                Ok(None) => skill_service
+                                    .install(db, skill, app)
+                                    .await
+                                    .map(|_| RecipeItemStatus::Installed)
+                                    .map_err(|e| e.to_string()),
+                            },
+                            None => Err(format!("仓库中未找到 Skill {}", item.key)),
+                        }
+                    }
+                },
+                ConfigTarget::Mcp => {
+                    results.push(RecipeItemResult {
+                        resource_type: item.resource_type,
+                        key: item.key.clone(),
+                        status: RecipeItemStatus::Skipped,
+                        error: Some("MCP 服务器暂不支持从仓库安装，请手动添加".to_string()),
+                    });
+                    continue;
+                }
+            };
+
+            let (status, error) = match outcome {
+                Ok(status) => {
+                    succeeded.insert(index);
+                    progress.mark_done(item);
+                    (status, None)
+                }
+                Err(e) => {
+                    complete = false;
+                    log::warn!("Recipe {} 安装 {} 失败: {}", recipe.name, item.key, e);
+                    (RecipeItemStatus::Failed, Some(e))
+                }
+            };
+            results.push(RecipeItemResult {
+                resource_type: item.resource_type,
+                key: item.key.clone(),
+                status,
+                error,
+            });
+        }
+
+        let installed = results
+            .iter()
+            .filter(|r| r.status == RecipeItemStatus::Installed)
+            .count();
+        let failed = results
+            .iter()
+            .filter(|r| r.status == RecipeItemStatus::Failed)
+            .count();
+        log::info!(
+            "Recipe {} 安装完成：新安装 {}，失败 {}",
+            recipe.name,
+            installed,
+            failed
+        );
+
+        Ok(RecipeInstallResult {
+            name: recipe.name,
+            items: results,
+            installed,
+            failed,
+            resume_token: progress.finish(complete),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys_in_order(recipe: &Recipe) -> Vec<&str> {
+        RecipeService::install_order(recipe)
+            .unwrap()
+            .into_iter()
+            .map(|i| recipe.items[i].key.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn install_order_respects_dependencies_and_type_rank() {
+        let recipe = RecipeService::parse_recipe(
+            r#"{"name":"review","items":[
+                {"type":"command","key":"review/pr","dependsOn":["agent:review/reviewer"]},
+                {"type":"hook","key":"review/lint","dependsOn":["review/pr"]},
+                {"type":"agent","key":"review/reviewer"},
+                {"type":"skill","key":"skills/git"}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            keys_in_order(&recipe),
+            vec!["skills/git", "review/reviewer", "review/pr", "review/lint"]
+        );
+    }
+
+    #[test]
+    fn install_order_rejects_bad_references() {
+        let unknown = RecipeService::parse_recipe(
+            r#"{"name":"x","items":[{"type":"command","key":"a","dependsOn":["missing"]}]}"#,
+        )
+        .unwrap();
+        assert!(RecipeService::install_order(&unknown).is_err());
+
+        let cycle = RecipeService::parse_recipe(
+            r#"{"name":"x","items":[
+                {"type":"command","key":"a","dependsOn":["b"]},
+                {"type":"agent","key":"b","dependsOn":["a"]}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(RecipeService::install_order(&cycle).is_err());
+
+        assert!(RecipeService::parse_recipe(r#"{"name":"x","items":[]}"#).is_err());
+    }
+
+    #[test]
+    fn interrupted_install_resumes_from_progress() {
+        let db = Arc::new(Database::memory().unwrap());
+        let recipe = RecipeService::parse_recipe(
+            r#"{"name":"big","items":[
+                {"type":"command","key":"a"},
+                {"type":"command","key":"b"},
+                {"type":"command","key":"c"},
+                {"type":"command","key":"d"}
+            ]}"#,
+        )
+        .unwrap();
+        let order = RecipeService::install_order(&recipe).unwrap();
+
+        let token = "owner/repo@main:recipe.json:claude".to_string();
+
+        // 第一次运行：完成 2 个条目后进程中断，没有机会返回 resume token
+        let mut progress = ImportProgress::load(&db, token.clone()).unwrap();
+        for &i in order.iter().take(2) {
+            progress.mark_done(&recipe.items[i]);
+        }
+        drop(progress);
+
+        // 恢复运行：只剩后 2 个条目需要安装
+        let mut progress = ImportProgress::load(&db, token.clone()).unwrap();
+        let pending: Vec<&str> = order
+            .iter()
+            .map(|&i| &recipe.items[i])
+            .filter(|item| !progress.is_done(item))
+            .map(|item| item.key.as_str())
+            .collect();
+        assert_eq!(pending, vec!["c", "d"]);
+
+        for &i in &order[2..] {
+            progress.mark_done(&recipe.items[i]);
+        }
+        assert_eq!(progress.finish(true), None);
+        assert!(db.get_imported_items(&token).unwrap().is_empty());
+    }
+
+    #[test]
+    fn failed_install_resumes_with_default_token() {
+        let db = Arc::new(Database::memory().unwrap());
+        let recipe = RecipeService::parse_recipe(
+            r#"{"name":"big","items":[
+                {"type":"command","key":"a"},
+                {"type":"command","key":"b"}
+            ]}"#,
+        )
+        .unwrap();
+        let repo = CommandRepo {
+            owner: "owner".to_string(),
+            name: "repo".to_string(),
+            branch: "main".to_string(),
+            enabled: true,
+            builtin: false,
+            description_zh: None,
+            description_en: None,
+            description_ja: None,
+            added_at: 0,
+            excluded_keys: Vec::new(),
+            has_commands: None,
+            has_agents: None,
+            has_hooks: None,
+            user_edited_description: false,
+            browse_only: false,
+        };
+        let token = ImportProgress::default_token(&repo, "/recipe.json", &AppType::Claude);
+        assert_eq!(
+            token,
+            ImportProgress::default_token(&repo, "recipe.json", &AppType::Claude)
+        );
+
+        // 第一次运行："a" 成功，"b" 失败
+        let mut progress = ImportProgress::load(&db, token.clone()).unwrap();
+        progress.mark_done(&recipe.items[0]);
+        assert_eq!(progress.finish(false), Some(token.clone()));
+
+        // 不带 token 重新运行同一个 recipe 时使用相同的默认 token，"a" 被跳过
+        let progress = ImportProgress::load(&db, token).unwrap();
+        assert!(progress.is_done(&recipe.items[0]));
+        assert!(!progress.is_done(&recipe.items[1]));
+    }
+}
@@ -0,0 +1,231 @@
+//! 按来源仓库汇总已安装资源
+//!
+//! 移除仓库订阅前，列出从该仓库安装的 Commands/Agents/Hooks/Skills，
+//! 并支持一次性卸载（卸载走各资源自身的 `uninstall`，文件进入回收站可恢复）。
+//!
+//! 仓库匹配忽略 owner/name 的大小写（与 GitHub 一致）；本地导入的资源没有仓库信息，不会被匹配。
+
+use crate::app_config::{InstalledAgent, InstalledCommand, InstalledHook, InstalledSkill};
+use crate::database::Database;
+use crate::services::agent::AgentService;
+use crate::services::command::CommandService;
+use crate::services::config::ConfigTarget;
+use crate::services::hook::HookService;
+use crate::services::resource_lock::ResourceLockService;
+use crate::services::skill::SkillService;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// 从某个仓库安装的全部资源
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledByRepo {
+    pub commands: Vec<InstalledCommand>,
+    pub agents: Vec<InstalledAgent>,
+    pub hooks: Vec<InstalledHook>,
+    pub skills: Vec<InstalledSkill>,
+}
+
+impl InstalledByRepo {
+    pub fn total(&self) -> usize {
+        self.commands.len() + self.agents.len() + self.hooks.len() + self.skills.len()
+    }
+}
+
+/// 批量卸载结果（按资源类型列出已卸载的 ID）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoUninstallResult {
+    pub commands: Vec<String>,
+    pub agents: Vec<String>,
+    pub hooks: Vec<String>,
+    pub skills: Vec<String>,
+    /// 未能卸载的资源及原因（格式 "类型 id: 错误"）
+    pub failed: Vec<String>,
+}
+
+/// 资源记录的仓库是否为 `owner/name`
+fn matches_repo(
+    repo_owner: &Option<String>,
+    repo_name: &Option<String>,
+    owner: &str,
+    name: &str,
+) -> bool {
+    match (repo_owner, repo_name) {
+        (Some(o), Some(n)) => o.eq_ignore_ascii_case(owner) && n.eq_ignore_ascii_case(name),
+        _ => false,
+    }
+}
+
+/// 逐个卸载，成功的 ID 写入 `removed`，失败写入 `failed`
+pub(crate) fn uninstall_each(
+    resource_type: ConfigTarget,
+    ids: Vec<String>,
+    uninstall: impl Fn(&str) -> Result<()>,
+    removed: &mut Vec<String>,
+    failed: &mut Vec<String>,
+) {
+    for id in ids {
+        match uninstall(&id) {
+            Ok(()) => removed.push(id),
+            Err(e) => {
+                log::warn!("卸载 {resource_type} {id} 失败: {e}");
+                failed.push(format!("{resource_type} {id}: {e}"));
+            }
+        }
+    }
+}
+
+pub struct RepoInstallsService;
+
+impl RepoInstallsService {
+    /// 列出从 `owner/name` 安装的全部资源
+    pub fn list_installed_by_repo(
+        db: &Arc<Database>,
+        owner: &str,
+        name: &str,
+    ) -> Result<InstalledByRepo> {
+        Ok(InstalledByRepo {
+            commands: db
+                .get_all_installed_commands()?
+                .into_values()
+                .filter(|c| matches_repo(&c.repo_owner, &c.repo_name, owner, name))
+                .collect(),
+            agents: db
+                .get_all_installed_agents()?
+                .into_values()
+                .filter(|a| matches_repo(&a.repo_owner, &a.repo_name, owner, name))
+                .collect(),
+            hooks: db
+                .get_all_installed_hooks()?
+                .into_values()
+                .filter(|h| matches_repo(&h.repo_owner, &h.repo_name, owner, name))
+                .collect(),
+            skills: db
+                .get_all_installed_skills()?
+                .into_values()
+                .filter(|s| matches_repo(&s.repo_owner, &s.repo_name, owner, name))
+                .collect(),
+        })
+    }
+
+    /// 卸载从 `owner/name` 安装的全部资源
+    ///
+    /// 调用方应先用 [`Self::list_installed_by_repo`] 向用户展示卸载计划并确认；
+    /// 单个资源卸载失败不会中断其余资源。其中有锁定的资源且未传 `force` 时整批取消
+    pub fn uninstall_all_from_repo(
+        db: &Arc<Database>,
+        owner: &str,
+        name: &str,
+        force: bool,
+    ) -> Result<RepoUninstallResult> {
+        let plan = Self::list_installed_by_repo(db, owner, name)?;
+        let targets = [
+            (
+                ConfigTarget::Command,
+                plan.commands
+                    .iter()
+                    .map(|c| c.id.as_str())
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                ConfigTarget::Agent,
+                plan.agents.iter().map(|a| a.id.as_str()).collect(),
+            ),
+            (
+                ConfigTarget::Hook,
+                plan.hooks.iter().map(|h| h.id.as_str()).collect(),
+            ),
+            (
+                ConfigTarget::Skill,
+                plan.skills.iter().map(|s| s.id.as_str()).collect(),
+            ),
+        ];
+        for (resource_type, ids) in targets {
+            ResourceLockService::ensure_unlocked(db, resource_type, ids, force)?;
+        }
+
+        let mut result = RepoUninstallResult::default();
+
+        uninstall_each(
+            ConfigTarget::Command,
+            plan.commands.into_iter().map(|c| c.id).collect(),
+            |id| CommandService::uninstall(db, id),
+            &mut result.commands,
+            &mut result.failed,
+        );
+        uninstall_each(
+            ConfigTarget::Agent,
+            plan.agents.into_iter().map(|a| a.id).collect(),
+            |id| AgentService::uninstall(db, id),
+            &mut result.agents,
+            &mut result.failed,
+        );
+        uninstall_each(
+            ConfigTarget::Hook,
+            plan.hooks.into_iter().map(|h| h.id).collect(),
+            |id| HookService::uninstall(db, id),
+            &mut result.hooks,
+            &mut result.failed,
+        );
+        uninstall_each(
+            ConfigTarget::Skill,
+            plan.skills.into_iter().map(|s| s.id).collect(),
+            |id| SkillService::uninstall(db, id).map(|_| ()),
+            &mut result.skills,
+            &mut result.failed,
+        );
+
+        log::info!(
+            "已卸载来自 {owner}/{name} 的资源: {} 个 Command, {} 个 Agent, {} 个 Hook, {} 个 Skill, {} 个失败",
+            result.commands.len(),
+            result.agents.len(),
+            result.hooks.len(),
+            result.skills.len(),
+            result.failed.len()
+        );
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn matches_repo_ignores_case_and_skips_local_resources() {
+        let owner = Some("Anthropics".to_string());
+        let name = Some("Skills".to_string());
+
+        assert!(matches_repo(&owner, &name, "anthropics", "skills"));
+        assert!(!matches_repo(&owner, &name, "anthropics", "commands"));
+        assert!(!matches_repo(&None, &None, "anthropics", "skills"));
+        assert!(!matches_repo(&owner, &None, "anthropics", "skills"));
+    }
+
+    #[test]
+    fn uninstall_each_continues_after_failure() {
+        let mut removed = vec![];
+        let mut failed = vec![];
+
+        uninstall_each(
+            ConfigTarget::Command,
+            vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            |id| {
+                if id == "b" {
+                    Err(anyhow!("locked"))
+                } else {
+                    Ok(())
+                }
+            },
+            &mut removed,
+            &mut failed,
+        );
+
+        assert_eq!(removed, ["a", "c"]);
+        assert_eq!(failed, ["Command b: locked"]);
+    }
+}
@@ -0,0 +1,240 @@
+//! 仓库级元数据（`ccswitch.json`）
+//!
+//! 精选仓库可以在根目录放置 `ccswitch.json`，声明仓库的展示名称、描述、图标，
+//! 以及各资源的名称/描述/分类覆盖。发现阶段扫描完仓库后合并到 `Discoverable*` 条目中，
+//! 合并结果随发现缓存一起保存；仓库级信息另存到 settings 表（`repo_metadata:owner/name`）。
+//!
+//! ## ccswitch.json 格式
+//!
+//! ```json
+//! {
+//!   "displayName": "Awesome Commands",
+//!   "description": "常用开发命令合集",
+//!   "icon": "https://example.com/icon.png",
+//!   "commands": { "review/pr": { "name": "PR 审查", "category": "review" } },
+//!   "agents": { "review/code-reviewer": { "description": "代码审查助手" } },
+//!   "hooks": { "security/pre-bash-check-pre": { "name": "Bash 安全检查" } },
+//!   "skills": { "skills/pdf": { "name": "PDF 工具" } }
+//! }
+//! ```
+//!
+//! - 资源 key 与发现结果的 key 一致，Skills 使用目录路径
+//! - 覆盖只填补 frontmatter 缺失的字段（名称回退为文件名/目录名、描述为空、无分类），
+//!   不会覆盖资源文件自身声明的值
+//! - 文件不存在或解析失败时静默忽略
+
+use crate::app_config::{DiscoverableAgent, DiscoverableCommand, DiscoverableHook};
+use crate::services::skill::DiscoverableSkill;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// 仓库根目录的元数据文件名
+pub const REPO_METADATA_FILE: &str = "ccswitch.json";
+
+/// 单个资源的展示覆盖
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 分类（仅 Commands 使用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+}
+
+/// `ccswitch.json` 的内容
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub commands: HashMap<String, ResourceOverride>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub agents: HashMap<String, ResourceOverride>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub hooks: HashMap<String, ResourceOverride>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub skills: HashMap<String, ResourceOverride>,
+}
+
+/// 名称仍是回退值（文件名/目录名）时才使用覆盖
+fn fill_name(name: &mut String, fallbacks: &[&str], value: &Option<String>) {
+    if let Some(value) = value {
+        if fallbacks.contains(&name.as_str()) {
+            *name = value.clone();
+        }
+    }
+}
+
+fn fill_description(description: &mut String, value: &Option<String>) {
+    if let Some(value) = value {
+        if description.trim().is_empty() {
+            *description = value.clone();
+        }
+    }
+}
+
+impl RepoMetadata {
+    /// 从解压后的仓库根目录读取 `ccswitch.json`，不存在或无效时返回 None
+    pub fn load(repo_dir: &Path) -> Option<Self> {
+        let path = repo_dir.join(REPO_METADATA_FILE);
+        let content = fs::read_to_string(&path).ok()?;
+        match serde_json::from_str(&content) {
+            Ok(metadata) => Some(metadata),
+            Err(e) => {
+                log::warn!("忽略无效的 {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    pub fn apply_to_commands(&self, commands: &mut [DiscoverableCommand]) {
+        for command in commands {
+            let Some(o) = self.commands.get(&command.key) else {
+                continue;
+            };
+            fill_name(&mut command.name, &[&command.filename], &o.name);
+            fill_description(&mut command.description, &o.description);
+            if let Some(category) = &o.category {
+                if command.category.is_none() && command.categories.is_empty() {
+                    command.category = Some(category.clone());
+                    command.categories = vec![category.clone()];
+                }
+            }
+        }
+    }
+
+    pub fn apply_to_agents(&self, agents: &mut [DiscoverableAgent]) {
+        for agent in agents {
+            let Some(o) = self.agents.get(&agent.key) else {
+                continue;
+            };
+            fill_name(&mut agent.name, &[&agent.filename], &o.name);
+            fill_description(&mut agent.description, &o.description);
+        }
+    }
+
+    pub fn apply_to_hooks(&self, hooks: &mut [DiscoverableHook]) {
+        for hook in hooks {
+            let Some(o) = self.hooks.get(&hook.key) else {
+                continue;
+            };
+            fill_name(&mut hook.name, &[&hook.filename], &o.name);
+            let mut description = hook.description.take().unwrap_or_default();
+            fill_description(&mut description, &o.description);
+            hook.description = Some(description).filter(|d| !d.is_empty());
+        }
+    }
+
+    pub fn apply_to_skills(&self, skills: &mut [DiscoverableSkill]) {
+        for skill in skills {
+            let Some(o) = self.skills.get(&skill.directory) else {
+                continue;
+            };
+            let dir_name = Path::new(&skill.directory)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            fill_name(&mut skill.name, &[&skill.directory, &dir_name], &o.name);
+            fill_description(&mut skill.description, &o.description);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(key: &str, name: &str, description: &str) -> DiscoverableCommand {
+        let (namespace, filename) = crate::services::command::CommandService::parse_id(key);
+        DiscoverableCommand {
+            key: key.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            namespace,
+            filename,
+            category: None,
+            categories: vec![],
+            readme_url: None,
+            repo_owner: "owner".to_string(),
+            repo_name: "repo".to_string(),
+            repo_branch: "main".to_string(),
+            source_path: None,
+            localized: Default::default(),
+        }
+    }
+
+    #[test]
+    fn overrides_fill_only_missing_frontmatter() {
+        let metadata: RepoMetadata = serde_json::from_str(
+            r#"{
+                "displayName": "Curated",
+                "commands": {
+                    "review/pr": { "name": "PR 审查", "description": "审查 PR", "category": "review" },
+                    "git/commit": { "name": "提交", "description": "生成提交信息" }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(metadata.display_name.as_deref(), Some("Curated"));
+
+        let mut commands = vec![
+            command("review/pr", "pr", ""),
+            command("git/commit", "Smart Commit", "来自 frontmatter"),
+            command("misc/other", "other", ""),
+        ];
+        metadata.apply_to_commands(&mut commands);
+
+        assert_eq!(commands[0].name, "PR 审查");
+        assert_eq!(commands[0].description, "审查 PR");
+        assert_eq!(commands[0].categories, ["review"]);
+        // frontmatter 已声明的字段保持不变
+        assert_eq!(commands[1].name, "Smart Commit");
+        assert_eq!(commands[1].description, "来自 frontmatter");
+        assert_eq!(commands[2].name, "other");
+    }
+
+    #[test]
+    fn load_ignores_missing_or_invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(RepoMetadata::load(dir.path()).is_none());
+
+        fs::write(dir.path().join(REPO_METADATA_FILE), "not json").unwrap();
+        assert!(RepoMetadata::load(dir.path()).is_none());
+
+        fs::write(
+            dir.path().join(REPO_METADATA_FILE),
+            r#"{"icon":"🧰","skills":{"skills/pdf":{"name":"PDF"}}}"#,
+        )
+        .unwrap();
+        let metadata = RepoMetadata::load(dir.path()).unwrap();
+        assert_eq!(metadata.icon.as_deref(), Some("🧰"));
+        assert!(metadata.skills.contains_key("skills/pdf"));
+    }
+
+    #[test]
+    fn repo_metadata_round_trips_through_settings() {
+        let db = crate::database::Database::memory().unwrap();
+        let metadata = RepoMetadata {
+            display_name: Some("Curated".to_string()),
+            ..Default::default()
+        };
+
+        db.save_repo_metadata("owner", "repo", Some(&metadata))
+            .unwrap();
+        let cached = db.get_repo_metadata("owner", "repo").unwrap().unwrap();
+        assert_eq!(cached.display_name.as_deref(), Some("Curated"));
+
+        db.save_repo_metadata("owner", "repo", None).unwrap();
+        assert!(db.get_repo_metadata("owner", "repo").unwrap().is_none());
+    }
+}
@@ -0,0 +1,268 @@
+//! 导出单个资源
+//!
+//! 把一个 Command/Agent/Hook 实际生效的文件内容（项目级安装取项目副本）和一份小清单打包返回，
+//! 便于粘贴到 issue 或发给他人；可选写入到指定路径。与完整的配置导出无关。
+
+use crate::app_config::InstallScope;
+use crate::config::atomic_write;
+use crate::database::Database;
+use crate::services::agent::AgentService;
+use crate::services::command::CommandService;
+use crate::services::hook::HookService;
+use crate::services::update::ResourceType;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// 导出清单（描述资源来源，不含本地状态）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifest {
+    pub resource_type: ResourceType,
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<String>,
+    /// 导出内容的 SHA-256
+    pub content_hash: String,
+    pub exported_at: i64,
+    /// 导出时的 CC Switch 版本
+    pub app_version: String,
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedResource {
+    /// 建议的文件名（如 `commit.md`、`pre-bash-check.json`）
+    pub filename: String,
+    pub content: String,
+    pub metadata: ExportManifest,
+    /// 写入的文件路径（未指定目标路径时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub written_path: Option<String>,
+}
+
+/// 三类资源记录中导出需要的公共字段
+struct ResourceRecord {
+    name: String,
+    description: Option<String>,
+    repo_owner: Option<String>,
+    repo_name: Option<String>,
+    repo_branch: Option<String>,
+    source_path: Option<String>,
+}
+
+impl ExportedResource {
+    /// 写入资源文件，并在旁边写入 `<文件名>.manifest.json`
+    ///
+    /// `target` 为已存在的目录时写到该目录下的 `filename`，否则视为文件路径
+    fn write_to(&self, target: &Path) -> Result<PathBuf> {
+        let path = if target.is_dir() {
+            target.join(&self.filename)
+        } else {
+            target.to_path_buf()
+        };
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let manifest_name = format!(
+            "{}.manifest.json",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        );
+        let manifest = serde_json::to_string_pretty(&self.metadata)?;
+
+        atomic_write(&path, self.content.as_bytes())?;
+        atomic_write(&path.with_file_name(manifest_name), manifest.as_bytes())?;
+        Ok(path)
+    }
+}
+
+pub struct ResourceExportService;
+
+impl ResourceExportService {
+    /// 导出单个资源，`target` 不为空时同时写入磁盘
+    pub fn export_resource(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+        id: &str,
+        target: Option<&Path>,
+    ) -> Result<ExportedResource> {
+        let not_found = || anyhow!("{resource_type} 未安装: {id}");
+
+        let (path, record) = match resource_type {
+            ResourceType::Command => {
+                let c = db.get_installed_command(id)?.ok_or_else(not_found)?;
+                let scope = InstallScope::from_db(&c.scope, c.project_path.as_deref());
+                let path = CommandService::resolve_installed_path(id, &scope)?;
+                let record = ResourceRecord {
+                    name: c.name,
+                    description: c.description,
+                    repo_owner: c.repo_owner,
+                    repo_name: c.repo_name,
+                    repo_branch: c.repo_branch,
+                    source_path: c.source_path,
+                };
+                (path, record)
+            }
+            ResourceType::Agent => {
+                let a = db.get_installed_agent(id)?.ok_or_else(not_found)?;
+                let scope = InstallScope::from_db(&a.scope, a.project_path.as_deref());
+                let path = AgentService::resolve_installed_path(id, &scope)?;
+                let record = ResourceRecord {
+                    name: a.name,
+                    description: a.description,
+                    repo_owner: a.repo_owner,
+                    repo_name: a.repo_name,
+                    repo_branch: a.repo_branch,
+                    source_path: a.source_path,
+                };
+                (path, record)
+            }
+            ResourceType::Hook => {
+                let h = db.get_installed_hook(id)?.ok_or_else(not_found)?;
+                let scope = InstallScope::from_db(&h.scope, h.project_path.as_deref());
+                let path = HookService::resolve_installed_path(id, &scope)?;
+                let record = ResourceRecord {
+                    name: h.name,
+                    description: h.description,
+                    repo_owner: h.repo_owner,
+                    repo_name: h.repo_name,
+                    repo_branch: h.repo_branch,
+                    source_path: h.source_path,
+                };
+                (path, record)
+            }
+            ResourceType::Skill => {
+                return Err(anyhow!("{resource_type} 不支持单独导出"));
+            }
+        };
+
+        let content =
+            fs::read_to_string(&path).map_err(|e| anyhow!("读取 {} 失败: {e}", path.display()))?;
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| anyhow!("无效的资源路径: {}", path.display()))?;
+
+        let metadata = ExportManifest {
+            resource_type,
+            id: id.to_string(),
+            name: record.name,
+            description: record.description,
+            repo_owner: record.repo_owner,
+            repo_name: record.repo_name,
+            repo_branch: record.repo_branch,
+            source_path: record.source_path,
+            content_hash: CommandService::compute_hash(&content),
+            exported_at: chrono::Utc::now().timestamp(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+        };
+
+        let mut exported = ExportedResource {
+            filename,
+            content,
+            metadata,
+            written_path: None,
+        };
+
+        if let Some(target) = target {
+            let written = exported.write_to(target)?;
+            log::info!("已导出 {resource_type} {id} 到 {}", written.display());
+            exported.written_path = Some(written.to_string_lossy().to_string());
+        }
+
+        Ok(exported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{create_test_agent, TempHome};
+    use serial_test::serial;
+
+    fn sample() -> ExportedResource {
+        ExportedResource {
+            filename: "commit.md".to_string(),
+            content: "---\nname: commit\n---\n# Commit\n".to_string(),
+            metadata: ExportManifest {
+                resource_type: ResourceType::Command,
+                id: "git/commit".to_string(),
+                name: "commit".to_string(),
+                description: None,
+                repo_owner: None,
+                repo_name: None,
+                repo_branch: None,
+                source_path: None,
+                content_hash: String::new(),
+                exported_at: 0,
+                app_version: "0.0.0".to_string(),
+            },
+            written_path: None,
+        }
+    }
+
+    #[test]
+    fn write_to_directory_uses_filename_and_writes_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let exported = sample();
+
+        let path = exported.write_to(dir.path()).unwrap();
+        assert_eq!(path, dir.path().join("commit.md"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), exported.content);
+
+        let manifest = fs::read_to_string(dir.path().join("commit.md.manifest.json")).unwrap();
+        let manifest: ExportManifest = serde_json::from_str(&manifest).unwrap();
+        assert_eq!(manifest, exported.metadata);
+    }
+
+    #[test]
+    fn write_to_file_path_creates_parent_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("share").join("my-commit.md");
+
+        let path = sample().write_to(&target).unwrap();
+        assert_eq!(path, target);
+        assert!(target.exists());
+        assert!(dir.path().join("share/my-commit.md.manifest.json").exists());
+    }
+
+    #[test]
+    #[serial]
+    fn project_scoped_agent_exports_project_copy() {
+        let home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let project = home.path().join("project");
+        let mut agent = create_test_agent("dev/reviewer", "dev", "reviewer");
+        agent.scope = "project".to_string();
+        agent.project_path = Some(project.to_string_lossy().to_string());
+        db.save_agent(&agent).unwrap();
+
+        let ssot = AgentService::get_ssot_dir()
+            .unwrap()
+            .join("dev/reviewer.md");
+        fs::create_dir_all(ssot.parent().unwrap()).unwrap();
+        fs::write(&ssot, "ssot").unwrap();
+        let project_copy = project.join(".claude/agents/dev/reviewer.md");
+        fs::create_dir_all(project_copy.parent().unwrap()).unwrap();
+        fs::write(&project_copy, "project").unwrap();
+
+        let exported =
+            ResourceExportService::export_resource(&db, ResourceType::Agent, "dev/reviewer", None)
+                .unwrap();
+        assert_eq!(exported.content, "project");
+        assert_eq!(exported.filename, "reviewer.md");
+    }
+}
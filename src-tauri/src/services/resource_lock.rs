@@ -0,0 +1,119 @@
+//! 资源锁定
+//!
+//! 关键资源可以锁定，避免被批量操作误改：批量更新、批量卸载（按 ID、按仓库或清理未启用资源）
+//! 以及合并重复资源的目标中包含锁定资源时直接报错，除非调用方显式传入 force；
+//! 「为应用禁用全部」与隔离无效 Hook 会跳过锁定资源。单个资源的操作不受影响。
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::services::config::ConfigTarget;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// 资源类型对应的数据库表
+fn table(resource_type: ConfigTarget) -> &'static str {
+    match resource_type {
+        ConfigTarget::Command => "commands",
+        ConfigTarget::Agent => "agents",
+        ConfigTarget::Hook => "hooks",
+        ConfigTarget::Skill => "skills",
+        ConfigTarget::Mcp => "mcp_servers",
+    }
+}
+
+pub struct ResourceLockService;
+
+impl ResourceLockService {
+    /// 锁定或解锁资源
+    pub fn set_locked(
+        db: &Arc<Database>,
+        resource_type: ConfigTarget,
+        id: &str,
+        locked: bool,
+    ) -> Result<(), AppError> {
+        if !db.set_resource_locked(table(resource_type), id, locked)? {
+            return Err(AppError::InvalidInput(format!(
+                "{resource_type} 不存在: {id}"
+            )));
+        }
+        log::info!(
+            "已{} {resource_type} {id}",
+            if locked { "锁定" } else { "解锁" }
+        );
+        Ok(())
+    }
+
+    /// 该类型下所有已锁定资源的 ID
+    pub fn locked_ids(
+        db: &Arc<Database>,
+        resource_type: ConfigTarget,
+    ) -> Result<HashSet<String>, AppError> {
+        db.get_locked_resource_ids(table(resource_type))
+    }
+
+    /// 批量操作前检查目标中是否有锁定资源；`force` 为 true 时跳过检查
+    pub fn ensure_unlocked<'a>(
+        db: &Arc<Database>,
+        resource_type: ConfigTarget,
+        ids: impl IntoIterator<Item = &'a str>,
+        force: bool,
+    ) -> Result<(), AppError> {
+        if force {
+            return Ok(());
+        }
+
+        let locked_ids = Self::locked_ids(db, resource_type)?;
+        let mut locked: Vec<&str> = ids
+            .into_iter()
+            .filter(|id| locked_ids.contains(*id))
+            .collect();
+        if locked.is_empty() {
+            return Ok(());
+        }
+        locked.sort_unstable();
+        locked.dedup();
+
+        let list = locked.join(", ");
+        Err(AppError::localized(
+            "resource.locked",
+            format!("以下 {resource_type} 已锁定，批量操作已取消：{list}。请先解锁，或强制执行。"),
+            format!(
+                "The following {resource_type} items are locked, so the bulk operation was cancelled: {list}. Unlock them or force the operation."
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::{McpApps, McpServer};
+
+    #[test]
+    fn locked_resources_block_bulk_ops_and_survive_saves() {
+        let db = Arc::new(Database::memory().unwrap());
+        let server = McpServer {
+            id: "fetch".to_string(),
+            name: "fetch".to_string(),
+            server: serde_json::json!({ "command": "uvx" }),
+            apps: McpApps::default(),
+            description: None,
+            homepage: None,
+            docs: None,
+            tags: Vec::new(),
+        };
+        db.save_mcp_server(&server).unwrap();
+
+        ResourceLockService::set_locked(&db, ConfigTarget::Mcp, "fetch", true).unwrap();
+        // 覆盖写入不会清除锁定状态
+        db.save_mcp_server(&server).unwrap();
+
+        let err = ResourceLockService::ensure_unlocked(&db, ConfigTarget::Mcp, ["fetch"], false)
+            .unwrap_err();
+        assert!(err.to_string().contains("fetch"));
+        assert!(
+            ResourceLockService::ensure_unlocked(&db, ConfigTarget::Mcp, ["fetch"], true).is_ok()
+        );
+        assert!(ResourceLockService::set_locked(&db, ConfigTarget::Mcp, "missing", true).is_err());
+    }
+}
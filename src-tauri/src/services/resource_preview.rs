@@ -0,0 +1,180 @@
+//! 资源预览渲染
+//!
+//! 在后端把 SSOT 文件正文（frontmatter 之后）渲染为 HTML，前端列表无需各自携带
+//! Markdown 渲染器。仓库内容来自第三方，输出前统一经 ammonia 清理，
+//! 去除脚本、事件属性与 `javascript:` 链接。
+
+use crate::app_config::InstallScope;
+use crate::database::Database;
+use crate::services::agent::AgentService;
+use crate::services::command::CommandService;
+use crate::services::hook::HookService;
+use crate::services::skill::SkillService;
+use crate::services::update::ResourceType;
+use anyhow::{anyhow, Result};
+use pulldown_cmark::{html, Options, Parser};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub struct ResourcePreviewService;
+
+impl ResourcePreviewService {
+    /// 资源实际生效文件的路径，供编辑使用
+    ///
+    /// 按记录的安装范围解析：项目级安装优先取项目目录中的副本，缺失时回退到 SSOT
+    pub fn resource_path(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+        id: &str,
+    ) -> Result<PathBuf> {
+        let not_found = || anyhow!("{resource_type} 不存在: {id}");
+        let path = match resource_type {
+            ResourceType::Command => {
+                let command = db.get_installed_command(id)?.ok_or_else(not_found)?;
+                let scope = InstallScope::from_db(&command.scope, command.project_path.as_deref());
+                CommandService::resolve_installed_path(id, &scope)?
+            }
+            ResourceType::Agent => {
+                let agent = db.get_installed_agent(id)?.ok_or_else(not_found)?;
+                let scope = InstallScope::from_db(&agent.scope, agent.project_path.as_deref());
+                AgentService::resolve_installed_path(id, &scope)?
+            }
+            ResourceType::Hook => {
+                let hook = db.get_installed_hook(id)?.ok_or_else(not_found)?;
+                let scope = InstallScope::from_db(&hook.scope, hook.project_path.as_deref());
+                HookService::resolve_installed_path(id, &scope)?
+            }
+            ResourceType::Skill => {
+                let skill = db.get_installed_skill(id)?.ok_or_else(not_found)?;
+                let scope = InstallScope::from_db(&skill.scope, skill.project_path.as_deref());
+                let project_copy = match scope {
+                    InstallScope::Project(project_path) => Some(
+                        SkillService::get_project_skills_dir(&project_path)?
+                            .join(&skill.directory)
+                            .join("SKILL.md"),
+                    ),
+                    InstallScope::Global => None,
+                };
+                match project_copy.filter(|path| path.exists()) {
+                    Some(path) => path,
+                    None => SkillService::get_ssot_dir()?
+                        .join(&skill.directory)
+                        .join("SKILL.md"),
+                }
+            }
+        };
+
+        if !path.exists() {
+            return Err(anyhow!("{resource_type} 不存在: {id}"));
+        }
+        Ok(path)
+    }
+
+    /// 将资源正文渲染为已清理的 HTML
+    ///
+    /// Markdown 资源跳过 frontmatter 后渲染；Hook 为 JSON，转义后放入代码块
+    pub fn render(db: &Arc<Database>, resource_type: ResourceType, id: &str) -> Result<String> {
+        let path = Self::resource_path(db, resource_type, id)?;
+        let content = fs::read_to_string(&path).map_err(|e| anyhow!("读取文件失败: {e}"))?;
+
+        Ok(match resource_type {
+            ResourceType::Hook => render_code_block(&content),
+            _ => render_markdown(strip_frontmatter(&content)),
+        })
+    }
+}
+
+/// 去掉开头的 YAML frontmatter，没有 frontmatter 时原样返回
+pub(crate) fn strip_frontmatter(content: &str) -> &str {
+    let content = content.trim_start_matches('\u{feff}');
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return content;
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        if line.trim_end() == "---" {
+            return &rest[offset..];
+        }
+    }
+    // frontmatter 未闭合，按普通正文处理
+    content
+}
+
+/// 渲染 Markdown 并清理生成的 HTML
+fn render_markdown(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, Parser::new_ext(markdown, options));
+    ammonia::clean(&unsafe_html)
+}
+
+fn render_code_block(content: &str) -> String {
+    format!("<pre><code>{}</code></pre>", ammonia::clean_text(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{create_test_command, TempHome};
+    use serial_test::serial;
+
+    #[test]
+    fn renders_body_below_frontmatter_without_scripts() {
+        let content = "---\nname: review\ndescription: 审查代码\n---\n# Review\n\n\
+                       <script>alert(1)</script>\n\n\
+                       <img src=\"x.png\" onerror=\"alert(1)\">\n\n\
+                       [link](javascript:alert(1)) and **bold**\n";
+
+        let html = render_markdown(strip_frontmatter(content));
+
+        assert!(html.contains("<h1>Review</h1>"));
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(!html.contains("name: review"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("javascript:"));
+    }
+
+    #[test]
+    fn unterminated_frontmatter_is_kept_as_body() {
+        let content = "---\nname: review\n# Review\n";
+        assert_eq!(strip_frontmatter(content), content);
+    }
+
+    #[test]
+    #[serial]
+    fn project_scoped_command_renders_project_copy() {
+        let home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let project = home.path().join("project");
+        let mut command = create_test_command("review", "", "review");
+        command.scope = "project".to_string();
+        command.project_path = Some(project.to_string_lossy().to_string());
+        db.save_command(&command).unwrap();
+
+        let ssot = CommandService::get_ssot_dir().unwrap().join("review.md");
+        fs::write(&ssot, "# SSOT\n").unwrap();
+        let project_copy = project.join(".claude/commands/review.md");
+        fs::create_dir_all(project_copy.parent().unwrap()).unwrap();
+        fs::write(&project_copy, "# Project\n").unwrap();
+
+        let path = ResourcePreviewService::resource_path(&db, ResourceType::Command, "review");
+        assert_eq!(path.unwrap(), project_copy);
+        let html = ResourcePreviewService::render(&db, ResourceType::Command, "review").unwrap();
+        assert!(html.contains("<h1>Project</h1>"));
+
+        // 项目副本缺失时回退到 SSOT
+        fs::remove_file(&project_copy).unwrap();
+        let html = ResourcePreviewService::render(&db, ResourceType::Command, "review").unwrap();
+        assert!(html.contains("<h1>SSOT</h1>"));
+    }
+}
@@ -0,0 +1,396 @@
+//! 可断点续传的仓库 ZIP 下载
+//!
+//! 网络较慢时，大仓库的 ZIP 可能在超时前只下载了一部分。开启「断点续传」设置后，
+//! 已下载的部分保存在应用配置目录的 `downloads/` 下，下次下载同一 URL 时带 `Range` 与
+//! `If-Range` 头续传；服务器不支持 Range、没有返回强 ETag 或内容已变化时从头下载。
+
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
+use reqwest::header::{ACCEPT_ENCODING, ETAG, IF_RANGE, RANGE};
+use reqwest::{Client, StatusCode};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// 服务器返回非成功状态码
+///
+/// 调用方可通过 `downcast_ref` 取出状态码，给出针对性的提示
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpStatusError(pub u16);
+
+impl std::fmt::Display for HttpStatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "下载失败: HTTP {}", self.0)
+    }
+}
+
+impl std::error::Error for HttpStatusError {}
+
+/// ZIP 本地文件头魔数
+const ZIP_MAGIC: &[u8; 4] = b"PK\x03\x04";
+
+/// 下载仓库 ZIP，按设置决定是否断点续传
+///
+/// 响应体不是 ZIP（如 HTML 错误页、被截断的内容）时返回明确的错误
+pub async fn download_repo_zip(
+    client: &Client,
+    url: &str,
+    timeout: Duration,
+    max_bytes: u64,
+) -> Result<Vec<u8>> {
+    let bytes = if crate::settings::resumable_repo_downloads_enabled() {
+        let partial = partial_path(url);
+        // 同一 URL 的下载依次进行，避免并发写同一个临时文件
+        let lock = partial_lock(&partial);
+        let _guard = lock.lock().await;
+        download(client, url, timeout, max_bytes, Some(&partial)).await
+    } else {
+        download(client, url, timeout, max_bytes, None).await
+    }?;
+    ensure_zip_magic(&bytes)?;
+    Ok(bytes)
+}
+
+/// 校验响应体以 ZIP 魔数开头
+fn ensure_zip_magic(bytes: &[u8]) -> Result<()> {
+    if !bytes.starts_with(ZIP_MAGIC) {
+        return Err(anyhow!("下载内容不是有效的 ZIP 文件，请检查仓库地址和分支"));
+    }
+    Ok(())
+}
+
+/// 下载 URL 的完整内容
+///
+/// `partial` 为未完成下载的临时文件；为 None 时不读写临时文件。
+/// 读取响应体中途失败时保留临时文件，供下次续传
+async fn download(
+    client: &Client,
+    url: &str,
+    timeout: Duration,
+    max_bytes: u64,
+    partial: Option<&Path>,
+) -> Result<Vec<u8>> {
+    let mut existing = partial.and_then(read_partial);
+
+    let response = loop {
+        let mut request = client.get(url).timeout(timeout);
+        if let Some((bytes, etag)) = &existing {
+            request = request
+                .header(RANGE, format!("bytes={}-", bytes.len()))
+                .header(IF_RANGE, etag.as_str())
+                // Range 按传输的字节计算，续传时不能让服务器压缩
+                .header(ACCEPT_ENCODING, "identity");
+        }
+        let response = request.send().await?;
+
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE && existing.is_some() {
+            // 临时文件与服务器内容对不上，丢弃后从头下载
+            discard(partial);
+            existing = None;
+            continue;
+        }
+        break response;
+    };
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(HttpStatusError(status.as_u16()).into());
+    }
+
+    let response_etag = response
+        .headers()
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .filter(|etag| is_strong_etag(etag))
+        .map(str::to_string);
+    let (mut bytes, etag) = match existing {
+        Some((bytes, etag)) if status == StatusCode::PARTIAL_CONTENT => {
+            log::info!("从第 {} 字节续传 {url}", bytes.len());
+            (bytes, Some(response_etag.unwrap_or(etag)))
+        }
+        // 服务器忽略了 Range 或内容已变化，从头下载
+        _ => (Vec::new(), response_etag),
+    };
+
+    if let Some(len) = response.content_length() {
+        if bytes.len() as u64 + len > max_bytes {
+            discard(partial);
+            return Err(too_large_error(max_bytes));
+        }
+    }
+
+    // 只有带强 ETag 的响应才能在下次安全续传
+    let mut file = match (partial, etag) {
+        (Some(partial), Some(etag)) => Some(open_partial(partial, &etag, !bytes.is_empty())?),
+        (Some(partial), None) => {
+            remove_partial(partial);
+            None
+        }
+        (None, _) => None,
+    };
+
+    // 分块读取，防止未声明 Content-Length 的超大响应耗尽内存
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        if bytes.len() as u64 + chunk.len() as u64 > max_bytes {
+            discard(partial);
+            return Err(too_large_error(max_bytes));
+        }
+        if let Some(file) = file.as_mut() {
+            file.write_all(&chunk)?;
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    discard(partial);
+    Ok(bytes)
+}
+
+/// 未完成下载的临时文件路径（按 URL 区分）
+///
+/// 放在应用配置目录而不是系统临时目录：临时目录可能被系统定期清理，也可能被其他用户读写
+fn partial_path(url: &str) -> PathBuf {
+    let digest = format!("{:x}", Sha256::digest(url.as_bytes()));
+    crate::config::get_app_config_dir()
+        .join("downloads")
+        .join(format!("{}.zip.part", &digest[..16]))
+}
+
+/// 临时文件对应的互斥锁
+fn partial_lock(partial: &Path) -> Arc<tokio::sync::Mutex<()>> {
+    static LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
+    let mut locks = LOCKS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    locks.entry(partial.to_path_buf()).or_default().clone()
+}
+
+/// 弱 ETag（`W/` 前缀）只表示语义等价，不能用于 `If-Range`（RFC 9110 §13.1.5）
+fn is_strong_etag(etag: &str) -> bool {
+    !etag.trim_start().starts_with("W/")
+}
+
+fn etag_path(partial: &Path) -> PathBuf {
+    partial.with_extension("etag")
+}
+
+/// 读取已下载的部分及其 ETag，任一缺失时返回 None
+fn read_partial(partial: &Path) -> Option<(Vec<u8>, String)> {
+    let etag = fs::read_to_string(etag_path(partial)).ok()?;
+    let bytes = fs::read(partial).ok()?;
+    (!bytes.is_empty() && !etag.is_empty() && is_strong_etag(&etag)).then_some((bytes, etag))
+}
+
+/// 打开临时文件：续传时追加，否则清空并记录新的 ETag
+fn open_partial(partial: &Path, etag: &str, append: bool) -> Result<fs::File> {
+    if let Some(parent) = partial.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(etag_path(partial), etag)?;
+    let file = if append {
+        fs::OpenOptions::new().append(true).open(partial)?
+    } else {
+        fs::File::create(partial)?
+    };
+    Ok(file)
+}
+
+fn discard(partial: Option<&Path>) {
+    if let Some(partial) = partial {
+        remove_partial(partial);
+    }
+}
+
+fn remove_partial(partial: &Path) {
+    let _ = fs::remove_file(partial);
+    let _ = fs::remove_file(etag_path(partial));
+}
+
+fn too_large_error(max_bytes: u64) -> anyhow::Error {
+    anyhow!("下载内容超过大小上限（{} MB）", max_bytes / 1024 / 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// 模拟支持 Range 的服务器：收到 `Range: bytes=N-` 且 If-Range 匹配时返回 206
+    async fn serve_once(listener: &tokio::net::TcpListener, body: &[u8], etag: &str) -> String {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = vec![0u8; 4096];
+        let n = socket.read(&mut request).await.unwrap();
+        let request = String::from_utf8_lossy(&request[..n]).to_ascii_lowercase();
+
+        let range_start = request
+            .lines()
+            .find_map(|l| l.strip_prefix("range: bytes="))
+            .and_then(|r| r.trim_end_matches('-').parse::<usize>().ok());
+        let if_range_ok = request.contains(&format!("if-range: {}", etag.to_ascii_lowercase()));
+
+        let response = match range_start {
+            Some(start) if if_range_ok => format!(
+                "HTTP/1.1 206 Partial Content\r\nETag: {etag}\r\n\
+                 Content-Range: bytes {start}-{}/{}\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n",
+                body.len() - 1,
+                body.len(),
+                body.len() - start
+            ),
+            _ => format!(
+                "HTTP/1.1 200 OK\r\nETag: {etag}\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n",
+                body.len()
+            ),
+        };
+        let start = match range_start {
+            Some(start) if if_range_ok => start,
+            _ => 0,
+        };
+        socket.write_all(response.as_bytes()).await.unwrap();
+        socket.write_all(&body[start..]).await.unwrap();
+        request
+    }
+
+    #[tokio::test]
+    async fn resumes_from_partial_file_when_server_supports_range() {
+        let body: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+        let temp = tempfile::tempdir().unwrap();
+        let partial = temp.path().join("repo.zip.part");
+        fs::write(&partial, &body[..1000]).unwrap();
+        fs::write(etag_path(&partial), "\"v1\"").unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/repo.zip", listener.local_addr().unwrap());
+        let expected = body.clone();
+        let server = tokio::spawn(async move { serve_once(&listener, &expected, "\"v1\"").await });
+
+        let client = Client::new();
+        let bytes = download(
+            &client,
+            &url,
+            Duration::from_secs(10),
+            u64::MAX,
+            Some(&partial),
+        )
+        .await
+        .unwrap();
+
+        let request = server.await.unwrap();
+        assert!(request.contains("range: bytes=1000-"));
+        assert_eq!(bytes, body);
+        assert!(!partial.exists(), "完成后应清除临时文件");
+    }
+
+    #[tokio::test]
+    async fn weak_etag_is_not_used_for_if_range() {
+        let body = b"PK\x03\x04full content".to_vec();
+        let temp = tempfile::tempdir().unwrap();
+        let partial = temp.path().join("repo.zip.part");
+        fs::write(&partial, b"PK\x03\x04").unwrap();
+        fs::write(etag_path(&partial), "W/\"v1\"").unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/repo.zip", listener.local_addr().unwrap());
+        let expected = body.clone();
+        let server =
+            tokio::spawn(async move { serve_once(&listener, &expected, "W/\"v1\"").await });
+
+        let client = Client::new();
+        let bytes = download(
+            &client,
+            &url,
+            Duration::from_secs(10),
+            u64::MAX,
+            Some(&partial),
+        )
+        .await
+        .unwrap();
+
+        let request = server.await.unwrap();
+        assert!(!request.contains("range:"), "弱 ETag 不应触发续传");
+        assert_eq!(bytes, body);
+    }
+
+    #[test]
+    fn partial_files_live_under_app_config_dir() {
+        let partial = partial_path("https://github.com/o/r/archive/refs/heads/main.zip");
+        assert!(partial.starts_with(crate::config::get_app_config_dir().join("downloads")));
+        assert_eq!(
+            partial,
+            partial_path("https://github.com/o/r/archive/refs/heads/main.zip")
+        );
+    }
+
+    #[test]
+    fn same_partial_file_shares_one_lock() {
+        let a = partial_lock(Path::new("/tmp/a.zip.part"));
+        let b = partial_lock(Path::new("/tmp/a.zip.part"));
+        let c = partial_lock(Path::new("/tmp/c.zip.part"));
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+    }
+
+    #[test]
+    fn ensure_zip_magic_accepts_zip_header() {
+        assert!(ensure_zip_magic(b"PK\x03\x04rest").is_ok());
+    }
+
+    #[test]
+    fn ensure_zip_magic_rejects_non_zip_body() {
+        assert!(ensure_zip_magic(b"<!DOCTYPE html>").is_err());
+        assert!(ensure_zip_magic(b"").is_err());
+    }
+
+    #[tokio::test]
+    async fn repo_zip_download_rejects_non_zip_body() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/repo.zip", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            serve_once(&listener, b"<!DOCTYPE html><p>Not Found</p>", "\"v1\"").await
+        });
+
+        let client = Client::new();
+        let err = download_repo_zip(&client, &url, Duration::from_secs(10), u64::MAX)
+            .await
+            .unwrap_err();
+
+        server.await.unwrap();
+        assert!(err.to_string().contains("ZIP"));
+    }
+
+    #[tokio::test]
+    async fn restarts_when_content_changed() {
+        let body = b"PK\x03\x04new content".to_vec();
+        let temp = tempfile::tempdir().unwrap();
+        let partial = temp.path().join("repo.zip.part");
+        fs::write(&partial, b"PK\x03\x04old").unwrap();
+        fs::write(etag_path(&partial), "\"v1\"").unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/repo.zip", listener.local_addr().unwrap());
+        let expected = body.clone();
+        // ETag 已变化，If-Range 不匹配，服务器返回完整内容
+        let server = tokio::spawn(async move { serve_once(&listener, &expected, "\"v2\"").await });
+
+        let client = Client::new();
+        let bytes = download(
+            &client,
+            &url,
+            Duration::from_secs(10),
+            u64::MAX,
+            Some(&partial),
+        )
+        .await
+        .unwrap();
+
+        server.await.unwrap();
+        assert_eq!(bytes, body);
+    }
+}
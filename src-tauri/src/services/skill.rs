@@ -20,7 +20,13 @@ use crate::app_config::{AppType, InstallScope, InstalledSkill, SkillApps, Unmana
 use crate::config::get_app_config_dir;
 use crate::database::Database;
 use crate::error::format_skill_error;
+use crate::services::command::{is_same_repo, CommandService};
+use crate::services::config::{ConfigService, ConfigTarget, MultiAppInstallResult};
 use crate::services::github_api::GitHubApiService;
+use crate::services::repo_metadata::RepoMetadata;
+use crate::services::resource_lock::ResourceLockService;
+use crate::services::resumable_download::{self, HttpStatusError};
+use crate::services::update::ResourceType;
 
 // ========== 数据结构 ==========
 
@@ -131,6 +137,9 @@ pub struct SkillRepo {
     /// 添加时间戳（内置仓库为 0）
     #[serde(default)]
     pub added_at: i64,
+    /// 仅浏览：可在发现列表中阅读，但不能安装新 Skill
+    #[serde(default)]
+    pub browse_only: bool,
 }
 
 /// 技能安装状态（旧版兼容）
@@ -185,6 +194,29 @@ pub struct SkillUpdateInfo {
     pub remote_hash: String,
 }
 
+/// Skill 本地变更类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillChangeKind {
+    /// SSOT 目录内容与安装时记录的 file_hash 不一致
+    Modified,
+    /// 数据库有记录但 SSOT 目录已丢失
+    Missing,
+}
+
+/// Skill 本地变更
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillLocalChange {
+    pub id: String,
+    pub name: String,
+    pub kind: SkillChangeKind,
+    /// 本地目录当前哈希（目录丢失时为空）
+    pub local_hash: Option<String>,
+    /// 数据库中记录的 file_hash
+    pub recorded_hash: String,
+}
+
 /// Skill 存储位置迁移结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -489,6 +521,7 @@ impl SkillService {
                 home.join(".agents").join("skills")
             }
         };
+        crate::config::validate_ssot_dir(&dir)?;
         fs::create_dir_all(&dir)?;
         Ok(dir)
     }
@@ -750,6 +783,8 @@ impl SkillService {
             }
         }
 
+        Self::ensure_repo_installable(db, &skill.repo_owner, &skill.repo_name)?;
+
         let dest = ssot_dir.join(&install_name);
 
         let mut repo_branch = skill.repo_branch.clone();
@@ -766,6 +801,7 @@ impl SkillService {
                 description_en: None,
                 description_ja: None,
                 added_at: 0,
+                browse_only: false,
             };
 
             // 下载仓库
@@ -907,6 +943,27 @@ impl SkillService {
         Ok(installed_skill)
     }
 
+    /// 一次安装并启用到多个应用
+    ///
+    /// SSOT 只写入一次，之后逐个启用其余应用；不支持 Skill 的应用被跳过，启用失败的应用记录在结果中
+    pub async fn install_for_apps(
+        &self,
+        db: &Arc<Database>,
+        skill: &DiscoverableSkill,
+        apps: Vec<AppType>,
+    ) -> Result<MultiAppInstallResult<InstalledSkill>> {
+        ConfigService::install_for_apps(
+            ResourceType::Skill,
+            apps,
+            |app| async move { self.install(db, skill, &app).await },
+            |installed: &InstalledSkill, app: &AppType| {
+                Self::toggle_app(db, &installed.id, app, true)
+            },
+            |installed: &InstalledSkill| db.get_installed_skill(&installed.id).ok().flatten(),
+        )
+        .await
+    }
+
     /// 带范围安装 Skill
     ///
     /// 流程：
@@ -946,6 +1003,7 @@ impl SkillService {
                 description_en: None,
                 description_ja: None,
                 added_at: 0,
+                browse_only: false,
             };
 
             // 下载仓库
@@ -1124,6 +1182,27 @@ impl SkillService {
         Ok(())
     }
 
+    /// 批量卸载 Skills，返回成功卸载的数量
+    ///
+    /// 目标中有锁定的 Skill 且未传 force 时整批取消；单个卸载失败只记录日志，不中断整批
+    pub fn uninstall_batch(db: &Arc<Database>, ids: &[String], force: bool) -> Result<usize> {
+        ResourceLockService::ensure_unlocked(
+            db,
+            ConfigTarget::Skill,
+            ids.iter().map(String::as_str),
+            force,
+        )?;
+
+        let mut success_count = 0;
+        for id in ids {
+            match Self::uninstall(db, id) {
+                Ok(_) => success_count += 1,
+                Err(e) => log::warn!("卸载 Skill {} 失败: {}", id, e),
+            }
+        }
+        Ok(success_count)
+    }
+
     /// 卸载 Skill
     ///
     /// 流程：
@@ -1212,7 +1291,7 @@ impl SkillService {
         for entry in entries {
             let entry = entry?;
             let name = entry.file_name().to_string_lossy().to_string();
-            if name.starts_with('.') {
+            if !GitHubApiService::is_hashed_path(&name) {
                 continue;
             }
             let path = entry.path();
@@ -1225,6 +1304,68 @@ impl SkillService {
         Ok(())
     }
 
+    /// 按 GitHub 侧 `get_directory_hash` 的算法计算本地目录哈希
+    ///
+    /// 逐文件计算 Git blob SHA，按相对路径（`/` 分隔）字典序拼接后取 SHA256，
+    /// 结果可直接与 `file_hash` 比较。隐藏文件按 `GitHubApiService::is_hashed_path`
+    /// 与远端同样跳过
+    pub fn compute_directory_hash(dir: &Path) -> Result<String> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        Self::collect_files_for_hash(dir, dir, &mut files)?;
+
+        let mut blobs = Vec::with_capacity(files.len());
+        for file_path in &files {
+            let relative = file_path.strip_prefix(dir).unwrap_or(file_path);
+            let rel_str = relative.to_string_lossy().replace('\\', "/");
+            let content = fs::read(file_path)
+                .with_context(|| format!("读取文件失败: {}", file_path.display()))?;
+            blobs.push((rel_str, GitHubApiService::compute_blob_sha(&content)));
+        }
+        // 与 GitHub tree 一致：按路径字符串排序，而不是按路径组件排序
+        blobs.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(GitHubApiService::combine_blob_shas(
+            blobs.iter().map(|(_, sha)| sha.as_str()),
+        ))
+    }
+
+    /// 检测已安装 Skill 的本地改动
+    ///
+    /// 仅检查记录了 file_hash 的 Skill：SSOT 目录丢失记为 Missing，
+    /// 目录哈希与 file_hash 不一致记为 Modified
+    pub fn detect_local_changes(db: &Arc<Database>) -> Result<Vec<SkillLocalChange>> {
+        let ssot_dir = Self::get_ssot_dir()?;
+        let mut changes = Vec::new();
+
+        for skill in db.get_all_installed_skills()?.into_values() {
+            let Some(recorded_hash) = skill.file_hash.clone() else {
+                continue;
+            };
+
+            let dir = ssot_dir.join(&skill.directory);
+            let (kind, local_hash) = if !dir.is_dir() {
+                (SkillChangeKind::Missing, None)
+            } else {
+                let hash = Self::compute_directory_hash(&dir)?;
+                if hash == recorded_hash {
+                    continue;
+                }
+                (SkillChangeKind::Modified, Some(hash))
+            };
+
+            changes.push(SkillLocalChange {
+                id: skill.id,
+                name: skill.name,
+                kind,
+                local_hash,
+                recorded_hash,
+            });
+        }
+
+        changes.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(changes)
+    }
+
     /// 检查所有已安装 Skill 的更新
     ///
     /// 仅检查有 repo_owner 的 Skill（本地 Skill 跳过），
@@ -1263,6 +1404,7 @@ impl SkillService {
                 description_en: None,
                 description_ja: None,
                 added_at: 0,
+                browse_only: false,
             };
 
             // 下载仓库 ZIP
@@ -1375,6 +1517,7 @@ impl SkillService {
             description_en: None,
             description_ja: None,
             added_at: 0,
+            browse_only: false,
         };
 
         let ssot_dir = Self::get_ssot_dir()?;
@@ -2165,8 +2308,11 @@ impl SkillService {
             .iter()
             .map(|repo| self.fetch_repo_skills(repo));
 
-        let results: Vec<Result<Vec<DiscoverableSkill>>> =
-            futures::future::join_all(fetch_tasks).await;
+        let results: Vec<Result<Vec<DiscoverableSkill>>> = CommandService::run_bounded(
+            fetch_tasks,
+            crate::settings::effective_discovery_concurrency(),
+        )
+        .await;
 
         for (repo, result) in enabled_repos.into_iter().zip(results) {
             match result {
@@ -2272,6 +2418,10 @@ impl SkillService {
         resolved_repo.branch = resolved_branch;
         self.scan_dir_recursive(&scan_dir, &scan_dir, &resolved_repo, &mut skills)?;
 
+        if let Some(metadata) = RepoMetadata::load(&temp_dir) {
+            metadata.apply_to_skills(&mut skills);
+        }
+
         let _ = fs::remove_dir_all(&temp_dir);
 
         Ok(skills)
@@ -2671,22 +2821,34 @@ impl SkillService {
     /// 下载并解压 ZIP
     async fn download_and_extract(&self, url: &str, dest: &Path) -> Result<()> {
         let client = crate::proxy::http_client::get();
-        let response = client.get(url).send().await?;
-        if !response.status().is_success() {
-            let status = response.status().as_u16().to_string();
-            return Err(anyhow::anyhow!(format_skill_error(
-                "DOWNLOAD_FAILED",
-                &[("status", &status)],
-                match status.as_str() {
-                    "403" => Some("http403"),
-                    "404" => Some("http404"),
-                    "429" => Some("http429"),
-                    _ => Some("checkNetwork"),
-                },
-            )));
-        }
+        let bytes = resumable_download::download_repo_zip(
+            &client,
+            url,
+            crate::settings::get_timeout_config().repo_download(),
+            crate::settings::effective_max_download_bytes(),
+        )
+        .await
+        .map_err(|e| match e.downcast_ref::<HttpStatusError>() {
+            Some(HttpStatusError(status)) => {
+                let status = status.to_string();
+                anyhow::anyhow!(format_skill_error(
+                    "DOWNLOAD_FAILED",
+                    &[("status", &status)],
+                    match status.as_str() {
+                        "403" => Some("http403"),
+                        "404" => Some("http404"),
+                        "429" => Some("http429"),
+                        _ => Some("checkNetwork"),
+                    },
+                ))
+            }
+            None => e,
+        })?;
+        Self::extract_repo_zip(&bytes, dest)
+    }
 
-        let bytes = response.bytes().await?;
+    /// 解压仓库 ZIP 到目标目录（去掉压缩包内的根目录前缀，跳过越界条目）
+    fn extract_repo_zip(bytes: &[u8], dest: &Path) -> Result<()> {
         let cursor = std::io::Cursor::new(bytes);
         let mut archive = zip::ZipArchive::new(cursor)?;
 
@@ -2702,6 +2864,8 @@ impl SkillService {
             )));
         };
 
+        fs::create_dir_all(dest)?;
+
         // 第一遍：解压普通文件和目录，收集 symlink 条目
         let mut symlinks: Vec<(PathBuf, String)> = Vec::new();
 
@@ -2720,7 +2884,10 @@ impl SkillService {
                 continue;
             }
 
-            let outpath = dest.join(relative_path);
+            let Some(outpath) = CommandService::safe_join(dest, relative_path) else {
+                log::warn!("跳过越界的压缩包条目: {}", file_path);
+                continue;
+            };
 
             if file.is_symlink() {
                 // 读取 symlink 目标路径
@@ -3137,7 +3304,10 @@ impl SkillService {
                 None => continue,
             };
 
-            let outpath = temp_path.join(&file_path);
+            let Some(outpath) = CommandService::safe_join(&temp_path, &file_path) else {
+                log::warn!("跳过越界的压缩包条目: {}", file_path.display());
+                continue;
+            };
 
             if file.is_symlink() {
                 let mut target = String::new();
@@ -3226,6 +3396,28 @@ impl SkillService {
         Ok(())
     }
 
+    /// 拒绝从仅浏览的仓库安装新 Skill
+    ///
+    /// 同一仓库已安装的同名 Skill 在此之前已经返回，这里只处理新安装
+    pub(crate) fn ensure_repo_installable(
+        db: &Arc<Database>,
+        owner: &str,
+        name: &str,
+    ) -> Result<()> {
+        let browse_only = db
+            .get_skill_repos()?
+            .into_iter()
+            .find(|r| r.browse_only && is_same_repo((&r.owner, &r.name), (owner, name)));
+        if let Some(repo) = browse_only {
+            return Err(anyhow!(
+                "仓库 {}/{} 仅供浏览，不能从中安装；如需安装请先取消仓库的仅浏览标记",
+                repo.owner,
+                repo.name
+            ));
+        }
+        Ok(())
+    }
+
     // ========== skills.sh 搜索 ==========
 
     /// 搜索 skills.sh 公共目录
@@ -3357,6 +3549,7 @@ fn save_repos_from_lock(
                     description_en: None,
                     description_ja: None,
                     added_at: chrono::Utc::now().timestamp(),
+                    browse_only: false,
                 };
                 if let Err(e) = db.save_skill_repo(&skill_repo) {
                     log::warn!("保存 skill 仓库 {}/{} 失败: {}", info.owner, info.repo, e);
@@ -3509,6 +3702,37 @@ mod tests {
         .expect("write SKILL.md");
     }
 
+    #[test]
+    fn extract_repo_zip_skips_entries_escaping_destination() {
+        use std::io::Write;
+
+        let mut buf = std::io::Cursor::new(Vec::new());
+        {
+            let mut writer = zip::ZipWriter::new(&mut buf);
+            let options = zip::write::SimpleFileOptions::default();
+            writer
+                .start_file("repo-main/skills/ok/SKILL.md", options)
+                .unwrap();
+            writer.write_all(b"---\nname: ok\n---\n").unwrap();
+            writer
+                .start_file("repo-main/../../.bashrc", options)
+                .unwrap();
+            writer.write_all(b"evil").unwrap();
+            writer.start_file("repo-main//etc/evil", options).unwrap();
+            writer.write_all(b"evil").unwrap();
+            writer.finish().unwrap();
+        }
+
+        let temp = tempdir().unwrap();
+        let dest = temp.path().join("a").join("b");
+        SkillService::extract_repo_zip(buf.get_ref(), &dest).unwrap();
+
+        assert!(dest.join("skills/ok/SKILL.md").exists());
+        assert!(!temp.path().join("a").join(".bashrc").exists());
+        assert!(!temp.path().join(".bashrc").exists());
+        assert!(!Path::new("/etc/evil").exists());
+    }
+
     #[test]
     fn test_compute_namespace() {
         let repo_owner = "cexll";
@@ -3590,4 +3814,60 @@ mod tests {
 
         assert_eq!(resolved, nested);
     }
+
+    #[test]
+    fn compute_directory_hash_matches_github_tree_order() {
+        let temp = tempdir().unwrap();
+        let dir = temp.path();
+        write_skill(dir, "demo");
+        fs::create_dir_all(dir.join("a")).unwrap();
+        fs::create_dir_all(dir.join("a-b")).unwrap();
+        fs::write(dir.join("a").join("x.txt"), "x").unwrap();
+        fs::write(dir.join("a-b").join("y.txt"), "y").unwrap();
+        fs::write(dir.join(".DS_Store"), "junk").unwrap();
+
+        // 按路径字符串字节序（'-' < '/'），而不是按路径组件排序；隐藏文件被跳过
+        let blob =
+            |path: &str| GitHubApiService::compute_blob_sha(&fs::read(dir.join(path)).unwrap());
+        let expected_shas = [blob("SKILL.md"), blob("a-b/y.txt"), blob("a/x.txt")];
+        let expected =
+            GitHubApiService::combine_blob_shas(expected_shas.iter().map(String::as_str));
+
+        let hash = SkillService::compute_directory_hash(dir).unwrap();
+        assert_eq!(hash, expected);
+
+        fs::write(dir.join("a").join("x.txt"), "edited").unwrap();
+        assert_ne!(SkillService::compute_directory_hash(dir).unwrap(), hash);
+    }
+
+    #[test]
+    fn browse_only_skill_repo_blocks_new_installs() {
+        let db = Arc::new(Database::memory().unwrap());
+        db.save_skill_repo(&SkillRepo {
+            owner: "Team".to_string(),
+            name: "skills".to_string(),
+            branch: "main".to_string(),
+            enabled: true,
+            builtin: false,
+            description_zh: None,
+            description_en: None,
+            description_ja: None,
+            added_at: 1,
+            browse_only: false,
+        })
+        .unwrap();
+        assert!(SkillService::ensure_repo_installable(&db, "team", "skills").is_ok());
+
+        assert!(db
+            .set_skill_repo_browse_only("Team", "skills", true)
+            .unwrap());
+        assert!(db
+            .get_skill_repos()
+            .unwrap()
+            .iter()
+            .any(|r| r.owner == "Team" && r.browse_only));
+        let err = SkillService::ensure_repo_installable(&db, "team", "skills").unwrap_err();
+        assert!(err.to_string().contains("Team/skills"));
+        assert!(SkillService::ensure_repo_installable(&db, "team", "other").is_ok());
+    }
 }
@@ -18,6 +18,18 @@ pub struct EndpointLatency {
     pub error: Option<String>,
 }
 
+/// 测速历史记录
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeedtestRecord {
+    pub url: String,
+    pub latency_ms: Option<i64>,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    /// 测速时间（Unix 秒）
+    pub tested_at: i64,
+}
+
 /// 网络测速相关业务
 pub struct SpeedtestService;
 
@@ -17,6 +17,7 @@ use crate::proxy::providers::transform::anthropic_to_openai;
 use crate::proxy::providers::transform_gemini::anthropic_to_gemini;
 use crate::proxy::providers::transform_responses::anthropic_to_responses;
 use crate::proxy::providers::{get_adapter, AuthInfo, AuthStrategy};
+use crate::services::provider::{compose_deployment_url, provider_with_deployment};
 
 /// 健康状态枚举
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -203,6 +204,10 @@ impl StreamCheckService {
     ) -> Result<StreamCheckResult, AppError> {
         let start = Instant::now();
 
+        // 部署名（Azure 等）与基础 URL 拼接后再测试，与写入 live 配置的端点一致
+        let provider = &provider_with_deployment(app_type, provider)?;
+        let deployment = provider.meta.as_ref().and_then(|m| m.deployment.as_deref());
+
         // OpenCode / OpenClaw 的 settings_config 结构与 Claude/Codex/Gemini 不同
         // （baseUrl / apiKey 直接作为根字段而非嵌套在 env），并且协议由 `api`
         // 或 `npm` 字段显式指定。它们不走 get_adapter 路径，而是直接分发。
@@ -216,7 +221,10 @@ impl StreamCheckService {
         let adapter = get_adapter(app_type);
 
         let base_url = match base_url_override {
-            Some(base_url) => base_url,
+            Some(base_url) => match deployment {
+                Some(deployment) => compose_deployment_url(&base_url, deployment),
+                None => base_url,
+            },
             None => adapter
                 .extract_base_url(provider)
                 .map_err(|e| AppError::Message(format!("Failed to extract base_url: {e}")))?,
@@ -232,6 +240,7 @@ impl StreamCheckService {
 
         let model_to_test = Self::resolve_test_model(app_type, provider, config);
         let test_prompt = &config.test_prompt;
+        let extra_headers = Self::merge_meta_extra_headers(None, provider);
 
         let result = match app_type {
             AppType::Claude => {
@@ -244,7 +253,7 @@ impl StreamCheckService {
                     request_timeout,
                     provider,
                     claude_api_format_override.as_deref(),
-                    None,
+                    extra_headers.as_ref(),
                 )
                 .await
             }
@@ -257,6 +266,7 @@ impl StreamCheckService {
                     test_prompt,
                     request_timeout,
                     provider,
+                    extra_headers.as_ref(),
                 )
                 .await
             }
@@ -268,7 +278,7 @@ impl StreamCheckService {
                     &model_to_test,
                     test_prompt,
                     request_timeout,
-                    None,
+                    extra_headers.as_ref(),
                 )
                 .await
             }
@@ -297,7 +307,8 @@ impl StreamCheckService {
     ///
     /// `extra_headers` 是一个可选的供应商级自定义 header 集合（从 OpenClaw
     /// 的 `settings_config.headers` 或 OpenCode 的 `settings_config.options.headers`
-    /// 读取），在所有内置 header 之后追加，用于覆盖或补充（例如自定义 User-Agent）。
+    /// 读取，并合并供应商的 `meta.extraHeaders`），在所有内置 header 之后追加，
+    /// 用于覆盖或补充（例如自定义 User-Agent）。
     #[allow(clippy::too_many_arguments)]
     async fn check_claude_stream(
         client: &Client,
@@ -516,6 +527,7 @@ impl StreamCheckService {
         test_prompt: &str,
         timeout: std::time::Duration,
         provider: &Provider,
+        extra_headers: Option<&serde_json::Map<String, serde_json::Value>>,
     ) -> Result<(u16, String), AppError> {
         let is_full_url = provider
             .meta
@@ -545,7 +557,7 @@ impl StreamCheckService {
 
         for (i, url) in urls.iter().enumerate() {
             // 严格按照 Codex CLI 请求格式设置 headers
-            let response = client
+            let mut request_builder = client
                 .post(url)
                 .header("authorization", format!("Bearer {}", auth.api_key))
                 .header("content-type", "application/json")
@@ -555,7 +567,18 @@ impl StreamCheckService {
                     "user-agent",
                     format!("codex_cli_rs/0.80.0 ({os_name} 15.7.2; {arch_name}) Terminal"),
                 )
-                .header("originator", "codex_cli_rs")
+                .header("originator", "codex_cli_rs");
+
+            // 供应商自定义 headers 最后追加
+            if let Some(headers) = extra_headers {
+                for (key, value) in headers {
+                    if let Some(v) = value.as_str() {
+                        request_builder = request_builder.header(key.as_str(), v);
+                    }
+                }
+            }
+
+            let response = request_builder
                 .timeout(timeout)
                 .json(&body)
                 .send()
@@ -840,7 +863,8 @@ impl StreamCheckService {
         let base_url = Self::extract_openclaw_base_url(provider)?;
         let api_key = Self::extract_openclaw_api_key(provider)?;
         let api = Self::extract_openclaw_protocol(provider);
-        let extra_headers = Self::extract_openclaw_headers(provider);
+        let extra_headers =
+            Self::merge_meta_extra_headers(Self::extract_openclaw_headers(provider), provider);
 
         match api.as_deref() {
             Some("openai-completions") => {
@@ -854,7 +878,7 @@ impl StreamCheckService {
                     timeout,
                     provider,
                     Some("openai_chat"),
-                    extra_headers,
+                    extra_headers.as_ref(),
                 )
                 .await
             }
@@ -869,7 +893,7 @@ impl StreamCheckService {
                     timeout,
                     provider,
                     Some("openai_responses"),
-                    extra_headers,
+                    extra_headers.as_ref(),
                 )
                 .await
             }
@@ -887,7 +911,7 @@ impl StreamCheckService {
                     timeout,
                     provider,
                     Some("anthropic"),
-                    extra_headers,
+                    extra_headers.as_ref(),
                 )
                 .await
             }
@@ -900,7 +924,7 @@ impl StreamCheckService {
                     model,
                     test_prompt,
                     timeout,
-                    extra_headers,
+                    extra_headers.as_ref(),
                 )
                 .await
             }
@@ -932,6 +956,22 @@ impl StreamCheckService {
     }
 
     /// 提取 OpenClaw 供应商的自定义 headers（来自 `settings_config.headers`）
+    /// 合并配置中的 headers 与供应商附加请求头（`meta.extraHeaders`），
+    /// 同名时附加请求头优先；两者都为空时返回 None
+    fn merge_meta_extra_headers(
+        base: Option<&serde_json::Map<String, serde_json::Value>>,
+        provider: &Provider,
+    ) -> Option<serde_json::Map<String, serde_json::Value>> {
+        let mut merged = base.cloned().unwrap_or_default();
+        if let Some(meta) = provider.meta.as_ref() {
+            for (name, value) in &meta.extra_headers {
+                merged.retain(|k, _| !k.eq_ignore_ascii_case(name));
+                merged.insert(name.clone(), serde_json::Value::String(value.clone()));
+            }
+        }
+        (!merged.is_empty()).then_some(merged)
+    }
+
     fn extract_openclaw_headers(
         provider: &Provider,
     ) -> Option<&serde_json::Map<String, serde_json::Value>> {
@@ -1075,6 +1115,7 @@ impl StreamCheckService {
         let base_url = Self::extract_hermes_base_url(provider)?;
         let api_key = Self::extract_hermes_api_key(provider)?;
         let auth = AuthInfo::new(api_key, auth_strategy);
+        let extra_headers = Self::merge_meta_extra_headers(None, provider);
         Self::check_claude_stream(
             client,
             &base_url,
@@ -1084,7 +1125,7 @@ impl StreamCheckService {
             timeout,
             provider,
             Some(api_format),
-            None,
+            extra_headers.as_ref(),
         )
         .await
     }
@@ -1112,7 +1153,8 @@ impl StreamCheckService {
         // 若用户未显式填 baseURL，则根据 npm 回退到 AI SDK 包自带的默认端点
         let base_url = Self::resolve_opencode_base_url(provider, npm.as_deref())?;
         let api_key = Self::extract_opencode_api_key(provider)?;
-        let extra_headers = Self::extract_opencode_headers(provider);
+        let extra_headers =
+            Self::merge_meta_extra_headers(Self::extract_opencode_headers(provider), provider);
 
         match npm.as_deref() {
             Some("@ai-sdk/openai-compatible") => {
@@ -1126,7 +1168,7 @@ impl StreamCheckService {
                     timeout,
                     provider,
                     Some("openai_chat"),
-                    extra_headers,
+                    extra_headers.as_ref(),
                 )
                 .await
             }
@@ -1141,7 +1183,7 @@ impl StreamCheckService {
                     timeout,
                     provider,
                     Some("openai_responses"),
-                    extra_headers,
+                    extra_headers.as_ref(),
                 )
                 .await
             }
@@ -1158,7 +1200,7 @@ impl StreamCheckService {
                     timeout,
                     provider,
                     Some("anthropic"),
-                    extra_headers,
+                    extra_headers.as_ref(),
                 )
                 .await
             }
@@ -1171,7 +1213,7 @@ impl StreamCheckService {
                     model,
                     test_prompt,
                     timeout,
-                    extra_headers,
+                    extra_headers.as_ref(),
                 )
                 .await
             }
@@ -1638,6 +1680,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_merge_meta_extra_headers_overrides_case_insensitively() {
+        let mut p = make_provider(serde_json::json!({
+            "baseUrl": "https://openrouter.ai/api/v1",
+            "apiKey": "k",
+            "api": "openai-completions",
+            "headers": { "x-title": "Old", "X-Trace": "abc" },
+        }));
+        assert!(StreamCheckService::merge_meta_extra_headers(None, &p).is_none());
+
+        p.meta = Some(crate::provider::ProviderMeta {
+            extra_headers: std::collections::HashMap::from([(
+                "X-Title".to_string(),
+                "CC Switch".to_string(),
+            )]),
+            ..Default::default()
+        });
+        let merged = StreamCheckService::merge_meta_extra_headers(
+            StreamCheckService::extract_openclaw_headers(&p),
+            &p,
+        )
+        .unwrap();
+        assert_eq!(merged.len(), 2);
+        assert_eq!(
+            merged.get("X-Title").and_then(|v| v.as_str()),
+            Some("CC Switch")
+        );
+        assert_eq!(merged.get("X-Trace").and_then(|v| v.as_str()), Some("abc"));
+    }
+
     #[test]
     fn test_determine_status() {
         assert_eq!(
@@ -0,0 +1,486 @@
+//! 回收站服务
+//!
+//! 卸载 Commands/Agents/Hooks 时不直接删除 SSOT 文件，而是移动到
+//! `~/.cc-switch/.trash/<timestamp>_<type>_<id>/`，并在 `meta.json` 中归档数据库记录，
+//! 以便误删后恢复。超过保留期的条目在启动时自动清理。
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::app_config::{AppType, InstallScope, InstalledAgent, InstalledCommand, InstalledHook};
+use crate::config::get_app_config_dir;
+use crate::database::Database;
+use crate::services::agent::AgentService;
+use crate::services::command::CommandService;
+use crate::services::hook::HookService;
+
+/// 回收站条目默认保留天数
+pub const TRASH_RETENTION_DAYS: i64 = 30;
+
+/// 回收站条目中的元数据文件名
+const TRASH_META_FILE: &str = "meta.json";
+
+/// 被移入回收站的资源（含归档的数据库记录）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "resourceType", content = "record", rename_all = "camelCase")]
+pub enum TrashedResource {
+    Command(InstalledCommand),
+    Agent(InstalledAgent),
+    Hook(InstalledHook),
+}
+
+impl TrashedResource {
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Command(c) => &c.id,
+            Self::Agent(a) => &a.id,
+            Self::Hook(h) => &h.id,
+        }
+    }
+
+    fn type_label(&self) -> &'static str {
+        match self {
+            Self::Command(_) => "command",
+            Self::Agent(_) => "agent",
+            Self::Hook(_) => "hook",
+        }
+    }
+
+    /// 归档记录中的安装范围
+    fn scope(&self) -> InstallScope {
+        let (scope, project_path) = match self {
+            Self::Command(c) => (&c.scope, &c.project_path),
+            Self::Agent(a) => (&a.scope, &a.project_path),
+            Self::Hook(h) => (&h.scope, &h.project_path),
+        };
+        InstallScope::from_db(scope, project_path.as_deref())
+    }
+
+    /// 按安装范围解析资源实际生效的文件（项目级安装优先取项目副本）
+    fn installed_path(&self) -> Result<PathBuf> {
+        let scope = self.scope();
+        match self {
+            Self::Command(c) => CommandService::resolve_installed_path(&c.id, &scope),
+            Self::Agent(a) => AgentService::resolve_installed_path(&a.id, &scope),
+            Self::Hook(h) => HookService::resolve_installed_path(&h.id, &scope),
+        }
+    }
+
+    /// 删除项目级安装在项目目录中的副本
+    fn remove_project_copy(&self, project_path: &Path) -> Result<()> {
+        match self {
+            Self::Command(c) => CommandService::remove_from_project(&c.id, project_path),
+            Self::Agent(a) => AgentService::remove_from_project(&a.id, project_path),
+            Self::Hook(h) => HookService::remove_from_project(&h.id, project_path),
+        }
+    }
+
+    /// 按归档记录重新部署：项目级安装复制回原项目目录，全局安装同步到原先启用的应用
+    fn redeploy(&self, db: &Arc<Database>) -> Result<()> {
+        let apps = [AppType::Claude, AppType::Codex, AppType::Gemini];
+        match (self, self.scope()) {
+            (Self::Command(c), InstallScope::Project(path)) => {
+                CommandService::copy_to_project(&c.id, &path)
+            }
+            (Self::Agent(a), InstallScope::Project(path)) => {
+                AgentService::copy_to_project(&a.id, &path)
+            }
+            (Self::Hook(h), InstallScope::Project(path)) => {
+                HookService::copy_to_project(&h.id, &path)
+            }
+            (Self::Command(command), InstallScope::Global) => apps
+                .iter()
+                .filter(|app| command.apps.is_enabled_for(app))
+                .try_for_each(|app| CommandService::copy_to_app(&command.id, app).map(|_| ())),
+            (Self::Agent(agent), InstallScope::Global) => apps
+                .iter()
+                .filter(|app| agent.apps.is_enabled_for(app.as_str()))
+                .try_for_each(|app| AgentService::copy_to_app(&agent.id, app).map(|_| ())),
+            (Self::Hook(_), InstallScope::Global) => HookService::sync_all_to_apps(db).map(|_| ()),
+        }
+    }
+
+    /// 资源在 SSOT 中的路径
+    fn ssot_path(&self) -> Result<PathBuf> {
+        Ok(match self {
+            Self::Command(c) => {
+                CommandService::get_ssot_dir()?.join(CommandService::id_to_relative_path(&c.id))
+            }
+            Self::Agent(a) => {
+                AgentService::get_ssot_dir()?.join(AgentService::id_to_relative_path(&a.id))
+            }
+            Self::Hook(h) => {
+                HookService::get_ssot_dir()?.join(HookService::id_to_relative_path(&h.id))
+            }
+        })
+    }
+}
+
+/// 回收站条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    pub record_id: String,
+    pub trash_path: String,
+    pub deleted_at: i64,
+    pub resource: TrashedResource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrashMetadata {
+    resource: TrashedResource,
+    deleted_at: i64,
+    file_name: String,
+}
+
+pub struct TrashService;
+
+impl TrashService {
+    /// 获取回收站目录（~/.cc-switch/.trash/）
+    fn get_trash_dir() -> Result<PathBuf> {
+        let dir = get_app_config_dir().join(".trash");
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    fn sanitize_segment(segment: &str) -> String {
+        let sanitized = segment
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+                _ => '-',
+            })
+            .collect::<String>()
+            .trim_matches('-')
+            .to_string();
+
+        if sanitized.is_empty() {
+            "item".to_string()
+        } else {
+            sanitized
+        }
+    }
+
+    fn entry_path_for_id(record_id: &str) -> Result<PathBuf> {
+        if record_id.contains("..")
+            || record_id.contains('/')
+            || record_id.contains('\\')
+            || record_id.trim().is_empty()
+        {
+            return Err(anyhow!("Invalid trash record id: {record_id}"));
+        }
+
+        Ok(Self::get_trash_dir()?.join(record_id))
+    }
+
+    fn read_metadata(entry_path: &Path) -> Result<TrashMetadata> {
+        let metadata_path = entry_path.join(TRASH_META_FILE);
+        let content = fs::read_to_string(&metadata_path)
+            .with_context(|| format!("failed to read {}", metadata_path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("failed to parse {}", metadata_path.display()))
+    }
+
+    /// 移动文件，跨文件系统时回退为复制后删除
+    fn move_file(from: &Path, to: &Path) -> Result<()> {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if fs::rename(from, to).is_err() {
+            fs::copy(from, to)?;
+            fs::remove_file(from)?;
+        }
+        Ok(())
+    }
+
+    /// 将资源的 SSOT 文件移入回收站并归档其数据库记录
+    ///
+    /// SSOT 文件缺失时改为归档项目级安装的副本；两者都不存在时跳过
+    /// （无法恢复的条目没有保留意义），返回 None。项目目录中的副本随之删除
+    pub fn move_to_trash(resource: TrashedResource) -> Result<Option<PathBuf>> {
+        let scope = resource.scope();
+        let mut source = resource.ssot_path()?;
+        if !source.exists() {
+            source = resource.installed_path()?;
+        }
+        if !source.exists() {
+            log::warn!(
+                "{} {} 的 SSOT 文件不存在，跳过回收站",
+                resource.type_label(),
+                resource.id()
+            );
+            return Ok(None);
+        }
+
+        let trash_root = Self::get_trash_dir()?;
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        let slug = Self::sanitize_segment(resource.id());
+        let label = resource.type_label();
+        let mut entry_path = trash_root.join(format!("{timestamp}_{label}_{slug}"));
+        let mut counter = 1;
+        while entry_path.exists() {
+            entry_path = trash_root.join(format!("{timestamp}_{label}_{slug}_{counter}"));
+            counter += 1;
+        }
+
+        let file_name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "content".to_string());
+
+        fs::create_dir_all(&entry_path)?;
+        let metadata = TrashMetadata {
+            resource,
+            deleted_at: Utc::now().timestamp(),
+            file_name: file_name.clone(),
+        };
+        let write_entry = || -> Result<()> {
+            let metadata_json = serde_json::to_string_pretty(&metadata)
+                .context("failed to serialize trash metadata")?;
+            fs::write(entry_path.join(TRASH_META_FILE), metadata_json)?;
+            Self::move_file(&source, &entry_path.join(&file_name))
+        };
+
+        if let Err(err) = write_entry() {
+            let _ = fs::remove_dir_all(&entry_path);
+            return Err(err);
+        }
+
+        if let InstallScope::Project(project_path) = &scope {
+            if let Err(err) = metadata.resource.remove_project_copy(project_path) {
+                log::warn!("删除项目副本失败 {}: {err:#}", project_path.display());
+            }
+        }
+
+        log::info!(
+            "{} {} 已移入回收站: {}",
+            metadata.resource.type_label(),
+            metadata.resource.id(),
+            entry_path.display()
+        );
+
+        Ok(Some(entry_path))
+    }
+
+    /// 列出回收站条目（按删除时间倒序）
+    pub fn list() -> Result<Vec<TrashEntry>> {
+        let trash_dir = Self::get_trash_dir()?;
+        let mut entries = Vec::new();
+
+        for entry in fs::read_dir(&trash_dir)? {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    log::warn!("读取回收站目录项失败: {err}");
+                    continue;
+                }
+            };
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+
+            match Self::read_metadata(&path) {
+                Ok(metadata) => entries.push(TrashEntry {
+                    record_id: entry.file_name().to_string_lossy().to_string(),
+                    trash_path: path.to_string_lossy().to_string(),
+                    deleted_at: metadata.deleted_at,
+                    resource: metadata.resource,
+                }),
+                Err(err) => {
+                    log::warn!("解析回收站条目失败 {}: {err:#}", path.display());
+                }
+            }
+        }
+
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+        Ok(entries)
+    }
+
+    /// 从回收站恢复资源
+    ///
+    /// 文件移回 SSOT，数据库记录按归档内容重建，并重新同步到原先启用的应用
+    pub fn restore(db: &Arc<Database>, record_id: &str) -> Result<TrashedResource> {
+        let entry_path = Self::entry_path_for_id(record_id)?;
+        let metadata = Self::read_metadata(&entry_path)?;
+        let resource = metadata.resource;
+        let id = resource.id().to_string();
+
+        let already_installed = match &resource {
+            TrashedResource::Command(_) => db.get_installed_command(&id)?.is_some(),
+            TrashedResource::Agent(_) => db.get_installed_agent(&id)?.is_some(),
+            TrashedResource::Hook(_) => db.get_installed_hook(&id)?.is_some(),
+        };
+        if already_installed {
+            return Err(anyhow!(
+                "{} 已存在，请先卸载当前版本: {}",
+                resource.type_label(),
+                id
+            ));
+        }
+
+        let target = resource.ssot_path()?;
+        if target.exists() {
+            return Err(anyhow!("恢复目标已存在: {}", target.display()));
+        }
+
+        let trashed_file = entry_path.join(&metadata.file_name);
+        Self::move_file(&trashed_file, &target)?;
+
+        let save_result = match &resource {
+            TrashedResource::Command(command) => db.save_command(command),
+            TrashedResource::Agent(agent) => db.save_agent(agent),
+            TrashedResource::Hook(hook) => db.save_hook(hook),
+        };
+        if let Err(err) = save_result {
+            let _ = Self::move_file(&target, &trashed_file);
+            return Err(err.into());
+        }
+
+        // 同步失败不回滚：记录已恢复，用户可在列表中重新切换应用开关
+        if let Err(err) = resource.redeploy(db) {
+            log::warn!("恢复 {} 后同步到应用失败: {err:#}", id);
+        }
+
+        let _ = fs::remove_dir_all(&entry_path);
+        log::info!("{} {} 已从回收站恢复", resource.type_label(), id);
+
+        Ok(resource)
+    }
+
+    /// 永久删除单个回收站条目
+    pub fn delete(record_id: &str) -> Result<()> {
+        let entry_path = Self::entry_path_for_id(record_id)?;
+        if !entry_path.is_dir() {
+            return Err(anyhow!("回收站条目不存在: {record_id}"));
+        }
+        fs::remove_dir_all(&entry_path)
+            .with_context(|| format!("failed to delete {}", entry_path.display()))?;
+        Ok(())
+    }
+
+    /// 清空回收站，返回删除的条目数
+    pub fn empty() -> Result<usize> {
+        let trash_dir = Self::get_trash_dir()?;
+        let mut removed = 0;
+        for entry in fs::read_dir(&trash_dir)?.flatten() {
+            let path = entry.path();
+            if path.is_dir() && fs::remove_dir_all(&path).is_ok() {
+                removed += 1;
+            }
+        }
+        log::info!("回收站已清空，删除 {} 个条目", removed);
+        Ok(removed)
+    }
+
+    /// 清理超过保留天数的回收站条目，返回删除的条目数
+    pub fn purge_expired(retention_days: i64) -> Result<usize> {
+        let cutoff = Utc::now().timestamp() - retention_days * 24 * 60 * 60;
+        let mut removed = 0;
+        for entry in Self::list()? {
+            if entry.deleted_at < cutoff && fs::remove_dir_all(&entry.trash_path).is_ok() {
+                removed += 1;
+            }
+        }
+        if removed > 0 {
+            log::info!("已清理 {} 个过期回收站条目", removed);
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{create_test_command, TempHome};
+    use serial_test::serial;
+
+    /// 写入 SSOT 文件并保存记录，返回 SSOT 路径
+    fn install_command(db: &Arc<Database>, command: &InstalledCommand) -> PathBuf {
+        let ssot = CommandService::get_ssot_dir()
+            .unwrap()
+            .join(CommandService::id_to_relative_path(&command.id));
+        fs::create_dir_all(ssot.parent().unwrap()).unwrap();
+        fs::write(&ssot, "# review").unwrap();
+        db.save_command(command).unwrap();
+        ssot
+    }
+
+    #[test]
+    fn sanitize_segment_replaces_path_separators() {
+        assert_eq!(TrashService::sanitize_segment("sc/agent"), "sc-agent");
+        assert_eq!(TrashService::sanitize_segment("//"), "item");
+    }
+
+    #[test]
+    fn entry_path_rejects_traversal_ids() {
+        assert!(TrashService::entry_path_for_id("../etc").is_err());
+        assert!(TrashService::entry_path_for_id("a/b").is_err());
+        assert!(TrashService::entry_path_for_id("  ").is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn uninstall_then_restore_round_trips_global_command() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let command = create_test_command("sc/review", "sc", "review");
+        let ssot = install_command(&db, &command);
+        CommandService::copy_to_app(&command.id, &AppType::Claude).unwrap();
+        let app_copy = CommandService::get_app_commands_dir(&AppType::Claude)
+            .unwrap()
+            .join("sc/review.md");
+
+        CommandService::uninstall(&db, &command.id).unwrap();
+        assert!(!ssot.exists());
+        assert!(!app_copy.exists());
+        assert!(db.get_installed_command(&command.id).unwrap().is_none());
+
+        let entries = TrashService::list().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].resource.id(), "sc/review");
+
+        TrashService::restore(&db, &entries[0].record_id).unwrap();
+        assert_eq!(fs::read_to_string(&ssot).unwrap(), "# review");
+        assert!(app_copy.exists());
+        assert!(db.get_installed_command(&command.id).unwrap().is_some());
+        assert!(TrashService::list().unwrap().is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn uninstall_then_restore_keeps_project_scope() {
+        let home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let project = home.path().join("project");
+        let mut command = create_test_command("sc/review", "sc", "review");
+        command.scope = "project".to_string();
+        command.project_path = Some(project.to_string_lossy().to_string());
+        let ssot = install_command(&db, &command);
+        CommandService::copy_to_project(&command.id, &project).unwrap();
+        let project_copy = project.join(".claude/commands/sc/review.md");
+
+        CommandService::uninstall(&db, &command.id).unwrap();
+        assert!(!ssot.exists());
+        assert!(!project_copy.exists());
+
+        let entries = TrashService::list().unwrap();
+        TrashService::restore(&db, &entries[0].record_id).unwrap();
+
+        assert!(ssot.exists());
+        assert_eq!(fs::read_to_string(&project_copy).unwrap(), "# review");
+        // 项目级安装不应被部署到全局应用目录
+        let app_copy = CommandService::get_app_commands_dir(&AppType::Claude)
+            .unwrap()
+            .join("sc/review.md");
+        assert!(!app_copy.exists());
+        let restored = db.get_installed_command(&command.id).unwrap().unwrap();
+        assert_eq!(restored.scope, "project");
+        assert_eq!(restored.project_path, command.project_path);
+    }
+}
@@ -6,12 +6,19 @@
 //! - 批量更新
 //! - 并发控制（最多 5 个并发请求）
 
-use crate::app_config::InstalledSkill;
+use crate::app_config::{
+    AppType, DiscoverableAgent, DiscoverableCommand, DiscoverableHook, InstallScope, InstalledSkill,
+};
 use crate::database::Database;
 use crate::error::AppError;
+use crate::services::agent::AgentService;
+use crate::services::command::CommandService;
 use crate::services::github_api::{GitHubApiError, GitHubApiService, UpdateCheckResult};
+use crate::services::hook::HookService;
+use crate::services::resource_preview::strip_frontmatter;
 use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Semaphore;
 
@@ -19,7 +26,7 @@ use tokio::sync::Semaphore;
 const MAX_CONCURRENT_REQUESTS: usize = 5;
 
 /// 资源类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ResourceType {
     Skill,
@@ -39,6 +46,35 @@ impl std::fmt::Display for ResourceType {
     }
 }
 
+/// 文件资源（Commands/Hooks/Agents）的更新检测方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateCheckMode {
+    /// 优先通过 Contents API 获取 blob SHA，API 出错（限流、网络等）时回退到内容比对
+    #[default]
+    ShaPreferred,
+    /// 始终下载原始文件比对内容，不消耗 API 配额
+    ContentAlways,
+}
+
+/// 与指定 tag 比对的更新检测结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagUpdateCheckResult {
+    #[serde(flatten)]
+    pub result: UpdateCheckResult,
+    /// 实际比对的 tag
+    pub tag: String,
+}
+
+/// 单个文件的哈希比对结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileHashOutcome {
+    Unchanged,
+    /// 有更新，携带新的 blob SHA
+    Changed(String),
+}
+
 /// 更新检测进度
 #[derive(Debug, Clone, Serialize)]
 pub struct UpdateCheckProgress {
@@ -90,6 +126,47 @@ pub struct BatchUpdateResult {
     pub results: Vec<UpdateExecuteResult>,
 }
 
+/// 本地哈希规范化结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HashNormalizeResult {
+    /// 已替换为 blob SHA 的数量
+    pub fixed_count: u32,
+    /// 未能规范化的项（格式 "类型 id: 原因"）
+    pub skipped: Vec<String>,
+}
+
+/// 判断是否为 Git blob SHA（40 位十六进制）
+fn is_blob_sha(hash: &str) -> bool {
+    hash.len() == 40 && hash.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// 判断是否为安装时回退计算的本地内容哈希
+///
+/// 本地哈希为 64 位十六进制 SHA-256，Git blob SHA 为 40 位
+pub fn is_local_content_hash(hash: &str) -> bool {
+    hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// SSOT 内容与记录的本地哈希一致时，返回该内容的 Git blob SHA
+///
+/// 内容未变化说明仍是安装时的版本，本地计算的 blob SHA 与 GitHub 返回的一致
+fn blob_sha_for_local_hash(local_hash: &str, content: &str) -> Option<String> {
+    (CommandService::compute_hash(content) == local_hash)
+        .then(|| GitHubApiService::compute_blob_sha(content.as_bytes()))
+}
+
+/// Markdown 资源的正文指纹
+///
+/// 去掉 YAML frontmatter、统一换行并忽略行尾与首尾空白后计算 SHA-256。
+/// 用于正文比对模式：frontmatter 字段重排或空白调整不会再提示更新，
+/// 代价是 frontmatter 中的实际变化（如 allowed-tools、model）也会被忽略
+pub fn body_fingerprint(content: &str) -> String {
+    let body = strip_frontmatter(content).replace("\r\n", "\n");
+    let lines: Vec<&str> = body.lines().map(str::trim_end).collect();
+    CommandService::compute_hash(lines.join("\n").trim())
+}
+
 /// 更新服务
 pub struct UpdateService {
     github_api: Arc<GitHubApiService>,
@@ -183,7 +260,7 @@ impl UpdateService {
                 UpdateCheckResult {
                     id: skill.id.clone(),
                     has_update,
-                    new_hash: if has_update { Some(new_hash) } else { None },
+                    new_hash,
                     commit_message,
                     updated_at,
                     error: None,
@@ -334,8 +411,41 @@ impl UpdateService {
     // ========== 通用更新检测（用于 Commands/Hooks/Agents） ==========
 
     /// 检查单个文件资源的更新（适用于 Commands/Hooks/Agents）
+    ///
+    /// 开启正文比对（`update_compare_body_only`）时，Commands/Agents 的文件哈希有变化后
+    /// 还会比较本地 SSOT 与上游的正文指纹，仅 frontmatter 变化视为无更新，
+    /// 并把上游哈希记为新的基线，之后的检测不必再下载原始文件
+    #[allow(clippy::too_many_arguments)]
     pub async fn check_file_resource_update(
         &self,
+        db: &Database,
+        resource_type: ResourceType,
+        id: &str,
+        repo_owner: Option<&str>,
+        repo_name: Option<&str>,
+        repo_branch: Option<&str>,
+        source_path: Option<&str>,
+        current_hash: Option<&str>,
+    ) -> UpdateCheckResult {
+        self.compare_file_resource(
+            Some(db),
+            resource_type,
+            id,
+            repo_owner,
+            repo_name,
+            repo_branch,
+            source_path,
+            current_hash,
+        )
+        .await
+    }
+
+    /// 比较单个文件资源与上游，`baseline_db` 为 None 时不更新基线哈希（只读检测）
+    #[allow(clippy::too_many_arguments)]
+    async fn compare_file_resource(
+        &self,
+        baseline_db: Option<&Database>,
+        resource_type: ResourceType,
         id: &str,
         repo_owner: Option<&str>,
         repo_name: Option<&str>,
@@ -361,15 +471,34 @@ impl UpdateService {
         let branch = repo_branch.unwrap_or("main");
         let path = source_path.unwrap();
 
-        // 获取文件的 blob SHA
-        let hash_result = self
-            .github_api
-            .get_file_blob_sha(owner, repo, branch, path)
-            .await;
+        let hash_result = compare_file_hash(
+            crate::settings::get_update_check_mode(),
+            current_hash,
+            self.github_api.get_file_blob_sha(owner, repo, branch, path),
+            self.github_api.download_raw_file(owner, repo, branch, path),
+        )
+        .await;
 
         match hash_result {
-            Ok((new_hash, _size)) => {
-                let has_update = current_hash != Some(&new_hash);
+            Ok(outcome) => {
+                let new_hash = match outcome {
+                    FileHashOutcome::Changed(hash)
+                        if matches!(resource_type, ResourceType::Command | ResourceType::Agent)
+                            && crate::settings::get_update_compare_body_only()
+                            && self
+                                .body_unchanged(resource_type, id, owner, repo, branch, path)
+                                .await =>
+                    {
+                        log::debug!("{resource_type} {id} 仅 frontmatter 有变化，视为无更新");
+                        if let Some(db) = baseline_db {
+                            Self::save_baseline_hash(db, resource_type, id, &hash);
+                        }
+                        None
+                    }
+                    FileHashOutcome::Changed(hash) => Some(hash),
+                    FileHashOutcome::Unchanged => None,
+                };
+                let has_update = new_hash.is_some();
 
                 let (commit_message, updated_at) = if has_update {
                     self.github_api
@@ -385,7 +514,7 @@ impl UpdateService {
                 UpdateCheckResult {
                     id: id.to_string(),
                     has_update,
-                    new_hash: if has_update { Some(new_hash) } else { None },
+                    new_hash,
                     commit_message,
                     updated_at,
                     error: None,
@@ -412,11 +541,597 @@ impl UpdateService {
             },
         }
     }
+
+    /// 记录新的上游哈希作为更新检测基线（仅 Commands/Agents 使用正文比对），失败时只记录日志
+    fn save_baseline_hash(db: &Database, resource_type: ResourceType, id: &str, hash: &str) {
+        let result = match resource_type {
+            ResourceType::Command => db.update_command_hash(id, hash),
+            ResourceType::Agent => db.update_agent_hash(id, hash),
+            _ => return,
+        };
+        if let Err(e) = result {
+            log::warn!("更新 {resource_type} {id} 的哈希基线失败: {e}");
+        }
+    }
+
+    /// 本地 SSOT 与上游文件的正文指纹一致时返回 true
+    ///
+    /// 读取或下载失败时返回 false，按有更新处理
+    async fn body_unchanged(
+        &self,
+        resource_type: ResourceType,
+        id: &str,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        path: &str,
+    ) -> bool {
+        let local_path = match resource_type {
+            ResourceType::Command => CommandService::get_ssot_dir()
+                .map(|dir| dir.join(CommandService::id_to_relative_path(id))),
+            ResourceType::Agent => AgentService::get_ssot_dir()
+                .map(|dir| dir.join(AgentService::id_to_relative_path(id))),
+            _ => return false,
+        };
+        let Some(local) = local_path
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+        else {
+            return false;
+        };
+        match self
+            .github_api
+            .download_raw_file(owner, repo, branch, path)
+            .await
+        {
+            Ok(remote) => {
+                body_fingerprint(&local) == body_fingerprint(&String::from_utf8_lossy(&remote))
+            }
+            Err(e) => {
+                log::debug!("下载 {resource_type} {id} 上游内容失败，跳过正文比对: {e}");
+                false
+            }
+        }
+    }
+
+    // ========== 指定 Tag 的更新检测 ==========
+
+    /// 与上游指定 tag 上的文件比对，而非分支 HEAD
+    ///
+    /// 适合跟随仓库发布版本的用户；比对方式与分支检测一致，只是把 ref 换成 tag。
+    /// 只读检测：不会把 tag 上的哈希写入资源的基线
+    pub async fn check_update_against_tag(
+        &self,
+        db: &Database,
+        resource_type: ResourceType,
+        id: &str,
+        tag: &str,
+    ) -> Result<TagUpdateCheckResult, AppError> {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return Err(AppError::InvalidInput("tag 不能为空".to_string()));
+        }
+
+        let result = match resource_type {
+            ResourceType::Skill => {
+                let mut skill = db
+                    .get_installed_skill(id)?
+                    .ok_or_else(|| AppError::Message(format!("Skill 不存在: {id}")))?;
+                skill.repo_branch = Some(tag.to_string());
+                self.check_skill_update(&skill).await
+            }
+            ResourceType::Command => {
+                let command = db
+                    .get_installed_command(id)?
+                    .ok_or_else(|| AppError::Message(format!("Command 不存在: {id}")))?;
+                self.compare_file_resource(
+                    None,
+                    resource_type,
+                    &command.id,
+                    command.repo_owner.as_deref(),
+                    command.repo_name.as_deref(),
+                    Some(tag),
+                    command.source_path.as_deref(),
+                    command.file_hash.as_deref(),
+                )
+                .await
+            }
+            ResourceType::Agent => {
+                let agent = db
+                    .get_installed_agent(id)?
+                    .ok_or_else(|| AppError::Message(format!("Agent 不存在: {id}")))?;
+                self.compare_file_resource(
+                    None,
+                    resource_type,
+                    &agent.id,
+                    agent.repo_owner.as_deref(),
+                    agent.repo_name.as_deref(),
+                    Some(tag),
+                    agent.source_path.as_deref(),
+                    agent.file_hash.as_deref(),
+                )
+                .await
+            }
+            ResourceType::Hook => {
+                let hook = db
+                    .get_installed_hook(id)?
+                    .ok_or_else(|| AppError::Message(format!("Hook 不存在: {id}")))?;
+                self.compare_file_resource(
+                    None,
+                    resource_type,
+                    &hook.id,
+                    hook.repo_owner.as_deref(),
+                    hook.repo_name.as_deref(),
+                    Some(tag),
+                    hook.source_path.as_deref(),
+                    hook.file_hash.as_deref(),
+                )
+                .await
+            }
+        };
+
+        Ok(TagUpdateCheckResult {
+            result,
+            tag: tag.to_string(),
+        })
+    }
+
+    // ========== 上游差异对比 ==========
+
+    /// 生成本地内容到上游内容的 unified diff，内容一致时返回 None
+    pub fn unified_diff(local: &str, upstream: &str, id: &str) -> Option<String> {
+        if local == upstream {
+            return None;
+        }
+
+        let diff = similar::TextDiff::from_lines(local, upstream)
+            .unified_diff()
+            .context_radius(3)
+            .header(&format!("local/{id}"), &format!("upstream/{id}"))
+            .to_string();
+        Some(diff)
+    }
+
+    /// 获取已安装资源与仓库当前版本的差异
+    ///
+    /// 按 source_path 拉取远程内容，与本地 SSOT 文件生成 unified diff，内容一致时返回 None。
+    /// 不依赖 file_hash，可排查哈希算法不一致导致的“已是最新”误判。
+    pub async fn upstream_diff(
+        db: &Database,
+        resource_type: ResourceType,
+        id: &str,
+    ) -> Result<Option<String>, AppError> {
+        let not_remote =
+            || AppError::Message(format!("本地导入的 {resource_type} 不支持差异对比: {id}"));
+
+        let (local_path, upstream) = match resource_type {
+            ResourceType::Command => {
+                let installed = db
+                    .get_installed_command(id)?
+                    .ok_or_else(|| AppError::Message(format!("Command 不存在: {id}")))?;
+                let discoverable = DiscoverableCommand {
+                    key: installed.id.clone(),
+                    name: installed.name.clone(),
+                    description: installed.description.clone().unwrap_or_default(),
+                    namespace: installed.namespace.clone(),
+                    filename: installed.filename.clone(),
+                    category: installed.category.clone(),
+                    categories: installed.categories.clone(),
+                    readme_url: installed.readme_url.clone(),
+                    repo_owner: installed.repo_owner.clone().ok_or_else(not_remote)?,
+                    repo_name: installed.repo_name.clone().unwrap_or_default(),
+                    repo_branch: installed
+                        .repo_branch
+                        .clone()
+                        .unwrap_or_else(|| "main".to_string()),
+                    source_path: installed.source_path.clone(),
+                    localized: Default::default(),
+                };
+                let upstream = CommandService::new()
+                    .download_command_content(&discoverable)
+                    .await
+                    .map_err(|e| AppError::Message(e.to_string()))?;
+                let ssot_dir =
+                    CommandService::get_ssot_dir().map_err(|e| AppError::Message(e.to_string()))?;
+                (
+                    ssot_dir.join(CommandService::id_to_relative_path(id)),
+                    upstream,
+                )
+            }
+            ResourceType::Agent => {
+                let installed = db
+                    .get_installed_agent(id)?
+                    .ok_or_else(|| AppError::Message(format!("Agent 不存在: {id}")))?;
+                let discoverable = DiscoverableAgent {
+                    key: installed.id.clone(),
+                    name: installed.name.clone(),
+                    description: installed.description.clone().unwrap_or_default(),
+                    namespace: installed.namespace.clone(),
+                    filename: installed.filename.clone(),
+                    model: installed.model.clone(),
+                    tools: installed.tools.clone(),
+                    readme_url: installed.readme_url.clone(),
+                    repo_owner: installed.repo_owner.clone().ok_or_else(not_remote)?,
+                    repo_name: installed.repo_name.clone().unwrap_or_default(),
+                    repo_branch: installed
+                        .repo_branch
+                        .clone()
+                        .unwrap_or_else(|| "main".to_string()),
+                    source_path: installed.source_path.clone(),
+                    localized: Default::default(),
+                };
+                let upstream = AgentService::new()
+                    .download_agent_content(&discoverable)
+                    .await
+                    .map_err(|e| AppError::Message(e.to_string()))?;
+                let ssot_dir =
+                    AgentService::get_ssot_dir().map_err(|e| AppError::Message(e.to_string()))?;
+                (
+                    ssot_dir.join(AgentService::id_to_relative_path(id)),
+                    upstream,
+                )
+            }
+            ResourceType::Hook => {
+                let installed = db
+                    .get_installed_hook(id)?
+                    .ok_or_else(|| AppError::Message(format!("Hook 不存在: {id}")))?;
+                let discoverable = DiscoverableHook {
+                    key: installed.id.clone(),
+                    name: installed.name.clone(),
+                    description: installed.description.clone(),
+                    namespace: installed.namespace.clone(),
+                    filename: installed.filename.clone(),
+                    event_type: installed.event_type.clone(),
+                    rules: installed.rules.clone(),
+                    priority: installed.priority,
+                    readme_url: installed.readme_url.clone(),
+                    repo_owner: installed.repo_owner.clone().ok_or_else(not_remote)?,
+                    repo_name: installed.repo_name.clone().unwrap_or_default(),
+                    repo_branch: installed
+                        .repo_branch
+                        .clone()
+                        .unwrap_or_else(|| "main".to_string()),
+                    source_path: installed.source_path.clone(),
+                    localized: Default::default(),
+                };
+                let upstream = HookService::new()
+                    .download_hook_content(&discoverable)
+                    .await
+                    .map_err(|e| AppError::Message(e.to_string()))?;
+                let ssot_dir =
+                    HookService::get_ssot_dir().map_err(|e| AppError::Message(e.to_string()))?;
+                (
+                    ssot_dir.join(HookService::id_to_relative_path(id)),
+                    upstream,
+                )
+            }
+            ResourceType::Skill => {
+                return Err(AppError::Message(
+                    "Skill 为目录资源，暂不支持差异对比".to_string(),
+                ));
+            }
+        };
+
+        // 本地文件缺失时视为空内容，diff 会展示完整的上游文件；其他读取错误直接返回
+        let local = match std::fs::read_to_string(&local_path) {
+            Ok(local) => local,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(AppError::io(&local_path, e)),
+        };
+        Ok(Self::unified_diff(&local, &upstream, id))
+    }
+
+    // ========== 还原上游版本 ==========
+
+    /// 丢弃本地修改，将单个资源还原为安装时记录的上游版本
+    ///
+    /// 记录的 file_hash 为 Git blob SHA 时按该 SHA 取回内容：即使分支已前进或资源固定在某个提交，
+    /// 也还原到安装时的版本，基线哈希不变。没有 blob SHA（本地回退哈希或未记录）时，
+    /// 从记录的分支或固定的提交重新下载并刷新基线。
+    /// 覆盖 SSOT 后同步到已启用的应用，用户备注、应用开关等本地状态保持不变。返回还原后的基线哈希
+    pub async fn revert_to_upstream(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+        id: &str,
+    ) -> Result<Option<String>, AppError> {
+        let to_app_error = |e: anyhow::Error| AppError::Message(e.to_string());
+        let not_found = || AppError::Message(format!("{resource_type} 不存在: {id}"));
+        let skill_unsupported =
+            || AppError::Message("Skill 为目录资源，请使用更新功能重新安装".to_string());
+
+        let (owner, name, branch, source_path, file_hash) = match resource_type {
+            ResourceType::Command => {
+                let c = db.get_installed_command(id)?.ok_or_else(not_found)?;
+                (
+                    c.repo_owner,
+                    c.repo_name,
+                    c.repo_branch,
+                    c.source_path,
+                    c.file_hash,
+                )
+            }
+            ResourceType::Agent => {
+                let a = db.get_installed_agent(id)?.ok_or_else(not_found)?;
+                (
+                    a.repo_owner,
+                    a.repo_name,
+                    a.repo_branch,
+                    a.source_path,
+                    a.file_hash,
+                )
+            }
+            ResourceType::Hook => {
+                let h = db.get_installed_hook(id)?.ok_or_else(not_found)?;
+                (
+                    h.repo_owner,
+                    h.repo_name,
+                    h.repo_branch,
+                    h.source_path,
+                    h.file_hash,
+                )
+            }
+            ResourceType::Skill => return Err(skill_unsupported()),
+        };
+        // 本地导入的资源没有仓库或 source_path
+        let (Some(owner), Some(name), Some(_)) = (owner, name, source_path) else {
+            return Err(AppError::Message(format!(
+                "本地导入的 {resource_type} 没有上游版本，无法还原: {id}"
+            )));
+        };
+
+        if let Some(sha) = file_hash.filter(|hash| is_blob_sha(hash)) {
+            let github_api = GitHubApiService::new(db.get_setting("github_pat")?);
+            let content = github_api.get_blob(&owner, &name, &sha).await?;
+            if !GitHubApiService::compute_blob_sha(&content).eq_ignore_ascii_case(&sha) {
+                return Err(AppError::Message(format!(
+                    "取回的内容与记录的 blob SHA 不一致: {sha}"
+                )));
+            }
+            let content = String::from_utf8(content)
+                .map_err(|_| AppError::Message(format!("{id} 的上游内容不是 UTF-8 文本")))?;
+            Self::write_reverted(db, resource_type, id, &content).map_err(to_app_error)?;
+
+            log::info!("{resource_type} {id} 已还原为安装时的版本 {sha}");
+            return Ok(Some(sha));
+        }
+
+        let branch = branch.unwrap_or_else(|| "main".to_string());
+        let new_hash = match resource_type {
+            ResourceType::Command => {
+                CommandService::new()
+                    .switch_branch(db, id, &branch)
+                    .await
+                    .map_err(to_app_error)?
+                    .file_hash
+            }
+            ResourceType::Agent => {
+                AgentService::new()
+                    .switch_branch(db, id, &branch)
+                    .await
+                    .map_err(to_app_error)?
+                    .file_hash
+            }
+            ResourceType::Hook => {
+                HookService::new()
+                    .switch_branch(db, id, &branch)
+                    .await
+                    .map_err(to_app_error)?
+                    .file_hash
+            }
+            ResourceType::Skill => return Err(skill_unsupported()),
+        };
+
+        log::info!("{resource_type} {id} 已从 {branch} 重新下载上游版本");
+        Ok(new_hash)
+    }
+
+    /// 用取回的上游内容覆盖 SSOT，并同步到已启用的应用
+    ///
+    /// Hooks 的应用配置由数据库中的规则生成，因此同时按内容刷新事件类型与规则
+    fn write_reverted(
+        db: &Arc<Database>,
+        resource_type: ResourceType,
+        id: &str,
+        content: &str,
+    ) -> anyhow::Result<()> {
+        let write = |dest: PathBuf| -> anyhow::Result<()> {
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&dest, content)?;
+            Ok(())
+        };
+        let apps = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+        match resource_type {
+            ResourceType::Command => {
+                let installed = db
+                    .get_installed_command(id)?
+                    .ok_or_else(|| anyhow::anyhow!("Command not found: {id}"))?;
+                write(
+                    CommandService::get_ssot_dir()?.join(CommandService::id_to_relative_path(id)),
+                )?;
+                for app in apps.iter().filter(|app| installed.apps.is_enabled_for(app)) {
+                    CommandService::copy_to_app(id, app)?;
+                }
+            }
+            ResourceType::Agent => {
+                let installed = db
+                    .get_installed_agent(id)?
+                    .ok_or_else(|| anyhow::anyhow!("Agent not found: {id}"))?;
+                write(AgentService::get_ssot_dir()?.join(AgentService::id_to_relative_path(id)))?;
+                for app in apps
+                    .iter()
+                    .filter(|app| installed.apps.is_enabled_for(app.as_str()))
+                {
+                    AgentService::copy_to_app(id, app)?;
+                }
+            }
+            ResourceType::Hook => {
+                let mut installed = db
+                    .get_installed_hook(id)?
+                    .ok_or_else(|| anyhow::anyhow!("Hook not found: {id}"))?;
+                let metadata = HookService::parse_hook_metadata(content)?;
+                write(HookService::get_ssot_dir()?.join(HookService::id_to_relative_path(id)))?;
+                if let Some(event_type) = metadata.event_type {
+                    installed.event_type = event_type;
+                }
+                if !metadata.rules.is_empty() {
+                    installed.rules = metadata.rules;
+                }
+                db.save_hook(&installed)?;
+                HookService::sync_all_to_apps(db)?;
+            }
+            ResourceType::Skill => {
+                return Err(anyhow::anyhow!("Skill 为目录资源，请使用更新功能重新安装"));
+            }
+        }
+        Ok(())
+    }
+
+    // ========== 本地哈希规范化 ==========
+
+    /// 把安装时回退计算的本地内容哈希替换为 Git blob SHA
+    ///
+    /// 仅处理有仓库来源的 Commands/Agents/Hooks。两种哈希不可比较，
+    /// 未规范化的项在 SHA 优先模式下会一直显示有更新。
+    /// SSOT 文件在安装后被修改过时无法还原安装时的版本，这些项跳过并报告
+    pub fn normalize_hashes_to_blob_sha(db: &Database) -> Result<HashNormalizeResult, AppError> {
+        let path_err = |e: anyhow::Error| AppError::Message(e.to_string());
+        let mut candidates: Vec<(ResourceType, String, String, PathBuf)> = Vec::new();
+
+        // 按记录的安装范围解析文件：项目级安装优先取项目副本
+        for c in db.get_all_installed_commands()?.into_values() {
+            if let (Some(_), Some(hash)) = (&c.repo_owner, c.file_hash) {
+                let scope = InstallScope::from_db(&c.scope, c.project_path.as_deref());
+                let path =
+                    CommandService::resolve_installed_path(&c.id, &scope).map_err(path_err)?;
+                candidates.push((ResourceType::Command, c.id, hash, path));
+            }
+        }
+        for a in db.get_all_installed_agents()?.into_values() {
+            if let (Some(_), Some(hash)) = (&a.repo_owner, a.file_hash) {
+                let scope = InstallScope::from_db(&a.scope, a.project_path.as_deref());
+                let path = AgentService::resolve_installed_path(&a.id, &scope).map_err(path_err)?;
+                candidates.push((ResourceType::Agent, a.id, hash, path));
+            }
+        }
+        for h in db.get_all_installed_hooks()?.into_values() {
+            if let (Some(_), Some(hash)) = (&h.repo_owner, h.file_hash) {
+                let scope = InstallScope::from_db(&h.scope, h.project_path.as_deref());
+                let path = HookService::resolve_installed_path(&h.id, &scope).map_err(path_err)?;
+                candidates.push((ResourceType::Hook, h.id, hash, path));
+            }
+        }
+
+        let mut result = HashNormalizeResult::default();
+        for (resource_type, id, hash, path) in candidates {
+            if !is_local_content_hash(&hash) {
+                continue;
+            }
+
+            let content = match std::fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    result
+                        .skipped
+                        .push(format!("{resource_type} {id}: 读取文件失败: {e}"));
+                    continue;
+                }
+            };
+            let Some(blob_sha) = blob_sha_for_local_hash(&hash, &content) else {
+                result
+                    .skipped
+                    .push(format!("{resource_type} {id}: 文件在安装后已修改"));
+                continue;
+            };
+
+            match resource_type {
+                ResourceType::Command => db.update_command_hash(&id, &blob_sha)?,
+                ResourceType::Agent => db.update_agent_hash(&id, &blob_sha)?,
+                _ => db.update_hook_hash(&id, &blob_sha)?,
+            };
+            log::info!("{resource_type} {id} 的本地哈希已替换为 blob SHA: {blob_sha}");
+            result.fixed_count += 1;
+        }
+
+        Ok(result)
+    }
+}
+
+/// 比对单个文件的远程内容与本地记录的哈希
+///
+/// - `ShaPreferred`：先取 blob SHA；除 NotFound 外的错误回退到下载原始内容比对，
+///   回退也失败时返回原始错误
+/// - `ContentAlways`：直接下载原始内容比对
+///
+/// 内容比对时本地哈希与以下任一值相同即视为未变化：原始内容的 blob SHA、
+/// CRLF 归一化为 LF 后的 blob SHA、原始内容的 sha256（安装时 API 失败的回退格式）。
+/// 有变化时返回原始内容的 blob SHA，与 API 结果保持一致。
+pub async fn compare_file_hash<S, C>(
+    mode: UpdateCheckMode,
+    current_hash: Option<&str>,
+    sha_lookup: S,
+    content_fetch: C,
+) -> Result<FileHashOutcome, GitHubApiError>
+where
+    S: std::future::Future<Output = Result<(String, u64), GitHubApiError>>,
+    C: std::future::Future<Output = Result<Vec<u8>, GitHubApiError>>,
+{
+    let content = match mode {
+        UpdateCheckMode::ShaPreferred => match sha_lookup.await {
+            Ok((new_hash, _size)) => {
+                return Ok(if current_hash == Some(new_hash.as_str()) {
+                    FileHashOutcome::Unchanged
+                } else {
+                    FileHashOutcome::Changed(new_hash)
+                });
+            }
+            Err(GitHubApiError::NotFound) => return Err(GitHubApiError::NotFound),
+            Err(e) => {
+                log::debug!("获取 blob SHA 失败，回退到内容比对: {e}");
+                content_fetch.await.map_err(|_| e)?
+            }
+        },
+        UpdateCheckMode::ContentAlways => content_fetch.await?,
+    };
+
+    let new_hash = GitHubApiService::compute_blob_sha(&content);
+    let Some(current_hash) = current_hash else {
+        return Ok(FileHashOutcome::Changed(new_hash));
+    };
+
+    let text = String::from_utf8_lossy(&content);
+    let candidates = [
+        new_hash.clone(),
+        GitHubApiService::compute_blob_sha(text.replace("\r\n", "\n").as_bytes()),
+        CommandService::compute_hash(&text),
+    ];
+    if candidates.iter().any(|hash| hash == current_hash) {
+        Ok(FileHashOutcome::Unchanged)
+    } else {
+        Ok(FileHashOutcome::Changed(new_hash))
+    }
+}
+
+/// 计算资源更新（重新安装）后写回的应用启用状态
+///
+/// - `preserve = true`（默认）：恢复更新开始时的快照；更新进行中对应用开关的改动会被覆盖
+/// - `preserve = false`：以重新安装前一刻数据库中的状态为准，保留更新期间的开关改动；
+///   记录已不存在时回退到快照
+pub fn apps_after_update<T>(snapshot: T, current: Option<T>, preserve: bool) -> T {
+    if preserve {
+        snapshot
+    } else {
+        current.unwrap_or(snapshot)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{create_test_command, TempHome};
+    use serial_test::serial;
 
     #[test]
     fn test_resource_type_display() {
@@ -437,4 +1152,400 @@ mod tests {
         };
         assert_eq!(result.success_count, 0);
     }
+
+    #[test]
+    fn test_blob_sha_for_local_hash() {
+        let content = "---\nname: agent\n---\nbody\n";
+        let local = CommandService::compute_hash(content);
+        assert!(is_local_content_hash(&local));
+
+        let blob = blob_sha_for_local_hash(&local, content).unwrap();
+        assert_eq!(blob, GitHubApiService::compute_blob_sha(content.as_bytes()));
+        assert!(!is_local_content_hash(&blob));
+
+        // 文件已修改时无法确定安装时的版本
+        assert!(blob_sha_for_local_hash(&local, "edited").is_none());
+    }
+
+    #[test]
+    fn body_fingerprint_ignores_frontmatter_changes() {
+        let original =
+            "---\nname: review\ndescription: Review code\n---\n\n# Review\nCheck the diff\n";
+        let reordered =
+            "---\ndescription: Review code\nname: review\nallowed-tools: Read\n---\r\n\r\n# Review  \r\nCheck the diff\r\n";
+        let edited = "---\nname: review\ndescription: Review code\n---\n\n# Review\nCheck the diff carefully\n";
+
+        assert_eq!(body_fingerprint(original), body_fingerprint(reordered));
+        assert_ne!(body_fingerprint(original), body_fingerprint(edited));
+        // 没有 frontmatter 时对整个文件计算
+        assert_eq!(
+            body_fingerprint("# Review\nCheck the diff\n"),
+            body_fingerprint(original)
+        );
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        assert!(UpdateService::unified_diff("a\nb\n", "a\nb\n", "commit").is_none());
+
+        let diff = UpdateService::unified_diff("a\nb\n", "a\nc\n", "commit").unwrap();
+        assert!(diff.contains("--- local/commit"));
+        assert!(diff.contains("+++ upstream/commit"));
+        assert!(diff.contains("-b"));
+        assert!(diff.contains("+c"));
+    }
+
+    #[test]
+    fn apps_after_update_honors_preserve_setting() {
+        use crate::app_config::CommandApps;
+
+        let snapshot = CommandApps {
+            claude: true,
+            codex: true,
+            gemini: false,
+        };
+        // 更新进行中用户关闭了 Codex
+        let current = CommandApps {
+            codex: false,
+            ..snapshot.clone()
+        };
+
+        let preserved = apps_after_update(snapshot.clone(), Some(current.clone()), true);
+        assert_eq!(preserved, snapshot);
+
+        let honored = apps_after_update(snapshot.clone(), Some(current.clone()), false);
+        assert_eq!(honored, current);
+
+        // 记录在更新期间被删除时回退到快照
+        assert_eq!(apps_after_update(snapshot.clone(), None, false), snapshot);
+    }
+
+    #[tokio::test]
+    async fn compare_file_hash_falls_back_to_content_when_sha_api_fails() {
+        use crate::services::github_api::RateLimitInfo;
+
+        let content = b"# Review\r\nCheck the diff\r\n".to_vec();
+        let stored = GitHubApiService::compute_blob_sha(&content);
+        let rate_limited = || async {
+            Err(GitHubApiError::RateLimited(RateLimitInfo {
+                remaining: 0,
+                limit: 60,
+                reset_at: 0,
+            }))
+        };
+
+        let outcome = compare_file_hash(
+            UpdateCheckMode::ShaPreferred,
+            Some(&stored),
+            rate_limited(),
+            async { Ok(content.clone()) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, FileHashOutcome::Unchanged);
+
+        // 安装时记录的 sha256 回退格式同样视为未变化
+        let sha256 = CommandService::compute_hash(&String::from_utf8_lossy(&content));
+        let outcome = compare_file_hash(
+            UpdateCheckMode::ShaPreferred,
+            Some(&sha256),
+            rate_limited(),
+            async { Ok(content.clone()) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, FileHashOutcome::Unchanged);
+
+        let changed = b"# Review\nCheck the diff carefully\n".to_vec();
+        let outcome = compare_file_hash(
+            UpdateCheckMode::ShaPreferred,
+            Some(&stored),
+            rate_limited(),
+            async { Ok(changed.clone()) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            outcome,
+            FileHashOutcome::Changed(GitHubApiService::compute_blob_sha(&changed))
+        );
+
+        // 回退也失败时返回原始错误
+        let err = compare_file_hash(
+            UpdateCheckMode::ShaPreferred,
+            Some(&stored),
+            rate_limited(),
+            async { Err(GitHubApiError::NetworkError("offline".to_string())) },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, GitHubApiError::RateLimited(_)));
+    }
+
+    /// 本地 HTTP 服务：对任意 GET 返回同一份内容，返回地址与已收到请求的路径
+    async fn serve_raw_file(
+        body: &'static str,
+    ) -> (
+        String,
+        Arc<std::sync::Mutex<Vec<String>>>,
+        tokio::task::JoinHandle<()>,
+    ) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let server = tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = vec![0u8; 4096];
+                let n = socket.read(&mut request).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..n]);
+                let path = request.split_whitespace().nth(1).unwrap_or("").to_string();
+                recorded.lock().unwrap().push(path);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        (format!("http://{addr}"), requests, server)
+    }
+
+    /// 使用内容比对模式，并从本地服务下载原始文件
+    fn use_raw_server(base_url: &str, compare_body_only: bool) {
+        let mut settings = crate::settings::get_settings();
+        settings.update_check_mode = UpdateCheckMode::ContentAlways;
+        settings.github_raw_base_url = Some(base_url.to_string());
+        settings.update_compare_body_only = compare_body_only;
+        crate::settings::update_settings(settings).unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn frontmatter_only_change_moves_baseline_when_body_compare_is_on() {
+        let _home = TempHome::new();
+        crate::settings::reload_settings().expect("reload settings");
+
+        let local = "---\nname: review\nmodel: sonnet\n---\n# Review\nCheck the diff\n";
+        let upstream = "---\nmodel: sonnet\nname: review\n---\n# Review\nCheck the diff\n";
+        let (base_url, requests, server) = serve_raw_file(upstream).await;
+
+        let db = Database::memory().unwrap();
+        let original_hash = GitHubApiService::compute_blob_sha(local.as_bytes());
+        let upstream_hash = GitHubApiService::compute_blob_sha(upstream.as_bytes());
+        let mut command = create_test_command("review", "", "review");
+        command.file_hash = Some(original_hash.clone());
+        db.save_command(&command).unwrap();
+        let ssot = CommandService::get_ssot_dir()
+            .unwrap()
+            .join(CommandService::id_to_relative_path("review"));
+        std::fs::create_dir_all(ssot.parent().unwrap()).unwrap();
+        std::fs::write(&ssot, local).unwrap();
+
+        async fn check(db: &Database) -> UpdateCheckResult {
+            let command = db.get_installed_command("review").unwrap().unwrap();
+            UpdateService::new(None)
+                .check_file_resource_update(
+                    db,
+                    ResourceType::Command,
+                    &command.id,
+                    command.repo_owner.as_deref(),
+                    command.repo_name.as_deref(),
+                    command.repo_branch.as_deref(),
+                    command.source_path.as_deref(),
+                    command.file_hash.as_deref(),
+                )
+                .await
+        }
+        let stored_hash = |db: &Database| {
+            db.get_installed_command("review")
+                .unwrap()
+                .unwrap()
+                .file_hash
+        };
+
+        // 开启正文比对：无更新，且上游哈希成为新基线
+        use_raw_server(&base_url, true);
+        let result = check(&db).await;
+        assert!(!result.has_update);
+        assert_eq!(result.new_hash, None);
+        assert_eq!(stored_hash(&db), Some(upstream_hash.clone()));
+
+        // 基线已前移，再次检测只下载一次用于哈希比对，不再做正文比对
+        let before = requests.lock().unwrap().len();
+        assert!(!check(&db).await.has_update);
+        assert_eq!(requests.lock().unwrap().len() - before, 1);
+
+        // 关闭正文比对：frontmatter 变化按更新处理，基线保持不变
+        db.update_command_hash("review", &original_hash).unwrap();
+        use_raw_server(&base_url, false);
+        let result = check(&db).await;
+        assert!(result.has_update);
+        assert_eq!(result.new_hash, Some(upstream_hash));
+        assert_eq!(stored_hash(&db), Some(original_hash));
+
+        server.abort();
+        crate::settings::update_settings(crate::settings::AppSettings::default()).unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn check_update_against_tag_reads_the_tag_ref() {
+        let _home = TempHome::new();
+        crate::settings::reload_settings().expect("reload settings");
+
+        let content = "---\nname: review\n---\n# Review\n";
+        let (base_url, requests, server) = serve_raw_file(content).await;
+        use_raw_server(&base_url, false);
+
+        let db = Database::memory().unwrap();
+        let mut command = create_test_command("review", "git", "review");
+        command.file_hash = Some(GitHubApiService::compute_blob_sha(content.as_bytes()));
+        db.save_command(&command).unwrap();
+
+        let service = UpdateService::new(None);
+        let checked = service
+            .check_update_against_tag(&db, ResourceType::Command, "review", " v1.2.0 ")
+            .await
+            .unwrap();
+        assert_eq!(checked.tag, "v1.2.0");
+        assert_eq!(checked.result.id, "review");
+        assert!(!checked.result.has_update);
+        assert_eq!(
+            requests.lock().unwrap().as_slice(),
+            ["/test-owner/test-repo/v1.2.0/commands/git/review.md"]
+        );
+
+        assert!(service
+            .check_update_against_tag(&db, ResourceType::Command, "review", "  ")
+            .await
+            .is_err());
+
+        server.abort();
+        crate::settings::update_settings(crate::settings::AppSettings::default()).unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn check_update_against_tag_keeps_baseline_hash() {
+        let _home = TempHome::new();
+        crate::settings::reload_settings().expect("reload settings");
+
+        let local = "---\nname: review\nmodel: sonnet\n---\n# Review\nCheck the diff\n";
+        let tagged = "---\nmodel: sonnet\nname: review\n---\n# Review\nCheck the diff\n";
+        let (base_url, _requests, server) = serve_raw_file(tagged).await;
+        use_raw_server(&base_url, true);
+
+        let db = Database::memory().unwrap();
+        let original_hash = GitHubApiService::compute_blob_sha(local.as_bytes());
+        let mut command = create_test_command("review", "", "review");
+        command.file_hash = Some(original_hash.clone());
+        db.save_command(&command).unwrap();
+        let ssot = CommandService::get_ssot_dir()
+            .unwrap()
+            .join(CommandService::id_to_relative_path("review"));
+        std::fs::create_dir_all(ssot.parent().unwrap()).unwrap();
+        std::fs::write(&ssot, local).unwrap();
+
+        // 仅 frontmatter 不同，视为无更新，但 tag 检测不应改写分支跟踪的基线
+        let checked = UpdateService::new(None)
+            .check_update_against_tag(&db, ResourceType::Command, "review", "v1.2.0")
+            .await
+            .unwrap();
+        assert!(!checked.result.has_update);
+        let stored = db.get_installed_command("review").unwrap().unwrap();
+        assert_eq!(stored.file_hash, Some(original_hash));
+
+        server.abort();
+        crate::settings::update_settings(crate::settings::AppSettings::default()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn compare_file_hash_content_always_skips_sha_lookup() {
+        let content = b"echo ok\n".to_vec();
+        let stored = GitHubApiService::compute_blob_sha(&content);
+
+        let outcome = compare_file_hash(
+            UpdateCheckMode::ContentAlways,
+            Some(&stored),
+            async { panic!("ContentAlways 模式不应调用 SHA 接口") },
+            async { Ok(content.clone()) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, FileHashOutcome::Unchanged);
+    }
+
+    #[test]
+    #[serial]
+    fn normalize_hashes_reads_project_copy_for_project_scope() {
+        let home = TempHome::new();
+        let db = Database::memory().unwrap();
+        let project = home.path().join("project");
+        let content = "---\nname: review\n---\n# Review\n";
+
+        let mut command = create_test_command("review", "", "review");
+        command.scope = "project".to_string();
+        command.project_path = Some(project.to_string_lossy().to_string());
+        command.file_hash = Some(CommandService::compute_hash(content));
+        db.save_command(&command).unwrap();
+
+        // 只有项目副本，SSOT 中没有对应文件
+        let project_copy = project.join(".claude/commands/review.md");
+        std::fs::create_dir_all(project_copy.parent().unwrap()).unwrap();
+        std::fs::write(&project_copy, content).unwrap();
+
+        let result = UpdateService::normalize_hashes_to_blob_sha(&db).unwrap();
+        assert_eq!(result.fixed_count, 1, "skipped: {:?}", result.skipped);
+        let saved = db.get_installed_command("review").unwrap().unwrap();
+        assert_eq!(
+            saved.file_hash,
+            Some(GitHubApiService::compute_blob_sha(content.as_bytes()))
+        );
+    }
+
+    #[test]
+    fn blob_sha_detection() {
+        assert!(is_blob_sha(&GitHubApiService::compute_blob_sha(b"hello")));
+        assert!(!is_blob_sha(&CommandService::compute_hash("hello")));
+        assert!(!is_blob_sha("abc123"));
+    }
+
+    #[test]
+    #[serial]
+    fn write_reverted_restores_ssot_and_enabled_app_copy() {
+        let home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+        let upstream = "---\nname: review\n---\n# Review\n";
+
+        let command = create_test_command("review", "", "review");
+        db.save_command(&command).unwrap();
+        let ssot = CommandService::get_ssot_dir().unwrap().join("review.md");
+        std::fs::create_dir_all(ssot.parent().unwrap()).unwrap();
+        std::fs::write(&ssot, "# Local edits\n").unwrap();
+
+        UpdateService::write_reverted(&db, ResourceType::Command, "review", upstream).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&ssot).unwrap(), upstream);
+        let app_copy = home.path().join(".claude/commands/review.md");
+        assert_eq!(std::fs::read_to_string(app_copy).unwrap(), upstream);
+    }
+
+    #[tokio::test]
+    async fn revert_rejects_locally_imported_resource() {
+        let db = Arc::new(Database::memory().unwrap());
+        let mut command = create_test_command("local", "", "local");
+        command.repo_owner = None;
+        command.repo_name = None;
+        command.source_path = None;
+        db.save_command(&command).unwrap();
+
+        let err = UpdateService::revert_to_upstream(&db, ResourceType::Command, "local")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("没有上游版本"));
+    }
 }
@@ -1043,6 +1043,19 @@ impl Database {
         }
     }
 
+    /// 清除已有请求日志中的内容字段（错误正文），只保留元数据
+    ///
+    /// 仅在用户显式操作时调用；切换到 metadata 级别不会自动改写历史日志。
+    /// 返回被清除的记录数
+    pub fn redact_request_log_content(&self) -> Result<usize, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "UPDATE proxy_request_logs SET error_message = NULL WHERE error_message IS NOT NULL",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
     /// 检查 Provider 使用限额
     pub fn check_provider_limits(
         &self,
@@ -9,6 +9,7 @@ use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 
 use crate::error::AppError;
+use crate::services::notification::{notify_background, BackgroundNotice};
 use crate::services::webdav_sync as webdav_sync_service;
 use crate::settings::{self, WebDavSyncSettings};
 
@@ -125,11 +126,26 @@ async fn run_auto_sync_upload(
     match result {
         Ok(_) => {
             emit_auto_sync_status_updated(app, "success", None);
+            notify_background(
+                app,
+                BackgroundNotice {
+                    title: "WebDAV 自动同步完成",
+                    body: "本地配置已上传到 WebDAV",
+                },
+            );
             Ok(())
         }
         Err(err) => {
             persist_auto_sync_error(&mut sync_settings, &err);
-            emit_auto_sync_status_updated(app, "error", Some(&err.to_string()));
+            let message = err.to_string();
+            emit_auto_sync_status_updated(app, "error", Some(&message));
+            notify_background(
+                app,
+                BackgroundNotice {
+                    title: "WebDAV 自动同步失败",
+                    body: &message,
+                },
+            );
             Err(err)
         }
     }
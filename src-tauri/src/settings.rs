@@ -3,10 +3,13 @@ use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
 use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
 
 use crate::app_config::AppType;
 use crate::error::AppError;
+use crate::proxy::usage::logger::RequestLogLevel;
 use crate::services::skill::{SkillStorageLocation, SyncMethod};
+use crate::services::update::UpdateCheckMode;
 
 /// 自定义端点配置（历史兼容，实际存储在 provider.meta.custom_endpoints）
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -167,6 +170,79 @@ impl WebDavSyncSettings {
     }
 }
 
+fn default_file_download_secs() -> u64 {
+    30
+}
+fn default_repo_download_secs() -> u64 {
+    60
+}
+fn default_api_call_secs() -> u64 {
+    30
+}
+
+/// 单项超时的上限（秒），防止误填过大值导致界面长时间无响应
+const MAX_TIMEOUT_SECS: u64 = 3600;
+
+/// 默认的原始文件下载地址前缀
+const DEFAULT_GITHUB_RAW_BASE_URL: &str = "https://raw.githubusercontent.com";
+
+/// 资源下载超时设置（秒）
+///
+/// Commands/Agents/Hooks 的下载与 GitHub API 调用统一从这里读取，
+/// 网络较慢或经过企业代理时可调大
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeoutConfig {
+    /// 单文件下载（raw.githubusercontent.com）
+    #[serde(default = "default_file_download_secs")]
+    pub file_download_secs: u64,
+    /// 整个仓库 ZIP 下载（含多分支尝试）
+    #[serde(default = "default_repo_download_secs")]
+    pub repo_download_secs: u64,
+    /// GitHub API 调用
+    #[serde(default = "default_api_call_secs")]
+    pub api_call_secs: u64,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            file_download_secs: default_file_download_secs(),
+            repo_download_secs: default_repo_download_secs(),
+            api_call_secs: default_api_call_secs(),
+        }
+    }
+}
+
+impl TimeoutConfig {
+    pub fn validate(&self) -> Result<(), AppError> {
+        for (field, value) in [
+            ("fileDownloadSecs", self.file_download_secs),
+            ("repoDownloadSecs", self.repo_download_secs),
+            ("apiCallSecs", self.api_call_secs),
+        ] {
+            if value == 0 || value > MAX_TIMEOUT_SECS {
+                return Err(AppError::InvalidInput(format!(
+                    "{field} 必须在 1 到 {MAX_TIMEOUT_SECS} 秒之间，当前为 {value}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn file_download(&self) -> Duration {
+        Duration::from_secs(self.file_download_secs)
+    }
+
+    pub fn repo_download(&self) -> Duration {
+        Duration::from_secs(self.repo_download_secs)
+    }
+
+    pub fn api_call(&self) -> Duration {
+        Duration::from_secs(self.api_call_secs)
+    }
+}
+
 /// 应用设置结构
 ///
 /// 存储设备级别设置，保存在本地 `~/.cc-switch/settings.json`，不随数据库同步。
@@ -266,6 +342,58 @@ pub struct AppSettings {
     #[serde(default)]
     pub skill_storage_location: SkillStorageLocation,
 
+    // ===== 资源校验设置 =====
+    /// 启动时校验 Commands/Agents/Hooks 的 SSOT 文件完整性（只读，仅本地哈希）
+    #[serde(default)]
+    pub verify_integrity_on_startup: bool,
+    /// 资源更新后是否恢复更新开始时的应用启用状态（默认 true）
+    ///
+    /// 关闭后以重新安装前一刻的数据库状态为准，更新期间对应用开关的改动不会被覆盖
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preserve_app_state_on_update: Option<bool>,
+    /// Commands/Agents/Hooks 的更新检测方式（默认优先 blob SHA，失败时回退内容比对）
+    #[serde(default)]
+    pub update_check_mode: UpdateCheckMode,
+    /// Commands/Agents 更新检测时只比较正文（忽略 YAML frontmatter），默认比较整个文件
+    ///
+    /// 可避免维护者调整 frontmatter 顺序或空白带来的误报，但 frontmatter 中的实际变化也会被忽略
+    #[serde(default)]
+    pub update_compare_body_only: bool,
+    /// 更新检测回退到内容比对时下载原始文件的地址前缀（默认 https://raw.githubusercontent.com）
+    ///
+    /// 无法直连 GitHub 时可填写镜像地址，请求路径为 `{base}/{owner}/{repo}/{branch}/{path}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_raw_base_url: Option<String>,
+
+    // ===== 通知设置 =====
+    /// 后台任务（如 WebDAV 自动同步）完成或失败时发送系统通知
+    #[serde(default)]
+    pub background_notifications: bool,
+
+    // ===== 安全设置 =====
+    /// 是否加密存储供应商密钥（主密钥保存在系统钥匙串，设备级）
+    #[serde(default)]
+    pub encrypt_provider_keys: bool,
+    /// 代理请求日志记录级别：full（默认）、metadata（不保存错误正文等内容）、off
+    ///
+    /// 切换级别不会改写已有日志，需要通过 `redact_request_logs` 显式清除
+    #[serde(default)]
+    pub request_log_level: RequestLogLevel,
+
+    // ===== 网络设置 =====
+    /// 资源下载超时（设备级，网络环境不同）
+    #[serde(default)]
+    pub download_timeouts: TimeoutConfig,
+    /// 发现时同时下载的仓库数（默认 4）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discovery_concurrency: Option<u32>,
+    /// 仓库 ZIP 下载中断后是否断点续传（默认关闭）
+    #[serde(default)]
+    pub resumable_repo_downloads: bool,
+    /// 仓库 ZIP 下载的大小上限（MB，默认 50）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_repo_download_mb: Option<u64>,
+
     // ===== WebDAV 同步设置 =====
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub webdav_sync: Option<WebDavSyncSettings>,
@@ -281,6 +409,9 @@ pub struct AppSettings {
     /// Maximum number of backup files to retain (default 10)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub backup_retain_count: Option<u32>,
+    /// Days of speedtest history to retain (default 30)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speedtest_history_retain_days: Option<u32>,
 
     // ===== 终端设置 =====
     /// 首选终端应用（可选，默认使用系统默认终端）
@@ -333,10 +464,23 @@ impl Default for AppSettings {
             current_provider_hermes: None,
             skill_sync_method: SyncMethod::default(),
             skill_storage_location: SkillStorageLocation::default(),
+            verify_integrity_on_startup: false,
+            preserve_app_state_on_update: None,
+            update_check_mode: UpdateCheckMode::default(),
+            update_compare_body_only: false,
+            github_raw_base_url: None,
+            background_notifications: false,
+            encrypt_provider_keys: false,
+            request_log_level: RequestLogLevel::default(),
+            download_timeouts: TimeoutConfig::default(),
+            discovery_concurrency: None,
+            resumable_repo_downloads: false,
+            max_repo_download_mb: None,
             webdav_sync: None,
             webdav_backup: None,
             backup_interval_hours: None,
             backup_retain_count: None,
+            speedtest_history_retain_days: None,
             preferred_terminal: None,
         }
     }
@@ -352,6 +496,21 @@ impl AppSettings {
         )
     }
 
+    /// 设置中的应用目录覆盖（已解析 `~`）
+    fn override_dirs(&self) -> Vec<(&'static str, PathBuf)> {
+        [
+            ("Claude", &self.claude_config_dir),
+            ("Codex", &self.codex_config_dir),
+            ("Gemini", &self.gemini_config_dir),
+            ("OpenCode", &self.opencode_config_dir),
+            ("OpenClaw", &self.openclaw_config_dir),
+            ("Hermes", &self.hermes_config_dir),
+        ]
+        .into_iter()
+        .filter_map(|(app, dir)| dir.as_deref().map(|d| (app, resolve_override_path(d))))
+        .collect()
+    }
+
     fn normalize_paths(&mut self) {
         self.claude_config_dir = self
             .claude_config_dir
@@ -517,6 +676,11 @@ pub fn get_settings_for_frontend() -> AppSettings {
 
 pub fn update_settings(mut new_settings: AppSettings) -> Result<(), AppError> {
     new_settings.normalize_paths();
+    // 应用目录覆盖不能包含 SSOT 目录，否则同步时会循环复制
+    crate::config::check_ssot_dir(
+        &crate::config::get_app_config_dir(),
+        &new_settings.override_dirs(),
+    )?;
     save_settings_file(&new_settings)?;
 
     let mut guard = settings_store().write().unwrap_or_else(|e| {
@@ -705,6 +869,36 @@ pub fn set_skill_storage_location(location: SkillStorageLocation) -> Result<(),
     })
 }
 
+/// 设置是否加密存储供应商密钥
+///
+/// 只修改开关本身，数据迁移由 `ProviderService::set_key_encryption` 负责
+pub fn set_encrypt_provider_keys(enabled: bool) -> Result<(), AppError> {
+    mutate_settings(|s| {
+        s.encrypt_provider_keys = enabled;
+    })
+}
+
+// ===== 网络超时管理函数 =====
+
+/// 获取资源下载超时设置
+pub fn get_timeout_config() -> TimeoutConfig {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .download_timeouts
+}
+
+/// 设置资源下载超时（校验通过后保存）
+pub fn set_timeout_config(config: TimeoutConfig) -> Result<(), AppError> {
+    config.validate()?;
+    mutate_settings(|s| {
+        s.download_timeouts = config;
+    })
+}
+
 // ===== 备份策略管理函数 =====
 
 /// Get the effective auto-backup interval in hours (default 24)
@@ -732,6 +926,131 @@ pub fn effective_backup_retain_count() -> usize {
         .unwrap_or(10)
 }
 
+/// Get the effective speedtest history retention in days (default 30, minimum 1)
+pub fn effective_speedtest_history_retain_days() -> i64 {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .speedtest_history_retain_days
+        .map(|n| (n as i64).max(1))
+        .unwrap_or(30)
+}
+
+/// 发现时同时下载的仓库数（默认 4，范围 1-16）
+pub fn effective_discovery_concurrency() -> usize {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .discovery_concurrency
+        .map(|n| n.clamp(1, 16) as usize)
+        .unwrap_or(4)
+}
+
+/// 仓库 ZIP 下载的大小上限（字节），未设置或为 0 时使用默认的 50 MB
+pub fn effective_max_download_bytes() -> u64 {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .max_repo_download_mb
+        .filter(|mb| *mb > 0)
+        .map(|mb| mb.saturating_mul(1024 * 1024))
+        .unwrap_or(crate::services::command::DEFAULT_MAX_DOWNLOAD_BYTES)
+}
+
+/// 仓库 ZIP 下载是否断点续传
+pub fn resumable_repo_downloads_enabled() -> bool {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .resumable_repo_downloads
+}
+
+/// Whether resource updates restore the pre-update app-enable snapshot (default true)
+pub fn effective_preserve_app_state_on_update() -> bool {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .preserve_app_state_on_update
+        .unwrap_or(true)
+}
+
+/// 获取界面语言（未设置时为中文，与托盘等后端文案一致）
+pub fn get_language() -> String {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .language
+        .clone()
+        .unwrap_or_else(|| "zh".to_string())
+}
+
+/// 获取文件资源的更新检测方式
+pub fn get_update_check_mode() -> UpdateCheckMode {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .update_check_mode
+}
+
+/// 获取原始文件下载地址前缀（未配置时为 raw.githubusercontent.com，不含末尾 `/`）
+pub fn get_github_raw_base_url() -> String {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .github_raw_base_url
+        .as_deref()
+        .map(|url| url.trim().trim_end_matches('/'))
+        .filter(|url| !url.is_empty())
+        .unwrap_or(DEFAULT_GITHUB_RAW_BASE_URL)
+        .to_string()
+}
+
+/// Commands/Agents 更新检测是否只比较正文
+pub fn get_update_compare_body_only() -> bool {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .update_compare_body_only
+}
+
+/// 获取代理请求日志记录级别
+pub fn get_request_log_level() -> RequestLogLevel {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .request_log_level
+}
+
 // ===== 终端设置管理函数 =====
 
 /// 获取首选终端应用
@@ -0,0 +1,166 @@
+//! 单元测试共用的辅助工具
+//!
+//! 提供隔离的临时 HOME 目录，以及 Commands/Agents/Hooks 的测试记录构造函数，
+//! 避免各测试模块重复粘贴完整的结构体字面量。
+
+use std::env;
+
+use tempfile::TempDir;
+
+use crate::app_config::{
+    AgentApps, CommandApps, HookApps, HookEventType, HookRule, HookType, InstalledAgent,
+    InstalledCommand, InstalledHook,
+};
+
+/// 临时 HOME 目录，Drop 时恢复原有环境变量
+///
+/// 环境变量是进程级的，使用方需配合 `#[serial]` 避免并发测试互相覆盖
+pub(crate) struct TempHome {
+    dir: TempDir,
+    original_home: Option<String>,
+    original_userprofile: Option<String>,
+    original_test_home: Option<String>,
+}
+
+impl TempHome {
+    pub(crate) fn new() -> Self {
+        let dir = TempDir::new().expect("failed to create temp home");
+        let original_home = env::var("HOME").ok();
+        let original_userprofile = env::var("USERPROFILE").ok();
+        let original_test_home = env::var("CC_SWITCH_TEST_HOME").ok();
+
+        env::set_var("HOME", dir.path());
+        env::set_var("USERPROFILE", dir.path());
+        env::set_var("CC_SWITCH_TEST_HOME", dir.path());
+
+        Self {
+            dir,
+            original_home,
+            original_userprofile,
+            original_test_home,
+        }
+    }
+
+    pub(crate) fn path(&self) -> &std::path::Path {
+        self.dir.path()
+    }
+}
+
+impl Drop for TempHome {
+    fn drop(&mut self) {
+        match &self.original_home {
+            Some(value) => env::set_var("HOME", value),
+            None => env::remove_var("HOME"),
+        }
+
+        match &self.original_userprofile {
+            Some(value) => env::set_var("USERPROFILE", value),
+            None => env::remove_var("USERPROFILE"),
+        }
+
+        match &self.original_test_home {
+            Some(value) => env::set_var("CC_SWITCH_TEST_HOME", value),
+            None => env::remove_var("CC_SWITCH_TEST_HOME"),
+        }
+    }
+}
+
+pub(crate) fn create_test_command(id: &str, namespace: &str, filename: &str) -> InstalledCommand {
+    InstalledCommand {
+        id: id.to_string(),
+        name: format!("Test Command {}", filename),
+        description: Some("A test command".to_string()),
+        namespace: namespace.to_string(),
+        filename: filename.to_string(),
+        category: Some("test".to_string()),
+        categories: vec!["test".to_string()],
+        allowed_tools: Some(vec!["Bash".to_string(), "Read".to_string()]),
+        mcp_servers: None,
+        personas: None,
+        requires_env: Vec::new(),
+        extra_metadata: None,
+        repo_owner: Some("test-owner".to_string()),
+        repo_name: Some("test-repo".to_string()),
+        repo_branch: Some("main".to_string()),
+        readme_url: None,
+        source_path: Some(format!("commands/{}/{}.md", namespace, filename)),
+        apps: CommandApps {
+            claude: true,
+            codex: false,
+            gemini: false,
+        },
+        file_hash: Some("abc123".to_string()),
+        installed_at: 1700000000,
+        scope: "global".to_string(),
+        project_path: None,
+        user_note: None,
+        display_name_override: None,
+    }
+}
+
+pub(crate) fn create_test_agent(id: &str, namespace: &str, filename: &str) -> InstalledAgent {
+    InstalledAgent {
+        id: id.to_string(),
+        name: format!("Test Agent {}", filename),
+        description: Some("A test agent".to_string()),
+        namespace: namespace.to_string(),
+        filename: filename.to_string(),
+        model: Some("sonnet".to_string()),
+        tools: Some(vec!["Read".to_string(), "Write".to_string()]),
+        requires_env: Vec::new(),
+        extra_metadata: None,
+        repo_owner: Some("test-owner".to_string()),
+        repo_name: Some("test-repo".to_string()),
+        repo_branch: Some("main".to_string()),
+        readme_url: None,
+        source_path: Some(format!("agents/{}.md", filename)),
+        apps: AgentApps {
+            claude: true,
+            codex: false,
+            gemini: false,
+        },
+        file_hash: Some("abc123".to_string()),
+        installed_at: 1700000000,
+        scope: "global".to_string(),
+        project_path: None,
+        user_note: None,
+        display_name_override: None,
+    }
+}
+
+pub(crate) fn create_test_hook(id: &str, namespace: &str, filename: &str) -> InstalledHook {
+    InstalledHook {
+        id: id.to_string(),
+        name: format!("Test Hook {}", filename),
+        description: Some("A test hook".to_string()),
+        namespace: namespace.to_string(),
+        filename: filename.to_string(),
+        event_type: HookEventType::PreToolUse,
+        rules: vec![HookRule {
+            matcher: "Bash".to_string(),
+            hooks: vec![HookType::Command {
+                command: "/usr/bin/test-hook".to_string(),
+            }],
+            os: None,
+        }],
+        enabled: true,
+        priority: 100,
+        requires_env: Vec::new(),
+        repo_owner: Some("test-owner".to_string()),
+        repo_name: Some("test-repo".to_string()),
+        repo_branch: Some("main".to_string()),
+        readme_url: None,
+        source_path: Some(format!("hooks/{}.json", filename)),
+        apps: HookApps {
+            claude: true,
+            codex: false,
+            gemini: false,
+        },
+        file_hash: Some("abc123".to_string()),
+        installed_at: 1700000000,
+        scope: "global".to_string(),
+        project_path: None,
+        user_note: None,
+        display_name_override: None,
+    }
+}
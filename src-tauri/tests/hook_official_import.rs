@@ -0,0 +1,61 @@
+use std::fs;
+
+use cc_switch_lib::{AppType, HookService};
+
+#[path = "support.rs"]
+mod support;
+use support::{create_test_state, ensure_test_home, reset_test_fs, test_mutex};
+
+#[test]
+fn import_official_format_creates_one_hook_per_supported_event() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let path = home.join("team-hooks.json");
+    fs::write(
+        &path,
+        serde_json::json!({
+            "hooks": {
+                "PreToolUse": [{
+                    "matcher": "Bash",
+                    "hooks": [{ "type": "command", "command": "./check.sh" }]
+                }],
+                "PostToolUse": [{
+                    "matcher": ["Edit", "Write"],
+                    "hooks": [{ "type": "command", "command": "prettier --write" }]
+                }],
+                "SessionStart": [{
+                    "hooks": [{ "type": "command", "command": "echo hi" }]
+                }]
+            }
+        })
+        .to_string(),
+    )
+    .expect("write official hooks file");
+
+    let state = create_test_state().expect("create test state");
+    let result = HookService::import_official_format(&state.db, &path, &AppType::Claude)
+        .expect("import official hooks");
+
+    let ids: Vec<&str> = result.imported.iter().map(|h| h.id.as_str()).collect();
+    assert_eq!(ids, ["team-hooks-post-tool-use", "team-hooks-pre-tool-use"]);
+    assert_eq!(result.imported[0].rules[0].matcher, "Edit|Write");
+    assert!(result.imported.iter().all(|h| h.apps.claude));
+    assert_eq!(result.warnings.len(), 1);
+    assert!(result.warnings[0].contains("SessionStart"));
+
+    // SSOT 中写入的是 CC Switch 格式
+    let ssot = HookService::get_ssot_dir()
+        .expect("hook ssot dir")
+        .join("team-hooks-pre-tool-use.json");
+    let written = HookService::parse_hook_metadata(&fs::read_to_string(ssot).unwrap())
+        .expect("parse written hook");
+    assert_eq!(written.rules[0].matcher, "Bash");
+
+    // 再次导入时已存在的事件被跳过
+    let again = HookService::import_official_format(&state.db, &path, &AppType::Claude)
+        .expect("re-import official hooks");
+    assert!(again.imported.is_empty());
+    assert_eq!(again.warnings.len(), 3);
+}
@@ -682,6 +682,157 @@ fn provider_service_switch_claude_updates_live_and_state() {
     );
 }
 
+#[test]
+fn removed_extra_header_is_not_restored_by_backfill() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let settings_path = get_claude_settings_path();
+    std::fs::create_dir_all(settings_path.parent().unwrap()).expect("create claude settings dir");
+
+    let mut with_header = Provider::with_id(
+        "with-header".to_string(),
+        "With Header".to_string(),
+        json!({ "env": { "ANTHROPIC_API_KEY": "a-key" } }),
+        None,
+    );
+    with_header.meta = Some(ProviderMeta {
+        extra_headers: [("X-Team".to_string(), "alpha".to_string())].into(),
+        ..Default::default()
+    });
+    let plain = Provider::with_id(
+        "plain".to_string(),
+        "Plain".to_string(),
+        json!({ "env": { "ANTHROPIC_API_KEY": "b-key" } }),
+        None,
+    );
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "plain".to_string();
+        manager
+            .providers
+            .insert("with-header".to_string(), with_header);
+        manager.providers.insert("plain".to_string(), plain);
+    }
+    let state = create_test_state_with_config(&config).expect("create test state");
+    let live_headers = || {
+        let live: serde_json::Value =
+            read_json_file(&get_claude_settings_path()).expect("read live");
+        live["env"]["ANTHROPIC_CUSTOM_HEADERS"]
+            .as_str()
+            .map(str::to_string)
+    };
+
+    ProviderService::switch(&state, AppType::Claude, "with-header").expect("switch to with-header");
+    assert_eq!(live_headers().as_deref(), Some("X-Team: alpha"));
+
+    // 切走时回填，附加请求头不应写回 settings_config
+    ProviderService::switch(&state, AppType::Claude, "plain").expect("switch to plain");
+    let mut stored = state
+        .db
+        .get_provider_by_id("with-header", AppType::Claude.as_str())
+        .expect("get provider")
+        .expect("provider exists");
+    assert!(stored.settings_config["env"]
+        .get("ANTHROPIC_CUSTOM_HEADERS")
+        .is_none());
+
+    // 删除附加请求头后再次切换两次，live 配置中不再出现该请求头
+    stored.meta.as_mut().unwrap().extra_headers.clear();
+    state
+        .db
+        .save_provider(AppType::Claude.as_str(), &stored)
+        .expect("save provider");
+    ProviderService::switch(&state, AppType::Claude, "with-header").expect("switch back");
+    assert_eq!(live_headers(), None);
+    ProviderService::switch(&state, AppType::Claude, "plain").expect("switch to plain again");
+    ProviderService::switch(&state, AppType::Claude, "with-header").expect("switch back again");
+    assert_eq!(live_headers(), None);
+}
+
+#[test]
+fn changed_deployment_does_not_stack_after_backfill() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let settings_path = get_claude_settings_path();
+    std::fs::create_dir_all(settings_path.parent().unwrap()).expect("create claude settings dir");
+
+    let mut gateway = Provider::with_id(
+        "gateway".to_string(),
+        "Gateway".to_string(),
+        json!({ "env": {
+            "ANTHROPIC_API_KEY": "a-key",
+            "ANTHROPIC_BASE_URL": "https://gw.example.com/v1"
+        } }),
+        None,
+    );
+    gateway.meta = Some(ProviderMeta {
+        deployment: Some("prod".to_string()),
+        ..Default::default()
+    });
+    let plain = Provider::with_id(
+        "plain".to_string(),
+        "Plain".to_string(),
+        json!({ "env": { "ANTHROPIC_API_KEY": "b-key" } }),
+        None,
+    );
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "plain".to_string();
+        manager.providers.insert("gateway".to_string(), gateway);
+        manager.providers.insert("plain".to_string(), plain);
+    }
+    let state = create_test_state_with_config(&config).expect("create test state");
+    let live_base_url = || {
+        let live: serde_json::Value =
+            read_json_file(&get_claude_settings_path()).expect("read live");
+        live["env"]["ANTHROPIC_BASE_URL"]
+            .as_str()
+            .map(str::to_string)
+    };
+
+    ProviderService::switch(&state, AppType::Claude, "gateway").expect("switch to gateway");
+    assert_eq!(
+        live_base_url().as_deref(),
+        Some("https://gw.example.com/v1/prod")
+    );
+
+    // 切走时回填，保存的仍是不含部署名的基础 URL
+    ProviderService::switch(&state, AppType::Claude, "plain").expect("switch to plain");
+    let mut stored = state
+        .db
+        .get_provider_by_id("gateway", AppType::Claude.as_str())
+        .expect("get provider")
+        .expect("provider exists");
+    assert_eq!(
+        stored.settings_config["env"]["ANTHROPIC_BASE_URL"],
+        json!("https://gw.example.com/v1")
+    );
+
+    // 修改部署名后再切换，新旧部署名不会叠加
+    stored.meta.as_mut().unwrap().deployment = Some("staging".to_string());
+    state
+        .db
+        .save_provider(AppType::Claude.as_str(), &stored)
+        .expect("save provider");
+    ProviderService::switch(&state, AppType::Claude, "gateway").expect("switch back");
+    assert_eq!(
+        live_base_url().as_deref(),
+        Some("https://gw.example.com/v1/staging")
+    );
+}
+
 #[test]
 fn provider_service_switch_missing_provider_returns_error() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
@@ -289,6 +289,15 @@ function App() {
       try {
         unsubscribe = await providersApi.onSwitched(
           async (event: ProviderSwitchEvent) => {
+            if (event.warnings?.length) {
+              toast.warning(
+                t("notifications.backfillWarning", {
+                  defaultValue:
+                    "切换成功，但旧供应商配置回填失败，您手动修改的配置可能未保存",
+                }),
+                { duration: 5000 },
+              );
+            }
             if (event.appType === activeApp) {
               await refetch();
             }
@@ -303,7 +312,7 @@ function App() {
     return () => {
       unsubscribe?.();
     };
-  }, [activeApp, refetch]);
+  }, [activeApp, refetch, t]);
 
   useEffect(() => {
     let unsubscribe: (() => void) | undefined;
@@ -174,8 +174,12 @@ export const AgentDiscovery: React.FC<AgentDiscoveryProps> = ({ onBack }) => {
   };
 
   const handleAddRepo = async (repo: CommandRepo) => {
-    await addRepoMutation.mutateAsync(repo);
-    toast.success(t("agents.repo.addSuccess"), { closeButton: true });
+    const validation = await addRepoMutation.mutateAsync(repo);
+    if (validation.warning) {
+      toast.warning(validation.warning, { closeButton: true });
+    } else {
+      toast.success(t("agents.repo.addSuccess"), { closeButton: true });
+    }
   };
 
   const handleRemoveRepo = async (owner: string, name: string) => {
@@ -490,8 +490,12 @@ export const AgentsPage: React.FC = () => {
   };
 
   const handleAddRepo = async (repo: CommandRepo) => {
-    await addRepoMutation.mutateAsync(repo);
-    toast.success(t("agents.repo.addSuccess"), { closeButton: true });
+    const validation = await addRepoMutation.mutateAsync(repo);
+    if (validation.warning) {
+      toast.warning(validation.warning, { closeButton: true });
+    } else {
+      toast.success(t("agents.repo.addSuccess"), { closeButton: true });
+    }
   };
 
   const handleRemoveRepo = async (owner: string, name: string) => {
@@ -202,8 +202,12 @@ export const CommandDiscovery: React.FC<CommandDiscoveryProps> = ({
 
   // 仓库管理
   const handleAddRepo = async (repo: CommandRepo) => {
-    await addRepoMutation.mutateAsync(repo);
-    toast.success(t("commands.repo.addSuccess"), { closeButton: true });
+    const validation = await addRepoMutation.mutateAsync(repo);
+    if (validation.warning) {
+      toast.warning(validation.warning, { closeButton: true });
+    } else {
+      toast.success(t("commands.repo.addSuccess"), { closeButton: true });
+    }
   };
 
   const handleRemoveRepo = async (owner: string, name: string) => {
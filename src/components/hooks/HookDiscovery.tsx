@@ -118,8 +118,12 @@ export const HookDiscovery: React.FC<HookDiscoveryProps> = ({ onBack }) => {
   };
 
   const handleAddRepo = async (repo: CommandRepo) => {
-    await addRepoMutation.mutateAsync(repo);
-    toast.success(t("hooks.repo.addSuccess"), { closeButton: true });
+    const validation = await addRepoMutation.mutateAsync(repo);
+    if (validation.warning) {
+      toast.warning(validation.warning, { closeButton: true });
+    } else {
+      toast.success(t("hooks.repo.addSuccess"), { closeButton: true });
+    }
   };
 
   const handleRemoveRepo = async (owner: string, name: string) => {
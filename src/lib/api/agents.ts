@@ -1,4 +1,14 @@
 import { invoke } from "@tauri-apps/api/core";
+import type {
+  DriftSyncReport,
+  FileParseIssues,
+  PaginatedDiscovery,
+  RepoValidation,
+  SyncAction,
+  SyncReport,
+} from "./commands";
+import type { MultiAppInstallResult } from "./config";
+import type { AppId } from "./types";
 
 // ========== 类型定义 ==========
 
@@ -20,6 +30,8 @@ export interface InstalledAgent {
   filename: string;
   model?: string;
   tools?: string[];
+  /** 运行所需的环境变量（frontmatter requires_env） */
+  requiresEnv?: string[];
   extraMetadata?: Record<string, unknown>;
   repoOwner?: string;
   repoName?: string;
@@ -33,12 +45,17 @@ export interface InstalledAgent {
   scope: "global" | "project";
   /** 项目路径（当 scope="project" 时有效） */
   projectPath?: string;
+  /** 用户备注 */
+  userNote?: string;
+  /** 用户自定义显示名称（优先于 name 展示） */
+  displayNameOverride?: string;
 }
 
 /** 可发现的 Agent（来自仓库） */
 export interface DiscoverableAgent {
   key: string;
   name: string;
+  /** 按界面语言选择的描述（缺少该语言时回退英文，再回退 frontmatter 的 description） */
   description: string;
   namespace: string;
   filename: string;
@@ -48,6 +65,18 @@ export interface DiscoverableAgent {
   repoOwner: string;
   repoName: string;
   repoBranch: string;
+  /** 多语言描述（frontmatter 提供 description_zh 等字段时才有） */
+  description_zh?: string;
+  description_en?: string;
+  description_ja?: string;
+}
+
+/** Agent 兼容性警告（非致命） */
+export interface CompatWarning {
+  kind: "appUnsupported" | "unsupportedModel" | "unsupportedTool";
+  app: string;
+  value?: string;
+  message: string;
 }
 
 /** 命名空间信息 */
@@ -118,13 +147,13 @@ export const agentsApi = {
     return await invoke("get_agent_namespaces");
   },
 
-  /** 安装 Agent（统一安装） */
+  /** 安装 Agent（统一安装），warnings 为不阻止安装的兼容性等问题 */
   async installUnified(
     agent: DiscoverableAgent,
     currentApp: AppType,
     scope?: "global" | "project",
     projectPath?: string,
-  ): Promise<InstalledAgent> {
+  ): Promise<InstalledAgent & { warnings?: string[] }> {
     return await invoke("install_agent_unified", {
       agent,
       currentApp,
@@ -133,14 +162,35 @@ export const agentsApi = {
     });
   },
 
+  /** 一次安装 Agent 并启用到多个应用，不支持的应用会被跳过 */
+  async installForApps(
+    agent: DiscoverableAgent,
+    apps: AppId[],
+  ): Promise<MultiAppInstallResult<InstalledAgent>> {
+    return await invoke("install_agent_for_apps", { agent, apps });
+  },
+
+  /** 从粘贴的文件内容创建 Agent（未提供 id 时从 name 推导） */
+  async createFromContent(
+    content: string,
+    currentApp: AppType,
+    id?: string,
+  ): Promise<InstalledAgent> {
+    return await invoke("create_agent_from_content", {
+      content,
+      currentApp,
+      id,
+    });
+  },
+
   /** 卸载 Agent（统一卸载） */
   async uninstallUnified(id: string): Promise<boolean> {
     return await invoke("uninstall_agent_unified", { id });
   },
 
-  /** 批量卸载 Agents */
-  async uninstallBatch(ids: string[]): Promise<number> {
-    return await invoke("uninstall_agents_batch", { ids });
+  /** 批量卸载 Agents（目标中有锁定资源时需 force） */
+  async uninstallBatch(ids: string[], force?: boolean): Promise<number> {
+    return await invoke("uninstall_agents_batch", { ids, force });
   },
 
   /** 切换 Agent 的应用启用状态 */
@@ -152,6 +202,29 @@ export const agentsApi = {
     return await invoke("toggle_agent_app", { id, app, enabled });
   },
 
+  /** 为命名空间下的全部 Agents 切换应用启用状态（根命名空间传空字符串），返回变更数量 */
+  async toggleNamespaceApp(
+    namespace: string,
+    app: AppType,
+    enabled: boolean,
+  ): Promise<number> {
+    return await invoke("toggle_agent_namespace_app", {
+      namespace,
+      app,
+      enabled,
+    });
+  },
+
+  /** 设置 Agent 的用户备注 */
+  async setUserNote(id: string, note: string | null): Promise<boolean> {
+    return await invoke("set_agent_user_note", { id, note });
+  },
+
+  /** 设置 Agent 的自定义显示名称（传 null 恢复上游名称） */
+  async setDisplayName(id: string, displayName: string | null): Promise<boolean> {
+    return await invoke("set_agent_display_name", { id, displayName });
+  },
+
   /** 修改 Agent 的安装范围 */
   async changeScope(
     id: string,
@@ -167,6 +240,11 @@ export const agentsApi = {
     });
   },
 
+  /** 切换 Agent 的来源分支（先校验文件在新分支上存在） */
+  async switchBranch(id: string, newBranch: string): Promise<InstalledAgent> {
+    return await invoke("switch_agent_branch", { id, newBranch });
+  },
+
   /** 创建命名空间 */
   async createNamespace(namespace: string): Promise<boolean> {
     return await invoke("create_agent_namespace", { namespace });
@@ -195,6 +273,23 @@ export const agentsApi = {
     return await invoke("discover_available_agents", { forceRefresh });
   },
 
+  /**
+   * 分页发现可安装的 Agents（仓库完整扫描并缓存，仅分页返回）
+   * @param page 页码（从 0 开始）
+   * @param pageSize 每页条目数（不传则返回全部）
+   */
+  async discoverAvailablePaged(
+    page = 0,
+    pageSize?: number,
+    forceRefresh = false,
+  ): Promise<PaginatedDiscovery<DiscoverableAgent>> {
+    return await invoke("discover_available_agents_paged", {
+      forceRefresh,
+      page,
+      pageSize,
+    });
+  },
+
   // ========== 文件操作 API ==========
 
   /** 获取 Agent 文件内容 */
@@ -202,6 +297,11 @@ export const agentsApi = {
     return await invoke("get_agent_content", { id });
   },
 
+  /** 列出 frontmatter 存在解析问题的已安装 Agents */
+  async listParseIssues(): Promise<FileParseIssues[]> {
+    return await invoke("list_agent_parse_issues");
+  },
+
   /** 在外部编辑器中打开 Agent */
   async openInEditor(id: string): Promise<boolean> {
     return await invoke("open_agent_in_editor", { id });
@@ -212,6 +312,14 @@ export const agentsApi = {
     return await invoke("check_app_agents_support_cmd", { app });
   },
 
+  /** 检查 Agent 在目标应用中的模型/工具兼容性（安装前提示） */
+  async checkCompatibility(
+    agent: DiscoverableAgent,
+    app: AppType,
+  ): Promise<CompatWarning[]> {
+    return await invoke("check_agent_compatibility", { agent, app });
+  },
+
   // ========== 仓库管理 API（与 Commands 共用表） ==========
 
   /** 获取仓库列表 */
@@ -219,8 +327,8 @@ export const agentsApi = {
     return await invoke("get_agent_repos");
   },
 
-  /** 添加仓库 */
-  async addRepo(repo: CommandRepo): Promise<boolean> {
+  /** 添加仓库（添加前校验仓库、分支与资源目录，失败时抛出错误；离线时返回 warning） */
+  async addRepo(repo: CommandRepo): Promise<RepoValidation> {
     return await invoke("add_agent_repo", { repo });
   },
 
@@ -261,7 +369,17 @@ export const agentsApi = {
   },
 
   /** 同步所有 Agents 到应用目录 */
-  async syncToApps(): Promise<number> {
+  async syncToApps(): Promise<SyncReport> {
     return await invoke("sync_agents_to_apps");
   },
+
+  /** 只同步内容与 SSOT 不一致的 Agents */
+  async syncDriftedToApps(): Promise<DriftSyncReport> {
+    return await invoke("sync_drifted_agents_to_apps");
+  },
+
+  /** 预演同步 Agents，返回每项将执行的操作（不写入磁盘） */
+  async planSyncToApps(): Promise<SyncAction[]> {
+    return await invoke("plan_sync_agents_to_apps");
+  },
 };
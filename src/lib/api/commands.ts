@@ -1,4 +1,9 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { InstalledAgent } from "./agents";
+import type { ConfigTarget, MultiAppInstallResult } from "./config";
+import type { InstalledHook } from "./hooks";
+import type { InstalledSkill } from "./skills";
+import type { AppId } from "./types";
 
 // ========== 类型定义 ==========
 
@@ -11,6 +16,64 @@ export interface CommandApps {
   gemini: boolean;
 }
 
+/** 清理发现缓存时各表删除的行数 */
+export interface DiscoveryCachePruneResult {
+  commands: number;
+  agents: number;
+  hooks: number;
+}
+
+/** frontmatter 解析问题（解析仍然宽松，仅用于提示） */
+export interface ParseWarning {
+  /** yaml_fallback：改用了容错解析；unparsed_field：该字段被忽略 */
+  kind: "yaml_fallback" | "unparsed_field";
+  field?: string;
+  message: string;
+}
+
+/** 存在解析问题的已安装文件 */
+export interface FileParseIssues {
+  id: string;
+  name: string;
+  warnings: ParseWarning[];
+}
+
+/** 从某个仓库安装的全部资源 */
+export interface InstalledByRepo {
+  commands: InstalledCommand[];
+  agents: InstalledAgent[];
+  hooks: InstalledHook[];
+  skills: InstalledSkill[];
+}
+
+/** 按仓库批量卸载的结果（按类型列出已卸载的 ID） */
+export interface RepoUninstallResult {
+  commands: string[];
+  agents: string[];
+  hooks: string[];
+  skills: string[];
+  /** 未能卸载的资源及原因 */
+  failed: string[];
+}
+
+/** ccswitch.json 中单个资源的展示覆盖（只填补 frontmatter 缺失的字段） */
+export interface ResourceOverride {
+  name?: string;
+  description?: string;
+  category?: string;
+}
+
+/** 仓库根目录 ccswitch.json 的内容 */
+export interface RepoMetadata {
+  displayName?: string;
+  description?: string;
+  icon?: string;
+  commands?: Record<string, ResourceOverride>;
+  agents?: Record<string, ResourceOverride>;
+  hooks?: Record<string, ResourceOverride>;
+  skills?: Record<string, ResourceOverride>;
+}
+
 /** 已安装的 Command（v3.11.0+ 统一结构） */
 export interface InstalledCommand {
   id: string; // "namespace/filename" 或 "filename"
@@ -19,9 +82,13 @@ export interface InstalledCommand {
   namespace: string; // 空字符串表示根命名空间
   filename: string;
   category?: string;
+  categories?: string[];
   allowedTools?: string[];
   mcpServers?: string[];
   personas?: string[];
+  /** 运行所需的环境变量（frontmatter requires_env） */
+  requiresEnv?: string[];
+  /** 其他 frontmatter 字段，键名保持原样（如 "argument-hint"、"model"、"allowed-tools"） */
   extraMetadata?: Record<string, unknown>;
   repoOwner?: string;
   repoName?: string;
@@ -35,20 +102,130 @@ export interface InstalledCommand {
   scope: "global" | "project";
   /** 项目路径（当 scope="project" 时有效） */
   projectPath?: string;
+  /** 用户备注 */
+  userNote?: string;
+  /** 用户自定义显示名称（优先于 name 展示） */
+  displayNameOverride?: string;
 }
 
 /** 可发现的 Command（来自仓库） */
 export interface DiscoverableCommand {
   key: string;
   name: string;
+  /** 按界面语言选择的描述（缺少该语言时回退英文，再回退 frontmatter 的 description） */
   description: string;
   namespace: string;
   filename: string;
   category?: string;
+  categories?: string[];
   readmeUrl?: string;
   repoOwner: string;
   repoName: string;
   repoBranch: string;
+  /** 多语言描述（frontmatter 提供 description_zh 等字段时才有） */
+  description_zh?: string;
+  description_en?: string;
+  description_ja?: string;
+}
+
+/** 分页后的发现结果（Commands/Agents/Hooks 共用） */
+export interface PaginatedDiscovery<T> {
+  items: T[];
+  total: number; // 条目总数
+  page: number; // 页码，从 0 开始
+  pageSize: number;
+  hasMore: boolean;
+}
+
+/** 单个仓库在一次发现中的数据来源 */
+export interface RepoDiscoveryStatus {
+  owner: string;
+  name: string;
+  branch: string;
+  fromCache: boolean; // false 表示本次从 GitHub 获取
+  scannedAt?: number; // 来源数据的扫描时间（Unix 秒）
+  count: number;
+  error?: string;
+}
+
+/** 带仓库来源信息的发现结果 */
+export interface DiscoveryResult<T> {
+  items: T[];
+  repos: RepoDiscoveryStatus[];
+}
+
+/** 同步预演中的单个操作（Commands/Agents/Hooks 共用） */
+export interface SyncAction {
+  app: string;
+  id: string;
+  action: "create" | "overwrite" | "skip";
+  reason?: string;
+}
+
+/** 批量同步结果：单个文件失败不会中断整批同步 */
+export interface SyncReport {
+  synced: number;
+  failures: Array<{
+    app: string;
+    id: string;
+    kind: "permissionDenied" | "other"; // permissionDenied：目录只读或无写权限
+    message: string;
+  }>;
+}
+
+/** 仅同步漂移文件时被覆盖的文件 */
+export interface SyncedFile {
+  app: string;
+  id: string;
+}
+
+/** 仅同步漂移文件的结果：内容与 SSOT 一致的文件不会被改写 */
+export interface DriftSyncReport {
+  synced: SyncedFile[];
+  failures: SyncReport["failures"];
+}
+
+/** 按 key 批量安装时单个 key 的结果 */
+export interface KeyInstallResult {
+  key: string;
+  success: boolean;
+  /** 安装后的 Command ID */
+  installedId?: string;
+  error?: string;
+}
+
+/** 仓库校验结果（添加仓库时返回） */
+export interface RepoValidation {
+  branch: string; // 实际校验的分支
+  commands: number;
+  agents: number;
+  hooks: number;
+  warning?: string; // 离线或限流时未能校验，仓库仍已添加
+}
+
+/** 两个仓库的 Commands 重叠情况（按 key 比较） */
+export interface RepoOverlap {
+  onlyA: string[];
+  onlyB: string[];
+  conflicting: string[]; // 同 key 不同内容
+  identical: string[];
+  failed: string[]; // 内容获取失败、无法比较（"key: 错误"）
+}
+
+/** 导入仓库列表的结果 */
+export interface RepoImportResult {
+  /** 新添加的仓库（owner/name） */
+  added: string[];
+  /** 跳过的仓库（内置、已存在或文件内重复） */
+  skipped: string[];
+}
+
+/** 应用上已启用 Commands 可调用的工具汇总 */
+export interface EffectiveAllowedTools {
+  /** 所有已启用 Commands 的 allowedTools 并集 */
+  tools: string[];
+  /** 其中的高权限工具（执行命令、修改文件或访问网络） */
+  powerful: string[];
 }
 
 /** 命名空间信息 */
@@ -66,6 +243,8 @@ export interface UnmanagedCommand {
   namespace: string;
   filename: string;
   foundIn: string[]; // 发现于哪些应用目录
+  /** 既没有 frontmatter 也没有斜杠命令特征，可能是误放入的笔记 */
+  likelyNotACommand: boolean;
 }
 
 /** 仓库配置 */
@@ -84,6 +263,18 @@ export interface CommandRepo {
   description_ja?: string;
   /** 添加时间戳（内置仓库为 0） */
   added_at: number;
+  /** 从发现列表中排除的 key（不影响已安装项） */
+  excluded_keys?: string[];
+  /** 仓库是否提供 Commands（未扫描时缺省） */
+  has_commands?: boolean;
+  /** 仓库是否提供 Agents（未扫描时缺省） */
+  has_agents?: boolean;
+  /** 仓库是否提供 Hooks（未扫描时缺省） */
+  has_hooks?: boolean;
+  /** 用户修改过描述（内置仓库同步时保留） */
+  user_edited_description?: boolean;
+  /** 仅浏览：可在发现列表中阅读，但不能安装新资源 */
+  browse_only?: boolean;
 }
 
 /** 变更事件类型 */
@@ -102,16 +293,51 @@ export interface ChangeEvent {
 }
 
 /** 冲突解决选项 */
+/** SSOT 对账结果 */
+export interface ReconcileReport {
+  adopted: number; // 新纳入管理的 SSOT 文件
+  updated: number; // 已刷新元数据的记录
+  removed: number; // 文件丢失而删除的记录
+}
+
 export type ConflictResolution = "keepSsot" | "keepApp";
 
+/** Recipe 中单个条目的安装状态 */
+export type RecipeItemStatus =
+  | "installed"
+  | "already_installed"
+  | "resumed"
+  | "skipped"
+  | "failed";
+
+export interface RecipeItemResult {
+  resourceType: ConfigTarget;
+  key: string;
+  status: RecipeItemStatus;
+  error?: string | null;
+}
+
+/** Recipe 安装结果，items 按实际安装顺序排列 */
+export interface RecipeInstallResult {
+  name: string;
+  items: RecipeItemResult[];
+  installed: number;
+  failed: number;
+  /** 未全部完成时返回，传给 installRecipe 以从中断处继续 */
+  resumeToken?: string | null;
+}
+
 // ========== API ==========
 
 export const commandsApi = {
   // ========== 统一管理 API (v3.11.0+) ==========
 
-  /** 获取所有已安装的 Commands */
-  async getInstalled(): Promise<InstalledCommand[]> {
-    return await invoke("get_installed_commands");
+  /**
+   * 获取所有已安装的 Commands
+   * @param category 仅返回属于该分类的 Commands（可选）
+   */
+  async getInstalled(category?: string): Promise<InstalledCommand[]> {
+    return await invoke("get_installed_commands", { category });
   },
 
   /** 获取所有命名空间 */
@@ -119,13 +345,20 @@ export const commandsApi = {
     return await invoke("get_command_namespaces");
   },
 
-  /** 安装 Command（统一安装） */
+  /** 汇总指定应用上已启用 Commands 可调用的工具（安全审查用） */
+  async getEffectiveAllowedTools(
+    app: AppType,
+  ): Promise<EffectiveAllowedTools> {
+    return await invoke("get_effective_allowed_tools", { app });
+  },
+
+  /** 安装 Command（统一安装），warnings 为不阻止安装的 frontmatter 解析问题 */
   async installUnified(
     command: DiscoverableCommand,
     currentApp: AppType,
     scope?: "global" | "project",
     projectPath?: string,
-  ): Promise<InstalledCommand> {
+  ): Promise<InstalledCommand & { warnings?: string[] }> {
     return await invoke("install_command_unified", {
       command,
       currentApp,
@@ -134,14 +367,69 @@ export const commandsApi = {
     });
   },
 
+  /** 一次安装 Command 并启用到多个应用，不支持的应用会被跳过 */
+  async installForApps(
+    command: DiscoverableCommand,
+    apps: AppId[],
+  ): Promise<MultiAppInstallResult<InstalledCommand>> {
+    return await invoke("install_command_for_apps", { command, apps });
+  },
+
+  /** 从给定目录向上检测项目根目录（包含 .git 或 .claude），未找到时返回 null */
+  async detectCurrentProject(cwd: string): Promise<string | null> {
+    return await invoke("detect_current_project", { cwd });
+  },
+
+  /** 安装 Command 到从 cwd 检测到的项目（项目范围），未找到项目时报错 */
+  async installToDetectedProject(
+    command: DiscoverableCommand,
+    currentApp: AppId,
+    cwd: string,
+  ): Promise<InstalledCommand> {
+    return await invoke("install_command_to_detected_project", {
+      command,
+      currentApp,
+      cwd,
+    });
+  },
+
+  /**
+   * 按 key 列表批量安装（如团队共享的列表，每行一个 key，`#` 开头为注释）
+   * @param preferredRepo key 存在于多个仓库时使用的仓库（`owner/name`）
+   */
+  async installByKeys(
+    keys: string[],
+    currentApp: AppType,
+    preferredRepo?: string,
+  ): Promise<KeyInstallResult[]> {
+    return await invoke("install_commands_by_keys", {
+      keys,
+      currentApp,
+      preferredRepo,
+    });
+  },
+
+  /** 从粘贴的文件内容创建 Command（未提供 id 时从 name 推导） */
+  async createFromContent(
+    content: string,
+    currentApp: AppType,
+    id?: string,
+  ): Promise<InstalledCommand> {
+    return await invoke("create_command_from_content", {
+      content,
+      currentApp,
+      id,
+    });
+  },
+
   /** 卸载 Command（统一卸载） */
   async uninstallUnified(id: string): Promise<boolean> {
     return await invoke("uninstall_command_unified", { id });
   },
 
-  /** 批量卸载 Commands */
-  async uninstallBatch(ids: string[]): Promise<number> {
-    return await invoke("uninstall_commands_batch", { ids });
+  /** 批量卸载 Commands（目标中有锁定资源时需 force） */
+  async uninstallBatch(ids: string[], force?: boolean): Promise<number> {
+    return await invoke("uninstall_commands_batch", { ids, force });
   },
 
   /** 切换 Command 的应用启用状态 */
@@ -153,6 +441,29 @@ export const commandsApi = {
     return await invoke("toggle_command_app", { id, app, enabled });
   },
 
+  /** 为命名空间下的全部 Commands 切换应用启用状态（根命名空间传空字符串），返回变更数量 */
+  async toggleNamespaceApp(
+    namespace: string,
+    app: AppType,
+    enabled: boolean,
+  ): Promise<number> {
+    return await invoke("toggle_command_namespace_app", {
+      namespace,
+      app,
+      enabled,
+    });
+  },
+
+  /** 设置 Command 的用户备注 */
+  async setUserNote(id: string, note: string | null): Promise<boolean> {
+    return await invoke("set_command_user_note", { id, note });
+  },
+
+  /** 设置 Command 的自定义显示名称（传 null 恢复上游名称） */
+  async setDisplayName(id: string, displayName: string | null): Promise<boolean> {
+    return await invoke("set_command_display_name", { id, displayName });
+  },
+
   /** 修改 Command 的安装范围 */
   async changeScope(
     id: string,
@@ -168,6 +479,11 @@ export const commandsApi = {
     });
   },
 
+  /** 切换 Command 的来源分支（先校验文件在新分支上存在） */
+  async switchBranch(id: string, newBranch: string): Promise<InstalledCommand> {
+    return await invoke("switch_command_branch", { id, newBranch });
+  },
+
   /** 创建命名空间 */
   async createNamespace(namespace: string): Promise<boolean> {
     return await invoke("create_command_namespace", { namespace });
@@ -191,11 +507,54 @@ export const commandsApi = {
   /**
    * 发现可安装的 Commands（从仓库获取，带缓存支持）
    * @param forceRefresh 是否强制刷新（跳过缓存）
+   * @param category 仅返回属于该分类的 Commands（可选）
    */
   async discoverAvailable(
     forceRefresh = false,
+    category?: string,
   ): Promise<DiscoverableCommand[]> {
-    return await invoke("discover_available_commands", { forceRefresh });
+    return await invoke("discover_available_commands", {
+      forceRefresh,
+      category,
+    });
+  },
+
+  /**
+   * 发现可安装的 Commands，并返回每个仓库的数据来源（缓存/新获取）与扫描时间
+   */
+  async discoverAvailableDetailed(
+    forceRefresh = false,
+    category?: string,
+  ): Promise<DiscoveryResult<DiscoverableCommand>> {
+    return await invoke("discover_available_commands_detailed", {
+      forceRefresh,
+      category,
+    });
+  },
+
+  /**
+   * 分页发现可安装的 Commands（仓库完整扫描并缓存，仅分页返回）
+   * @param page 页码（从 0 开始）
+   * @param pageSize 每页条目数（不传则返回全部）
+   */
+  async discoverAvailablePaged(
+    page = 0,
+    pageSize?: number,
+    forceRefresh = false,
+  ): Promise<PaginatedDiscovery<DiscoverableCommand>> {
+    return await invoke("discover_available_commands_paged", {
+      forceRefresh,
+      page,
+      pageSize,
+    });
+  },
+
+  /** 比较两个仓库中重叠的 Commands，便于决定保留哪个仓库 */
+  async compareRepos(
+    repoA: CommandRepo,
+    repoB: CommandRepo,
+  ): Promise<RepoOverlap> {
+    return await invoke("compare_command_repos", { repoA, repoB });
   },
 
   // ========== 文件操作 API ==========
@@ -205,6 +564,11 @@ export const commandsApi = {
     return await invoke("get_command_content", { id });
   },
 
+  /** 列出 frontmatter 存在解析问题的已安装 Commands */
+  async listParseIssues(): Promise<FileParseIssues[]> {
+    return await invoke("list_command_parse_issues");
+  },
+
   /** 在外部编辑器中打开 Command */
   async openInEditor(id: string): Promise<boolean> {
     return await invoke("open_command_in_editor", { id });
@@ -222,8 +586,16 @@ export const commandsApi = {
     return await invoke("get_command_repos");
   },
 
-  /** 添加仓库 */
-  async addRepo(repo: CommandRepo): Promise<boolean> {
+  /** 获取仓库缓存的 ccswitch.json 元数据（未提供或未发现过时为 null） */
+  async getRepoMetadata(
+    owner: string,
+    name: string,
+  ): Promise<RepoMetadata | null> {
+    return await invoke("get_repo_metadata", { owner, name });
+  },
+
+  /** 添加仓库（添加前校验仓库、分支与资源目录，失败时抛出错误；离线时返回 warning） */
+  async addRepo(repo: CommandRepo): Promise<RepoValidation> {
     return await invoke("add_command_repo", { repo });
   },
 
@@ -232,6 +604,61 @@ export const commandsApi = {
     return await invoke("remove_command_repo", { owner, name });
   },
 
+  /** 列出从某个仓库安装的全部资源，移除仓库前用于展示卸载计划 */
+  async listInstalledByRepo(
+    owner: string,
+    name: string,
+  ): Promise<InstalledByRepo> {
+    return await invoke("list_installed_by_repo", { owner, name });
+  },
+
+  /** 卸载从某个仓库安装的全部资源（调用前需用户确认卸载计划；含锁定资源时需 force） */
+  async uninstallAllFromRepo(
+    owner: string,
+    name: string,
+    force?: boolean,
+  ): Promise<RepoUninstallResult> {
+    return await invoke("uninstall_all_from_repo", { owner, name, force });
+  },
+
+  /** 从仓库的发现结果中排除某个 Command（已安装的不受影响） */
+  async excludeFromRepo(
+    owner: string,
+    name: string,
+    key: string,
+  ): Promise<boolean> {
+    return await invoke("exclude_command_from_repo", { owner, name, key });
+  },
+
+  /** 取消排除仓库中的某个 Command */
+  async unexcludeFromRepo(
+    owner: string,
+    name: string,
+    key: string,
+  ): Promise<boolean> {
+    return await invoke("unexclude_command_from_repo", { owner, name, key });
+  },
+
+  /**
+   * 安装仓库中 recipe.json 列出的整套资源（按依赖顺序，逐项返回结果）
+   * @param recipePath recipe.json 在仓库中的路径
+   * @param resumeToken 上次安装返回的 resumeToken，传入时跳过已完成的条目；
+   *   不传时按仓库、recipe 路径和应用自动续接上次中断的进度
+   */
+  async installRecipe(
+    repo: CommandRepo,
+    recipePath: string,
+    currentApp: AppType,
+    resumeToken?: string,
+  ): Promise<RecipeInstallResult> {
+    return await invoke("install_recipe", {
+      repo,
+      recipePath,
+      currentApp,
+      resumeToken,
+    });
+  },
+
   /** 恢复内置仓库（添加缺失的内置仓库，不删除用户添加的） */
   async restoreBuiltinRepos(): Promise<number> {
     return await invoke("restore_builtin_command_repos");
@@ -242,6 +669,59 @@ export const commandsApi = {
     return await invoke("is_builtin_command_repo", { owner, name });
   },
 
+  /** 修改仓库描述（标记为用户编辑，内置仓库同步时不再覆盖） */
+  async updateRepoDescriptions(
+    owner: string,
+    name: string,
+    descriptions: {
+      descriptionZh?: string;
+      descriptionEn?: string;
+      descriptionJa?: string;
+    },
+  ): Promise<boolean> {
+    return await invoke("update_command_repo_descriptions", {
+      owner,
+      name,
+      ...descriptions,
+    });
+  },
+
+  /** 设置或清除仓库的描述编辑标记（清除后下次同步恢复内置描述） */
+  async setRepoUserEditedDescription(
+    owner: string,
+    name: string,
+    edited: boolean,
+  ): Promise<boolean> {
+    return await invoke("set_command_repo_user_edited_description", {
+      owner,
+      name,
+      edited,
+    });
+  },
+
+  /** 导出仓库列表（JSON，不含资源） */
+  async exportRepos(): Promise<string> {
+    return await invoke("export_command_repos");
+  },
+
+  /** 导入仓库列表（跳过内置仓库和已存在的仓库） */
+  async importRepos(json: string): Promise<RepoImportResult> {
+    return await invoke("import_command_repos", { json });
+  },
+
+  /** 设置仓库的仅浏览标记（仅浏览的仓库不能安装新资源） */
+  async setRepoBrowseOnly(
+    owner: string,
+    name: string,
+    browseOnly: boolean,
+  ): Promise<boolean> {
+    return await invoke("set_command_repo_browse_only", {
+      owner,
+      name,
+      browseOnly,
+    });
+  },
+
   /**
    * 清除 Commands 发现缓存
    * @param owner 仓库所有者（可选，不提供则清除全部）
@@ -252,6 +732,11 @@ export const commandsApi = {
     return await invoke("clear_command_cache", { owner, name });
   },
 
+  /** 清理已删除仓库残留的 Commands/Agents/Hooks 发现缓存，返回各表删除的行数 */
+  async pruneOrphanedCaches(): Promise<DiscoveryCachePruneResult> {
+    return await invoke("prune_orphaned_caches");
+  },
+
   // ========== 变更检测 API ==========
 
   /** 检测 Commands 变更 */
@@ -273,8 +758,23 @@ export const commandsApi = {
     return await invoke("refresh_commands_from_ssot");
   },
 
+  /** 以 SSOT 目录为准对账数据库（纳入新文件、刷新变更、删除丢失记录） */
+  async reconcileFromSsot(): Promise<ReconcileReport> {
+    return await invoke("reconcile_commands_from_ssot");
+  },
+
   /** 同步所有 Commands 到应用目录 */
-  async syncToApps(): Promise<number> {
+  async syncToApps(): Promise<SyncReport> {
     return await invoke("sync_commands_to_apps");
   },
+
+  /** 只同步内容与 SSOT 不一致的 Commands */
+  async syncDriftedToApps(): Promise<DriftSyncReport> {
+    return await invoke("sync_drifted_commands_to_apps");
+  },
+
+  /** 预演同步 Commands，返回每项将执行的操作（不写入磁盘） */
+  async planSyncToApps(): Promise<SyncAction[]> {
+    return await invoke("plan_sync_commands_to_apps");
+  },
 };
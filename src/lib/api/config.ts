@@ -1,8 +1,13 @@
 // 配置相关 API
 import { invoke } from "@tauri-apps/api/core";
+import type { AppId } from "./types";
+import type { ResourceType } from "./update";
 
 export type AppType = "claude" | "codex" | "gemini" | "omo" | "omo_slim";
 
+/** 配置操作的目标类型：在 ResourceType 之外加上 MCP 服务器（锁定、跨应用复制等） */
+export type ConfigTarget = ResourceType | "mcp";
+
 /**
  * 获取 Claude 通用配置片段（已废弃，使用 getCommonConfigSnippet）
  * @returns 通用配置片段（JSON 字符串），如果不存在则返回 null
@@ -75,3 +80,220 @@ export async function extractCommonConfigSnippet(
 
   return invoke<string>("extract_common_config_snippet", args);
 }
+
+/** 单类资源的同步状态计数 */
+export interface ResourceSyncStatus {
+  total: number;
+  inSync: number;
+  drifted: number; // SSOT 在安装后被修改
+  conflicted: number; // 应用目录与 SSOT 不一致
+  orphaned: number; // SSOT 文件丢失
+}
+
+export interface SyncStatus {
+  commands: ResourceSyncStatus;
+  agents: ResourceSyncStatus;
+  hooks: ResourceSyncStatus;
+  skills: ResourceSyncStatus;
+}
+
+/**
+ * 获取 Commands/Agents/Hooks/Skills 的同步状态汇总（仅计数，适合轮询显示徽标）
+ */
+export async function getSyncStatus(): Promise<SyncStatus> {
+  return invoke<SyncStatus>("get_sync_status");
+}
+
+/** 单个应用中已启用的各类资源数量 */
+export interface ResourceCounts {
+  commands: number;
+  agents: number;
+  hooks: number; // 仅统计全局启用的 Hooks
+  skills: number;
+  mcpServers: number;
+}
+
+/**
+ * 按应用统计已启用的资源数量（后端 SQL 聚合，无需拉取完整列表）
+ */
+export async function getResourceCounts(): Promise<
+  Partial<Record<AppId, ResourceCounts>>
+> {
+  return invoke("get_resource_counts");
+}
+
+export type IntegrityIssueKind =
+  | "hash_mismatch"
+  | "missing_file"
+  | "unreadable"
+  | "missing_env"
+  | "unknown_event_type";
+
+/** 一次安装到多个应用的结果 */
+export interface MultiAppInstallResult<T> {
+  installed: T;
+  /** 已启用的应用 */
+  enabledApps: AppId[];
+  /** 不支持该资源类型而跳过的应用说明 */
+  skipped: string[];
+  /** 启用失败的应用说明（资源已安装，可稍后单独启用） */
+  failed: string[];
+}
+
+/** 单条完整性问题 */
+export interface IntegrityIssue {
+  resourceType: "command" | "agent" | "hook";
+  id: string;
+  kind: IntegrityIssueKind;
+  expectedHash?: string | null;
+  actualHash?: string | null;
+  message?: string | null;
+}
+
+/**
+ * 校验 Commands/Agents/Hooks 的 SSOT 文件完整性（只读，仅本地哈希，不访问网络）
+ */
+export async function verifyIntegrity(): Promise<IntegrityIssue[]> {
+  return invoke<IntegrityIssue[]>("verify_integrity");
+}
+
+/**
+ * 获取启动时完整性校验发现的问题（同时通过 `integrity-issues` 事件推送）
+ */
+export async function getStartupIntegrityIssues(): Promise<IntegrityIssue[]> {
+  return invoke<IntegrityIssue[]>("get_startup_integrity_issues");
+}
+
+export interface CopiedResource {
+  resourceType: ConfigTarget;
+  id: string;
+}
+
+export interface SkippedResource {
+  resourceType: ConfigTarget;
+  id?: string | null; // 为空表示整个资源类型被跳过
+  reason: string;
+}
+
+/** 跨应用复制配置的结果 */
+export interface CopyConfigSummary {
+  copied: CopiedResource[];
+  alreadyEnabled: number;
+  skipped: SkippedResource[];
+}
+
+/** 按应用批量启用/禁用的结果 */
+export interface BulkToggleResult {
+  changed: number;
+  failed: SkippedResource[]; // id 为空表示整类同步失败
+}
+
+/**
+ * 将源应用已启用的资源复制到目标应用（启用并同步），不支持的资源类型会被跳过
+ */
+export async function copyAppConfig(
+  from: AppId,
+  to: AppId,
+  resourceTypes: ConfigTarget[],
+): Promise<CopyConfigSummary> {
+  return invoke<CopyConfigSummary>("copy_app_config", {
+    from,
+    to,
+    resourceTypes,
+  });
+}
+
+/**
+ * 为指定应用启用所有已安装的资源并同步，返回变更数量与失败项（不支持的资源类型会被跳过）
+ */
+export async function enableAllForApp(
+  app: AppId,
+  resourceTypes: ResourceType[],
+): Promise<BulkToggleResult> {
+  return invoke<BulkToggleResult>("enable_all_for_app", {
+    app,
+    resourceTypes,
+  });
+}
+
+/**
+ * 为指定应用禁用所有已安装的资源并同步，返回变更数量与失败项
+ * 锁定的资源会被跳过，除非传入 force
+ */
+export async function disableAllForApp(
+  app: AppId,
+  resourceTypes: ResourceType[],
+  force?: boolean,
+): Promise<BulkToggleResult> {
+  return invoke<BulkToggleResult>("disable_all_for_app", {
+    app,
+    resourceTypes,
+    force,
+  });
+}
+
+/** 应用目录中存在、但 SSOT 中没有对应文件的资源文件 */
+export interface ExtraFile {
+  app: AppId;
+  id: string;
+  path: string;
+  size: number;
+  modifiedAt?: number | null;
+}
+
+/**
+ * 列出应用目录中没有 SSOT 对应文件的 Commands/Agents 文件，供用户选择导入或忽略
+ */
+export async function listExtraAppFiles(
+  resourceType: Extract<ResourceType, "command" | "agent">,
+): Promise<ExtraFile[]> {
+  return invoke<ExtraFile[]>("list_extra_app_files", { resourceType });
+}
+
+/** 单个路径的诊断信息 */
+export interface PathProbe {
+  path: string;
+  exists: boolean;
+  /** 路径不存在时表示能否在最近的上级目录中创建 */
+  writable: boolean;
+}
+
+/** 某个应用下某类资源的实际路径 */
+export interface PathReportEntry {
+  app: AppId;
+  resourceType: ConfigTarget;
+  /** SSOT 位置（MCP 为数据库文件） */
+  ssot?: PathProbe | null;
+  /** 应用侧目录或配置文件（已应用目录覆盖） */
+  appPath?: PathProbe | null;
+  error?: string | null;
+}
+
+export interface PathReport {
+  homeDir?: string | null;
+  configDir: PathProbe;
+  entries: PathReportEntry[];
+  /** 当前生效的应用目录覆盖 */
+  overrides: OverrideInfo[];
+}
+
+/** 设置中生效的应用配置目录覆盖 */
+export interface OverrideInfo {
+  app: AppId;
+  overridePath: string;
+  exists: boolean;
+}
+
+/**
+ * 获取各应用、各类资源实际生效的路径及其存在/可写状态，用于排查路径配置问题
+ */
+export async function getPathReport(): Promise<PathReport> {
+  return invoke<PathReport>("get_path_report");
+}
+
+/**
+ * 列出配置目录被覆盖的应用，排查"文件没有出现在预期位置"一类问题
+ */
+export async function getOverrideReport(): Promise<OverrideInfo[]> {
+  return invoke<OverrideInfo[]>("get_override_report");
+}
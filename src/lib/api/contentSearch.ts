@@ -0,0 +1,33 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ResourceType } from "./update";
+
+// ========== 类型定义 ==========
+
+/** 匹配的一行及其上下文 */
+export interface LineMatch {
+  /** 在文件中的行号（从 1 开始，包含 frontmatter） */
+  lineNumber: number;
+  text: string;
+  before?: string;
+  after?: string;
+}
+
+/** 单个资源的搜索结果 */
+export interface ContentHit {
+  id: string;
+  matches: LineMatch[];
+  /** 匹配行超过上限，只返回了前几处 */
+  truncated: boolean;
+}
+
+// ========== API ==========
+
+export const contentSearchApi = {
+  /** 在已安装资源的正文中搜索（不区分大小写） */
+  async search(
+    resourceType: ResourceType,
+    query: string,
+  ): Promise<ContentHit[]> {
+    return await invoke("search_content", { resourceType, query });
+  },
+};
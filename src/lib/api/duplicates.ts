@@ -0,0 +1,57 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { AppId } from "./types";
+import type { ResourceType } from "./update";
+
+// ========== 类型定义 ==========
+
+/** 重复组中的单个已安装资源 */
+export interface DuplicateItem {
+  id: string;
+  name: string;
+  /** 来源仓库（owner/name），本地导入的资源为空 */
+  repo?: string;
+  /** 已启用的应用 */
+  apps: AppId[];
+}
+
+/** 内容相同的一组已安装资源 */
+export interface DuplicateGroup {
+  resourceType: ResourceType;
+  contentHash: string;
+  items: DuplicateItem[];
+}
+
+/** 合并结果 */
+export interface MergeResult {
+  keptId: string;
+  /** 已卸载的副本 */
+  removed: string[];
+  /** 从副本合并过来、保留项原本未启用的应用 */
+  newlyEnabled: AppId[];
+  /** 未能卸载的副本及原因 */
+  failed: string[];
+}
+
+// ========== API ==========
+
+export const duplicatesApi = {
+  /** 查找内容重复的已安装资源（支持 command/agent/skill） */
+  async find(resourceType: ResourceType): Promise<DuplicateGroup[]> {
+    return await invoke("find_duplicate_installs", { resourceType });
+  },
+
+  /** 把副本的应用启用状态合并到 keepId，并卸载副本（副本中有锁定资源时需 force） */
+  async merge(
+    resourceType: ResourceType,
+    keepId: string,
+    removeIds: string[],
+    force?: boolean,
+  ): Promise<MergeResult> {
+    return await invoke("merge_duplicates", {
+      resourceType,
+      keepId,
+      removeIds,
+      force,
+    });
+  },
+};
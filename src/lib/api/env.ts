@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import type { EnvConflict, BackupInfo } from "@/types/env";
+import type { ResourceType } from "./update";
 
 /**
  * 环境变量管理 API
@@ -16,6 +17,19 @@ export async function checkEnvConflicts(
   return invoke<EnvConflict[]>("check_env_conflicts", { app: appType });
 }
 
+/**
+ * 检查已安装资源 requires_env 中声明的环境变量
+ * @param resourceType 资源类型 ("command" | "agent" | "hook")
+ * @param id 资源 ID
+ * @returns 未设置的环境变量名
+ */
+export async function checkEnvRequirements(
+  resourceType: ResourceType,
+  id: string,
+): Promise<string[]> {
+  return invoke<string[]>("check_env_requirements", { resourceType, id });
+}
+
 /**
  * 删除指定的环境变量 (会自动备份)
  * @param conflicts 要删除的环境变量冲突列表
@@ -1,4 +1,11 @@
 import { invoke } from "@tauri-apps/api/core";
+import type {
+  PaginatedDiscovery,
+  RepoValidation,
+  SyncAction,
+} from "./commands";
+import type { MultiAppInstallResult } from "./config";
+import type { AppId } from "./types";
 
 // ========== 类型定义 ==========
 
@@ -27,9 +34,19 @@ export type HookType =
 export interface HookRule {
   matcher: string; // "Bash", "Edit|Write", "*", ""
   hooks: HookType[];
+  /** 仅在指定操作系统上生效（省略时所有系统均生效） */
+  os?: "macos" | "windows" | "linux";
 }
 
 /** 已安装的 Hook */
+/** 从官方格式文件导入 Hooks 的结果 */
+export interface OfficialImportResult {
+  /** 新建的 Hooks（每个事件类型一个） */
+  imported: InstalledHook[];
+  /** 被跳过的事件及原因 */
+  warnings: string[];
+}
+
 export interface InstalledHook {
   id: string; // "namespace/filename" 或 "filename"
   name: string;
@@ -43,6 +60,7 @@ export interface InstalledHook {
   // 状态
   enabled: boolean; // 全局启用状态
   priority: number; // 执行优先级（数字越小越先执行）
+  requiresEnv?: string[]; // 运行所需的环境变量
 
   // 仓库信息
   repoOwner?: string;
@@ -62,12 +80,17 @@ export interface InstalledHook {
   scope: "global" | "project";
   /** 项目路径（当 scope="project" 时有效） */
   projectPath?: string;
+  /** 用户备注 */
+  userNote?: string;
+  /** 用户自定义显示名称（优先于 name 展示） */
+  displayNameOverride?: string;
 }
 
 /** 可发现的 Hook（来自仓库） */
 export interface DiscoverableHook {
   key: string; // 在仓库中的唯一标识
   name: string;
+  /** 按界面语言选择的描述（缺少该语言时回退英文，再回退 Hook JSON 的 description） */
   description?: string;
   namespace: string;
   filename: string;
@@ -81,6 +104,10 @@ export interface DiscoverableHook {
   repoBranch: string;
   readmeUrl?: string;
   sourcePath?: string;
+  /** 多语言描述（Hook JSON 提供 description_zh 等字段时才有） */
+  description_zh?: string;
+  description_en?: string;
+  description_ja?: string;
 }
 
 /** 命名空间信息 */
@@ -99,6 +126,70 @@ export interface UnmanagedHook {
   foundIn: string[]; // 发现于哪些应用目录
 }
 
+/** Hook 命令校验问题 */
+export interface HookCommandIssue {
+  hookId: string;
+  hookName: string;
+  app: string;
+  command: string;
+  target: string;
+  kind: "notFound" | "notExecutable" | "notInPath";
+  message: string;
+}
+
+/** Hook SSOT 文件的 JSON 校验结果 */
+export interface HookValidation {
+  hookId: string;
+  hookName: string;
+  enabled: boolean;
+  /** 解析错误；为空表示校验通过 */
+  error?: string;
+}
+
+/** 全量校验 Hook 文件的结果 */
+export interface HookValidationReport {
+  results: HookValidation[];
+  /** 因解析失败被停用的 Hook ID */
+  quarantined: string[];
+}
+
+/** Hook 试运行结果（单条命令） */
+export interface HookTestRunResult {
+  matcher: string;
+  command: string;
+  /** 退出码（超时被终止时为空） */
+  exitCode?: number;
+  stdout: string;
+  stderr: string;
+  timedOut: boolean;
+  durationMs: number;
+  /** 退出码为 2，按 Claude hook 协议表示阻止操作 */
+  blocked: boolean;
+}
+
+/** 重建 hooks 字段的结果摘要 */
+export interface HookRebuildSummary {
+  app: string;
+  hooksWritten: number;
+  eventCount: number;
+  backupPath?: string;
+}
+
+/** 批量移动中的一项（旧 ID → 新 ID） */
+export interface HookMove {
+  from: string;
+  to: string;
+}
+
+/** 批量移动命名空间的结果 */
+export interface BulkMoveResult {
+  moved: HookMove[];
+  /** 目标 ID 已被占用而跳过的项 */
+  collisions: HookMove[];
+  /** 移动失败的项（格式 "id: 错误"） */
+  failed: string[];
+}
+
 /** 仓库配置（与 Commands/Agents 共用） */
 export interface CommandRepo {
   owner: string;
@@ -147,6 +238,35 @@ export const hooksApi = {
     });
   },
 
+  /** 一次安装 Hook 并启用到多个应用，不支持的应用会被跳过 */
+  async installForApps(
+    hook: DiscoverableHook,
+    apps: AppId[],
+  ): Promise<MultiAppInstallResult<InstalledHook>> {
+    return await invoke("install_hook_for_apps", { hook, apps });
+  },
+
+  /** 从粘贴的Hook JSON创建 Hook（未提供 id 时从 name 推导） */
+  async createFromContent(
+    content: string,
+    currentApp: AppType,
+    id?: string,
+  ): Promise<InstalledHook> {
+    return await invoke("create_hook_from_content", {
+      content,
+      currentApp,
+      id,
+    });
+  },
+
+  /** 从本地的 Claude 官方 hooks 格式文件导入（每个事件类型一个 Hook） */
+  async importOfficial(
+    path: string,
+    currentApp: AppType,
+  ): Promise<OfficialImportResult> {
+    return await invoke("import_official_hooks", { path, currentApp });
+  },
+
   /** 卸载 Hook（统一卸载） */
   async uninstallUnified(id: string): Promise<boolean> {
     return await invoke("uninstall_hook_unified", { id });
@@ -166,6 +286,16 @@ export const hooksApi = {
     return await invoke("toggle_hook_app", { id, app, enabled });
   },
 
+  /** 设置 Hook 的用户备注 */
+  async setUserNote(id: string, note: string | null): Promise<boolean> {
+    return await invoke("set_hook_user_note", { id, note });
+  },
+
+  /** 设置 Hook 的自定义显示名称（传 null 恢复上游名称） */
+  async setDisplayName(id: string, displayName: string | null): Promise<boolean> {
+    return await invoke("set_hook_display_name", { id, displayName });
+  },
+
   /** 修改 Hook 的安装范围 */
   async changeScope(
     id: string,
@@ -181,6 +311,11 @@ export const hooksApi = {
     });
   },
 
+  /** 切换 Hook 的来源分支（先校验文件在新分支上存在） */
+  async switchBranch(id: string, newBranch: string): Promise<InstalledHook> {
+    return await invoke("switch_hook_branch", { id, newBranch });
+  },
+
   /** 更新 Hook 优先级 */
   async updatePriority(id: string, priority: number): Promise<boolean> {
     return await invoke("update_hook_priority", { id, priority });
@@ -201,11 +336,68 @@ export const hooksApi = {
     return await invoke("delete_hook_namespace", { namespace });
   },
 
+  /** 将 Hook 移动到另一个命名空间（空字符串表示根命名空间） */
+  async moveToNamespace(id: string, namespace: string): Promise<InstalledHook> {
+    return await invoke("move_hook_to_namespace", { id, namespace });
+  },
+
+  /**
+   * 将 ID 匹配通配模式的 Hooks 批量移动到另一个命名空间
+   * （`*` 不跨越 `/`，`**` 可跨越 `/`）
+   */
+  async bulkMoveNamespace(
+    pattern: string,
+    namespace: string,
+  ): Promise<BulkMoveResult> {
+    return await invoke("bulk_move_hooks_namespace", { pattern, namespace });
+  },
+
   /** 扫描未管理的 Hooks */
   async scanUnmanaged(): Promise<UnmanagedHook[]> {
     return await invoke("scan_unmanaged_hooks");
   },
 
+  /**
+   * 预览 matcher 匹配的工具名
+   * @param tools 候选工具名，缺省时使用 Claude Code 内置工具列表
+   */
+  async previewMatcher(matcher: string, tools?: string[]): Promise<string[]> {
+    return await invoke("preview_hook_matcher", { matcher, tools });
+  },
+
+  /** 重建指定应用 settings.json 的 hooks 字段（会先备份原文件） */
+  async rebuildAppHooks(app: string): Promise<HookRebuildSummary> {
+    return await invoke("rebuild_app_hooks", { app });
+  },
+
+  /** 上次同步后应用配置中的 hooks 是否被外部修改（重新同步可能覆盖） */
+  async checkExternalModification(app: string): Promise<boolean> {
+    return await invoke("check_hooks_external_modification", { app });
+  },
+
+  /** 校验已安装 Hooks 的命令是否可执行 */
+  async validateCommands(): Promise<HookCommandIssue[]> {
+    return await invoke("validate_hook_commands");
+  },
+
+  /** 校验所有 Hook 的 JSON 文件，可选停用解析失败的 Hooks */
+  async validateAll(
+    quarantineInvalid = false,
+  ): Promise<HookValidationReport> {
+    return await invoke("validate_hooks", { quarantineInvalid });
+  },
+
+  /**
+   * 在临时工作目录中试运行 Hook 的命令，可传入自定义事件 JSON。
+   * 命令以当前用户权限运行，不做文件系统或网络隔离；超时时连同子进程一起终止
+   */
+  async testRun(
+    id: string,
+    simulatedEvent?: Record<string, unknown>,
+  ): Promise<HookTestRunResult[]> {
+    return await invoke("test_run_hook", { id, simulatedEvent });
+  },
+
   /**
    * 发现可安装的 Hooks（从仓库获取，带缓存支持）
    * @param forceRefresh 是否强制刷新（跳过缓存）
@@ -214,6 +406,23 @@ export const hooksApi = {
     return await invoke("discover_available_hooks", { forceRefresh });
   },
 
+  /**
+   * 分页发现可安装的 Hooks（仓库完整扫描并缓存，仅分页返回）
+   * @param page 页码（从 0 开始）
+   * @param pageSize 每页条目数（不传则返回全部）
+   */
+  async discoverAvailablePaged(
+    page = 0,
+    pageSize?: number,
+    forceRefresh = false,
+  ): Promise<PaginatedDiscovery<DiscoverableHook>> {
+    return await invoke("discover_available_hooks_paged", {
+      forceRefresh,
+      page,
+      pageSize,
+    });
+  },
+
   // ========== 文件操作 API ==========
 
   /** 获取 Hook 文件内容 */
@@ -238,8 +447,8 @@ export const hooksApi = {
     return await invoke("get_hook_repos");
   },
 
-  /** 添加仓库 */
-  async addRepo(repo: CommandRepo): Promise<boolean> {
+  /** 添加仓库（添加前校验仓库、分支与资源目录，失败时抛出错误；离线时返回 warning） */
+  async addRepo(repo: CommandRepo): Promise<RepoValidation> {
     return await invoke("add_hook_repo", { repo });
   },
 
@@ -269,4 +478,9 @@ export const hooksApi = {
   async syncToApps(): Promise<number> {
     return await invoke("sync_hooks_to_apps");
   },
+
+  /** 预演同步 Hooks，返回每项将执行的操作（不写入磁盘） */
+  async planSyncToApps(): Promise<SyncAction[]> {
+    return await invoke("plan_sync_hooks_to_apps");
+  },
 };
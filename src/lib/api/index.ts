@@ -3,17 +3,50 @@ export { providersApi, universalProvidersApi } from "./providers";
 export { settingsApi } from "./settings";
 export { backupsApi } from "./settings";
 export { mcpApi } from "./mcp";
+export type { McpWarning, McpWarningKind } from "./mcp";
 export { promptsApi } from "./prompts";
 export { skillsApi } from "./skills";
 export { usageApi } from "./usage";
 export { subscriptionApi } from "./subscription";
 export { vscodeApi } from "./vscode";
+export type { SpeedtestRecord } from "./vscode";
 export { proxyApi } from "./proxy";
 export { commandsApi } from "./commands";
 export { updateApi } from "./update";
 export { appUpdaterApi } from "./appUpdater";
 export type { SkippedVersionInfo, UpdaterConfigInfo } from "./appUpdater";
 export { projectApi } from "./project";
+export { trashApi } from "./trash";
+export type { TrashEntry, TrashedResource } from "./trash";
+export { contentSearchApi } from "./contentSearch";
+export type { ContentHit, LineMatch } from "./contentSearch";
+export { duplicatesApi } from "./duplicates";
+export type { DuplicateGroup, DuplicateItem, MergeResult } from "./duplicates";
+export { namespaceCheckApi } from "./namespaceCheck";
+export type {
+  NamespaceCleanupReport,
+  NamespaceMismatch,
+} from "./namespaceCheck";
+export { orphanedInstallsApi } from "./orphanedInstalls";
+export type { OrphanUninstallResult } from "./orphanedInstalls";
+export { pinStalenessApi } from "./pinStaleness";
+export type { PinnedChange, PinnedStatus } from "./pinStaleness";
+export { provenanceApi } from "./provenance";
+export type {
+  AlternativeSource,
+  InstallSource,
+  Provenance,
+} from "./provenance";
+export { repoCacheApi } from "./repoCache";
+export { resourceExportApi } from "./resourceExport";
+export type { ExportedResource, ExportManifest } from "./resourceExport";
+export { resourceLockApi } from "./resourceLock";
+export { resourcePreviewApi } from "./resourcePreview";
+export { resourceStreamApi } from "./resourceStream";
+export type {
+  InstalledResourceChunk,
+  InstalledResourceStreamDone,
+} from "./resourceStream";
 export type { ProjectInfo } from "./project";
 export { openclawApi } from "./openclaw";
 export { sessionsApi } from "./sessions";
@@ -21,7 +54,12 @@ export { workspaceApi } from "./workspace";
 export * as configApi from "./config";
 export * as authApi from "./auth";
 export * as copilotApi from "./copilot";
-export type { ProviderSwitchEvent } from "./providers";
+export type {
+  ProviderRotationPolicy,
+  ProviderRotationState,
+  ProviderSwitchEvent,
+  ProviderTemplate,
+} from "./providers";
 export type { Prompt } from "./prompts";
 export type {
   InstalledCommand,
@@ -30,15 +68,33 @@ export type {
   UnmanagedCommand,
   CommandRepo,
   ChangeEvent,
+  PaginatedDiscovery,
+  DiscoveryResult,
+  RepoDiscoveryStatus,
+  SyncAction,
+  SyncReport,
+  SyncedFile,
+  DriftSyncReport,
+  RepoOverlap,
+  EffectiveAllowedTools,
+  RepoImportResult,
+  KeyInstallResult,
+  ReconcileReport,
+  InstalledByRepo,
+  ParseWarning,
+  FileParseIssues,
+  RepoUninstallResult,
 } from "./commands";
 export type {
   ResourceType,
   UpdateCheckResult,
+  TagUpdateCheckResult,
   BatchCheckResult,
   RateLimitInfo,
   SkillUpdateResult,
   UpdateExecuteResult,
   BatchUpdateResult,
+  HashNormalizeResult,
 } from "./update";
 export type {
   CopilotDeviceCodeResponse,
@@ -8,6 +8,24 @@ import type {
 } from "@/types";
 import type { AppId } from "./types";
 
+/** MCP 配置检查发现的问题类型 */
+export type McpWarningKind =
+  /** 命令不在 CC Switch 进程的 PATH 中（终端中可能可用） */
+  | "missingCommand"
+  /** 环境变量在 CC Switch 进程中未定义（可能只在 shell 配置中设置） */
+  | "undefinedEnvVar"
+  | "circularEnvVar"
+  | "duplicateName"
+  | "invalidTransport";
+
+/** MCP 配置检查警告（非致命） */
+export interface McpWarning {
+  serverId: string;
+  serverName: string;
+  kind: McpWarningKind;
+  message: string;
+}
+
 export const mcpApi = {
   async getStatus(): Promise<McpStatus> {
     return await invoke("get_claude_mcp_status");
@@ -126,4 +144,13 @@ export const mcpApi = {
   async importFromApps(): Promise<number> {
     return await invoke("import_mcp_from_apps");
   },
+
+  /**
+   * 检查 MCP 配置中的常见问题（命令缺失、环境变量未定义或循环引用、重名、无效传输类型）
+   *
+   * 命令与环境变量按 CC Switch 进程自身的 PATH 和环境检查，可能与终端中的不同
+   */
+  async lint(): Promise<McpWarning[]> {
+    return await invoke("lint_mcp_servers");
+  },
 };
@@ -0,0 +1,51 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ResourceType } from "./update";
+
+// ========== 类型定义 ==========
+
+/** 数据库命名空间与 SSOT 文件位置不一致的记录 */
+export interface NamespaceMismatch {
+  /** 数据库中的 ID */
+  id: string;
+  storedNamespace: string;
+  /** 按 SSOT 文件路径计算出的命名空间 */
+  actualNamespace: string;
+  /** 文件实际位置对应的 ID（与 id 不同表示文件已被移动） */
+  actualId: string;
+}
+
+/** 空命名空间清理结果 */
+export interface NamespaceCleanupReport {
+  /** 已清理的命名空间 */
+  removed: string[];
+  /** 已删除的目录 */
+  removedDirs: string[];
+  /** 删除失败的目录（格式 "路径: 错误"） */
+  failed: string[];
+}
+
+// ========== API ==========
+
+export const namespaceCheckApi = {
+  /** 检查 Commands/Agents/Hooks 的命名空间与 SSOT 文件位置是否一致 */
+  async verify(resourceType: ResourceType): Promise<NamespaceMismatch[]> {
+    return await invoke("verify_namespace_consistency", { resourceType });
+  },
+
+  /** 按 SSOT 文件的实际位置修复命名空间，返回已修复的项 */
+  async repair(resourceType: ResourceType): Promise<NamespaceMismatch[]> {
+    return await invoke("repair_namespaces", { resourceType });
+  },
+
+  /** 列出数据库中没有记录、但目录仍残留的空命名空间 */
+  async listEmpty(resourceType: ResourceType): Promise<string[]> {
+    return await invoke("list_empty_namespaces", { resourceType });
+  },
+
+  /** 删除空命名空间在 SSOT 和各应用目录中的残留目录 */
+  async cleanupEmpty(
+    resourceType: ResourceType,
+  ): Promise<NamespaceCleanupReport> {
+    return await invoke("cleanup_empty_namespaces", { resourceType });
+  },
+};
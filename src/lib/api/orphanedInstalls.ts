@@ -0,0 +1,38 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ConfigTarget } from "./config";
+
+// ========== 类型定义 ==========
+
+/** 批量卸载未启用资源的结果 */
+export interface OrphanUninstallResult {
+  removed: string[];
+  /** 确认后已被重新启用或已不存在、因此未卸载的 ID */
+  skipped: string[];
+  /** 未能卸载的资源及原因（格式 "类型 id: 错误"） */
+  failed: string[];
+}
+
+// ========== API ==========
+
+export const orphanedInstallsApi = {
+  /** 列出已安装但没有在任何应用中启用的资源 ID */
+  async list(resourceType: ConfigTarget): Promise<string[]> {
+    return await invoke("list_orphaned_installs", { resourceType });
+  },
+
+  /**
+   * 卸载用户确认过的未启用资源（应先展示 list 的结果并确认）
+   * @param force 为 true 时忽略资源锁定；否则目标中有锁定资源时整批取消
+   */
+  async uninstall(
+    resourceType: ConfigTarget,
+    ids: string[],
+    force?: boolean,
+  ): Promise<OrphanUninstallResult> {
+    return await invoke("uninstall_orphaned_installs", {
+      resourceType,
+      ids,
+      force,
+    });
+  },
+};
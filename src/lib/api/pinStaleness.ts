@@ -0,0 +1,36 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ResourceType } from "./update";
+
+// ========== 类型定义 ==========
+
+/** 文件在当前分支与固定提交之间的差异 */
+export type PinnedChange = "unchanged" | "modified" | "deleted";
+
+/** 固定到提交 SHA 的资源状态 */
+export interface PinnedStatus {
+  resourceType: ResourceType;
+  id: string;
+  repoOwner: string;
+  repoName: string;
+  /** 固定的提交 SHA */
+  pinnedCommit: string;
+  /** 比较所用的分支 */
+  branch?: string;
+  /** 分支中的文件已与固定提交不同 */
+  behind: boolean;
+  change?: PinnedChange;
+  pinnedBlob?: string;
+  currentBlob?: string;
+  /** 固定提交到当前分支的 unified diff */
+  diff?: string;
+  error?: string;
+}
+
+// ========== API ==========
+
+export const pinStalenessApi = {
+  /** 检查固定到提交的资源在当前分支中是否已有变化 */
+  async check(): Promise<PinnedStatus[]> {
+    return await invoke("check_pinned_staleness");
+  },
+};
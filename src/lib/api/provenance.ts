@@ -0,0 +1,61 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ResourceType } from "./update";
+
+// ========== 类型定义 ==========
+
+/** 已安装资源的来源信息 */
+export interface Provenance {
+  repoOwner?: string;
+  repoName?: string;
+  repoBranch?: string;
+  /** 文件在仓库中的路径（Skills 为目录路径） */
+  sourcePath?: string;
+  readmeUrl?: string;
+  /** 获取文件时使用的 raw.githubusercontent.com 地址 */
+  rawUrl?: string;
+  installedAt: number;
+  /** 安装时记录的哈希（Git blob SHA / tree SHA） */
+  pinnedSha?: string;
+  /** 本地导入或手动创建，没有仓库来源 */
+  local: boolean;
+}
+
+/** 其他已配置仓库中同一 key 的副本 */
+export interface AlternativeSource {
+  repoOwner: string;
+  repoName: string;
+  repoBranch: string;
+  sourcePath?: string;
+  githubUrl?: string;
+  blobSha?: string;
+  /** 内容与已安装版本不同；无法比较时为空 */
+  differs?: boolean;
+  error?: string;
+}
+
+/** 资源的安装来源及其他仓库中的副本 */
+export interface InstallSource extends Provenance {
+  resourceType: ResourceType;
+  id: string;
+  /** 安装所用文件（Skills 为目录）的 GitHub 地址 */
+  githubUrl?: string;
+  /** 其他已启用仓库中同一 key 的副本 */
+  alternatives: AlternativeSource[];
+}
+
+// ========== API ==========
+
+export const provenanceApi = {
+  /** 获取已安装资源的来源信息（只读） */
+  async get(resourceType: ResourceType, id: string): Promise<Provenance> {
+    return await invoke("get_provenance", { resourceType, id });
+  },
+
+  /** 获取安装来源，并检查其他仓库中是否有同一 key 的不同版本 */
+  async getInstallSource(
+    resourceType: ResourceType,
+    id: string,
+  ): Promise<InstallSource> {
+    return await invoke("get_install_source", { resourceType, id });
+  },
+};
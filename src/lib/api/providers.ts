@@ -15,12 +15,55 @@ export interface ProviderSortUpdate {
 export interface ProviderSwitchEvent {
   appType: AppId;
   providerId: string;
+  /** 切换来源（如 "rotation"） */
+  source?: string;
+  /** 切换过程中产生的非致命警告 */
+  warnings?: string[];
 }
 
 export interface SwitchResult {
   warnings: string[];
 }
 
+export interface ProviderTemplateField {
+  key: string;
+  hint: { zh: string; en: string; ja: string };
+}
+
+/** 内置供应商模板（预填 Base URL 与默认模型） */
+export interface ProviderTemplate {
+  id: string;
+  name: string;
+  app: AppId;
+  baseUrl: string;
+  defaultModel: string;
+  websiteUrl?: string;
+  apiKeyUrl?: string;
+  category?: string;
+  icon?: string;
+  iconColor?: string;
+  requiredFields: ProviderTemplateField[];
+}
+
+/** 供应商轮换策略（按应用保存） */
+export interface ProviderRotationPolicy {
+  enabled: boolean;
+  /** 使用量达到限额的百分比时触发轮换（1-100） */
+  thresholdPercent: number;
+  /** 两次轮换之间的最短间隔（秒） */
+  cooldownSecs: number;
+  /** 轮换顺序；为空时使用故障转移队列的顺序 */
+  providerIds: string[];
+}
+
+/** 最近一次供应商轮换的状态 */
+export interface ProviderRotationState {
+  lastRotatedAt?: number;
+  lastFrom?: string;
+  lastTo?: string;
+  rotationCount: number;
+}
+
 export interface OpenTerminalOptions {
   cwd?: string;
 }
@@ -42,6 +85,20 @@ export const providersApi = {
     return await invoke("add_provider", { provider, app: appId, addToLive });
   },
 
+  async getTemplates(): Promise<ProviderTemplate[]> {
+    return await invoke("get_provider_templates");
+  },
+
+  async createFromTemplate(
+    templateId: string,
+    apiKey: string,
+  ): Promise<Provider> {
+    return await invoke("create_provider_from_template", {
+      templateId,
+      apiKey,
+    });
+  },
+
   async update(
     provider: Provider,
     appId: AppId,
@@ -85,6 +142,40 @@ export const providersApi = {
     return await invoke("update_providers_sort_order", { updates, app: appId });
   },
 
+  /**
+   * 开启/关闭供应商密钥加密（同时迁移已有密钥）
+   * @returns 被改写的供应商数量
+   */
+  async setKeyEncryption(enabled: boolean): Promise<number> {
+    return await invoke("set_provider_key_encryption", { enabled });
+  },
+
+  async getRotationPolicy(appId: AppId): Promise<ProviderRotationPolicy> {
+    return await invoke("get_provider_rotation_policy", { app: appId });
+  },
+
+  async setRotationPolicy(
+    appId: AppId,
+    policy: ProviderRotationPolicy,
+  ): Promise<void> {
+    return await invoke("set_provider_rotation_policy", {
+      app: appId,
+      policy,
+    });
+  },
+
+  async getRotationState(appId: AppId): Promise<ProviderRotationState> {
+    return await invoke("get_provider_rotation_state", { app: appId });
+  },
+
+  /**
+   * 当前供应商接近限额时轮换到下一个供应商
+   * @returns 切换到的供应商 ID，未轮换时为 null
+   */
+  async rotateIfNeeded(appId: AppId): Promise<string | null> {
+    return await invoke("rotate_provider_if_needed", { app: appId });
+  },
+
   async onSwitched(
     handler: (event: ProviderSwitchEvent) => void,
   ): Promise<UnlistenFn> {
@@ -0,0 +1,25 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ResourceType } from "./update";
+
+// ========== API ==========
+
+export const repoCacheApi = {
+  /**
+   * 只重新下载并扫描一个仓库，更新其发现缓存
+   * @param branch 为空时使用仓库配置的分支；与配置的分支不一致时报错
+   * @returns 该仓库中该类资源的新数量
+   */
+  async refresh(
+    owner: string,
+    name: string,
+    branch: string,
+    resourceType: ResourceType,
+  ): Promise<number> {
+    return await invoke("refresh_repo_cache", {
+      owner,
+      name,
+      branch,
+      resourceType,
+    });
+  },
+};
@@ -0,0 +1,47 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ResourceType } from "./update";
+
+// ========== 类型定义 ==========
+
+/** 导出清单（描述资源来源，不含本地状态） */
+export interface ExportManifest {
+  resourceType: ResourceType;
+  id: string;
+  name: string;
+  description?: string;
+  repoOwner?: string;
+  repoName?: string;
+  repoBranch?: string;
+  sourcePath?: string;
+  /** 导出内容的 SHA-256 */
+  contentHash: string;
+  exportedAt: number;
+  /** 导出时的 CC Switch 版本 */
+  appVersion: string;
+}
+
+/** 单个资源的导出结果 */
+export interface ExportedResource {
+  /** 建议的文件名（如 commit.md） */
+  filename: string;
+  content: string;
+  metadata: ExportManifest;
+  /** 写入的文件路径（未指定目标路径时为空） */
+  writtenPath?: string;
+}
+
+// ========== API ==========
+
+export const resourceExportApi = {
+  /**
+   * 导出单个 Command/Agent/Hook，便于分享
+   * @param targetPath 可选，目录或文件路径；提供时同时写入文件和 .manifest.json
+   */
+  async export(
+    resourceType: ResourceType,
+    id: string,
+    targetPath?: string,
+  ): Promise<ExportedResource> {
+    return await invoke("export_resource", { resourceType, id, targetPath });
+  },
+};
@@ -0,0 +1,20 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ConfigTarget } from "./config";
+
+// ========== API ==========
+
+export const resourceLockApi = {
+  /** 锁定或解锁资源（锁定的资源不参与批量更新、卸载与禁用） */
+  async setLocked(
+    resourceType: ConfigTarget,
+    id: string,
+    locked: boolean,
+  ): Promise<void> {
+    await invoke("set_resource_locked", { resourceType, id, locked });
+  },
+
+  /** 列出该类型下已锁定资源的 ID */
+  async getLocked(resourceType: ConfigTarget): Promise<string[]> {
+    return await invoke("get_locked_resources", { resourceType });
+  },
+};
@@ -0,0 +1,16 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ResourceType } from "./update";
+
+// ========== API ==========
+
+export const resourcePreviewApi = {
+  /** 获取资源正文渲染后的 HTML（后端已清理脚本等不安全内容） */
+  async getRendered(resourceType: ResourceType, id: string): Promise<string> {
+    return await invoke("get_resource_rendered", { resourceType, id });
+  },
+
+  /** 获取资源 SSOT 文件路径，供编辑使用 */
+  async getPath(resourceType: ResourceType, id: string): Promise<string> {
+    return await invoke("get_resource_path", { resourceType, id });
+  },
+};
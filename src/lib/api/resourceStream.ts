@@ -0,0 +1,73 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { ResourceType } from "./update";
+
+// ========== 类型定义 ==========
+
+/** 单批已安装资源 */
+export interface InstalledResourceChunk<T> {
+  requestId: string;
+  resourceType: ResourceType;
+  offset: number;
+  items: T[];
+}
+
+/** 分批推送完成事件 */
+export interface InstalledResourceStreamDone {
+  requestId: string;
+  resourceType: ResourceType;
+  total: number;
+}
+
+// ========== API ==========
+
+export const resourceStreamApi = {
+  /**
+   * 分批加载已安装资源（不支持 MCP）
+   * @param onChunk 每收到一批资源时调用
+   * @returns 资源总数，在完成事件到达后 resolve
+   */
+  async streamInstalled<T>(
+    resourceType: ResourceType,
+    onChunk: (items: T[], offset: number) => void,
+    chunkSize?: number,
+  ): Promise<number> {
+    const requestId = `${resourceType}-${Date.now()}-${Math.random()
+      .toString(36)
+      .slice(2)}`;
+
+    let resolveDone: (total: number) => void = () => {};
+    const done = new Promise<number>((resolve) => {
+      resolveDone = resolve;
+    });
+
+    const unlistenChunk = await listen<InstalledResourceChunk<T>>(
+      "installed-resources-chunk",
+      (event) => {
+        if (event.payload.requestId === requestId) {
+          onChunk(event.payload.items, event.payload.offset);
+        }
+      },
+    );
+    const unlistenDone = await listen<InstalledResourceStreamDone>(
+      "installed-resources-done",
+      (event) => {
+        if (event.payload.requestId === requestId) {
+          resolveDone(event.payload.total);
+        }
+      },
+    );
+
+    try {
+      await invoke("stream_installed_resources", {
+        resourceType,
+        requestId,
+        chunkSize,
+      });
+      return await done;
+    } finally {
+      unlistenChunk();
+      unlistenDone();
+    }
+  },
+};
@@ -215,6 +215,21 @@ export const settingsApi = {
   async setLogConfig(config: LogConfig): Promise<boolean> {
     return await invoke("set_log_config", { config });
   },
+
+  /** 运行时调整日志级别（同时开启日志），返回更新后的配置 */
+  async setLogLevel(level: LogConfig["level"]): Promise<LogConfig> {
+    return await invoke("set_log_level", { level });
+  },
+
+  /** 获取资源下载超时配置（秒） */
+  async getDownloadTimeouts(): Promise<TimeoutConfig> {
+    return await invoke("get_download_timeouts");
+  },
+
+  /** 设置资源下载超时配置，各项须在 1~3600 秒之间 */
+  async setDownloadTimeouts(config: TimeoutConfig): Promise<boolean> {
+    return await invoke("set_download_timeouts", { config });
+  },
 };
 
 export interface RectifierConfig {
@@ -235,12 +250,24 @@ export interface LogConfig {
   level: "error" | "warn" | "info" | "debug" | "trace";
 }
 
+export interface TimeoutConfig {
+  fileDownloadSecs: number;
+  repoDownloadSecs: number;
+  apiCallSecs: number;
+}
+
 export interface BackupEntry {
   filename: string;
   sizeBytes: number;
   createdAt: string;
 }
 
+/** Schema 自检发现的缺失列 */
+export interface MissingColumn {
+  table: string;
+  column: string;
+}
+
 export const backupsApi = {
   async createDbBackup(): Promise<string> {
     return await invoke("create_db_backup");
@@ -261,4 +288,19 @@ export const backupsApi = {
   async deleteDbBackup(filename: string): Promise<void> {
     await invoke("delete_db_backup", { filename });
   },
+
+  /** 将整个数据库快照到指定文件，返回快照的 Schema 版本 */
+  async snapshotDb(path: string): Promise<number> {
+    return await invoke("snapshot_db", { path });
+  },
+
+  /** 校验快照并安排在下次启动时恢复（需重启应用），返回快照的 Schema 版本 */
+  async restoreDb(path: string): Promise<number> {
+    return await invoke("restore_db", { path });
+  },
+
+  /** 列出资源表中缺失的列（为空表示结构完整） */
+  async verifySchema(): Promise<MissingColumn[]> {
+    return await invoke("verify_schema");
+  },
 };
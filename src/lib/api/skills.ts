@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 
 import type { AppId } from "@/lib/api/types";
+import type { MultiAppInstallResult } from "./config";
 
 export type AppType =
   | "claude"
@@ -145,6 +146,8 @@ export interface SkillRepo {
   description_ja?: string;
   /** 添加时间戳（内置仓库为 0） */
   added_at: number;
+  /** 仅浏览：可在发现列表中阅读，但不能安装新 Skill */
+  browse_only?: boolean;
 }
 
 /** Skill 冲突信息 */
@@ -190,14 +193,22 @@ export const skillsApi = {
     });
   },
 
+  /** 一次安装 Skill 并启用到多个应用，不支持的应用会被跳过 */
+  async installForApps(
+    skill: DiscoverableSkill,
+    apps: AppId[],
+  ): Promise<MultiAppInstallResult<InstalledSkill>> {
+    return await invoke("install_skill_for_apps", { skill, apps });
+  },
+
   /** 卸载 Skill（统一卸载） */
   async uninstallUnified(id: string): Promise<SkillUninstallResult> {
     return await invoke("uninstall_skill_unified", { id });
   },
 
-  /** 批量卸载 Skills */
-  async uninstallBatch(ids: string[]): Promise<number> {
-    return await invoke("uninstall_skills_batch", { ids });
+  /** 批量卸载 Skills（目标中有锁定资源时需 force） */
+  async uninstallBatch(ids: string[], force?: boolean): Promise<number> {
+    return await invoke("uninstall_skills_batch", { ids, force });
   },
 
   /** 从备份恢复 Skill */
@@ -327,6 +338,19 @@ export const skillsApi = {
     return await invoke("is_builtin_skill_repo", { owner, name });
   },
 
+  /** 设置仓库的仅浏览标记（仅浏览的仓库不能安装新 Skill） */
+  async setRepoBrowseOnly(
+    owner: string,
+    name: string,
+    browseOnly: boolean,
+  ): Promise<boolean> {
+    return await invoke("set_skill_repo_browse_only", {
+      owner,
+      name,
+      browseOnly,
+    });
+  },
+
   // ========== 命名空间管理 (v3.12.0+) ==========
 
   /** 获取所有命名空间 */
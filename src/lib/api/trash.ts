@@ -0,0 +1,45 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { InstalledAgent } from "./agents";
+import type { InstalledCommand } from "./commands";
+import type { InstalledHook } from "./hooks";
+
+// ========== 类型定义 ==========
+
+/** 回收站中的资源（含归档的安装记录） */
+export type TrashedResource =
+  | { resourceType: "command"; record: InstalledCommand }
+  | { resourceType: "agent"; record: InstalledAgent }
+  | { resourceType: "hook"; record: InstalledHook };
+
+/** 回收站条目 */
+export interface TrashEntry {
+  recordId: string;
+  trashPath: string;
+  /** 删除时间（Unix 时间戳） */
+  deletedAt: number;
+  resource: TrashedResource;
+}
+
+// ========== API ==========
+
+export const trashApi = {
+  /** 获取回收站条目（按删除时间倒序） */
+  async list(): Promise<TrashEntry[]> {
+    return await invoke("get_trash_entries");
+  },
+
+  /** 从回收站恢复资源 */
+  async restore(recordId: string): Promise<TrashedResource> {
+    return await invoke("restore_from_trash", { recordId });
+  },
+
+  /** 永久删除单个回收站条目 */
+  async delete(recordId: string): Promise<boolean> {
+    return await invoke("delete_trash_entry", { recordId });
+  },
+
+  /** 清空回收站，返回删除的条目数 */
+  async empty(): Promise<number> {
+    return await invoke("empty_trash");
+  },
+};
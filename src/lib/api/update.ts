@@ -23,6 +23,12 @@ export interface UpdateCheckResult {
   remoteDeleted: boolean;
 }
 
+/** 与指定 tag 比对的更新检测结果 */
+export interface TagUpdateCheckResult extends UpdateCheckResult {
+  /** 实际比对的 tag */
+  tag: string;
+}
+
 /** 批量更新检测结果 */
 export interface BatchCheckResult {
   /** 成功检测的数量 */
@@ -103,6 +109,14 @@ export interface BatchUpdateResult {
   results: UpdateExecuteResult[];
 }
 
+/** 本地哈希规范化结果 */
+export interface HashNormalizeResult {
+  /** 已替换为 blob SHA 的数量 */
+  fixedCount: number;
+  /** 未能规范化的项（格式 "类型 id: 原因"） */
+  skipped: string[];
+}
+
 // ========== API ==========
 
 export const updateApi = {
@@ -157,6 +171,44 @@ export const updateApi = {
     return await invoke("check_resource_updates", { resourceType });
   },
 
+  /** 与上游指定 tag 比对单个资源（而非分支 HEAD） */
+  async checkAgainstTag(
+    resourceType: ResourceType,
+    id: string,
+    tag: string,
+  ): Promise<TagUpdateCheckResult> {
+    return await invoke("check_update_against_tag", { resourceType, id, tag });
+  },
+
+  /** 列出仓库的 tag，按语义化版本从新到旧排序 */
+  async listRepoTags(owner: string, repo: string): Promise<string[]> {
+    return await invoke("list_repo_tags", { owner, repo });
+  },
+
+  /** 获取已安装资源与上游版本的 unified diff（一致时返回 null） */
+  async getUpstreamDiff(
+    resourceType: ResourceType,
+    id: string,
+  ): Promise<string | null> {
+    return await invoke("get_upstream_diff", { resourceType, id });
+  },
+
+  /**
+   * 丢弃本地修改，将资源还原为安装时记录的上游版本（仅 Command/Agent/Hook）
+   * @returns 还原后的更新检测基线哈希
+   */
+  async revertToUpstream(
+    resourceType: ResourceType,
+    id: string,
+  ): Promise<string | null> {
+    return await invoke("revert_to_upstream", { resourceType, id });
+  },
+
+  /** 导出发现缓存（JSON Lines，诊断用途） */
+  async dumpDiscoveryCache(resourceType: ResourceType): Promise<string> {
+    return await invoke("dump_discovery_cache", { resourceType });
+  },
+
   // ========== GitHub Token 管理 ==========
 
   /** 验证 GitHub Token */
@@ -181,9 +233,12 @@ export const updateApi = {
     return await invoke("execute_skill_update", { skillId });
   },
 
-  /** 批量更新 Skills */
-  async updateSkillsBatch(skillIds: string[]): Promise<BatchUpdateResult> {
-    return await invoke("update_skills_batch", { skillIds });
+  /** 批量更新 Skills（目标中有锁定资源时需 force） */
+  async updateSkillsBatch(
+    skillIds: string[],
+    force?: boolean,
+  ): Promise<BatchUpdateResult> {
+    return await invoke("update_skills_batch", { skillIds, force });
   },
 
   // ========== Commands 更新执行 ==========
@@ -193,9 +248,12 @@ export const updateApi = {
     return await invoke("update_command", { commandId });
   },
 
-  /** 批量更新 Commands */
-  async updateCommandsBatch(commandIds: string[]): Promise<BatchUpdateResult> {
-    return await invoke("update_commands_batch", { commandIds });
+  /** 批量更新 Commands（目标中有锁定资源时需 force） */
+  async updateCommandsBatch(
+    commandIds: string[],
+    force?: boolean,
+  ): Promise<BatchUpdateResult> {
+    return await invoke("update_commands_batch", { commandIds, force });
   },
 
   // ========== Agents 更新执行 ==========
@@ -205,9 +263,12 @@ export const updateApi = {
     return await invoke("update_agent", { agentId });
   },
 
-  /** 批量更新 Agents */
-  async updateAgentsBatch(agentIds: string[]): Promise<BatchUpdateResult> {
-    return await invoke("update_agents_batch", { agentIds });
+  /** 批量更新 Agents（目标中有锁定资源时需 force） */
+  async updateAgentsBatch(
+    agentIds: string[],
+    force?: boolean,
+  ): Promise<BatchUpdateResult> {
+    return await invoke("update_agents_batch", { agentIds, force });
   },
 
   // ========== 修复工具 ==========
@@ -226,4 +287,9 @@ export const updateApi = {
   async fixAgentsHash(): Promise<BatchUpdateResult> {
     return await invoke("fix_agents_hash");
   },
+
+  /** 把回退计算的本地哈希替换为 blob SHA，消除误报的更新 */
+  async normalizeHashesToBlobSha(): Promise<HashNormalizeResult> {
+    return await invoke("normalize_hashes_to_blob_sha");
+  },
 };
@@ -136,4 +136,9 @@ export const usageApi = {
   getDataSourceBreakdown: async (): Promise<DataSourceSummary[]> => {
     return invoke("get_usage_data_sources");
   },
+
+  // 清除已有请求日志中的错误正文等内容字段，返回被清除的记录数
+  redactRequestLogs: async (): Promise<number> => {
+    return invoke("redact_request_logs");
+  },
 };
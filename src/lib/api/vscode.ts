@@ -9,6 +9,15 @@ export interface EndpointLatencyResult {
   error?: string;
 }
 
+export interface SpeedtestRecord {
+  url: string;
+  latencyMs: number | null;
+  status?: number;
+  error?: string;
+  /** 测速时间（Unix 秒） */
+  testedAt: number;
+}
+
 export const vscodeApi = {
   async getLiveProviderSettings(appId: AppId) {
     return await invoke("read_live_provider_settings", { app: appId });
@@ -24,6 +33,17 @@ export const vscodeApi = {
     });
   },
 
+  async getSpeedtestHistory(
+    url?: string,
+    limit?: number,
+  ): Promise<SpeedtestRecord[]> {
+    return await invoke("get_speedtest_history", { url, limit });
+  },
+
+  async clearSpeedtestHistory(url?: string): Promise<number> {
+    return await invoke("clear_speedtest_history", { url });
+  },
+
   async getCustomEndpoints(
     appId: AppId,
     providerId: string,
@@ -175,6 +175,10 @@ export interface ProviderMeta {
   providerType?: string;
   // GitHub Copilot 关联账号 ID（旧字段，保留兼容读取）
   githubAccountId?: string;
+  // 附加请求头（如 OpenRouter 的 HTTP-Referer / X-Title），写入 live 配置并用于测试连接
+  extraHeaders?: Record<string, string>;
+  // 部署名（Azure OpenAI 等），写入 live 配置和测试连接时与基础 URL 拼接
+  deployment?: string;
 }
 
 // Skill 同步方式
@@ -183,6 +187,12 @@ export type SkillSyncMethod = "auto" | "symlink" | "copy";
 // Skill 存储位置
 export type SkillStorageLocation = "cc_switch" | "unified";
 
+// 代理请求日志记录级别
+export type RequestLogLevel = "off" | "metadata" | "full";
+
+// 文件资源更新检测方式：优先 blob SHA（失败时回退内容比对）/ 始终比对内容
+export type UpdateCheckMode = "sha_preferred" | "content_always";
+
 // Claude API 格式类型
 // - "anthropic": 原生 Anthropic Messages API 格式，直接透传
 // - "openai_chat": OpenAI Chat Completions 格式，需要格式转换
@@ -315,6 +325,44 @@ export interface Settings {
   // Skill 存储位置：cc_switch（默认）或 unified（~/.agents/skills/）
   skillStorageLocation?: SkillStorageLocation;
 
+  // ===== 资源校验设置 =====
+  // 启动时校验 Commands/Agents/Hooks 的 SSOT 文件完整性
+  verifyIntegrityOnStartup?: boolean;
+  // 资源更新后恢复更新开始时的应用启用状态（默认 true）；
+  // 关闭后保留更新期间对应用开关的改动
+  preserveAppStateOnUpdate?: boolean;
+  // Commands/Agents/Hooks 更新检测方式（默认 sha_preferred）
+  updateCheckMode?: UpdateCheckMode;
+  // Commands/Agents 更新检测只比较正文（忽略 frontmatter），默认比较整个文件；
+  // 可减少 frontmatter 重排带来的误报，但 frontmatter 的实际变化也会被忽略
+  updateCompareBodyOnly?: boolean;
+  // 更新检测回退到内容比对时的原始文件地址前缀（默认 https://raw.githubusercontent.com）
+  githubRawBaseUrl?: string;
+
+  // ===== 通知设置 =====
+  // 后台任务完成或失败时发送系统通知
+  backgroundNotifications?: boolean;
+
+  // ===== 安全设置 =====
+  // 是否加密存储供应商密钥（仅能通过 providersApi.setKeyEncryption 修改）
+  encryptProviderKeys?: boolean;
+  // 代理请求日志记录级别（默认 full）；切换不改写已有日志，需调用 usageApi.redactRequestLogs
+  requestLogLevel?: RequestLogLevel;
+
+  // ===== 网络设置 =====
+  // 资源下载超时（仅能通过 settingsApi.setDownloadTimeouts 修改）
+  downloadTimeouts?: {
+    fileDownloadSecs: number;
+    repoDownloadSecs: number;
+    apiCallSecs: number;
+  };
+  // 发现时同时下载的仓库数（默认 4，范围 1-16）
+  discoveryConcurrency?: number;
+  // 仓库 ZIP 下载中断后是否断点续传（默认关闭）
+  resumableRepoDownloads?: boolean;
+  // 仓库 ZIP 下载的大小上限（MB，默认 50）
+  maxRepoDownloadMb?: number;
+
   // ===== WebDAV v2 同步设置 =====
   webdavSync?: WebDavSyncSettings;
 
@@ -323,6 +371,8 @@ export interface Settings {
   backupIntervalHours?: number;
   // Maximum backup files to retain (default 10)
   backupRetainCount?: number;
+  // Days of speedtest history to retain (default 30)
+  speedtestHistoryRetainDays?: number;
 
   // ===== 终端设置 =====
   // 首选终端应用（可选，默认使用系统默认终端）